@@ -0,0 +1,50 @@
+// 解码帧复制耗时对比：逐行复制（原先的做法）vs 行宽等于 stride 时整块复制 + 帧缓冲区池
+// 运行: cargo run --release --features internals --example frame_copy_bench -- [次数]
+//
+// 使用合成的 4K RGBA 平面（无填充和带对齐填充两种 stride），不需要媒体文件。
+
+use myy_player::internals::{copy_rgba_plane, FramePool};
+use myy_player::FrameData;
+use std::time::{Duration, Instant};
+
+const WIDTH: u32 = 3840;
+const HEIGHT: u32 = 2160;
+
+/// 原先的实现：每帧新分配缓冲区并逐行复制
+fn copy_rows(plane: &[u8], stride: usize, width: u32, height: u32) -> Vec<u8> {
+    let row_size = width as usize * 4;
+    let mut data = vec![0u8; row_size * height as usize];
+    for (y, row) in data.chunks_exact_mut(row_size).enumerate() {
+        let src = &plane[y * stride..];
+        row.copy_from_slice(&src[..row_size]);
+    }
+    data
+}
+
+fn time<T>(count: usize, mut copy: impl FnMut() -> T) -> Duration {
+    let started = Instant::now();
+    for _ in 0..count {
+        drop(std::hint::black_box(copy()));
+    }
+    started.elapsed() / count as u32
+}
+
+fn main() {
+    let count: usize = std::env::args().nth(1).and_then(|n| n.parse().ok()).unwrap_or(50);
+    // 3840 宽的行正好对齐；3839 宽时 swscale 把每行补齐到 64 字节
+    for (label, width) in [("无填充", WIDTH), ("带填充", WIDTH - 1)] {
+        let stride = (width as usize * 4).next_multiple_of(64);
+        let plane: Vec<u8> = (0..stride * HEIGHT as usize).map(|i| (i % 251) as u8).collect();
+        let pool = FramePool::new();
+        assert_eq!(copy_rgba_plane(&plane, stride, width, HEIGHT, Some(&pool)), copy_rows(&plane, stride, width, HEIGHT));
+
+        let rows = time(count, || copy_rows(&plane, stride, width, HEIGHT));
+        let plain = time(count, || copy_rgba_plane(&plane, stride, width, HEIGHT, None));
+        // 每帧释放后缓冲区回到池中，模拟稳定播放时缓冲区循环使用
+        let pooled = time(count, || FrameData::pooled(copy_rgba_plane(&plane, stride, width, HEIGHT, Some(&pool)), Some(&pool)));
+        println!(
+            "{}x{}（{}，stride {}）: 逐行 {:?} / 当前 {:?} / 当前 + 池 {:?}",
+            width, HEIGHT, label, stride, rows, plain, pooled
+        );
+    }
+}
@@ -0,0 +1,48 @@
+// 无界面播放示例：通过 Player 门面打开文件，打印解码出的视频帧 PTS
+// 运行: cargo run --example minimal_player -- <媒体文件> [帧数]
+
+use myy_player::{MediaSource, Player, PlayerEvent};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+fn main() -> myy_player::Result<()> {
+    env_logger::init();
+
+    let mut args = std::env::args().skip(1);
+    let Some(path) = args.next() else {
+        eprintln!("用法: minimal_player <媒体文件> [帧数]");
+        std::process::exit(2);
+    };
+    let max_frames: usize = args.next().and_then(|n| n.parse().ok()).unwrap_or(50);
+
+    let mut player = Player::new()?;
+    let events = player.events();
+    let info = player.open(MediaSource::LocalFile(PathBuf::from(path)))?;
+    println!("✓ 已打开: {}x{} {:.3}fps，时长 {}ms", info.width, info.height, info.fps, info.duration);
+    player.play()?;
+
+    let mut printed = 0;
+    while printed < max_frames {
+        player.update();
+        for event in events.try_iter() {
+            match event {
+                PlayerEvent::Error(message) => eprintln!("✗ 播放中断: {}", message),
+                PlayerEvent::Finished => println!("✓ 播放完毕"),
+                other => println!("事件: {:?}", other),
+            }
+        }
+
+        match player.next_frame() {
+            Some(frame) => {
+                println!("帧 #{:<4} PTS={}ms ({}x{})", printed, frame.pts_ms(), frame.width(), frame.height());
+                printed += 1;
+            }
+            None if player.is_finished() => break,
+            None => thread::sleep(Duration::from_millis(5)),
+        }
+    }
+
+    player.stop();
+    Ok(())
+}
@@ -0,0 +1,72 @@
+// 预览解码延迟对比：整幅解码后缩小 vs 解码时直接缩小（预览管线的做法）
+// 运行: cargo run --release --features internals --example preview_bench -- <媒体文件（建议 4K）> [次数]
+//
+// 两种方式都在文件中均匀分布的时间点 Seek 并解码第一帧，不使用预览缓存。
+
+use myy_player::internals::{decode_first_frame, downscale, Demuxer, PreviewDecoder, PreviewPurpose, VideoDecoder};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+fn main() -> myy_player::Result<()> {
+    env_logger::init();
+    ffmpeg_next::init().map_err(|e| myy_player::PlayerError::Other(format!("FFmpeg 初始化失败: {}", e)))?;
+
+    let mut args = std::env::args().skip(1);
+    let Some(path) = args.next() else {
+        eprintln!("用法: preview_bench <媒体文件> [次数]");
+        std::process::exit(2);
+    };
+    let count: usize = args.next().and_then(|n| n.parse().ok()).unwrap_or(16);
+    let cancel = AtomicBool::new(false);
+
+    let mut demuxer = Demuxer::open(&path)?;
+    let info = demuxer.get_media_info()?;
+    println!("源: {}x{}，时长 {}ms，{} 个时间点", info.width, info.height, info.duration, count);
+    // 时间轴等分为 count 段，取各段中点（与胶片条相同）
+    let times: Vec<i64> = (0..count as i64).map(|i| (2 * i + 1) * info.duration / (2 * count as i64)).collect();
+
+    for purpose in [PreviewPurpose::Hover, PreviewPurpose::Filmstrip] {
+        let max_width = purpose.max_width();
+
+        // 之前的做法：整幅解码为 RGBA，再按区域平均缩小
+        let stream = demuxer.video_stream().ok_or(myy_player::PlayerError::NoVideoStream)?;
+        let mut decoder = VideoDecoder::from_stream_software(stream)?;
+        let full = measure(&times, |time_ms| {
+            decode_first_frame(&mut demuxer, &mut decoder, time_ms, &cancel).map(|frame| frame.map(|frame| downscale(&frame, max_width)))
+        })?;
+
+        // 预览管线：转换时直接缩小到目标宽度
+        let mut preview = PreviewDecoder::open(Path::new(&path), max_width)?;
+        let capped = measure(&times, |time_ms| preview.decode_at(time_ms, &cancel))?;
+
+        println!(
+            "{:?} ({}px): 整幅解码 {:.1}ms/张，限制尺寸解码 {:.1}ms/张（{:.1}x）",
+            purpose,
+            max_width,
+            per_frame_ms(full),
+            per_frame_ms(capped),
+            full.0.as_secs_f64() / capped.0.as_secs_f64().max(f64::EPSILON)
+        );
+    }
+    Ok(())
+}
+
+/// 依次生成每个时间点的预览，返回 (总耗时, 成功张数)
+fn measure(
+    times: &[i64],
+    mut decode: impl FnMut(i64) -> myy_player::Result<Option<myy_player::VideoFrame>>,
+) -> myy_player::Result<(Duration, usize)> {
+    let started = Instant::now();
+    let mut decoded = 0;
+    for &time_ms in times {
+        if decode(time_ms)?.is_some() {
+            decoded += 1;
+        }
+    }
+    Ok((started.elapsed(), decoded))
+}
+
+fn per_frame_ms((elapsed, decoded): (Duration, usize)) -> f64 {
+    elapsed.as_secs_f64() * 1000.0 / decoded.max(1) as f64
+}
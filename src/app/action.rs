@@ -0,0 +1,85 @@
+// 播放器动作定义（键盘、媒体键、IPC 等输入源统一通过 dispatch_action 分发）
+
+use std::path::PathBuf;
+
+use super::seek_step::SeekStep;
+use super::window_size::WindowScale;
+use myy_player::TrackSource;
+
+/// 播放器动作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerAction {
+    /// 播放/暂停
+    PlayPause,
+    /// 按设置中的步长快进 / 快退（连续按键合并为一次跳转）
+    SeekForward(SeekStep),
+    SeekBack(SeekStep),
+    /// 播放列表下一项 / 上一项
+    PlayNext,
+    PlayPrevious,
+    /// 跳到下一章 / 上一章（本章已播放超过 3 秒时上一章回到本章开头）
+    NextChapter,
+    PreviousChapter,
+    /// 逐帧前进 / 后退（播放中先暂停）
+    StepFrameForward,
+    StepFrameBackward,
+    /// 跳到下一个 / 上一个关键帧（按后台建立的关键帧索引）
+    NextKeyframe,
+    PreviousKeyframe,
+    /// 回到直播边缘（直播流）
+    JumpToLive,
+    /// 切换全屏
+    ToggleFullscreen,
+    /// 显示/隐藏信息面板
+    ToggleInfo,
+    /// 显示/隐藏设置抽屉
+    ToggleSettings,
+    /// 显示/隐藏胶片视图
+    ToggleFilmstrip,
+    /// 关闭设置抽屉或胶片视图、退出全屏（都不是时隐藏信息面板）
+    Escape,
+    /// 音量增大 / 减小（每次 VOLUME_STEP，静音时取消静音）
+    VolumeUp,
+    VolumeDown,
+    /// 静音 / 取消静音
+    ToggleMute,
+    /// 循环切换画面缩放模式（适应 / 填满 / 拉伸 / 原始大小）
+    CycleFitMode,
+    /// 循环切换音频轨道
+    CycleAudioTrack,
+    /// 循环切换字幕轨道（包含"关闭"）
+    CycleSubtitleTrack,
+    /// 选择音频轨道（流索引）
+    SelectAudioTrack(usize),
+    /// 选择字幕轨道（None 表示关闭）
+    SelectSubtitleTrack(Option<TrackSource>),
+    /// 监视文件夹中出现的新文件（追加到队列或抢占播放）
+    OpenWatchedFile(PathBuf),
+    /// 在当前位置标记 A/B 循环的 A 点 / B 点（循环生效时清除循环）
+    MarkLoopA,
+    MarkLoopB,
+    /// 开启 / 关闭单曲循环（同步到设置）
+    ToggleRepeatOne,
+    /// 调整字幕延迟（毫秒，正值推后）/ 重置为 0
+    ShiftSubtitleDelay(i64),
+    ResetSubtitleDelay,
+    /// 把片头标记为从 0 到当前位置（同一文件夹的其他文件随后自动跳过片头）
+    MarkIntroEnd,
+    /// 截图（with_subtitles 为 true 时烧录当前显示的字幕）
+    Snapshot { with_subtitles: bool },
+    /// 开始连拍（按住 Shift+S 期间保存每一帧显示的画面）
+    StartBurst,
+    /// 按视频原始尺寸的比例调整窗口大小
+    SnapWindow(WindowScale),
+    /// 新建空白标签页
+    NewSession,
+    /// 切换到指定标签页
+    SwitchSession(usize),
+    /// 切换到下一个/上一个标签页
+    NextSession,
+    PreviousSession,
+    /// 关闭标签页（None 表示当前标签页）
+    CloseSession(Option<usize>),
+    /// 模拟 GPU 设备丢失（调试命令，验证渲染恢复流程）
+    SimulateGpuLoss,
+}
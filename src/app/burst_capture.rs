@@ -0,0 +1,267 @@
+// 连拍（按住 Shift+S 保存每一帧显示的画面，用于从运动场景中挑选最清晰的一帧）
+//
+// 连拍最长 3 秒、最多 90 帧。界面线程只克隆帧（像素数据共享，不复制）送入有界通道，
+// 旋转和编码都在后台线程完成，不阻塞播放；编码跟不上时通道已满，新帧直接丢弃并计数。
+// 松开按键或达到上限后关闭通道，后台线程保存完队列中剩余的帧后结束，界面再显示保存和丢弃的帧数。
+// 保存期间登记为后台任务；关闭窗口时取消任务，后台线程不再等待新帧，保存完已收到的帧后结束。
+
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError};
+use log::{error, info};
+use std::path::PathBuf;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::snapshot::{self, SnapshotOptions, TemplateValues};
+use super::job_registry::JobHandle;
+use myy_player::VideoFrame;
+
+/// 最长连拍时间
+pub const BURST_DURATION: Duration = Duration::from_secs(3);
+
+/// 最多连拍帧数
+pub const BURST_MAX_FRAMES: u32 = 90;
+
+/// 等待编码的帧数上限（超出时丢弃新帧）
+const QUEUE_CAPACITY: usize = 8;
+
+/// 等待新帧时检查取消信号的间隔
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 送入编码线程的帧（帧序号从 1 开始）
+struct BurstFrame {
+    frame: VideoFrame,
+    rotation: u32,
+    n: u32,
+}
+
+/// 连拍结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BurstReport {
+    pub saved: u32,
+    /// 编码跟不上而丢弃的帧数
+    pub dropped: u32,
+    /// 编码或写入失败的帧数
+    pub failed: u32,
+    /// 第一帧的保存路径
+    pub first_path: Option<PathBuf>,
+}
+
+/// 编码线程的统计
+#[derive(Debug, Default)]
+struct WorkerReport {
+    saved: u32,
+    failed: u32,
+    first_path: Option<PathBuf>,
+}
+
+/// 一次连拍
+pub struct BurstCapture {
+    tx: Option<Sender<BurstFrame>>,
+    worker: Option<JoinHandle<WorkerReport>>,
+    started: Instant,
+    /// 已送出的帧数（包括丢弃的）
+    captured: u32,
+    dropped: u32,
+    last_pts: Option<i64>,
+}
+
+impl BurstCapture {
+    /// 开始连拍（name 为文件名模板中的 {name}，position_ms 为开始时的播放位置；job 在编码线程结束时注销）
+    pub fn start(options: SnapshotOptions, name: String, position_ms: i64, job: JobHandle) -> std::io::Result<Self> {
+        let (tx, rx) = bounded(QUEUE_CAPACITY);
+        let worker = thread::Builder::new()
+            .name("snapshot-burst".to_string())
+            .spawn(move || encode_frames(rx, options, name, position_ms, job))?;
+        info!("📸 开始连拍（最长 {} 秒，最多 {} 帧）", BURST_DURATION.as_secs(), BURST_MAX_FRAMES);
+        Ok(Self { tx: Some(tx), worker: Some(worker), started: Instant::now(), captured: 0, dropped: 0, last_pts: None })
+    }
+
+    /// 是否还在接收帧（没有松开按键、没有达到时长或帧数上限，任务也没有被取消）
+    pub fn is_capturing(&self) -> bool {
+        self.tx.is_some()
+            && !self.worker.as_ref().is_some_and(JoinHandle::is_finished)
+            && self.started.elapsed() < BURST_DURATION
+            && self.captured < BURST_MAX_FRAMES
+    }
+
+    /// 送入一帧显示的画面（同一帧只保存一次；编码跟不上时丢弃）
+    pub fn offer(&mut self, frame: &VideoFrame, rotation: u32) {
+        if !self.is_capturing() || self.last_pts == Some(frame.pts) {
+            return;
+        }
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        self.last_pts = Some(frame.pts);
+        self.captured += 1;
+        let burst_frame = BurstFrame { frame: frame.clone(), rotation, n: self.captured };
+        match tx.try_send(burst_frame) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => self.dropped += 1,
+            Err(TrySendError::Disconnected(_)) => {
+                // 编码线程提前退出（不应出现），不再接收帧
+                self.dropped += 1;
+                self.tx = None;
+            }
+        }
+    }
+
+    /// 停止接收帧（编码线程保存完队列中的帧后结束）
+    pub fn stop(&mut self) {
+        if self.tx.take().is_some() {
+            info!("📸 连拍结束，共 {} 帧（丢弃 {} 帧）", self.captured, self.dropped);
+        }
+    }
+
+    /// 停止后编码线程已结束时取出结果（还在保存时为 None）
+    pub fn poll_report(&mut self) -> Option<BurstReport> {
+        if self.tx.is_some() || !self.worker.as_ref().is_some_and(JoinHandle::is_finished) {
+            return None;
+        }
+        let report = self.worker.take()?.join().unwrap_or_else(|_| {
+            error!("连拍编码线程异常退出");
+            WorkerReport::default()
+        });
+        Some(BurstReport { saved: report.saved, dropped: self.dropped, failed: report.failed, first_path: report.first_path })
+    }
+}
+
+/// 下一帧：通道关闭后为 None；任务被取消后不再等待，只取出已在队列中的帧
+fn next_frame(rx: &Receiver<BurstFrame>, job: &JobHandle) -> Option<BurstFrame> {
+    loop {
+        if job.is_cancelled() {
+            return rx.try_recv().ok();
+        }
+        match rx.recv_timeout(CANCEL_POLL_INTERVAL) {
+            Ok(frame) => return Some(frame),
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+}
+
+/// 编码线程：逐帧旋转、编码并保存，直到通道关闭或任务被取消（已收到的帧保存完再结束）
+fn encode_frames(rx: Receiver<BurstFrame>, options: SnapshotOptions, name: String, position_ms: i64, job: JobHandle) -> WorkerReport {
+    let mut report = WorkerReport::default();
+    let unix_secs = snapshot::unix_now();
+    // 模板中没有 {n} 时追加帧序号，保证文件按顺序排列
+    let options = if options.template.contains("{n}") {
+        options
+    } else {
+        SnapshotOptions { template: format!("{}_{{n}}", options.template), ..options }
+    };
+
+    while let Some(BurstFrame { frame, rotation, n }) = next_frame(&rx, &job) {
+        let image = snapshot::rotate_frame(&frame, rotation);
+        let values = TemplateValues { name: &name, position_ms, unix_secs, n };
+        let path = snapshot::snapshot_path(&options, &values);
+        match snapshot::save(&image, &path, options.format, options.jpeg_quality) {
+            Ok(()) => {
+                report.saved += 1;
+                report.first_path.get_or_insert(path);
+            }
+            Err(e) => {
+                error!("连拍第 {} 帧保存失败: {}", n, e);
+                report.failed += 1;
+            }
+        }
+        job.set_progress(n as f32 / BURST_MAX_FRAMES as f32);
+    }
+    if job.is_cancelled() {
+        info!("📸 连拍任务已取消，已保存 {} 帧", report.saved);
+    }
+    job.complete();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::job_registry::{CancelBehavior, JobRegistry};
+    use myy_player::PixelFormat;
+
+    fn frame(pts: i64) -> VideoFrame {
+        VideoFrame { pts, duration: 40, width: 4, height: 2, format: PixelFormat::RGBA, planes: None, data: vec![128; 32].into() }
+    }
+
+    fn wait_report(burst: &mut BurstCapture) -> BurstReport {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            if let Some(report) = burst.poll_report() {
+                return report;
+            }
+            assert!(Instant::now() < deadline, "连拍编码线程没有结束");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn png_options(dir: &std::path::Path) -> SnapshotOptions {
+        SnapshotOptions {
+            format: snapshot::SnapshotFormat::Png,
+            jpeg_quality: 90,
+            dir: dir.to_path_buf(),
+            template: "{name}".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_burst_saves_numbered_sequence_and_counts_drops() {
+        let dir = std::env::temp_dir().join(format!("myy_burst_{}", std::process::id()));
+        let jobs = JobRegistry::new();
+        let job = jobs.register("连拍", CancelBehavior::Finalize);
+        let mut burst = BurstCapture::start(png_options(&dir), "clip".to_string(), 0, job).unwrap();
+
+        // 同一帧只保存一次；一次性送入的帧超过队列容量时丢弃
+        for pts in (0..40).map(|i| i * 40) {
+            burst.offer(&frame(pts), 0);
+            burst.offer(&frame(pts), 0);
+        }
+        assert!(burst.poll_report().is_none(), "停止前不应报告结果");
+        burst.stop();
+        burst.offer(&frame(10_000), 0);
+
+        let report = wait_report(&mut burst);
+        assert_eq!(report.saved + report.dropped + report.failed, 40);
+        assert_eq!(report.failed, 0);
+        assert!(report.saved >= QUEUE_CAPACITY as u32);
+        assert_eq!(report.first_path, Some(dir.join("clip_001.png")));
+        let files = std::fs::read_dir(&dir).unwrap().count() as u32;
+        assert_eq!(files, report.saved);
+        // 编码线程结束后任务注销
+        assert!(jobs.is_idle());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_cancelled_burst_finalizes_received_frames() {
+        let dir = std::env::temp_dir().join(format!("myy_burst_cancel_{}", std::process::id()));
+        let jobs = JobRegistry::new();
+        let job = jobs.register("连拍", CancelBehavior::Finalize);
+        let mut burst = BurstCapture::start(png_options(&dir), "clip".to_string(), 0, job).unwrap();
+        for pts in (0..4).map(|i| i * 40) {
+            burst.offer(&frame(pts), 0);
+        }
+
+        // 关闭窗口时取消任务：仍按着按键（通道没有关闭），编码线程保存完已收到的帧后结束
+        jobs.cancel_all();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !jobs.is_idle() {
+            assert!(Instant::now() < deadline, "取消后连拍任务没有结束");
+            thread::sleep(Duration::from_millis(5));
+        }
+        // 之后不再接收帧
+        assert!(!burst.is_capturing());
+        burst.offer(&frame(1_000), 0);
+        burst.stop();
+
+        let report = wait_report(&mut burst);
+        assert_eq!((report.saved, report.failed, report.dropped), (4, 0, 0));
+        // 保存的每个文件都是完整可读的图片
+        for n in 1..=4 {
+            let path = dir.join(format!("clip_{:03}.png", n));
+            let image = image::open(&path).unwrap_or_else(|e| panic!("{} 无法读取: {}", path.display(), e));
+            assert_eq!((image.width(), image.height()), (4, 2));
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
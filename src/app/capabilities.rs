@@ -0,0 +1,111 @@
+// 启动自检（检测硬件解码、FFmpeg 版本、GPU 后端，供起始页底部显示和日志记录）
+
+use eframe::wgpu;
+use log::info;
+
+use myy_player::Player;
+
+/// 检测到的运行环境能力
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub hw_decoder: Option<&'static str>,  // 优先使用的硬件解码方式（不支持时为 None）
+    pub ffmpeg_version: String,
+    pub gpu: Option<(String, String)>,     // (图形后端, GPU 厂商)，渲染器不可用时为 None
+}
+
+impl Capabilities {
+    /// 执行启动自检
+    pub fn detect(render_state: Option<&eframe::egui_wgpu::RenderState>) -> Self {
+        let hw_decoder = Player::preferred_hw_decoder();
+        let ffmpeg_version = short_version(&Player::ffmpeg_version());
+        let gpu = render_state.map(|state| {
+            let adapter = state.adapter.get_info();
+            (backend_name(adapter.backend).to_string(), vendor_name(adapter.vendor, &adapter.name))
+        });
+
+        let capabilities = Self { hw_decoder, ffmpeg_version, gpu };
+        info!("🩺 启动自检: {}", capabilities.summary());
+        capabilities
+    }
+
+    /// 单行摘要（如"硬件解码: D3D11VA · FFmpeg 6.1 · wgpu: Vulkan/NVIDIA"）
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("硬件解码: {}", self.hw_decoder.unwrap_or("不可用"))];
+        if !self.ffmpeg_version.is_empty() {
+            parts.push(format!("FFmpeg {}", self.ffmpeg_version));
+        }
+        match &self.gpu {
+            Some((backend, vendor)) => parts.push(format!("wgpu: {}/{}", backend, vendor)),
+            None => parts.push("wgpu: 不可用".to_string()),
+        }
+        parts.join(" · ")
+    }
+}
+
+/// FFmpeg 版本字符串取主次版本号（"6.1.1-full_build-www.gyan.dev" -> "6.1"，开发版 "N-112345-g..." 原样保留）
+fn short_version(version: &str) -> String {
+    let numeric: Vec<&str> = version
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()
+        .unwrap_or("")
+        .split('.')
+        .filter(|part| !part.is_empty())
+        .collect();
+    if numeric.is_empty() {
+        return version.to_string();
+    }
+    numeric.iter().take(2).copied().collect::<Vec<_>>().join(".")
+}
+
+/// 图形后端的显示名称
+fn backend_name(backend: wgpu::Backend) -> &'static str {
+    match backend {
+        wgpu::Backend::Vulkan => "Vulkan",
+        wgpu::Backend::Dx12 => "DX12",
+        wgpu::Backend::Metal => "Metal",
+        wgpu::Backend::Gl => "OpenGL",
+        wgpu::Backend::BrowserWebGpu => "WebGPU",
+        wgpu::Backend::Empty => "Empty",
+    }
+}
+
+/// 按 PCI 厂商 ID 识别 GPU 厂商（未知厂商使用适配器名称）
+fn vendor_name(vendor_id: u32, adapter_name: &str) -> String {
+    match vendor_id {
+        0x10DE => "NVIDIA".to_string(),
+        0x1002 => "AMD".to_string(),
+        0x8086 => "Intel".to_string(),
+        0x106B => "Apple".to_string(),
+        0x5143 => "Qualcomm".to_string(),
+        _ if adapter_name.is_empty() => "未知".to_string(),
+        _ => adapter_name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_line() {
+        let capabilities = Capabilities {
+            hw_decoder: Some("D3D11VA"),
+            ffmpeg_version: "6.1".to_string(),
+            gpu: Some(("Vulkan".to_string(), "NVIDIA".to_string())),
+        };
+        assert_eq!(capabilities.summary(), "硬件解码: D3D11VA · FFmpeg 6.1 · wgpu: Vulkan/NVIDIA");
+
+        let minimal = Capabilities { hw_decoder: None, ffmpeg_version: String::new(), gpu: None };
+        assert_eq!(minimal.summary(), "硬件解码: 不可用 · wgpu: 不可用");
+    }
+
+    #[test]
+    fn test_version_and_vendor_names() {
+        assert_eq!(short_version("6.1"), "6.1");
+        assert_eq!(short_version("6.1.1-full_build-www.gyan.dev"), "6.1");
+        assert_eq!(short_version("N-112345-g0123456789"), "N-112345-g0123456789");
+        assert_eq!(vendor_name(0x10DE, "NVIDIA GeForce RTX 3070"), "NVIDIA");
+        assert_eq!(vendor_name(0x1234, "llvmpipe (LLVM 15.0.7, 256 bits)"), "llvmpipe (LLVM 15.0.7, 256 bits)");
+        assert_eq!(vendor_name(0, ""), "未知");
+    }
+}
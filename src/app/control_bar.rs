@@ -0,0 +1,420 @@
+// 控制栏的按钮和滑块（键盘操作与屏幕阅读器）
+//
+// 控制栏的所有控件都能获得焦点：Tab / Shift+Tab 按 进度条 → 按钮 → 音量 的顺序切换，
+// Enter / 空格触发获得焦点的按钮，方向键调整获得焦点的滑块（此时全局快捷键让位）。
+// 每个控件都向 AccessKit 报告角色和名称（如"播放"、"音量 65%"）。
+
+use egui::{Response, Ui, WidgetInfo};
+
+use super::icons::{Icon, IconAtlas, IconButton};
+use super::recent_files;
+use super::time_format::{format_duration, format_time};
+use myy_player::{Chapter, MAX_VOLUME};
+
+/// 按钮尺寸
+const BUTTON_SIZE: f32 = 26.0;
+const ICON_SIZE: f32 = 22.0;
+
+/// 文件类图标略小，与播放控制图标视觉上等大
+const FILE_ICON_SIZE: f32 = 18.0;
+
+/// 音量滑块尺寸
+const VOLUME_SLIDER_SIZE: egui::Vec2 = egui::Vec2::new(100.0, 16.0);
+
+/// 速度选择器中的播放速度
+pub const PLAYBACK_SPEEDS: [f32; 6] = [0.5, 0.75, 1.0, 1.25, 1.5, 2.0];
+
+/// 控制栏按钮
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlButton {
+    OpenFile,
+    OpenUrl,
+    Previous,
+    PlayPause,
+    Next,
+    JumpToLive,
+    Stop,
+}
+
+/// 决定按钮外观的播放状态
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ControlBarState {
+    pub has_media: bool,
+    pub is_playing: bool,
+    pub is_live: bool,
+    /// 播放列表中有上一项 / 下一项
+    pub has_previous: bool,
+    pub has_next: bool,
+}
+
+impl ControlButton {
+    /// 当前显示的按钮（即 Tab 顺序；直播流用"回到直播"代替"停止"）
+    pub fn visible(state: &ControlBarState) -> [ControlButton; 6] {
+        let last = if state.is_live { ControlButton::JumpToLive } else { ControlButton::Stop };
+        [ControlButton::OpenFile, ControlButton::OpenUrl, ControlButton::Previous, ControlButton::PlayPause, ControlButton::Next, last]
+    }
+
+    fn icon(self, state: &ControlBarState) -> (Icon, f32) {
+        match self {
+            ControlButton::OpenFile => (Icon::OpenFile, FILE_ICON_SIZE),
+            ControlButton::OpenUrl => (Icon::OpenUrl, FILE_ICON_SIZE),
+            ControlButton::Previous => (Icon::Previous, ICON_SIZE),
+            ControlButton::Next => (Icon::Next, ICON_SIZE),
+            ControlButton::PlayPause if state.is_playing => (Icon::Pause, ICON_SIZE),
+            ControlButton::PlayPause => (Icon::Play, ICON_SIZE),
+            ControlButton::JumpToLive => (Icon::Live, ICON_SIZE),
+            ControlButton::Stop => (Icon::Stop, ICON_SIZE),
+        }
+    }
+
+    /// 无障碍名称
+    pub fn label(self, state: &ControlBarState) -> &'static str {
+        match self {
+            ControlButton::OpenFile => "打开文件",
+            ControlButton::OpenUrl => "打开网络流",
+            ControlButton::Previous => "上一个",
+            ControlButton::Next => "下一个",
+            ControlButton::PlayPause if state.is_playing => "暂停",
+            ControlButton::PlayPause => "播放",
+            ControlButton::JumpToLive => "回到直播",
+            ControlButton::Stop => "停止",
+        }
+    }
+
+    /// 悬停提示（带快捷键）
+    fn tooltip(self, state: &ControlBarState) -> &'static str {
+        match self {
+            ControlButton::PlayPause if state.is_playing => "暂停 (空格)",
+            ControlButton::PlayPause => "播放 (空格)",
+            ControlButton::JumpToLive => "回到直播 (End)",
+            ControlButton::Previous => "上一个 (PageUp)",
+            ControlButton::Next => "下一个 (PageDown)",
+            _ => self.label(state),
+        }
+    }
+
+    fn enabled(self, state: &ControlBarState) -> bool {
+        match self {
+            ControlButton::PlayPause | ControlButton::Stop => state.has_media,
+            ControlButton::Previous => state.has_previous,
+            ControlButton::Next => state.has_next,
+            _ => true,
+        }
+    }
+}
+
+/// 绘制控制按钮，返回被点击（或通过键盘触发）的按钮
+pub fn show_buttons(ui: &mut Ui, icons: &mut IconAtlas, state: &ControlBarState) -> Option<ControlButton> {
+    let mut clicked = None;
+    for button in ControlButton::visible(state) {
+        let (icon, icon_size) = button.icon(state);
+        // 深色背景，悬停时略微提亮
+        let response = IconButton::new(icon, BUTTON_SIZE, icon_size)
+            .fill(egui::Color32::from_rgb(29, 29, 29), egui::Color32::from_rgb(50, 50, 50))
+            .tint(egui::Color32::from_gray(225), egui::Color32::WHITE)
+            .enabled(button.enabled(state))
+            .label(button.label(state))
+            .tooltip(button.tooltip(state))
+            .show(ui, icons);
+        if response.clicked() {
+            clicked = Some(button);
+        }
+    }
+    clicked
+}
+
+/// 设置抽屉按钮（齿轮，位于控制栏右侧）
+pub fn settings_button(ui: &mut Ui, icons: &mut IconAtlas) -> Response {
+    IconButton::new(Icon::Settings, BUTTON_SIZE, FILE_ICON_SIZE)
+        .fill(egui::Color32::from_rgb(29, 29, 29), egui::Color32::from_rgb(50, 50, 50))
+        .tint(egui::Color32::from_gray(225), egui::Color32::WHITE)
+        .label("设置")
+        .tooltip("设置 (Ctrl+,)")
+        .show(ui, icons)
+}
+
+/// 胶片视图按钮（位于设置按钮左侧；不可用时禁用并在提示中说明原因）
+pub fn filmstrip_button(ui: &mut Ui, icons: &mut IconAtlas, active: bool, unavailable: Option<&str>) -> Response {
+    let tint = if active { egui::Color32::from_rgb(255, 200, 80) } else { egui::Color32::from_gray(225) };
+    IconButton::new(Icon::Filmstrip, BUTTON_SIZE, FILE_ICON_SIZE)
+        .fill(egui::Color32::from_rgb(29, 29, 29), egui::Color32::from_rgb(50, 50, 50))
+        .tint(tint, egui::Color32::WHITE)
+        .enabled(unavailable.is_none())
+        .label("胶片视图")
+        .tooltip(unavailable.unwrap_or("胶片视图 (F)"))
+        .show(ui, icons)
+}
+
+/// 片头片尾按钮（已标记时高亮）
+pub fn bookmark_button(ui: &mut Ui, icons: &mut IconAtlas, marked: bool) -> Response {
+    let tint = if marked { egui::Color32::from_rgb(255, 200, 80) } else { egui::Color32::from_gray(225) };
+    IconButton::new(Icon::Bookmark, BUTTON_SIZE, FILE_ICON_SIZE)
+        .fill(egui::Color32::from_rgb(29, 29, 29), egui::Color32::from_rgb(50, 50, 50))
+        .tint(tint, egui::Color32::WHITE)
+        .label("片头片尾")
+        .tooltip("标记片头 / 片尾 (B: 标记片头结束)")
+        .show(ui, icons)
+}
+
+/// 全屏按钮（位于控制栏最右侧，图标随全屏状态切换）
+pub fn fullscreen_button(ui: &mut Ui, icons: &mut IconAtlas, fullscreen: bool) -> Response {
+    let (icon, label, tooltip) = if fullscreen {
+        (Icon::ExitFullscreen, "退出全屏", "退出全屏 (F11 / Esc)")
+    } else {
+        (Icon::Fullscreen, "全屏", "全屏 (F11)")
+    };
+    IconButton::new(icon, BUTTON_SIZE, FILE_ICON_SIZE)
+        .fill(egui::Color32::from_rgb(29, 29, 29), egui::Color32::from_rgb(50, 50, 50))
+        .tint(egui::Color32::from_gray(225), egui::Color32::WHITE)
+        .label(label)
+        .tooltip(tooltip)
+        .show(ui, icons)
+}
+
+/// 截图按钮（没有画面时禁用）
+pub fn snapshot_button(ui: &mut Ui, icons: &mut IconAtlas, enabled: bool) -> Response {
+    IconButton::new(Icon::Camera, BUTTON_SIZE, FILE_ICON_SIZE)
+        .fill(egui::Color32::from_rgb(29, 29, 29), egui::Color32::from_rgb(50, 50, 50))
+        .tint(egui::Color32::from_gray(225), egui::Color32::WHITE)
+        .enabled(enabled)
+        .label("截图")
+        .tooltip("截图 (S)")
+        .show(ui, icons)
+}
+
+/// 字幕按钮（循环切换字幕轨道；显示字幕时高亮，没有字幕轨道时禁用）
+pub fn subtitle_button(ui: &mut Ui, icons: &mut IconAtlas, active: bool, available: bool) -> Response {
+    let tint = if active { egui::Color32::from_rgb(255, 200, 80) } else { egui::Color32::from_gray(225) };
+    IconButton::new(Icon::Subtitle, BUTTON_SIZE, FILE_ICON_SIZE)
+        .fill(egui::Color32::from_rgb(29, 29, 29), egui::Color32::from_rgb(50, 50, 50))
+        .tint(tint, egui::Color32::WHITE)
+        .enabled(available)
+        .label("切换字幕")
+        .tooltip("切换字幕 (V)")
+        .show(ui, icons)
+}
+
+/// 单曲循环按钮（开启时高亮）
+pub fn loop_button(ui: &mut Ui, icons: &mut IconAtlas, repeat_one: bool) -> Response {
+    let (tint, label) = if repeat_one {
+        (egui::Color32::from_rgb(255, 200, 80), "关闭单曲循环")
+    } else {
+        (egui::Color32::from_gray(225), "单曲循环")
+    };
+    IconButton::new(Icon::Loop, BUTTON_SIZE, FILE_ICON_SIZE)
+        .fill(egui::Color32::from_rgb(29, 29, 29), egui::Color32::from_rgb(50, 50, 50))
+        .tint(tint, egui::Color32::WHITE)
+        .label(label)
+        .tooltip("单曲循环 (R)")
+        .show(ui, icons)
+}
+
+/// 静音按钮（位于音量滑块左侧，图标随静音状态切换）
+pub fn mute_button(ui: &mut Ui, icons: &mut IconAtlas, muted: bool) -> Response {
+    let (icon, label, tooltip) = if muted {
+        (Icon::VolumeMuted, "取消静音", "取消静音 (M)")
+    } else {
+        (Icon::VolumeHigh, "静音", "静音 (M)")
+    };
+    IconButton::new(icon, BUTTON_SIZE, FILE_ICON_SIZE)
+        .fill(egui::Color32::from_rgb(29, 29, 29), egui::Color32::from_rgb(50, 50, 50))
+        .tint(egui::Color32::from_gray(225), egui::Color32::WHITE)
+        .label(label)
+        .tooltip(tooltip)
+        .show(ui, icons)
+}
+
+/// 进度条的无障碍名称
+pub fn progress_label(position_ms: i64, duration_ms: i64) -> String {
+    format!("播放进度 {} / {}", format_time(position_ms.max(0)), format_duration(duration_ms))
+}
+
+/// 音量滑块的无障碍名称
+pub fn volume_label(volume: f32) -> String {
+    format!("音量 {:.0}%", volume * 100.0)
+}
+
+/// 进度条（单位：秒；获得焦点时方向键逐步调整）
+pub fn progress_slider(ui: &mut Ui, position: &mut f64, duration: f64, enabled: bool, label: String) -> Response {
+    let response = ui.add_enabled(
+        enabled,
+        egui::Slider::new(position, 0.0..=duration.max(1.0))
+            .show_value(false)
+            .text("")
+    );
+    let value = *position;
+    response.widget_info(|| WidgetInfo::slider(value, &label));
+    response
+}
+
+/// 音量滑块
+pub fn volume_slider(ui: &mut Ui, volume: &mut f32) -> Response {
+    let response = ui.add_sized(
+        VOLUME_SLIDER_SIZE,
+        egui::Slider::new(volume, 0.0..=MAX_VOLUME).show_value(false)
+    );
+    let value = *volume;
+    response.widget_info(|| WidgetInfo::slider(value as f64, volume_label(value)));
+    response
+}
+
+/// 播放速度的显示文本（如 "1.25x"）
+pub fn speed_label(speed: f32) -> String {
+    format!("{}x", speed)
+}
+
+/// 播放速度选择器（直播时禁用），返回是否选择了新的速度
+pub fn speed_selector(ui: &mut Ui, speed: &mut f32, enabled: bool) -> bool {
+    let before = *speed;
+    ui.add_enabled_ui(enabled, |ui| {
+        egui::ComboBox::from_id_source("playback_speed")
+            .selected_text(speed_label(*speed))
+            .width(56.0)
+            .show_ui(ui, |ui| {
+                for value in PLAYBACK_SPEEDS {
+                    ui.selectable_value(speed, value, speed_label(value));
+                }
+            })
+            .response
+            .on_hover_text("播放速度");
+    });
+    *speed != before
+}
+
+/// 最近打开菜单中选择的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecentMenuAction {
+    Open(String),
+    Clear,
+}
+
+/// 最近打开菜单（entries 为可打开的条目，最近的在前；没有条目时禁用）
+pub fn recent_menu(ui: &mut Ui, entries: &[&str]) -> Option<RecentMenuAction> {
+    let mut action = None;
+    ui.add_enabled_ui(!entries.is_empty(), |ui| {
+        ui.menu_button(egui::RichText::new("最近 ▾").size(12.0).color(egui::Color32::WHITE), |ui| {
+            for &entry in entries {
+                if ui.button(recent_files::display_name(entry)).on_hover_text(entry).clicked() {
+                    action = Some(RecentMenuAction::Open(entry.to_string()));
+                    ui.close_menu();
+                }
+            }
+            ui.separator();
+            if ui.button("清空最近打开").clicked() {
+                action = Some(RecentMenuAction::Clear);
+                ui.close_menu();
+            }
+        })
+        .response
+        .on_hover_text("最近打开");
+    });
+    action
+}
+
+/// 章节列表菜单（current 为当前所在章节），返回选择的章节索引
+pub fn chapter_menu(ui: &mut Ui, chapters: &[Chapter], current: Option<usize>) -> Option<usize> {
+    let mut selected = None;
+    ui.menu_button(egui::RichText::new("章节 ▾").size(12.0).color(egui::Color32::WHITE), |ui| {
+        egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+            for (i, chapter) in chapters.iter().enumerate() {
+                let text = format!("{}  {}", format_time(chapter.start_ms), chapter.title);
+                if ui.selectable_label(current == Some(i), text).clicked() {
+                    selected = Some(i);
+                    ui.close_menu();
+                }
+            }
+        });
+    })
+    .response
+    .on_hover_text("章节列表 (Ctrl+Shift+←/→ 切换章节)");
+    selected
+}
+
+/// 是否有控件持有键盘焦点（方向键、空格、Enter 交给控件，全局快捷键不处理）
+pub fn widget_has_focus(ctx: &egui::Context) -> bool {
+    ctx.memory(|memory| memory.focused().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::accesskit::Role;
+
+    /// 在无窗口的上下文中绘制一帧控制栏，返回 AccessKit 树中的节点 (角色, 名称)
+    fn accesskit_nodes(state: ControlBarState) -> Vec<(Role, Option<String>)> {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let mut icons = IconAtlas::new();
+        let output = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let mut position = 12.0;
+                progress_slider(ui, &mut position, 600.0, state.has_media, progress_label(12_000, 600_000));
+                ui.horizontal(|ui| {
+                    show_buttons(ui, &mut icons, &state);
+                    mute_button(ui, &mut icons, state.is_playing);
+                    volume_slider(ui, &mut 0.65);
+                });
+            });
+        });
+        let update = output.platform_output.accesskit_update.expect("没有生成 AccessKit 树");
+        update
+            .nodes
+            .iter()
+            .map(|(_, node)| (node.role(), node.name().map(str::to_string)))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_control_has_name_and_role() {
+        for state in [
+            ControlBarState::default(),
+            ControlBarState { has_media: true, is_playing: true, ..ControlBarState::default() },
+            ControlBarState { has_media: true, is_live: true, has_next: true, ..ControlBarState::default() },
+        ] {
+            let nodes = accesskit_nodes(state);
+            let interactive: Vec<_> = nodes
+                .iter()
+                .filter(|(role, _)| matches!(role, Role::Button | Role::Slider))
+                .collect();
+            // 进度条 + 6 个按钮 + 静音 + 音量
+            assert_eq!(interactive.len(), 9, "{:?}", nodes);
+            for (role, name) in &interactive {
+                assert!(name.as_deref().is_some_and(|name| !name.is_empty()), "{:?} 缺少名称", role);
+            }
+            // 不存在无角色的可交互元素
+            assert!(nodes.iter().all(|(role, _)| *role != Role::Unknown), "{:?}", nodes);
+
+            let names: Vec<_> = interactive.iter().filter_map(|(_, name)| name.as_deref()).collect();
+            for button in ControlButton::visible(&state) {
+                assert!(names.contains(&button.label(&state)), "缺少按钮 {:?}: {:?}", button, names);
+            }
+            assert!(names.contains(&"音量 65%"));
+            assert!(names.contains(&if state.is_playing { "取消静音" } else { "静音" }), "{:?}", names);
+            assert!(names.contains(&"播放进度 00:12 / 10:00"), "{:?}", names);
+        }
+    }
+
+    #[test]
+    fn test_play_button_label_follows_state() {
+        let playing = ControlBarState { has_media: true, is_playing: true, ..ControlBarState::default() };
+        assert_eq!(ControlButton::PlayPause.label(&playing), "暂停");
+        assert_eq!(ControlButton::PlayPause.label(&ControlBarState::default()), "播放");
+        assert_eq!(ControlButton::visible(&playing)[5], ControlButton::Stop);
+        let live = ControlBarState { is_live: true, ..playing };
+        assert_eq!(ControlButton::visible(&live)[5], ControlButton::JumpToLive);
+    }
+
+    #[test]
+    fn test_speed_labels() {
+        let labels: Vec<_> = PLAYBACK_SPEEDS.iter().map(|&speed| speed_label(speed)).collect();
+        assert_eq!(labels, ["0.5x", "0.75x", "1x", "1.25x", "1.5x", "2x"]);
+    }
+
+    #[test]
+    fn test_playlist_buttons_follow_playlist_position() {
+        let last_item = ControlBarState { has_media: true, has_previous: true, ..ControlBarState::default() };
+        assert!(ControlButton::Previous.enabled(&last_item));
+        assert!(!ControlButton::Next.enabled(&last_item));
+        assert_eq!(ControlButton::Next.tooltip(&last_item), "下一个 (PageDown)");
+    }
+}
@@ -0,0 +1,79 @@
+// 拖放打开（拖入文件时的高亮提示和拖入内容的分类）
+//
+// 一次拖入多个文件时：字幕文件加载为当前视频的外部字幕，媒体文件按拖入顺序作为播放列表播放；
+// 扩展名可识别的媒体文件优先，都不可识别时仍交给 FFmpeg 尝试打开。没有媒体文件时才按文件夹（图像序列）处理。
+
+use egui::{Align2, Color32, Context, FontId, Id, LayerId, Order};
+use std::path::PathBuf;
+
+use super::m3u::is_playlist_file;
+use myy_player::{is_subtitle_file, is_supported_image_file, is_supported_video_file};
+
+/// 拖入内容的分类结果
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DroppedFiles {
+    /// 要播放的媒体文件（按拖入顺序）
+    pub media: Vec<PathBuf>,
+    /// 外部字幕文件
+    pub subtitles: Vec<PathBuf>,
+    /// 第一个文件夹（按图像序列打开）
+    pub folder: Option<PathBuf>,
+}
+
+impl DroppedFiles {
+    /// 按扩展名分类（is_dir 判断路径是否为文件夹）
+    pub fn classify(paths: Vec<PathBuf>, is_dir: impl Fn(&PathBuf) -> bool) -> Self {
+        let mut dropped = Self::default();
+        let mut unrecognized = Vec::new();
+        for path in paths {
+            if is_dir(&path) {
+                dropped.folder.get_or_insert(path);
+            } else if is_subtitle_file(&path) {
+                dropped.subtitles.push(path);
+            } else if is_supported_video_file(&path) || is_supported_image_file(&path) || is_playlist_file(&path) {
+                dropped.media.push(path);
+            } else {
+                unrecognized.push(path);
+            }
+        }
+        if dropped.media.is_empty() {
+            dropped.media = unrecognized;
+        }
+        dropped
+    }
+}
+
+/// 拖动文件悬停在窗口上时绘制高亮边框和提示
+pub fn paint_hover_overlay(ctx: &Context, file_count: usize) {
+    let rect = ctx.screen_rect();
+    let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("drop_target")));
+    painter.rect_filled(rect, 0.0, Color32::from_black_alpha(140));
+    painter.rect_stroke(rect.shrink(6.0), 8.0, egui::Stroke::new(3.0, Color32::from_rgb(100, 170, 255)));
+    let text = if file_count > 1 { format!("松开以打开 {} 个文件", file_count) } else { "松开以打开".to_string() };
+    painter.text(rect.center(), Align2::CENTER_CENTER, text, FontId::proportional(24.0), Color32::WHITE);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(paths: &[&str]) -> DroppedFiles {
+        DroppedFiles::classify(paths.iter().map(PathBuf::from).collect(), |path| path.extension().is_none())
+    }
+
+    #[test]
+    fn test_subtitles_are_separated_and_media_keeps_order() {
+        let dropped = classify(&["/v/b.mkv", "/v/b.srt", "/v/notes.txt", "/v/a.MP4", "/v/frames"]);
+        assert_eq!(dropped.media, [PathBuf::from("/v/b.mkv"), PathBuf::from("/v/a.MP4")]);
+        assert_eq!(dropped.subtitles, [PathBuf::from("/v/b.srt")]);
+        assert_eq!(dropped.folder, Some(PathBuf::from("/v/frames")));
+    }
+
+    #[test]
+    fn test_unrecognized_files_are_tried_when_nothing_else_matches() {
+        let dropped = classify(&["/v/clip.webm", "/v/c.ass"]);
+        assert_eq!(dropped.media, [PathBuf::from("/v/clip.webm")]);
+        assert_eq!(dropped.subtitles.len(), 1);
+        assert_eq!(classify(&["/v/only.vtt"]).media.len(), 0);
+    }
+}
@@ -0,0 +1,120 @@
+// 从中间省略过长的文件名（信息栏、最近文件、标签页、窗口标题等）
+//
+// 从中间删除字符并插入"…"，保留开头和扩展名，直到 fits 判定放得下。按字素簇处理，
+// 中英文混排和 emoji 组合序列都不会被拆开。界面按实际字体宽度判定（middle_ellipsis），
+// 窗口标题等无法按像素测量的场合按字符数判定（middle_ellipsis_chars）。
+
+use egui::{Color32, FontId, Ui};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// 省略号
+const ELLIPSIS: &str = "…";
+
+/// 保留为扩展名的最大字符数（更长的"扩展名"多半是文件名的一部分）
+const MAX_EXTENSION_CHARS: usize = 8;
+
+/// 扩展名（包含"."）的起始字节位置；没有扩展名或以"."开头的隐藏文件返回 None
+fn extension_start(text: &str) -> Option<usize> {
+    let dot = text.rfind('.').filter(|&dot| dot > 0)?;
+    let extension = &text[dot + 1..];
+    let count = extension.graphemes(true).count();
+    (count > 0 && count <= MAX_EXTENSION_CHARS && !extension.contains(char::is_whitespace)).then_some(dot)
+}
+
+/// 从中间省略文本直到 fits 返回 true（保留开头和扩展名；始终放不下时返回最短的形式）
+///
+/// fits 对保留字符数单调：保留越多越宽。
+pub fn middle_ellipsis_with(text: &str, mut fits: impl FnMut(&str) -> bool) -> String {
+    if fits(text) {
+        return text.to_string();
+    }
+    let (stem, extension) = text.split_at(extension_start(text).unwrap_or(text.len()));
+    let graphemes: Vec<&str> = stem.graphemes(true).collect();
+    let build = |keep: usize| {
+        let head = keep.div_ceil(2);
+        let tail = keep / 2;
+        let mut result = String::with_capacity(text.len());
+        result.extend(graphemes[..head].iter().copied());
+        result.push_str(ELLIPSIS);
+        result.extend(graphemes[graphemes.len() - tail..].iter().copied());
+        result.push_str(extension);
+        result
+    };
+
+    // 二分查找放得下的最多保留字符数（全部保留时已经确认放不下）
+    let (mut low, mut high) = (0, graphemes.len());
+    while low + 1 < high {
+        let mid = (low + high) / 2;
+        if fits(&build(mid)) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    build(low)
+}
+
+/// 按字符数从中间省略（窗口标题等无法按像素测量的场合）
+pub fn middle_ellipsis_chars(text: &str, max_chars: usize) -> String {
+    middle_ellipsis_with(text, |candidate| candidate.graphemes(true).count() <= max_chars)
+}
+
+/// 从中间省略文本，直到按 font 排版后的宽度不超过 max_width（保留开头和扩展名）
+pub fn middle_ellipsis(text: &str, max_width: f32, font: &FontId, ui: &Ui) -> String {
+    ui.fonts(|fonts| {
+        middle_ellipsis_with(text, |candidate| {
+            fonts.layout_no_wrap(candidate.to_string(), font.clone(), Color32::WHITE).size().x <= max_width
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fits_measured_width() {
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let font = FontId::proportional(13.0);
+                let width = |text: &str| ui.fonts(|fonts| fonts.layout_no_wrap(text.to_string(), font.clone(), Color32::WHITE).size().x);
+
+                let name = "Pleasant Goat and Big Big Wolf - Episode 01 [1080p].mkv";
+                let fitted = middle_ellipsis(name, 150.0, &font, ui);
+                assert!(width(&fitted) <= 150.0);
+                assert!(fitted.starts_with("Pleasant"));
+                assert!(fitted.ends_with(".mkv"));
+
+                assert_eq!(middle_ellipsis("short.mp4", 150.0, &font, ui), "short.mp4");
+            });
+        });
+    }
+
+    #[test]
+    fn test_middle_ellipsis_keeps_start_and_extension() {
+        let name = "[字幕组] 喜洋洋与灰太狼 Pleasant Goat 第01集 1080p.mkv";
+        let fitted = middle_ellipsis_chars(name, 20);
+        assert_eq!(fitted.graphemes(true).count(), 20);
+        assert!(fitted.starts_with("[字幕组] 喜"));
+        assert!(fitted.ends_with("1080p.mkv"));
+        assert!(fitted.contains(ELLIPSIS));
+
+        // 短于预算时原样返回
+        assert_eq!(middle_ellipsis_chars("短名.mp4", 20), "短名.mp4");
+        // 放不下时退化为省略号加扩展名
+        assert_eq!(middle_ellipsis_chars("很长很长的文件名.mp4", 2), "….mp4");
+        // 没有扩展名、隐藏文件
+        assert_eq!(middle_ellipsis_chars("abcdefghij", 5), "ab…ij");
+        assert_eq!(middle_ellipsis_chars(".bashrc_backup_copy", 7), ".ba…opy");
+    }
+
+    #[test]
+    fn test_middle_ellipsis_never_splits_emoji() {
+        let flag = "🇨🇳";
+        let text = format!("{}{}{}{}{}{}.webm", flag, flag, flag, flag, flag, flag);
+        let fitted = middle_ellipsis_chars(&text, 8);
+        assert_eq!(fitted, format!("{}…{}.webm", flag, flag));
+        assert!(!fitted.contains('\u{FFFD}'));
+    }
+}
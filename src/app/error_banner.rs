@@ -0,0 +1,114 @@
+// 打开失败提示条
+//
+// 打开文件、连接网络流或附加解封装器失败时，在视频区域顶部显示红色提示条：第一行是按错误分类的标题
+// （文件不存在、不支持的编码、网络超时等），第二行是处理建议，第三行是原始错误。
+// 提示条显示 BANNER_DURATION 后自动消失，也可以点击关闭；之后成功打开媒体时立即清除。
+
+use std::time::{Duration, Instant};
+
+use myy_player::{ErrorCategory, PlayerError};
+
+use super::osd::OsdStyle;
+
+/// 提示条的显示时长
+pub const BANNER_DURATION: Duration = Duration::from_secs(8);
+
+/// 提示条背景色
+const BANNER_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(150, 30, 30, 230);
+
+/// 提示文字（分类标题、处理建议、原始错误各一行）
+pub fn error_message(category: ErrorCategory, detail: &str) -> String {
+    format!("{}\n{}\n{}", category.title(), category.hint(), detail)
+}
+
+/// 错误链中第一个可分类的错误（播放器错误或 IO 错误）
+pub fn category_of(error: &anyhow::Error) -> ErrorCategory {
+    error
+        .chain()
+        .find_map(|cause| {
+            if let Some(e) = cause.downcast_ref::<PlayerError>() {
+                Some(e.category())
+            } else {
+                cause.downcast_ref::<std::io::Error>().map(|e| ErrorCategory::of_io(e.kind()))
+            }
+        })
+        .unwrap_or(ErrorCategory::Other)
+}
+
+/// 在视频区域顶部显示提示条（超时或点击关闭后清除）
+pub fn show(ui: &mut egui::Ui, video_rect: egui::Rect, style: OsdStyle, last_error: &mut Option<(String, Instant)>) {
+    let Some((message, shown_at)) = last_error.as_ref() else {
+        return;
+    };
+    let elapsed = shown_at.elapsed();
+    if elapsed >= BANNER_DURATION {
+        *last_error = None;
+        return;
+    }
+    ui.ctx().request_repaint_after(BANNER_DURATION - elapsed);
+
+    let mut lines = message.lines();
+    let title = lines.next().unwrap_or_default();
+    let mut dismiss = false;
+    egui::Area::new(egui::Id::new("error_banner"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(video_rect.center_top() + egui::Vec2::new(0.0, style.size(20.0)))
+        .pivot(egui::Align2::CENTER_TOP)
+        .show(ui.ctx(), |ui| {
+            egui::Frame::none()
+                .fill(BANNER_COLOR)
+                .rounding(style.size(4.0))
+                .inner_margin(egui::Margin::symmetric(style.size(14.0), style.size(10.0)))
+                .show(ui, |ui| {
+                    ui.set_max_width(style.max_width(video_rect) - style.size(28.0));
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("⚠ {}", title))
+                                .size(style.size(16.0))
+                                .strong()
+                                .color(egui::Color32::WHITE)
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            dismiss = ui
+                                .add(egui::Button::new(egui::RichText::new("✕").color(egui::Color32::WHITE)).frame(false))
+                                .on_hover_text("关闭")
+                                .clicked();
+                        });
+                    });
+                    for (index, line) in lines.enumerate() {
+                        // 处理建议用正常亮度，原始错误用较暗的颜色
+                        let color = if index == 0 { egui::Color32::from_gray(235) } else { egui::Color32::from_gray(200) };
+                        let size = if index == 0 { 14.0 } else { 12.0 };
+                        ui.label(egui::RichText::new(line).size(style.size(size)).color(color));
+                    }
+                });
+        });
+    if dismiss {
+        *last_error = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_category_is_found_in_error_chain() {
+        let missing = anyhow::Error::from(PlayerError::FFmpegError(ffmpeg_next::Error::DecoderNotFound));
+        assert_eq!(category_of(&missing), ErrorCategory::UnsupportedCodec);
+
+        let io: std::result::Result<(), _> = Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied"));
+        let wrapped = io.context("读取播放列表失败").unwrap_err();
+        assert_eq!(category_of(&wrapped), ErrorCategory::PermissionDenied);
+
+        assert_eq!(category_of(&anyhow::anyhow!("播放列表为空")), ErrorCategory::Other);
+    }
+
+    #[test]
+    fn test_message_has_title_hint_and_detail() {
+        let message = error_message(ErrorCategory::NetworkTimeout, "http://example.com/live.m3u8: 超时");
+        let lines: Vec<&str> = message.lines().collect();
+        assert_eq!(lines, [ErrorCategory::NetworkTimeout.title(), ErrorCategory::NetworkTimeout.hint(), "http://example.com/live.m3u8: 超时"]);
+    }
+}
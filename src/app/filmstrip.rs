@@ -0,0 +1,287 @@
+// 胶片视图（F 键或控制栏按钮切换，Esc 关闭）
+//
+// 在控制栏上方显示沿时间轴均匀分布的缩略图：点击跳转到该时间点，当前位置所在的一段高亮。
+// 每个缩略图对应时间轴上的一段，取该段的中点（避开片头黑场和片尾）。
+// 缩略图由后台线程逐个生成（见 Thumbnailer），未生成的位置先显示占位框；
+// 生成结果进入共享的预览缓存，再次打开同一文件时直接从缓存读取。
+// 切换文件时重新生成；最近几个文件生成完的胶片保留在内存中，切回时直接显示。
+// 生成中的胶片登记为后台任务，关闭窗口时可以取消。
+// 直播流、无法跳转的媒体和网络流不提供胶片视图。
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use egui::{ColorImage, Context, TextureHandle, TextureOptions};
+use log::debug;
+
+use super::sessions::tab_title;
+use super::job_registry::{CancelBehavior, JobRegistry};
+use super::time_format::format_time;
+use myy_player::{PreviewCache, Thumbnailer};
+
+/// 缩略图数量
+pub const FILMSTRIP_COUNT: usize = 16;
+
+/// 内存中保留的已完成胶片数
+const CACHE_CAPACITY: usize = 4;
+
+/// 缩略图最大显示宽度（逻辑像素，窗口较窄时按比例缩小）
+const MAX_THUMBNAIL_WIDTH: f32 = 120.0;
+
+/// 缩略图间距
+const THUMBNAIL_GAP: f32 = 4.0;
+
+/// 高亮当前段的边框颜色
+const HIGHLIGHT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 80);
+
+/// 生成中的胶片的刷新间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 时间轴均分为 count 段，返回每段中点的时间（毫秒）
+fn filmstrip_times(duration_ms: i64, count: usize) -> Vec<i64> {
+    if duration_ms <= 0 || count == 0 {
+        return Vec::new();
+    }
+    (0..count)
+        .map(|i| ((2 * i + 1) as i128 * duration_ms as i128 / (2 * count) as i128) as i64)
+        .collect()
+}
+
+/// 当前位置所在的段（超出时间轴时夹紧到首尾两段）
+fn segment_index(position_ms: i64, duration_ms: i64, count: usize) -> Option<usize> {
+    if duration_ms <= 0 || count == 0 {
+        return None;
+    }
+    let position = position_ms.clamp(0, duration_ms) as i128;
+    Some(((position * count as i128 / duration_ms as i128) as usize).min(count - 1))
+}
+
+/// 胶片视图不可用的原因（可用时为 None）
+pub fn unavailable_reason(has_media: bool, is_live: bool, is_network: bool, still_image: bool, duration_ms: i64) -> Option<&'static str> {
+    if !has_media {
+        Some("没有正在播放的媒体")
+    } else if is_live {
+        Some("直播流无法生成缩略图")
+    } else if still_image || duration_ms <= 0 {
+        Some("当前媒体不支持跳转，无法生成缩略图")
+    } else if is_network {
+        Some("网络流不生成缩略图（避免重复下载）")
+    } else {
+        None
+    }
+}
+
+/// 一个文件的胶片
+struct Strip {
+    path: String,
+    duration_ms: i64,
+    times: Vec<i64>,
+    textures: Vec<Option<TextureHandle>>,
+    worker: Option<Thumbnailer>,  // 生成完毕后为 None
+}
+
+impl Strip {
+    fn new(path: &str, duration_ms: i64, preview_cache: Arc<PreviewCache>, jobs: &JobRegistry) -> Self {
+        let times = filmstrip_times(duration_ms, FILMSTRIP_COUNT);
+        debug!("🎞️ 开始生成胶片: {} 张 ({})", times.len(), path);
+        // 任务随生成线程的回调一起释放（生成完毕或取消后注销）
+        let job = jobs.register(format!("生成缩略图 {}", tab_title(path)), CancelBehavior::Discard);
+        let worker = Thumbnailer::spawn(PathBuf::from(path), times.clone(), preview_cache, move |progress| {
+            job.set_progress(progress);
+            !job.is_cancelled()
+        });
+        Self {
+            path: path.to_string(),
+            duration_ms,
+            textures: vec![None; times.len()],
+            worker: Some(worker),
+            times,
+        }
+    }
+
+    fn matches(&self, path: &str, duration_ms: i64) -> bool {
+        self.path == path && self.duration_ms == duration_ms
+    }
+
+    /// 把新生成的缩略图上传为纹理
+    fn poll(&mut self, ctx: &Context) {
+        let Some(worker) = &self.worker else {
+            return;
+        };
+        let finished = worker.is_finished();
+        for thumbnail in worker.poll() {
+            let image = &thumbnail.image;
+            let size = [image.width as usize, image.height as usize];
+            if image.data.len() != size[0] * size[1] * 4 {
+                continue;
+            }
+            if let Some(slot) = self.textures.get_mut(thumbnail.index) {
+                *slot = Some(ctx.load_texture(
+                    format!("filmstrip_{}", thumbnail.index),
+                    ColorImage::from_rgba_unmultiplied(size, &image.data),
+                    TextureOptions::LINEAR,
+                ));
+            }
+        }
+        // 线程退出前发出的缩略图已在本次取完
+        if finished {
+            self.worker = None;
+        } else {
+            ctx.request_repaint_after(POLL_INTERVAL);
+        }
+    }
+}
+
+/// 胶片视图
+pub struct Filmstrip {
+    visible: bool,
+    current: Option<Strip>,
+    cache: VecDeque<Strip>,  // 最近生成完的胶片（最新的在前）
+    preview_cache: Arc<PreviewCache>,  // 共享的预览缓存（内存 + 磁盘）
+    jobs: JobRegistry,  // 生成中的胶片登记为后台任务
+}
+
+impl Filmstrip {
+    pub fn new(preview_cache: Arc<PreviewCache>, jobs: JobRegistry) -> Self {
+        Self { visible: false, current: None, cache: VecDeque::new(), preview_cache, jobs }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// 关闭胶片视图（返回之前是否显示）
+    pub fn close(&mut self) -> bool {
+        std::mem::replace(&mut self.visible, false)
+    }
+
+    /// 切换到指定文件的胶片（缓存中没有时开始生成；未生成完的旧胶片被丢弃，后台线程随之取消）
+    fn prepare(&mut self, path: &str, duration_ms: i64) -> &mut Strip {
+        if !self.current.as_ref().is_some_and(|strip| strip.matches(path, duration_ms)) {
+            if let Some(previous) = self.current.take().filter(|strip| strip.worker.is_none()) {
+                self.cache.retain(|strip| !strip.matches(&previous.path, previous.duration_ms));
+                self.cache.push_front(previous);
+                self.cache.truncate(CACHE_CAPACITY);
+            }
+            let cached = self.cache.iter().position(|strip| strip.matches(path, duration_ms));
+            self.current = Some(match cached.and_then(|index| self.cache.remove(index)) {
+                Some(strip) => strip,
+                None => Strip::new(path, duration_ms, self.preview_cache.clone(), &self.jobs),
+            });
+        }
+        let (preview_cache, jobs) = (&self.preview_cache, &self.jobs);
+        self.current.get_or_insert_with(|| Strip::new(path, duration_ms, preview_cache.clone(), jobs))
+    }
+
+    /// 在 video_rect 底部绘制胶片，返回被点击的缩略图对应的时间点
+    pub fn show(&mut self, ctx: &Context, video_rect: egui::Rect, path: &str, duration_ms: i64, position_ms: i64) -> Option<i64> {
+        if !self.visible {
+            return None;
+        }
+        let strip = self.prepare(path, duration_ms);
+        strip.poll(ctx);
+
+        let count = strip.times.len();
+        let thumbnail_width = ((video_rect.width() - 24.0) / count as f32 - THUMBNAIL_GAP).clamp(24.0, MAX_THUMBNAIL_WIDTH);
+        let thumbnail_size = egui::vec2(thumbnail_width, thumbnail_width * 9.0 / 16.0);
+        let current = segment_index(position_ms, duration_ms, count);
+
+        let mut clicked = None;
+        egui::Area::new(egui::Id::new("filmstrip"))
+            .fixed_pos(video_rect.center_bottom() - egui::vec2(0.0, 8.0))
+            .pivot(egui::Align2::CENTER_BOTTOM)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_black_alpha(200))
+                    .rounding(4.0)
+                    .inner_margin(egui::Margin::same(6.0))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.spacing_mut().item_spacing = egui::vec2(THUMBNAIL_GAP, 0.0);
+                            for (index, (&time_ms, texture)) in strip.times.iter().zip(&strip.textures).enumerate() {
+                                ui.vertical(|ui| {
+                                    let (rect, response) = ui.allocate_exact_size(thumbnail_size, egui::Sense::click());
+                                    match texture {
+                                        Some(texture) => {
+                                            // 按画面比例居中（占位框为 16:9）
+                                            let image_size = texture.size_vec2();
+                                            let scale = (rect.width() / image_size.x).min(rect.height() / image_size.y);
+                                            ui.painter().rect_filled(rect, 2.0, egui::Color32::BLACK);
+                                            ui.painter().image(
+                                                texture.id(),
+                                                egui::Rect::from_center_size(rect.center(), image_size * scale),
+                                                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                                                egui::Color32::WHITE,
+                                            );
+                                        }
+                                        None => {
+                                            ui.painter().rect_filled(rect, 2.0, egui::Color32::from_gray(45));
+                                        }
+                                    }
+                                    if current == Some(index) {
+                                        ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(2.0, HIGHLIGHT_COLOR));
+                                    } else if response.hovered() {
+                                        ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::WHITE));
+                                    }
+                                    let label = format_time(time_ms);
+                                    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, format!("跳转到 {}", label)));
+                                    if response.hovered() {
+                                        ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                                    }
+                                    if response.clicked() {
+                                        clicked = Some(time_ms);
+                                    }
+                                    ui.add_sized(
+                                        egui::vec2(thumbnail_size.x, 14.0),
+                                        egui::Label::new(egui::RichText::new(label).size(10.0).color(egui::Color32::from_gray(200))),
+                                    );
+                                });
+                            }
+                        });
+                    });
+            });
+        clicked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unavailable_reasons() {
+        assert_eq!(unavailable_reason(true, false, false, false, 60_000), None);
+        assert!(unavailable_reason(false, false, false, false, 0).is_some());
+        assert_eq!(unavailable_reason(true, true, true, false, 0), Some("直播流无法生成缩略图"));
+        assert!(unavailable_reason(true, false, false, true, 0).is_some());
+        assert!(unavailable_reason(true, false, false, false, 0).is_some());
+        assert!(unavailable_reason(true, false, true, false, 60_000).is_some());
+    }
+
+    #[test]
+    fn test_times_are_segment_midpoints() {
+        assert_eq!(filmstrip_times(10_000, 5), vec![1_000, 3_000, 5_000, 7_000, 9_000]);
+        assert_eq!(filmstrip_times(0, 5), Vec::<i64>::new());
+        assert_eq!(filmstrip_times(10_000, 0), Vec::<i64>::new());
+        for (i, time) in filmstrip_times(7_200_000, 16).into_iter().enumerate() {
+            assert_eq!(segment_index(time, 7_200_000, 16), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_segment_index_is_clamped() {
+        assert_eq!(segment_index(0, 10_000, 5), Some(0));
+        assert_eq!(segment_index(1_999, 10_000, 5), Some(0));
+        assert_eq!(segment_index(2_000, 10_000, 5), Some(1));
+        assert_eq!(segment_index(10_000, 10_000, 5), Some(4));
+        assert_eq!(segment_index(-500, 10_000, 5), Some(0));
+        assert_eq!(segment_index(99_000, 10_000, 5), Some(4));
+        assert_eq!(segment_index(5_000, 0, 5), None);
+    }
+}
@@ -0,0 +1,399 @@
+// 图标系统（VS Code Codicons SVG，按需以当前 DPI 光栅化并缓存为纹理）
+//
+// 所有 SVG 使用 16x16 viewBox、白色填充，显示时通过 tint 着色（悬停、禁用等状态）。
+
+use egui::{Color32, ColorImage, Context, FontId, Response, TextureHandle, TextureId, TextureOptions, Ui, Vec2};
+use log::{debug, error};
+use std::collections::HashMap;
+
+/// 禁用状态的图标颜色
+const DISABLED_TINT: Color32 = Color32::from_rgb(90, 90, 90);
+
+/// 图标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Icon {
+    Play,
+    Pause,
+    Stop,
+    Previous,
+    Next,
+    OpenFile,
+    OpenFolder,
+    OpenUrl,
+    Fullscreen,
+    ExitFullscreen,
+    VolumeHigh,
+    VolumeMuted,
+    Subtitle,
+    Settings,
+    Camera,
+    Loop,
+    Bookmark,
+    Live,
+    Filmstrip,
+}
+
+impl Icon {
+    /// codicon 名称（用于纹理命名和日志）
+    pub fn name(self) -> &'static str {
+        match self {
+            Icon::Play => "play",
+            Icon::Pause => "debug-pause",
+            Icon::Stop => "debug-stop",
+            Icon::Previous => "skip-previous",
+            Icon::Next => "skip-next",
+            Icon::OpenFile => "folder-opened",
+            Icon::OpenFolder => "folder",
+            Icon::OpenUrl => "globe",
+            Icon::Fullscreen => "screen-full",
+            Icon::ExitFullscreen => "screen-normal",
+            Icon::VolumeHigh => "unmute",
+            Icon::VolumeMuted => "mute",
+            Icon::Subtitle => "closed-caption",
+            Icon::Settings => "settings-gear",
+            Icon::Camera => "device-camera",
+            Icon::Loop => "sync",
+            Icon::Bookmark => "bookmark",
+            Icon::Live => "broadcast",
+            Icon::Filmstrip => "filmstrip",
+        }
+    }
+
+    /// 内嵌的 SVG 源（修改后需同步更新快照测试中的哈希）
+    pub fn svg(self) -> &'static str {
+        match self {
+            Icon::Play => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M3 3v10l10-5z" fill="white"/></svg>"#,
+            Icon::Pause => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M4.5 3C4.22386 3 4 3.22386 4 3.5V12.5C4 12.7761 4.22386 13 4.5 13H7.5C7.77614 13 8 12.7761 8 12.5V3.5C8 3.22386 7.77614 3 7.5 3H4.5ZM9.5 3C9.22386 3 9 3.22386 9 3.5V12.5C9 12.7761 9.22386 13 9.5 13H12.5C12.7761 13 13 12.7761 13 12.5V3.5C13 3.22386 12.7761 3 12.5 3H9.5Z" fill="white"/></svg>"#,
+            Icon::Stop => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><rect x="3" y="3" width="10" height="10" rx="1" fill="white"/></svg>"#,
+            Icon::Previous => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M3 3h1.5v10H3zM13 3v10L5 8z" fill="white"/></svg>"#,
+            Icon::Next => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M11.5 3H13v10h-1.5zM3 3v10l8-5z" fill="white"/></svg>"#,
+            Icon::OpenFile => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M1.75 2A1.75 1.75 0 0 0 0 3.75v8.5C0 13.216.784 14 1.75 14h12.5A1.75 1.75 0 0 0 16 12.25v-8.5A1.75 1.75 0 0 0 14.25 2H7.5a.25.25 0 0 1-.2-.1l-.9-1.2C6.07.22 5.26 0 4.75 0h-3A1.75 1.75 0 0 0 0 1.75V3h1.5a.25.25 0 0 1 .2.1l.9 1.2c.23.31.934.7 1.44.7H1.75zM1.5 6.5v5.75c0 .138.112.25.25.25H14.25a.25.25 0 0 0 .25-.25V6.5H1.5z" fill="white"/></svg>"#,
+            Icon::OpenFolder => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M1.5 3.5a1 1 0 0 1 1-1h3.6l1.5 1.5h5.9a1 1 0 0 1 1 1v7.5a1 1 0 0 1-1 1h-11a1 1 0 0 1-1-1z" fill="none" stroke="white"/></svg>"#,
+            Icon::OpenUrl => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><g fill="none" stroke="white"><circle cx="8" cy="8" r="6.5"/><ellipse cx="8" cy="8" rx="2.75" ry="6.5"/><path d="M1.5 8h13M2.5 4.75h11M2.5 11.25h11"/></g></svg>"#,
+            Icon::Fullscreen => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M2 2h4v1H3v3H2zM10 2h4v4h-1V3h-3zM2 10h1v3h3v1H2zM13 10h1v4h-4v-1h3z" fill="white"/></svg>"#,
+            Icon::ExitFullscreen => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M5 2h1v4H2V5h3zM10 2h1v3h3v1h-4zM2 10h4v4H5v-3H2zM10 10h4v1h-3v3h-1z" fill="white"/></svg>"#,
+            Icon::VolumeHigh => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M1 5.5h3L8 2.5v11L4 10.5H1z" fill="white"/><path d="M10 5.5a3.5 3.5 0 0 1 0 5M11.75 3.5a6 6 0 0 1 0 9" fill="none" stroke="white"/></svg>"#,
+            Icon::VolumeMuted => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M1 5.5h3L8 2.5v11L4 10.5H1z" fill="white"/><path d="M10.5 6l4 4M14.5 6l-4 4" fill="none" stroke="white"/></svg>"#,
+            Icon::Subtitle => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><rect x="1.5" y="3.5" width="13" height="9" rx="1" fill="none" stroke="white"/><path d="M4 8.5h3M9 8.5h3M4 10.5h5M10.5 10.5H12" fill="none" stroke="white"/></svg>"#,
+            Icon::Settings => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><g fill="none" stroke="white"><circle cx="8" cy="8" r="2"/><circle cx="8" cy="8" r="4.5"/><path d="M8 1v2.5M8 12.5V15M1 8h2.5M12.5 8H15M3.05 3.05l1.77 1.77M11.18 11.18l1.77 1.77M3.05 12.95l1.77-1.77M11.18 4.82l1.77-1.77" stroke-width="1.5"/></g></svg>"#,
+            Icon::Camera => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><g fill="none" stroke="white"><path d="M5.5 3L4.5 4.5H2a.5.5 0 0 0-.5.5v7.5a.5.5 0 0 0 .5.5h12a.5.5 0 0 0 .5-.5V5a.5.5 0 0 0-.5-.5h-2.5L10.5 3z"/><circle cx="8" cy="8.75" r="2.5"/></g></svg>"#,
+            Icon::Loop => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M2.5 8a5.5 5.5 0 0 1 9.6-3.6M13.5 8a5.5 5.5 0 0 1-9.6 3.6" fill="none" stroke="white"/><path d="M12.5 1.5V5H9zM3.5 14.5V11H7z" fill="white"/></svg>"#,
+            Icon::Bookmark => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M4 1.5h8a.5.5 0 0 1 .5.5v12.5L8 11.5l-4.5 3V2a.5.5 0 0 1 .5-.5z" fill="none" stroke="white"/></svg>"#,
+            Icon::Live => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><circle cx="8" cy="8" r="2" fill="white"/><path d="M5.17 5.17a4 4 0 0 0 0 5.66M10.83 5.17a4 4 0 0 1 0 5.66M3.05 3.05a7 7 0 0 0 0 9.9M12.95 3.05a7 7 0 0 1 0 9.9" fill="none" stroke="white" stroke-width="1.2"/></svg>"#,
+            Icon::Filmstrip => r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><rect x="1.5" y="2.5" width="13" height="11" rx="1" fill="none" stroke="white"/><path d="M4.5 2.5v11M11.5 2.5v11M4.5 8h7" fill="none" stroke="white"/><path d="M2.25 4h1.5v1.5h-1.5zM2.25 7.25h1.5v1.5h-1.5zM2.25 10.5h1.5v1.5h-1.5zM12.25 4h1.5v1.5h-1.5zM12.25 7.25h1.5v1.5h-1.5zM12.25 10.5h1.5v1.5h-1.5z" fill="white"/></svg>"#,
+        }
+    }
+}
+
+/// 图标纹理缓存（按图标和物理像素尺寸缓存，DPI 变化时整体失效，避免旧尺寸的纹理堆积）
+#[derive(Default)]
+pub struct IconAtlas {
+    textures: HashMap<(Icon, u32), TextureHandle>,
+    pixels_per_point: f32,
+}
+
+impl IconAtlas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取按当前 DPI 光栅化的图标纹理（logical_size 为逻辑尺寸）
+    pub fn texture(&mut self, ctx: &Context, icon: Icon, logical_size: f32) -> TextureId {
+        let pixels_per_point = ctx.pixels_per_point();
+        if pixels_per_point != self.pixels_per_point {
+            if !self.textures.is_empty() {
+                debug!("🎨 DPI 变化 ({} -> {})，重新光栅化图标", self.pixels_per_point, pixels_per_point);
+            }
+            self.textures.clear();
+            self.pixels_per_point = pixels_per_point;
+        }
+
+        let size_px = (logical_size * pixels_per_point).ceil().max(1.0) as u32;
+        self.textures
+            .entry((icon, size_px))
+            .or_insert_with(|| {
+                ctx.load_texture(
+                    format!("icon_{}_{}", icon.name(), size_px),
+                    rasterize(icon, size_px),
+                    TextureOptions::LINEAR,
+                )
+            })
+            .id()
+    }
+}
+
+/// 图标按钮（统一处理悬停背景、禁用状态、提示文字、键盘焦点和无障碍名称）
+pub struct IconButton<'a> {
+    icon: Icon,
+    size: Vec2,
+    icon_size: f32,
+    /// 图标右侧的文字（起始页的大按钮）
+    text: Option<(&'a str, FontId)>,
+    rounding: f32,
+    fill: Color32,
+    hover_fill: Color32,
+    tint: Color32,
+    hover_tint: Color32,
+    enabled: bool,
+    label: Option<&'a str>,
+    tooltip: Option<&'a str>,
+}
+
+impl<'a> IconButton<'a> {
+    /// 正方形按钮（size 为按钮边长，icon_size 为图标边长）
+    pub fn new(icon: Icon, size: f32, icon_size: f32) -> Self {
+        Self {
+            icon,
+            size: Vec2::splat(size),
+            icon_size,
+            text: None,
+            rounding: 0.0,
+            fill: Color32::TRANSPARENT,
+            hover_fill: Color32::from_rgb(60, 60, 60),
+            tint: Color32::WHITE,
+            hover_tint: Color32::WHITE,
+            enabled: true,
+            label: None,
+            tooltip: None,
+        }
+    }
+
+    /// 带文字的按钮（图标在文字左侧，两者整体居中；size 为按钮尺寸）
+    pub fn with_text(icon: Icon, size: Vec2, icon_size: f32, text: &'a str, font: FontId) -> Self {
+        Self { size, text: Some((text, font)), ..Self::new(icon, 0.0, icon_size) }
+    }
+
+    /// 背景圆角
+    pub fn rounding(mut self, rounding: f32) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// 背景色（常态 / 悬停）
+    pub fn fill(mut self, fill: Color32, hover_fill: Color32) -> Self {
+        self.fill = fill;
+        self.hover_fill = hover_fill;
+        self
+    }
+
+    /// 图标颜色（常态 / 悬停）
+    pub fn tint(mut self, tint: Color32, hover_tint: Color32) -> Self {
+        self.tint = tint;
+        self.hover_tint = hover_tint;
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// 屏幕阅读器读出的名称（未设置时使用提示文字）
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn tooltip(mut self, tooltip: &'a str) -> Self {
+        self.tooltip = Some(tooltip);
+        self
+    }
+
+    /// 绘制按钮（禁用时不响应点击；获得焦点时显示焦点框，Enter / 空格触发）
+    pub fn show(self, ui: &mut Ui, atlas: &mut IconAtlas) -> Response {
+        let sense = if self.enabled { egui::Sense::click() } else { egui::Sense::hover() };
+        let (rect, mut response) = ui.allocate_exact_size(self.size, sense);
+
+        let hovered = self.enabled && response.hovered();
+        if hovered {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        let fill = if hovered { self.hover_fill } else { self.fill };
+        if fill != Color32::TRANSPARENT {
+            ui.painter().rect_filled(rect, self.rounding, fill);
+        }
+
+        let tint = match (self.enabled, hovered) {
+            (false, _) => DISABLED_TINT,
+            (true, true) => self.hover_tint,
+            (true, false) => self.tint,
+        };
+        // 文字与图标同色
+        let galley = self.text.as_ref().map(|(text, font)| ui.painter().layout_no_wrap(text.to_string(), font.clone(), tint));
+        // 图标与文字之间的间距
+        let gap = self.icon_size * 0.5;
+        let content_width = self.icon_size + galley.as_ref().map_or(0.0, |galley| gap + galley.size().x);
+        let icon_center = egui::pos2(rect.center().x - content_width / 2.0 + self.icon_size / 2.0, rect.center().y);
+
+        let texture = atlas.texture(ui.ctx(), self.icon, self.icon_size);
+        ui.painter().image(
+            texture,
+            egui::Rect::from_center_size(icon_center, Vec2::splat(self.icon_size)),
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            tint,
+        );
+        if let Some(galley) = galley {
+            let text_pos = egui::pos2(icon_center.x + self.icon_size / 2.0 + gap, rect.center().y - galley.size().y / 2.0);
+            ui.painter().galley(text_pos, galley, tint);
+        }
+
+        // Tab 切换到按钮时的焦点框
+        if response.has_focus() {
+            ui.painter().rect_stroke(rect.shrink(1.0), self.rounding.max(2.0), ui.visuals().selection.stroke);
+        }
+
+        let text = self.text.as_ref().map(|(text, _)| *text);
+        let name = self.label.or(text).or(self.tooltip).unwrap_or(self.icon.name());
+        response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, name));
+
+        if let Some(tooltip) = self.tooltip {
+            response = response.on_hover_text(tooltip);
+        }
+        response
+    }
+}
+
+/// 将图标光栅化为 size_px x size_px 的 ColorImage（保持比例居中）
+pub fn rasterize(icon: Icon, size_px: u32) -> ColorImage {
+    use resvg::tiny_skia;
+    use usvg::{Options, Tree, TreeParsing};
+
+    let size = size_px as usize;
+    let tree = match Tree::from_str(icon.svg(), &Options::default()) {
+        Ok(tree) => tree,
+        Err(e) => {
+            error!("解析图标 SVG 失败 ({}): {}", icon.name(), e);
+            return placeholder_image(size);
+        }
+    };
+
+    let mut pixmap = match tiny_skia::Pixmap::new(size_px, size_px) {
+        Some(pixmap) => pixmap,
+        None => {
+            error!("创建 Pixmap 失败 ({}x{})", size_px, size_px);
+            return placeholder_image(size);
+        }
+    };
+
+    // 计算缩放和居中
+    let svg_size = tree.view_box.rect.size();
+    let scale = (size_px as f32 / svg_size.width()).min(size_px as f32 / svg_size.height());
+    let x = (size_px as f32 - svg_size.width() * scale) / 2.0;
+    let y = (size_px as f32 - svg_size.height() * scale) / 2.0;
+    let transform = tiny_skia::Transform::from_translate(x, y).post_scale(scale, scale);
+
+    pixmap.fill(tiny_skia::Color::TRANSPARENT);
+    resvg::Tree::from_usvg(&tree).render(transform, &mut pixmap.as_mut());
+
+    // tiny_skia 输出 premultiplied RGBA，egui 需要 unmultiplied
+    let pixels: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let c = p.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect();
+
+    ColorImage::from_rgba_unmultiplied([size, size], &pixels)
+}
+
+/// 占位图标（SVG 渲染失败时使用）
+fn placeholder_image(size: usize) -> ColorImage {
+    ColorImage::new([size, size], Color32::from_gray(200))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_ICONS: [Icon; 19] = [
+        Icon::Play,
+        Icon::Pause,
+        Icon::Stop,
+        Icon::Previous,
+        Icon::Next,
+        Icon::OpenFile,
+        Icon::OpenFolder,
+        Icon::OpenUrl,
+        Icon::Fullscreen,
+        Icon::ExitFullscreen,
+        Icon::VolumeHigh,
+        Icon::VolumeMuted,
+        Icon::Subtitle,
+        Icon::Settings,
+        Icon::Camera,
+        Icon::Loop,
+        Icon::Bookmark,
+        Icon::Live,
+        Icon::Filmstrip,
+    ];
+
+    /// 控制栏图标的 1x / 2x 尺寸
+    const SNAPSHOT_SIZES: [u32; 2] = [22, 44];
+
+    /// 光栅化结果快照（FNV-1a 哈希，按 ALL_ICONS 顺序，每项对应 SNAPSHOT_SIZES）
+    const SNAPSHOTS: [[u64; 2]; 19] = [
+        [0x3da38828f0e5d535, 0xec87aadc2015aaa9], // play
+        [0x6d5f5e1d37a0450f, 0x84ac8d90d5b16d9d], // debug-pause
+        [0x43dc3fbe20577837, 0xf4173a1ec10096cd], // debug-stop
+        [0xf43bded097b82497, 0x794373f2c303c739], // skip-previous
+        [0x9c9affac8e0813f1, 0xe1e567108053a029], // skip-next
+        [0x931b1b873b36b575, 0xd60fbf8f7b73ef2f], // folder-opened
+        [0xc6700944d791afff, 0x1100d0cfac6d2aab], // folder
+        [0xcd218390af268661, 0xbf79ddc128756527], // globe
+        [0xc9f3acc70087774e, 0xd57ae38d67e4fd25], // screen-full
+        [0x114982ddaf67c096, 0xb6706065949dd305], // screen-normal
+        [0xff961804c8150cb9, 0x9f040bd65311a6d4], // unmute
+        [0x99f818c8c2adae7c, 0x3f0c6206213465ad], // mute
+        [0x7c1b6cdf17567464, 0x8c537a24275050a9], // closed-caption
+        [0xe3f1b89e9e9d62f8, 0x7acbfcfa77ea3f1e], // settings-gear
+        [0x7735014d53401b8b, 0xc1a5df7f62e4390f], // device-camera
+        [0x5ea2a33cc27be214, 0x4ab08a199e568243], // sync
+        [0x02ff7b277d4507ee, 0x7353793bee264722], // bookmark
+        [0x3c4b2ee51e381fbe, 0xa6acb5f586400c8a], // broadcast
+        [0xb9a2b5ff5fad4e65, 0x43e27ba295bf7a85], // filmstrip
+    ];
+
+    fn fnv1a(image: &ColorImage) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in image.pixels.iter().flat_map(|p| p.to_array()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        hash
+    }
+
+    #[test]
+    fn test_icons_match_snapshots() {
+        let mut mismatches = Vec::new();
+        for (icon, expected) in ALL_ICONS.iter().zip(SNAPSHOTS.iter()) {
+            for (&size, &hash) in SNAPSHOT_SIZES.iter().zip(expected.iter()) {
+                let actual = fnv1a(&rasterize(*icon, size));
+                if actual != hash {
+                    mismatches.push(format!("{} @{}px: {:#018x}", icon.name(), size, actual));
+                }
+            }
+        }
+        assert!(mismatches.is_empty(), "图标光栅化结果与快照不一致：\n{}", mismatches.join("\n"));
+    }
+
+    #[test]
+    fn test_icons_are_not_blank() {
+        for icon in ALL_ICONS {
+            let image = rasterize(icon, 22);
+            assert_eq!(image.size, [22, 22]);
+            let opaque = image.pixels.iter().filter(|p| p.a() > 0).count();
+            assert!(opaque > 0 && opaque < 22 * 22, "{} 光栅化结果异常", icon.name());
+        }
+    }
+
+    #[test]
+    fn test_svg_sources_parse() {
+        use usvg::{Options, Tree, TreeParsing};
+        for icon in ALL_ICONS {
+            assert!(Tree::from_str(icon.svg(), &Options::default()).is_ok(), "{} 的 SVG 无法解析", icon.name());
+        }
+    }
+}
@@ -0,0 +1,222 @@
+// 后台任务登记（连拍保存、缩略图生成、配置导入等长时间任务）
+//
+// 任务开始时向 JobRegistry 登记并取得 JobHandle，通过它上报进度、检查取消信号；
+// 任务结束（complete 或 JobHandle 被 drop）时自动注销。关闭窗口时据此判断是否需要确认，
+// 选择"取消任务并退出"后，各任务收到取消信号，按登记时声明的方式收尾后注销，全部注销后才真正退出。
+
+use log::{debug, info};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 任务被取消时如何处理已写出的部分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelBehavior {
+    /// 收尾使输出可用（连拍保存完已收到的帧）
+    Finalize,
+    /// 没有输出文件，直接放弃
+    Discard,
+}
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Running,
+    Cancelling,  // 已发出取消信号，等待任务收尾
+}
+
+/// 任务快照（供界面显示）
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobInfo {
+    pub id: u64,
+    pub name: String,
+    pub progress: Option<f32>,  // 0.0 - 1.0（无法估计时为 None）
+    pub state: JobState,
+    pub on_cancel: CancelBehavior,
+}
+
+struct JobEntry {
+    info: JobInfo,
+    cancel: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: u64,
+    jobs: BTreeMap<u64, JobEntry>,
+}
+
+/// 任务登记表（可在线程间共享）
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    inner: Arc<Mutex<Registry>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记任务
+    pub fn register(&self, name: impl Into<String>, on_cancel: CancelBehavior) -> JobHandle {
+        let mut registry = self.inner.lock().unwrap();
+        registry.next_id += 1;
+        let id = registry.next_id;
+        let cancel = Arc::new(AtomicBool::new(false));
+        let info = JobInfo { id, name: name.into(), progress: None, state: JobState::Running, on_cancel };
+        debug!("📋 登记任务 #{}: {}", id, info.name);
+        registry.jobs.insert(id, JobEntry { info, cancel: cancel.clone() });
+        JobHandle { id, registry: self.clone(), cancel }
+    }
+
+    /// 当前登记的任务（按登记顺序）
+    pub fn snapshot(&self) -> Vec<JobInfo> {
+        self.inner.lock().unwrap().jobs.values().map(|entry| entry.info.clone()).collect()
+    }
+
+    /// 是否没有进行中的任务
+    pub fn is_idle(&self) -> bool {
+        self.inner.lock().unwrap().jobs.is_empty()
+    }
+
+    /// 向指定任务发出取消信号
+    pub fn cancel(&self, id: u64) {
+        if let Some(entry) = self.inner.lock().unwrap().jobs.get_mut(&id) {
+            entry.info.state = JobState::Cancelling;
+            entry.cancel.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// 向所有任务发出取消信号（任务收尾后自行注销）
+    pub fn cancel_all(&self) {
+        let mut registry = self.inner.lock().unwrap();
+        for entry in registry.jobs.values_mut() {
+            entry.info.state = JobState::Cancelling;
+            entry.cancel.store(true, Ordering::SeqCst);
+        }
+        if !registry.jobs.is_empty() {
+            info!("🛑 取消 {} 个后台任务", registry.jobs.len());
+        }
+    }
+
+    fn set_progress(&self, id: u64, progress: f32) {
+        if let Some(entry) = self.inner.lock().unwrap().jobs.get_mut(&id) {
+            entry.info.progress = Some(progress.clamp(0.0, 1.0));
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        if let Some(entry) = self.inner.lock().unwrap().jobs.remove(&id) {
+            debug!("📋 任务 #{} 结束: {}", id, entry.info.name);
+        }
+    }
+}
+
+/// 任务句柄（由执行任务的线程持有，drop 时注销任务）
+pub struct JobHandle {
+    id: u64,
+    registry: JobRegistry,
+    cancel: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    /// 上报进度（0.0 - 1.0）
+    pub fn set_progress(&self, progress: f32) {
+        self.registry.set_progress(self.id, progress);
+    }
+
+    /// 是否收到取消信号（收到后应按 CancelBehavior 收尾，然后 drop 句柄）
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+
+    /// 任务完成
+    pub fn complete(self) {}
+}
+
+impl Drop for JobHandle {
+    fn drop(&mut self) {
+        self.registry.remove(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_register_progress_complete() {
+        let registry = JobRegistry::new();
+        assert!(registry.is_idle());
+
+        let job = registry.register("生成缩略图", CancelBehavior::Discard);
+        job.set_progress(1.5);
+        let jobs = registry.snapshot();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].progress, Some(1.0));
+        assert_eq!(jobs[0].state, JobState::Running);
+
+        job.complete();
+        assert!(registry.is_idle());
+    }
+
+    #[test]
+    fn test_cancel_marks_state_until_worker_finishes() {
+        let registry = JobRegistry::new();
+        let burst = registry.register("连拍", CancelBehavior::Finalize);
+        let download = registry.register("字幕下载", CancelBehavior::Discard);
+
+        registry.cancel(burst.id);
+        assert!(burst.is_cancelled());
+        assert!(!download.is_cancelled());
+        let states: Vec<_> = registry.snapshot().iter().map(|job| job.state).collect();
+        assert_eq!(states, vec![JobState::Cancelling, JobState::Running]);
+
+        // 收到取消信号后任务仍然登记，直到工作线程收尾并释放句柄
+        drop(burst);
+        assert_eq!(registry.snapshot().len(), 1);
+        registry.cancel_all();
+        assert!(download.is_cancelled());
+        drop(download);
+        assert!(registry.is_idle());
+    }
+
+    #[test]
+    fn test_concurrent_jobs_drain_after_cancel_all() {
+        let registry = JobRegistry::new();
+        let mut workers = Vec::new();
+        for i in 0..8 {
+            let job = registry.register(format!("任务 {}", i), CancelBehavior::Finalize);
+            workers.push(thread::spawn(move || {
+                // 偶数任务自然完成，奇数任务运行到被取消为止
+                for step in 0..1_000 {
+                    if job.is_cancelled() {
+                        return false;
+                    }
+                    job.set_progress(step as f32 / 1_000.0);
+                    if i % 2 == 0 && step == 10 {
+                        job.complete();
+                        return true;
+                    }
+                    thread::sleep(Duration::from_millis(1));
+                }
+                true
+            }));
+        }
+
+        // 等待偶数任务完成后取消其余任务
+        while registry.snapshot().len() > 4 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        registry.cancel_all();
+        let completed = workers.into_iter().map(|w| w.join().unwrap()).filter(|done| *done).count();
+        assert_eq!(completed, 4);
+        assert!(registry.is_idle());
+
+        // 取消之后登记的新任务不受影响
+        let late = registry.register("新任务", CancelBehavior::Discard);
+        assert!(!late.is_cancelled());
+    }
+}
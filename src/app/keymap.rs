@@ -0,0 +1,324 @@
+// 快捷键映射（按键组合 -> 播放器动作）
+//
+// 默认绑定即原先写死在键盘处理中的快捷键。用户修改过绑定时，完整的映射以 { "Ctrl+Right": "next_chapter" }
+// 的形式保存在设置文件中；读取时跳过无法识别的按键和动作（记录警告），不影响其他绑定和设置的读取。
+// 一个按键组合只能绑定一个动作：重新绑定到已被其他动作使用的组合时拒绝并说明冲突。
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use egui::{Key, Modifiers};
+use log::warn;
+
+use super::action::PlayerAction;
+use super::seek_step::SeekStep;
+use super::window_size::WindowScale;
+
+/// 字幕延迟的调整步长（毫秒，Z / X 与 Shift+Z / X）
+const SUBTITLE_DELAY_STEP_MS: i64 = 100;
+const SUBTITLE_DELAY_LARGE_STEP_MS: i64 = 500;
+
+/// 按键组合（Ctrl 在 macOS 上对应 Cmd）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KeyCombo {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub key: Key,
+}
+
+impl KeyCombo {
+    pub const fn new(key: Key) -> Self {
+        Self { ctrl: false, shift: false, alt: false, key }
+    }
+
+    pub const fn ctrl(key: Key) -> Self {
+        Self { ctrl: true, ..Self::new(key) }
+    }
+
+    pub const fn shift(key: Key) -> Self {
+        Self { shift: true, ..Self::new(key) }
+    }
+
+    pub const fn alt(key: Key) -> Self {
+        Self { alt: true, ..Self::new(key) }
+    }
+
+    /// 按键事件对应的组合
+    pub fn from_event(key: Key, modifiers: Modifiers) -> Self {
+        Self { ctrl: modifiers.command, shift: modifiers.shift, alt: modifiers.alt, key }
+    }
+
+    /// 解析 "Ctrl+Shift+Tab" 形式的文字（按键名称见 egui::Key::name）
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let key = Key::from_name(parts.pop()?)?;
+        let mut combo = Self::new(key);
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "cmd" => combo.ctrl = true,
+                "shift" => combo.shift = true,
+                "alt" => combo.alt = true,
+                _ => return None,
+            }
+        }
+        Some(combo)
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (held, name) in [(self.ctrl, "Ctrl+"), (self.shift, "Shift+"), (self.alt, "Alt+")] {
+            if held {
+                f.write_str(name)?;
+            }
+        }
+        f.write_str(self.key.name())
+    }
+}
+
+/// 可绑定快捷键的动作（设置文件中的名称、设置页中的说明、默认按键）
+pub struct Bindable {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub action: PlayerAction,
+    pub default: KeyCombo,
+}
+
+/// 所有可绑定的动作（设置页按此顺序列出）
+pub fn bindable_actions() -> Vec<Bindable> {
+    let bindable = |id, label, action, default| Bindable { id, label, action, default };
+    vec![
+        bindable("play_pause", "播放/暂停", PlayerAction::PlayPause, KeyCombo::new(Key::Space)),
+        bindable("seek_back", "快退（小步）", PlayerAction::SeekBack(SeekStep::Small), KeyCombo::new(Key::ArrowLeft)),
+        bindable("seek_forward", "快进（小步）", PlayerAction::SeekForward(SeekStep::Small), KeyCombo::new(Key::ArrowRight)),
+        bindable("seek_back_medium", "快退（中步）", PlayerAction::SeekBack(SeekStep::Medium), KeyCombo::alt(Key::ArrowLeft)),
+        bindable("seek_forward_medium", "快进（中步）", PlayerAction::SeekForward(SeekStep::Medium), KeyCombo::alt(Key::ArrowRight)),
+        bindable("seek_back_large", "快退（大步）", PlayerAction::SeekBack(SeekStep::Large), KeyCombo::ctrl(Key::ArrowLeft)),
+        bindable("seek_forward_large", "快进（大步）", PlayerAction::SeekForward(SeekStep::Large), KeyCombo::ctrl(Key::ArrowRight)),
+        bindable(
+            "previous_chapter",
+            "上一章",
+            PlayerAction::PreviousChapter,
+            KeyCombo { shift: true, ..KeyCombo::ctrl(Key::ArrowLeft) },
+        ),
+        bindable(
+            "next_chapter",
+            "下一章",
+            PlayerAction::NextChapter,
+            KeyCombo { shift: true, ..KeyCombo::ctrl(Key::ArrowRight) },
+        ),
+        bindable("volume_up", "音量增大", PlayerAction::VolumeUp, KeyCombo::new(Key::ArrowUp)),
+        bindable("volume_down", "音量减小", PlayerAction::VolumeDown, KeyCombo::new(Key::ArrowDown)),
+        bindable("toggle_mute", "静音", PlayerAction::ToggleMute, KeyCombo::new(Key::M)),
+        bindable("step_frame_forward", "下一帧", PlayerAction::StepFrameForward, KeyCombo::new(Key::Period)),
+        bindable("step_frame_backward", "上一帧", PlayerAction::StepFrameBackward, KeyCombo::new(Key::Comma)),
+        bindable("previous_keyframe", "上一个关键帧", PlayerAction::PreviousKeyframe, KeyCombo::shift(Key::ArrowLeft)),
+        bindable("next_keyframe", "下一个关键帧", PlayerAction::NextKeyframe, KeyCombo::shift(Key::ArrowRight)),
+        bindable("play_previous", "播放列表上一项", PlayerAction::PlayPrevious, KeyCombo::new(Key::PageUp)),
+        bindable("play_next", "播放列表下一项", PlayerAction::PlayNext, KeyCombo::new(Key::PageDown)),
+        bindable("jump_to_live", "回到直播", PlayerAction::JumpToLive, KeyCombo::new(Key::End)),
+        bindable("toggle_fullscreen", "全屏", PlayerAction::ToggleFullscreen, KeyCombo::new(Key::F11)),
+        bindable("escape", "关闭面板 / 退出全屏", PlayerAction::Escape, KeyCombo::new(Key::Escape)),
+        bindable("toggle_info", "信息面板", PlayerAction::ToggleInfo, KeyCombo::new(Key::I)),
+        bindable("toggle_settings", "设置", PlayerAction::ToggleSettings, KeyCombo::ctrl(Key::Comma)),
+        bindable("toggle_filmstrip", "胶片视图", PlayerAction::ToggleFilmstrip, KeyCombo::new(Key::F)),
+        bindable("mark_loop_a", "标记 A 点", PlayerAction::MarkLoopA, KeyCombo::new(Key::OpenBracket)),
+        bindable("mark_loop_b", "标记 B 点", PlayerAction::MarkLoopB, KeyCombo::new(Key::CloseBracket)),
+        bindable("toggle_repeat_one", "单曲循环", PlayerAction::ToggleRepeatOne, KeyCombo::new(Key::R)),
+        bindable("mark_intro_end", "标记片头结束", PlayerAction::MarkIntroEnd, KeyCombo::new(Key::B)),
+        bindable(
+            "subtitle_earlier",
+            "字幕提前 100ms",
+            PlayerAction::ShiftSubtitleDelay(-SUBTITLE_DELAY_STEP_MS),
+            KeyCombo::new(Key::Z),
+        ),
+        bindable(
+            "subtitle_later",
+            "字幕推后 100ms",
+            PlayerAction::ShiftSubtitleDelay(SUBTITLE_DELAY_STEP_MS),
+            KeyCombo::new(Key::X),
+        ),
+        bindable(
+            "subtitle_earlier_large",
+            "字幕提前 500ms",
+            PlayerAction::ShiftSubtitleDelay(-SUBTITLE_DELAY_LARGE_STEP_MS),
+            KeyCombo::shift(Key::Z),
+        ),
+        bindable(
+            "subtitle_later_large",
+            "字幕推后 500ms",
+            PlayerAction::ShiftSubtitleDelay(SUBTITLE_DELAY_LARGE_STEP_MS),
+            KeyCombo::shift(Key::X),
+        ),
+        bindable("reset_subtitle_delay", "重置字幕延迟", PlayerAction::ResetSubtitleDelay, KeyCombo::ctrl(Key::Z)),
+        bindable("cycle_audio_track", "切换音频轨道", PlayerAction::CycleAudioTrack, KeyCombo::new(Key::A)),
+        bindable("cycle_subtitle_track", "切换字幕轨道", PlayerAction::CycleSubtitleTrack, KeyCombo::new(Key::V)),
+        bindable("cycle_fit_mode", "切换画面缩放", PlayerAction::CycleFitMode, KeyCombo::shift(Key::A)),
+        // 截图是否包含字幕由设置决定（见 snapshot_with_subtitles）
+        bindable("snapshot", "截图", PlayerAction::Snapshot { with_subtitles: false }, KeyCombo::new(Key::S)),
+        bindable(
+            "snapshot_with_subtitles",
+            "带字幕截图",
+            PlayerAction::Snapshot { with_subtitles: true },
+            KeyCombo::ctrl(Key::S),
+        ),
+        bindable("start_burst", "连拍（按住）", PlayerAction::StartBurst, KeyCombo::shift(Key::S)),
+        bindable("new_session", "新建标签页", PlayerAction::NewSession, KeyCombo::ctrl(Key::T)),
+        bindable("close_session", "关闭标签页", PlayerAction::CloseSession(None), KeyCombo::ctrl(Key::W)),
+        bindable("next_session", "下一个标签页", PlayerAction::NextSession, KeyCombo::ctrl(Key::Tab)),
+        bindable(
+            "previous_session",
+            "上一个标签页",
+            PlayerAction::PreviousSession,
+            KeyCombo { shift: true, ..KeyCombo::ctrl(Key::Tab) },
+        ),
+        bindable("window_50", "窗口 50%", PlayerAction::SnapWindow(WindowScale::Percent(50)), KeyCombo::alt(Key::Num1)),
+        bindable("window_100", "窗口 100%", PlayerAction::SnapWindow(WindowScale::Percent(100)), KeyCombo::alt(Key::Num2)),
+        bindable("window_200", "窗口 200%", PlayerAction::SnapWindow(WindowScale::Percent(200)), KeyCombo::alt(Key::Num3)),
+    ]
+}
+
+/// 快捷键映射
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: HashMap<KeyCombo, PlayerAction>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self { bindings: bindable_actions().into_iter().map(|bindable| (bindable.default, bindable.action)).collect() }
+    }
+}
+
+impl Keymap {
+    /// 从设置读取（None 使用默认绑定；无法识别的按键或动作跳过并记录警告）
+    pub fn from_config(config: Option<&BTreeMap<String, String>>) -> Self {
+        let Some(config) = config else {
+            return Self::default();
+        };
+        let actions = bindable_actions();
+        let mut bindings = HashMap::new();
+        for (combo_text, id) in config {
+            let Some(combo) = KeyCombo::parse(combo_text) else {
+                warn!("⚠️ 忽略无法识别的快捷键: {}", combo_text);
+                continue;
+            };
+            let Some(bindable) = actions.iter().find(|bindable| bindable.id == id) else {
+                warn!("⚠️ 忽略未知的快捷键动作: {} = {}", combo_text, id);
+                continue;
+            };
+            bindings.insert(combo, bindable.action.clone());
+        }
+        Self { bindings }
+    }
+
+    /// 保存到设置的形式（按键文字 -> 动作名称）
+    pub fn to_config(&self) -> BTreeMap<String, String> {
+        let actions = bindable_actions();
+        self.bindings
+            .iter()
+            .filter_map(|(combo, action)| {
+                let bindable = actions.iter().find(|bindable| bindable.action == *action)?;
+                Some((combo.to_string(), bindable.id.to_string()))
+            })
+            .collect()
+    }
+
+    /// 按键组合对应的动作
+    pub fn action(&self, combo: &KeyCombo) -> Option<&PlayerAction> {
+        self.bindings.get(combo)
+    }
+
+    /// 绑定到动作的按键组合（按显示顺序）
+    pub fn combos_for(&self, action: &PlayerAction) -> Vec<KeyCombo> {
+        let mut combos: Vec<KeyCombo> =
+            self.bindings.iter().filter(|(_, bound)| *bound == action).map(|(combo, _)| *combo).collect();
+        combos.sort();
+        combos
+    }
+
+    /// 把动作改绑到新的按键组合（替换原有的按键）；组合已被其他动作使用时返回错误说明
+    pub fn rebind(&mut self, action: &PlayerAction, combo: KeyCombo) -> Result<(), String> {
+        if let Some(bound) = self.bindings.get(&combo).filter(|bound| *bound != action) {
+            let label = bindable_actions()
+                .into_iter()
+                .find(|bindable| bindable.action == *bound)
+                .map_or("其他动作", |bindable| bindable.label);
+            return Err(format!("{} 已用于「{}」", combo, label));
+        }
+        self.bindings.retain(|_, bound| bound != action);
+        self.bindings.insert(combo, action.clone());
+        Ok(())
+    }
+
+    /// 取消动作的所有按键
+    pub fn unbind(&mut self, action: &PlayerAction) {
+        self.bindings.retain(|_, bound| bound != action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combo_text_round_trip() {
+        let combo = KeyCombo { shift: true, ..KeyCombo::ctrl(Key::Tab) };
+        assert_eq!(combo.to_string(), "Ctrl+Shift+Tab");
+        assert_eq!(KeyCombo::parse("Ctrl+Shift+Tab"), Some(combo));
+        assert_eq!(KeyCombo::parse("alt + 1"), Some(KeyCombo::alt(Key::Num1)));
+        assert_eq!(KeyCombo::parse("OpenBracket"), Some(KeyCombo::new(Key::OpenBracket)));
+        assert_eq!(KeyCombo::parse("Hyper+A"), None);
+        assert_eq!(KeyCombo::parse("Ctrl+"), None);
+        for bindable in bindable_actions() {
+            assert_eq!(KeyCombo::parse(&bindable.default.to_string()), Some(bindable.default), "{}", bindable.id);
+        }
+    }
+
+    #[test]
+    fn test_defaults_are_unique_and_survive_config_round_trip() {
+        let actions = bindable_actions();
+        let keymap = Keymap::default();
+        assert_eq!(keymap.bindings.len(), actions.len());
+        assert_eq!(keymap.action(&KeyCombo::new(Key::Space)), Some(&PlayerAction::PlayPause));
+        assert_eq!(keymap.action(&KeyCombo::ctrl(Key::ArrowRight)), Some(&PlayerAction::SeekForward(SeekStep::Large)));
+        assert_eq!(keymap.action(&KeyCombo { shift: true, ..KeyCombo::ctrl(Key::ArrowRight) }), Some(&PlayerAction::NextChapter));
+        assert_eq!(keymap.action(&KeyCombo::shift(Key::ArrowLeft)), Some(&PlayerAction::PreviousKeyframe));
+        assert_eq!(keymap.action(&KeyCombo::alt(Key::ArrowLeft)), Some(&PlayerAction::SeekBack(SeekStep::Medium)));
+        assert_eq!(keymap.action(&KeyCombo::shift(Key::Space)), None);
+        assert_eq!(Keymap::from_config(Some(&keymap.to_config())), keymap);
+    }
+
+    #[test]
+    fn test_unknown_entries_are_skipped() {
+        let config: BTreeMap<String, String> = [
+            ("K", "play_pause"),
+            ("J", "rewind_to_moon"),
+            ("Ctrl+Nonsense", "toggle_mute"),
+        ]
+        .into_iter()
+        .map(|(combo, id)| (combo.to_string(), id.to_string()))
+        .collect();
+        let keymap = Keymap::from_config(Some(&config));
+        assert_eq!(keymap.action(&KeyCombo::new(Key::K)), Some(&PlayerAction::PlayPause));
+        assert_eq!(keymap.bindings.len(), 1);
+    }
+
+    #[test]
+    fn test_rebind_replaces_and_rejects_conflicts() {
+        let mut keymap = Keymap::default();
+        keymap.rebind(&PlayerAction::PlayPause, KeyCombo::new(Key::K)).unwrap();
+        assert_eq!(keymap.combos_for(&PlayerAction::PlayPause), [KeyCombo::new(Key::K)]);
+        assert_eq!(keymap.action(&KeyCombo::new(Key::Space)), None);
+        // 重新绑定到自己当前的按键不算冲突
+        keymap.rebind(&PlayerAction::PlayPause, KeyCombo::new(Key::K)).unwrap();
+
+        let error = keymap.rebind(&PlayerAction::ToggleMute, KeyCombo::new(Key::K)).unwrap_err();
+        assert!(error.contains("播放/暂停"), "{}", error);
+        assert_eq!(keymap.combos_for(&PlayerAction::ToggleMute), [KeyCombo::new(Key::M)]);
+
+        keymap.unbind(&PlayerAction::ToggleMute);
+        assert!(keymap.combos_for(&PlayerAction::ToggleMute).is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+// 本地 M3U / M3U8 播放列表（导入为播放队列、导出当前队列）
+//
+// .m3u8 同时也是 HLS 的格式。打开本地 .m3u/.m3u8 文件时先判断类型：
+// - 含有 HLS 分片/变体标签（#EXT-X-TARGETDURATION 等）：按 HLS 交给 FFmpeg 播放
+// - 否则视为播放列表：每行一个本地路径或 URL，#EXTINF 的标题作为显示名称，
+//   相对路径按播放列表所在目录解析，不存在的文件跳过并计数
+// 文件编码：去掉 UTF-8 BOM，合法 UTF-8 按 UTF-8 解析，否则按 GBK 解析（老的中文 .m3u 多为 GBK）
+
+use myy_player::{PlayerError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 播放列表文件扩展名
+pub const PLAYLIST_EXTENSIONS: &[&str] = &["m3u", "m3u8"];
+
+/// 只在 HLS 播放列表中出现的标签
+const HLS_TAGS: &[&str] = &[
+    "#EXT-X-TARGETDURATION",
+    "#EXT-X-MEDIA-SEQUENCE",
+    "#EXT-X-DISCONTINUITY-SEQUENCE",
+    "#EXT-X-PLAYLIST-TYPE",
+    "#EXT-X-ENDLIST",
+    "#EXT-X-STREAM-INF",
+    "#EXT-X-I-FRAME-STREAM-INF",
+    "#EXT-X-KEY",
+    "#EXT-X-MAP",
+    "#EXT-X-BYTERANGE",
+    "#EXT-X-PART-INF",
+];
+
+/// 播放列表中的一项（解析前）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct M3uEntry {
+    pub location: String,       // 路径或 URL（原样）
+    pub title: Option<String>,  // #EXTINF 标题
+}
+
+/// .m3u/.m3u8 文件的内容类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum M3uContent {
+    /// HLS 媒体/主播放列表（按流媒体播放）
+    Hls,
+    /// 本地播放列表
+    Playlist(Vec<M3uEntry>),
+}
+
+/// 播放队列中的一项（已解析为可打开的路径或 URL）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistItem {
+    pub path: String,
+    pub title: Option<String>,
+}
+
+/// 导入的播放列表
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalPlaylist {
+    pub items: Vec<PlaylistItem>,
+    pub missing: Vec<String>,  // 找不到的本地文件（原样）
+}
+
+/// 是否为播放列表文件（按扩展名，不区分大小写）
+pub fn is_playlist_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PLAYLIST_EXTENSIONS.iter().any(|known| ext.eq_ignore_ascii_case(known)))
+}
+
+/// 解码播放列表文本（去掉 BOM；不是合法 UTF-8 时按 GBK 解码）
+pub fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => encoding_rs::GBK.decode_without_bom_handling(bytes).0.into_owned(),
+    }
+}
+
+/// #EXTINF:时长 属性...,标题 中的标题（属性值中的逗号在引号内）
+fn extinf_title(info: &str) -> Option<String> {
+    let mut quoted = false;
+    let comma = info.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ',' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let title = info[comma + 1..].trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// 解析文本并判断是 HLS 还是本地播放列表
+pub fn parse(text: &str) -> M3uContent {
+    let mut entries = Vec::new();
+    let mut pending_title = None;
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if HLS_TAGS.iter().any(|tag| line.starts_with(tag)) {
+            return M3uContent::Hls;
+        }
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            pending_title = extinf_title(info);
+        } else if !line.starts_with('#') {
+            entries.push(M3uEntry { location: line.to_string(), title: pending_title.take() });
+        }
+    }
+    M3uContent::Playlist(entries)
+}
+
+/// 是否为 URL（scheme://，file:// 除外）
+pub fn is_url(location: &str) -> bool {
+    location.split_once("://").is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+            && !scheme.eq_ignore_ascii_case("file")
+    })
+}
+
+/// 本地路径（file:// 转为路径，相对路径按 base_dir 解析）
+fn local_path(location: &str, base_dir: &Path) -> PathBuf {
+    let location = match location.get(..7) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("file://") => {
+            let path = &location[7..];
+            // file:///C:/Videos/a.mp4 → C:/Videos/a.mp4
+            match path.as_bytes() {
+                [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => &path[1..],
+                _ => path,
+            }
+        }
+        _ => location,
+    };
+    base_dir.join(location)
+}
+
+/// 把条目解析为可打开的路径或 URL；exists 判定本地文件是否存在
+pub fn resolve_entries(entries: Vec<M3uEntry>, base_dir: &Path, exists: impl Fn(&Path) -> bool) -> LocalPlaylist {
+    let mut playlist = LocalPlaylist::default();
+    for entry in entries {
+        if is_url(&entry.location) {
+            playlist.items.push(PlaylistItem { path: entry.location, title: entry.title });
+            continue;
+        }
+        let path = local_path(&entry.location, base_dir);
+        if exists(&path) {
+            playlist.items.push(PlaylistItem { path: path.to_string_lossy().to_string(), title: entry.title });
+        } else {
+            playlist.missing.push(entry.location);
+        }
+    }
+    playlist
+}
+
+/// 读取本地播放列表文件；HLS 播放列表返回 None（按流媒体播放）
+pub fn read_playlist(file: &Path) -> Result<Option<LocalPlaylist>> {
+    let bytes = fs::read(file)?;
+    match parse(&decode_text(&bytes)) {
+        M3uContent::Hls => Ok(None),
+        M3uContent::Playlist(entries) => {
+            let base_dir = file.parent().unwrap_or(Path::new(""));
+            Ok(Some(resolve_entries(entries, base_dir, Path::exists)))
+        }
+    }
+}
+
+/// 生成 .m3u8 文本（UTF-8，每项带 #EXTINF 标题；没有标题时使用文件名）
+pub fn format_playlist(items: &[PlaylistItem]) -> String {
+    let mut text = String::from("#EXTM3U\n");
+    for item in items {
+        let title = item.title.clone().unwrap_or_else(|| {
+            Path::new(&item.path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| item.path.clone())
+        });
+        text.push_str(&format!("#EXTINF:-1,{}\n{}\n", title.replace(['\r', '\n'], " "), item.path));
+    }
+    text
+}
+
+/// 把播放队列写入 .m3u8 文件
+pub fn write_playlist(file: &Path, items: &[PlaylistItem]) -> Result<()> {
+    if items.is_empty() {
+        return Err(PlayerError::Other("播放队列为空".to_string()));
+    }
+    fs::write(file, format_playlist(items))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(location: &str, title: Option<&str>) -> M3uEntry {
+        M3uEntry { location: location.to_string(), title: title.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_parse_playlist_with_bom_and_crlf() {
+        let bytes = b"\xEF\xBB\xBF#EXTM3U\r\n#EXTINF:1320,\xE7\xAC\xAC\xE4\xB8\x80\xE9\x9B\x86\r\nep01.mkv\r\n\r\n#EXTINF:-1 tvg-name=\"a,b\" group-title=\"TV\",News, Live\r\nhttp://example.com/live.ts\r\nep02.mkv";
+        let M3uContent::Playlist(entries) = parse(&decode_text(bytes)) else {
+            panic!("应识别为播放列表");
+        };
+        assert_eq!(entries, vec![
+            entry("ep01.mkv", Some("第一集")),
+            entry("http://example.com/live.ts", Some("News, Live")),
+            entry("ep02.mkv", None),
+        ]);
+    }
+
+    #[test]
+    fn test_decode_gbk_entries() {
+        let (gbk, _, _) = encoding_rs::GBK.encode("#EXTINF:-1,喜羊羊与灰太狼\r\n动画\\喜羊羊 01.mp4\r\n");
+        assert!(std::str::from_utf8(&gbk).is_err());
+        let text = decode_text(&gbk);
+        assert_eq!(parse(&text), M3uContent::Playlist(vec![entry("动画\\喜羊羊 01.mp4", Some("喜羊羊与灰太狼"))]));
+        // UTF-8 不会被误判为 GBK
+        assert_eq!(decode_text("喜羊羊.mp4".as_bytes()), "喜羊羊.mp4");
+    }
+
+    #[test]
+    fn test_hls_playlists_are_detected() {
+        let media = "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:6\n#EXTINF:6.0,\nseg0.ts\n#EXTINF:6.0,\nseg1.ts\n";
+        assert_eq!(parse(media), M3uContent::Hls);
+        let master = "#EXTM3U\r\n#EXT-X-STREAM-INF:BANDWIDTH=1280000\r\nlow/index.m3u8\r\n";
+        assert_eq!(parse(master), M3uContent::Hls);
+        // 只有 #EXTINF 的 IPTV 列表仍是播放列表
+        let iptv = "#EXTM3U x-tvg-url=\"guide.xml\"\n#EXTINF:-1,CCTV-1\nhttp://example.com/cctv1.m3u8\n";
+        assert!(matches!(parse(iptv), M3uContent::Playlist(entries) if entries.len() == 1));
+    }
+
+    #[test]
+    fn test_resolve_mixed_urls_and_paths() {
+        let base = Path::new("/videos/list");
+        let entries = vec![
+            entry("ep01.mkv", Some("第一集")),
+            entry("https://example.com/a.mp4", None),
+            entry("/media/movie.mp4", None),
+            entry("file:///media/other.mp4", Some("Other")),
+            entry("../missing.avi", None),
+            entry("rtmp://live.example.com/app/stream", None),
+        ];
+        let exists = |path: &Path| path != Path::new("/videos/list/../missing.avi");
+        let playlist = resolve_entries(entries, base, exists);
+        let paths: Vec<&str> = playlist.items.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(paths, [
+            "/videos/list/ep01.mkv",
+            "https://example.com/a.mp4",
+            "/media/movie.mp4",
+            "/media/other.mp4",
+            "rtmp://live.example.com/app/stream",
+        ]);
+        assert_eq!(playlist.items[0].title.as_deref(), Some("第一集"));
+        assert_eq!(playlist.missing, ["../missing.avi"]);
+
+        assert!(!is_url("C:\\Videos\\a.mp4"));
+        assert!(!is_url("file:///C:/a.mp4"));
+        assert_eq!(local_path("file:///C:/Videos/a.mp4", base), base.join("C:/Videos/a.mp4"));
+        assert!(is_playlist_file(Path::new("/a/List.M3U8")));
+        assert!(!is_playlist_file(Path::new("/a/list.mp4")));
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let items = vec![
+            PlaylistItem { path: "/videos/ep01.mkv".to_string(), title: Some("第一集".to_string()) },
+            PlaylistItem { path: "https://example.com/a.mp4".to_string(), title: None },
+        ];
+        let text = format_playlist(&items);
+        assert!(text.starts_with("#EXTM3U\n#EXTINF:-1,第一集\n/videos/ep01.mkv\n"));
+        let M3uContent::Playlist(entries) = parse(&text) else {
+            panic!("应识别为播放列表");
+        };
+        assert_eq!(entries, vec![entry("/videos/ep01.mkv", Some("第一集")), entry("https://example.com/a.mp4", Some("a"))]);
+        assert!(write_playlist(Path::new("/nonexistent/empty.m3u8"), &[]).is_err());
+    }
+}
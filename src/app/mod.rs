@@ -1,18 +1,109 @@
+mod action;
+mod burst_capture;
+mod capabilities;
+mod control_bar;
+mod drop_target;
+mod ellipsis;
+mod error_banner;
+mod filmstrip;
+mod icons;
+mod job_registry;
+mod keymap;
+mod m3u;
+mod osd;
+mod recent_files;
+mod safe_mode;
+mod seek_step;
+mod sessions;
+mod settings_drawer;
+mod skip_ranges;
+mod snapshot;
+mod start_screen;
+mod subtitle_backdrop;
+mod subtitle_stack;
+mod sync_tuning;
+mod time_format;
+mod timeline_preview;
+mod user_data;
+mod video_clicks;
+mod volume;
+mod watch_folder;
+mod wheel;
+mod window_size;
+
 use anyhow::Result;
-use egui::{Context, Ui, FontDefinitions, FontData, FontFamily, ColorImage, TextureHandle, TextureOptions};
+use egui::{Context, Ui, FontDefinitions, FontData, FontFamily};
 use log::{debug, error, info, warn};
 use parking_lot::RwLock;
+use std::ffi::OsString;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::player::manager::PlaybackManager;
 use crate::renderer::egui_video_renderer::EguiVideoRenderer;
-use crate::core::{MediaSource, StreamState};
+use crate::renderer::video_view::FitMode;
+use myy_player::Player;
+use myy_player::{chapter_at, Chapter, MediaSource, MAX_VOLUME, PresentedFrameInfo, StreamState, SubtitleFrame, TrackInfo, TrackSource, SUPPORTED_VIDEO_EXTENSIONS};
+use myy_player::{find_sequence_in_folder, infer_sequence, is_supported_image_file, SequencePattern, DEFAULT_SEQUENCE_FPS, SUPPORTED_IMAGE_EXTENSIONS};
+use myy_player::{DemuxEvent, PlayerError};
+use watch_folder::WatchFolder;
+use myy_player::{PositionHistory, WatchState};
+use job_registry::{CancelBehavior, JobHandle, JobRegistry, JobState};
+use myy_player::{LiveResume, LiveStatus, TimeshiftLimits};
+use m3u::{PlaylistItem, PLAYLIST_EXTENSIONS};
+use crate::logging::{LogComponent, LogControl, SELECTABLE_LEVELS};
+use myy_player::StreamPackets;
+use myy_player::StallEvent;
+use myy_player::RESYNC_THRESHOLD_MS;
+use myy_player::SeekMode;
+use myy_player::SeekOutcome;
+use myy_player::FirstFrameDiagnosis;
+use myy_player::CrashMarkers;
+use myy_player::PreviewCache;
+use myy_player::RepeatMode;
+use myy_player::AbLoopMark;
+use myy_player::RuntimeFlags;
+use crate::platform::display_mode::{ContentRate, DisplayRefresh, RefreshRateSwitch};
+
+pub use action::PlayerAction;
+pub use window_size::MIN_INNER_SIZE;
+use capabilities::Capabilities;
+use subtitle_backdrop::{AdaptiveBackdrop, BackdropStyle};
+use subtitle_stack::SubtitleStacker;
+use sync_tuning::SyncTuning;
+use time_format::{format_duration, format_time, ms_to_secs, slider_fraction};
+use control_bar::{ControlBarState, ControlButton, RecentMenuAction};
+use drop_target::DroppedFiles;
+use crate::cli::{CommandLine, StartupMedia};
+use crate::single_instance::InstanceServer;
+use ellipsis::{middle_ellipsis, middle_ellipsis_chars};
+use filmstrip::Filmstrip;
+use timeline_preview::TimelinePreview;
+use icons::IconAtlas;
+use osd::OsdStyle;
+use sessions::{tab_title, Session, SessionList, MAX_SESSIONS};
+use start_screen::{RecentFile, RecentFilter, StartAction, MAX_RECENT_FILES};
+use settings_drawer::{SettingsDrawer, UiTheme, LOUDNESS_NORMALIZATION_HINT};
+use skip_ranges::{SkipEvent, SkipKind, SkipMark, SkipNotice, SkipRangeStore, SkipTracker, UNDO_WINDOW};
+use burst_capture::BurstCapture;
+use safe_mode::CrashNotice;
+use snapshot::{SnapshotOptions, SubtitleLayout, TemplateValues};
+use user_data::{save_settings, ImportPlan, SettingsAutoSave, UserData};
+pub use user_data::{load_settings_or_default, settings_file, user_data_dir, write_atomic, UserSettings};
+use volume::{format_boost, format_volume, is_boosted, VolumeRamp, VOLUME_STEP};
+use keymap::{KeyCombo, Keymap};
+use seek_step::{format_offset, SeekAccumulator};
+use video_clicks::{ClickInput, ClickTracker, VideoGesture};
+use wheel::{WheelAccumulator, WHEEL_SEEK_MS};
+use window_size::{fitted_scale, target_inner_size, WindowScale};
 
 pub struct VideoPlayerApp {
-    /// 播放管理器
-    playback_manager: Arc<RwLock<PlaybackManager>>,
+    /// 播放器（当前活动会话）
+    player: Arc<RwLock<Player>>,
+    
+    /// 会话标签页（每个会话独立的播放器）
+    sessions: SessionList<Session>,
     
     /// egui 视频渲染器
     video_renderer: Option<EguiVideoRenderer>,
@@ -20,25 +111,169 @@ pub struct VideoPlayerApp {
     /// UI 状态
     ui_state: UiState,
     
+    /// 用户设置（设置抽屉直接修改，自动保存到 settings.json）
+    settings: UserSettings,
+    settings_autosave: SettingsAutoSave,
+    settings_drawer: SettingsDrawer,
+    
+    /// 快捷键映射（来自设置中的自定义绑定）
+    keymap: Keymap,
+    /// 连续跳转按键的合并
+    seek_accumulator: SeekAccumulator,
+    /// 画面单击 / 双击的区分
+    video_clicks: ClickTracker,
+    /// 画面上滚轮滚动量的累积
+    wheel: WheelAccumulator,
+    
+    /// 启动时的音量渐入（只作用于启动后的第一次播放）
+    volume_ramp: VolumeRamp,
+    
+    /// 胶片视图（控制栏上方的缩略图）
+    filmstrip: Filmstrip,
+    
+    /// 进度条悬停预览提示框
+    timeline_preview: TimelinePreview,
+    
     /// 性能统计
     perf_stats: PerformanceStats,
     
     /// 当前显示的帧 PTS（用于避免重复更新）
     current_frame_pts: Option<i64>,
     
+    /// 当前片源的音画同步阈值（按帧率推导，帧率变化或修改覆盖时重新计算）
+    sync_tuning: SyncTuning,
+    sync_tuning_fps: Option<f64>,
+    
     /// 图标缓存
-    icons: Option<ControlIcons>,
+    icons: IconAtlas,
     
     /// Windows 标题栏颜色是否已设置（避免重复设置）
     #[cfg(target_os = "windows")]
     title_bar_color_set: bool,
     
-    /// Demuxer 创建结果接收通道（新架构）
-    demuxer_result_rx: crossbeam_channel::Receiver<crate::player::DemuxerCreationResult>,
-    demuxer_result_tx: crossbeam_channel::Sender<crate::player::DemuxerCreationResult>,
-    
     /// 正在加载的 URL（用于显示加载提示）
     loading_url: Option<String>,
+    
+    /// 网络中断后重新连接时要恢复到的位置（毫秒）
+    reconnect_resume_ms: Option<i64>,
+    
+    /// 屏幕提示（OSD）
+    osd_message: Option<OsdMessage>,
+    
+    /// 无法正确显示颜色的 HDR 格式提示（常驻，直到选择"仍然播放"或打开其他文件）
+    hdr_notice: Option<String>,
+    
+    /// 音频解码失败提示（可切换到的下一条音轨, 显示时刻）
+    audio_failure_notice: Option<(Option<usize>, Instant)>,
+    
+    /// 视频流解码不出画面的提示（常驻，直到选择"仅播放音频"、关闭或打开其他文件）
+    first_frame_notice: Option<FirstFrameDiagnosis>,
+    
+    /// 进行中的连拍（松开按键后保存完剩余帧才结束）
+    burst: Option<BurstCapture>,
+    
+    /// 运行时开关（--safe-mode 启动时为安全模式）
+    runtime_flags: RuntimeFlags,
+    
+    /// 解码崩溃记录（下次打开崩溃过的文件时提示以安全模式打开）
+    crash_markers: CrashMarkers,
+    
+    /// 解码崩溃提示（常驻，直到以安全模式重新打开、关闭或打开其他文件）
+    crash_notice: Option<CrashNotice>,
+    
+    /// 下一次打开文件时对该文件使用安全模式（崩溃提示中选择"以安全模式重新打开"）
+    safe_reopen: bool,
+    
+    /// 自动匹配刷新率：已切换的显示模式（drop 时恢复）
+    refresh_rate_switch: Option<RefreshRateSwitch>,
+    
+    /// 自动匹配刷新率：最近一次要求匹配的视频帧率（变化时才重新切换，撤销后不再重试）
+    refresh_rate_wanted: Option<ContentRate>,
+    
+    /// 刷新率切换提示（切换后的刷新率, 显示时刻；可撤销）
+    refresh_rate_notice: Option<(u32, Instant)>,
+    
+    /// 窗口所在显示器的刷新率（判断视频帧率是否高于显示刷新率）
+    display_refresh: DisplayRefresh,
+    
+    /// 片头 / 片尾跳过范围（按文件夹和文件保存）
+    skip_ranges: SkipRangeStore,
+    
+    /// 当前播放中已处理的跳过范围
+    skip_tracker: SkipTracker,
+    
+    /// 跳过提示（可撤销自动跳过 / 手动跳过未确认的范围）
+    skip_notice: Option<SkipNotice>,
+    
+    /// 监视文件夹（自动播放新文件）
+    watch_folder: Option<WatchFolder>,
+    
+    /// 导入的播放列表中的 #EXTINF 标题（路径 → 显示名称）
+    playlist_titles: HashMap<String, String>,
+    
+    /// 字幕堆叠布局（重叠字幕）
+    subtitle_stacker: SubtitleStacker<SubtitleCueKey>,
+    
+    /// 字幕背景自适应状态
+    subtitle_backdrop: AdaptiveBackdrop,
+    
+    /// 上一帧显示的字幕布局及其所在的视频区域（带字幕截图使用）
+    displayed_subtitles: Option<(egui::Rect, SubtitleLayout)>,
+    
+    /// 数据包检查面板暂停时冻结的记录
+    packet_snapshot: Vec<StreamPackets>,
+    
+    /// 视频视口（上一帧视频区域，用于按比例调整窗口大小）
+    video_viewport: Option<egui::Rect>,
+    
+    /// 等待确认的配置导入（文件路径, 导入计划, 任务登记）
+    pending_import: Option<(PathBuf, ImportPlan, JobHandle)>,
+    
+    /// 等待确认的图像序列播放
+    pending_sequence: Option<SequencePrompt>,
+    /// 命令行指定的启动媒体和外部字幕（第一次 update 时打开：网络地址需要异步解封装通道）
+    startup_open: Option<(StartupMedia, Option<PathBuf>)>,
+    
+    /// 其他实例转发来的媒体（单实例模式；None 表示只唤起窗口）和监听端（退出时删除锁文件）
+    forwarded_rx: Option<crossbeam_channel::Receiver<(Option<StartupMedia>, Option<PathBuf>)>>,
+    instance_server: Option<InstanceServer>,
+    
+    /// 后台任务登记（导出、录制、字幕下载、配置导入）
+    jobs: JobRegistry,
+    
+    /// 关闭窗口确认（有后台任务时拦截关闭请求）
+    close_prompt: Option<CloseStage>,
+    close_confirmed: bool,  // 任务已全部结束，放行下一次关闭请求
+    
+    /// 启动自检结果摘要（起始页底部显示）
+    capabilities: String,
+    
+    /// 运行时日志级别控制（开发者面板）
+    log_control: LogControl,
+}
+
+/// 关闭确认阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CloseStage {
+    Asking,      // 等待用户选择
+    Waiting,     // 等待任务完成后退出
+    Cancelling,  // 已取消任务，等待收尾后退出
+}
+
+/// 图像序列确认（选择帧率，或只显示选中的图像）
+struct SequencePrompt {
+    image: Option<PathBuf>,                                 // 选中的图像（拖入文件夹时为 None）
+    pattern: std::result::Result<SequencePattern, String>,  // 识别出的序列或识别失败原因
+    fps: u32,
+}
+
+/// 字幕标识（开始时间, 结束时间, 文本）
+type SubtitleCueKey = (i64, i64, String);
+
+/// 屏幕提示消息（显示在视频区域左上角，短暂停留后淡出）
+struct OsdMessage {
+    text: String,
+    created_at: Instant,
 }
 
 #[derive(Default)]
@@ -46,11 +281,14 @@ struct UiState {
     /// 当前文件路径
     current_file: Option<String>,
     
+    /// 起始页最近播放列表的筛选
+    recent_filter: RecentFilter,
+    
     /// 控制面板可见性
     controls_visible: bool,
     controls_hide_timer: Option<Instant>,
     
-    /// 音量 (0.0 - 1.0)
+    /// 音量 (0.0 - MAX_VOLUME)
     volume: f32,
     
     /// 播放速度
@@ -68,6 +306,20 @@ struct UiState {
     /// 信息面板可见性
     info_panel_visible: bool,
     
+    /// 切换标签页时跳转到相同的时间点（A/B 对比）
+    sync_session_position: bool,
+    
+    /// 直播暂停的时刻（恢复时决定是否回到直播）
+    live_paused_at: Option<Instant>,
+    
+    /// 数据包检查面板（开发者工具）
+    packet_panel_visible: bool,
+    packet_panel_paused: bool,  // 暂停刷新表格，便于阅读
+    debug_clock_jump_ms: i64,   // 调试时钟跳变量
+    
+    /// 最近一次打开失败的提示（提示文字、出现时间），显示在视频区域顶部
+    last_error: Option<(String, Instant)>,
+    
     /// 网络流相关
     show_url_dialog: bool,        // 是否显示打开 URL 对话框
     url_input: String,            // URL 输入框内容
@@ -75,24 +327,18 @@ struct UiState {
 
 struct PerformanceStats {
     fps: f32,
+    merged_frames: u64,  // 高帧率源合并显示时跳过的帧数
     frame_time: Duration,
     last_frame_time: Instant,
     frame_count: u32,
     last_fps_update: Instant,
 }
 
-/// 控制按钮图标
-struct ControlIcons {
-    play: TextureHandle,
-    pause: TextureHandle,
-    stop: TextureHandle,
-    open_file: TextureHandle,
-}
-
 impl Default for PerformanceStats {
     fn default() -> Self {
         Self {
             fps: 0.0,
+            merged_frames: 0,
             frame_time: Duration::from_secs(0),
             last_frame_time: Instant::now(),
             frame_count: 0,
@@ -102,14 +348,36 @@ impl Default for PerformanceStats {
 }
 
 impl VideoPlayerApp {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        settings: UserSettings,
+        debug_ui: bool,
+        runtime_flags: RuntimeFlags,
+        log_control: LogControl,
+    ) -> Self {
         info!("🎮 初始化 VideoPlayerApp");
 
         // 配置中文字体
         Self::setup_chinese_fonts(&cc.egui_ctx);
 
-        // 创建播放管理器
-        let playback_manager = Arc::new(RwLock::new(PlaybackManager::new()));
+        // 创建播放器
+        let player = Arc::new(RwLock::new(Player::new().expect("FFmpeg 已在启动时初始化")));
+        // 胶片视图与进度条悬停预览共用的预览缓存
+        let preview_cache = Arc::new(PreviewCache::open(PreviewCache::default_dir()));
+        // 后台任务登记（胶片视图生成缩略图、连拍保存、配置导入）
+        let jobs = JobRegistry::new();
+        player.write().set_preview_cache(preview_cache.clone());
+        player
+            .write()
+            .enable_position_history(Arc::new(PositionHistory::open(PositionHistory::default_file())));
+        player.write().set_runtime_flags(runtime_flags);
+        if runtime_flags.is_safe_mode() {
+            info!("🛡️ 安全模式：已关闭硬件解码、字幕自动加载、监视文件夹和自动匹配刷新率");
+        }
+        if debug_ui {
+            info!("🔧 已启用开发者面板（数据包检查）");
+            player.read().set_developer_tools_enabled(true);
+        }
 
         // 初始化视频渲染器
         let video_renderer = if let Some(wgpu_render_state) = cc.wgpu_render_state.as_ref() {
@@ -127,65 +395,110 @@ impl VideoPlayerApp {
             error!("❌ 无法获取 wgpu 渲染状态");
             None
         };
+        // GPU 渲染器可用时解码器直接输出 YUV，颜色转换交给着色器
+        Player::set_yuv_output_enabled(video_renderer.as_ref().is_some_and(|renderer| renderer.accepts_yuv()));
+
+        // 启动自检（硬件解码、FFmpeg、GPU 后端）
+        let capabilities = Capabilities::detect(cc.wgpu_render_state.as_ref()).summary();
 
         // 创建图标
-        let icons = Self::create_control_icons(&cc.egui_ctx);
 
         // 配置窗口标题栏样式（背景色和文字颜色）
-        Self::setup_window_theme(&cc.egui_ctx);
+        Self::setup_window_theme(&cc.egui_ctx, settings.theme);
 
-        // 创建 Demuxer 结果通道（新架构）
-        let (demuxer_result_tx, demuxer_result_rx) = crossbeam_channel::unbounded();
-
-        Self {
-            playback_manager,
+        let mut app = Self {
+            sessions: SessionList::new(Session::new(player.clone())),
+            player,
             video_renderer,
             ui_state: UiState {
-                volume: 1.0,
-                playback_speed: 1.0,
+                volume: settings.volume,
+                playback_speed: settings.playback_speed,
                 controls_visible: true,
+                packet_panel_visible: debug_ui,
+                debug_clock_jump_ms: 500,
                 ..Default::default()
             },
+            settings_autosave: SettingsAutoSave::new(settings.clone()),
+            settings_drawer: SettingsDrawer::default(),
+            keymap: Keymap::default(),
+            seek_accumulator: SeekAccumulator::default(),
+            video_clicks: ClickTracker::default(),
+            wheel: WheelAccumulator::default(),
+            volume_ramp: VolumeRamp::new(settings.startup_fade_in, settings.startup_fade_in_secs),
+            filmstrip: Filmstrip::new(preview_cache, jobs.clone()),
+            timeline_preview: TimelinePreview::default(),
+            settings,
             perf_stats: PerformanceStats {
                 last_frame_time: Instant::now(),
                 last_fps_update: Instant::now(),
                 ..Default::default()
             },
             current_frame_pts: None,
-            icons: Some(icons),
+            sync_tuning: SyncTuning::default(),
+            sync_tuning_fps: None,
+            icons: IconAtlas::new(),
             #[cfg(target_os = "windows")]
             title_bar_color_set: false,
-            demuxer_result_rx,
-            demuxer_result_tx,
             loading_url: None,
+            reconnect_resume_ms: None,
+            osd_message: None,
+            hdr_notice: None,
+            audio_failure_notice: None,
+            first_frame_notice: None,
+            burst: None,
+            runtime_flags,
+            crash_markers: CrashMarkers::new(CrashMarkers::default_file()),
+            crash_notice: None,
+            safe_reopen: false,
+            skip_ranges: SkipRangeStore::load(&SkipRangeStore::default_file()).unwrap_or_else(|e| {
+                warn!("⚠️ 读取片头片尾记录失败: {}", e);
+                SkipRangeStore::default()
+            }),
+            skip_tracker: SkipTracker::default(),
+            refresh_rate_switch: None,
+            refresh_rate_wanted: None,
+            refresh_rate_notice: None,
+            display_refresh: DisplayRefresh::default(),
+            skip_notice: None,
+            watch_folder: None,
+            playlist_titles: HashMap::new(),
+            subtitle_stacker: SubtitleStacker::new(),
+            displayed_subtitles: None,
+            subtitle_backdrop: AdaptiveBackdrop::default(),
+            packet_snapshot: Vec::new(),
+            video_viewport: None,
+            pending_import: None,
+            pending_sequence: None,
+            startup_open: None,
+            forwarded_rx: None,
+            instance_server: None,
+            jobs,
+            close_prompt: None,
+            close_confirmed: false,
+            capabilities,
+            log_control,
+        };
+        app.apply_settings();
+        // 恢复上次的静音状态和播放速度（音量在打开文件时设置）
+        {
+            let mut manager = app.player.write();
+            manager.set_muted(app.settings.muted);
+            manager.set_speed(app.settings.playback_speed);
         }
+        app
     }
 
     /// 配置窗口主题（标题栏颜色）
-    fn setup_window_theme(ctx: &Context) {
-        // 设置窗口视觉样式
-        let mut style = (*ctx.style()).clone();
-        
-        // 设置背景颜色为深色
-        style.visuals.dark_mode = true;
-        style.visuals.window_fill = egui::Color32::from_rgb(29, 29, 29);
-        style.visuals.panel_fill = egui::Color32::from_rgb(29, 29, 29);
-        
-        ctx.set_style(style);
+    fn setup_window_theme(ctx: &Context, theme: UiTheme) {
+        // 设置窗口视觉样式（深色主题背景为 rgb(29, 29, 29)）
+        theme.apply(ctx);
         // 注意：系统标题栏颜色的设置将在 setup_window_style 中进行（需要 frame 参数）
     }
     
     /// 设置窗口样式（包括系统标题栏背景色）
     fn setup_window_style(&mut self, ctx: &Context, frame: &mut eframe::Frame) {
-        // 设置窗口视觉样式
-        let mut style = (*ctx.style()).clone();
-        
-        // 设置背景颜色为深色
-        style.visuals.dark_mode = true;
-        style.visuals.window_fill = egui::Color32::from_rgb(29, 29, 29);
-        style.visuals.panel_fill = egui::Color32::from_rgb(29, 29, 29);
-        
-        ctx.set_style(style);
+        // 设置窗口视觉样式（设置抽屉中选择的主题）
+        self.settings.theme.apply(ctx);
         
         // 在 Windows 上尝试设置标题栏背景色（只设置一次）
         #[cfg(target_os = "windows")]
@@ -211,62 +524,37 @@ impl VideoPlayerApp {
             
             // raw_window_handle 0.6 使用 RawWindowHandle 枚举
             if let RawWindowHandle::Win32(handle) = raw_handle {
-                unsafe {
-                    use windows::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWINDOWATTRIBUTE};
-                    use windows::Win32::Foundation::HWND;
-                    use log::{info, warn};
-                    
-                    // HWND 期望 isize 类型，handle.hwnd.get() 返回指针，需要转换为 isize
-                    let hwnd = HWND(handle.hwnd.get() as isize);
-                    
-                    // 首先启用深色模式标题栏（Windows 11，必需）
-                    // DWMWA_USE_IMMERSIVE_DARK_MODE = 20
-                    let mut use_dark_mode = 1u32; // TRUE
-                    let result1 = DwmSetWindowAttribute(
-                        hwnd,
-                        DWMWINDOWATTRIBUTE(20), // DWMWA_USE_IMMERSIVE_DARK_MODE
-                        &mut use_dark_mode as *mut _ as *mut _,
-                        std::mem::size_of::<u32>() as u32,
-                    );
-                    if result1.is_err() {
-                        warn!("⚠️  启用深色模式标题栏失败: {:?}", result1);
-                        return false;
-                    }
-                    info!("✓ 已启用深色模式标题栏");
-                    
-                    // 设置标题栏背景色为 rgb(29, 29, 29)
-                    // RGB 格式转换为 COLORREF: BGR (Blue-Green-Red)
-                    let color_value = (29u32) | (29u32 << 8) | (29u32 << 16);
-                    
-                    // 设置标题栏颜色 (DWMWA_CAPTION_COLOR = 35, Windows 11 Build 22621+)
-                    let mut caption_color = color_value;
-                    let result2 = DwmSetWindowAttribute(
-                        hwnd,
-                        DWMWINDOWATTRIBUTE(35), // DWMWA_CAPTION_COLOR
-                        &mut caption_color as *mut _ as *mut _,
-                        std::mem::size_of::<u32>() as u32,
-                    );
-                    if result2.is_ok() {
+                use crate::platform::title_bar::{set_win32_border_color, set_win32_caption_color, set_win32_dark_mode};
+                use windows::Win32::Foundation::HWND;
+                use log::{info, warn};
+
+                // HWND 期望 isize 类型，handle.hwnd.get() 返回指针，需要转换为 isize
+                let hwnd = HWND(handle.hwnd.get() as isize);
+                const TITLE_BAR_COLOR: [u8; 3] = [29, 29, 29];
+
+                // 首先启用深色模式标题栏（Windows 11，必需）
+                if let Err(e) = set_win32_dark_mode(hwnd, true) {
+                    warn!("⚠️  启用深色模式标题栏失败: {:?}", e);
+                    return false;
+                }
+                info!("✓ 已启用深色模式标题栏");
+
+                // 设置标题栏背景色为 rgb(29, 29, 29)（Windows 11 Build 22621+）
+                match set_win32_caption_color(hwnd, TITLE_BAR_COLOR) {
+                    Ok(()) => {
                         info!("✓ 已设置标题栏颜色为 rgb(29, 29, 29)");
                         return true;
-                    } else {
-                        warn!("⚠️  设置标题栏颜色失败 (错误: {:?})，尝试设置边框颜色", result2);
                     }
-                    
-                    // 设置窗口边框颜色（作为备选方案，Windows 10 1809+ 支持）
-                    let mut border_color = color_value;
-                    let result3 = DwmSetWindowAttribute(
-                        hwnd,
-                        DWMWINDOWATTRIBUTE(34), // DWMWA_BORDER_COLOR
-                        &mut border_color as *mut _ as *mut _,
-                        std::mem::size_of::<u32>() as u32,
-                    );
-                    if result3.is_ok() {
+                    Err(e) => warn!("⚠️  设置标题栏颜色失败 (错误: {:?})，尝试设置边框颜色", e),
+                }
+
+                // 设置窗口边框颜色（作为备选方案，Windows 10 1809+ 支持）
+                match set_win32_border_color(hwnd, TITLE_BAR_COLOR) {
+                    Ok(()) => {
                         info!("✓ 已设置窗口边框颜色为 rgb(29, 29, 29)");
                         return true;
-                    } else {
-                        warn!("⚠️  设置窗口边框颜色也失败 (错误: {:?})", result3);
                     }
+                    Err(e) => warn!("⚠️  设置窗口边框颜色也失败 (错误: {:?})", e),
                 }
             } else {
                 use log::warn;
@@ -282,11 +570,7 @@ impl VideoPlayerApp {
     #[cfg(not(target_os = "windows"))]
     fn setup_window_style(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
         // 非 Windows 平台：只设置 egui 样式
-        let mut style = (*ctx.style()).clone();
-        style.visuals.dark_mode = true;
-        style.visuals.window_fill = egui::Color32::from_rgb(29, 29, 29);
-        style.visuals.panel_fill = egui::Color32::from_rgb(29, 29, 29);
-        ctx.set_style(style);
+        self.settings.theme.apply(ctx);
     }
 
     /// 配置中文字体支持
@@ -352,160 +636,334 @@ impl VideoPlayerApp {
         ctx.set_fonts(fonts);
     }
 
-    /// 创建控制按钮图标（使用 VS Code Codicons SVG）
-    /// 直接使用 codicons 的 SVG 字符串，通过 resvg 渲染
-    fn create_control_icons(ctx: &Context) -> ControlIcons {
-        // 使用高分辨率渲染以获得更好的显示效果
-        let icon_size = 96;
-        
-        info!("🎨 创建控制按钮图标（使用 VS Code Codicons，分辨率: {}x{}）", icon_size, icon_size);
-        
-        // VS Code Codicons SVG 图标（来自 https://github.com/microsoft/vscode-codicons）
-        // 使用真实的 codicons SVG 路径数据
-        
-        // 播放图标 - play (codicons: play-triangle)
-        let play_svg = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M3 3v10l10-5z" fill="white"/></svg>"#;
-        let play_image = Self::svg_to_image(play_svg, icon_size);
-        let play = ctx.load_texture("play_icon", play_image, TextureOptions::LINEAR);
-
-        // 暂停图标 - debug-pause (codicons)
-        let pause_svg = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M4.5 3C4.22386 3 4 3.22386 4 3.5V12.5C4 12.7761 4.22386 13 4.5 13H7.5C7.77614 13 8 12.7761 8 12.5V3.5C8 3.22386 7.77614 3 7.5 3H4.5ZM9.5 3C9.22386 3 9 3.22386 9 3.5V12.5C9 12.7761 9.22386 13 9.5 13H12.5C12.7761 13 13 12.7761 13 12.5V3.5C13 3.22386 12.7761 3 12.5 3H9.5Z" fill="white"/></svg>"#;
-        let pause_image = Self::svg_to_image(pause_svg, icon_size);
-        let pause = ctx.load_texture("pause_icon", pause_image, TextureOptions::LINEAR);
-
-        // 停止图标 - debug-stop (codicons)
-        let stop_svg = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><rect x="3" y="3" width="10" height="10" rx="1" fill="white"/></svg>"#;
-        let stop_image = Self::svg_to_image(stop_svg, icon_size);
-        let stop = ctx.load_texture("stop_icon", stop_image, TextureOptions::LINEAR);
-
-        // 打开文件夹图标 - folder-opened (codicons)
-        let folder_svg = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><path d="M1.75 2A1.75 1.75 0 0 0 0 3.75v8.5C0 13.216.784 14 1.75 14h12.5A1.75 1.75 0 0 0 16 12.25v-8.5A1.75 1.75 0 0 0 14.25 2H7.5a.25.25 0 0 1-.2-.1l-.9-1.2C6.07.22 5.26 0 4.75 0h-3A1.75 1.75 0 0 0 0 1.75V3h1.5a.25.25 0 0 1 .2.1l.9 1.2c.23.31.934.7 1.44.7H1.75zM1.5 6.5v5.75c0 .138.112.25.25.25H14.25a.25.25 0 0 0 .25-.25V6.5H1.5z" fill="white"/></svg>"#;
-        let folder_image = Self::svg_to_image(folder_svg, icon_size);
-        let open_file = ctx.load_texture("open_file_icon", folder_image, TextureOptions::LINEAR);
-
-        info!("✅ 控制按钮图标创建完成");
-        
-        ControlIcons {
-            play,
-            pause,
-            stop,
-            open_file,
+    /// 打开文件（图像文件先检测是否属于图像序列，.m3u/.m3u8 先判断是否为本地播放列表）
+    pub fn open_file(&mut self, file_path: String) -> Result<()> {
+        let path = PathBuf::from(&file_path);
+        if is_supported_image_file(&path) {
+            return self.open_image_file(path);
         }
+        if m3u::is_playlist_file(&path) {
+            if let Some(playlist) = m3u::read_playlist(&path)? {
+                return self.open_playlist(&path, playlist);
+            }
+            info!("📡 {} 是 HLS 播放列表，按流媒体播放", path.display());
+        }
+        self.open_source(MediaSource::LocalFile(path), file_path)
     }
     
-    /// 将 SVG 字符串转换为 egui ColorImage
-    fn svg_to_image(svg_str: &str, size: usize) -> ColorImage {
-        use resvg::tiny_skia;
-        use usvg::{Options, Tree, TreeParsing};
+    /// 把本地播放列表导入为播放队列并播放第一项（嵌套的播放列表和不存在的文件跳过）
+    fn open_playlist(&mut self, file: &Path, playlist: m3u::LocalPlaylist) -> Result<()> {
+        let (items, nested): (Vec<PlaylistItem>, Vec<PlaylistItem>) = playlist
+            .items
+            .into_iter()
+            .partition(|item| m3u::is_url(&item.path) || !m3u::is_playlist_file(Path::new(&item.path)));
+        for item in &nested {
+            warn!("⚠️ 跳过嵌套的播放列表: {}", item.path);
+        }
+        let skipped = playlist.missing.len() + nested.len();
+        for missing in &playlist.missing {
+            warn!("⚠️ 播放列表中的文件不存在: {}", missing);
+        }
+        info!("📃 导入播放列表 {}: {} 项，跳过 {} 项", file.display(), items.len(), skipped);
         
-        // SVG 已经包含 fill="white"，不需要替换 currentColor
-        let svg_with_color = svg_str.to_string();
+        let mut items = items.into_iter();
+        let Some(first) = items.next() else {
+            anyhow::bail!("播放列表中没有可播放的文件（跳过 {} 项）", skipped);
+        };
+        self.playlist_titles = std::iter::once(&first)
+            .chain(items.as_slice())
+            .filter_map(|item| Some((item.path.clone(), item.title.clone()?)))
+            .collect();
+        let paths: Vec<String> = std::iter::once(first.path).chain(items.map(|item| item.path)).collect();
+        let first = paths[0].clone();
+        self.player.write().set_playlist(paths);
+        self.open_queue_item(first)?;
         
-        // 解析 SVG
-        let opt = Options::default();
-        let tree = match Tree::from_str(&svg_with_color, &opt) {
-            Ok(tree) => tree,
-            Err(e) => {
-                error!("解析 SVG 失败: {}", e);
-                return Self::create_placeholder_image(size);
+        let total = self.player.read().playlist().len();
+        if skipped > 0 {
+            self.show_osd(format!("已导入播放列表: {} 项，{} 项不存在已跳过", total, skipped));
+        } else {
+            self.show_osd(format!("已导入播放列表: {} 项", total));
+        }
+        Ok(())
+    }
+    
+    /// 打开播放队列中的一项（URL 按网络流异步打开）
+    fn open_queue_item(&mut self, path: String) -> Result<()> {
+        if m3u::is_url(&path) {
+            self.open_stream_async(path);
+            Ok(())
+        } else {
+            self.open_file(path)
+        }
+    }
+    
+    /// 把播放列表（没有播放列表时为当前文件）导出为 .m3u8（带 #EXTINF 标题）
+    fn export_playlist(&mut self) {
+        let paths = {
+            let manager = self.player.read();
+            if manager.playlist().is_empty() {
+                self.ui_state.current_file.iter().cloned().collect()
+            } else {
+                manager.playlist().items().to_vec()
             }
         };
+        let items: Vec<PlaylistItem> = paths
+            .into_iter()
+            .map(|path| PlaylistItem { title: self.playlist_titles.get(&path).cloned(), path })
+            .collect();
+        if items.is_empty() {
+            self.show_osd("播放队列为空");
+            return;
+        }
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("播放列表", &["m3u8"])
+            .set_file_name("播放列表.m3u8")
+            .save_file()
+        else {
+            return;
+        };
         
-        // 创建渲染目标
-        let mut pixmap = match tiny_skia::Pixmap::new(size as u32, size as u32) {
-            Some(pixmap) => pixmap,
-            None => {
-                error!("创建 Pixmap 失败");
-                return Self::create_placeholder_image(size);
+        match m3u::write_playlist(&path, &items) {
+            Ok(()) => {
+                info!("💾 播放列表已导出: {}（{} 项）", path.display(), items.len());
+                self.show_osd(format!("播放列表已导出: {} 项", items.len()));
+            }
+            Err(e) => {
+                error!("导出播放列表失败: {}", e);
+                self.show_osd(format!("导出播放列表失败: {}", e));
+            }
+        }
+    }
+
+    /// 打开图像：同目录下存在编号连续的同名图像时询问是否按序列播放，否则直接显示
+    fn open_image_file(&mut self, path: PathBuf) -> Result<()> {
+        let pattern = match infer_sequence(&path) {
+            Ok(None) => {
+                let display = path.to_string_lossy().to_string();
+                return self.open_source(MediaSource::Image(path), display);
             }
+            Ok(Some(pattern)) => Ok(pattern),
+            Err(e) => Err(e.to_string()),
         };
+        self.pending_sequence = Some(SequencePrompt { image: Some(path), pattern, fps: DEFAULT_SEQUENCE_FPS });
+        Ok(())
+    }
+
+    /// 选择文件并打开（工具栏和起始页共用）
+    fn open_file_dialog(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("视频文件", SUPPORTED_VIDEO_EXTENSIONS)
+            .add_filter("图像文件", SUPPORTED_IMAGE_EXTENSIONS)
+            .add_filter("播放列表", PLAYLIST_EXTENSIONS)
+            .pick_file()
+        {
+            if let Some(path_str) = path.to_str() {
+                if let Err(e) = self.open_file(path_str.to_string()) {
+                    error!("打开文件失败: {}", e);
+                    self.report_open_error(&e);
+                }
+            }
+        }
+    }
+    
+    /// 选择文件夹并按图像序列打开（与拖入文件夹相同）
+    fn open_folder_dialog(&mut self) {
+        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+            self.open_image_folder(&dir);
+        }
+    }
+    
+    /// 执行起始页上的操作
+    fn handle_start_action(&mut self, action: StartAction) {
+        info!("🏠 起始页操作: {:?}", action);
+        match action {
+            StartAction::OpenFile => self.open_file_dialog(),
+            StartAction::OpenFolder => self.open_folder_dialog(),
+            StartAction::OpenUrl => self.ui_state.show_url_dialog = true,
+            StartAction::ResumeRecent(path) => {
+                if let Err(e) = self.open_file(path) {
+                    error!("打开文件失败: {}", e);
+                    self.report_open_error(&e);
+                }
+            }
+            StartAction::SetRecentFilter(filter) => self.ui_state.recent_filter = filter,
+            StartAction::ToggleWatched(path) => {
+                if let Some(history) = self.player.read().position_history() {
+                    let watched = history.watch_states([path.as_str()]) == [WatchState::Watched];
+                    history.set_watched(&path, !watched);
+                }
+            }
+        }
+    }
+    
+    /// 打开拖入的文件（见 drop_target）
+    fn open_dropped(&mut self, paths: Vec<PathBuf>) {
+        for path in &paths {
+            info!("📥 拖入: {}", path.display());
+        }
+        let dropped = DroppedFiles::classify(paths, |path| path.is_dir());
         
-        // 计算缩放和居中
-        let svg_size = tree.view_box.rect.size();
-        let scale = (size as f32 / svg_size.width()).min(size as f32 / svg_size.height());
-        let scaled_width = svg_size.width() * scale;
-        let scaled_height = svg_size.height() * scale;
-        let x = (size as f32 - scaled_width) / 2.0;
-        let y = (size as f32 - scaled_height) / 2.0;
-        
-        let transform = tiny_skia::Transform::from_translate(x, y).post_scale(scale, scale);
-        
-        // 渲染 SVG（确保完全透明背景）
-        pixmap.fill(tiny_skia::Color::TRANSPARENT);
-        let rtree = resvg::Tree::from_usvg(&tree);
-        // 使用 BlendMode::SourceOver 确保正确渲染透明部分
-        rtree.render(transform, &mut pixmap.as_mut());
-        
-        // 转换为 RGBA
-        // tiny_skia::Pixmap 使用 premultiplied BGRA 格式（Blue, Green, Red, Alpha）
-        // 需要转换为 unmultiplied RGBA 格式（Red, Green, Blue, Alpha）
-        // 关键：premultiplied 意味着颜色值已经乘以了 alpha，需要除以 alpha 得到原始值
-        let pixels: Vec<u8> = pixmap.pixels()
-            .iter()
-            .flat_map(|p| {
-                let alpha = p.alpha();
-                if alpha == 0 {
-                    // 完全透明的像素，直接返回透明
-                    [0, 0, 0, 0]
-                } else {
-                    // tiny_skia::ColorU8 提供了 red(), green(), blue(), alpha() 方法
-                    // 这些值已经是 premultiplied 的，需要转换
-                    let alpha_f = alpha as f32 / 255.0;
-                    
-                    // 从 premultiplied 转换为 unmultiplied
-                    // 公式：unmultiplied = premultiplied / alpha
-                    let r = (p.red() as f32 / alpha_f).min(255.0).max(0.0) as u8;
-                    let g = (p.green() as f32 / alpha_f).min(255.0).max(0.0) as u8;
-                    let b = (p.blue() as f32 / alpha_f).min(255.0).max(0.0) as u8;
-                    
-                    // 输出为 RGBA 格式（egui 需要的格式）
-                    [r, g, b, alpha]
+        // 先打开媒体文件，字幕随后加载到新打开的视频上
+        let mut media = dropped.media.into_iter().map(|path| path.to_string_lossy().to_string());
+        if let Some(first) = media.next() {
+            let rest: Vec<String> = media.collect();
+            let result = if rest.is_empty() {
+                self.open_file(first)
+            } else {
+                let count = rest.len() + 1;
+                self.playlist_titles.clear();
+                self.player.write().set_playlist(std::iter::once(first.clone()).chain(rest).collect());
+                let result = self.open_file(first);
+                if result.is_ok() {
+                    self.show_osd(format!("已添加到播放列表: {} 项", count));
                 }
-            })
-            .collect();
+                result
+            };
+            if let Err(e) = result {
+                error!("打开文件失败: {}", e);
+                self.report_open_error(&e);
+                return;
+            }
+        } else if let Some(folder) = dropped.folder {
+            self.open_image_folder(&folder);
+        }
         
-        ColorImage::from_rgba_unmultiplied([size, size], &pixels)
+        for subtitle in dropped.subtitles {
+            let name = subtitle.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            let result = self.player.write().add_external_subtitle(&subtitle);
+            match result {
+                Ok(()) => self.show_osd(format!("已加载字幕: {}", name)),
+                Err(e) => {
+                    warn!("⚠️ 加载字幕失败 {}: {}", subtitle.display(), e);
+                    self.show_osd(format!("无法加载字幕（请先打开本地视频）: {}", name));
+                }
+            }
+        }
     }
     
-    /// 创建占位符图标（当 SVG 渲染失败时使用）
-    fn create_placeholder_image(size: usize) -> ColorImage {
-        use image::{Rgba, RgbaImage, DynamicImage};
-        let mut image = RgbaImage::new(size as u32, size as u32);
-        for pixel in image.pixels_mut() {
-            *pixel = Rgba([200, 200, 200, 255]);
+    /// 拖入文件夹：查找其中的图像序列并询问帧率
+    fn open_image_folder(&mut self, dir: &Path) {
+        match find_sequence_in_folder(dir) {
+            Ok(pattern) => {
+                self.pending_sequence = Some(SequencePrompt { image: None, pattern: Ok(pattern), fps: DEFAULT_SEQUENCE_FPS });
+            }
+            Err(e) => {
+                warn!("⚠️ 文件夹中没有可播放的图像序列: {}", e);
+                self.show_osd(e.to_string());
+            }
         }
-        let dynamic = DynamicImage::ImageRgba8(image);
-        let rgb_image = dynamic.to_rgb8();
-        let pixels: Vec<u8> = rgb_image.pixels()
-            .flat_map(|p| [p[0], p[1], p[2], 255])
-            .collect();
-        ColorImage::from_rgba_unmultiplied([size, size], &pixels)
     }
 
-    // 旧的图标生成函数已完全移除，现在使用 VS Code Codicons SVG
-    // 所有 generate_*_icon 函数已删除，改用 Codicons SVG 渲染
-
-    /// 打开文件
-    pub fn open_file(&mut self, file_path: String) -> Result<()> {
+    /// 启动后打开命令行指定的媒体（在第一次 update 时进行）
+    pub fn open_on_start(&mut self, media: StartupMedia, subtitle: Option<PathBuf>) {
+        self.startup_open = Some((media, subtitle));
+    }
+    
+    /// 接收其他实例转发的文件（单实例模式，监听线程收到后唤醒界面）
+    pub fn listen_for_forwarded(&mut self, server: InstanceServer, ctx: &Context) {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let ctx = ctx.clone();
+        let spawned = server.spawn(move |args| {
+            match CommandLine::parse(args.into_iter().map(OsString::from)) {
+                Ok(command_line) => {
+                    let _ = tx.send((command_line.media, command_line.subtitle));
+                    ctx.request_repaint();
+                }
+                Err(e) => warn!("⚠️ 转发的参数无效: {}", e),
+            }
+        });
+        match spawned {
+            Ok(()) => {
+                self.forwarded_rx = Some(rx);
+                self.instance_server = Some(server);
+            }
+            Err(e) => warn!("⚠️ 启动单实例监听失败: {}", e),
+        }
+    }
+    
+    /// 打开命令行（或其他实例转发）指定的媒体；外部字幕代替自动查找的字幕（仅本地文件）
+    fn open_startup_media(&mut self, media: StartupMedia, subtitle: Option<PathBuf>) {
+        info!("🚀 打开命令行指定的媒体: {:?}", media);
+        let path = match media {
+            StartupMedia::Url(url) => {
+                if subtitle.is_some() {
+                    warn!("⚠️ 网络流不支持外部字幕，忽略字幕参数");
+                }
+                self.open_stream_async(url);
+                return;
+            }
+            StartupMedia::File(path) => path,
+        };
+        // 底层按字符串打开文件：无法表示为 UTF-8 的路径明确报错，不做有损转换
+        let Ok(path) = path.into_os_string().into_string() else {
+            error!("❌ 文件路径包含无法识别的字符，无法打开");
+            self.show_osd("文件路径包含无法识别的字符，无法打开");
+            return;
+        };
+        if let Err(e) = self.open_file(path) {
+            error!("打开文件失败: {}", e);
+            self.report_open_error(&e);
+            return;
+        }
+        if let Some(subtitle) = subtitle {
+            let result = self.player.write().add_external_subtitle(&subtitle);
+            if let Err(e) = result {
+                warn!("⚠️ 加载字幕失败 {}: {}", subtitle.display(), e);
+                self.show_osd(format!("加载字幕失败: {}", e));
+            }
+        }
+    }
+    
+    /// 打开媒体源并自动播放（文件、图像序列、单张图像共用）
+    fn open_source(&mut self, source: MediaSource, file_path: String) -> Result<()> {
         info!("📂 打开文件: {}", file_path);
         
         // 先清理 UI 状态，避免旧视频的数据影响新视频
         self.current_frame_pts = None;
+        self.seek_accumulator.cancel();
         self.ui_state.seeking = false;
         self.ui_state.seek_position = 0.0;
         self.ui_state.seek_complete_time = None;
         self.ui_state.seek_executed = false;
+        self.perf_stats.merged_frames = 0;
+        self.subtitle_stacker.clear();
+        if let Some(renderer) = &mut self.video_renderer {
+            renderer.reset_zoom();
+        }
         
         // 清理视频渲染器的纹理缓存（在打开新文件之前清理，避免显示旧视频帧）
         if let Some(renderer) = &mut self.video_renderer {
             renderer.cleanup();
+            self.subtitle_backdrop.reset();
             info!("🧹 已清理视频渲染器缓存");
         }
         
         // 打开新文件（manager.open_file() 内部会调用 stop() 清理播放器状态）
         // stop() 会：停止所有线程、清空所有帧队列、重置播放时钟、清理音频输出
-        let mut manager = self.playback_manager.write();
-        manager.open_file(&file_path)?;
+        let mut manager = self.player.write();
+        // 崩溃提示中选择以安全模式重新打开时只对这一次打开生效
+        let flags = if std::mem::take(&mut self.safe_reopen) { RuntimeFlags::SAFE_MODE } else { self.runtime_flags };
+        manager.set_runtime_flags(flags);
+        manager.open_media_source(source)?;
+        self.ui_state.last_error = None;
+        
+        // 从上次记录的位置继续播放
+        let resumed_at = manager.resume_position();
+        if let Some(position_ms) = resumed_at {
+            info!("⏯️  从上次位置继续播放: {}", format_time(position_ms));
+            manager.seek_with(position_ms, SeekMode::Accurate);
+        }
+        
+        // 恢复该文件记忆的音量，否则恢复默认音量（避免把上一个文件的增益带到新文件）
+        let previous_volume = self.ui_state.volume;
+        if let Some(volume) = manager.remembered_volume() {
+            self.ui_state.volume = volume;
+        } else if self.settings.restore_default_volume {
+            self.ui_state.volume = self.settings.default_volume;
+        }
+        // 等待启动渐入时先静音，由 update 按进度升高
+        manager.set_volume(self.ui_state.volume * self.volume_ramp.gain(Instant::now()));
         
         // 自动开始播放
         if let Err(e) = manager.play() {
@@ -517,12 +975,31 @@ impl VideoPlayerApp {
         
         // 打开新文件后，再次确保 UI 状态正确（双重保险）
         self.current_frame_pts = None;
+        self.audio_failure_notice = None;
+        self.first_frame_notice = None;
+        // 上次播放该文件时解码崩溃：提示可以安全模式重新打开
+        self.crash_notice = Some(PathBuf::from(&file_path))
+            .filter(|path| !flags.is_safe_mode() && self.crash_markers.contains(path))
+            .map(CrashNotice::Previous);
+        self.skip_tracker.reset();
+        self.skip_notice = None;
         
         // 更新 UI 状态
+        self.settings.recent_files.push(&file_path);
         self.ui_state.current_file = Some(file_path);
         self.ui_state.controls_visible = true;
         self.ui_state.controls_hide_timer = Some(Instant::now() + Duration::from_secs(3));
         
+        drop(manager);
+        if let Some(position_ms) = resumed_at {
+            self.show_osd(format!("从 {} 继续播放", format_time(position_ms)));
+        }
+        if self.ui_state.volume != previous_volume {
+            info!("🔊 音量: {}", format_volume(self.ui_state.volume));
+            self.show_osd(format!("音量: {}", format_volume(self.ui_state.volume)));
+        }
+        self.check_hdr_compatibility();
+        
         info!("✅ 文件打开完成，状态已重置");
         
         Ok(())
@@ -584,12 +1061,13 @@ impl VideoPlayerApp {
 
     /// 动态更新窗口标题（在系统标题栏显示文件名）
     fn update_window_title(&mut self, ctx: &Context) {
-        let new_title = if let Some(file_path) = &self.ui_state.current_file {
-            let file_name = Path::new(file_path)
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or(file_path);
-            format!("喜洋洋播放器 - {}", file_name)
+        let new_title = if self.ui_state.current_file.is_some() {
+            // 播放器被占用时（如正在打开文件）保持原标题
+            let Some(name) = self.display_name() else {
+                return;
+            };
+            // 自行省略过长的文件名（系统截断时会截掉扩展名和集数）
+            format!("喜洋洋播放器 - {}", middle_ellipsis_chars(&name, WINDOW_TITLE_MAX_CHARS))
         } else {
             "喜洋洋播放器".to_string()
         };
@@ -601,10 +1079,27 @@ impl VideoPlayerApp {
         }
     }
 
+    /// 当前媒体的显示名称（播放列表 #EXTINF 标题、内嵌或 .nfo 标题，没有时为文件名）；未打开文件或播放器被占用时为 None
+    fn display_name(&self) -> Option<String> {
+        let file_path = self.ui_state.current_file.as_ref()?;
+        if let Some(title) = self.playlist_titles.get(file_path) {
+            return Some(title.clone());
+        }
+        let title = self.player.try_read()?.media_title();
+        Some(title.unwrap_or_else(|| {
+            Path::new(file_path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(file_path)
+                .to_string()
+        }))
+    }
+
     /// 渲染信息栏（在系统标题栏下方显示文件名等信息，使用自定义标题栏背景）
     fn render_info_bar(&mut self, ctx: &Context) {
         // 使用与之前自定义标题栏相同的背景色和样式
         let title_bar_color = egui::Color32::from_rgb(29, 29, 29);
+        let display_name = self.display_name();
         
         // 在系统标题栏下方显示信息栏（始终显示）
         egui::TopBottomPanel::top("info_bar")
@@ -631,276 +1126,77 @@ impl VideoPlayerApp {
                                 .size(13.0)
                         );
                         
-                        // 显示文件名（白色，如果有）
-                        if let Some(file_path) = &self.ui_state.current_file {
-                            let file_name = Path::new(file_path)
-                                .file_name()
-                                .and_then(|n| n.to_str())
-                                .unwrap_or(file_path);
-                            
+                        // 显示标题或文件名（白色，如果有；过长时从中间省略）
+                        if let Some(name) = &display_name {
                             ui.add_space(12.0);
-                            ui.label(
-                                egui::RichText::new(file_name)
-                                    .color(egui::Color32::WHITE)
-                                    .size(13.0)
-                            );
+                            let font = egui::FontId::proportional(13.0);
+                            let fitted = middle_ellipsis(name, ui.available_width() - 12.0, &font, ui);
+                            let response = ui.label(egui::RichText::new(&fitted).color(egui::Color32::WHITE).font(font));
+                            if fitted != *name {
+                                response.on_hover_text(name);
+                            }
                         }
                     });
                 });
             });
     }
+}
 
-    /// 渲染自定义标题栏
-    fn render_custom_title_bar(&mut self, ctx: &Context) {
-        const TITLE_BAR_HEIGHT: f32 = 32.0;
-        const BUTTON_SIZE: f32 = 32.0;
-        const BUTTON_ICON_SIZE: f32 = 14.0;
-        
-        let title_bar_color = egui::Color32::from_rgb(29, 29, 29);
-        let _title_text_color = egui::Color32::from_rgb(112, 112, 112);
-        let _filename_color = egui::Color32::WHITE;
+impl eframe::App for VideoPlayerApp {
+    fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
+        if let Some((media, subtitle)) = self.startup_open.take() {
+            self.open_startup_media(media, subtitle);
+        }
+        let forwarded = self.forwarded_rx.as_ref().and_then(|rx| rx.try_iter().last());
+        if let Some((media, subtitle)) = forwarded {
+            // 双击文件时把已运行的窗口带到前台
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            if let Some(media) = media {
+                self.open_startup_media(media, subtitle);
+            }
+        }
         
-        // 顶部标题栏面板
-        egui::TopBottomPanel::top("custom_title_bar")
-            .frame(egui::Frame::none()
-                .fill(title_bar_color)
-                .stroke(egui::Stroke::new(0.0, egui::Color32::TRANSPARENT)))
-            .resizable(false)
-            .show_separator_line(false)
-            .show(ctx, |ui| {
-                ui.set_height(TITLE_BAR_HEIGHT);
-                ui.horizontal(|ui| {
-                    ui.set_height(TITLE_BAR_HEIGHT);
+        // 处理异步打开的结果
+        let opened = self.player.try_write().and_then(|mut manager| manager.poll_open());
+        if let Some((url, result)) = opened {
+            match result {
+                Ok(media_info) => {
+                    info!("✅ 播放器已就绪: {:?}", media_info);
                     
-                    // 左侧：标题和文件名
-                    ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                        ui.spacing_mut().item_spacing = egui::Vec2::new(8.0, 0.0);
-                        ui.add_space(12.0);
-                        
-                        // 播放器标题（深色 RGB(29, 29, 29)）
-                        ui.label(
-                            egui::RichText::new("喜洋洋播放器")
-                                .color(egui::Color32::from_rgb(29, 29, 29))
-                                .size(13.0)
-                        );
-                        
-                        // 文件名（白色，如果有）
-                        if let Some(file_path) = &self.ui_state.current_file {
-                            let file_name = Path::new(file_path)
-                                .file_name()
-                                .and_then(|n| n.to_str())
-                                .unwrap_or(file_path);
-                            
-                            ui.add_space(12.0);
-                            ui.label(
-                                egui::RichText::new(file_name)
-                                    .color(egui::Color32::WHITE)
-                                    .size(13.0)
-                            );
-                        }
-                    });
+                    // 切换媒体源后清理 UI 状态，避免残留帧
+                    self.current_frame_pts = None;
+                    if let Some(renderer) = &mut self.video_renderer {
+                        renderer.reset_zoom();
+                        renderer.cleanup();
+                        self.subtitle_backdrop.reset();
+                    }
+                    self.ui_state.seeking = false;
+                    self.ui_state.seek_position = 0.0;
+                    self.ui_state.seek_complete_time = None;
+                    self.ui_state.seek_executed = false;
+                    self.ui_state.last_error = None;
+                    self.ui_state.current_file = Some(url.clone());
+                    self.settings.recent_files.push(&url);
                     
-                    // 中间：可拖拽区域（占用剩余空间）
-                    ui.allocate_ui_with_layout(
-                        egui::Vec2::new(ui.available_width() - BUTTON_SIZE * 3.0, TITLE_BAR_HEIGHT),
-                        egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                        |ui| {
-                            let drag_response = ui.allocate_response(
-                                ui.available_size(),
-                                egui::Sense::drag()
-                            );
-                            
-                            if drag_response.dragged() {
-                                let delta = drag_response.drag_delta();
-                                if delta != egui::Vec2::ZERO {
-                                    if let Some(outer_rect) = ctx.input(|i| i.viewport().outer_rect) {
-                                        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(
-                                            outer_rect.min + delta
-                                        ));
-                                    }
-                                }
-                            }
+                    let mut manager = self.player.write();
+                    // 自动播放
+                    if let Err(e) = manager.play() {
+                        error!("❌ 自动播放失败: {}", e);
+                    }
+                    
+                    // 重新连接：回到中断时的位置（直播流直接从直播边缘继续）
+                    if let Some(position_ms) = self.reconnect_resume_ms.take() {
+                        if position_ms > 0 && !media_info.is_live {
+                            manager.seek_with(position_ms, SeekMode::Accurate);
                         }
-                    );
+                    }
+                    drop(manager);
                     
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.spacing_mut().item_spacing = egui::Vec2::new(0.0, 0.0);
-                        
-                        // 右侧：窗口控制按钮（统一大小和样式）
-                        
-                        // 辅助函数：绘制窗口控制按钮
-                        let draw_window_button = |ui: &mut egui::Ui, size: f32, hover_color: egui::Color32| -> egui::Response {
-                            let response = ui.add_sized(
-                                egui::Vec2::new(size, size),
-                                egui::Button::new("")
-                                    .frame(false)
-                            );
-                            
-                            // 绘制按钮背景
-                            if response.hovered() {
-                                ui.painter().rect_filled(
-                                    response.rect,
-                                    0.0,
-                                    hover_color
-                                );
-                            }
-                            
-                            response
-                        };
-                        
-                        // 关闭按钮（×）
-                        let close_response = draw_window_button(ui, BUTTON_SIZE, egui::Color32::from_rgb(232, 17, 35));
-                        
-                        // 绘制关闭图标（×）
-                        let close_icon_color = if close_response.hovered() {
-                            egui::Color32::WHITE
-                        } else {
-                            egui::Color32::from_rgb(200, 200, 200)
-                        };
-                        let icon_size = 10.0;
-                        let center = close_response.rect.center();
-                        let half_size = icon_size / 2.0;
-                        ui.painter().line_segment(
-                            [center + egui::Vec2::new(-half_size, -half_size), center + egui::Vec2::new(half_size, half_size)],
-                            egui::Stroke::new(1.5, close_icon_color)
-                        );
-                        ui.painter().line_segment(
-                            [center + egui::Vec2::new(-half_size, half_size), center + egui::Vec2::new(half_size, -half_size)],
-                            egui::Stroke::new(1.5, close_icon_color)
-                        );
-                        
-                        if close_response.clicked() {
-                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                        }
-                        
-                        // 最大化/还原按钮
-                        let is_maximized = ctx.input(|i| i.viewport().maximized.unwrap_or(false));
-                        let max_response = draw_window_button(ui, BUTTON_SIZE, egui::Color32::from_rgb(60, 60, 60));
-                        
-                        // 绘制最大化/还原图标
-                        let max_icon_color = if max_response.hovered() {
-                            egui::Color32::WHITE
-                        } else {
-                            egui::Color32::from_rgb(200, 200, 200)
-                        };
-                        let icon_rect = egui::Rect::from_center_size(
-                            max_response.rect.center(),
-                            egui::Vec2::new(10.0, 10.0)
-                        );
-                        if is_maximized {
-                            // 还原图标：两个重叠的方框
-                            ui.painter().rect_stroke(
-                                icon_rect.translate(egui::Vec2::new(-2.0, -2.0)),
-                                0.0,
-                                egui::Stroke::new(1.0, max_icon_color)
-                            );
-                            ui.painter().rect_stroke(
-                                icon_rect.translate(egui::Vec2::new(2.0, 2.0)),
-                                0.0,
-                                egui::Stroke::new(1.0, max_icon_color)
-                            );
-                        } else {
-                            // 最大化图标：单个方框
-                            ui.painter().rect_stroke(
-                                icon_rect,
-                                0.0,
-                                egui::Stroke::new(1.0, max_icon_color)
-                            );
-                        }
-                        
-                        if max_response.clicked() {
-                            ctx.send_viewport_cmd(egui::ViewportCommand::Maximized(!is_maximized));
-                        }
-                        
-                        // 最小化按钮（—）
-                        let min_response = draw_window_button(ui, BUTTON_SIZE, egui::Color32::from_rgb(60, 60, 60));
-                        
-                        // 绘制最小化图标（横线）
-                        let min_icon_color = if min_response.hovered() {
-                            egui::Color32::WHITE
-                        } else {
-                            egui::Color32::from_rgb(200, 200, 200)
-                        };
-                        let center = min_response.rect.center();
-                        ui.painter().line_segment(
-                            [
-                                center + egui::Vec2::new(-5.0, 0.0),
-                                center + egui::Vec2::new(5.0, 0.0)
-                            ],
-                            egui::Stroke::new(1.5, min_icon_color)
-                        );
-                        
-                        if min_response.clicked() {
-                            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
-                        }
-                    });
-                });
-            });
-    }
-}
-
-impl eframe::App for VideoPlayerApp {
-    fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
-        // 处理 Demuxer 创建结果（新架构 - 异步打开）
-        if let Ok(result) = self.demuxer_result_rx.try_recv() {
-            use crate::player::DemuxerCreationResult;
-            
-            match result {
-                DemuxerCreationResult::Success { demuxer, url } => {
-                    info!("✅ Demuxer 创建成功: {}", url);
-                    
-                    // 判断是否为网络流
-                    let is_network = url.starts_with("http://") 
-                        || url.starts_with("https://")
-                        || url.starts_with("rtsp://")
-                        || url.starts_with("rtmp://")
-                        || url.contains(".m3u8");  // HLS
-                    
-                    // 切换媒体源前先清理 UI 状态，避免残留帧
-                    self.current_frame_pts = None;
-                    self.ui_state.seeking = false;
-                    self.ui_state.seek_position = 0.0;
-                    self.ui_state.seek_complete_time = None;
-                    self.ui_state.seek_executed = false;
-                    if let Some(renderer) = &mut self.video_renderer {
-                        renderer.cleanup();
-                    }
-                    
-                    // 在主线程中附加 Demuxer
-                    if let Some(mut manager) = self.playback_manager.try_write() {
-                        let result = if is_network {
-                            // 网络流：使用新架构（DemuxerThread）
-                            info!("🌐 使用新架构（DemuxerThread）处理网络流");
-                            manager.attach_demuxer_async(demuxer)
-                        } else {
-                            // 本地文件：使用现有方式
-                            info!("📁 使用现有方式处理本地文件");
-                            manager.attach_demuxer(demuxer)
-                        };
-                        
-                        match result {
-                            Ok(media_info) => {
-                                info!("✅ 播放器已就绪: {:?}", media_info);
-                                self.ui_state.current_file = Some(url.clone());
-                                
-                                // 自动播放
-                                if let Err(e) = manager.play() {
-                                    error!("❌ 自动播放失败: {}", e);
-                                }
-                            }
-                            Err(e) => {
-                                error!("❌ 附加 Demuxer 失败: {}", e);
-                            }
-                        }
-                    }
-                    
-                    // 清除加载状态
-                    self.loading_url = None;
-                }
-                DemuxerCreationResult::Failed { url, error } => {
-                    error!("❌ 创建 Demuxer 失败: {} - {}", url, error);
                     self.loading_url = None;
+                    self.check_hdr_compatibility();
                 }
+                Err(error) => self.open_stream_failed(&url, error),
             }
         }
         
@@ -910,13 +1206,132 @@ impl eframe::App for VideoPlayerApp {
         // 设置系统标题栏样式（背景色等）
         self.setup_window_style(ctx, _frame);
         
+        // 全屏播放时切换到与视频帧率匹配的刷新率
+        self.update_refresh_rate(ctx, _frame);
+        if let Some(hz) = self.display_refresh.update(_frame) {
+            info!("🖥️ 显示器刷新率: {:.3} Hz", hz);
+        }
+        
         // 隐藏自定义信息栏（不再显示）
         // self.render_info_bar(ctx);
         
+        // 启动渐入：第一次开始播放时开始计时，渐入期间每帧按进度设置实际音量
+        let ramp_volume = if self.volume_ramp.is_active() {
+            let now = Instant::now();
+            if self.player.try_read().is_some_and(|manager| manager.is_playing()) {
+                self.volume_ramp.start(now);
+            }
+            let gain = self.volume_ramp.gain(now);
+            if self.volume_ramp.is_active() {
+                ctx.request_repaint();
+            }
+            Some(self.ui_state.volume * gain)
+        } else {
+            None
+        };
+        
         // 更新音频输出（重要！必须定期调用以保持音频播放）
-        if let Some(mut manager) = self.playback_manager.try_write() {
+        let demux_event = self.player.try_write().and_then(|mut manager| {
+            if let Some(volume) = ramp_volume {
+                manager.set_volume(volume);
+            }
             manager.update_audio();
+            let event = manager.poll_demux_end();
+            // 播放完毕：时钟停在末尾，播放按钮恢复为播放（再次播放时从头开始）
+            manager.update_ended();
+            manager.checkpoint_position();
+            manager.update_watched();
+            event
+        });
+        if let Some(event) = demux_event {
+            self.handle_demux_event(event);
+        }
+        
+        // 拖入文件：悬停时高亮提示；松开后字幕加载到当前视频，媒体文件打开（多个时作为播放列表），文件夹按图像序列处理
+        let hovered = ctx.input(|i| i.raw.hovered_files.len());
+        if hovered > 0 {
+            drop_target::paint_hover_overlay(ctx, hovered);
+        }
+        let dropped: Vec<PathBuf> = ctx.input(|i| i.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect());
+        if !dropped.is_empty() {
+            self.open_dropped(dropped);
+        }
+        
+        // 监视文件夹：新文件通过动作分发处理
+        while let Some(path) = self.watch_folder.as_ref().and_then(|w| w.try_recv()) {
+            self.dispatch_action(ctx, PlayerAction::OpenWatchedFile(path));
+        }
+        
+        // 单曲循环（超过无缝循环上限）：播放完毕后回到开头
+        if let Some(manager) = self.player.try_read() {
+            manager.restart_loop_if_due();
+        }
+        
+        // A/B 循环：越过 B 点时回到 A 点（拖动进度条期间不检查，由拖拽结束时的 seek 决定位置）
+        if !self.ui_state.seeking {
+            let looped = self.player.try_write().is_some_and(|mut manager| manager.update_ab_loop());
+            if looped {
+                self.current_frame_pts = None;
+            }
+        }
+
+        // 视频轨道损坏（连续的帧尺寸无效）：视频已停止，音频继续播放
+        let video_corrupt = self.player.try_read().is_some_and(|manager| manager.take_video_corrupt_notice());
+        if video_corrupt {
+            self.show_osd("视频轨道似乎已损坏，已停止视频解码（音频继续播放）");
+        }
+
+        // 硬件解码失败：已切换为软件解码（信息面板持续显示原因）
+        let hw_fallback = self.player.try_read().and_then(|manager| manager.poll_hw_fallback());
+        if hw_fallback.is_some() {
+            self.show_osd("硬解失败，已切换软解");
+        }
+
+        // Seek 失败或媒体不支持跳转（网络流常见）：提示原因，进度条回到实际位置
+        if let Some(result) = self.player.try_read().and_then(|manager| manager.poll_seek_failure()) {
+            match result.outcome {
+                SeekOutcome::Unsupported => self.show_osd("该媒体不支持跳转"),
+                SeekOutcome::Failed(reason) => self.show_osd(format!("跳转到 {} 失败: {}", format_time(result.target_ms), reason)),
+                _ => {}
+            }
+        }
+
+        // 视频流在期限内没有解码出画面（DRM 保护或文件损坏）：提示原因，可改为只播放音频
+        if let Some(diagnosis) = self.player.try_read().and_then(|manager| manager.poll_first_frame()) {
+            self.first_frame_notice = Some(diagnosis);
+        }
+
+        // 解码线程崩溃（可以展开的构建中进程继续运行）：提示可以安全模式重新打开
+        if let Some(path) = Player::take_decode_crash() {
+            self.crash_notice = Some(CrashNotice::Now(path));
+        }
+
+        // 连拍：松开按键或达到上限后停止，保存完毕后提示结果
+        self.poll_burst(ctx);
+        
+        // 解封装停滞（网络卡顿或开发者面板模拟的停滞）及恢复
+        match self.player.try_read().and_then(|manager| manager.poll_stall()) {
+            Some(StallEvent::Stalled { .. }) => self.show_osd("数据读取停滞，等待恢复…"),
+            Some(StallEvent::Recovered { stalled_for }) => {
+                self.show_osd(format!("数据读取已恢复（停滞 {:.1} 秒）", stalled_for.as_secs_f32()))
+            }
+            None => {}
+        }
+
+        // 音频解码器失效：已切换为无声播放，有其他音轨时提示可切换
+        let audio_failed = self
+            .player
+            .try_read()
+            .and_then(|manager| manager.take_audio_failure_notice().then(|| manager.next_audio_track()));
+        if let Some(next_track) = audio_failed {
+            self.audio_failure_notice = Some((next_track, Instant::now()));
         }
+
+        // 进入标记的片头 / 片尾时自动跳过（或提示跳过）
+        self.check_skip_ranges();
+        
+        // 当前文件播放完毕后打开队列中的下一个文件
+        self.advance_file_queue();
         
         // 更新性能统计
         self.update_performance_stats();
@@ -933,6 +1348,10 @@ impl eframe::App for VideoPlayerApp {
             self.render_controls_panel(ctx);
         }
         
+        // 会话标签栏（多个会话时显示在视频区域上方）
+        self.render_safe_mode_banner(ctx);
+        self.render_session_tabs(ctx);
+        
         // 主视频区域 - 占满整个窗口
         egui::CentralPanel::default()
             .frame(egui::Frame::none())
@@ -948,11 +1367,30 @@ impl eframe::App for VideoPlayerApp {
         // 信息面板 - 悬浮在左上角
         self.render_info_panel(ctx);
         
+        // 设置抽屉 - 覆盖在右侧，不暂停播放
+        self.render_settings_drawer(ctx);
+        
+        // 数据包检查面板（开发者工具）
+        self.render_packet_panel(ctx);
+        
+        // 配置导入确认对话框
+        self.render_import_dialog(ctx);
+        
+        // 图像序列确认对话框
+        self.render_sequence_dialog(ctx);
+        
         // URL 对话框 - 最后渲染，确保在最上层
         self.render_url_dialog(ctx);
+        
+        // 关闭窗口：有后台任务时先确认
+        self.handle_close_request(ctx);
+        self.render_close_dialog(ctx);
 
         // 处理键盘快捷键
         self.handle_keyboard_input(ctx);
+        if let Some(target_ms) = self.seek_accumulator.poll(Instant::now()) {
+            self.player.write().seek_with(target_ms, SeekMode::Accurate);
+        }
 
         // 持续请求重绘以达到 60fps
         // 使用更短的间隔确保高帧率
@@ -968,9 +1406,26 @@ impl eframe::App for VideoPlayerApp {
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         info!("🔚 VideoPlayerApp 退出");
         
-        // 停止播放
-        if let Some(mut manager) = self.playback_manager.try_write() {
-            let _ = manager.stop();
+        // 恢复被修改的显示模式
+        self.refresh_rate_switch = None;
+        
+        // 保存设置（包括尚未到自动保存时间的修改和当前的音量、播放速度）
+        self.remember_playback_state();
+        if let Err(e) = save_settings(&settings_file(), &self.settings) {
+            error!("保存设置失败: {}", e);
+        }
+        
+        // 停止监视文件夹（等待监视线程退出）
+        if let Some(mut watch_folder) = self.watch_folder.take() {
+            watch_folder.stop();
+        }
+        
+        // 停止所有会话的播放（记录位置并等待写盘完成）
+        for session in self.sessions.iter() {
+            if let Some(mut manager) = session.manager.try_write() {
+                manager.stop();
+                manager.close_position_history();
+            }
         }
     }
 }
@@ -979,13 +1434,25 @@ impl VideoPlayerApp {
     /// 渲染视频区域
     fn render_video_area(&mut self, ui: &mut Ui) {
         let available_rect = ui.available_rect_before_wrap();
+        self.video_viewport = Some(available_rect);
+        
+        // 先登记视频区域的右键菜单交互，使之位于起始页按钮下层，不抢占按钮点击
+        // 画面区域只响应鼠标，不参与 Tab 焦点切换
+        let video_area = ui.interact(
+            available_rect,
+            ui.id().with("video_area"),
+            egui::Sense { focusable: false, ..egui::Sense::click_and_drag() },
+        );
+        let mut start_action = None;
+        // 空闲时显示的起始页（最近播放列表及其筛选）
+        let mut start_page = None;
         
         // ==================== UI 层：视频帧渲染与同步 ====================
         if let Some(renderer) = &mut self.video_renderer {
-            if let Some(manager) = self.playback_manager.try_read() {
+            if let Some(manager) = self.player.try_read() {
                 // ========== 获取当前播放时间（音频时钟） ==========
                 // 这是音画同步的关键：UI 根据音频时钟来选择显示哪一帧
-                let current_time_ms = manager.get_position().map(|pos| (pos * 1000.0) as i64).unwrap_or(0);
+                let current_time_ms = manager.get_clock_ms();
                 
                 // ========== 帧更新策略：按需获取（防止快进优化版）==========
                 // 目的：避免过度频繁地从队列获取帧，减少锁竞争，防止视频"快进"
@@ -994,34 +1461,53 @@ impl VideoPlayerApp {
                 // - 即使视频落后音频，也要保持最小帧间隔
                 // - 避免"一次性追上"导致的快进感
                 // 
-                // 三级策略：
-                // 1. 同步状态（-10ms ~ +50ms）：正常显示，1帧/更新
-                // 2. 轻微落后（50-150ms）：慢速追赶，1帧/更新，但阈值降低到30ms
-                // 3. 严重落后（>150ms）：快速跳跃，直接丢弃过期帧
-                let frame = if let Some(current_pts) = self.current_frame_pts {
+                // 三级策略（阈值按片源帧时长推导，见 SyncTuning）：
+                // 1. 同步良好（落后不超过两帧）：正常显示，每隔一帧时长取 1 帧
+                // 2. 轻微落后（两帧到四帧）：慢速追赶，取帧间隔缩短到 3/4 帧时长
+                // 3. 严重落后（超过四帧）：快速跳跃，直接丢弃过期帧
+                // 高帧率源（帧率高于显示刷新率）：单次取出不晚于时钟的最新帧，中间帧合并
+                let media_info = manager.get_media_info();
+                let fps = media_info.as_ref().map_or(0.0, |info| info.fps);
+                if self.sync_tuning_fps != Some(fps) {
+                    self.sync_tuning_fps = Some(fps);
+                    self.sync_tuning = SyncTuning::derive(fps).with_overrides(&self.settings.sync_overrides);
+                    info!("⏱️  音画同步阈值（{:.3}fps）: {}", fps, self.sync_tuning.summary());
+                }
+                // 倍速播放时两次刷新之间时钟推进更多，落后阈值按速度缩放
+                let tuning = self.sync_tuning.scaled_for_speed(manager.speed());
+                let is_high_fps = media_info
+                    .as_ref()
+                    .map(|info| info.fps > self.display_refresh.hz())
+                    .unwrap_or(false);
+                if let Some(info) = &media_info {
+                    renderer.set_display_geometry(info.pixel_aspect, info.rotation);
+                }
+                renderer.set_fit_mode(self.settings.fit_mode);
+                
+                let frame = if let Some(frame) = manager.take_paused_seek_frame() {
+                    // --- 暂停状态下 seek：立即显示目标帧（时钟不前进，不做时间比较） ---
+                    Some(frame)
+                } else if manager.is_paused_seek_pending() {
+                    // 目标帧解码完成前继续显示当前画面
+                    None
+                } else if is_high_fps && self.current_frame_pts.is_some() {
+                    // --- 高帧率：每次刷新追上时钟，跳过的帧计为"合并"而非"落后丢弃" ---
+                    let (frame, merged) = manager.take_newest_frame_until(current_time_ms);
+                    self.perf_stats.merged_frames += merged as u64;
+                    frame
+                } else if let Some(current_pts) = self.current_frame_pts {
                     // --- 已有当前帧：检查是否需要更新 ---
                     let time_diff = current_time_ms - current_pts;
                     
                     // 根据落后程度选择不同的更新阈值
-                    let update_threshold = if time_diff > 150 {
-                        // 严重落后（>150ms）：直接跳跃到最新帧
-                        0  // 立即更新
-                    } else if time_diff > 50 {
-                        // 轻微落后（50-150ms）：慢速追赶
-                        // 阈值降低到30ms，追赶速度约为 1.33x 播放速度
-                        // 例如：24fps → 32fps 的追赶速度，用户几乎感觉不到
-                        30
-                    } else {
-                        // 同步良好（-10~50ms）：正常播放
-                        // 保持40ms阈值，即 24fps
-                        40
-                    };
+                    // 严重落后：立即更新；轻微落后：按 3/4 帧时长慢速追赶（约 1.33x 播放速度）；同步良好：按帧时长
+                    let update_threshold = tuning.update_threshold(time_diff);
                     
                     if time_diff >= update_threshold {
                         // 需要更新帧
                         
-                        if time_diff > 150 {
-                            // --- 严重落后（>150ms）：快速跳跃 ---
+                        if tuning.is_severe(time_diff) {
+                            // --- 严重落后：快速跳跃 ---
                             // 场景：卡顿、解码慢、seek 后等
                             // 策略：跳过所有过期帧，直接显示最接近当前时间的帧
                             debug!("🎬 视频严重落后 {}ms，快速跳跃到最新帧", time_diff);
@@ -1031,12 +1517,12 @@ impl VideoPlayerApp {
                             // 最多检查10帧，避免阻塞UI
                             for _ in 0..10 {
                                 if let Some(f) = manager.get_current_frame() {
-                                    // 如果这一帧还是太旧（比当前时间早80ms以上），继续取下一帧
-                                    if f.pts < current_time_ms - 80 {
+                                    // 如果这一帧还是太旧（比当前时间早超过追赶阈值），继续取下一帧
+                                    if f.pts < current_time_ms - tuning.catch_up_threshold_ms {
                                         skipped_count += 1;
                                         latest_frame = Some(f);  // 暂存，继续找更新的
                                     } else {
-                                        // 找到合适的帧（在目标前后80ms内），停止
+                                        // 找到合适的帧（在追赶阈值内），停止
                                         latest_frame = Some(f);
                                         break;
                                     }
@@ -1053,8 +1539,7 @@ impl VideoPlayerApp {
                         } else {
                             // --- 同步良好 或 轻微落后：逐帧播放/慢速追赶 ---
                             // 每次UI更新最多取1帧
-                            // 轻微落后时通过降低阈值（30ms）来慢速追赶
-                            // 追赶速度：24fps → 约32fps，非常平滑
+                            // 轻微落后时通过缩短取帧间隔来慢速追赶（24fps → 约32fps，非常平滑）
                             manager.get_current_frame()
                         }
                     } else {
@@ -1083,10 +1568,19 @@ impl VideoPlayerApp {
                             debug!("🎬 音视频同步差异: {}ms (音频={}, 视频={})", sync_diff, current_time_ms, frame.pts);
                         }
                         
-                        if let Err(e) = renderer.update_and_render(ui, &frame, available_rect) {
+                        let pts = frame.pts;
+                        let shown = frame.clone();
+                        if let Err(e) = renderer.update_and_render(ui, frame, available_rect) {
                             error!("视频渲染失败: {}", e);
+                        } else {
+                            // 上报实际呈现的帧（进度条据此判断画面是否落后）
+                            manager.notify_frame_presented(&shown);
+                            // 连拍中：保存每一帧显示的画面
+                            if let (Some(burst), Some((frame, rotation))) = (&mut self.burst, renderer.displayed_frame()) {
+                                burst.offer(&frame, rotation);
+                            }
                         }
-                        self.current_frame_pts = Some(frame.pts);
+                        self.current_frame_pts = Some(pts);
                     } else {
                         // 相同 PTS 的帧（理论上不应该出现，但做容错处理）
                         // 只渲染不更新纹理，避免不必要的 GPU 操作
@@ -1097,13 +1591,18 @@ impl VideoPlayerApp {
                 } else {
                     // --- 没有新帧：继续显示上一帧 ---
                     // 原因可能是：
-                    // 1. 时间未到（current_time_ms < current_pts + 40）
+                    // 1. 时间未到（时间差小于更新阈值）
                     // 2. 解码线程还没来得及推送新帧到队列
                     // 3. Seek 后，新帧还在路上
+                    // 4. 纯音频文件，显示内嵌封面（只上传一次）
                     let has_frame = renderer.has_texture();
-                    if !has_frame {
+                    if let (false, Some(cover)) = (has_frame, manager.cover_art()) {
+                        if let Err(e) = renderer.update_and_render(ui, cover.clone(), available_rect) {
+                            error!("封面渲染失败: {}", e);
+                        }
+                    } else if !has_frame {
                         // 没有任何帧可显示，渲染占位符
-                        self.render_placeholder(ui, available_rect);
+                        start_page = self.render_placeholder(ui, available_rect, Some(&*manager));
                         self.current_frame_pts = None;
                     } else {
                         // 有上一帧的纹理，继续显示（避免闪烁）
@@ -1115,960 +1614,3805 @@ impl VideoPlayerApp {
                 
                 // ========== 渲染字幕 ==========
                 // 叠加在视频上方，根据当前播放时间选择合适的字幕
-                self.render_subtitle(ui, available_rect, current_time_ms);
+                let subtitles = manager.get_current_subtitles(current_time_ms);
+                let backdrop = &mut self.subtitle_backdrop;
+                let (adaptive, fixed_alpha) = (self.settings.adaptive_subtitle_backdrop, self.settings.subtitle_backdrop_alpha);
+                let renderer = self.video_renderer.as_ref();
+                let scale = self.settings.subtitle_scale;
+                let layout = Self::render_subtitle(&mut self.subtitle_stacker, ui, available_rect, subtitles, scale, |region| {
+                    // 手动设置优先；没有画面（纯音频）时沿用上次的样式
+                    if !adaptive {
+                        return BackdropStyle { alpha: fixed_alpha, light_scheme: false };
+                    }
+                    match renderer.and_then(|renderer| renderer.region_luma(available_rect, region)) {
+                        Some(luma) => backdrop.update(luma),
+                        None => backdrop.current(),
+                    }
+                });
+                self.displayed_subtitles = layout.map(|layout| (available_rect, layout));
             } else {
-                self.render_placeholder(ui, available_rect);
+                start_page = self.render_placeholder(ui, available_rect, None);
             }
         } else {
             // 渲染器未初始化时显示错误信息
             self.render_error_message(ui, available_rect, "视频渲染器未初始化");
         }
-    }
-    
-    /// 渲染字幕
-    /// 
-    /// 功能特点：
-    /// - 字幕显示在视频底部中央
-    /// - 支持多行字幕
-    /// - 黑色描边提高可读性
-    /// - 半透明背景
-    /// - 自适应字体大小
-    fn render_subtitle(&self, ui: &mut Ui, video_rect: egui::Rect, current_time_ms: i64) {
-        // 获取当前时间的字幕
-        if let Some(manager) = self.playback_manager.try_read() {
-            if let Some(subtitle) = manager.get_current_subtitle(current_time_ms) {
-                // 字幕显示参数
-                let subtitle_margin_bottom = 80.0; // 距离底部的间距
-                let subtitle_max_width = video_rect.width() * 0.85; // 字幕最大宽度为视频宽度的85%
-                
-                // 根据视频尺寸自适应字体大小
-                let base_font_size = (video_rect.height() * 0.03).max(18.0).min(32.0);
-                let font_size = base_font_size;
-                let line_height = font_size * 1.3;
-                
-                // 分行显示字幕文本
-                let lines: Vec<&str> = subtitle.text.lines()
-                    .filter(|line| !line.trim().is_empty())
-                    .collect();
-                
-                if lines.is_empty() {
-                    return;
+        if let Some((recent, filter)) = start_page {
+            start_action = start_screen::show(ui, &mut self.icons, available_rect, &recent, filter, &self.capabilities);
+        }
+        
+        // ========== 画面缩放（Ctrl+滚轮，以光标为中心）与平移（拖动） ==========
+        let mut zoom_osd = None;
+        if let Some(renderer) = &mut self.video_renderer {
+            let zoom_delta = if video_area.hovered() { ui.input(|i| i.zoom_delta()) } else { 1.0 };
+            if let (true, Some(anchor)) = (zoom_delta != 1.0, video_area.hover_pos()) {
+                renderer.zoom_at(zoom_delta, anchor, available_rect);
+                zoom_osd = Some(renderer.zoom());
+            }
+            if video_area.dragged_by(egui::PointerButton::Primary) {
+                renderer.pan_by(video_area.drag_delta(), available_rect);
+            }
+        }
+        if let Some(zoom) = zoom_osd {
+            self.show_osd(format!("缩放: {:.0}%", zoom * 100.0));
+        }
+        
+        // ========== 滚轮：竖直调整音量，水平（或 Shift+滚轮）跳转 ==========
+        // 起始页的最近文件列表需要滚动，只在打开了媒体时响应
+        if video_area.hovered() && !self.dialog_open() && !self.player.read().is_idle() {
+            let delta = ui.input(|i| i.raw_scroll_delta);
+            if delta != egui::Vec2::ZERO {
+                // 滚动量由画面使用，下层的面板不再响应
+                ui.ctx().input_mut(|i| {
+                    i.raw_scroll_delta = egui::Vec2::ZERO;
+                    i.smooth_scroll_delta = egui::Vec2::ZERO;
+                });
+                let steps = self.wheel.add(delta);
+                if steps.volume != 0 {
+                    self.adjust_volume(steps.volume as f32 * VOLUME_STEP);
+                }
+                if steps.seek != 0 {
+                    self.seek_by(steps.seek as i64 * WHEEL_SEEK_MS);
+                }
+            }
+        }
+        
+        // 渲染器通知（如 GPU 异常后切换到兼容模式）
+        if let Some(renderer) = self.video_renderer.as_mut() {
+            if let Some(message) = renderer.take_notification() {
+                // 兼容模式下不再直接显示 YUV 帧，改回由解码器转换为 RGBA
+                Player::set_yuv_output_enabled(renderer.accepts_yuv());
+                self.show_osd(message);
+            }
+        }
+        
+        // 起始页操作（已释放播放器的读锁）
+        if let Some(action) = start_action {
+            self.handle_start_action(action);
+        }
+        
+        // ========== 右键菜单：轨道选择、窗口大小 ==========
+        let mut snap_scale = None;
+        let mut fit_mode = None;
+        let mut reset_zoom = false;
+        let zoomed = self.video_renderer.as_ref().is_some_and(|renderer| renderer.is_zoomed());
+        let mut track_action = None;
+        let mut forced_setting_changed = false;
+        let mut export_playlist = false;
+        let has_queue = self.ui_state.current_file.is_some() || !self.player.read().playlist().is_empty();
+        let menu_open = video_area
+            .context_menu(|ui| {
+                if let Some(manager) = self.player.try_read() {
+                    let current_audio = manager.current_audio_stream().map(TrackSource::Embedded);
+                    ui.menu_button("音频轨道", |ui| {
+                        if manager.get_audio_tracks().is_empty() {
+                            ui.label("无音频轨道");
+                        }
+                        for track in manager.get_audio_tracks() {
+                            if Self::track_menu_item(ui, track, current_audio.as_ref() == Some(&track.source)) {
+                                if let TrackSource::Embedded(index) = track.source {
+                                    track_action = Some(PlayerAction::SelectAudioTrack(index));
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    
+                    let current_subtitle = manager.current_subtitle_track().cloned();
+                    ui.menu_button("字幕轨道", |ui| {
+                        if ui.selectable_label(current_subtitle.is_none(), "关闭").clicked() {
+                            track_action = Some(PlayerAction::SelectSubtitleTrack(None));
+                            ui.close_menu();
+                        }
+                        for track in manager.get_subtitle_tracks() {
+                            if Self::track_menu_item(ui, track, current_subtitle.as_ref() == Some(&track.source)) {
+                                track_action = Some(PlayerAction::SelectSubtitleTrack(Some(track.source.clone())));
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        forced_setting_changed = ui
+                            .checkbox(&mut self.settings.auto_forced_subtitles, "字幕关闭时显示强制字幕")
+                            .on_hover_text("打开文件时，如果存在与音频语言一致的强制字幕，即使字幕关闭也自动选择")
+                            .changed();
+                        ui.checkbox(&mut self.settings.adaptive_subtitle_backdrop, "字幕背景随画面亮度调整")
+                            .on_hover_text("画面越亮背景越深，极亮的画面改用浅色背景和深色文字");
+                        if !self.settings.adaptive_subtitle_backdrop {
+                            ui.add(
+                                egui::Slider::new(&mut self.settings.subtitle_backdrop_alpha, 0..=255)
+                                    .text("背景不透明度")
+                            );
+                        }
+                    });
+                    ui.separator();
                 }
                 
-                // 计算所需的总高度
-                let total_height = lines.len() as f32 * line_height + 16.0; // 16.0 是上下padding
-                
-                // 计算字幕显示区域
-                let subtitle_rect = egui::Rect::from_min_max(
-                    egui::pos2(
-                        video_rect.center().x - subtitle_max_width / 2.0,
-                        video_rect.bottom() - subtitle_margin_bottom - total_height
-                    ),
-                    egui::pos2(
-                        video_rect.center().x + subtitle_max_width / 2.0,
-                        video_rect.bottom() - subtitle_margin_bottom
-                    )
-                );
-                
-                // 绘制半透明背景（提高可读性）
-                ui.painter().rect_filled(
-                    subtitle_rect.expand(8.0), // 扩大区域以创建padding
-                    6.0, // 圆角
-                    egui::Color32::from_rgba_premultiplied(0, 0, 0, 150) // 半透明黑色背景
-                );
-                
-                // 绘制字幕文本（带描边效果以提高可读性）
-                let painter = ui.painter();
-                let text_color = egui::Color32::WHITE;
-                let stroke_color = egui::Color32::from_rgb(0, 0, 0);
-                let stroke_width = 2.0; // 描边宽度
-                
-                // 计算文本起始位置（垂直居中）
-                let start_y = subtitle_rect.center().y - (lines.len() as f32 - 1.0) * line_height / 2.0;
+                if ui.add(egui::Button::new("新建标签页").shortcut_text("Ctrl+T")).clicked() {
+                    track_action = Some(PlayerAction::NewSession);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(has_queue, egui::Button::new("导出播放列表…")).clicked() {
+                    export_playlist = true;
+                    ui.close_menu();
+                }
+                ui.separator();
                 
-                for (i, line) in lines.iter().enumerate() {
-                    let trimmed_line = line.trim();
-                    if trimmed_line.is_empty() {
-                        continue;
+                ui.menu_button("画面", |ui| {
+                    for mode in FitMode::ALL {
+                        if ui.selectable_label(self.settings.fit_mode == mode, mode.label()).clicked() {
+                            fit_mode = Some(mode);
+                            ui.close_menu();
+                        }
                     }
-                    
-                    let y_pos = start_y + i as f32 * line_height;
-                    let text_pos = egui::pos2(subtitle_rect.center().x, y_pos);
-                    
-                    // 绘制描边（多个方向的偏移以创建描边效果）
-                    // 使用更精细的偏移模式，创建更好的描边效果
-                    for dx in [-stroke_width, 0.0, stroke_width] {
-                        for dy in [-stroke_width, 0.0, stroke_width] {
-                            if dx != 0.0 || dy != 0.0 {
-                                painter.text(
-                                    text_pos + egui::vec2(dx, dy),
-                                    egui::Align2::CENTER_CENTER,
-                                    trimmed_line,
-                                    egui::FontId::proportional(font_size),
-                                    stroke_color,
-                                );
-                            }
+                    ui.separator();
+                    if ui
+                        .add_enabled(zoomed, egui::Button::new("重置缩放"))
+                        .on_hover_text("Ctrl+滚轮缩放画面，放大后拖动画面平移；Shift+A 切换模式")
+                        .clicked()
+                    {
+                        reset_zoom = true;
+                        ui.close_menu();
+                    }
+                });
+                ui.menu_button("窗口大小", |ui| {
+                    for (scale, shortcut) in [
+                        (WindowScale::Percent(50), "Alt+1"),
+                        (WindowScale::Percent(100), "Alt+2"),
+                        (WindowScale::Percent(200), "Alt+3"),
+                        (WindowScale::FitScreen, ""),
+                    ] {
+                        if ui.add(egui::Button::new(scale.label()).shortcut_text(shortcut)).clicked() {
+                            snap_scale = Some(scale);
+                            ui.close_menu();
                         }
                     }
-                    
-                    // 绘制文本本身
-                    painter.text(
-                        text_pos,
-                        egui::Align2::CENTER_CENTER,
-                        trimmed_line,
-                        egui::FontId::proportional(font_size),
-                        text_color,
-                    );
-                }
+                });
+                let current_scale = self
+                    .player
+                    .try_read()
+                    .and_then(|manager| manager.get_media_info())
+                    .map(|info| {
+                        let (width, height) = info.display_size();
+                        fitted_scale(egui::vec2(width as f32, height as f32), available_rect.size())
+                    })
+                    .filter(|scale| *scale > 0.0);
+                let current_text = current_scale
+                    .map(|scale| format!("{:.0}%", scale * 100.0))
+                    .unwrap_or_else(|| "—".to_string());
+                ui.label(
+                    egui::RichText::new(format!("当前缩放: {}", current_text))
+                        .size(12.0)
+                        .color(egui::Color32::GRAY)
+                );
+            })
+            .is_some();
+        if let Some(scale) = snap_scale {
+            self.dispatch_action(ui.ctx(), PlayerAction::SnapWindow(scale));
+        }
+        if let Some(mode) = fit_mode {
+            self.set_fit_mode(mode);
+        }
+        if let (true, Some(renderer)) = (reset_zoom, &mut self.video_renderer) {
+            renderer.reset_zoom();
+        }
+        if let Some(action) = track_action {
+            self.dispatch_action(ui.ctx(), action);
+        }
+        if forced_setting_changed {
+            self.player.write().set_auto_forced_subtitles(self.settings.auto_forced_subtitles);
+        }
+        if export_playlist {
+            self.export_playlist();
+        }
+        
+        // ========== 单击播放/暂停，双击切换全屏 ==========
+        // 控制栏是独立的面板、对话框和提示在更上层，点击它们不会落到画面区域
+        let input = ClickInput {
+            clicked: video_area.clicked(),
+            double_clicked: video_area.double_clicked(),
+            pointer_down: ui.input(|i| i.pointer.any_down()),
+        };
+        let gesture = self.video_clicks.update(input, self.settings.click_to_pause, Instant::now());
+        if menu_open {
+            self.video_clicks.menu_shown();
+        }
+        match gesture {
+            Some(VideoGesture::ToggleFullscreen) => self.toggle_fullscreen(ui.ctx()),
+            // 起始页（没有打开媒体）上单击不播放
+            Some(VideoGesture::TogglePause) if !self.player.read().is_idle() => {
+                self.dispatch_action(ui.ctx(), PlayerAction::PlayPause);
             }
+            _ => {}
         }
+        
+        // ========== 渲染 OSD ==========
+        self.render_osd(ui, available_rect);
+        self.render_hdr_notice(ui, available_rect);
+        self.render_audio_failure_notice(ui, available_rect);
+        error_banner::show(ui, available_rect, self.osd_style(), &mut self.ui_state.last_error);
+        self.render_first_frame_notice(ui, available_rect);
+        self.render_crash_notice(ui, available_rect);
+        self.render_skip_notice(ui, available_rect);
+        self.render_refresh_rate_notice(ui, available_rect);
+        self.render_filmstrip(ui.ctx(), available_rect);
     }
-
-    /// 渲染占位符
-    fn render_placeholder(&self, ui: &mut Ui, rect: egui::Rect) {
-        ui.allocate_ui_at_rect(rect, |ui| {
-            ui.centered_and_justified(|ui| {
-                ui.vertical_centered(|ui| {
-                    ui.add_space(60.0);
-                    
-                    // 如果正在加载，显示加载动画
-                    if let Some(ref url) = self.loading_url {
-                        ui.label(
-                            egui::RichText::new("⏳")
-                                .size(64.0)
-                                .color(egui::Color32::from_rgb(100, 149, 237))
-                        );
-                        ui.add_space(10.0);
-                        ui.label(
-                            egui::RichText::new("正在连接网络流...")
-                                .size(24.0)
-                                .color(egui::Color32::LIGHT_GRAY)
-                        );
-                        ui.add_space(5.0);
-                        ui.label(
-                            egui::RichText::new(url)
-                                .size(14.0)
-                                .color(egui::Color32::GRAY)
-                        );
-                        
-                        // 添加旋转动画
-                        ui.ctx().request_repaint();
-                    } else {
-                        // 默认占位符
-                        ui.label(
-                            egui::RichText::new("🎬")
-                                .size(64.0)
-                                .color(egui::Color32::GRAY)
-                        );
-                        ui.add_space(10.0);
-                        ui.label(
-                            egui::RichText::new("喜洋洋播放器")
-                                .size(24.0)
-                                .color(egui::Color32::LIGHT_GRAY)
-                        );
-                        ui.add_space(5.0);
-                        ui.label(
-                            egui::RichText::new("拖拽视频文件到此处或点击打开文件")
-                                .size(14.0)
-                                .color(egui::Color32::GRAY)
-                        );
-                    }
-                });
-            });
-        });
+    
+    /// 胶片视图不可用的原因（直播、不可跳转的媒体、网络流）
+    fn filmstrip_unavailable_reason(&self) -> Option<&'static str> {
+        let manager = self.player.read();
+        filmstrip::unavailable_reason(
+            self.ui_state.current_file.is_some() && !manager.is_idle(),
+            manager.is_live(),
+            manager.is_network_stream(),
+            manager.is_still_image(),
+            manager.get_duration_ms(),
+        )
     }
-
-    /// 渲染错误信息
-    fn render_error_message(&self, ui: &mut Ui, rect: egui::Rect, message: &str) {
-        ui.allocate_ui_at_rect(rect, |ui| {
-            ui.centered_and_justified(|ui| {
+    
+    /// 渲染胶片视图（视频区域底部，控制栏上方），点击缩略图跳转
+    fn render_filmstrip(&mut self, ctx: &Context, video_rect: egui::Rect) {
+        if !self.filmstrip.is_visible() {
+            return;
+        }
+        // 切换到不支持的媒体（如直播流）时自动关闭
+        if self.filmstrip_unavailable_reason().is_some() {
+            self.filmstrip.close();
+            return;
+        }
+        let Some(path) = self.ui_state.current_file.clone() else {
+            return;
+        };
+        let (duration_ms, position_ms) = {
+            let manager = self.player.read();
+            (manager.get_duration_ms(), manager.get_position_ms())
+        };
+        if let Some(target_ms) = self.filmstrip.show(ctx, video_rect, &path, duration_ms, position_ms) {
+            debug!("🎞️ 胶片视图跳转: {}ms", target_ms);
+            self.player.write().seek_with(target_ms, SeekMode::Accurate);
+        }
+    }
+    
+    /// 按视频原始尺寸的比例调整窗口大小（目标是视频视口，超出屏幕时夹紧并提示）
+    fn snap_window(&mut self, ctx: &Context, scale: WindowScale) {
+        if self.is_fullscreen(ctx) {
+            self.show_osd("全屏时无法调整窗口大小");
+            return;
+        }
+        let Some(info) = self.player.read().get_media_info() else {
+            self.show_osd("没有正在播放的视频");
+            return;
+        };
+        let Some(viewport) = self.video_viewport else {
+            return;
+        };
+        let (width, height) = info.display_size();
+        if width <= 0.0 || height <= 0.0 {
+            self.show_osd("没有正在播放的视频");
+            return;
+        }
+        
+        // 视频视口以外的部分（控制栏、信息栏等）保持不变
+        let chrome = (ctx.screen_rect().size() - viewport.size()).max(egui::Vec2::ZERO);
+        
+        // egui 不提供屏幕工作区，用显示器尺寸减去窗口装饰与任务栏预留
+        let max_inner = ctx.input(|i| {
+            let viewport = i.viewport();
+            let decoration = match (viewport.outer_rect, viewport.inner_rect) {
+                (Some(outer), Some(inner)) => outer.size() - inner.size(),
+                _ => egui::Vec2::ZERO,
+            };
+            viewport
+                .monitor_size
+                .map(|monitor| monitor - decoration - egui::vec2(0.0, TASKBAR_RESERVE))
+                .unwrap_or(egui::Vec2::INFINITY)
+        });
+        
+        let (size, clamped) = target_inner_size(egui::vec2(width as f32, height as f32), scale, chrome, max_inner);
+        info!("🪟 调整窗口大小: {} -> {:.0}x{:.0}（视频 {:.0}x{:.0}）", scale.label(), size.x, size.y, width, height);
+        
+        if ctx.input(|i| i.viewport().maximized.unwrap_or(false)) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Maximized(false));
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+        
+        if clamped {
+            warn!("⚠️  窗口大小超出屏幕或最小尺寸限制，已夹紧");
+            self.show_osd(format!("窗口大小: {}（受屏幕限制，未能精确缩放）", scale.label()));
+        } else {
+            self.show_osd(format!("窗口大小: {}", scale.label()));
+        }
+    }
+    
+    /// 提示跳到的章节（"章节 2/5: 标题"）
+    fn show_chapter_osd(&mut self, chapters: &[Chapter], index: usize) {
+        self.show_osd(format!("章节 {}/{}: {}", index + 1, chapters.len(), chapters[index].title));
+    }
+    
+    /// 在视频区域顶部显示打开失败的提示（按错误分类给出原因和处理建议）
+    fn report_open_error(&mut self, error: &anyhow::Error) {
+        let message = error_banner::error_message(error_banner::category_of(error), &error.to_string());
+        self.ui_state.last_error = Some((message, Instant::now()));
+    }
+    
+    /// 显示屏幕提示
+    fn show_osd(&mut self, text: impl Into<String>) {
+        self.osd_message = Some(OsdMessage {
+            text: text.into(),
+            created_at: Instant::now(),
+        });
+    }
+    
+    /// 叠加层样式（设置中的缩放和屏幕提示位置）
+    fn osd_style(&self) -> OsdStyle {
+        OsdStyle::new(self.settings.osd_scale, self.settings.osd_anchor)
+    }
+    
+    /// 渲染屏幕提示（OSD）
+    fn render_osd(&mut self, ui: &mut Ui, video_rect: egui::Rect) {
+        const OSD_DURATION: f32 = 1.5;  // 总显示时长（秒）
+        const OSD_FADE: f32 = 0.3;      // 淡出时长（秒）
+        
+        let Some(osd) = &self.osd_message else {
+            return;
+        };
+        
+        let elapsed = osd.created_at.elapsed().as_secs_f32();
+        if elapsed >= OSD_DURATION {
+            self.osd_message = None;
+            return;
+        }
+        let alpha = ((OSD_DURATION - elapsed) / OSD_FADE).min(1.0);
+        
+        // 放大后的提示框不超过视频宽度的 90%，超出时换行
+        let style = self.osd_style();
+        let font_id = egui::FontId::proportional(style.size(20.0));
+        let padding = style.vec2(12.0, 6.0);
+        let galley = ui.painter().layout(
+            osd.text.clone(),
+            font_id,
+            egui::Color32::WHITE.gamma_multiply(alpha),
+            (style.max_width(video_rect) - padding.x * 2.0).max(0.0),
+        );
+        let bg_rect = style.message_rect(video_rect, galley.size() + padding * 2.0);
+        ui.painter().rect_filled(
+            bg_rect,
+            style.size(4.0),
+            egui::Color32::from_black_alpha((160.0 * alpha) as u8),
+        );
+        ui.painter().galley(bg_rect.min + padding, galley, egui::Color32::WHITE);
+    }
+    
+    /// 检查新打开片源的 HDR 兼容性：无法正确显示颜色时暂停并常驻提示，带兼容基础层时短暂提示
+    fn check_hdr_compatibility(&mut self) {
+        let compat = self
+            .player
+            .read()
+            .get_media_info()
+            .map(|info| info.hdr_compat)
+            .unwrap_or_default();
+        self.hdr_notice = None;
+        let Some(notice) = compat.notice() else {
+            return;
+        };
+        if compat.is_supported() {
+            self.show_osd(notice);
+        } else {
+            // 先暂停，由用户决定是否仍然播放
+            self.player.read().pause();
+            self.hdr_notice = Some(notice);
+        }
+    }
+    
+    /// 渲染 HDR 兼容性提示（视频区域顶部居中）
+    fn render_hdr_notice(&mut self, ui: &mut Ui, video_rect: egui::Rect) {
+        let Some(notice) = &self.hdr_notice else {
+            return;
+        };
+        
+        let style = self.osd_style();
+        let mut play_anyway = false;
+        egui::Area::new(egui::Id::new("hdr_notice"))
+            .fixed_pos(video_rect.center_top() + egui::Vec2::new(0.0, style.size(20.0)))
+            .pivot(egui::Align2::CENTER_TOP)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_black_alpha(200))
+                    .rounding(style.size(4.0))
+                    .inner_margin(egui::Margin::symmetric(style.size(12.0), style.size(8.0)))
+                    .show(ui, |ui| {
+                        ui.set_max_width(style.max_width(video_rect) - style.size(24.0));
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(
+                                egui::RichText::new(format!("⚠ {}", notice))
+                                    .size(style.size(14.0))
+                                    .color(egui::Color32::from_rgb(255, 200, 80))
+                            );
+                            play_anyway = ui.link(egui::RichText::new("仍然播放").size(style.size(14.0))).clicked();
+                        });
+                    });
+            });
+        
+        if play_anyway {
+            info!("▶️ 忽略 HDR 兼容性提示，仍然播放");
+            self.hdr_notice = None;
+            if let Err(e) = self.player.write().play() {
+                error!("播放失败: {}", e);
+            }
+        }
+    }
+    
+    /// 渲染音频解码失败提示（视频区域顶部居中，有其他音轨时可一键切换）
+    fn render_audio_failure_notice(&mut self, ui: &mut Ui, video_rect: egui::Rect) {
+        let Some((next_track, shown_at)) = self.audio_failure_notice else {
+            return;
+        };
+        if shown_at.elapsed() >= AUDIO_FAILURE_NOTICE_DURATION {
+            self.audio_failure_notice = None;
+            return;
+        }
+        ui.ctx().request_repaint_after(AUDIO_FAILURE_NOTICE_DURATION - shown_at.elapsed());
+
+        let style = self.osd_style();
+        let mut switch_track = false;
+        let mut dismiss = false;
+        egui::Area::new(egui::Id::new("audio_failure_notice"))
+            .fixed_pos(video_rect.center_top() + egui::Vec2::new(0.0, style.size(20.0)))
+            .pivot(egui::Align2::CENTER_TOP)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_black_alpha(200))
+                    .rounding(style.size(4.0))
+                    .inner_margin(egui::Margin::symmetric(style.size(12.0), style.size(8.0)))
+                    .show(ui, |ui| {
+                        ui.set_max_width(style.max_width(video_rect) - style.size(24.0));
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(
+                                egui::RichText::new("⚠ 音频解码失败，已切换为无声播放")
+                                    .size(style.size(14.0))
+                                    .color(egui::Color32::from_rgb(255, 200, 80))
+                            );
+                            if next_track.is_some() {
+                                switch_track = ui.link(egui::RichText::new("切换到下一条音轨").size(style.size(14.0))).clicked();
+                            }
+                            dismiss = ui.link(egui::RichText::new("关闭").size(style.size(14.0))).clicked();
+                        });
+                    });
+            });
+
+        if switch_track || dismiss {
+            self.audio_failure_notice = None;
+        }
+        if let (true, Some(stream_index)) = (switch_track, next_track) {
+            info!("🔊 音频解码失败，切换到下一条音轨: 流 {}", stream_index);
+            self.select_audio_track(stream_index);
+        }
+    }
+    
+    /// 渲染视频流解码不出画面的提示（视频区域顶部居中，可改为只播放音频）
+    fn render_first_frame_notice(&mut self, ui: &mut Ui, video_rect: egui::Rect) {
+        let Some(diagnosis) = self.first_frame_notice else {
+            return;
+        };
+
+        let style = self.osd_style();
+        let mut audio_only = false;
+        let mut dismiss = false;
+        egui::Area::new(egui::Id::new("first_frame_notice"))
+            .fixed_pos(video_rect.center_top() + egui::Vec2::new(0.0, style.size(20.0)))
+            .pivot(egui::Align2::CENTER_TOP)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_black_alpha(200))
+                    .rounding(style.size(4.0))
+                    .inner_margin(egui::Margin::symmetric(style.size(12.0), style.size(8.0)))
+                    .show(ui, |ui| {
+                        ui.set_max_width(style.max_width(video_rect) - style.size(24.0));
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(
+                                egui::RichText::new(format!("⚠ {}", diagnosis.message()))
+                                    .size(style.size(14.0))
+                                    .color(egui::Color32::from_rgb(255, 200, 80))
+                            );
+                            audio_only = ui.link(egui::RichText::new("仅播放音频").size(style.size(14.0))).clicked();
+                            dismiss = ui.link(egui::RichText::new("关闭").size(style.size(14.0))).clicked();
+                        });
+                    });
+            });
+
+        if audio_only || dismiss {
+            self.first_frame_notice = None;
+        }
+        if audio_only {
+            self.player.read().continue_audio_only();
+        }
+    }
+    
+    /// 渲染解码崩溃提示（视频区域顶部居中，可对该文件以安全模式重新打开）
+    fn render_crash_notice(&mut self, ui: &mut Ui, video_rect: egui::Rect) {
+        let Some(notice) = self.crash_notice.clone() else {
+            return;
+        };
+
+        let style = self.osd_style();
+        let mut reopen = false;
+        let mut dismiss = false;
+        egui::Area::new(egui::Id::new("crash_notice"))
+            .fixed_pos(video_rect.center_top() + egui::Vec2::new(0.0, style.size(20.0)))
+            .pivot(egui::Align2::CENTER_TOP)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_black_alpha(200))
+                    .rounding(style.size(4.0))
+                    .inner_margin(egui::Margin::symmetric(style.size(12.0), style.size(8.0)))
+                    .show(ui, |ui| {
+                        ui.set_max_width(style.max_width(video_rect) - style.size(24.0));
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(
+                                egui::RichText::new(format!("⚠ {}", notice.message()))
+                                    .size(style.size(14.0))
+                                    .color(egui::Color32::from_rgb(255, 120, 100))
+                            );
+                            reopen = ui.link(egui::RichText::new("以安全模式重新打开").size(style.size(14.0))).clicked();
+                            dismiss = ui.link(egui::RichText::new("关闭").size(style.size(14.0))).clicked();
+                        });
+                    });
+            });
+
+        if reopen || dismiss {
+            self.crash_notice = None;
+        }
+        if reopen {
+            self.safe_reopen = true;
+            let path = notice.path().to_string_lossy().into_owned();
+            if let Err(e) = self.open_file(path) {
+                error!("以安全模式重新打开失败: {}", e);
+                self.report_open_error(&e);
+            }
+        } else if dismiss {
+            // 继续以正常模式播放：不再提示
+            if let Err(e) = self.crash_markers.clear(notice.path()) {
+                warn!("⚠️ 清除崩溃标记失败: {}", e);
+            }
+        }
+    }
+    
+    /// 渲染安全模式提示条（窗口顶部常驻，可启动正常模式进程对比）
+    fn render_safe_mode_banner(&mut self, ctx: &Context) {
+        if !self.runtime_flags.is_safe_mode() {
+            return;
+        }
+        let mut launch = false;
+        egui::TopBottomPanel::top("safe_mode_banner")
+            .frame(
+                egui::Frame::none()
+                    .fill(egui::Color32::from_rgb(120, 80, 0))
+                    .inner_margin(egui::Margin::symmetric(12.0, 4.0))
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("🛡 安全模式：硬件解码、字幕自动加载、监视文件夹和自动匹配刷新率已关闭")
+                            .color(egui::Color32::WHITE)
+                    );
+                    launch = ui.button("以正常模式打开对比").clicked();
+                });
+            });
+        if launch {
+            if let Err(e) = safe_mode::launch_normal_mode(self.ui_state.current_file.as_deref()) {
+                error!("启动正常模式失败: {}", e);
+                self.show_osd(format!("启动正常模式失败: {}", e));
+            }
+        }
+    }
+    
+    /// 打开文件（或切换标签页）时重置跳过状态
+    fn reset_skip_state(&mut self) {
+        self.skip_tracker.reset();
+        self.skip_notice = None;
+    }
+    
+    /// 播放进入标记的片头 / 片尾：已确认的范围自动跳过，未确认的提示手动跳过
+    fn check_skip_ranges(&mut self) {
+        let Some(path) = self.ui_state.current_file.as_deref() else {
+            return;
+        };
+        let Some((_, ranges)) = self.skip_ranges.resolve(path) else {
+            return;
+        };
+        if self.ui_state.seeking {
+            return;
+        }
+        let event = {
+            let Some(manager) = self.player.try_read() else {
+                return;
+            };
+            if !manager.is_playing() || manager.live_status().is_some() {
+                return;
+            }
+            self.skip_tracker.check(ranges, manager.get_position_ms(), manager.get_duration_ms())
+        };
+        let Some(event) = event else {
+            return;
+        };
+        if let SkipEvent::Skip { kind, to_ms, .. } = event {
+            info!("⏭️ 自动跳过{}，跳到 {}", kind.name(), format_time(to_ms));
+            self.player.write().seek_with(to_ms, SeekMode::Accurate);
+        }
+        self.skip_notice = Some(SkipNotice::new(event, Instant::now()));
+    }
+    
+    /// 渲染跳过提示（视频区域右下角；自动跳过后可撤销，未确认的范围可手动跳过）
+    fn render_skip_notice(&mut self, ui: &mut Ui, video_rect: egui::Rect) {
+        let Some(notice) = self.skip_notice else {
+            return;
+        };
+        let now = Instant::now();
+        if !notice.is_active(now) {
+            self.skip_notice = None;
+            return;
+        }
+        ui.ctx().request_repaint_after(UNDO_WINDOW.saturating_sub(now.saturating_duration_since(notice.shown_at)));
+
+        let style = self.osd_style();
+        let mut clicked = false;
+        egui::Area::new(egui::Id::new("skip_notice"))
+            .fixed_pos(video_rect.right_bottom() - egui::Vec2::new(style.size(20.0), style.size(100.0)))
+            .pivot(egui::Align2::RIGHT_BOTTOM)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_black_alpha(200))
+                    .rounding(style.size(4.0))
+                    .inner_margin(egui::Margin::symmetric(style.size(12.0), style.size(8.0)))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(notice.text()).size(style.size(14.0)).color(egui::Color32::WHITE));
+                            clicked = ui
+                                .link(egui::RichText::new(notice.action_label()).size(style.size(14.0)).color(SKIP_NOTICE_COLOR))
+                                .clicked();
+                        });
+                    });
+            });
+        if !clicked {
+            return;
+        }
+
+        self.skip_notice = None;
+        match notice.event {
+            SkipEvent::Skip { kind, from_ms, .. } => {
+                info!("↩️ 撤销跳过{}，回到 {}", kind.name(), format_time(from_ms));
+                self.player.write().seek_with(from_ms, SeekMode::Accurate);
+            }
+            SkipEvent::Offer { kind, to_ms } => {
+                // 用户确认了这个范围：之后自动跳过
+                if let Some(path) = self.ui_state.current_file.clone() {
+                    self.skip_ranges.confirm(&path, kind);
+                    self.save_skip_ranges();
+                }
+                info!("⏭️ 跳过{}，跳到 {}", kind.name(), format_time(to_ms));
+                self.player.write().seek_with(to_ms, SeekMode::Accurate);
+            }
+        }
+    }
+    
+    /// 在当前播放位置标记片头 / 片尾（有单独设置时只修改当前文件，否则修改整个文件夹）
+    fn mark_skip_position(&mut self, mark: SkipMark) {
+        let Some(path) = self.ui_state.current_file.clone() else {
+            return;
+        };
+        let position_ms = self.player.read().get_position_ms().max(0);
+        self.skip_ranges.update(&path, |ranges| mark.apply(ranges, position_ms));
+        // 刚标记的范围在本次播放中不再提示
+        self.skip_tracker.mark_handled(mark.kind());
+        self.save_skip_ranges();
+        self.show_osd(format!("已标记{}: {}", mark.name(), format_time(position_ms)));
+    }
+    
+    /// 保存片头片尾记录
+    fn save_skip_ranges(&self) {
+        if let Err(e) = self.skip_ranges.save(&SkipRangeStore::default_file()) {
+            error!("保存片头片尾记录失败: {}", e);
+        }
+    }
+    
+    /// 控制栏的片头片尾按钮和标记菜单
+    fn show_skip_ranges_button(&mut self, ui: &mut Ui) {
+        let Some(path) = self.ui_state.current_file.clone() else {
+            return;
+        };
+        let marked = self.skip_ranges.resolve(&path).is_some();
+        let response = control_bar::bookmark_button(ui, &mut self.icons, marked);
+        let popup_id = ui.make_persistent_id("skip_ranges_popup");
+        if response.clicked() {
+            ui.memory_mut(|memory| memory.toggle_popup(popup_id));
+        }
+
+        let mut mark = None;
+        let mut clear = None;
+        let mut file_override = self.skip_ranges.has_file_override(&path);
+        let ranges = self.skip_ranges.resolve(&path).map(|(_, ranges)| ranges.clone()).unwrap_or_default();
+        egui::popup::popup_above_or_below_widget(ui, popup_id, &response, egui::AboveOrBelow::Above, |ui| {
+            ui.set_min_width(180.0);
+            for candidate in [SkipMark::IntroStart, SkipMark::IntroEnd, SkipMark::OutroStart] {
+                if ui.button(format!("标记{}", candidate.name())).clicked() {
+                    mark = Some(candidate);
+                }
+            }
+            ui.separator();
+            if let Some(end_ms) = ranges.intro_end {
+                let start_ms = ranges.intro_start.unwrap_or(0);
+                ui.label(format!("片头: {} - {}", format_time(start_ms), format_time(end_ms)));
+            }
+            if let Some(start_ms) = ranges.outro_start {
+                ui.label(format!("片尾: {} 起", format_time(start_ms)));
+            }
+            ui.checkbox(&mut file_override, "仅对此文件");
+            ui.horizontal(|ui| {
+                for kind in [SkipKind::Intro, SkipKind::Outro] {
+                    if ui.button(format!("清除{}", kind.name())).clicked() {
+                        clear = Some(kind);
+                    }
+                }
+            });
+        });
+
+        if file_override != self.skip_ranges.has_file_override(&path) {
+            self.skip_ranges.set_file_override(&path, file_override);
+            self.save_skip_ranges();
+        }
+        if let Some(mark) = mark {
+            self.mark_skip_position(mark);
+        }
+        if let Some(kind) = clear {
+            self.skip_ranges.update(&path, |ranges| ranges.clear(kind));
+            self.save_skip_ranges();
+            self.show_osd(format!("已清除{}", kind.name()));
+        }
+    }
+    
+    /// 渲染字幕
+    /// 
+    /// 功能特点：
+    /// - 字幕显示在视频底部中央
+    /// - 重叠字幕最多同时显示 4 条，自底向上堆叠，每条在显示期间位置固定
+    /// - 支持多行字幕
+    /// - 黑色描边提高可读性
+    /// - 每条字幕独立的半透明背景（不透明度由 backdrop 根据字幕区域决定）
+    /// - 自适应字体大小
+    /// - 总高度不超过视频高度的 40%，超出时省略最早的字幕
+    ///
+    /// 返回本帧绘制的字幕布局（带字幕截图使用）
+    fn render_subtitle(
+        stacker: &mut SubtitleStacker<SubtitleCueKey>,
+        ui: &mut Ui,
+        video_rect: egui::Rect,
+        subtitles: Vec<SubtitleFrame>,
+        scale: f32,
+        backdrop: impl FnOnce(egui::Rect) -> BackdropStyle,
+    ) -> Option<SubtitleLayout> {
+        // 字幕显示参数
+        let subtitle_margin_bottom = 80.0; // 距离底部的间距
+        let subtitle_max_width = video_rect.width() * 0.85; // 字幕最大宽度为视频宽度的85%
+        let box_gap = 6.0; // 相邻字幕背景之间的间距
+        
+        // 根据视频尺寸自适应字体大小（再按设置中的字幕大小缩放）
+        let base_font_size = (video_rect.height() * 0.03).max(18.0).min(32.0);
+        let font_size = base_font_size * scale;
+        let line_height = font_size * 1.3;
+        
+        // 分行（每条字幕额外占用一行高度作为背景 padding）
+        // TODO: 带显式定位的 ASS 字幕不参与堆叠（待样式支持）
+        let cues: Vec<(SubtitleCueKey, Vec<String>)> = subtitles
+            .into_iter()
+            .map(|subtitle| {
+                let lines: Vec<String> = subtitle.text.lines()
+                    .map(|line| line.trim().to_string())
+                    .filter(|line| !line.is_empty())
+                    .collect();
+                ((subtitle.pts, subtitle.end_pts, subtitle.text), lines)
+            })
+            .filter(|(_, lines)| !lines.is_empty())
+            .collect();
+        
+        let stack_input: Vec<(SubtitleCueKey, u32)> = cues
+            .iter()
+            .map(|(key, lines)| (key.clone(), lines.len() as u32 + 1))
+            .collect();
+        let max_height = (video_rect.height() * SUBTITLE_MAX_HEIGHT_RATIO / line_height).floor() as u32;
+        let placements = stacker.update(&stack_input, max_height);
+        
+        let painter = ui.painter();
+        let stroke_width = 2.0; // 描边宽度
+        let stack_bottom = video_rect.bottom() - subtitle_margin_bottom;
+        
+        // 计算字幕显示区域（背景框）
+        let boxes: Vec<(egui::Rect, Vec<String>)> = placements
+            .iter()
+            .filter_map(|placement| {
+                let (_, lines) = cues.iter().find(|(key, _)| key == &placement.key)?;
+                let box_bottom = stack_bottom - placement.offset as f32 * line_height;
+                let box_top = box_bottom - placement.height as f32 * line_height + box_gap;
+                let subtitle_rect = egui::Rect::from_min_max(
+                    egui::pos2(video_rect.center().x - subtitle_max_width / 2.0, box_top),
+                    egui::pos2(video_rect.center().x + subtitle_max_width / 2.0, box_bottom),
+                );
+                Some((subtitle_rect, lines.clone()))
+            })
+            .collect();
+        if boxes.is_empty() {
+            return None;
+        }
+        
+        // 所有字幕共用一个背景样式（按字幕整体区域的画面亮度）
+        let region = boxes.iter().fold(egui::Rect::NOTHING, |region, (rect, _)| region.union(*rect));
+        let style = backdrop(region);
+        let (background, text_color, stroke_color) = if style.light_scheme {
+            (egui::Color32::from_white_alpha(style.alpha), egui::Color32::BLACK, egui::Color32::from_white_alpha(160))
+        } else {
+            (egui::Color32::from_black_alpha(style.alpha), egui::Color32::WHITE, egui::Color32::BLACK)
+        };
+        let layout = SubtitleLayout {
+            font_size,
+            line_height,
+            stroke_width,
+            corner_radius: 6.0,
+            boxes,
+            background,
+            text_color,
+            stroke_color,
+        };
+        
+        for (subtitle_rect, lines) in &layout.boxes {
+            // 绘制半透明背景（提高可读性）
+            painter.rect_filled(
+                *subtitle_rect,
+                layout.corner_radius,
+                background,
+            );
+            
+            // 计算文本起始位置（垂直居中）
+            let start_y = subtitle_rect.center().y - (lines.len() as f32 - 1.0) * line_height / 2.0;
+            
+            for (i, line) in lines.iter().enumerate() {
+                let y_pos = start_y + i as f32 * line_height;
+                let text_pos = egui::pos2(subtitle_rect.center().x, y_pos);
+                
+                // 绘制描边（多个方向的偏移以创建描边效果）
+                for dx in [-stroke_width, 0.0, stroke_width] {
+                    for dy in [-stroke_width, 0.0, stroke_width] {
+                        if dx != 0.0 || dy != 0.0 {
+                            painter.text(
+                                text_pos + egui::vec2(dx, dy),
+                                egui::Align2::CENTER_CENTER,
+                                line,
+                                egui::FontId::proportional(font_size),
+                                stroke_color,
+                            );
+                        }
+                    }
+                }
+                
+                // 绘制文本本身
+                painter.text(
+                    text_pos,
+                    egui::Align2::CENTER_CENTER,
+                    line,
+                    egui::FontId::proportional(font_size),
+                    text_color,
+                );
+            }
+        }
+        Some(layout)
+    }
+
+    /// 渲染占位符：加载网络流时显示连接提示，空闲时返回起始页要显示的最近播放列表和筛选（由调用方绘制）；
+    /// 媒体已打开但第一帧（或封面）尚未到达时保持空白，避免起始页在第一帧之前闪现
+    fn render_placeholder(
+        &self,
+        ui: &mut Ui,
+        rect: egui::Rect,
+        manager: Option<&Player>,
+    ) -> Option<(Vec<RecentFile>, Option<RecentFilter>)> {
+        if let Some(ref url) = self.loading_url {
+            ui.allocate_ui_at_rect(rect, |ui| {
+                ui.centered_and_justified(|ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(60.0);
+                        ui.label(
+                            egui::RichText::new("⏳")
+                                .size(64.0)
+                                .color(egui::Color32::from_rgb(100, 149, 237))
+                        );
+                        ui.add_space(10.0);
+                        ui.label(
+                            egui::RichText::new("正在连接网络流...")
+                                .size(24.0)
+                                .color(egui::Color32::LIGHT_GRAY)
+                        );
+                        ui.add_space(5.0);
+                        let font = egui::FontId::proportional(14.0);
+                        ui.label(
+                            egui::RichText::new(middle_ellipsis(url, rect.width() - 40.0, &font, ui))
+                                .font(font)
+                                .color(egui::Color32::GRAY)
+                        );
+                        
+                        // 添加旋转动画
+                        ui.ctx().request_repaint();
+                    });
+                });
+            });
+            return None;
+        }
+        
+        // 视频流解码不出画面、已改为只播放音频
+        if manager.is_some_and(|manager| manager.is_audio_only()) {
+            ui.allocate_ui_at_rect(rect, |ui| {
+                ui.centered_and_justified(|ui| {
+                    ui.label(
+                        egui::RichText::new("🔈 仅播放音频")
+                            .size(24.0)
+                            .color(egui::Color32::GRAY)
+                    );
+                });
+            });
+            return None;
+        }
+        
+        // 播放器被占用时（如正在打开文件）只在从未打开过媒体时显示起始页
+        let idle = manager.map_or(self.ui_state.current_file.is_none(), |manager| manager.is_idle());
+        if !idle {
+            return None;
+        }
+        // 一次读取全部记录后筛选（不逐个文件读取）
+        let records = manager
+            .and_then(|manager| manager.position_history())
+            .map(|history| history.recent(usize::MAX))
+            .unwrap_or_default();
+        let filter = self.ui_state.recent_filter;
+        let recent: Vec<RecentFile> = records
+            .iter()
+            .map(|(path, record)| RecentFile {
+                path: path.clone(),
+                title: record.title.clone(),
+                position_ms: record.position_ms,
+                watch: record.watch_state(),
+            })
+            .filter(|file| filter.matches(file.watch))
+            .take(MAX_RECENT_FILES)
+            .collect();
+        let filter = (!records.is_empty()).then_some(filter);
+        Some((recent, filter))
+    }
+
+    /// 渲染错误信息
+    fn render_error_message(&self, ui: &mut Ui, rect: egui::Rect, message: &str) {
+        ui.allocate_ui_at_rect(rect, |ui| {
+            ui.centered_and_justified(|ui| {
                 ui.vertical_centered(|ui| {
                     ui.label(
-                        egui::RichText::new("❌")
-                            .size(48.0)
-                            .color(egui::Color32::RED)
+                        egui::RichText::new("❌")
+                            .size(48.0)
+                            .color(egui::Color32::RED)
+                    );
+                    ui.add_space(10.0);
+                    ui.label(
+                        egui::RichText::new(message)
+                            .size(16.0)
+                            .color(egui::Color32::LIGHT_RED)
+                    );
+                });
+            });
+        });
+    }
+
+    /// 渲染控制面板
+    fn render_controls_panel(&mut self, ctx: &Context) {
+        egui::TopBottomPanel::bottom("controls")
+            .resizable(false)
+            .height_range(64.0..=64.0)
+            .frame(
+                egui::Frame::none()
+                    .fill(egui::Color32::from_rgb(29, 29, 29))
+                    .stroke(egui::Stroke::new(0.0, egui::Color32::TRANSPARENT))
+            )
+            .show_separator_line(false)
+            .show(ctx, |ui| {
+                    // 时间轴（进度条）- 占据大部分宽度
+                    ui.add_space(4.0); 
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing = egui::Vec2::new(8.0, 0.0);
+                        ui.add_space(20.0); 
+                        let (duration_ms, clock_position_ms, presented_frame, is_playing, chapters, still_image, live_status, ab_loop, buffered_ms) = {
+                            let manager = self.player.read();
+                            (
+                                manager.get_duration_ms(),
+                                manager.get_position_ms(),
+                                // 无缝循环时已呈现帧的 PTS 在内部时间轴上，折回到文件内的位置
+                                manager
+                                    .get_presented_frame()
+                                    .map(|info| PresentedFrameInfo { pts: manager.loop_position(info.pts), ..info }),
+                                manager.is_playing(),
+                                manager.get_chapters().to_vec(),
+                                manager.is_still_image(),
+                                manager.live_status(),
+                                (manager.loop_range(), manager.ab_loop_pending_a()),
+                                manager.get_buffered_position_ms(),
+                            )
+                        };
+                        
+                        // 直播流（没有可回看窗口）：用 LIVE 标记和直播延迟代替进度条
+                        if let Some(status) = live_status.filter(|status| status.window.is_none()) {
+                            Self::render_live_timeline(ui, &status, clock_position_ms, self.osd_style());
+                            return;
+                        }
+                        
+                        // 可回看的直播：进度条只覆盖可回看窗口，位置相对窗口开始
+                        let live_window = live_status.and_then(|status| status.window);
+                        let timeline_offset_ms = live_window.map_or(0, |(start, _)| start);
+                        let duration_ms = live_window.map_or(duration_ms, |(start, end)| end - start);
+                        let clock_position_ms = clock_position_ms - timeline_offset_ms;
+                        let presented_frame = presented_frame.map(|info| PresentedFrameInfo { pts: info.pts - timeline_offset_ms, ..info });
+                        let duration = ms_to_secs(duration_ms);
+                        
+                        // 画面落后于时钟的时间（仅播放中且超过 1 秒才视为落后）
+                        let frame_lag = presented_frame
+                            .map(|info| (info.pts, clock_position_ms - info.pts))
+                            .filter(|&(_, lag_ms)| is_playing && lag_ms > PRESENTATION_LAG_THRESHOLD_MS);
+                        
+                        // 显示位置：默认跟随时钟，开启"进度条跟随画面"后跟随已呈现帧
+                        let position_ms = match (self.settings.progress_follows_frame, presented_frame) {
+                            (true, Some(info)) => info.pts,
+                            _ => clock_position_ms,
+                        };
+                        // 时长已知时不超过时长（播放完毕后停在末尾）
+                        let position_ms = if duration_ms > 0 { position_ms.min(duration_ms) } else { position_ms };
+                        let position = ms_to_secs(position_ms);
+                        
+                        // 当前时间标签（左侧固定宽度）
+                        let current_time_text = format_time(position_ms.max(0));
+                        let _left_label_response = ui.label(
+                            egui::RichText::new(current_time_text)
+                                .size(12.0)
+                                .color(egui::Color32::WHITE)
+                        );
+                        
+                        // 进度条 - 使用剩余所有空间
+                        let mut seek_pos = if self.ui_state.seeking {
+                            self.ui_state.seek_position
+                        } else {
+                            position
+                        };
+                        
+                        // 计算右侧标签的预估宽度
+                        let total_time_text = format_duration(duration_ms);
+                        let estimated_total_time_width = 78.0; // "HH:MM:SS" 格式
+                        
+                        // 获取当前可用宽度（已减去左侧标签）
+                        let remaining_width = ui.available_width();
+                        
+                        // 进度条应该占据大部分空间（减去右侧标签和间距）
+                        let progress_width = remaining_width - estimated_total_time_width; 
+                        
+                        // 使用 allocate_ui_with_layout 来强制分配指定宽度
+                        let progress_ui = ui.allocate_ui_with_layout(
+                            egui::Vec2::new(progress_width, 20.0),
+                           // egui::Layout::main_space_between(egui::Align::Center),
+                            egui::Layout::left_to_right(egui::Align::Center).with_main_wrap(true),
+                            |ui| {
+                                ui.style_mut().spacing.slider_width = progress_width;
+                                ui.style_mut().spacing.slider_rail_height = 2.0;
+                                // 预留缓冲范围和章节底纹的绘制位置，使其位于滑块轨道之下
+                                let buffered_shading = ui.painter().add(egui::Shape::Noop);
+                                let chapter_shading = ui.painter().add(egui::Shape::Noop);
+                                // 单张图像没有时间轴
+                                let response = control_bar::progress_slider(
+                                    ui,
+                                    &mut seek_pos,
+                                    duration,
+                                    !still_image,
+                                    control_bar::progress_label(position_ms, duration_ms),
+                                );
+                                (response, buffered_shading, chapter_shading)
+                            }
+                        );
+                        
+                        let (progress_response, buffered_shading, chapter_shading) = progress_ui.inner;
+                        
+                        // 网络流的缓冲范围：从播放位置到已缓冲的位置（较浅的颜色）
+                        if let Some(buffered_ms) = buffered_ms.filter(|_| duration_ms > 0) {
+                            let rail_rect = progress_response.rect;
+                            let rail_y = rail_rect.center().y;
+                            let buffered_x = |ms: i64| slider_x_for_fraction(rail_rect, slider_fraction(ms - timeline_offset_ms, duration_ms));
+                            ui.painter().set(
+                                buffered_shading,
+                                egui::Shape::rect_filled(
+                                    egui::Rect::from_x_y_ranges(
+                                        buffered_x(position_ms + timeline_offset_ms)..=buffered_x(buffered_ms),
+                                        (rail_y - 3.0)..=(rail_y + 3.0),
+                                    ),
+                                    1.5,
+                                    egui::Color32::from_white_alpha(60),
+                                ),
+                            );
+                            // 暂停时界面不连续刷新：至少每秒更新一次缓冲位置
+                            ui.ctx().request_repaint_after(BUFFERED_RANGE_REFRESH);
+                        }
+                        
+                        // 章节：底纹、刻度和悬停提示
+                        if !chapters.is_empty() && duration_ms > 0 {
+                            let rail_rect = progress_response.rect;
+                            let rail_y = rail_rect.center().y;
+                            let chapter_x = |ms: i64| slider_x_for_fraction(rail_rect, slider_fraction(ms, duration_ms));
+                            let hover_ms = progress_response
+                                .hover_pos()
+                                .map(|pos| (slider_fraction_for_x(rail_rect, pos.x) as f64 * duration_ms as f64) as i64);
+                            let hovered_chapter = hover_ms.and_then(|ms| chapter_at(&chapters, ms));
+                            
+                            // 交替深浅的底纹标出每个章节的范围
+                            if self.settings.chapter_shading {
+                                let shapes = chapters
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(i, chapter)| {
+                                        let alpha = if i % 2 == 0 { 24 } else { 10 };
+                                        egui::Shape::rect_filled(
+                                            egui::Rect::from_x_y_ranges(
+                                                chapter_x(chapter.start_ms)..=chapter_x(chapter.end_ms),
+                                                (rail_y - 4.0)..=(rail_y + 4.0),
+                                            ),
+                                            0.0,
+                                            egui::Color32::from_white_alpha(alpha),
+                                        )
+                                    })
+                                    .collect();
+                                ui.painter().set(chapter_shading, egui::Shape::Vec(shapes));
+                            }
+                            
+                            // 章节起点刻度（悬停所在章节的刻度高亮）
+                            for (i, chapter) in chapters.iter().enumerate().filter(|(_, c)| c.start_ms > 0) {
+                                let x = chapter_x(chapter.start_ms);
+                                let (half_height, color) = if hovered_chapter == Some(i) {
+                                    (6.0, egui::Color32::WHITE)
+                                } else {
+                                    (4.0, egui::Color32::from_gray(140))
+                                };
+                                ui.painter().line_segment(
+                                    [egui::pos2(x, rail_y - half_height), egui::pos2(x, rail_y + half_height)],
+                                    egui::Stroke::new(1.5, color),
+                                );
+                            }
+                        }
+                        
+                        // 悬停预览：悬停位置上方显示该位置的画面、章节标题和时间
+                        let hover = progress_response
+                            .hover_pos()
+                            .filter(|_| duration_ms > 0 && !still_image && !self.ui_state.seeking)
+                            .map(|pos| (pos.x, (slider_fraction_for_x(progress_response.rect, pos.x) as f64 * duration_ms as f64) as i64));
+                        match hover {
+                            Some((hover_x, ms)) => {
+                                let preview = self.player.read().hover_preview(ms + timeline_offset_ms);
+                                let hover_text = match chapter_at(&chapters, ms) {
+                                    Some(i) => format!("{}\n{}", chapters[i].title, format_time(ms)),
+                                    None => format_time(ms),
+                                };
+                                self.timeline_preview.show(ctx, hover_x, progress_response.rect.top(), preview, &hover_text);
+                            }
+                            None => self.player.read().hover_preview_leave(),
+                        }
+                        
+                        // A/B 循环：循环范围的底色和 A / B 点刻度（只标记了 A 点时只有 A 点刻度）
+                        if duration_ms > 0 {
+                            let rail_rect = progress_response.rect;
+                            let rail_y = rail_rect.center().y;
+                            let loop_x = |ms: i64| slider_x_for_fraction(rail_rect, slider_fraction(ms - timeline_offset_ms, duration_ms));
+                            let loop_color = egui::Color32::from_rgb(90, 170, 255);
+                            let points = match ab_loop {
+                                (Some((a, b)), _) => {
+                                    ui.painter().rect_filled(
+                                        egui::Rect::from_x_y_ranges(loop_x(a)..=loop_x(b), (rail_y - 3.0)..=(rail_y + 3.0)),
+                                        0.0,
+                                        loop_color.gamma_multiply(0.35),
+                                    );
+                                    vec![a, b]
+                                }
+                                (None, Some(a)) => vec![a],
+                                (None, None) => Vec::new(),
+                            };
+                            for ms in points {
+                                let x = loop_x(ms);
+                                ui.painter().line_segment(
+                                    [egui::pos2(x, rail_y - 7.0), egui::pos2(x, rail_y + 7.0)],
+                                    egui::Stroke::new(2.0, loop_color),
+                                );
+                            }
+                        }
+                        
+                        // 画面落后标记：在已呈现帧的位置绘制琥珀色标记（跟随画面时无需标记）
+                        if let Some((frame_pts, lag_ms)) = frame_lag {
+                            if !self.settings.progress_follows_frame && !self.ui_state.seeking {
+                                let marker_x = slider_x_for_fraction(progress_response.rect, slider_fraction(frame_pts, duration_ms));
+                                let marker_rect = egui::Rect::from_center_size(
+                                    egui::pos2(marker_x, progress_response.rect.center().y),
+                                    egui::Vec2::new(4.0, 10.0)
+                                );
+                                ui.painter().rect_filled(marker_rect, 1.0, egui::Color32::from_rgb(255, 191, 0));
+                                ui.interact(marker_rect, ui.id().with("frame_lag_marker"), egui::Sense::hover())
+                                    .on_hover_text(format!("画面落后 {:.1} 秒", lag_ms as f64 / 1000.0));
+                            }
+                        }
+                        
+                        // 右键菜单：进度条位置策略
+                        progress_response.context_menu(|ui| {
+                            ui.checkbox(&mut self.settings.progress_follows_frame, "进度条跟随画面");
+                            ui.checkbox(&mut self.settings.chapter_shading, "显示章节底纹");
+                        });
+                        
+                        // 在进度条上设置鼠标手势指针
+                        if progress_response.hovered() || progress_response.dragged() {
+                            ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
+                        }
+                        
+                        // 获得焦点时用方向键调整（或单击轨道）：直接 seek（拖拽的 seek 在松开时执行）
+                        if progress_response.changed() && !progress_response.dragged() && !self.ui_state.seeking {
+                            if let Err(e) = self.player.write().seek_to_seconds(seek_pos + ms_to_secs(timeline_offset_ms), SeekMode::Accurate) {
+                                error!("Seek 失败: {}", e);
+                            }
+                            self.current_frame_pts = None;
+                        }
+                        
+                        // 检测拖拽开始
+                        if progress_response.drag_started() {
+                            self.ui_state.seeking = true;
+                            self.ui_state.seek_position = seek_pos;
+                            self.ui_state.seek_executed = false;  // 重置执行标志
+                            info!("开始拖拽进度条，位置: {:.2}s", seek_pos);
+                        }
+                        
+                        // 更新拖拽中的位置
+                        if progress_response.dragged() {
+                            self.ui_state.seek_position = seek_pos;
+                        }
+                        
+                        // 检测拖拽结束（只执行一次seek）
+                        if self.ui_state.seeking && !self.ui_state.seek_executed {
+                            // 方法1: 使用 drag_stopped() （最可靠）
+                            let is_drag_stopped = progress_response.drag_stopped();
+                            // 方法2: 检查鼠标按钮是否释放
+                            let is_button_released = ctx.input(|i| i.pointer.primary_released());
+                            // 方法3: 检查是否不再拖拽且没有按下按钮
+                            let is_no_longer_dragging = !progress_response.dragged() && 
+                                                         !progress_response.is_pointer_button_down_on();
+                            
+                            if is_drag_stopped || is_button_released || is_no_longer_dragging {
+                                info!("拖拽结束，执行 seek 到: {:.2}s", self.ui_state.seek_position);
+                                let mut manager = self.player.write();
+                                // 拖动进度条：从关键帧开始播放，松开后立即出画面
+                                if let Err(e) = manager.seek_to_seconds(self.ui_state.seek_position + ms_to_secs(timeline_offset_ms), SeekMode::Fast) {
+                                    error!("Seek 失败: {}", e);
+                                } else {
+                                    info!("Seek 成功执行");
+                                    // 重置当前帧 PTS，强制获取新帧（特别是向后 seek 时）
+                                    self.current_frame_pts = None;
+                                    // 标记seek已执行，防止重复
+                                    self.ui_state.seek_executed = true;
+                                    // 记录seek完成时间，延迟500ms后重置seeking状态
+                                    // 这样进度条会继续显示目标位置，直到实际帧到达
+                                    self.ui_state.seek_complete_time = Some(Instant::now());
+                                }
+                            }
+                        }
+                        
+                        // 自动重置seeking状态：解封装线程完成 seek 后再等 500ms（让新位置的帧到达），
+                        // 失败或不支持时立即回到实际位置；网络流 seek 较慢，最多等待 SEEK_RESULT_TIMEOUT
+                        if let Some(seek_time) = self.ui_state.seek_complete_time {
+                            let outcome = self.player.read().get_last_seek_result().map(|result| result.outcome);
+                            let done = match &outcome {
+                                Some(SeekOutcome::Pending) => seek_time.elapsed() > SEEK_RESULT_TIMEOUT,
+                                Some(outcome) if outcome.is_failure() => true,
+                                _ => seek_time.elapsed() > Duration::from_millis(500),
+                            };
+                            if done {
+                                self.ui_state.seeking = false;
+                                self.ui_state.seek_complete_time = None;
+                                self.ui_state.seek_executed = false;
+                                debug!("Seek 状态已自动重置（{:?}）", outcome);
+                            } else {
+                                ctx.request_repaint_after(Duration::from_millis(100));
+                            }
+                        }
+                        
+                        // 总时长标签（右侧）
+                        // ui.label(
+                        //     egui::RichText::new(total_time_text)
+                        //         .size(12.0)
+                        //         .color(egui::Color32::WHITE)
+                        // );
+
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.add_space(20.0); // 右侧margin 20px
+                            match live_status {
+                                // 可回看的直播：右侧显示 LIVE 标记（落后直播时变灰）
+                                Some(status) => {
+                                    let position_ms = clock_position_ms + timeline_offset_ms;
+                                    live_badge(ui, status.is_at_edge(position_ms), self.osd_style());
+                                    // 落后直播边缘时显示时移量
+                                    if let Some(latency_ms) = status.latency_ms(position_ms).filter(|_| !status.is_at_edge(position_ms)) {
+                                        ui.label(
+                                            egui::RichText::new(format!("-{}", format_time(latency_ms)))
+                                                .size(12.0)
+                                                .color(egui::Color32::GRAY)
+                                        );
+                                    }
+                                }
+                                None => {
+                                    ui.label(
+                                        egui::RichText::new(total_time_text)
+                                            .size(12.0)
+                                            .color(egui::Color32::WHITE)
+                                    );
+                                }
+                            }
+                        });
+                    });
+
+                ui.vertical(|ui| {
+                    ui.add_space(2.0);
+                    // 第一行：控制按钮和音量（水平居中，垂直对齐）
+                    ui.horizontal(|ui| {
+                        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().item_spacing = egui::Vec2::new(12.0, 0.0);
+                                ui.add_space(16.0);
+                                
+                                // 控制按钮（可用 Tab 切换焦点，Enter / 空格触发）
+                                let state = {
+                                    let manager = self.player.read();
+                                    ControlBarState {
+                                        has_media: self.ui_state.current_file.is_some(),
+                                        is_playing: manager.is_playing(),
+                                        is_live: manager.is_live(),
+                                        has_previous: manager.playlist().previous_index().is_some(),
+                                        has_next: manager.playlist().next_index().is_some(),
+                                    }
+                                };
+                                match control_bar::show_buttons(ui, &mut self.icons, &state) {
+                                    Some(ControlButton::OpenFile) => self.open_file_dialog(),
+                                    Some(ControlButton::OpenUrl) => {
+                                        info!("🌐 网络流按钮被点击");
+                                        self.ui_state.show_url_dialog = true;
+                                    }
+                                    Some(ControlButton::PlayPause) => self.dispatch_action(ctx, PlayerAction::PlayPause),
+                                    Some(ControlButton::JumpToLive) => self.dispatch_action(ctx, PlayerAction::JumpToLive),
+                                    Some(ControlButton::Previous) => self.dispatch_action(ctx, PlayerAction::PlayPrevious),
+                                    Some(ControlButton::Next) => self.dispatch_action(ctx, PlayerAction::PlayNext),
+                                    Some(ControlButton::Stop) => self.stop_playback(),
+                                    None => {}
+                                }
+                                
+                                // 最近打开
+                                match control_bar::recent_menu(ui, &self.settings.recent_files.available()) {
+                                    Some(RecentMenuAction::Open(path)) => self.open_recent(path),
+                                    Some(RecentMenuAction::Clear) => {
+                                        self.settings.recent_files.clear();
+                                        self.show_osd("已清空最近打开");
+                                    }
+                                    None => {}
+                                }
+                                
+                                // 章节列表（没有章节时不显示）
+                                let (chapters, position_ms) = {
+                                    let manager = self.player.read();
+                                    (manager.get_chapters().to_vec(), manager.get_position_ms())
+                                };
+                                if !chapters.is_empty() {
+                                    if let Some(index) = control_bar::chapter_menu(ui, &chapters, chapter_at(&chapters, position_ms)) {
+                                        self.player.write().seek_with(chapters[index].start_ms, SeekMode::Accurate);
+                                        self.show_chapter_osd(&chapters, index);
+                                    }
+                                }
+                                
+                                // 音量控制（静音按钮 + 音量滑块）
+                                let muted = self.player.read().is_muted();
+                                if control_bar::mute_button(ui, &mut self.icons, muted).clicked() {
+                                    self.dispatch_action(ctx, PlayerAction::ToggleMute);
+                                }
+                                let volume_slider_response = ui.scope(|ui| {
+                                    ui.style_mut().spacing.slider_rail_height = 2.0;
+                                    control_bar::volume_slider(ui, &mut self.ui_state.volume)
+                                });
+                                let volume_response = volume_slider_response.inner;
+                                // 超过 100% 的部分用警示色标出
+                                if is_boosted(self.ui_state.volume) {
+                                    let rect = volume_response.rect;
+                                    let boost_rect = egui::Rect::from_x_y_ranges(
+                                        slider_x_for_fraction(rect, 1.0 / MAX_VOLUME)
+                                            ..=slider_x_for_fraction(rect, self.ui_state.volume / MAX_VOLUME),
+                                        rect.center().y - 1.5..=rect.center().y + 1.5,
+                                    );
+                                    ui.painter().rect_filled(boost_rect, 1.0, VOLUME_BOOST_COLOR);
+                                }
+                                // 100% 刻度（右侧为增益放大区间）
+                                {
+                                    let rect = volume_response.rect;
+                                    let x = slider_x_for_fraction(rect, 1.0 / MAX_VOLUME);
+                                    ui.painter().line_segment(
+                                        [egui::pos2(x, rect.center().y - 4.0), egui::pos2(x, rect.center().y + 4.0)],
+                                        egui::Stroke::new(1.0, egui::Color32::from_gray(150)),
+                                    );
+                                }
+                                // 启动渐入期间在轨道上显示实际音量
+                                if let Some(progress) = self.volume_ramp.progress(Instant::now()) {
+                                    let rect = volume_response.rect;
+                                    let level = self.ui_state.volume * progress / MAX_VOLUME;
+                                    let ramp_rect = egui::Rect::from_x_y_ranges(
+                                        slider_x_for_fraction(rect, 0.0)..=slider_x_for_fraction(rect, level),
+                                        rect.center().y - 1.5..=rect.center().y + 1.5,
+                                    );
+                                    ui.painter().rect_filled(ramp_rect, 1.0, VOLUME_RAMP_COLOR);
+                                }
+                                // 在音量滑块上设置鼠标手势指针
+                                if volume_response.hovered() || volume_response.dragged() {
+                                    ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
+                                }
+                                // 检测音量变化，同步到播放器
+                                if volume_response.changed() || volume_response.dragged() {
+                                    // 用户调整音量时立即结束渐入；静音时自动取消静音
+                                    self.volume_ramp.cancel();
+                                    if let Some(manager) = self.player.try_read() {
+                                        manager.set_volume(self.ui_state.volume);
+                                        if manager.is_muted() {
+                                            manager.toggle_mute();
+                                        }
+                                    }
+                                }
+                                if volume_response.changed() {
+                                    self.show_osd(format!("音量: {}", format_volume(self.ui_state.volume)));
+                                }
+                                // 调整结束后记住该文件的音量
+                                if volume_response.drag_stopped() || (volume_response.changed() && !volume_response.dragged()) {
+                                    self.player.write().remember_volume(self.ui_state.volume);
+                                }
+                                // 右键菜单：默认音量设置
+                                volume_response.context_menu(|ui| {
+                                    ui.checkbox(&mut self.settings.restore_default_volume, "恢复默认音量");
+                                    if ui
+                                        .checkbox(&mut self.settings.loudness_normalization, "响度均衡")
+                                        .on_hover_text(LOUDNESS_NORMALIZATION_HINT)
+                                        .changed()
+                                    {
+                                        self.player.write().set_loudness_normalization(self.settings.loudness_normalization);
+                                    }
+                                    let label = format!("设为默认音量（当前默认 {}）", format_volume(self.settings.default_volume));
+                                    if ui.button(label).clicked() {
+                                        self.settings.default_volume = self.ui_state.volume;
+                                        ui.close_menu();
+                                    }
+                                });
+                                ui.label(
+                                    egui::RichText::new(format!("{:.0}%", self.ui_state.volume * 100.0))
+                                        .size(12.0)
+                                        .color(egui::Color32::WHITE)
+                                );
+                                if let Some(boost) = format_boost(self.ui_state.volume) {
+                                    ui.label(
+                                        egui::RichText::new(boost)
+                                            .size(11.0)
+                                            .color(VOLUME_BOOST_COLOR)
+                                    );
+                                }
+                            });
+                        });
+                        
+                        // 全屏提示文本（最右边，距离窗口边缘20px）
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.add_space(40.0); // 右侧margin 20px
+                            let fullscreen = self.is_fullscreen(ctx);
+                            if control_bar::fullscreen_button(ui, &mut self.icons, fullscreen).clicked() {
+                                self.dispatch_action(ctx, PlayerAction::ToggleFullscreen);
+                            }
+                            if control_bar::settings_button(ui, &mut self.icons).clicked() {
+                                self.dispatch_action(ctx, PlayerAction::ToggleSettings);
+                            }
+                            let has_picture = self.video_renderer.as_ref().is_some_and(|renderer| renderer.has_texture());
+                            if control_bar::snapshot_button(ui, &mut self.icons, has_picture).clicked() {
+                                self.dispatch_action(ctx, PlayerAction::Snapshot { with_subtitles: false });
+                            }
+                            self.show_skip_ranges_button(ui);
+                            if control_bar::loop_button(ui, &mut self.icons, self.settings.repeat_one).clicked() {
+                                self.dispatch_action(ctx, PlayerAction::ToggleRepeatOne);
+                            }
+                            let (subtitle_active, has_subtitles) = {
+                                let manager = self.player.read();
+                                (manager.current_subtitle_track().is_some(), !manager.get_subtitle_tracks().is_empty())
+                            };
+                            if control_bar::subtitle_button(ui, &mut self.icons, subtitle_active, has_subtitles).clicked() {
+                                self.dispatch_action(ctx, PlayerAction::CycleSubtitleTrack);
+                            }
+                            let unavailable = self.filmstrip_unavailable_reason();
+                            if control_bar::filmstrip_button(ui, &mut self.icons, self.filmstrip.is_visible(), unavailable).clicked() {
+                                self.dispatch_action(ctx, PlayerAction::ToggleFilmstrip);
+                            }
+                            // 播放速度（直播时禁用）
+                            let is_live = {
+                                let manager = self.player.read();
+                                self.ui_state.playback_speed = manager.speed();
+                                manager.is_live()
+                            };
+                            if control_bar::speed_selector(ui, &mut self.ui_state.playback_speed, !is_live) {
+                                self.player.write().set_speed(self.ui_state.playback_speed);
+                                self.show_osd(format!("播放速度: {}", control_bar::speed_label(self.ui_state.playback_speed)));
+                            }
+                            ui.add_space(12.0);
+                            ui.label(
+                                egui::RichText::new("F11: 全屏/ESC: 退出全屏")
+                                    .size(11.0)
+                                    .color(egui::Color32::from_rgb(69, 69, 69)) // 使用灰色作为提示文本
+                            );
+                        });
+                    });
+                    
+                    ui.add_space(12.0);
+                });
+            });
+    }
+
+    /// 渲染设置抽屉，应用修改并在停止修改后自动保存
+    fn render_settings_drawer(&mut self, ctx: &Context) {
+        let changes = self.settings_drawer.show(ctx, &mut self.settings, self.sync_tuning);
+        if changes.playback {
+            Player::set_max_frame_dimension(self.settings.max_frame_dimension);
+            self.player.write().set_auto_forced_subtitles(self.settings.auto_forced_subtitles);
+            self.player.write().set_subtitles_by_default(self.settings.subtitles_enabled);
+            self.player.write().set_default_subtitle_offset_ms(self.settings.subtitle_offset_ms);
+            self.player.write().set_read_sidecar_titles(self.settings.read_sidecar_titles);
+            self.player.write().set_hw_decode_always_retry(self.settings.hw_decode_always_retry);
+            self.player.write().set_first_frame_deadline(self.first_frame_deadline());
+            self.player.write().set_loudness_normalization(self.settings.loudness_normalization);
+            self.apply_audio_device();
+            self.apply_timeshift();
+            self.apply_loop_settings(&mut self.player.write());
+        }
+        if changes.watch_folder {
+            self.apply_watch_folder_settings();
+        }
+        if changes.keymap {
+            self.keymap = Keymap::from_config(self.settings.key_bindings.as_ref());
+        }
+        if changes.osd_preview {
+            // 预览：按新的大小和位置显示一条提示
+            self.show_osd(format!("屏幕提示大小 {:.0}%", self.osd_style().scale() * 100.0));
+        }
+        if changes.sync_tuning {
+            // 下一帧按新的覆盖重新计算（并记录日志）
+            self.sync_tuning_fps = None;
+        }
+        if changes.rebuild_pipeline {
+            let hw_decode = safe_mode::hw_decode_allowed(&self.settings, self.runtime_flags);
+            Player::set_hw_decode_enabled(hw_decode);
+            info!("⚙️ 硬件解码: {}", if hw_decode { "开启" } else { "关闭" });
+            let result = self.player.write().rebuild_pipeline();
+            if let Err(e) = result {
+                error!("重建播放管线失败: {}", e);
+                self.show_osd(format!("应用设置失败: {}", e));
+            }
+        }
+        
+        self.remember_window_geometry(ctx);
+        self.remember_playback_state();
+        if let Some(settings) = self.settings_autosave.poll(&self.settings, Instant::now()) {
+            match save_settings(&settings_file(), &settings) {
+                Ok(()) => debug!("💾 设置已保存"),
+                Err(e) => error!("保存设置失败: {}", e),
+            }
+        }
+        // 等待自动保存期间保持刷新（画面静止时也能按时保存）
+        if self.settings_autosave.is_pending() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+    }
+    
+    /// 记录当前的音量、静音状态和播放速度（随设置保存，下次启动时恢复）
+    fn remember_playback_state(&mut self) {
+        self.settings.volume = self.ui_state.volume;
+        self.settings.playback_speed = self.ui_state.playback_speed;
+        if let Some(manager) = self.player.try_read() {
+            self.settings.muted = manager.is_muted();
+        }
+    }
+    
+    /// 记录窗口尺寸和最大化状态（全屏时不记录，最大化时保留还原后的尺寸）
+    fn remember_window_geometry(&mut self, ctx: &Context) {
+        let (inner_rect, maximized, fullscreen) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (viewport.inner_rect, viewport.maximized.unwrap_or(false), viewport.fullscreen.unwrap_or(false))
+        });
+        if fullscreen {
+            return;
+        }
+        self.settings.window_maximized = maximized;
+        if let (false, Some(rect)) = (maximized, inner_rect) {
+            self.settings.window_size = [rect.width().round(), rect.height().round()];
+        }
+    }
+    
+    /// 渲染信息面板
+    fn render_info_panel(&mut self, ctx: &Context) {
+        // 只在可见时才渲染
+        if !self.ui_state.info_panel_visible {
+            return;
+        }
+        
+        let mut packet_panel_changed = false;
+        let mut simulate_gpu_loss = false;
+        let mut export_requested = false;
+        let mut import_requested = false;
+        // 信息面板属于叠加层，随屏幕提示一起缩放
+        let info_font = self.osd_style().size(12.0);
+        
+        egui::Window::new("Media Info")
+            .anchor(egui::Align2::LEFT_TOP, egui::Vec2::new(10.0, 10.0))
+            .resizable(false)
+            .collapsible(true)
+            .default_open(false)
+            .frame(egui::Frame::window(&ctx.style()).fill(egui::Color32::from_black_alpha(200)))
+            .show(ctx, |ui| {
+                ui.vertical(|ui| {
+                    if let Some(file) = &self.ui_state.current_file {
+                        // 只显示文件名，避免路径中的中文字符乱码
+                        let file_name = std::path::Path::new(file)
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or(file);
+                        ui.label(
+                            egui::RichText::new(format!("File: {}", file_name))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                        );
+                    }
+                    
+                    let manager = self.player.read();
+                    // 友好标题（文件名仍在上面显示）
+                    if let Some(title) = manager.media_title() {
+                        ui.label(
+                            egui::RichText::new(format!("Title: {}", title))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                        );
+                    }
+                    if let Some(info) = manager.get_media_info() {
+                        // 纯音频源不显示分辨率（封面尺寸不是视频尺寸）
+                        if info.width > 0 && info.height > 0 {
+                            // 非方形像素或旋转时同时显示画面的显示尺寸
+                            let (display_width, display_height) = info.display_size();
+                            let (display_width, display_height) = (display_width.round() as u32, display_height.round() as u32);
+                            let resolution = if (display_width, display_height) == (info.width, info.height) {
+                                format!("Resolution: {}x{}", info.width, info.height)
+                            } else {
+                                format!("Resolution: {}x{} (display {}x{})", info.width, info.height, display_width, display_height)
+                            };
+                            ui.label(
+                                egui::RichText::new(resolution)
+                                    .size(info_font)
+                                    .color(egui::Color32::WHITE)
+                            );
+                        }
+                        ui.label(
+                            egui::RichText::new(format!("Duration: {}", format_duration(info.duration)))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                        );
+                        if manager.repeat_one() {
+                            ui.label(
+                                egui::RichText::new(format!("Loops: {}", manager.loop_count()))
+                                    .size(info_font)
+                                    .color(egui::Color32::WHITE)
+                            );
+                        }
+                        ui.label(
+                            egui::RichText::new(format!("Video: {}", info.video_codec))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                        );
+                        if !info.color_space.is_empty() {
+                            ui.label(
+                                egui::RichText::new(format!("Color: {}", info.color_space))
+                                    .size(info_font)
+                                    .color(egui::Color32::WHITE)
+                            );
+                        }
+                        let decoded = manager.decoder_stats();
+                        if decoded.hardware_frames + decoded.software_frames > 0 {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Decoded: {} HW / {} SW",
+                                    decoded.hardware_frames, decoded.software_frames
+                                ))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                            );
+                        }
+                        let buffers = manager.frame_pool_stats();
+                        if buffers.allocated > 0 {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Frame Buffers: {} reused / {} allocated",
+                                    buffers.reused, buffers.allocated
+                                ))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                            );
+                        }
+                        let subtitle_cues = manager.subtitle_cue_count();
+                        if subtitle_cues > 0 {
+                            ui.label(
+                                egui::RichText::new(format!("Subtitle Cues: {}", subtitle_cues))
+                                    .size(info_font)
+                                    .color(egui::Color32::WHITE)
+                            );
+                        }
+                        // 硬件解码回退到软件解码的原因（一直显示到重新打开文件）
+                        if let Some(reason) = manager.hw_fallback_reason() {
+                            ui.label(
+                                egui::RichText::new(reason.label())
+                                    .size(info_font * 0.9)
+                                    .color(egui::Color32::BLACK)
+                                    .background_color(HW_FALLBACK_CHIP_COLOR)
+                            );
+                        }
+                        ui.label(
+                            egui::RichText::new(format!("Audio: {}", info.audio_codec))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                        );
+                    }
+                    
+                    ui.separator();
+                    ui.label(
+                        egui::RichText::new(format!("FPS: {:.1}", self.perf_stats.fps))
+                            .size(info_font)
+                            .color(egui::Color32::WHITE)
                     );
-                    ui.add_space(10.0);
                     ui.label(
-                        egui::RichText::new(message)
-                            .size(16.0)
-                            .color(egui::Color32::LIGHT_RED)
+                        egui::RichText::new(format!("Frame Time: {:.1}ms", self.perf_stats.frame_time.as_secs_f32() * 1000.0))
+                            .size(info_font)
+                            .color(egui::Color32::WHITE)
+                    );
+                    if self.perf_stats.merged_frames > 0 {
+                        ui.label(
+                            egui::RichText::new(format!("Merged Frames: {}", self.perf_stats.merged_frames))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                        );
+                    }
+                    // 视频帧乱序校正次数（open GOP / B 帧重排序）
+                    let reorder_corrections = self.player.try_read().map_or(0, |manager| manager.reorder_corrections());
+                    if reorder_corrections > 0 {
+                        ui.label(
+                            egui::RichText::new(format!("Reordered Frames: {}", reorder_corrections))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                        );
+                    }
+                    // 解封装预读量（网络流按媒体时间限制，超出窗口时暂停下载）
+                    if let Some(read_ahead_ms) = self.player.try_read().and_then(|manager| manager.read_ahead_ms()) {
+                        ui.label(
+                            egui::RichText::new(format!("Read-ahead: {:.1}s", read_ahead_ms as f64 / 1000.0))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                        );
+                    }
+                    ui.label(
+                        egui::RichText::new(format!("Sync: {}", self.sync_tuning.summary()))
+                            .size(info_font)
+                            .color(egui::Color32::WHITE)
+                    );
+                    // 音频设备时钟偏差（测量值 / 播放时钟当前的修正量）
+                    if let Some(skew) = self.player.try_read().and_then(|manager| manager.audio_clock_skew()) {
+                        let measured = skew.measured_ppm.map_or("measuring".to_string(), |ppm| format!("{:+.0} ppm", ppm));
+                        ui.label(
+                            egui::RichText::new(format!("Audio Clock Skew: {} (applied {:+.0} ppm)", measured, skew.applied_ppm))
+                                .size(info_font)
+                                .color(egui::Color32::WHITE)
+                        )
+                        .on_hover_text("音频设备实际采样率与标称值的偏差，播放时钟按此修正，避免长时间播放后音画逐渐错位");
+                    }
+                    // 播放时钟与正在播放的音频之间的偏差（超过阈值时时钟对齐到音频）
+                    if let Some(offset_ms) = self.player.try_read().and_then(|manager| manager.av_offset_ms()) {
+                        ui.label(
+                            egui::RichText::new(format!("A/V Offset: {:+} ms", offset_ms))
+                                .size(info_font)
+                                .color(if offset_ms.abs() > RESYNC_THRESHOLD_MS { egui::Color32::from_rgb(255, 200, 80) } else { egui::Color32::WHITE })
+                        )
+                        .on_hover_text("画面时钟减去实际播放到的音频位置（正值表示画面领先声音），超出阈值时时钟自动对齐到音频");
+                    }
+                    // 最近一分钟的 FFmpeg 警告数：持续增长通常说明文件本身有损坏
+                    let ffmpeg_warnings = Player::ffmpeg_warnings_last_minute();
+                    ui.label(
+                        egui::RichText::new(format!("FFmpeg Warnings (1 min): {}", ffmpeg_warnings))
+                            .size(info_font)
+                            .color(if ffmpeg_warnings > 0 { egui::Color32::from_rgb(255, 200, 80) } else { egui::Color32::WHITE })
+                    )
+                    .on_hover_text("FFmpeg 报告的解码/解封装警告，详见日志");
+                    
+                    // ========== 配置迁移 ==========
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.small_button("导出配置…").clicked() {
+                            export_requested = true;
+                        }
+                        if ui.small_button("导入配置…").clicked() {
+                            import_requested = true;
+                        }
+                    });
+                    
+                    // ========== 开发者工具 ==========
+                    ui.separator();
+                    if ui.checkbox(&mut self.ui_state.packet_panel_visible, "数据包检查面板").changed() {
+                        packet_panel_changed = true;
+                    }
+                    if ui.small_button("模拟 GPU 设备丢失").clicked() {
+                        simulate_gpu_loss = true;
+                    }
+                });
+            });
+        
+        if packet_panel_changed {
+            self.apply_packet_panel_setting();
+        }
+        if simulate_gpu_loss {
+            self.dispatch_action(ctx, PlayerAction::SimulateGpuLoss);
+        }
+        if export_requested {
+            self.export_user_data();
+        }
+        if import_requested {
+            self.begin_import_user_data();
+        }
+    }
+    
+    /// 将用户设置应用到播放器等处（启动、导入配置和设置抽屉修改后调用）
+    fn apply_settings(&mut self) {
+        self.keymap = Keymap::from_config(self.settings.key_bindings.as_ref());
+        Player::set_max_frame_dimension(self.settings.max_frame_dimension);
+        Player::set_hw_decode_enabled(safe_mode::hw_decode_allowed(&self.settings, self.runtime_flags));
+        self.player.write().set_auto_forced_subtitles(self.settings.auto_forced_subtitles);
+        self.player.write().set_subtitles_by_default(self.settings.subtitles_enabled);
+        self.player.write().set_default_subtitle_offset_ms(self.settings.subtitle_offset_ms);
+        self.player.write().set_read_sidecar_titles(self.settings.read_sidecar_titles);
+        self.player.write().set_hw_decode_always_retry(self.settings.hw_decode_always_retry);
+        self.player.write().set_first_frame_deadline(self.first_frame_deadline());
+        self.player.write().set_loudness_normalization(self.settings.loudness_normalization);
+        self.apply_audio_device();
+        self.apply_timeshift();
+        self.sync_tuning_fps = None;
+        self.apply_loop_settings(&mut self.player.write());
+        self.apply_watch_folder_settings();
+    }
+    
+    /// 收集当前的用户数据（设置 + 播放记忆）
+    fn collect_user_data(&self) -> UserData {
+        UserData {
+            settings: self.settings.clone(),
+            history: self.player.read().file_memory().clone(),
+        }
+    }
+    
+    /// 导出配置到用户选择的文件
+    fn export_user_data(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("配置文件", &["json"])
+            .set_file_name("myy_player_config.json")
+            .save_file()
+        else {
+            return;
+        };
+        
+        let result = user_data::export(&self.collect_user_data())
+            .map_err(anyhow::Error::from)
+            .and_then(|json| std::fs::write(&path, json).map_err(anyhow::Error::from));
+        match result {
+            Ok(()) => {
+                info!("💾 配置已导出: {}", path.display());
+                self.show_osd("配置已导出");
+            }
+            Err(e) => {
+                error!("导出配置失败: {}", e);
+                self.show_osd(format!("导出配置失败: {}", e));
+            }
+        }
+    }
+    
+    /// 选择配置文件并校验（校验通过后弹出确认对话框，此时尚未修改任何状态）
+    fn begin_import_user_data(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("配置文件", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        
+        let result = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| user_data::parse_import(&json).map_err(anyhow::Error::from));
+        match result {
+            Ok(plan) => {
+                for missing in &plan.missing_paths {
+                    warn!("⚠️ 播放记忆中的文件在本机不存在: {}", missing);
+                }
+                let job = self.jobs.register(format!("导入配置 {}", tab_title(&path.to_string_lossy())), CancelBehavior::Discard);
+                self.pending_import = Some((path, plan, job));
+            }
+            Err(e) => {
+                error!("导入配置失败: {}", e);
+                self.show_osd(format!("导入配置失败: {}", e));
+            }
+        }
+    }
+    
+    /// 渲染配置导入确认对话框（列出将被覆盖的内容）
+    fn render_import_dialog(&mut self, ctx: &Context) {
+        let Some((path, plan, job)) = &self.pending_import else {
+            return;
+        };
+        if job.is_cancelled() {
+            info!("🛑 配置导入已取消: {}", path.display());
+            self.pending_import = None;
+            return;
+        }
+        
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("导入配置")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("来源: {}", path.display()));
+                ui.separator();
+                for line in plan.summary() {
+                    ui.label(line);
+                }
+                if !plan.missing_paths.is_empty() {
+                    egui::CollapsingHeader::new("本机不存在的文件").show(ui, |ui| {
+                        egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
+                            for missing in &plan.missing_paths {
+                                ui.label(egui::RichText::new(missing).size(11.0).color(egui::Color32::YELLOW));
+                            }
+                        });
+                    });
+                }
+                ui.separator();
+                ui.label(egui::RichText::new("导入前会把当前配置备份到同一目录").size(11.0).color(egui::Color32::GRAY));
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!plan.is_empty(), egui::Button::new("导入")).clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button("取消").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+        
+        if confirmed {
+            if let Some((path, plan, job)) = self.pending_import.take() {
+                self.apply_import(&path, plan);
+                job.complete();
+            }
+        } else if cancelled {
+            self.pending_import = None;
+        }
+    }
+    
+    /// 拦截关闭请求（有后台任务时取消关闭并弹出确认）；等待或取消的任务全部结束后真正退出
+    fn handle_close_request(&mut self, ctx: &Context) {
+        if ctx.input(|i| i.viewport().close_requested()) && !self.close_confirmed && !self.jobs.is_idle() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            if self.close_prompt.is_none() {
+                info!("🚪 有 {} 个后台任务，等待确认后关闭", self.jobs.snapshot().len());
+                self.close_prompt = Some(CloseStage::Asking);
+            }
+        }
+
+        if self.close_prompt.is_some() && self.jobs.is_idle() {
+            info!("🚪 后台任务已全部结束，关闭窗口");
+            self.close_prompt = None;
+            self.close_confirmed = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+    }
+
+    /// 渲染关闭确认对话框（列出进行中的任务）
+    fn render_close_dialog(&mut self, ctx: &Context) {
+        let Some(stage) = self.close_prompt else {
+            return;
+        };
+
+        let jobs = self.jobs.snapshot();
+        let mut choice = None;
+        egui::Window::new("有正在进行的任务")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                for job in &jobs {
+                    ui.horizontal(|ui| {
+                        ui.label(&job.name);
+                        let bar = match job.progress {
+                            Some(progress) => egui::ProgressBar::new(progress).show_percentage(),
+                            None => egui::ProgressBar::new(0.0).animate(true),
+                        };
+                        ui.add(bar.desired_width(160.0));
+                        if job.state == JobState::Running {
+                            if ui.small_button("取消").clicked() {
+                                info!("🛑 取消后台任务 #{}: {}", job.id, job.name);
+                                self.jobs.cancel(job.id);
+                            }
+                        } else {
+                            let hint = match job.on_cancel {
+                                CancelBehavior::Finalize => "正在收尾…",
+                                CancelBehavior::Discard => "正在取消…",
+                            };
+                            ui.label(egui::RichText::new(hint).size(11.0).color(egui::Color32::GRAY));
+                        }
+                    });
+                }
+                ui.separator();
+                match stage {
+                    CloseStage::Asking => {
+                        ui.label("直接退出会中断这些任务并留下不完整的文件");
+                        ui.horizontal(|ui| {
+                            if ui.button("等待完成").clicked() {
+                                choice = Some(CloseStage::Waiting);
+                            }
+                            if ui.button("取消任务并退出").clicked() {
+                                choice = Some(CloseStage::Cancelling);
+                            }
+                            if ui.button("返回").clicked() {
+                                choice = Some(CloseStage::Asking);
+                            }
+                        });
+                    }
+                    CloseStage::Waiting => {
+                        ui.label("任务完成后自动退出");
+                        ui.horizontal(|ui| {
+                            if ui.button("取消任务并退出").clicked() {
+                                choice = Some(CloseStage::Cancelling);
+                            }
+                            if ui.button("返回").clicked() {
+                                choice = Some(CloseStage::Asking);
+                            }
+                        });
+                    }
+                    CloseStage::Cancelling => {
+                        ui.label("正在取消任务，收尾后自动退出");
+                    }
+                }
+            });
+
+        match choice {
+            Some(CloseStage::Waiting) => {
+                info!("⏳ 等待后台任务完成后退出");
+                for session in self.sessions.iter() {
+                    if let Some(manager) = session.manager.try_read() {
+                        manager.pause();
+                    }
+                }
+                self.close_prompt = Some(CloseStage::Waiting);
+            }
+            Some(CloseStage::Cancelling) => {
+                self.jobs.cancel_all();
+                self.close_prompt = Some(CloseStage::Cancelling);
+            }
+            Some(CloseStage::Asking) => {
+                info!("↩️ 取消关闭");
+                self.close_prompt = None;
+            }
+            None => {}
+        }
+    }
+
+    /// 渲染图像序列确认对话框（选择帧率后按序列播放，或只显示选中的图像）
+    fn render_sequence_dialog(&mut self, ctx: &Context) {
+        let Some(prompt) = &mut self.pending_sequence else {
+            return;
+        };
+        
+        let mut play_sequence = false;
+        let mut show_image = false;
+        let mut cancelled = false;
+        egui::Window::new("图像序列")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                match &prompt.pattern {
+                    Ok(pattern) => {
+                        ui.label(format!("检测到图像序列: {}", pattern.display_name()));
+                        ui.label(
+                            egui::RichText::new(format!("共 {} 帧，从第 {} 帧开始", pattern.frame_count, pattern.start_number))
+                                .size(11.0)
+                                .color(egui::Color32::GRAY),
+                        );
+                        ui.horizontal(|ui| {
+                            ui.label("帧率:");
+                            ui.add(egui::DragValue::new(&mut prompt.fps).clamp_range(1..=240).suffix(" fps"));
+                        });
+                    }
+                    Err(reason) => {
+                        ui.label(egui::RichText::new(reason).color(egui::Color32::YELLOW));
+                    }
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if prompt.pattern.is_ok() && ui.button("按序列播放").clicked() {
+                        play_sequence = true;
+                    }
+                    if prompt.image.is_some() && ui.button("只显示这张图片").clicked() {
+                        show_image = true;
+                    }
+                    if ui.button("取消").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+        
+        if !(play_sequence || show_image || cancelled) {
+            return;
+        }
+        let Some(prompt) = self.pending_sequence.take() else {
+            return;
+        };
+        let result = match (play_sequence, prompt.pattern, prompt.image) {
+            (true, Ok(pattern), _) => {
+                let display = pattern.template.to_string_lossy().to_string();
+                self.open_source(MediaSource::ImageSequence { pattern, fps: prompt.fps }, display)
+            }
+            (false, _, Some(image)) if show_image => {
+                let display = image.to_string_lossy().to_string();
+                self.open_source(MediaSource::Image(image), display)
+            }
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            error!("打开图像失败: {}", e);
+            self.show_osd(format!("打开失败: {}", e));
+        }
+    }
+    
+    /// 应用导入：先备份当前配置（备份失败则不做任何修改），再一次性替换所有分区
+    fn apply_import(&mut self, source: &Path, plan: ImportPlan) {
+        let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or("myy_player_config");
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let backup_path = source.with_file_name(format!("{}.backup-{}.json", stem, timestamp));
+        
+        let backup = user_data::export(&self.collect_user_data())
+            .map_err(anyhow::Error::from)
+            .and_then(|json| std::fs::write(&backup_path, json).map_err(anyhow::Error::from));
+        if let Err(e) = backup {
+            error!("备份当前配置失败，已取消导入: {}", e);
+            self.show_osd(format!("备份失败，已取消导入: {}", e));
+            return;
+        }
+        info!("💾 当前配置已备份: {}", backup_path.display());
+        
+        if let Some(history) = plan.history {
+            self.player.write().set_file_memory(history);
+        }
+        if let Some(settings) = plan.settings {
+            self.settings = settings.sanitized();
+            self.apply_settings();
+        }
+        
+        info!("📥 配置已导入: {}", source.display());
+        self.show_osd("配置已导入");
+    }
+    
+    /// 根据面板可见性启用/停用数据包记录（面板关闭时解封装线程不做任何记录）
+    fn apply_packet_panel_setting(&mut self) {
+        let visible = self.ui_state.packet_panel_visible;
+        self.player.read().set_developer_tools_enabled(visible);
+        if !visible {
+            self.ui_state.packet_panel_paused = false;
+            self.packet_snapshot.clear();
+        }
+        info!("🔧 数据包检查面板: {}", if visible { "开启" } else { "关闭" });
+    }
+    
+    /// 渲染数据包检查面板（每个流最近的数据包及统计）
+    fn render_packet_panel(&mut self, ctx: &Context) {
+        if !self.ui_state.packet_panel_visible {
+            return;
+        }
+        
+        // 暂停时保留冻结的记录，否则每帧刷新
+        if !self.ui_state.packet_panel_paused {
+            self.packet_snapshot = self.player.read().packet_snapshot();
+        }
+        
+        let mut open = true;
+        let mut clear_requested = false;
+        egui::Window::new("Packet Inspector")
+            .open(&mut open)
+            .default_pos(egui::pos2(10.0, 200.0))
+            .default_size(egui::vec2(520.0, 420.0))
+            .resizable(true)
+            .frame(egui::Frame::window(&ctx.style()).fill(egui::Color32::from_black_alpha(220)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let pause_label = if self.ui_state.packet_panel_paused { "▶ 继续" } else { "⏸ 暂停" };
+                    if ui.button(pause_label).clicked() {
+                        self.ui_state.packet_panel_paused = !self.ui_state.packet_panel_paused;
+                    }
+                    if ui.button("清空").clicked() {
+                        clear_requested = true;
+                    }
+                    ui.label(
+                        egui::RichText::new(format!("每个流保留最近 {} 个包，时间戳为流时间基单位", Player::PACKETS_PER_STREAM))
+                            .size(11.0)
+                            .color(egui::Color32::GRAY)
                     );
                 });
+                
+                ui.collapsing("日志级别", |ui| Self::render_log_levels(ui, &self.log_control));
+                ui.collapsing("同步调试", |ui| self.render_debug_commands(ui));
+                
+                if self.packet_snapshot.is_empty() {
+                    ui.label("暂无数据包（打开文件或播放后开始记录）");
+                    return;
+                }
+                
+                egui::ScrollArea::vertical().id_source("packet_streams").show(ui, |ui| {
+                    for stream in &self.packet_snapshot {
+                        let summary = stream.summary();
+                        egui::CollapsingHeader::new(format!("流 #{}（{} 包）", stream.stream_index, stream.packets.len()))
+                            .id_source(("packet_stream", stream.stream_index))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                let keyframe_interval = summary
+                                    .keyframe_interval
+                                    .map(|interval| format!("{:.1} 包", interval))
+                                    .unwrap_or_else(|| "—".to_string());
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{:.1} 包/秒 | 平均 {:.0} 字节 | 关键帧间隔 {}",
+                                        summary.packets_per_sec, summary.average_size, keyframe_interval
+                                    ))
+                                    .size(12.0)
+                                    .color(egui::Color32::WHITE)
+                                );
+                                Self::render_packet_table(ui, stream);
+                            });
+                    }
+                });
             });
+        
+        if clear_requested {
+            self.player.read().clear_packet_records();
+            self.packet_snapshot.clear();
+        }
+        if !open {
+            self.ui_state.packet_panel_visible = false;
+            self.apply_packet_panel_setting();
+        }
+    }
+    
+    /// 同步调试工具：手动清空帧队列、时钟跳变、强制 flush 解码器、模拟解封装停滞、逐帧解码延迟
+    fn render_debug_commands(&mut self, ui: &mut Ui) {
+        let manager = self.player.read();
+        ui.horizontal(|ui| {
+            if ui.button("清空视频队列").clicked() {
+                manager.debug_flush_video_queue();
+            }
+            if ui.button("清空音频队列").clicked() {
+                manager.debug_flush_audio_queue();
+            }
+            if ui.button("强制 flush 解码器").clicked() {
+                manager.debug_flush_decoders();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.ui_state.debug_clock_jump_ms).clamp_range(-10_000..=10_000).suffix(" ms"));
+            if ui.button("时钟跳变").clicked() {
+                manager.debug_clock_jump(self.ui_state.debug_clock_jump_ms);
+            }
+            if ui
+                .button(format!("模拟解封装停滞 {}s", Player::DEBUG_DEMUX_STALL.as_secs()))
+                .on_hover_text("解封装线程暂停读包，验证停滞检测和恢复")
+                .clicked()
+            {
+                manager.debug_stall_demuxer();
+            }
         });
+        let mut delay_ms = manager.debug_decode_delay().as_millis() as u64;
+        let slider = egui::Slider::new(&mut delay_ms, 0..=Player::MAX_DEBUG_DECODE_DELAY.as_millis() as u64)
+            .text("逐帧解码延迟")
+            .suffix(" ms");
+        if ui.add(slider).changed() {
+            manager.set_debug_decode_delay(Duration::from_millis(delay_ms));
+        }
+        ui.label(
+            egui::RichText::new("执行前后的队列深度和时钟记录在日志中")
+                .size(11.0)
+                .color(egui::Color32::GRAY)
+        );
     }
-
-    /// 渲染控制面板
-    fn render_controls_panel(&mut self, ctx: &Context) {
-        egui::TopBottomPanel::bottom("controls")
-            .resizable(false)
-            .height_range(64.0..=64.0)
-            .frame(
-                egui::Frame::none()
-                    .fill(egui::Color32::from_rgb(29, 29, 29))
-                    .stroke(egui::Stroke::new(0.0, egui::Color32::TRANSPARENT))
-            )
-            .show_separator_line(false)
-            .show(ctx, |ui| {
-                    // 时间轴（进度条）- 占据大部分宽度
-                    ui.add_space(4.0); 
-                    ui.horizontal(|ui| {
-                        ui.spacing_mut().item_spacing = egui::Vec2::new(8.0, 0.0);
-                        ui.add_space(20.0); 
-                        let (duration, position) = {
-                            let manager = self.playback_manager.read();
-                            (
-                                manager.get_duration().unwrap_or(0.0),
-                                manager.get_position().unwrap_or(0.0),
-                            )
-                        };
-                        
-                        // 当前时间标签（左侧固定宽度）
-                        let current_time_text = format_time(position);
-                        let _left_label_response = ui.label(
-                            egui::RichText::new(current_time_text)
-                                .size(12.0)
-                                .color(egui::Color32::WHITE)
-                        );
-                        
-                        // 进度条 - 使用剩余所有空间
-                        let mut seek_pos = if self.ui_state.seeking {
-                            self.ui_state.seek_position
-                        } else {
-                            position
-                        };
-                        
-                        // 计算右侧标签的预估宽度
-                        let total_time_text = format_time(duration);
-                        let estimated_total_time_width = 78.0; // "HH:MM:SS" 格式
-                        
-                        // 获取当前可用宽度（已减去左侧标签）
-                        let remaining_width = ui.available_width();
-                        
-                        // 进度条应该占据大部分空间（减去右侧标签和间距）
-                        let progress_width = remaining_width - estimated_total_time_width; 
-                        
-                        // 使用 allocate_ui_with_layout 来强制分配指定宽度
-                        let progress_ui = ui.allocate_ui_with_layout(
-                            egui::Vec2::new(progress_width, 20.0),
-                           // egui::Layout::main_space_between(egui::Align::Center),
-                            egui::Layout::left_to_right(egui::Align::Center).with_main_wrap(true),
-                            |ui| {
-                                ui.style_mut().spacing.slider_width = progress_width;
-                                ui.style_mut().spacing.slider_rail_height = 2.0;
-                                ui.add(
-                                    egui::Slider::new(&mut seek_pos, 0.0..=duration.max(1.0))
-                                        .show_value(false)
-                                        .text("")
-                                )
-                            }
-                        );
-                        
-                        let progress_response = progress_ui.inner;
-                        
-                        // 在进度条上设置鼠标手势指针
-                        if progress_response.hovered() || progress_response.dragged() {
-                            ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
-                        }
-                        
-                        // 检测拖拽开始
-                        if progress_response.drag_started() {
-                            self.ui_state.seeking = true;
-                            self.ui_state.seek_position = seek_pos;
-                            self.ui_state.seek_executed = false;  // 重置执行标志
-                            info!("开始拖拽进度条，位置: {:.2}s", seek_pos);
-                        }
-                        
-                        // 更新拖拽中的位置
-                        if progress_response.dragged() {
-                            self.ui_state.seek_position = seek_pos;
+    
+    /// 按组件调整日志级别（立即生效，无需重启）
+    fn render_log_levels(ui: &mut Ui, control: &LogControl) {
+        egui::Grid::new("log_levels").num_columns(2).show(ui, |ui| {
+            for component in LogComponent::ALL {
+                let current = control.level(component);
+                let mut level = current;
+                ui.label(component.label());
+                egui::ComboBox::from_id_source(("log_level", component.label()))
+                    .selected_text(level.to_string())
+                    .show_ui(ui, |ui| {
+                        for option in SELECTABLE_LEVELS {
+                            ui.selectable_value(&mut level, option, option.to_string());
                         }
-                        
-                        // 检测拖拽结束（只执行一次seek）
-                        if self.ui_state.seeking && !self.ui_state.seek_executed {
-                            // 方法1: 使用 drag_stopped() （最可靠）
-                            let is_drag_stopped = progress_response.drag_stopped();
-                            // 方法2: 检查鼠标按钮是否释放
-                            let is_button_released = ctx.input(|i| i.pointer.primary_released());
-                            // 方法3: 检查是否不再拖拽且没有按下按钮
-                            let is_no_longer_dragging = !progress_response.dragged() && 
-                                                         !progress_response.is_pointer_button_down_on();
-                            
-                            if is_drag_stopped || is_button_released || is_no_longer_dragging {
-                                info!("拖拽结束，执行 seek 到: {:.2}s", self.ui_state.seek_position);
-                                let mut manager = self.playback_manager.write();
-                                if let Err(e) = manager.seek_to_seconds(self.ui_state.seek_position) {
-                                    error!("Seek 失败: {}", e);
-                                } else {
-                                    info!("Seek 成功执行");
-                                    // 重置当前帧 PTS，强制获取新帧（特别是向后 seek 时）
-                                    self.current_frame_pts = None;
-                                    // 标记seek已执行，防止重复
-                                    self.ui_state.seek_executed = true;
-                                    // 记录seek完成时间，延迟500ms后重置seeking状态
-                                    // 这样进度条会继续显示目标位置，直到实际帧到达
-                                    self.ui_state.seek_complete_time = Some(Instant::now());
-                                }
-                            }
+                    });
+                if level != current {
+                    control.set_level(component, level);
+                    info!("🔧 日志级别: {} -> {}", component.label(), level);
+                }
+                ui.end_row();
+            }
+        });
+        if ui.button("恢复默认").clicked() {
+            control.reset();
+            info!("🔧 日志级别已恢复默认");
+        }
+    }
+    
+    /// 渲染单个流的数据包表格（关键帧绿色，DTS 回跳红色）
+    fn render_packet_table(ui: &mut Ui, stream: &StreamPackets) {
+        let timestamp = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_else(|| "—".to_string());
+        
+        egui::ScrollArea::vertical()
+            .id_source(("packet_table", stream.stream_index))
+            .max_height(180.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                egui::Grid::new(("packet_grid", stream.stream_index))
+                    .striped(true)
+                    .num_columns(5)
+                    .show(ui, |ui| {
+                        for header in ["到达(s)", "PTS", "DTS", "大小", "关键帧"] {
+                            ui.label(egui::RichText::new(header).size(11.0).strong());
                         }
+                        ui.end_row();
                         
-                        // 自动重置seeking状态（在seek完成500ms后）
-                        if let Some(seek_time) = self.ui_state.seek_complete_time {
-                            if seek_time.elapsed() > Duration::from_millis(500) {
-                                self.ui_state.seeking = false;
-                                self.ui_state.seek_complete_time = None;
-                                self.ui_state.seek_executed = false;
-                                debug!("Seek 状态已自动重置");
-                            }
+                        for packet in &stream.packets {
+                            let color = if packet.dts_backwards {
+                                egui::Color32::from_rgb(255, 90, 90)
+                            } else if packet.is_key {
+                                egui::Color32::from_rgb(120, 220, 120)
+                            } else {
+                                egui::Color32::LIGHT_GRAY
+                            };
+                            let cell = |ui: &mut Ui, text: String| {
+                                ui.label(egui::RichText::new(text).size(11.0).monospace().color(color));
+                            };
+                            cell(ui, format!("{:.3}", packet.arrival.as_secs_f64()));
+                            cell(ui, timestamp(packet.pts));
+                            cell(ui, timestamp(packet.dts));
+                            cell(ui, packet.size.to_string());
+                            cell(ui, if packet.is_key { "K".to_string() } else { String::new() });
+                            ui.end_row();
                         }
-                        
-                        // 总时长标签（右侧）
-                        // ui.label(
-                        //     egui::RichText::new(total_time_text)
-                        //         .size(12.0)
-                        //         .color(egui::Color32::WHITE)
-                        // );
-
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.add_space(20.0); // 右侧margin 20px
-                            ui.label(
-                                egui::RichText::new(total_time_text)
-                                    .size(12.0)
-                                    .color(egui::Color32::WHITE)
-                            );
-                        });
                     });
+            });
+    }
 
-                ui.vertical(|ui| {
-                    ui.add_space(2.0);
-                    // 第一行：控制按钮和音量（水平居中，垂直对齐）
-                    ui.horizontal(|ui| {
-                        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-                            ui.horizontal(|ui| {
-                                ui.spacing_mut().item_spacing = egui::Vec2::new(12.0, 0.0);
-                                ui.add_space(16.0);
-                                
-                                // 统一按钮尺寸常量
-                                const BUTTON_SIZE: f32 = 26.0;
-                                const ICON_SIZE: f32 = 22.0;
-                                
-                                // 打开文件按钮（文件夹图标）- 深色背景
-                                if let Some(icons) = &self.icons {
-                                    // 使用自定义绘制：先绘制深色背景，再绘制图标
-                                    let button_rect = egui::Rect::from_min_size(ui.cursor().min, egui::Vec2::new(BUTTON_SIZE, BUTTON_SIZE));
-                                    let response = ui.allocate_rect(button_rect, egui::Sense::click());
-                                    
-                                    // 设置鼠标手势指针
-                                    if response.hovered() {
-                                        ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
-                                    }
-                                    
-                                    // 绘制深色背景
-                                    ui.painter().rect_filled(
-                                        button_rect,
-                                        0.0,  // 无圆角
-                                        egui::Color32::from_rgb(29, 29, 29)
-                                    );
-                                    
-                                    // 绘制图标（居中）
-                                    let icon_rect = egui::Rect::from_center_size(
-                                        button_rect.center(),
-                                        egui::Vec2::new(18.0, 18.0)
-                                    );
-                                    ui.painter().image(
-                                        icons.open_file.id(),
-                                        icon_rect,
-                                        egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                                        egui::Color32::WHITE
-                                    );
-                                    
-                                    if response.clicked() {
-                                        if let Some(path) = rfd::FileDialog::new()
-                                            .add_filter("视频文件", &["mp4", "avi", "mkv", "mov", "wmv", "flv"])
-                                            .pick_file()
-                                        {
-                                            if let Some(path_str) = path.to_str() {
-                                                if let Err(e) = self.open_file(path_str.to_string()) {
-                                                    error!("打开文件失败: {}", e);
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                
-                                // 打开网络流按钮 - 🌐 图标
-                                {
-                                    let button_rect = egui::Rect::from_min_size(ui.cursor().min, egui::Vec2::new(BUTTON_SIZE, BUTTON_SIZE));
-                                    let response = ui.allocate_rect(button_rect, egui::Sense::click());
-                                    
-                                    // 设置鼠标手势指针
-                                    if response.hovered() {
-                                        ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
-                                    }
-                                    
-                                    // 绘制深色背景
-                                    ui.painter().rect_filled(
-                                        button_rect,
-                                        0.0,
-                                        egui::Color32::from_rgb(29, 29, 29)
-                                    );
-                                    
-                                    // 绘制 🌐 图标（使用文字）
-                                    let text_pos = button_rect.center() - egui::Vec2::new(10.0, 10.0);
-                                    ui.painter().text(
-                                        text_pos,
-                                        egui::Align2::LEFT_TOP,
-                                        "🌐",
-                                        egui::FontId::proportional(16.0),
-                                        egui::Color32::WHITE
-                                    );
-                                    
-                                    if response.clicked() {
-                                        info!("🌐 网络流按钮被点击");
-                                        self.ui_state.show_url_dialog = true;
-                                        info!("show_url_dialog 设置为: {}", self.ui_state.show_url_dialog);
-                                    }
-                                }
-                                
-                                // 播放/暂停按钮 - 深色背景
-                                let is_playing = self.playback_manager.read().is_playing();
-                                if let Some(icons) = &self.icons {
-                                    // 使用自定义绘制：先绘制深色背景，再绘制图标
-                                    let button_rect = egui::Rect::from_min_size(ui.cursor().min, egui::Vec2::new(BUTTON_SIZE, BUTTON_SIZE));
-                                    let response = ui.allocate_rect(button_rect, egui::Sense::click());
-                                    
-                                    // 设置鼠标手势指针
-                                    if response.hovered() {
-                                        ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
-                                    }
-                                    
-                                    // 绘制深色背景
-                                    ui.painter().rect_filled(
-                                        button_rect,
-                                        0.0,  // 无圆角
-                                        egui::Color32::from_rgb(29, 29, 29)
-                                    );
-                                    
-                                    // 绘制图标（居中）
-                                    let icon_handle = if is_playing { &icons.pause } else { &icons.play };
-                                    let icon_rect = egui::Rect::from_center_size(
-                                        button_rect.center(),
-                                        egui::Vec2::new(ICON_SIZE, ICON_SIZE)
-                                    );
-                                    ui.painter().image(
-                                        icon_handle.id(),
-                                        icon_rect,
-                                        egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                                        egui::Color32::WHITE
-                                    );
-                                    
-                                    if response.clicked() {
-                                        let mut manager = self.playback_manager.write();
-                                        if is_playing {
-                                            let _ = manager.pause();
-                                        } else {
-                                            if let Err(e) = manager.play() {
-                                                error!("播放失败: {}", e);
-                                            }
-                                        }
-                                    }
-                                }
-
-                                // 停止按钮 - 深色背景
-                                if let Some(icons) = &self.icons {
-                                    // 使用自定义绘制：先绘制深色背景，再绘制图标
-                                    let button_rect = egui::Rect::from_min_size(ui.cursor().min, egui::Vec2::new(BUTTON_SIZE, BUTTON_SIZE));
-                                    let response = ui.allocate_rect(button_rect, egui::Sense::click());
-                                    
-                                    // 设置鼠标手势指针
-                                    if response.hovered() {
-                                        ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
-                                    }
-                                    
-                                    // 绘制深色背景
-                                    ui.painter().rect_filled(
-                                        button_rect,
-                                        0.0,  // 无圆角
-                                        egui::Color32::from_rgb(29, 29, 29)
-                                    );
-                                    
-                                    // 绘制图标（居中）
-                                    let icon_rect = egui::Rect::from_center_size(
-                                        button_rect.center(),
-                                        egui::Vec2::new(ICON_SIZE, ICON_SIZE)
-                                    );
-                                    ui.painter().image(
-                                        icons.stop.id(),
-                                        icon_rect,
-                                        egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                                        egui::Color32::WHITE
-                                    );
-                                    
-                                    if response.clicked() {
-                                        let mut manager = self.playback_manager.write();
-                                        manager.stop();
-                                        // 停止播放：重置到开头，清空当前帧
-                                        self.current_frame_pts = None;
-                                        // 清理视频渲染器的纹理缓存
-                                        if let Some(renderer) = &mut self.video_renderer {
-                                            renderer.cleanup();
-                                        }
-                                    }
-                                }
-                                
-                                // 音量控制
-                                ui.label(
-                                    egui::RichText::new("音量:")
-                                        .size(12.0)
-                                        .color(egui::Color32::WHITE)
-                                );
-                                let volume_slider_response = ui.scope(|ui| {
-                                    ui.style_mut().spacing.slider_rail_height = 2.0;
-                                    ui.add_sized(
-                                        egui::Vec2::new(100.0, 16.0),
-                                        egui::Slider::new(&mut self.ui_state.volume, 0.0..=1.0)
-                                            .show_value(false)
-                                    )
-                                });
-                                // 在音量滑块上设置鼠标手势指针
-                                if volume_slider_response.inner.hovered() || volume_slider_response.inner.dragged() {
-                                    ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
-                                }
-                                // 检测音量变化，同步到播放管理器
-                                if volume_slider_response.inner.changed() || volume_slider_response.inner.dragged() {
-                                    if let Some(manager) = self.playback_manager.try_read() {
-                                        manager.set_volume(self.ui_state.volume);
-                                    }
-                                }
-                                ui.label(
-                                    egui::RichText::new(format!("{:.0}%", self.ui_state.volume * 100.0))
-                                        .size(12.0)
-                                        .color(egui::Color32::WHITE)
-                                );
-                            });
-                        });
-                        
-                        // 全屏提示文本（最右边，距离窗口边缘20px）
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.add_space(40.0); // 右侧margin 20px
+    /// 检测是否处于全屏模式
+    fn is_fullscreen(&self, ctx: &Context) -> bool {
+        ctx.input(|i| i.viewport().fullscreen.unwrap_or(false))
+    }
+    
+    /// 切换全屏模式
+    fn toggle_fullscreen(&mut self, ctx: &Context) {
+        let is_fullscreen = self.is_fullscreen(ctx);
+        let will_be_fullscreen = !is_fullscreen;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(will_be_fullscreen));
+        self.ui_state.is_fullscreen = will_be_fullscreen;
+        
+        // 全屏时隐藏标题栏，退出全屏时恢复
+        ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(!will_be_fullscreen));
+        
+        // 进入全屏时，初始隐藏控制面板（提升观看体验）
+        if will_be_fullscreen {
+            self.ui_state.controls_visible = false;
+            self.ui_state.controls_hide_timer = None;
+        }
+    }
+
+    /// 自动匹配刷新率：全屏播放帧率匹配的视频时切换显示器刷新率；离开全屏、关闭功能
+    /// 或换到帧率不匹配的文件时恢复原来的模式（只在不是窗口模式时生效）
+    fn update_refresh_rate(&mut self, ctx: &Context, frame: &eframe::Frame) {
+        let wanted = if safe_mode::refresh_rate_allowed(&self.settings, self.runtime_flags) && self.is_fullscreen(ctx) {
+            // 播放器被占用时（如正在打开文件）保持当前状态
+            let Some(manager) = self.player.try_read() else {
+                return;
+            };
+            manager
+                .get_media_info()
+                .filter(|info| !info.is_live)
+                .and_then(|info| ContentRate::from_fps(info.fps))
+        } else {
+            None
+        };
+        if wanted == self.refresh_rate_wanted {
+            return;
+        }
+        self.refresh_rate_wanted = wanted;
+        self.display_refresh.invalidate();
+        
+        // 先恢复原来的模式（drop 时恢复）
+        if let Some(switch) = self.refresh_rate_switch.take() {
+            info!("🖥️ 恢复显示器刷新率: {} Hz", switch.original_hz);
+        }
+        self.refresh_rate_notice = None;
+        let Some(content) = wanted else {
+            return;
+        };
+        match RefreshRateSwitch::start(frame, content) {
+            Ok(Some(switch)) => {
+                info!("🖥️ 显示器刷新率 {} Hz → {} Hz（匹配 {} fps）", switch.original_hz, switch.target_hz, content.label());
+                self.refresh_rate_notice = Some((switch.target_hz, Instant::now()));
+                self.refresh_rate_switch = Some(switch);
+            }
+            Ok(None) => info!("🖥️ 当前刷新率已匹配 {} fps，无需切换", content.label()),
+            Err(e) => warn!("⚠️ 无法自动匹配刷新率: {}", e),
+        }
+    }
+    
+    /// 渲染刷新率切换提示（部分显示器切换时会黑屏一秒，提供撤销）
+    fn render_refresh_rate_notice(&mut self, ui: &mut Ui, video_rect: egui::Rect) {
+        let Some((target_hz, shown_at)) = self.refresh_rate_notice else {
+            return;
+        };
+        if shown_at.elapsed() >= REFRESH_RATE_NOTICE_DURATION {
+            self.refresh_rate_notice = None;
+            return;
+        }
+        ui.ctx().request_repaint_after(REFRESH_RATE_NOTICE_DURATION - shown_at.elapsed());
+        
+        let style = self.osd_style();
+        let mut undo = false;
+        egui::Area::new(egui::Id::new("refresh_rate_notice"))
+            .fixed_pos(video_rect.center_top() + egui::Vec2::new(0.0, style.size(20.0)))
+            .pivot(egui::Align2::CENTER_TOP)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_black_alpha(200))
+                    .rounding(style.size(4.0))
+                    .inner_margin(egui::Margin::symmetric(style.size(12.0), style.size(8.0)))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
                             ui.label(
-                                egui::RichText::new("F11: 全屏/ESC: 退出全屏")
-                                    .size(11.0)
-                                    .color(egui::Color32::from_rgb(69, 69, 69)) // 使用灰色作为提示文本
+                                egui::RichText::new(format!("刷新率已切换为 {} Hz", target_hz))
+                                    .size(style.size(14.0))
+                                    .color(egui::Color32::WHITE)
                             );
+                            undo = ui.link(egui::RichText::new("撤销").size(style.size(14.0))).clicked();
                         });
                     });
-                    
-                    ui.add_space(12.0);
-                });
             });
+        
+        if undo {
+            // 保留 refresh_rate_wanted：同一视频不再自动切换，直到离开全屏或换到其他帧率的文件
+            self.refresh_rate_notice = None;
+            self.display_refresh.invalidate();
+            if let Some(switch) = self.refresh_rate_switch.take() {
+                info!("🖥️ 撤销刷新率切换，恢复 {} Hz", switch.original_hz);
+                self.show_osd(format!("已恢复 {} Hz", switch.original_hz));
+            }
+        }
     }
-
-    /// 渲染信息面板
-    fn render_info_panel(&self, ctx: &Context) {
-        // 只在可见时才渲染
-        if !self.ui_state.info_panel_visible {
+    
+    /// 渲染 URL 对话框（打开网络流）
+    fn render_url_dialog(&mut self, ctx: &Context) {
+        if !self.ui_state.show_url_dialog {
             return;
         }
         
-        egui::Window::new("Media Info")
-            .anchor(egui::Align2::LEFT_TOP, egui::Vec2::new(10.0, 10.0))
+        let mut should_close = false;  // 用于跟踪是否应该关闭对话框
+        let mut should_open_url = false;  // 用于跟踪是否应该打开 URL
+        
+        let window_response = egui::Window::new("打开网络流")
+            .collapsible(false)
             .resizable(false)
-            .collapsible(true)
-            .default_open(false)
-            .frame(egui::Frame::window(&ctx.style()).fill(egui::Color32::from_black_alpha(200)))
+            .default_width(500.0)
+            .pivot(egui::Align2::CENTER_CENTER)
+            .default_pos(ctx.screen_rect().center())
             .show(ctx, |ui| {
                 ui.vertical(|ui| {
-                    if let Some(file) = &self.ui_state.current_file {
-                        // 只显示文件名，避免路径中的中文字符乱码
-                        let file_name = std::path::Path::new(file)
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or(file);
-                        ui.label(
-                            egui::RichText::new(format!("File: {}", file_name))
-                                .size(12.0)
-                                .color(egui::Color32::WHITE)
-                        );
+                    ui.label(egui::RichText::new("请输入流地址：").size(14.0));
+                    ui.add_space(10.0);
+                    
+                    // URL 输入框
+                    let text_edit = egui::TextEdit::singleline(&mut self.ui_state.url_input)
+                        .hint_text("例如: rtsp://example.com/stream")
+                        .desired_width(460.0)
+                        .font(egui::TextStyle::Monospace);
+                    
+                    let response = ui.add(text_edit);
+                    
+                    // 自动聚焦到输入框（只在第一帧）
+                    response.request_focus();
+                    
+                    ui.add_space(15.0);
+                    
+                    // 协议说明（可折叠）
+                    ui.collapsing("支持的协议", |ui| {
+                        ui.add_space(5.0);
+                        ui.label("• RTSP: rtsp://example.com/stream");
+                        ui.label("• RTMP: rtmp://example.com/live/stream");
+                        ui.label("• HLS: http://example.com/stream.m3u8");
+                        ui.label("• HTTP: http://example.com/video.mp4");
+                        ui.add_space(5.0);
+                    });
+                    
+                    ui.add_space(15.0);
+                    
+                    // 按钮
+                    let mut clicked_open = false;
+                    let mut clicked_cancel = false;
+                    
+                    ui.horizontal(|ui| {
+                        if ui.button(egui::RichText::new("  打开  ").size(14.0)).clicked() 
+                            || (response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))) {
+                            clicked_open = true;
+                        }
+                        
+                        if ui.button(egui::RichText::new("  取消  ").size(14.0)).clicked() {
+                            clicked_cancel = true;
+                        }
+                    });
+                    
+                    // 检测窗口关闭按钮（X）
+                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        clicked_cancel = true;
                     }
                     
-                    let manager = self.playback_manager.read();
-                    if let Some(info) = manager.get_media_info() {
-                        ui.label(
-                            egui::RichText::new(format!("Resolution: {}x{}", info.width, info.height))
-                                .size(12.0)
-                                .color(egui::Color32::WHITE)
-                        );
-                        ui.label(
-                            egui::RichText::new(format!("Duration: {}", format_time(info.duration as f64 / 1000.0)))
-                                .size(12.0)
-                                .color(egui::Color32::WHITE)
-                        );
-                        ui.label(
-                            egui::RichText::new(format!("Video: {}", info.video_codec))
-                                .size(12.0)
-                                .color(egui::Color32::WHITE)
-                        );
-                        ui.label(
-                            egui::RichText::new(format!("Audio: {}", info.audio_codec))
-                                .size(12.0)
-                                .color(egui::Color32::WHITE)
+                    // 返回按钮状态
+                    (clicked_open, clicked_cancel)
+                })
+            });
+        
+        // 处理窗口响应
+        if let Some(inner_response) = window_response {
+            // inner_response.inner 是 Option<InnerResponse<(bool, bool)>>
+            // 需要再次解包得到 (bool, bool)
+            if let Some(vertical_response) = inner_response.inner {
+                let (clicked_open, clicked_cancel) = vertical_response.inner;
+                if clicked_open {
+                    should_open_url = true;
+                    should_close = true;
+                }
+                if clicked_cancel {
+                    should_close = true;
+                }
+            }
+        } else {
+            // 窗口被关闭（用户点击了 X 按钮）
+            should_close = true;
+        }
+        
+        // 处理 Esc 键关闭
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            should_close = true;
+        }
+        
+        // 统一关闭对话框（立即关闭，避免UI卡顿）
+        if should_close {
+            self.ui_state.show_url_dialog = false;
+        }
+        
+        // 在闭包外部执行操作（避免借用冲突）
+        // 在子线程中打开URL，避免阻塞主线程
+        if should_open_url {
+            self.open_url_async();
+        }
+    }
+    
+    /// 打开网络流（同步版本，保留用于兼容）
+    fn open_url(&mut self) {
+        if self.ui_state.url_input.trim().is_empty() {
+            warn!("URL 为空，取消打开");
+            return;
+        }
+        
+        let url = self.ui_state.url_input.trim().to_string();
+        info!("📡 尝试打开网络流: {}", url);
+        
+        // 解析 URL
+        match MediaSource::from_url(&url) {
+            Ok(source) => {
+                if let Some(mut manager) = self.player.try_write() {
+                    match manager.open_media_source(source) {
+                        Ok(media_info) => {
+                            info!("✅ 网络流打开成功: {:?}", media_info);
+                            self.settings.recent_files.push(&url);
+                            self.ui_state.current_file = Some(url);
+                            
+                            // 自动播放
+                            if let Err(e) = manager.play() {
+                                error!("❌ 自动播放失败: {}", e);
+                            }
+                        }
+                        Err(e) => {
+                            error!("❌ 网络流打开失败: {}", e);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                error!("❌ URL 解析失败: {}", e);
+            }
+        }
+    }
+    
+    /// 异步打开地址栏中的网络流
+    fn open_url_async(&mut self) {
+        if self.ui_state.url_input.trim().is_empty() {
+            warn!("URL 为空，取消打开");
+            return;
+        }
+        
+        let url = self.ui_state.url_input.trim().to_string();
+        self.open_stream_async(url);
+    }
+    
+    /// 处理解封装结束事件（播放完毕由文件队列处理；网络中断重新打开流；读取失败提示）
+    fn handle_demux_event(&mut self, event: DemuxEvent) {
+        match event {
+            DemuxEvent::Finished => info!("🏁 播放完毕"),
+            DemuxEvent::Reconnect { url, position_ms, attempt } => {
+                self.show_osd(format!("网络超时，正在重新连接（第 {} 次）", attempt));
+                self.reconnect_resume_ms = Some(position_ms);
+                self.open_stream_async(url);
+            }
+            DemuxEvent::Failed(message) => {
+                self.show_osd(format!("播放中断: {}", message));
+            }
+        }
+    }
+    
+    /// 在子线程中打开网络流，结果在 update 中通过 Player::poll_open 取回
+    fn open_stream_async(&mut self, url: String) {
+        info!("📡 异步打开网络流: {}", url);
+        
+        // 设置加载状态
+        self.loading_url = Some(url.clone());
+        
+        // URL 解析很快，在主线程中完成；耗时的 Demuxer::open 在子线程中执行
+        match MediaSource::from_url(&url) {
+            Ok(source) => self.player.write().open_async(source),
+            Err(e) => {
+                error!("❌ URL 解析失败: {}", e);
+                self.open_stream_failed(&url, e.into());
+            }
+        }
+    }
+    
+    /// 异步打开失败：清除加载状态并显示错误横幅（重新连接失败时另外提示）
+    fn open_stream_failed(&mut self, url: &str, error: PlayerError) {
+        error!("❌ 打开失败: {} - {}", url, error);
+        self.loading_url = None;
+        if self.reconnect_resume_ms.take().is_some() {
+            self.show_osd(format!("重新连接失败: {}", error));
+        }
+        self.ui_state.last_error = Some((
+            error_banner::error_message(error.category(), &format!("{}: {}", url, error)),
+            Instant::now(),
+        ));
+    }
+    
+    /// 渲染网络流状态
+    fn render_stream_status(&self, ui: &mut Ui) {
+        if let Some(manager) = self.player.try_read() {
+            if let Some(state) = manager.get_stream_state() {
+                match state {
+                    StreamState::Connecting => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(egui::RichText::new("正在连接...").color(egui::Color32::YELLOW));
+                        });
+                    }
+                    StreamState::Buffering { progress } => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(egui::RichText::new(format!("缓冲中... {:.0}%", progress * 100.0))
+                                .color(egui::Color32::YELLOW));
+                        });
+                        
+                        // 缓冲进度条
+                        ui.add(egui::ProgressBar::new(progress)
+                            .show_percentage());
+                    }
+                    StreamState::Reconnecting { attempt } => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(egui::RichText::new(format!("重新连接中... (尝试 {})", attempt))
+                                .color(egui::Color32::from_rgb(255, 165, 0)));
+                        });
+                    }
+                    StreamState::Failed { reason } => {
+                        ui.colored_label(
+                            egui::Color32::RED,
+                            format!("❌ 连接失败: {}", reason)
                         );
                     }
-                    
-                    ui.separator();
-                    ui.label(
-                        egui::RichText::new(format!("FPS: {:.1}", self.perf_stats.fps))
-                            .size(12.0)
-                            .color(egui::Color32::WHITE)
-                    );
-                    ui.label(
-                        egui::RichText::new(format!("Frame Time: {:.1}ms", self.perf_stats.frame_time.as_secs_f32() * 1000.0))
-                            .size(12.0)
-                            .color(egui::Color32::WHITE)
-                    );
-                });
-            });
+                    _ => {}
+                }
+            }
+        }
     }
 
-    /// 检测是否处于全屏模式
-    fn is_fullscreen(&self, ctx: &Context) -> bool {
-        ctx.input(|i| i.viewport().fullscreen.unwrap_or(false))
+    /// 处理键盘输入（按键映射为 PlayerAction 后统一分发）
+    fn handle_keyboard_input(&mut self, ctx: &Context) {
+        // 文本输入框获得焦点或设置页正在录制快捷键时不处理快捷键
+        if ctx.wants_keyboard_input() || self.settings_drawer.is_capturing_key() {
+            return;
+        }
+        
+        // 起始页上空格用于触发按钮
+        let start_screen = self.loading_url.is_none() && self.player.read().is_idle();
+        // 控件获得焦点时空格和方向键交给控件（触发按钮、调整滑块）
+        let widget_focused = control_bar::widget_has_focus(ctx);
+        
+        let combos: Vec<KeyCombo> = ctx.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Key { key, pressed: true, modifiers, .. } => Some(KeyCombo::from_event(*key, *modifiers)),
+                    _ => None,
+                })
+                .collect()
+        });
+        let actions: Vec<PlayerAction> = combos
+            .iter()
+            .filter(|combo| !(start_screen && combo.key == egui::Key::Space))
+            .filter(|combo| {
+                !(widget_focused
+                    && matches!(
+                        combo.key,
+                        egui::Key::Space | egui::Key::ArrowLeft | egui::Key::ArrowRight | egui::Key::ArrowUp | egui::Key::ArrowDown
+                    ))
+            })
+            .filter_map(|combo| self.keymap.action(combo).cloned())
+            .filter_map(|action| match action {
+                // 截图是否包含字幕由设置决定（带字幕截图的绑定总是包含）
+                PlayerAction::Snapshot { with_subtitles } => Some(PlayerAction::Snapshot {
+                    with_subtitles: with_subtitles || self.settings.snapshot_with_subtitles,
+                }),
+                // 按住时的重复按键不重新开始连拍
+                PlayerAction::StartBurst if self.burst.is_some() => None,
+                action => Some(action),
+            })
+            .collect();
+        
+        // 在 input 闭包外分发，避免双重锁定
+        for action in actions {
+            self.dispatch_action(ctx, action);
+        }
     }
     
-    /// 切换全屏模式
-    fn toggle_fullscreen(&mut self, ctx: &Context) {
-        let is_fullscreen = self.is_fullscreen(ctx);
-        let will_be_fullscreen = !is_fullscreen;
-        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(will_be_fullscreen));
-        self.ui_state.is_fullscreen = will_be_fullscreen;
+    /// 执行播放器动作（键盘、媒体键、IPC 等输入源共用）
+    pub fn dispatch_action(&mut self, ctx: &Context, action: PlayerAction) {
+        debug!("🎮 执行动作: {:?}", action);
         
-        // 全屏时隐藏标题栏，退出全屏时恢复
-        ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(!will_be_fullscreen));
+        match action {
+            PlayerAction::PlayPause => {
+                if self.player.read().is_live() {
+                    self.toggle_live_pause();
+                    return;
+                }
+                let mut manager = self.player.write();
+                if manager.is_playing() {
+                    manager.pause();
+                } else if let Err(e) = manager.play() {
+                    error!("播放失败: {}", e);
+                }
+            }
+            PlayerAction::SeekBack(step) => self.seek_by(-self.settings.seek_steps.step_ms(step)),
+            PlayerAction::SeekForward(step) => self.seek_by(self.settings.seek_steps.step_ms(step)),
+            PlayerAction::StepFrameForward | PlayerAction::StepFrameBackward => {
+                let manager = self.player.read();
+                // 直播流不逐帧步进（直播的暂停由 toggle_live_pause 处理）
+                if manager.is_live() {
+                    return;
+                }
+                if action == PlayerAction::StepFrameForward {
+                    manager.step_frame_forward();
+                } else {
+                    manager.step_frame_backward();
+                }
+            }
+            PlayerAction::NextKeyframe | PlayerAction::PreviousKeyframe => {
+                let target = {
+                    let manager = self.player.read();
+                    // 直播流没有关键帧索引
+                    if manager.is_live() {
+                        return;
+                    }
+                    manager.seek_keyframe(action == PlayerAction::NextKeyframe)
+                };
+                match target {
+                    Some(pts) => self.show_osd(format!("关键帧 {}", format_time(pts))),
+                    None => self.show_osd("没有可跳转的关键帧"),
+                }
+            }
+            PlayerAction::NextChapter | PlayerAction::PreviousChapter => {
+                let forward = action == PlayerAction::NextChapter;
+                let (jumped, chapters) = {
+                    let manager = self.player.read();
+                    (manager.seek_chapter(forward), manager.get_chapters().to_vec())
+                };
+                match jumped {
+                    Some(index) => self.show_chapter_osd(&chapters, index),
+                    None if chapters.is_empty() => self.show_osd("没有章节"),
+                    None if forward => self.show_osd("已是最后一章"),
+                    None => self.show_osd("已是第一章"),
+                }
+            }
+            PlayerAction::PlayNext => self.play_playlist_item(true),
+            PlayerAction::PlayPrevious => self.play_playlist_item(false),
+            PlayerAction::MarkLoopA => {
+                let mark = self.player.write().mark_ab_loop_a();
+                self.show_ab_loop_mark(mark);
+            }
+            PlayerAction::MarkLoopB => {
+                let mark = self.player.write().mark_ab_loop_b();
+                match mark {
+                    Ok(mark) => self.show_ab_loop_mark(mark),
+                    Err(PlayerError::Other(message)) => self.show_osd(message),
+                    Err(e) => self.show_osd(e.to_string()),
+                }
+            }
+            PlayerAction::ToggleRepeatOne => {
+                self.settings.repeat_one = !self.settings.repeat_one;
+                self.apply_loop_settings(&mut self.player.write());
+                self.show_osd(if self.settings.repeat_one { "单曲循环: 开" } else { "单曲循环: 关" });
+            }
+            PlayerAction::ShiftSubtitleDelay(delta_ms) => {
+                let offset_ms = self.player.read().subtitle_offset_ms() + delta_ms;
+                self.set_subtitle_delay(offset_ms);
+            }
+            PlayerAction::ResetSubtitleDelay => self.set_subtitle_delay(0),
+            PlayerAction::MarkIntroEnd => self.mark_skip_position(SkipMark::IntroFromZero),
+            PlayerAction::JumpToLive => self.jump_to_live(),
+            PlayerAction::ToggleFullscreen => {
+                self.toggle_fullscreen(ctx);
+            }
+            PlayerAction::ToggleInfo => {
+                self.ui_state.info_panel_visible = !self.ui_state.info_panel_visible;
+            }
+            PlayerAction::ToggleSettings => self.settings_drawer.toggle(),
+            PlayerAction::ToggleFilmstrip => {
+                if let Some(reason) = self.filmstrip_unavailable_reason() {
+                    self.filmstrip.close();
+                    self.show_osd(reason);
+                } else {
+                    self.filmstrip.toggle();
+                }
+            }
+            PlayerAction::Escape => {
+                // 设置抽屉或胶片视图打开时只关闭它们
+                if self.settings_drawer.close() || self.filmstrip.close() {
+                    return;
+                }
+                if self.is_fullscreen(ctx) {
+                    self.toggle_fullscreen(ctx);
+                } else {
+                    self.ui_state.info_panel_visible = false;
+                }
+            }
+            PlayerAction::VolumeUp => self.adjust_volume(VOLUME_STEP),
+            PlayerAction::VolumeDown => self.adjust_volume(-VOLUME_STEP),
+            PlayerAction::ToggleMute => {
+                let muted = self.player.read().toggle_mute();
+                if muted {
+                    self.show_osd("静音");
+                } else {
+                    self.show_osd(format!("音量: {}", format_volume(self.ui_state.volume)));
+                }
+            }
+            PlayerAction::CycleFitMode => self.set_fit_mode(self.settings.fit_mode.next()),
+            PlayerAction::CycleAudioTrack => self.cycle_audio_track(),
+            PlayerAction::CycleSubtitleTrack => self.cycle_subtitle_track(),
+            PlayerAction::SelectAudioTrack(index) => self.select_audio_track(index),
+            PlayerAction::SelectSubtitleTrack(selection) => self.select_subtitle_track(selection),
+            PlayerAction::OpenWatchedFile(path) => self.open_watched_file(path),
+            PlayerAction::Snapshot { with_subtitles } => self.take_snapshot(ctx, with_subtitles),
+            PlayerAction::StartBurst => self.start_burst(),
+            PlayerAction::SnapWindow(scale) => self.snap_window(ctx, scale),
+            PlayerAction::NewSession => self.new_session(),
+            PlayerAction::SwitchSession(index) => self.switch_session(index),
+            PlayerAction::NextSession => self.switch_session(self.sessions.next_index()),
+            PlayerAction::PreviousSession => self.switch_session(self.sessions.previous_index()),
+            PlayerAction::CloseSession(index) => {
+                self.close_session(index.unwrap_or_else(|| self.sessions.active_index()))
+            }
+            PlayerAction::SimulateGpuLoss => {
+                // 只影响画面呈现，解码与音频不受影响
+                if let Some(renderer) = &mut self.video_renderer {
+                    renderer.simulate_device_loss();
+                    self.show_osd("已模拟 GPU 设备丢失，正在重建纹理");
+                }
+            }
+        }
+    }
+    
+    /// 调整音量（快捷键、滚轮）：与拖动音量滑块相同，结束渐入、取消静音并记住该文件的音量
+    fn adjust_volume(&mut self, delta: f32) {
+        self.volume_ramp.cancel();
+        self.ui_state.volume = (self.ui_state.volume + delta).clamp(0.0, MAX_VOLUME);
+        {
+            let mut manager = self.player.write();
+            manager.set_volume(self.ui_state.volume);
+            manager.set_muted(false);
+            manager.remember_volume(self.ui_state.volume);
+        }
+        self.show_osd(format!("音量: {}", format_volume(self.ui_state.volume)));
+    }
+    
+    /// 是否有对话框打开（打开网络流、导入配置、图像序列、退出确认）
+    fn dialog_open(&self) -> bool {
+        self.ui_state.show_url_dialog
+            || self.pending_import.is_some()
+            || self.pending_sequence.is_some()
+            || self.close_prompt.is_some()
+    }
+    
+    /// 相对当前位置跳转（快捷键、滚轮，连续操作合并为一次跳转），并提示累计的偏移
+    fn seek_by(&mut self, delta_ms: i64) {
+        let Some(window) = self.seek_range() else {
+            return;
+        };
+        let (position_ms, duration_ms) = {
+            let manager = self.player.read();
+            (manager.get_position_ms(), manager.get_duration_ms())
+        };
+        // 时长未知时不做上限夹紧（避免跳回开头）；可回看的直播夹紧到窗口
+        let bounds = match window {
+            Some((start, end)) => (start, Some(end)),
+            None if duration_ms > 0 => (0, Some(duration_ms)),
+            None => (0, None),
+        };
+        if let Some(target_ms) = self.seek_accumulator.press(position_ms, delta_ms, bounds, Instant::now()) {
+            self.player.write().seek_with(target_ms, SeekMode::Accurate);
+        }
+        if let Some((offset_ms, target_ms)) = self.seek_accumulator.pending() {
+            self.show_osd(format!("{} ({})", format_offset(offset_ms), format_time(target_ms)));
+        }
+    }
+    
+    /// 当前可 seek 的范围：None 表示不能 seek（直播流没有可回看窗口，提示用户），
+    /// Some(None) 为普通媒体，Some(Some(window)) 为直播流的可回看窗口
+    fn seek_range(&mut self) -> Option<Option<(i64, i64)>> {
+        let Some(status) = self.player.read().live_status() else {
+            return Some(None);
+        };
+        if status.window.is_none() {
+            self.show_osd("直播流不支持跳转，按 End 回到直播");
+            return None;
+        }
+        Some(status.window)
+    }
+    
+    /// 直播流的暂停/继续：暂停过久（超出缓冲）时继续播放会回到直播
+    fn toggle_live_pause(&mut self) {
+        let is_playing = self.player.read().is_playing();
+        if is_playing {
+            let manager = self.player.read();
+            manager.pause();
+            let timeshifting = manager.is_timeshifting();
+            drop(manager);
+            self.ui_state.live_paused_at = Some(Instant::now());
+            if timeshifting {
+                self.show_osd("已暂停（时移录制中）");
+            }
+            return;
+        }
         
-        // 进入全屏时，初始隐藏控制面板（提升观看体验）
-        if will_be_fullscreen {
-            self.ui_state.controls_visible = false;
-            self.ui_state.controls_hide_timer = None;
+        let paused_for = self.ui_state.live_paused_at.take().map(|at| at.elapsed()).unwrap_or_default();
+        let action = self.player.read().live_resume_action(paused_for);
+        match action {
+            LiveResume::InPlace => {
+                if let Err(e) = self.player.write().play() {
+                    error!("播放失败: {}", e);
+                }
+            }
+            LiveResume::JumpToLive => {
+                info!("🔴 直播暂停 {:.1}s，超出缓冲，回到直播", paused_for.as_secs_f32());
+                self.jump_to_live();
+            }
         }
     }
-
-    /// 渲染 URL 对话框（打开网络流）
-    fn render_url_dialog(&mut self, ctx: &Context) {
-        if !self.ui_state.show_url_dialog {
+    
+    /// 回到直播：有可回看窗口时跳到窗口末端，否则重新连接
+    fn jump_to_live(&mut self) {
+        if !self.player.read().is_live() {
+            return;
+        }
+        self.ui_state.live_paused_at = None;
+        if self.player.read().jump_to_live() {
+            let mut manager = self.player.write();
+            if !manager.is_playing() {
+                if let Err(e) = manager.play() {
+                    error!("播放失败: {}", e);
+                }
+            }
+            drop(manager);
+            self.show_osd("回到直播");
+        } else if let Some(url) = self.ui_state.current_file.clone() {
+            self.show_osd("正在重新连接直播…");
+            self.open_stream_async(url);
+        }
+    }
+    
+    /// 渲染直播时间轴（LIVE 标记 + 直播延迟 + 已观看时长）
+    fn render_live_timeline(ui: &mut Ui, status: &LiveStatus, position_ms: i64, style: OsdStyle) {
+        live_badge(ui, status.is_at_edge(position_ms), style);
+        let latency_text = match status.latency_ms(position_ms) {
+            Some(latency_ms) => format!("延迟 {:.1}s", latency_ms as f64 / 1000.0),
+            None => "延迟 —".to_string(),
+        };
+        ui.label(
+            egui::RichText::new(latency_text)
+                .size(12.0)
+                .color(egui::Color32::GRAY)
+        );
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.add_space(20.0);
+            ui.label(
+                egui::RichText::new(format!("已观看 {}", format_time(status.joined_ms)))
+                    .size(12.0)
+                    .color(egui::Color32::WHITE)
+            );
+        });
+    }
+    
+    /// 新建空白会话并切换过去（已达上限时提示）
+    fn new_session(&mut self) {
+        if self.sessions.is_full() {
+            self.show_osd(format!("最多同时打开 {} 个标签页", MAX_SESSIONS));
+            return;
+        }
+        let mut manager = match Player::new() {
+            Ok(manager) => manager,
+            Err(e) => {
+                warn!("⚠️ 新建标签页失败: {}", e);
+                self.show_osd(format!("新建标签页失败: {}", e));
+                return;
+            }
+        };
+        {
+            let current = self.player.read();
+            if let Some(history) = current.position_history() {
+                manager.enable_position_history(history);
+            }
+            manager.set_developer_tools_enabled(current.developer_tools_enabled());
+            manager.set_preview_cache(current.preview_cache());
+        }
+        manager.set_auto_forced_subtitles(self.settings.auto_forced_subtitles);
+        manager.set_subtitles_by_default(self.settings.subtitles_enabled);
+        manager.set_default_subtitle_offset_ms(self.settings.subtitle_offset_ms);
+        manager.set_runtime_flags(self.runtime_flags);
+        manager.set_first_frame_deadline(self.first_frame_deadline());
+        manager.set_loudness_normalization(self.settings.loudness_normalization);
+        manager.set_timeshift(self.timeshift_limits());
+        if let Err(e) = manager.set_audio_device(self.settings.audio_device.clone()) {
+            warn!("⚠️ 音频输出设备设置失败: {}", e);
+        }
+        self.apply_loop_settings(&mut manager);
+        
+        if let Ok(index) = self.sessions.push(Session::new(Arc::new(RwLock::new(manager)))) {
+            info!("🗂️  新建标签页 {}", index + 1);
+            self.switch_session(index);
+        }
+    }
+    
+    /// 切换会话：当前会话停在正在显示的帧并挂起，目标会话从挂起位置（或同步位置）恢复
+    fn switch_session(&mut self, index: usize) {
+        if index >= self.sessions.len() || index == self.sessions.active_index() {
             return;
         }
+        let started = Instant::now();
         
-        let mut should_close = false;  // 用于跟踪是否应该关闭对话框
-        let mut should_open_url = false;  // 用于跟踪是否应该打开 URL
+        let (position_ms, was_playing) = {
+            let mut manager = self.player.write();
+            let was_playing = manager.suspend();
+            (manager.get_position_ms(), was_playing)
+        };
+        let has_media = self.ui_state.current_file.is_some();
+        let previous = self.sessions.active_mut();
+        previous.was_playing = was_playing;
+        previous.current_file = self.ui_state.current_file.take();
         
-        let window_response = egui::Window::new("打开网络流")
-            .collapsible(false)
-            .resizable(false)
-            .default_width(500.0)
-            .pivot(egui::Align2::CENTER_CENTER)
-            .default_pos(ctx.screen_rect().center())
+        self.sessions.set_active(index);
+        let session = self.sessions.active();
+        let previous_player = std::mem::replace(&mut self.player, session.manager.clone());
+        self.ui_state.current_file = session.current_file.clone();
+        let resume_playing = session.was_playing || was_playing;
+        
+        // 清理上一个会话的画面和字幕状态
+        self.current_frame_pts = None;
+        self.ui_state.seeking = false;
+        self.ui_state.seek_complete_time = None;
+        self.ui_state.seek_executed = false;
+        self.subtitle_stacker.clear();
+        if let Some(renderer) = &mut self.video_renderer {
+            renderer.cleanup();
+            self.subtitle_backdrop.reset();
+        }
+        self.reset_skip_state();
+        
+        // 同步位置：新会话跳转到旧会话停下的时间点
+        let sync_position = (self.ui_state.sync_session_position && has_media).then_some(position_ms);
+        {
+            // 只有一个音频输出设备流：从挂起的会话交给目标会话
+            let mut manager = self.player.write();
+            previous_player.write().hand_over_audio_output(&mut manager);
+            manager.set_volume(self.ui_state.volume);
+            manager.resume(sync_position, resume_playing);
+        }
+        
+        info!("🗂️  切换到标签页 {}，耗时 {}ms", index + 1, started.elapsed().as_millis());
+        let title = self.sessions.active().title();
+        match sync_position {
+            Some(position_ms) => self.show_osd(format!("{} · {}", title, format_time(position_ms))),
+            None => self.show_osd(title),
+        }
+    }
+    
+    /// 关闭会话（最后一个会话不关闭；关闭活动会话时先切换到相邻会话）
+    fn close_session(&mut self, index: usize) {
+        if self.sessions.len() <= 1 || index >= self.sessions.len() {
+            return;
+        }
+        if index == self.sessions.active_index() {
+            let neighbour = if index + 1 < self.sessions.len() { index + 1 } else { index - 1 };
+            self.switch_session(neighbour);
+        }
+        if let Some(session) = self.sessions.remove(index) {
+            info!("🗂️  关闭标签页 {}", index + 1);
+            let mut manager = session.manager.write();
+            manager.stop();
+            manager.close_position_history();
+        }
+    }
+    
+    /// 渲染会话标签栏（文件名 + 实时位置，只有一个会话时不显示）
+    fn render_session_tabs(&mut self, ctx: &Context) {
+        if self.sessions.len() < 2 {
+            return;
+        }
+        
+        let mut action = None;
+        egui::TopBottomPanel::top("session_tabs")
+            .frame(
+                egui::Frame::none()
+                    .fill(egui::Color32::from_rgb(24, 24, 24))
+                    .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+            )
+            .show_separator_line(false)
             .show(ctx, |ui| {
-                ui.vertical(|ui| {
-                    ui.label(egui::RichText::new("请输入流地址：").size(14.0));
-                    ui.add_space(10.0);
-                    
-                    // URL 输入框
-                    let text_edit = egui::TextEdit::singleline(&mut self.ui_state.url_input)
-                        .hint_text("例如: rtsp://example.com/stream")
-                        .desired_width(460.0)
-                        .font(egui::TextStyle::Monospace);
-                    
-                    let response = ui.add(text_edit);
-                    
-                    // 自动聚焦到输入框（只在第一帧）
-                    response.request_focus();
-                    
-                    ui.add_space(15.0);
-                    
-                    // 协议说明（可折叠）
-                    ui.collapsing("支持的协议", |ui| {
-                        ui.add_space(5.0);
-                        ui.label("• RTSP: rtsp://example.com/stream");
-                        ui.label("• RTMP: rtmp://example.com/live/stream");
-                        ui.label("• HLS: http://example.com/stream.m3u8");
-                        ui.label("• HTTP: http://example.com/video.mp4");
-                        ui.add_space(5.0);
-                    });
-                    
-                    ui.add_space(15.0);
-                    
-                    // 按钮
-                    let mut clicked_open = false;
-                    let mut clicked_cancel = false;
-                    
-                    ui.horizontal(|ui| {
-                        if ui.button(egui::RichText::new("  打开  ").size(14.0)).clicked() 
-                            || (response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))) {
-                            clicked_open = true;
-                        }
+                ui.horizontal(|ui| {
+                    let active_index = self.sessions.active_index();
+                    for (index, session) in self.sessions.iter().enumerate() {
+                        let active = index == active_index;
+                        let file = if active { self.ui_state.current_file.as_deref() } else { session.current_file.as_deref() };
+                        let title_font = egui::FontId::proportional(13.0);
+                        let title = file
+                            .map(|file| middle_ellipsis(&tab_title(file), TAB_TITLE_MAX_WIDTH, &title_font, ui))
+                            .unwrap_or_else(|| "空白".to_string());
+                        let position = file
+                            .and(session.manager.try_read())
+                            .map(|manager| format_time(manager.get_position_ms().max(0)));
                         
-                        if ui.button(egui::RichText::new("  取消  ").size(14.0)).clicked() {
-                            clicked_cancel = true;
+                        let mut text = egui::text::LayoutJob::default();
+                        let color = if active { egui::Color32::WHITE } else { egui::Color32::GRAY };
+                        text.append(&title, 0.0, egui::TextFormat::simple(title_font, color));
+                        if let Some(position) = position {
+                            text.append(
+                                &position,
+                                8.0,
+                                egui::TextFormat::simple(egui::FontId::monospace(11.0), egui::Color32::from_gray(140)),
+                            );
                         }
-                    });
-                    
-                    // 检测窗口关闭按钮（X）
-                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
-                        clicked_cancel = true;
+                        let response = ui.selectable_label(active, text);
+                        let response = match file {
+                            Some(file) => response.on_hover_text(file),
+                            None => response,
+                        };
+                        if response.clicked() {
+                            action = Some(PlayerAction::SwitchSession(index));
+                        }
+                        if ui.small_button("×").on_hover_text("关闭标签页 (Ctrl+W)").clicked() {
+                            action = Some(PlayerAction::CloseSession(Some(index)));
+                        }
+                        ui.add_space(6.0);
+                    }
+                    if !self.sessions.is_full() && ui.small_button("+").on_hover_text("新建标签页 (Ctrl+T)").clicked() {
+                        action = Some(PlayerAction::NewSession);
                     }
                     
-                    // 返回按钮状态
-                    (clicked_open, clicked_cancel)
-                })
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.checkbox(&mut self.ui_state.sync_session_position, "同步位置")
+                            .on_hover_text("切换标签页时跳转到相同的时间点（对比不同编码）");
+                    });
+                });
             });
         
-        // 处理窗口响应
-        if let Some(inner_response) = window_response {
-            // inner_response.inner 是 Option<InnerResponse<(bool, bool)>>
-            // 需要再次解包得到 (bool, bool)
-            if let Some(vertical_response) = inner_response.inner {
-                let (clicked_open, clicked_cancel) = vertical_response.inner;
-                if clicked_open {
-                    should_open_url = true;
-                    should_close = true;
-                }
-                if clicked_cancel {
-                    should_close = true;
+        if let Some(action) = action {
+            self.dispatch_action(ctx, action);
+        }
+    }
+    
+    /// 处理监视文件夹中新出现的文件
+    fn open_watched_file(&mut self, path: PathBuf) {
+        let path_str = path.to_string_lossy().to_string();
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path_str.clone());
+        
+        let is_idle = {
+            let manager = self.player.read();
+            self.ui_state.current_file.is_none() || manager.is_source_exhausted()
+        };
+        
+        if self.settings.watch_folder_preempt || is_idle {
+            // 抢占模式（或当前无播放）：立即切换到新文件
+            info!("👀 播放监视文件夹新文件: {}", path_str);
+            if let Err(e) = self.open_file(path_str) {
+                error!("打开文件失败: {}", e);
+                self.report_open_error(&e);
+            } else {
+                self.show_osd(format!("新文件: {}", file_name));
+            }
+        } else {
+            info!("👀 加入播放列表: {}", path_str);
+            let mut manager = self.player.write();
+            manager.append_to_playlist(path_str);
+            let remaining = manager.playlist().remaining();
+            drop(manager);
+            self.show_osd(format!("已加入队列: {} [{}]", file_name, remaining));
+        }
+    }
+    
+    /// 当前文件接近结尾时预读播放列表中的下一项，播放完毕时打开下一项（接管预读的开头）
+    fn advance_file_queue(&mut self) {
+        let due = self
+            .player
+            .try_write()
+            .map(|mut manager| {
+                manager.update_playlist_prefetch();
+                manager.playlist_advance_due()
+            })
+            .unwrap_or(false);
+        if due {
+            self.play_playlist_item(true);
+        }
+    }
+    
+    /// 打开播放列表的下一项 / 上一项（下一项到末尾且不循环时停止，上一项到开头时回到开头）
+    fn play_playlist_item(&mut self, forward: bool) {
+        let item = {
+            let mut manager = self.player.write();
+            if forward { manager.next_playlist_item() } else { manager.previous_playlist_item() }
+        };
+        match item {
+            Some(path) => {
+                info!("▶️  播放列表中的{}: {}", if forward { "下一项" } else { "上一项" }, path);
+                if let Err(e) = self.open_queue_item(path) {
+                    error!("打开文件失败: {}", e);
                 }
             }
+            None if forward => {
+                self.stop_playback();
+                self.show_osd("已是播放列表最后一项");
+            }
+            None => {
+                self.player.write().seek_with(0, SeekMode::Fast);
+                self.show_osd("已是播放列表第一项");
+            }
+        }
+    }
+    
+    /// 提示 A/B 循环标记结果
+    fn show_ab_loop_mark(&mut self, mark: AbLoopMark) {
+        match mark {
+            AbLoopMark::PointA(a) => self.show_osd(format!("A 点: {}（按 ] 标记 B 点）", format_time(a))),
+            AbLoopMark::Range(a, b) => self.show_osd(format!("A-B 循环: {} - {}", format_time(a), format_time(b))),
+            AbLoopMark::Cleared => self.show_osd("已取消 A-B 循环"),
+        }
+    }
+    
+    /// 打开最近打开菜单中的条目（网络地址按网络流打开）
+    fn open_recent(&mut self, path: String) {
+        if recent_files::is_network_address(&path) {
+            self.open_stream_async(path);
+        } else if let Err(e) = self.open_file(path) {
+            error!("打开文件失败: {}", e);
+            self.report_open_error(&e);
+        }
+    }
+    
+    /// 设置字幕延迟并在屏幕上显示当前值
+    fn set_subtitle_delay(&mut self, offset_ms: i64) {
+        let offset_ms = {
+            let mut manager = self.player.write();
+            manager.set_subtitle_offset_ms(offset_ms);
+            manager.subtitle_offset_ms()
+        };
+        if offset_ms == 0 {
+            self.show_osd("字幕延迟: 0ms");
         } else {
-            // 窗口被关闭（用户点击了 X 按钮）
-            should_close = true;
+            self.show_osd(format!("字幕延迟: {:+}ms", offset_ms));
         }
-        
-        // 处理 Esc 键关闭
-        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-            should_close = true;
+    }
+    
+    /// 停止播放：重置到开头，清空当前帧
+    fn stop_playback(&mut self) {
+        self.player.write().stop();
+        self.current_frame_pts = None;
+        // 清理视频渲染器的纹理缓存
+        if let Some(renderer) = &mut self.video_renderer {
+            renderer.cleanup();
+            self.subtitle_backdrop.reset();
         }
-        
-        // 统一关闭对话框（立即关闭，避免UI卡顿）
-        if should_close {
-            self.ui_state.show_url_dialog = false;
+    }
+    
+    /// 首帧期限（设置中的秒数）
+    fn first_frame_deadline(&self) -> Duration {
+        Duration::from_secs(self.settings.first_frame_timeout_secs as u64)
+    }
+    
+    /// 直播时移设置（未开启时为 None）
+    fn timeshift_limits(&self) -> Option<TimeshiftLimits> {
+        self.settings
+            .timeshift_enabled
+            .then(|| TimeshiftLimits::new(self.settings.timeshift_minutes, self.settings.timeshift_max_mb))
+    }
+    
+    /// 把直播时移设置应用到播放器（开关变化时重新连接正在播放的直播）
+    fn apply_timeshift(&mut self) {
+        let limits = self.timeshift_limits();
+        let (toggled, is_live) = {
+            let mut manager = self.player.write();
+            let toggled = manager.timeshift().is_some() != limits.is_some();
+            manager.set_timeshift(limits);
+            (toggled, manager.is_live())
+        };
+        if let (true, true, Some(url)) = (toggled, is_live, self.ui_state.current_file.clone()) {
+            self.show_osd(if limits.is_some() { "已开启直播时移，正在重新连接…" } else { "已关闭直播时移，正在重新连接…" });
+            self.open_stream_async(url);
         }
-        
-        // 在闭包外部执行操作（避免借用冲突）
-        // 在子线程中打开URL，避免阻塞主线程
-        if should_open_url {
-            self.open_url_async();
+    }
+    
+    /// 切换画面缩放模式（清除手动缩放和平移）并显示提示
+    fn set_fit_mode(&mut self, mode: FitMode) {
+        self.settings.fit_mode = mode;
+        if let Some(renderer) = &mut self.video_renderer {
+            renderer.set_fit_mode(mode);
         }
+        self.show_osd(format!("画面: {}", mode.label()));
+    }
+
+    /// 把音频输出设备设置应用到播放器（正在播放时立即切换）
+    fn apply_audio_device(&mut self) {
+        let result = self.player.write().set_audio_device(self.settings.audio_device.clone());
+        if let Err(e) = result {
+            warn!("⚠️ 切换音频输出设备失败: {}", e);
+            self.show_osd(format!("切换音频输出设备失败: {}", e));
+        }
+    }
+
+    /// 把单曲循环设置应用到播放器
+    fn apply_loop_settings(&self, manager: &mut Player) {
+        manager.set_repeat_one(self.settings.repeat_one);
+        manager.set_playlist_repeat(if self.settings.repeat_playlist { RepeatMode::All } else { RepeatMode::Off });
+        manager.set_seamless_loop_limit_ms(self.settings.seamless_loop_limit_secs as i64 * 1000);
     }
     
-    /// 打开网络流（同步版本，保留用于兼容）
-    fn open_url(&mut self) {
-        if self.ui_state.url_input.trim().is_empty() {
-            warn!("URL 为空，取消打开");
-            return;
+    /// 根据设置启动/停止监视文件夹
+    fn apply_watch_folder_settings(&mut self) {
+        // 先停止旧的监视
+        if let Some(mut watch_folder) = self.watch_folder.take() {
+            watch_folder.stop();
         }
         
-        let url = self.ui_state.url_input.trim().to_string();
-        info!("📡 尝试打开网络流: {}", url);
-        
-        // 解析 URL
-        match MediaSource::from_url(&url) {
-            Ok(source) => {
-                if let Some(mut manager) = self.playback_manager.try_write() {
-                    match manager.open_media_source(source) {
-                        Ok(media_info) => {
-                            info!("✅ 网络流打开成功: {:?}", media_info);
-                            self.ui_state.current_file = Some(url);
-                            
-                            // 自动播放
-                            if let Err(e) = manager.play() {
-                                error!("❌ 自动播放失败: {}", e);
-                            }
+        if let Some(folder) = safe_mode::watch_folder_target(&self.settings, self.runtime_flags).map(str::to_string) {
+            match WatchFolder::start(Path::new(&folder)) {
+                Ok(watch_folder) => {
+                    self.show_osd(format!("监视文件夹: {}", watch_folder.folder().display()));
+                    self.watch_folder = Some(watch_folder);
+                }
+                Err(e) => {
+                    error!("启动监视文件夹失败: {}", e);
+                    self.settings.watch_folder_enabled = false;
+                    self.show_osd(format!("监视文件夹失败: {}", e));
+                }
+            }
+        }
+    }
+    
+    /// 循环切换音频轨道（到末尾后回到第一条）
+    fn cycle_audio_track(&mut self) {
+        let osd_text = {
+            let mut manager = self.player.write();
+            if manager.is_switching_track() {
+                "切换中…".to_string()
+            } else {
+                let tracks = manager.get_audio_tracks().to_vec();
+                if tracks.is_empty() {
+                    "音频: 无".to_string()
+                } else {
+                    let current = manager
+                        .current_audio_stream()
+                        .and_then(|index| tracks.iter().position(|t| t.source == TrackSource::Embedded(index)))
+                        .unwrap_or(0);
+                    let next = (current + 1) % tracks.len();
+                    
+                    let mut result = Ok(());
+                    if next != current {
+                        if let TrackSource::Embedded(index) = tracks[next].source {
+                            result = manager.select_audio_track(index);
                         }
+                    }
+                    
+                    match result {
+                        Ok(()) => format!("音频: {} [{}/{}]", tracks[next].display_name(), next + 1, tracks.len()),
                         Err(e) => {
-                            error!("❌ 网络流打开失败: {}", e);
+                            error!("切换音频轨道失败: {}", e);
+                            format!("音频切换失败: {}", e)
                         }
                     }
                 }
             }
-            Err(e) => {
-                error!("❌ URL 解析失败: {}", e);
+        };
+        
+        // 管线已重建，强制获取新帧
+        self.current_frame_pts = None;
+        self.show_osd(osd_text);
+    }
+    
+    /// 选择指定音频轨道（轨道菜单）
+    fn select_audio_track(&mut self, stream_index: usize) {
+        let osd_text = {
+            let mut manager = self.player.write();
+            if manager.is_switching_track() {
+                "切换中…".to_string()
+            } else {
+                let name = manager
+                    .get_audio_tracks()
+                    .iter()
+                    .find(|track| track.source == TrackSource::Embedded(stream_index))
+                    .map(|track| track.display_name())
+                    .unwrap_or_else(|| format!("轨道 {}", stream_index));
+                match manager.select_audio_track(stream_index) {
+                    Ok(()) => format!("音频: {}", name),
+                    Err(e) => {
+                        error!("切换音频轨道失败: {}", e);
+                        format!("音频切换失败: {}", e)
+                    }
+                }
+            }
+        };
+        
+        self.current_frame_pts = None;
+        self.show_osd(osd_text);
+    }
+    
+    /// 选择指定字幕轨道（轨道菜单，None 表示关闭）
+    fn select_subtitle_track(&mut self, selection: Option<TrackSource>) {
+        let osd_text = {
+            let mut manager = self.player.write();
+            if manager.is_switching_track() {
+                "切换中…".to_string()
+            } else {
+                let name = selection.as_ref().map(|source| {
+                    manager
+                        .get_subtitle_tracks()
+                        .iter()
+                        .find(|track| &track.source == source)
+                        .map(|track| track.display_name())
+                        .unwrap_or_default()
+                });
+                match manager.select_subtitle_track(selection) {
+                    Ok(()) => match name {
+                        Some(name) => format!("字幕: {}", name),
+                        None => "字幕: 关闭".to_string(),
+                    },
+                    Err(e) => {
+                        error!("切换字幕轨道失败: {}", e);
+                        format!("字幕切换失败: {}", e)
+                    }
+                }
             }
+        };
+        
+        self.current_frame_pts = None;
+        self.show_osd(osd_text);
+    }
+    
+    /// 轨道菜单项（名称 + 强制/SDH/解说徽标，悬停显示详细元数据），返回是否被点击
+    fn track_menu_item(ui: &mut Ui, track: &TrackInfo, selected: bool) -> bool {
+        let meta = &track.meta;
+        let mut details = Vec::new();
+        if let Some(title) = &meta.title {
+            details.push(format!("标题: {}", title));
+        }
+        if let Some(language) = &meta.language {
+            details.push(format!("语言: {}", language));
         }
+        details.push(format!("编码: {}", meta.codec));
+        if meta.sample_rate > 0 {
+            details.push(format!("采样率: {} Hz，{} 声道", meta.sample_rate, meta.channels));
+        }
+        
+        ui.horizontal(|ui| {
+            let clicked = ui
+                .selectable_label(selected, track.display_name())
+                .on_hover_text(details.join("\n"))
+                .clicked();
+            for badge in track.badges() {
+                ui.label(
+                    egui::RichText::new(badge)
+                        .size(11.0)
+                        .color(egui::Color32::BLACK)
+                        .background_color(TRACK_BADGE_COLOR)
+                );
+            }
+            clicked
+        })
+        .inner
     }
     
-    /// 异步打开网络流（使用新架构 - DemuxerFactory）
-    fn open_url_async(&mut self) {
-        if self.ui_state.url_input.trim().is_empty() {
-            warn!("URL 为空，取消打开");
+    /// 截图：保存当前画面（全分辨率）；with_subtitles 时烧录当前显示的字幕，没有字幕时保存普通截图
+    fn take_snapshot(&mut self, ctx: &Context, with_subtitles: bool) {
+        let Some(renderer) = self.video_renderer.as_ref() else {
+            return;
+        };
+        let Some((frame, rotation)) = renderer.displayed_frame() else {
+            self.show_osd("没有可截图的画面");
             return;
+        };
+        let mut image = snapshot::rotate_frame(&frame, rotation);
+        let subtitles = self
+            .displayed_subtitles
+            .as_ref()
+            .filter(|_| with_subtitles)
+            .and_then(|(container, layout)| {
+                let display_rect = renderer.displayed_rect(*container)?;
+                Some(layout.to_image_space(display_rect, image.width, image.height))
+            });
+        if let Some(layout) = &subtitles {
+            snapshot::burn_subtitles(ctx, &mut image, layout);
+        } else if with_subtitles {
+            debug!("📸 当前没有显示字幕，保存普通截图");
         }
         
-        let url = self.ui_state.url_input.trim().to_string();
-        
-        info!("📡 使用新架构异步打开网络流: {}", url);
-        
-        // 设置加载状态
-        self.loading_url = Some(url.clone());
-        
-        // 使用 DemuxerFactory 异步创建 Demuxer
-        use crate::player::DemuxerFactory;
-        
-        let result_tx = self.demuxer_result_tx.clone();
-        
-        // 🔥 优化：在主线程中解析 URL（操作很快，不需要单独线程）
-        info!("🔄 主线程解析 URL: {}", url);
-        match MediaSource::from_url(&url) {
-            Ok(source) => {
-                info!("✅ URL 解析成功，在子线程中创建 Demuxer");
-                
-                // 使用 DemuxerFactory 在子线程中创建 Demuxer（这里会创建线程执行耗时的 Demuxer::open）
-                DemuxerFactory::create_async(source, result_tx);
+        let options = self.snapshot_options();
+        let name = self.snapshot_name();
+        let values = TemplateValues { name: &name, position_ms: image.pts, unix_secs: snapshot::unix_now(), n: 1 };
+        let path = snapshot::snapshot_path(&options, &values);
+        match snapshot::save(&image, &path, options.format, options.jpeg_quality) {
+            Ok(()) => {
+                info!("📸 截图已保存{}: {}", if subtitles.is_some() { "（带字幕）" } else { "" }, path.display());
+                let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                self.show_osd(format!("截图已保存: {}", name));
             }
             Err(e) => {
-                error!("❌ URL 解析失败: {}", e);
-                
-                // 发送失败结果
-                let _ = result_tx.send(crate::player::DemuxerCreationResult::Failed {
-                    url: url.clone(),
-                    error: e.to_string(),
-                });
+                error!("保存截图失败: {}", e);
+                self.show_osd("截图保存失败");
             }
         }
     }
     
-    /// 渲染网络流状态
-    fn render_stream_status(&self, ui: &mut Ui) {
-        if let Some(manager) = self.playback_manager.try_read() {
-            if let Some(state) = manager.get_stream_state() {
-                match state {
-                    StreamState::Connecting => {
-                        ui.horizontal(|ui| {
-                            ui.spinner();
-                            ui.label(egui::RichText::new("正在连接...").color(egui::Color32::YELLOW));
-                        });
-                    }
-                    StreamState::Buffering { progress } => {
-                        ui.horizontal(|ui| {
-                            ui.spinner();
-                            ui.label(egui::RichText::new(format!("缓冲中... {:.0}%", progress * 100.0))
-                                .color(egui::Color32::YELLOW));
-                        });
-                        
-                        // 缓冲进度条
-                        ui.add(egui::ProgressBar::new(progress)
-                            .show_percentage());
-                    }
-                    StreamState::Reconnecting { attempt } => {
-                        ui.horizontal(|ui| {
-                            ui.spinner();
-                            ui.label(egui::RichText::new(format!("重新连接中... (尝试 {})", attempt))
-                                .color(egui::Color32::from_rgb(255, 165, 0)));
-                        });
-                    }
-                    StreamState::Failed { reason } => {
-                        ui.colored_label(
-                            egui::Color32::RED,
-                            format!("❌ 连接失败: {}", reason)
-                        );
-                    }
-                    _ => {}
-                }
+    /// 开始连拍（之后每一帧显示的画面都送入编码线程，见 poll_burst）
+    fn start_burst(&mut self) {
+        let Some((frame, rotation)) = self.video_renderer.as_ref().and_then(|renderer| renderer.displayed_frame()) else {
+            self.show_osd("没有可截图的画面");
+            return;
+        };
+        let job = self.jobs.register("连拍", CancelBehavior::Finalize);
+        match BurstCapture::start(self.snapshot_options(), self.snapshot_name(), frame.pts, job) {
+            Ok(mut burst) => {
+                // 当前显示的画面作为第一帧
+                burst.offer(&frame, rotation);
+                self.burst = Some(burst);
+                self.show_osd("连拍中…");
+            }
+            Err(e) => {
+                error!("启动连拍失败: {}", e);
+                self.show_osd("连拍失败");
             }
         }
     }
-
-    /// 处理键盘输入
-    fn handle_keyboard_input(&mut self, ctx: &Context) {
-        // 使用标志位在闭包外处理需要 ctx 的操作，避免双重锁定
-        let mut should_toggle_fullscreen = false;
-        let mut current_fullscreen_state = false;
-        let mut should_exit_fullscreen = false;
-        let mut should_hide_info_panel = false;
-        let mut should_toggle_info_panel = false;
-        
-        ctx.input(|i| {
-            // 空格键：播放/暂停
-            if i.key_pressed(egui::Key::Space) {
-                let mut manager = self.playback_manager.write();
-                if manager.is_playing() {
-                    let _ = manager.pause();
-                } else {
-                    let _ = manager.play();
-                }
-            }
-            
-            // 左右箭头：快进/快退
-            if i.key_pressed(egui::Key::ArrowLeft) {
-                let mut manager = self.playback_manager.write();
-                if let Ok(pos) = manager.get_position() {
-                    let _ = manager.seek_to_seconds((pos - 10.0).max(0.0));
-                }
-            }
-            
-            if i.key_pressed(egui::Key::ArrowRight) {
-                let mut manager = self.playback_manager.write();
-                if let Ok(pos) = manager.get_position() {
-                    let duration = manager.get_duration().unwrap_or(0.0);
-                    let _ = manager.seek_to_seconds((pos + 10.0).min(duration));
-                }
-            }
-            
-            // F11: 全屏切换（标记为需要切换，在闭包外执行）
-            if i.key_pressed(egui::Key::F11) {
-                should_toggle_fullscreen = true;
-                // 在闭包内获取当前全屏状态
-                current_fullscreen_state = i.viewport().fullscreen.unwrap_or(false);
-            }
-            
-            // Tab: 显示/隐藏信息面板
-            if i.key_pressed(egui::Key::Tab) {
-                should_toggle_info_panel = true;
-            }
-            
-            // Escape: 检查是否需要退出全屏或隐藏信息面板
-            if i.key_pressed(egui::Key::Escape) {
-                // 在 input 闭包内直接检查 fullscreen 状态
-                let is_fullscreen = i.viewport().fullscreen.unwrap_or(false);
-                if is_fullscreen {
-                    should_exit_fullscreen = true;
+    
+    /// 连拍：松开 Shift+S 或达到时长、帧数上限时停止；编码线程保存完毕后提示保存和丢弃的帧数
+    fn poll_burst(&mut self, ctx: &Context) {
+        let Some(burst) = &mut self.burst else {
+            return;
+        };
+        let held = ctx.input(|i| i.key_down(egui::Key::S) && i.modifiers.shift);
+        if !held || !burst.is_capturing() {
+            burst.stop();
+        }
+        let Some(report) = burst.poll_report() else {
+            // 暂停时界面不会持续刷新，定期检查按键和编码进度
+            ctx.request_repaint_after(Duration::from_millis(50));
+            return;
+        };
+        self.burst = None;
+        
+        let mut text = format!("连拍已保存 {} 帧", report.saved);
+        if report.dropped > 0 {
+            text.push_str(&format!("，丢弃 {} 帧", report.dropped));
+        }
+        if report.failed > 0 {
+            text.push_str(&format!("，{} 帧保存失败", report.failed));
+        }
+        if let Some(dir) = report.first_path.as_deref().and_then(Path::parent) {
+            info!("📸 {}: {}", text, dir.display());
+        }
+        self.show_osd(text);
+    }
+    
+    /// 当前的截图输出设置
+    fn snapshot_options(&self) -> SnapshotOptions {
+        SnapshotOptions {
+            format: self.settings.snapshot_format,
+            jpeg_quality: self.settings.snapshot_jpeg_quality,
+            dir: snapshot::snapshot_dir(self.settings.snapshot_dir.as_deref()),
+            template: self.settings.snapshot_template.clone(),
+        }
+    }
+    
+    /// 截图文件名中的媒体名称（本地文件为不带扩展名的文件名）
+    fn snapshot_name(&self) -> String {
+        self.ui_state
+            .current_file
+            .as_deref()
+            .map(Path::new)
+            .filter(|path| path.is_file())
+            .and_then(Path::file_stem)
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "snapshot".to_string())
+    }
+    
+    /// 循环切换字幕轨道（顺序：内嵌字幕 → 外部字幕 → 关闭）
+    fn cycle_subtitle_track(&mut self) {
+        let osd_text = {
+            let mut manager = self.player.write();
+            if manager.is_switching_track() {
+                "切换中…".to_string()
+            } else {
+                let tracks = manager.get_subtitle_tracks().to_vec();
+                if tracks.is_empty() {
+                    "字幕: 无".to_string()
                 } else {
-                    should_hide_info_panel = true;
+                    // 位置 0..len 为各字幕轨道，len 为"关闭"
+                    let current = manager
+                        .current_subtitle_track()
+                        .and_then(|source| tracks.iter().position(|t| &t.source == source))
+                        .unwrap_or(tracks.len());
+                    let next = (current + 1) % (tracks.len() + 1);
+                    let selection = tracks.get(next).map(|t| t.source.clone());
+                    
+                    match manager.select_subtitle_track(selection) {
+                        Ok(()) => match tracks.get(next) {
+                            Some(track) => format!("字幕: {} [{}/{}]", track.display_name(), next + 1, tracks.len()),
+                            None => "字幕: 关闭".to_string(),
+                        },
+                        Err(e) => {
+                            error!("切换字幕轨道失败: {}", e);
+                            format!("字幕切换失败: {}", e)
+                        }
+                    }
                 }
             }
-        });
-        
-        // 在闭包外执行需要 ctx 的操作，避免双重锁定
-        if should_toggle_fullscreen {
-            // F11: 切换全屏状态（使用闭包内获取的状态）
-            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(!current_fullscreen_state));
-            self.ui_state.is_fullscreen = !current_fullscreen_state;
-            ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(current_fullscreen_state));
-        } else if should_exit_fullscreen {
-            // Esc（在全屏时）: 退出全屏
-            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
-            self.ui_state.is_fullscreen = false;
-            ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(true));
-        } else if should_hide_info_panel {
-            // Esc（非全屏时）: 隐藏信息面板
-            self.ui_state.info_panel_visible = false;
-        }
+        };
         
-        if should_toggle_info_panel {
-            self.ui_state.info_panel_visible = !self.ui_state.info_panel_visible;
-        }
+        self.current_frame_pts = None;
+        self.show_osd(osd_text);
     }
 }
 
-/// 格式化时间显示
-fn format_time(seconds: f64) -> String {
-    let total_seconds = seconds as u64;
-    let hours = total_seconds / 3600;
-    let minutes = (total_seconds % 3600) / 60;
-    let secs = total_seconds % 60;
-    
-    if hours > 0 {
-        format!("{:02}:{:02}:{:02}", hours, minutes, secs)
-    } else {
-        format!("{:02}:{:02}", minutes, secs)
+/// 画面落后判定阈值（毫秒）
+const PRESENTATION_LAG_THRESHOLD_MS: i64 = 1000;
+
+/// 窗口标题中文件名的最大字符数（超出时从中间省略）
+const WINDOW_TITLE_MAX_CHARS: usize = 60;
+
+/// 标签页标题的最大宽度
+const TAB_TITLE_MAX_WIDTH: f32 = 200.0;
+
+/// 刷新率切换提示的显示时长
+const REFRESH_RATE_NOTICE_DURATION: Duration = Duration::from_secs(3);
+
+/// 进度条缓冲范围的刷新间隔（暂停时）
+const BUFFERED_RANGE_REFRESH: Duration = Duration::from_secs(1);
+
+/// 音频解码失败提示的显示时长
+const AUDIO_FAILURE_NOTICE_DURATION: Duration = Duration::from_secs(10);
+
+/// 跳过提示的文字颜色
+const SKIP_NOTICE_COLOR: egui::Color32 = egui::Color32::from_rgb(150, 200, 255);
+
+/// 直播标记颜色（处于直播边缘时）
+const LIVE_BADGE_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 40, 40);
+
+/// 直播标记（落后直播边缘时显示为灰色）
+fn live_badge(ui: &mut Ui, at_edge: bool, style: OsdStyle) {
+    let color = if at_edge { LIVE_BADGE_COLOR } else { egui::Color32::GRAY };
+    ui.label(egui::RichText::new("● LIVE").size(style.size(12.0)).strong().color(color));
+}
+
+/// 字幕堆叠总高度上限（相对视频高度）
+const SUBTITLE_MAX_HEIGHT_RATIO: f32 = 0.4;
+
+/// 调整窗口大小时为任务栏预留的高度（egui 不提供屏幕工作区）
+const TASKBAR_RESERVE: f32 = 48.0;
+
+/// 轨道徽标（强制/SDH/解说）底色
+const TRACK_BADGE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 80);
+
+/// 信息面板中"软解"标签的底色
+const HW_FALLBACK_CHIP_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 80);
+
+/// 拖动进度条后等待 seek 结果的上限（超过后进度条回到播放位置）
+const SEEK_RESULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 音量超过 100% 时的警示色
+const VOLUME_BOOST_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 170, 0);
+
+/// 音量滑块上启动渐入的填充色
+const VOLUME_RAMP_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(150, 200, 255, 200);
+
+/// 计算滑块上某个比例对应的 x 坐标（与 egui Slider 的手柄位置一致）
+fn slider_x_for_fraction(rect: egui::Rect, fraction: f32) -> f32 {
+    let handle_radius = rect.height() / 2.5;
+    let left = rect.left() + handle_radius;
+    let right = rect.right() - handle_radius;
+    left + fraction.clamp(0.0, 1.0) * (right - left)
+}
+
+/// 计算滑块上某个 x 坐标对应的比例（slider_x_for_fraction 的逆运算）
+fn slider_fraction_for_x(rect: egui::Rect, x: f32) -> f32 {
+    let handle_radius = rect.height() / 2.5;
+    let left = rect.left() + handle_radius;
+    let right = rect.right() - handle_radius;
+    if right <= left {
+        return 0.0;
     }
+    ((x - left) / (right - left)).clamp(0.0, 1.0)
 }
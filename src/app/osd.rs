@@ -0,0 +1,122 @@
+// 叠加层的缩放和位置（在电视上远距离观看时放大屏幕提示）
+//
+// 缩放只作用于画面上的叠加元素（屏幕提示、HDR 提示、直播标记、信息面板），与 egui 的
+// pixels_per_point 无关；字号、内边距和圆角按同一比例放大。放大后的提示框不超过视频宽度的 90%，
+// 超出时自动换行。设置修改后下一帧即按新参数布局。
+
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// 缩放范围
+pub const OSD_SCALE_RANGE: RangeInclusive<f32> = 1.0..=2.5;
+
+/// 叠加元素最大宽度（相对视频宽度）
+const MAX_WIDTH_RATIO: f32 = 0.9;
+
+/// 屏幕提示与视频边缘的距离（缩放前）
+const EDGE_MARGIN: f32 = 20.0;
+
+/// 屏幕提示的位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OsdAnchor {
+    #[default]
+    TopLeft,
+    TopCenter,
+    BottomCenter,
+}
+
+impl OsdAnchor {
+    pub const ALL: [OsdAnchor; 3] = [OsdAnchor::TopLeft, OsdAnchor::TopCenter, OsdAnchor::BottomCenter];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OsdAnchor::TopLeft => "左上",
+            OsdAnchor::TopCenter => "顶部居中",
+            OsdAnchor::BottomCenter => "底部居中",
+        }
+    }
+}
+
+/// 叠加层样式（缩放 + 屏幕提示位置）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OsdStyle {
+    scale: f32,
+    pub anchor: OsdAnchor,
+}
+
+impl Default for OsdStyle {
+    fn default() -> Self {
+        Self { scale: 1.0, anchor: OsdAnchor::default() }
+    }
+}
+
+impl OsdStyle {
+    /// 缩放超出范围时夹紧
+    pub fn new(scale: f32, anchor: OsdAnchor) -> Self {
+        let scale = if scale.is_finite() { scale.clamp(*OSD_SCALE_RANGE.start(), *OSD_SCALE_RANGE.end()) } else { 1.0 };
+        Self { scale, anchor }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// 缩放后的长度（字号、内边距、圆角）
+    pub fn size(&self, value: f32) -> f32 {
+        value * self.scale
+    }
+
+    pub fn vec2(&self, x: f32, y: f32) -> egui::Vec2 {
+        egui::vec2(x, y) * self.scale
+    }
+
+    /// 叠加元素的最大宽度（视频宽度的 90%）
+    pub fn max_width(&self, video_rect: egui::Rect) -> f32 {
+        video_rect.width() * MAX_WIDTH_RATIO
+    }
+
+    /// 按位置设置放置 box_size 大小的提示框
+    pub fn message_rect(&self, video_rect: egui::Rect, box_size: egui::Vec2) -> egui::Rect {
+        let margin = self.size(EDGE_MARGIN);
+        let min = match self.anchor {
+            OsdAnchor::TopLeft => video_rect.min + egui::vec2(margin, margin),
+            OsdAnchor::TopCenter => egui::pos2(video_rect.center().x - box_size.x / 2.0, video_rect.top() + margin),
+            OsdAnchor::BottomCenter => {
+                egui::pos2(video_rect.center().x - box_size.x / 2.0, video_rect.bottom() - margin - box_size.y)
+            }
+        };
+        egui::Rect::from_min_size(min, box_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_is_clamped() {
+        assert_eq!(OsdStyle::new(0.5, OsdAnchor::TopLeft).scale(), 1.0);
+        assert_eq!(OsdStyle::new(3.0, OsdAnchor::TopLeft).scale(), 2.5);
+        assert_eq!(OsdStyle::new(f32::NAN, OsdAnchor::TopLeft).scale(), 1.0);
+        let style = OsdStyle::new(2.0, OsdAnchor::TopLeft);
+        assert_eq!(style.size(20.0), 40.0);
+        assert_eq!(style.vec2(12.0, 6.0), egui::vec2(24.0, 12.0));
+    }
+
+    #[test]
+    fn test_message_rect_follows_anchor() {
+        let video = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(1000.0, 500.0));
+        let size = egui::vec2(200.0, 40.0);
+
+        let rect = OsdStyle::new(1.0, OsdAnchor::TopLeft).message_rect(video, size);
+        assert_eq!(rect.min, egui::pos2(20.0, 20.0));
+
+        let rect = OsdStyle::new(2.0, OsdAnchor::TopCenter).message_rect(video, size);
+        assert_eq!(rect.min, egui::pos2(400.0, 40.0));
+
+        let rect = OsdStyle::new(1.0, OsdAnchor::BottomCenter).message_rect(video, size);
+        assert_eq!(rect.max, egui::pos2(600.0, 480.0));
+
+        assert_eq!(OsdStyle::new(2.5, OsdAnchor::TopLeft).max_width(video), 900.0);
+    }
+}
@@ -0,0 +1,98 @@
+// 最近打开的文件（本地文件和网络地址，最近打开的在前）
+//
+// 列表随设置一起保存在 settings.json 中。同一路径只保留一条，重新打开时移到最前；
+// 显示时跳过磁盘上已不存在的本地文件（记录保留，文件恢复后重新显示），网络地址总是显示。
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 最多记录的条目数
+pub const MAX_RECENT_ENTRIES: usize = 15;
+
+/// 最近打开的文件列表
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RecentFiles {
+    entries: Vec<String>,
+}
+
+impl RecentFiles {
+    /// 记录一次打开（已存在时移到最前）
+    pub fn push(&mut self, path: &str) {
+        self.entries.retain(|entry| entry != path);
+        self.entries.insert(0, path.to_string());
+        self.entries.truncate(MAX_RECENT_ENTRIES);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// 可以打开的条目（跳过已不存在的本地文件）
+    pub fn available(&self) -> Vec<&str> {
+        self.entries
+            .iter()
+            .map(String::as_str)
+            .filter(|entry| is_network_address(entry) || Path::new(entry).exists())
+            .collect()
+    }
+}
+
+/// 是否为网络地址（如 http://、rtmp://）
+pub fn is_network_address(entry: &str) -> bool {
+    entry.contains("://")
+}
+
+/// 菜单中显示的名称（本地文件显示文件名，网络地址显示完整地址）
+pub fn display_name(entry: &str) -> String {
+    if is_network_address(entry) {
+        return entry.to_string();
+    }
+    Path::new(entry)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| entry.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_dedups_and_keeps_most_recent_first() {
+        let mut recent = RecentFiles::default();
+        for index in 0..20 {
+            recent.push(&format!("/videos/{}.mkv", index));
+        }
+        recent.push("/videos/10.mkv");
+        assert_eq!(recent.entries.len(), MAX_RECENT_ENTRIES);
+        assert_eq!(recent.entries[0], "/videos/10.mkv");
+        assert_eq!(recent.entries[1], "/videos/19.mkv");
+        assert_eq!(recent.entries.iter().filter(|entry| *entry == "/videos/10.mkv").count(), 1);
+        recent.clear();
+        assert!(recent.entries.is_empty());
+    }
+
+    #[test]
+    fn test_available_skips_missing_files_but_keeps_urls() {
+        let dir = std::env::temp_dir().join(format!("myy_recent_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("a.mkv");
+        std::fs::write(&existing, b"").unwrap();
+        let existing = existing.to_string_lossy().to_string();
+
+        let mut recent = RecentFiles::default();
+        recent.push(&existing);
+        recent.push("/definitely/missing/b.mkv");
+        recent.push("https://example.com/live.m3u8");
+        assert_eq!(recent.available(), ["https://example.com/live.m3u8", existing.as_str()]);
+        assert_eq!(display_name(&existing), "a.mkv");
+        assert_eq!(display_name("rtmp://host/app"), "rtmp://host/app");
+
+        // 序列化为普通数组
+        let json = serde_json::to_string(&recent).unwrap();
+        assert!(json.starts_with("[\"https://"), "{}", json);
+        assert_eq!(serde_json::from_str::<RecentFiles>(&json).unwrap(), recent);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+// 安全模式（--safe-mode 启动，或在解码崩溃提示中对单个文件以安全模式重新打开）
+//
+// 功能开关统一来自 RuntimeFlags：播放器负责硬件解码和字幕自动加载，
+// 界面在启动监视文件夹、切换刷新率、设置全局硬件解码开关前通过这里的函数查询。
+// 安全模式下窗口顶部常驻提示条，可启动一个正常模式的新进程打开同一文件进行对比。
+
+use log::info;
+use std::path::PathBuf;
+use std::process::Command;
+
+use super::user_data::UserSettings;
+use myy_player::RuntimeFlags;
+
+/// 解码崩溃提示
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrashNotice {
+    /// 本次播放中解码线程崩溃
+    Now(PathBuf),
+    /// 上次播放该文件时解码线程崩溃（进程已退出）
+    Previous(PathBuf),
+}
+
+impl CrashNotice {
+    pub fn path(&self) -> &PathBuf {
+        match self {
+            CrashNotice::Now(path) | CrashNotice::Previous(path) => path,
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            CrashNotice::Now(_) => "解码线程崩溃，播放已中断",
+            CrashNotice::Previous(_) => "上次播放此文件时播放器崩溃",
+        }
+    }
+}
+
+/// 全局硬件解码开关（设置开启且不在安全模式下）
+pub fn hw_decode_allowed(settings: &UserSettings, flags: RuntimeFlags) -> bool {
+    settings.hw_decode && flags.hw_decode
+}
+
+/// 需要监视的文件夹（未开启或安全模式下为 None）
+pub fn watch_folder_target(settings: &UserSettings, flags: RuntimeFlags) -> Option<&str> {
+    if !settings.watch_folder_enabled || !flags.watch_folder {
+        return None;
+    }
+    settings.watch_folder_path.as_deref()
+}
+
+/// 是否自动匹配刷新率（设置开启且不在安全模式下）
+pub fn refresh_rate_allowed(settings: &UserSettings, flags: RuntimeFlags) -> bool {
+    settings.match_refresh_rate && flags.refresh_rate_switching
+}
+
+/// 启动正常模式的新进程（打开同一文件，用于和安全模式对比）
+pub fn launch_normal_mode(file: Option<&str>) -> std::io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let mut command = Command::new(exe);
+    if let Some(file) = file {
+        command.arg(file);
+    }
+    command.spawn()?;
+    info!("🛡️ 已启动正常模式进程: {}", file.unwrap_or("（无文件）"));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_enabled() -> UserSettings {
+        UserSettings {
+            hw_decode: true,
+            watch_folder_enabled: true,
+            watch_folder_path: Some("/downloads".to_string()),
+            match_refresh_rate: true,
+            ..UserSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_normal_mode_follows_settings() {
+        let settings = all_enabled();
+        assert!(hw_decode_allowed(&settings, RuntimeFlags::NORMAL));
+        assert_eq!(watch_folder_target(&settings, RuntimeFlags::NORMAL), Some("/downloads"));
+        assert!(refresh_rate_allowed(&settings, RuntimeFlags::NORMAL));
+
+        let settings = UserSettings { hw_decode: false, watch_folder_enabled: false, match_refresh_rate: false, ..all_enabled() };
+        assert!(!hw_decode_allowed(&settings, RuntimeFlags::NORMAL));
+        assert_eq!(watch_folder_target(&settings, RuntimeFlags::NORMAL), None);
+        assert!(!refresh_rate_allowed(&settings, RuntimeFlags::NORMAL));
+    }
+
+    #[test]
+    fn test_safe_mode_disables_every_subsystem() {
+        let settings = all_enabled();
+        let flags = RuntimeFlags::SAFE_MODE;
+        assert!(flags.is_safe_mode());
+        assert!(!flags.subtitle_autoload);
+        assert!(!hw_decode_allowed(&settings, flags));
+        assert_eq!(watch_folder_target(&settings, flags), None);
+        assert!(!refresh_rate_allowed(&settings, flags));
+    }
+}
@@ -0,0 +1,199 @@
+// 方向键跳转的步长与连续按键合并
+//
+// 方向键按三档步长跳转：默认 ← / → 5 秒、Shift 30 秒、Ctrl 60 秒，步长可在设置中修改。
+// 快速连按时不逐次调用 seek（每次 seek 都会清空解码和输出队列）：第一次按键立即跳转，之后在
+// COALESCE_WINDOW 内的按键只累加目标位置，停止按键后一次跳到累计的位置；按住不放时每隔 FLUSH_INTERVAL
+// 跳转一次，让画面跟上。屏幕提示显示整串按键累计的偏移（"+30s"）。
+
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// 停止按键多久后执行累计的跳转
+pub const COALESCE_WINDOW: Duration = Duration::from_millis(250);
+
+/// 按住方向键时的最长跳转间隔
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 步长的可调范围（秒）
+pub const SEEK_STEP_RANGE_SECS: RangeInclusive<u32> = 1..=600;
+
+/// 跳转步长档位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeekStep {
+    Small,
+    Medium,
+    Large,
+}
+
+/// 各档步长（秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeekSteps {
+    pub small_secs: u32,
+    pub medium_secs: u32,
+    pub large_secs: u32,
+}
+
+impl Default for SeekSteps {
+    fn default() -> Self {
+        Self { small_secs: 5, medium_secs: 30, large_secs: 60 }
+    }
+}
+
+impl SeekSteps {
+    /// 档位对应的毫秒数
+    pub fn step_ms(&self, step: SeekStep) -> i64 {
+        let secs = match step {
+            SeekStep::Small => self.small_secs,
+            SeekStep::Medium => self.medium_secs,
+            SeekStep::Large => self.large_secs,
+        };
+        secs as i64 * 1000
+    }
+
+    /// 夹紧到可调范围
+    pub fn sanitized(self) -> Self {
+        let clamp = |secs: u32| secs.clamp(*SEEK_STEP_RANGE_SECS.start(), *SEEK_STEP_RANGE_SECS.end());
+        Self { small_secs: clamp(self.small_secs), medium_secs: clamp(self.medium_secs), large_secs: clamp(self.large_secs) }
+    }
+}
+
+/// 跳转偏移的提示文字（"+30s"、"-5s"、"+2m05s"）
+pub fn format_offset(offset_ms: i64) -> String {
+    let sign = if offset_ms < 0 { '-' } else { '+' };
+    let secs = (offset_ms.abs() + 500) / 1000;
+    if secs >= 60 {
+        format!("{}{}m{:02}s", sign, secs / 60, secs % 60)
+    } else {
+        format!("{}{}s", sign, secs)
+    }
+}
+
+/// 一串连续按键
+#[derive(Debug, Clone, Copy)]
+struct Burst {
+    start_ms: i64,  // 第一次按键时的播放位置
+    target_ms: i64,
+    last_input: Instant,
+    last_issued: Instant,
+    dirty: bool,  // 累计的目标尚未执行
+}
+
+/// 连续跳转按键的合并
+#[derive(Debug, Default)]
+pub struct SeekAccumulator {
+    burst: Option<Burst>,
+}
+
+impl SeekAccumulator {
+    /// 按下跳转键（bounds 为可跳转范围，上限未知时为 None）；返回需要立即跳转的目标
+    pub fn press(&mut self, position_ms: i64, delta_ms: i64, bounds: (i64, Option<i64>), now: Instant) -> Option<i64> {
+        let clamp = |target: i64| {
+            let target = bounds.1.map_or(target, |end| target.min(end));
+            target.max(bounds.0)
+        };
+        match &mut self.burst {
+            Some(burst) if now.duration_since(burst.last_input) < COALESCE_WINDOW => {
+                burst.target_ms = clamp(burst.target_ms + delta_ms);
+                burst.last_input = now;
+                burst.dirty = true;
+                None
+            }
+            _ => {
+                let target_ms = clamp(position_ms + delta_ms);
+                self.burst = Some(Burst { start_ms: position_ms, target_ms, last_input: now, last_issued: now, dirty: false });
+                Some(target_ms)
+            }
+        }
+    }
+
+    /// 每帧调用：返回到期的累计跳转（停止按键超过 COALESCE_WINDOW 或按住超过 FLUSH_INTERVAL）
+    pub fn poll(&mut self, now: Instant) -> Option<i64> {
+        let burst = self.burst.as_mut()?;
+        let quiet = now.duration_since(burst.last_input) >= COALESCE_WINDOW;
+        let target = if burst.dirty && (quiet || now.duration_since(burst.last_issued) >= FLUSH_INTERVAL) {
+            burst.dirty = false;
+            burst.last_issued = now;
+            Some(burst.target_ms)
+        } else {
+            None
+        };
+        if quiet {
+            self.burst = None;
+        }
+        target
+    }
+
+    /// 当前这串按键累计的偏移和目标位置
+    pub fn pending(&self) -> Option<(i64, i64)> {
+        self.burst.map(|burst| (burst.target_ms - burst.start_ms, burst.target_ms))
+    }
+
+    /// 放弃未执行的跳转（打开新文件）
+    pub fn cancel(&mut self) {
+        self.burst = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_offset() {
+        assert_eq!(format_offset(30_000), "+30s");
+        assert_eq!(format_offset(-5_000), "-5s");
+        assert_eq!(format_offset(125_000), "+2m05s");
+        assert_eq!(format_offset(0), "+0s");
+    }
+
+    #[test]
+    fn test_rapid_presses_coalesce_into_one_seek() {
+        let mut accumulator = SeekAccumulator::default();
+        let start = Instant::now();
+        let bounds = (0, Some(600_000));
+        // 第一次按键立即跳转，之后的按键只累加
+        assert_eq!(accumulator.press(100_000, 5_000, bounds, start), Some(105_000));
+        for i in 1..5 {
+            let now = start + Duration::from_millis(60 * i);
+            assert_eq!(accumulator.press(100_000, 5_000, bounds, now), None);
+            assert_eq!(accumulator.poll(now), None);
+        }
+        assert_eq!(accumulator.pending(), Some((25_000, 125_000)));
+
+        // 停止按键后一次跳到累计的位置
+        let last = start + Duration::from_millis(240);
+        assert_eq!(accumulator.poll(last + COALESCE_WINDOW), Some(125_000));
+        assert_eq!(accumulator.pending(), None);
+        assert_eq!(accumulator.poll(last + COALESCE_WINDOW * 2), None);
+    }
+
+    #[test]
+    fn test_held_key_flushes_periodically_and_clamps() {
+        let mut accumulator = SeekAccumulator::default();
+        let start = Instant::now();
+        let bounds = (0, Some(60_000));
+        accumulator.press(10_000, -30_000, bounds, start);
+        assert_eq!(accumulator.pending(), Some((-10_000, 0)));
+
+        let mut flushed = Vec::new();
+        for i in 1..=20 {
+            let now = start + Duration::from_millis(30 * i);
+            accumulator.press(0, 30_000, bounds, now);
+            flushed.extend(accumulator.poll(now));
+        }
+        // 按住 600ms：中途跳转一次，目标不超过上限
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(accumulator.pending().map(|(_, target)| target), Some(60_000));
+    }
+
+    #[test]
+    fn test_steps_are_clamped() {
+        let steps = SeekSteps { small_secs: 0, medium_secs: 30, large_secs: 10_000 }.sanitized();
+        assert_eq!(steps.step_ms(SeekStep::Small), 1_000);
+        assert_eq!(steps.step_ms(SeekStep::Medium), 30_000);
+        assert_eq!(steps.step_ms(SeekStep::Large), 600_000);
+    }
+}
@@ -0,0 +1,183 @@
+// 会话标签页（同时打开多个媒体，每个会话独立的播放器，只有活动会话全速解码）
+
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+use myy_player::Player;
+
+/// 同时打开的会话上限
+pub const MAX_SESSIONS: usize = 4;
+
+/// 单个会话（切换离开时保存的 UI 状态）
+pub struct Session {
+    pub manager: Arc<RwLock<Player>>,
+    pub current_file: Option<String>,  // 当前文件路径（标签标题）
+    pub was_playing: bool,             // 挂起前是否正在播放（切换回来时恢复）
+}
+
+impl Session {
+    pub fn new(manager: Arc<RwLock<Player>>) -> Self {
+        Self { manager, current_file: None, was_playing: false }
+    }
+
+    /// 标签标题（文件名，未打开文件时为"空白"）
+    pub fn title(&self) -> String {
+        self.current_file
+            .as_deref()
+            .map(tab_title)
+            .unwrap_or_else(|| "空白".to_string())
+    }
+}
+
+/// 从路径或 URL 取出文件名作为标签标题
+pub fn tab_title(path: &str) -> String {
+    path.trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// 标签列表（始终至少有一个会话）
+pub struct SessionList<T> {
+    items: Vec<T>,
+    active: usize,
+}
+
+impl<T> SessionList<T> {
+    pub fn new(first: T) -> Self {
+        Self { items: vec![first], active: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() >= MAX_SESSIONS
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    pub fn active(&self) -> &T {
+        &self.items[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut T {
+        &mut self.items[self.active]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    /// 追加会话（已达上限时原样返回）
+    pub fn push(&mut self, item: T) -> std::result::Result<usize, T> {
+        if self.is_full() {
+            return Err(item);
+        }
+        self.items.push(item);
+        Ok(self.items.len() - 1)
+    }
+
+    /// 关闭会话（最后一个会话不能关闭）；关闭活动会话时激活相邻的会话
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if self.items.len() <= 1 || index >= self.items.len() {
+            return None;
+        }
+        let item = self.items.remove(index);
+        if index < self.active || self.active >= self.items.len() {
+            self.active -= 1;
+        }
+        Some(item)
+    }
+
+    /// 切换活动会话，返回是否发生了切换
+    pub fn set_active(&mut self, index: usize) -> bool {
+        if index >= self.items.len() || index == self.active {
+            return false;
+        }
+        self.active = index;
+        true
+    }
+
+    /// 循环的下一个会话（Ctrl+Tab）
+    pub fn next_index(&self) -> usize {
+        (self.active + 1) % self.items.len()
+    }
+
+    /// 循环的上一个会话（Ctrl+Shift+Tab）
+    pub fn previous_index(&self) -> usize {
+        (self.active + self.items.len() - 1) % self.items.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(count: usize) -> SessionList<usize> {
+        let mut sessions = SessionList::new(0);
+        for i in 1..count {
+            sessions.push(i).unwrap();
+        }
+        sessions
+    }
+
+    #[test]
+    fn test_push_respects_limit() {
+        let mut sessions = list(MAX_SESSIONS);
+        assert!(sessions.is_full());
+        assert_eq!(sessions.push(99), Err(99));
+        assert_eq!(sessions.len(), MAX_SESSIONS);
+    }
+
+    #[test]
+    fn test_cycle_wraps_around() {
+        let mut sessions = list(3);
+        assert_eq!(sessions.next_index(), 1);
+        assert_eq!(sessions.previous_index(), 2);
+        assert!(sessions.set_active(2));
+        assert_eq!(sessions.next_index(), 0);
+        assert!(!sessions.set_active(2));
+        assert!(!sessions.set_active(3));
+    }
+
+    #[test]
+    fn test_remove_keeps_active_session() {
+        let mut sessions = list(4);
+        sessions.set_active(2);
+        assert_eq!(sessions.remove(0), Some(0));
+        assert_eq!(*sessions.active(), 2);
+        assert_eq!(sessions.remove(2), Some(3));
+        assert_eq!(*sessions.active(), 2);
+    }
+
+    #[test]
+    fn test_remove_active_activates_neighbour() {
+        let mut sessions = list(3);
+        sessions.set_active(2);
+        assert_eq!(sessions.remove(2), Some(2));
+        assert_eq!(*sessions.active(), 1);
+        sessions.set_active(0);
+        assert_eq!(sessions.remove(0), Some(0));
+        assert_eq!(*sessions.active(), 1);
+    }
+
+    #[test]
+    fn test_last_session_cannot_be_removed() {
+        let mut sessions = list(1);
+        assert_eq!(sessions.remove(0), None);
+        assert_eq!(sessions.len(), 1);
+    }
+
+    #[test]
+    fn test_tab_title() {
+        assert_eq!(tab_title("/movies/a/encode_x265.mkv"), "encode_x265.mkv");
+        assert_eq!(tab_title(r"C:\Videos\clip.mp4"), "clip.mp4");
+        assert_eq!(tab_title("https://example.com/live/"), "live");
+    }
+}
@@ -0,0 +1,715 @@
+// 设置抽屉（覆盖在画面右侧的设置面板，齿轮按钮或 Ctrl+, 切换，Esc 关闭）
+//
+// 各分区的控件直接绑定到 UserSettings，修改立即生效，停止修改一段时间后自动保存（见 user_data::SettingsAutoSave）。
+// 需要重建播放管线的设置（如硬件解码）先暂存，点击"应用"后才写入设置并重新打开当前文件，
+// 避免每次点击或拖动都中断播放。抽屉打开时不暂停播放，展开的分区随设置一起保存。
+
+use egui::{Context, RichText, Ui};
+use serde::{Deserialize, Serialize};
+
+use super::burst_capture::{BURST_DURATION, BURST_MAX_FRAMES};
+use super::keymap::{bindable_actions, KeyCombo, Keymap};
+use super::osd::{OsdAnchor, OSD_SCALE_RANGE};
+use super::seek_step::SEEK_STEP_RANGE_SECS;
+use super::snapshot::{self, SnapshotFormat, DEFAULT_TEMPLATE, JPEG_QUALITY_RANGE};
+use super::sync_tuning::{SyncOverrides, SyncTuning, THRESHOLD_RANGE_MS};
+use super::user_data::{UserSettings, SUBTITLE_SCALE_RANGE};
+use super::volume::{format_volume, STARTUP_FADE_RANGE};
+use myy_player::{Player, MAX_VOLUME};
+use crate::platform::{display_mode, file_manager};
+use log::warn;
+use crate::renderer::video_view::FitMode;
+use myy_player::MIN_FRAME_DIMENSION;
+
+/// 响度均衡的说明（设置抽屉和音量右键菜单共用）
+pub const LOUDNESS_NORMALIZATION_HINT: &str = "按平均响度缓慢调整增益，让对白为主和动作场面为主的文件音量相近";
+
+/// 抽屉宽度
+const DRAWER_WIDTH: f32 = 300.0;
+
+/// 帧尺寸上限的可调范围
+const MAX_FRAME_DIMENSION_RANGE: std::ops::RangeInclusive<u32> = 1024..=16384;
+
+/// 首帧期限的可调范围（秒）
+const FIRST_FRAME_TIMEOUT_RANGE: std::ops::RangeInclusive<u32> = 2..=60;
+
+/// 时移保留时长的可调范围（分钟）
+const TIMESHIFT_MINUTES_RANGE: std::ops::RangeInclusive<u32> = 1..=240;
+
+/// 时移保留大小的可调范围（MB）
+const TIMESHIFT_MB_RANGE: std::ops::RangeInclusive<u32> = 256..=32768;
+
+/// 默认字幕延迟的可调范围（± 毫秒）
+const SUBTITLE_OFFSET_RANGE_MS: i64 = 5_000;
+
+/// 设置分区
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SettingsSection {
+    Playback,
+    Subtitle,
+    Snapshot,
+    Audio,
+    Network,
+    Interface,
+    Shortcuts,
+    Advanced,
+}
+
+impl SettingsSection {
+    pub const ALL: [SettingsSection; 8] = [
+        SettingsSection::Playback,
+        SettingsSection::Subtitle,
+        SettingsSection::Snapshot,
+        SettingsSection::Audio,
+        SettingsSection::Network,
+        SettingsSection::Interface,
+        SettingsSection::Shortcuts,
+        SettingsSection::Advanced,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingsSection::Playback => "播放",
+            SettingsSection::Subtitle => "字幕",
+            SettingsSection::Snapshot => "截图",
+            SettingsSection::Audio => "音频",
+            SettingsSection::Network => "网络",
+            SettingsSection::Interface => "界面",
+            SettingsSection::Shortcuts => "快捷键",
+            SettingsSection::Advanced => "高级",
+        }
+    }
+}
+
+/// 界面主题（影响菜单、对话框和面板）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UiTheme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl UiTheme {
+    pub const ALL: [UiTheme; 2] = [UiTheme::Dark, UiTheme::Light];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            UiTheme::Dark => "深色",
+            UiTheme::Light => "浅色",
+        }
+    }
+
+    /// 对应的 egui 样式（深色背景与标题栏一致：rgb(29, 29, 29)）
+    pub fn visuals(self) -> egui::Visuals {
+        match self {
+            UiTheme::Dark => {
+                let mut visuals = egui::Visuals::dark();
+                visuals.window_fill = egui::Color32::from_rgb(29, 29, 29);
+                visuals.panel_fill = egui::Color32::from_rgb(29, 29, 29);
+                visuals
+            }
+            UiTheme::Light => egui::Visuals::light(),
+        }
+    }
+
+    /// 应用到上下文（样式没有变化时不做任何事）
+    pub fn apply(self, ctx: &Context) {
+        let visuals = self.visuals();
+        if ctx.style().visuals != visuals {
+            ctx.set_visuals(visuals);
+        }
+    }
+}
+
+/// 需要重建播放管线的设置：修改先暂存，点击"应用"后才写入设置
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Staged<T> {
+    draft: Option<T>,
+}
+
+impl<T: Copy + PartialEq> Staged<T> {
+    /// 控件显示的值（有暂存修改时显示暂存值）
+    pub fn value(&self, applied: T) -> T {
+        self.draft.unwrap_or(applied)
+    }
+
+    /// 修改暂存值（改回已生效的值时取消暂存）
+    pub fn edit(&mut self, applied: T, value: T) {
+        self.draft = (value != applied).then_some(value);
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.draft.is_some()
+    }
+
+    /// 取出暂存值（应用）
+    pub fn take(&mut self) -> Option<T> {
+        self.draft.take()
+    }
+
+    pub fn discard(&mut self) {
+        self.draft = None;
+    }
+}
+
+/// 抽屉中的修改（需要应用到播放器等处的副作用由 App 处理）
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DrawerChanges {
+    /// 单曲循环、强制字幕、帧尺寸上限等立即生效的播放设置
+    pub playback: bool,
+    pub watch_folder: bool,
+    pub sync_tuning: bool,
+    /// 应用了需要重建播放管线的设置
+    pub rebuild_pipeline: bool,
+    /// 叠加层缩放或位置变化（显示预览提示）
+    pub osd_preview: bool,
+    /// 快捷键绑定变化
+    pub keymap: bool,
+}
+
+/// 设置抽屉
+#[derive(Debug, Default)]
+pub struct SettingsDrawer {
+    open: bool,
+    hw_decode: Staged<bool>,
+    audio_devices: Option<Vec<String>>,  // 音频设备列表（第一次展开音频分区时读取，点击刷新重新读取）
+    capturing: Option<usize>,  // 正在录制新按键的动作（bindable_actions 中的序号）
+    shortcut_error: Option<String>,  // 最近一次改绑失败的原因（按键冲突）
+}
+
+impl SettingsDrawer {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    /// 关闭抽屉（返回之前是否打开，Esc 优先关闭抽屉）
+    pub fn close(&mut self) -> bool {
+        self.capturing = None;
+        std::mem::replace(&mut self.open, false)
+    }
+
+    /// 正在录制快捷键（此时按键不触发播放器动作）
+    pub fn is_capturing_key(&self) -> bool {
+        self.open && self.capturing.is_some()
+    }
+
+    /// 绘制抽屉（sync_tuning 为当前生效的同步阈值，用于显示自动值）
+    pub fn show(&mut self, ctx: &Context, settings: &mut UserSettings, sync_tuning: SyncTuning) -> DrawerChanges {
+        let mut changes = DrawerChanges::default();
+        if !self.open {
+            return changes;
+        }
+
+        let mut open = self.open;
+        egui::Window::new("设置")
+            .id(egui::Id::new("settings_drawer"))
+            .open(&mut open)
+            .anchor(egui::Align2::RIGHT_TOP, egui::Vec2::new(-10.0, 10.0))
+            .fixed_size(egui::Vec2::new(DRAWER_WIDTH, 0.0))
+            .collapsible(false)
+            .frame(egui::Frame::window(&ctx.style()).fill(ctx.style().visuals.window_fill.gamma_multiply(0.92)))
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(ctx.screen_rect().height() - 140.0)
+                    .show(ui, |ui| {
+                        for section in SettingsSection::ALL {
+                            self.show_section(ui, section, settings, sync_tuning, &mut changes);
+                        }
+                    });
+            });
+        if !open {
+            self.open = false;
+            self.capturing = None;
+        }
+        changes
+    }
+
+    /// 可折叠的分区（展开状态记录在设置中）
+    fn show_section(
+        &mut self,
+        ui: &mut Ui,
+        section: SettingsSection,
+        settings: &mut UserSettings,
+        sync_tuning: SyncTuning,
+        changes: &mut DrawerChanges,
+    ) {
+        let expanded = settings.expanded_sections.contains(&section);
+        let response = egui::CollapsingHeader::new(section.label())
+            .id_source(section)
+            .default_open(expanded)
+            .show(ui, |ui| match section {
+                SettingsSection::Playback => playback_section(ui, settings, changes),
+                SettingsSection::Subtitle => subtitle_section(ui, settings, changes),
+                SettingsSection::Snapshot => snapshot_section(ui, settings),
+                SettingsSection::Audio => self.audio_section(ui, settings, changes),
+                SettingsSection::Network => network_section(ui, settings, changes),
+                SettingsSection::Interface => interface_section(ui, settings, changes),
+                SettingsSection::Shortcuts => self.shortcuts_section(ui, settings, changes),
+                SettingsSection::Advanced => self.advanced_section(ui, settings, sync_tuning, changes),
+            });
+        if response.header_response.clicked() {
+            if expanded {
+                settings.expanded_sections.retain(|s| *s != section);
+            } else {
+                settings.expanded_sections.push(section);
+            }
+        }
+    }
+
+    fn audio_section(&mut self, ui: &mut Ui, settings: &mut UserSettings, changes: &mut DrawerChanges) {
+        // 输出设备（枚举设备较慢，不在每帧读取）
+        let devices = self.audio_devices.get_or_insert_with(Player::audio_devices);
+        let mut refresh = false;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("settings_audio_device")
+                .selected_text(settings.audio_device.as_deref().unwrap_or("系统默认"))
+                .width(DRAWER_WIDTH - 110.0)
+                .show_ui(ui, |ui| {
+                    changes.playback |= ui.selectable_value(&mut settings.audio_device, None, "系统默认").changed();
+                    for device in devices.iter() {
+                        changes.playback |= ui
+                            .selectable_value(&mut settings.audio_device, Some(device.clone()), device.as_str())
+                            .changed();
+                    }
+                });
+            refresh = ui.small_button("🔄").on_hover_text("刷新设备列表").clicked();
+            ui.label("输出设备");
+        });
+        if refresh {
+            self.audio_devices = None;
+        }
+        ui.add(
+            egui::Slider::new(&mut settings.default_volume, 0.0..=MAX_VOLUME)
+                .custom_formatter(|value, _| format_volume(value as f32))
+                .text("默认音量"),
+        );
+        ui.checkbox(&mut settings.restore_default_volume, "恢复默认音量")
+            .on_hover_text("打开没有调整过音量的文件时使用默认音量");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut settings.startup_fade_in, "启动时音量渐入")
+                .on_hover_text("应用启动后第一次播放时，音量在设定时长内从 0 逐渐升到目标音量（下次启动生效）");
+            ui.add_enabled(
+                settings.startup_fade_in,
+                egui::Slider::new(&mut settings.startup_fade_in_secs, STARTUP_FADE_RANGE)
+                    .step_by(0.5)
+                    .suffix(" 秒"),
+            );
+        });
+        changes.playback |= ui
+            .checkbox(&mut settings.loudness_normalization, "响度均衡")
+            .on_hover_text(LOUDNESS_NORMALIZATION_HINT)
+            .changed();
+    }
+
+    fn shortcuts_section(&mut self, ui: &mut Ui, settings: &mut UserSettings, changes: &mut DrawerChanges) {
+        let mut keymap = Keymap::from_config(settings.key_bindings.as_ref());
+        let actions = bindable_actions();
+
+        // 录制中：取第一个按下的键（Esc 取消）
+        if let Some(index) = self.capturing {
+            let pressed = ui.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Key { key, pressed: true, modifiers, .. } => Some(KeyCombo::from_event(*key, *modifiers)),
+                    _ => None,
+                })
+            });
+            if let Some(combo) = pressed {
+                self.capturing = None;
+                if combo != KeyCombo::new(egui::Key::Escape) {
+                    if let Some(bindable) = actions.get(index) {
+                        match keymap.rebind(&bindable.action, combo) {
+                            Ok(()) => {
+                                self.shortcut_error = None;
+                                settings.key_bindings = Some(keymap.to_config());
+                                changes.keymap = true;
+                            }
+                            Err(e) => self.shortcut_error = Some(e),
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(error) = &self.shortcut_error {
+            ui.colored_label(egui::Color32::LIGHT_RED, error);
+        }
+        egui::Grid::new("settings_shortcuts").num_columns(3).striped(true).show(ui, |ui| {
+            for (index, bindable) in actions.iter().enumerate() {
+                ui.label(bindable.label);
+                if self.capturing == Some(index) {
+                    ui.label(RichText::new("请按新的快捷键…").italics());
+                    if ui.small_button("取消").clicked() {
+                        self.capturing = None;
+                    }
+                } else {
+                    let combos = keymap.combos_for(&bindable.action);
+                    let text = if combos.is_empty() {
+                        "未绑定".to_string()
+                    } else {
+                        combos.iter().map(KeyCombo::to_string).collect::<Vec<_>>().join(" / ")
+                    };
+                    ui.label(RichText::new(text).monospace());
+                    ui.horizontal(|ui| {
+                        if ui.small_button("修改").on_hover_text("点击后按下新的快捷键，Esc 取消").clicked() {
+                            self.capturing = Some(index);
+                            self.shortcut_error = None;
+                        }
+                        if ui.add_enabled(!combos.is_empty(), egui::Button::new("清除").small()).clicked() {
+                            keymap.unbind(&bindable.action);
+                            settings.key_bindings = Some(keymap.to_config());
+                            changes.keymap = true;
+                        }
+                    });
+                }
+                ui.end_row();
+            }
+        });
+        if ui
+            .add_enabled(settings.key_bindings.is_some(), egui::Button::new("恢复默认"))
+            .on_hover_text("恢复所有快捷键的默认绑定")
+            .clicked()
+        {
+            settings.key_bindings = None;
+            self.capturing = None;
+            self.shortcut_error = None;
+            changes.keymap = true;
+        }
+    }
+
+    fn advanced_section(&mut self, ui: &mut Ui, settings: &mut UserSettings, sync_tuning: SyncTuning, changes: &mut DrawerChanges) {
+        // 硬件解码：重新创建解码器才能生效
+        let mut hw_decode = self.hw_decode.value(settings.hw_decode);
+        if ui
+            .checkbox(&mut hw_decode, "硬件解码")
+            .on_hover_text("优先使用 GPU 解码视频，不支持时自动回退到软件解码")
+            .changed()
+        {
+            self.hw_decode.edit(settings.hw_decode, hw_decode);
+        }
+        if self.hw_decode.is_pending() {
+            ui.label(hint("应用后将以当前位置重新打开正在播放的文件，播放会短暂中断"));
+            ui.horizontal(|ui| {
+                if ui.button("应用").clicked() {
+                    if let Some(value) = self.hw_decode.take() {
+                        settings.hw_decode = value;
+                        changes.rebuild_pipeline = true;
+                    }
+                }
+                if ui.button("取消").clicked() {
+                    self.hw_decode.discard();
+                }
+            });
+        }
+        changes.playback |= ui
+            .add_enabled(settings.hw_decode, egui::Checkbox::new(&mut settings.hw_decode_always_retry, "总是重试硬件解码"))
+            .on_hover_text("忽略之前记录的硬解失败，打开文件时总是先尝试硬件解码")
+            .changed();
+
+        ui.horizontal(|ui| {
+            changes.playback |= ui
+                .add(
+                    egui::DragValue::new(&mut settings.max_frame_dimension)
+                        .clamp_range(MAX_FRAME_DIMENSION_RANGE)
+                        .suffix(" px")
+                )
+                .on_hover_text(format!("宽或高超过该值（或小于 {} 像素）的视频帧按损坏处理", MIN_FRAME_DIMENSION))
+                .changed();
+            ui.label("帧尺寸上限");
+        });
+        ui.horizontal(|ui| {
+            changes.playback |= ui
+                .add(
+                    egui::DragValue::new(&mut settings.first_frame_timeout_secs)
+                        .clamp_range(FIRST_FRAME_TIMEOUT_RANGE)
+                        .suffix(" 秒")
+                )
+                .on_hover_text("视频播放超过该时间仍没有解码出画面时提示原因，并可改为只播放音频")
+                .changed();
+            ui.label("首帧期限");
+        });
+
+        ui.separator();
+        ui.label("音画同步");
+        let overrides = &mut settings.sync_overrides;
+        for (label, value, effective, tip) in [
+            ("更新间隔", &mut overrides.update_threshold_ms, sync_tuning.update_threshold_ms, "同步良好时两次取帧的最小间隔（自动 = 帧时长）"),
+            ("追赶阈值", &mut overrides.catch_up_threshold_ms, sync_tuning.catch_up_threshold_ms, "画面落后超过该值时加快取帧（自动 = 2 倍帧时长）"),
+            ("跳帧阈值", &mut overrides.jump_threshold_ms, sync_tuning.jump_threshold_ms, "画面落后超过该值时丢弃过期帧（自动 = 4 倍帧时长）"),
+        ] {
+            ui.horizontal(|ui| {
+                let mut edited = value.unwrap_or(effective);
+                let suffix = if value.is_some() { " ms（手动）" } else { " ms（自动）" };
+                if ui
+                    .add(egui::DragValue::new(&mut edited).clamp_range(THRESHOLD_RANGE_MS).suffix(suffix))
+                    .on_hover_text(tip)
+                    .changed()
+                {
+                    *value = Some(edited);
+                    changes.sync_tuning = true;
+                }
+                ui.label(label);
+            });
+        }
+        if ui.add_enabled(!overrides.is_empty(), egui::Button::new("恢复自动").small()).clicked() {
+            *overrides = SyncOverrides::default();
+            changes.sync_tuning = true;
+        }
+    }
+}
+
+fn playback_section(ui: &mut Ui, settings: &mut UserSettings, changes: &mut DrawerChanges) {
+    ui.horizontal(|ui| {
+        changes.playback |= ui.checkbox(&mut settings.repeat_one, "单曲循环").changed();
+        changes.playback |= ui.checkbox(&mut settings.repeat_playlist, "列表循环").changed();
+        changes.playback |= ui
+            .add(
+                egui::DragValue::new(&mut settings.seamless_loop_limit_secs)
+                    .clamp_range(0..=600)
+                    .suffix(" 秒")
+            )
+            .on_hover_text("不超过该时长的本地文件无缝循环（循环点没有停顿和闪烁），更长的文件回到开头重新播放")
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("跳转步长").on_hover_text("← / → 使用小步，Alt 中步，Ctrl 大步；快速连按时合并为一次跳转（Shift+← / → 跳到关键帧）");
+        for (secs, tip) in [
+            (&mut settings.seek_steps.small_secs, "小步"),
+            (&mut settings.seek_steps.medium_secs, "中步"),
+            (&mut settings.seek_steps.large_secs, "大步"),
+        ] {
+            ui.add(egui::DragValue::new(secs).clamp_range(SEEK_STEP_RANGE_SECS).suffix(" 秒")).on_hover_text(tip);
+        }
+    });
+    ui.add_enabled(display_mode::SUPPORTED, egui::Checkbox::new(&mut settings.match_refresh_rate, "自动匹配刷新率"))
+        .on_hover_text("全屏播放时把显示器切换到与视频帧率匹配的刷新率（如 23.976 fps → 24Hz），退出全屏时恢复")
+        .on_disabled_hover_text("当前平台不支持切换刷新率");
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        let folder_text = settings.watch_folder_path.as_deref().unwrap_or("未选择");
+        ui.label(format!("监视文件夹: {}", folder_text));
+        if ui.small_button("选择…").clicked() {
+            if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                settings.watch_folder_path = Some(folder.to_string_lossy().to_string());
+                changes.watch_folder = true;
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        changes.watch_folder |= ui.checkbox(&mut settings.watch_folder_enabled, "启用").changed();
+        ui.checkbox(&mut settings.watch_folder_preempt, "抢占模式")
+            .on_hover_text("新文件出现时立即切换播放，而不是加入队列");
+    });
+}
+
+fn network_section(ui: &mut Ui, settings: &mut UserSettings, changes: &mut DrawerChanges) {
+    changes.playback |= ui
+        .checkbox(&mut settings.timeshift_enabled, "直播时移")
+        .on_hover_text("观看直播时把直播流录到临时文件：暂停后从暂停处继续，可以在录制范围内回看，按 End 回到直播")
+        .changed();
+    ui.add_enabled_ui(settings.timeshift_enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("最多保留");
+            changes.playback |= ui
+                .add(egui::DragValue::new(&mut settings.timeshift_minutes).clamp_range(TIMESHIFT_MINUTES_RANGE).suffix(" 分钟"))
+                .changed();
+            changes.playback |= ui
+                .add(egui::DragValue::new(&mut settings.timeshift_max_mb).clamp_range(TIMESHIFT_MB_RANGE).suffix(" MB"))
+                .on_hover_text("超出任一上限时丢弃最早录制的内容（下次打开直播时生效）")
+                .changed();
+        });
+    });
+}
+
+fn subtitle_section(ui: &mut Ui, settings: &mut UserSettings, changes: &mut DrawerChanges) {
+    changes.playback |= ui
+        .checkbox(&mut settings.subtitles_enabled, "默认显示字幕")
+        .on_hover_text("打开没有记忆字幕选择的文件时自动选择第一条字幕")
+        .changed();
+    changes.playback |= ui
+        .checkbox(&mut settings.auto_forced_subtitles, "字幕关闭时显示强制字幕")
+        .on_hover_text("打开文件时，如果存在与音频语言一致的强制字幕，即使字幕关闭也自动选择")
+        .changed();
+    ui.checkbox(&mut settings.adaptive_subtitle_backdrop, "字幕背景随画面亮度调整")
+        .on_hover_text("画面越亮背景越深，极亮的画面改用浅色背景和深色文字");
+    ui.add_enabled(
+        !settings.adaptive_subtitle_backdrop,
+        egui::Slider::new(&mut settings.subtitle_backdrop_alpha, 0..=255).text("背景不透明度"),
+    );
+    ui.add(
+        egui::Slider::new(&mut settings.subtitle_scale, SUBTITLE_SCALE_RANGE)
+            .step_by(0.05)
+            .custom_formatter(|value, _| format!("{:.0}%", value * 100.0))
+            .text("字幕大小"),
+    );
+    changes.playback |= ui
+        .add(
+            egui::Slider::new(&mut settings.subtitle_offset_ms, -SUBTITLE_OFFSET_RANGE_MS..=SUBTITLE_OFFSET_RANGE_MS)
+                .step_by(50.0)
+                .suffix(" ms")
+                .text("默认字幕延迟"),
+        )
+        .on_hover_text("打开新文件时的字幕延迟（正值推后显示）；播放中用 Z / X 调整只影响当前文件")
+        .changed();
+}
+
+fn snapshot_section(ui: &mut Ui, settings: &mut UserSettings) {
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("settings_snapshot_format")
+            .selected_text(settings.snapshot_format.label())
+            .show_ui(ui, |ui| {
+                for format in SnapshotFormat::ALL {
+                    ui.selectable_value(&mut settings.snapshot_format, format, format.label());
+                }
+            });
+        ui.label("格式");
+    });
+    ui.add_enabled(
+        settings.snapshot_format == SnapshotFormat::Jpeg,
+        egui::Slider::new(&mut settings.snapshot_jpeg_quality, JPEG_QUALITY_RANGE).text("JPEG 质量"),
+    );
+
+    let dir = snapshot::snapshot_dir(settings.snapshot_dir.as_deref());
+    ui.label(format!("保存位置: {}", dir.display()));
+    ui.horizontal(|ui| {
+        if ui.small_button("选择…").clicked() {
+            if let Some(folder) = rfd::FileDialog::new().set_directory(&dir).pick_folder() {
+                settings.snapshot_dir = Some(folder.to_string_lossy().to_string());
+            }
+        }
+        if ui.add_enabled(settings.snapshot_dir.is_some(), egui::Button::new("默认").small()).clicked() {
+            settings.snapshot_dir = None;
+        }
+        if ui.small_button("打开截图文件夹").clicked() {
+            let opened = std::fs::create_dir_all(&dir).and_then(|_| file_manager::open_folder(&dir));
+            if let Err(e) = opened {
+                warn!("打开截图文件夹失败: {}", e);
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut settings.snapshot_template).desired_width(150.0))
+            .on_hover_text("{name} 媒体名称，{pos} 播放位置，{datetime} 截图时间（UTC），{n} 序号（连拍帧序号）");
+        ui.label("文件名");
+        if ui.add_enabled(settings.snapshot_template != DEFAULT_TEMPLATE, egui::Button::new("默认").small()).clicked() {
+            settings.snapshot_template = DEFAULT_TEMPLATE.to_string();
+        }
+    });
+    ui.checkbox(&mut settings.snapshot_with_subtitles, "截图包含字幕")
+        .on_hover_text("按 S 截图时把当前显示的字幕烧录到图片中（Ctrl+S 总是包含字幕，连拍不包含字幕）");
+    ui.label(hint(&format!("按住 Shift+S 连拍：最长 {} 秒、最多 {} 帧", BURST_DURATION.as_secs(), BURST_MAX_FRAMES)));
+}
+
+fn interface_section(ui: &mut Ui, settings: &mut UserSettings, changes: &mut DrawerChanges) {
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("settings_theme")
+            .selected_text(settings.theme.label())
+            .show_ui(ui, |ui| {
+                for theme in UiTheme::ALL {
+                    ui.selectable_value(&mut settings.theme, theme, theme.label());
+                }
+            });
+        ui.label("主题");
+    });
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("settings_fit_mode")
+            .selected_text(settings.fit_mode.label())
+            .show_ui(ui, |ui| {
+                for mode in FitMode::ALL {
+                    ui.selectable_value(&mut settings.fit_mode, mode, mode.label());
+                }
+            });
+        ui.label("画面缩放").on_hover_text("Shift+A 循环切换；Ctrl+滚轮缩放画面，放大后拖动画面平移");
+    });
+    ui.horizontal(|ui| {
+        changes.osd_preview |= ui
+            .add(
+                egui::Slider::new(&mut settings.osd_scale, OSD_SCALE_RANGE)
+                    .step_by(0.1)
+                    .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+            )
+            .on_hover_text("屏幕提示、直播标记和信息面板的大小（远距离观看电视时调大）")
+            .changed();
+        ui.label("屏幕提示大小");
+    });
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("settings_osd_anchor")
+            .selected_text(settings.osd_anchor.label())
+            .show_ui(ui, |ui| {
+                for anchor in OsdAnchor::ALL {
+                    changes.osd_preview |= ui.selectable_value(&mut settings.osd_anchor, anchor, anchor.label()).changed();
+                }
+            });
+        ui.label("屏幕提示位置");
+    });
+    ui.checkbox(&mut settings.progress_follows_frame, "进度条跟随画面");
+    ui.checkbox(&mut settings.chapter_shading, "显示章节底纹");
+    ui.checkbox(&mut settings.click_to_pause, "单击画面播放/暂停").on_hover_text("双击画面总是切换全屏");
+    changes.playback |= ui
+        .checkbox(&mut settings.read_sidecar_titles, "读取 .nfo 文件中的标题")
+        .on_hover_text("文件没有内嵌标题时，使用同目录 .nfo 文件中的标题代替文件名显示")
+        .changed();
+}
+
+/// 灰色的小号说明文字
+fn hint(text: &str) -> RichText {
+    RichText::new(text).size(11.0).color(egui::Color32::GRAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staged_setting_applies_only_on_take() {
+        let mut staged = Staged::default();
+        assert!(staged.value(true));
+
+        staged.edit(true, false);
+        assert!(staged.is_pending());
+        assert!(!staged.value(true));
+
+        // 改回已生效的值：没有待应用的修改
+        staged.edit(true, true);
+        assert!(!staged.is_pending());
+
+        staged.edit(true, false);
+        assert_eq!(staged.take(), Some(false));
+        assert!(!staged.is_pending());
+
+        staged.edit(true, false);
+        staged.discard();
+        assert!(staged.value(true));
+    }
+
+    #[test]
+    fn test_drawer_toggles_and_escape_closes() {
+        let mut drawer = SettingsDrawer::default();
+        assert!(!drawer.close());
+        drawer.toggle();
+        assert!(drawer.open);
+        assert!(drawer.close());
+        assert!(!drawer.open);
+    }
+
+    #[test]
+    fn test_closed_drawer_leaves_settings_untouched() {
+        let ctx = Context::default();
+        let mut drawer = SettingsDrawer::default();
+        let mut settings = UserSettings::default();
+        for open in [false, true] {
+            if open {
+                drawer.toggle();
+            }
+            let mut changes = DrawerChanges::default();
+            let _ = ctx.run(egui::RawInput::default(), |ctx| {
+                changes = drawer.show(ctx, &mut settings, SyncTuning::default());
+            });
+            // 没有交互时不产生修改，展开状态保持默认
+            assert_eq!(changes, DrawerChanges::default());
+            assert_eq!(settings.expanded_sections, vec![SettingsSection::Playback]);
+        }
+        assert!(drawer.open);
+    }
+}
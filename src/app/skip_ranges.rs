@@ -0,0 +1,460 @@
+// 自动跳过片头 / 片尾
+//
+// 剧集每一集的片头通常相同：在一集中标记一次，同一文件夹（按父目录区分剧集）下的其他文件都会自动跳过。
+// 单个文件可以单独设置，覆盖文件夹的设置。
+// - 片头：一段范围（开始, 结束），播放进入范围时跳到结束位置
+// - 片尾：一个开始位置，播放到这里时跳到文件末尾（播放队列中的下一集随即开始）
+//
+// 规则：
+// - 每个范围在一次播放中最多处理一次（跳过后手动回退到片头不会被再次弹开）
+// - 新标记的范围尚未确认：第一次进入时只提示"跳过片头"，用户点击跳过后确认，之后自动跳过
+// - 自动跳过后的屏幕提示在 UNDO_WINDOW 内可以点击撤销（回到跳过前的位置）
+//
+// 记录保存在用户数据目录的 skip_ranges.json 中。
+
+use super::user_data::{user_data_dir, write_atomic};
+use myy_player::{PlayerError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 自动跳过后可以撤销的时长
+pub const UNDO_WINDOW: Duration = Duration::from_secs(5);
+
+/// 进入范围时距离范围结束不足该时长则不再跳过（跳过几乎没有意义）
+const MIN_REMAINING_MS: i64 = 1_000;
+
+/// 范围类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipKind {
+    Intro,
+    Outro,
+}
+
+impl SkipKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SkipKind::Intro => "片头",
+            SkipKind::Outro => "片尾",
+        }
+    }
+}
+
+/// 一部剧集（或单个文件）的跳过范围
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkipRanges {
+    pub intro_start: Option<i64>,  // 片头开始（未标记时从 0 开始）
+    pub intro_end: Option<i64>,    // 片头结束
+    pub outro_start: Option<i64>,  // 片尾开始位置（结束于文件末尾）
+    pub intro_confirmed: bool,
+    pub outro_confirmed: bool,
+}
+
+impl SkipRanges {
+    /// 某类范围的（开始, 结束）；片尾需要知道文件时长
+    pub fn range(&self, kind: SkipKind, duration_ms: i64) -> Option<(i64, i64)> {
+        match kind {
+            SkipKind::Intro => {
+                let start = self.intro_start.unwrap_or(0);
+                self.intro_end.filter(|&end| end > start).map(|end| (start, end))
+            }
+            SkipKind::Outro => self.outro_start.filter(|&start| duration_ms > start).map(|start| (start, duration_ms)),
+        }
+    }
+
+    pub fn is_confirmed(&self, kind: SkipKind) -> bool {
+        match kind {
+            SkipKind::Intro => self.intro_confirmed,
+            SkipKind::Outro => self.outro_confirmed,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intro_start.is_none() && self.intro_end.is_none() && self.outro_start.is_none()
+    }
+
+    /// 设置片头开始（已标记的结束位置早于新的开始时清除结束位置）
+    pub fn set_intro_start(&mut self, start_ms: i64) {
+        self.intro_start = Some(start_ms.max(0));
+        self.intro_end = self.intro_end.filter(|&end| end > start_ms);
+        self.intro_confirmed = false;
+    }
+
+    /// 设置片头结束（已标记的开始位置晚于新的结束时从 0 开始）
+    pub fn set_intro_end(&mut self, end_ms: i64) {
+        self.intro_start = self.intro_start.filter(|&start| start < end_ms);
+        self.intro_end = Some(end_ms);
+        self.intro_confirmed = false;
+    }
+
+    pub fn set_outro_start(&mut self, start_ms: i64) {
+        self.outro_start = Some(start_ms.max(0));
+        self.outro_confirmed = false;
+    }
+
+    pub fn clear(&mut self, kind: SkipKind) {
+        match kind {
+            SkipKind::Intro => {
+                self.intro_start = None;
+                self.intro_end = None;
+                self.intro_confirmed = false;
+            }
+            SkipKind::Outro => {
+                self.outro_start = None;
+                self.outro_confirmed = false;
+            }
+        }
+    }
+
+    fn confirm(&mut self, kind: SkipKind) {
+        match kind {
+            SkipKind::Intro => self.intro_confirmed = true,
+            SkipKind::Outro => self.outro_confirmed = true,
+        }
+    }
+}
+
+/// 在当前播放位置标记的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipMark {
+    IntroStart,
+    IntroEnd,
+    /// 快捷操作：片头从 0 开始，到当前位置结束
+    IntroFromZero,
+    OutroStart,
+}
+
+impl SkipMark {
+    pub fn kind(self) -> SkipKind {
+        match self {
+            SkipMark::IntroStart | SkipMark::IntroEnd | SkipMark::IntroFromZero => SkipKind::Intro,
+            SkipMark::OutroStart => SkipKind::Outro,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SkipMark::IntroStart => "片头开始",
+            SkipMark::IntroEnd | SkipMark::IntroFromZero => "片头结束",
+            SkipMark::OutroStart => "片尾开始",
+        }
+    }
+
+    pub fn apply(self, ranges: &mut SkipRanges, position_ms: i64) {
+        match self {
+            SkipMark::IntroStart => ranges.set_intro_start(position_ms),
+            SkipMark::IntroEnd => ranges.set_intro_end(position_ms),
+            SkipMark::IntroFromZero => {
+                ranges.intro_start = None;
+                ranges.set_intro_end(position_ms);
+            }
+            SkipMark::OutroStart => ranges.set_outro_start(position_ms),
+        }
+    }
+}
+
+/// 文件生效的范围来自哪里
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipScope {
+    /// 同一文件夹下的所有文件
+    Folder,
+    /// 文件单独设置（覆盖文件夹的设置）
+    File,
+}
+
+/// 所有剧集的跳过范围（按文件夹和按文件两级）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkipRangeStore {
+    folders: BTreeMap<String, SkipRanges>,
+    files: BTreeMap<String, SkipRanges>,
+}
+
+/// 剧集的键：文件的父目录
+fn folder_key(path: &str) -> Option<String> {
+    let parent = Path::new(path).parent()?;
+    (!parent.as_os_str().is_empty()).then(|| parent.to_string_lossy().into_owned())
+}
+
+impl SkipRangeStore {
+    /// 记录文件的默认位置（用户数据目录下）
+    pub fn default_file() -> PathBuf {
+        user_data_dir().join("skip_ranges.json")
+    }
+
+    /// 读取记录（文件不存在时为空）
+    pub fn load(file: &Path) -> Result<Self> {
+        let json = match fs::read_to_string(file) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&json)
+            .map_err(|e| PlayerError::ConfigError(format!("片头片尾记录格式错误 ({}): {}", file.display(), e)))
+    }
+
+    /// 保存记录（原子写入）
+    pub fn save(&self, file: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(|e| PlayerError::ConfigError(format!("序列化失败: {}", e)))?;
+        Ok(write_atomic(file, &json)?)
+    }
+
+    /// 文件生效的范围和它的保存位置（文件单独设置优先）
+    pub fn resolve(&self, path: &str) -> Option<(SkipScope, &SkipRanges)> {
+        if let Some(ranges) = self.files.get(path) {
+            return Some((SkipScope::File, ranges));
+        }
+        self.folders.get(&folder_key(path)?).map(|ranges| (SkipScope::Folder, ranges))
+    }
+
+    /// 文件是否单独设置（不使用文件夹的范围）
+    pub fn has_file_override(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+
+    /// 开启时以文件夹当前的范围作为文件自己的设置；关闭时删除文件的设置，恢复使用文件夹的范围
+    pub fn set_file_override(&mut self, path: &str, enabled: bool) {
+        if !enabled {
+            self.files.remove(path);
+        } else if !self.has_file_override(path) {
+            let inherited = folder_key(path).and_then(|key| self.folders.get(&key)).cloned().unwrap_or_default();
+            self.files.insert(path.to_string(), inherited);
+        }
+    }
+
+    /// 修改文件生效的范围（有单独设置时修改文件，否则修改文件夹；文件夹的范围为空时删除该条目）
+    pub fn update(&mut self, path: &str, edit: impl FnOnce(&mut SkipRanges)) {
+        if let Some(ranges) = self.files.get_mut(path) {
+            edit(ranges);
+            return;
+        }
+        let Some(key) = folder_key(path) else {
+            return;
+        };
+        let ranges = self.folders.entry(key.clone()).or_default();
+        edit(ranges);
+        if ranges.is_empty() {
+            self.folders.remove(&key);
+        }
+    }
+
+    /// 用户手动跳过了尚未确认的范围：确认后自动跳过
+    pub fn confirm(&mut self, path: &str, kind: SkipKind) {
+        if self.resolve(path).is_some() {
+            self.update(path, |ranges| ranges.confirm(kind));
+        }
+    }
+}
+
+/// 播放进入跳过范围时的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipEvent {
+    /// 已确认的范围：跳到 to_ms（from_ms 是跳过前的位置，用于撤销）
+    Skip { kind: SkipKind, from_ms: i64, to_ms: i64 },
+    /// 尚未确认的范围：提示用户手动跳过
+    Offer { kind: SkipKind, to_ms: i64 },
+}
+
+/// 一次播放中的跳过状态（打开文件时重置）
+#[derive(Debug, Default)]
+pub struct SkipTracker {
+    intro_handled: bool,
+    outro_handled: bool,
+}
+
+impl SkipTracker {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 标记某个范围已处理（例如刚在当前位置标记了片头结束）
+    pub fn mark_handled(&mut self, kind: SkipKind) {
+        match kind {
+            SkipKind::Intro => self.intro_handled = true,
+            SkipKind::Outro => self.outro_handled = true,
+        }
+    }
+
+    fn is_handled(&self, kind: SkipKind) -> bool {
+        match kind {
+            SkipKind::Intro => self.intro_handled,
+            SkipKind::Outro => self.outro_handled,
+        }
+    }
+
+    /// 检查播放位置是否进入了尚未处理的范围（包括 seek 直接落在范围内）
+    pub fn check(&mut self, ranges: &SkipRanges, position_ms: i64, duration_ms: i64) -> Option<SkipEvent> {
+        for kind in [SkipKind::Intro, SkipKind::Outro] {
+            if self.is_handled(kind) {
+                continue;
+            }
+            let Some((start, end)) = ranges.range(kind, duration_ms) else {
+                continue;
+            };
+            if position_ms < start || position_ms > end - MIN_REMAINING_MS {
+                continue;
+            }
+            self.mark_handled(kind);
+            return Some(if ranges.is_confirmed(kind) {
+                SkipEvent::Skip { kind, from_ms: position_ms, to_ms: end }
+            } else {
+                SkipEvent::Offer { kind, to_ms: end }
+            });
+        }
+        None
+    }
+}
+
+/// 屏幕上的跳过提示（自动跳过后可撤销，未确认时可手动跳过）
+#[derive(Debug, Clone, Copy)]
+pub struct SkipNotice {
+    pub event: SkipEvent,
+    pub shown_at: Instant,
+}
+
+impl SkipNotice {
+    pub fn new(event: SkipEvent, now: Instant) -> Self {
+        Self { event, shown_at: now }
+    }
+
+    /// 提示是否仍然显示（撤销 / 手动跳过仍然可用）
+    pub fn is_active(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.shown_at) < UNDO_WINDOW
+    }
+
+    /// 提示文字
+    pub fn text(&self) -> String {
+        match self.event {
+            SkipEvent::Skip { kind, .. } => format!("已跳过{}", kind.name()),
+            SkipEvent::Offer { kind, .. } => format!("检测到标记的{}", kind.name()),
+        }
+    }
+
+    /// 可点击的操作
+    pub fn action_label(&self) -> String {
+        match self.event {
+            SkipEvent::Skip { .. } => "点击撤销".to_string(),
+            SkipEvent::Offer { kind, .. } => format!("跳过{}", kind.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DURATION: i64 = 24 * 60_000;
+
+    fn intro(start: i64, end: i64, confirmed: bool) -> SkipRanges {
+        SkipRanges { intro_start: Some(start), intro_end: Some(end), intro_confirmed: confirmed, ..Default::default() }
+    }
+
+    #[test]
+    fn test_range_entry_fires_once() {
+        let ranges = intro(30_000, 120_000, true);
+        let mut tracker = SkipTracker::default();
+        assert_eq!(tracker.check(&ranges, 29_900, DURATION), None);
+        assert_eq!(
+            tracker.check(&ranges, 30_000, DURATION),
+            Some(SkipEvent::Skip { kind: SkipKind::Intro, from_ms: 30_000, to_ms: 120_000 })
+        );
+        // 手动回退到片头不会再次跳过
+        assert_eq!(tracker.check(&ranges, 31_000, DURATION), None);
+
+        tracker.reset();
+        assert!(tracker.check(&ranges, 31_000, DURATION).is_some());
+    }
+
+    #[test]
+    fn test_seek_into_range_and_range_at_zero() {
+        // seek 直接落在范围中间
+        let ranges = intro(30_000, 120_000, true);
+        let mut tracker = SkipTracker::default();
+        assert_eq!(
+            tracker.check(&ranges, 75_000, DURATION),
+            Some(SkipEvent::Skip { kind: SkipKind::Intro, from_ms: 75_000, to_ms: 120_000 })
+        );
+
+        // 快到范围结束时不再跳过，也不算处理过
+        let mut tracker = SkipTracker::default();
+        assert_eq!(tracker.check(&ranges, 119_500, DURATION), None);
+        assert_eq!(tracker.check(&ranges, 121_000, DURATION), None);
+        assert!(tracker.check(&ranges, 60_000, DURATION).is_some());
+
+        // 从 0 开始的片头：开始播放时立即跳过
+        let ranges = intro(0, 90_000, true);
+        let mut tracker = SkipTracker::default();
+        assert!(matches!(tracker.check(&ranges, 0, DURATION), Some(SkipEvent::Skip { to_ms: 90_000, .. })));
+    }
+
+    #[test]
+    fn test_unconfirmed_range_is_offered_and_outro_skips_to_end() {
+        let mut ranges = intro(0, 90_000, false);
+        ranges.outro_start = Some(DURATION - 90_000);
+        let mut tracker = SkipTracker::default();
+        assert_eq!(tracker.check(&ranges, 1_000, DURATION), Some(SkipEvent::Offer { kind: SkipKind::Intro, to_ms: 90_000 }));
+        assert_eq!(tracker.check(&ranges, 2_000, DURATION), None);
+        assert_eq!(tracker.check(&ranges, DURATION - 60_000, DURATION), Some(SkipEvent::Offer { kind: SkipKind::Outro, to_ms: DURATION }));
+
+        // 时长未知时不处理片尾
+        let mut tracker = SkipTracker::default();
+        tracker.mark_handled(SkipKind::Intro);
+        assert_eq!(tracker.check(&ranges, DURATION - 60_000, 0), None);
+    }
+
+    #[test]
+    fn test_undo_window() {
+        let shown_at = Instant::now();
+        let notice = SkipNotice::new(SkipEvent::Skip { kind: SkipKind::Intro, from_ms: 0, to_ms: 90_000 }, shown_at);
+        assert_eq!(notice.text(), "已跳过片头");
+        assert!(notice.is_active(shown_at));
+        assert!(notice.is_active(shown_at + UNDO_WINDOW - Duration::from_millis(1)));
+        assert!(!notice.is_active(shown_at + UNDO_WINDOW));
+    }
+
+    #[test]
+    fn test_file_override_and_confirmation() {
+        let mut store = SkipRangeStore::default();
+        let episode1 = "/tv/show/s01e01.mkv";
+        let episode2 = "/tv/show/s01e02.mkv";
+        store.update(episode1, |ranges| SkipMark::IntroEnd.apply(ranges, 85_000));
+        let (scope, ranges) = store.resolve(episode2).unwrap();
+        assert_eq!((scope, ranges.range(SkipKind::Intro, 0), ranges.intro_confirmed), (SkipScope::Folder, Some((0, 85_000)), false));
+
+        store.confirm(episode2, SkipKind::Intro);
+        assert!(store.resolve(episode1).unwrap().1.intro_confirmed);
+
+        // 单个文件的设置覆盖文件夹（从文件夹的范围开始修改）
+        store.set_file_override(episode2, true);
+        store.update(episode2, |ranges| {
+            ranges.clear(SkipKind::Intro);
+            SkipMark::OutroStart.apply(ranges, 20 * 60_000);
+        });
+        let (scope, ranges) = store.resolve(episode2).unwrap();
+        assert_eq!((scope, ranges.range(SkipKind::Intro, 0), ranges.outro_start), (SkipScope::File, None, Some(20 * 60_000)));
+        assert_eq!(store.resolve(episode1).unwrap().1.range(SkipKind::Intro, 0), Some((0, 85_000)));
+        assert_eq!(store.resolve("/tv/other/e01.mkv"), None);
+
+        store.set_file_override(episode2, false);
+        assert_eq!(store.resolve(episode2).unwrap().0, SkipScope::Folder);
+
+        // 文件夹的范围清空后删除条目
+        store.update(episode1, |ranges| ranges.clear(SkipKind::Intro));
+        assert_eq!(store.resolve(episode2), None);
+        // 先标记开始、再标记结束
+        store.update(episode1, |ranges| SkipMark::IntroStart.apply(ranges, 10_000));
+        assert_eq!(store.resolve(episode2).unwrap().1.range(SkipKind::Intro, 0), None);
+        store.update(episode1, |ranges| SkipMark::IntroEnd.apply(ranges, 70_000));
+        assert_eq!(store.resolve(episode2).unwrap().1.range(SkipKind::Intro, 0), Some((10_000, 70_000)));
+        store.update(episode1, |ranges| SkipMark::IntroFromZero.apply(ranges, 80_000));
+        assert_eq!(store.resolve(episode2).unwrap().1.range(SkipKind::Intro, 0), Some((0, 80_000)));
+
+        let file = std::env::temp_dir().join(format!("myy_player_skip_ranges_{}.json", std::process::id()));
+        store.save(&file).unwrap();
+        assert_eq!(SkipRangeStore::load(&file).unwrap(), store);
+        let _ = fs::remove_file(&file);
+    }
+}
@@ -0,0 +1,511 @@
+// 视频截图（S：当前画面；Ctrl+S：带字幕；按住 Shift+S：连拍）
+//
+// 截图保存解码后的原始帧（按显示方向旋转，全分辨率，与窗口大小无关），屏幕提示和控制栏不会出现在截图中。
+// 带字幕截图把界面上正在显示的字幕布局（位置、字号、背景、描边）从屏幕坐标换算到画面像素坐标，
+// 用界面相同的字体在 CPU 上栅格化：按画面像素的字号重新排版，从 egui 的字体图集读取字形覆盖率，
+// 混合到帧数据上。没有正在显示的字幕时退回普通截图。
+//
+// 截图格式（PNG / JPEG / WebP 无损）、保存目录（默认 图片/myy_player）和文件名模板在设置中调整，
+// 模板中的标记：{name} 媒体名称，{pos} 播放位置，{datetime} 截图时间（UTC），{n} 序号（连拍时为帧序号）。
+// 展开后去掉文件名中不允许的字符；文件已存在时追加序号，不覆盖。
+
+use egui::{Color32, Context, FontId, Rect};
+use image::ImageEncoder;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use myy_player::VideoFrame;
+
+/// 默认文件名模板
+pub const DEFAULT_TEMPLATE: &str = "{name}_{pos}";
+
+/// JPEG 质量的可调范围
+pub const JPEG_QUALITY_RANGE: std::ops::RangeInclusive<u8> = 50..=100;
+
+/// 文件名中不允许的字符（Windows 最严格的规则，各平台统一）
+const ILLEGAL_FILENAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// 字幕堆叠底部与画面底边的最小距离（相对画面高度，字幕落在黑边里时上移）
+const BOTTOM_MARGIN_RATIO: f32 = 0.04;
+
+/// 界面上显示的字幕布局（带字幕截图按它换算到画面像素）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleLayout {
+    pub font_size: f32,
+    pub line_height: f32,
+    pub stroke_width: f32,
+    pub corner_radius: f32,
+    pub boxes: Vec<(Rect, Vec<String>)>,  // (背景框, 各行文本)
+    pub background: Color32,
+    pub text_color: Color32,
+    pub stroke_color: Color32,
+}
+
+impl SubtitleLayout {
+    /// 换算到画面像素坐标（display_rect 为画面在屏幕上的显示区域）
+    pub fn to_image_space(&self, display_rect: Rect, width: u32, height: u32) -> Self {
+        let (width, height) = (width as f32, height as f32);
+        let scale = egui::vec2(width / display_rect.width(), height / display_rect.height());
+        let map = |rect: Rect| {
+            Rect::from_min_max(
+                ((rect.min - display_rect.min) * scale).to_pos2(),
+                ((rect.max - display_rect.min) * scale).to_pos2(),
+            )
+        };
+        let mut boxes: Vec<(Rect, Vec<String>)> = self.boxes.iter().map(|(rect, lines)| (map(*rect), lines.clone())).collect();
+
+        // 字幕画在黑边里（窗口比画面高）时整体上移到画面内
+        let bottom = boxes.iter().map(|(rect, _)| rect.bottom()).fold(f32::MIN, f32::max);
+        let max_bottom = height * (1.0 - BOTTOM_MARGIN_RATIO);
+        if bottom > max_bottom {
+            let shift = egui::vec2(0.0, max_bottom - bottom);
+            for (rect, _) in &mut boxes {
+                *rect = rect.translate(shift);
+            }
+        }
+        for (rect, _) in &mut boxes {
+            *rect = rect.intersect(Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(width, height)));
+        }
+
+        Self {
+            font_size: self.font_size * scale.y,
+            line_height: self.line_height * scale.y,
+            stroke_width: self.stroke_width * scale.y,
+            corner_radius: self.corner_radius * scale.y,
+            boxes,
+            ..self.clone()
+        }
+    }
+}
+
+/// 按显示方向顺时针旋转帧（0/90/180/270）
+pub fn rotate_frame(frame: &VideoFrame, rotation: u32) -> VideoFrame {
+    let rotation = rotation % 360;
+    let (w, h) = (frame.width as usize, frame.height as usize);
+    let (out_w, out_h) = if rotation % 180 == 90 { (h, w) } else { (w, h) };
+    if rotation == 0 || frame.data.len() < w * h * 4 {
+        return frame.clone();
+    }
+    let mut data = vec![0u8; out_w * out_h * 4];
+    for y in 0..h {
+        for x in 0..w {
+            let (dx, dy) = match rotation {
+                90 => (h - 1 - y, x),
+                180 => (w - 1 - x, h - 1 - y),
+                _ => (y, w - 1 - x),
+            };
+            let src = (y * w + x) * 4;
+            let dst = (dy * out_w + dx) * 4;
+            data[dst..dst + 4].copy_from_slice(&frame.data[src..src + 4]);
+        }
+    }
+    VideoFrame { width: out_w as u32, height: out_h as u32, data: data.into(), ..frame.clone() }
+}
+
+/// 在帧上烧录字幕（layout 为画面像素坐标，与界面 render_subtitle 的绘制顺序一致）
+pub fn burn_subtitles(ctx: &Context, frame: &mut VideoFrame, layout: &SubtitleLayout) {
+    let mut canvas = Canvas { width: frame.width as usize, height: frame.height as usize, data: frame.data.make_mut() };
+    for (rect, _) in &layout.boxes {
+        canvas.fill_rounded_rect(*rect, layout.corner_radius, layout.background);
+    }
+
+    ctx.fonts(|fonts| {
+        // 字形在图集中按 字号 × pixels_per_point 栅格化：按画面像素字号排版，字形与画面像素一一对应
+        let pixels_per_point = fonts.pixels_per_point();
+        let font_id = FontId::proportional(layout.font_size / pixels_per_point);
+        let galleys: Vec<Vec<_>> = layout
+            .boxes
+            .iter()
+            .map(|(_, lines)| {
+                lines.iter().map(|line| fonts.layout_no_wrap(line.clone(), font_id.clone(), Color32::WHITE)).collect()
+            })
+            .collect();
+        let atlas = fonts.image();
+
+        for ((rect, lines), galleys) in layout.boxes.iter().zip(&galleys) {
+            let start_y = rect.center().y - (lines.len() as f32 - 1.0) * layout.line_height / 2.0;
+            for (i, galley) in galleys.iter().enumerate() {
+                let center = egui::pos2(rect.center().x, start_y + i as f32 * layout.line_height);
+                let origin = center - galley.size() * pixels_per_point / 2.0;
+                let stroke = layout.stroke_width;
+                for dx in [-stroke, 0.0, stroke] {
+                    for dy in [-stroke, 0.0, stroke] {
+                        if dx != 0.0 || dy != 0.0 {
+                            canvas.draw_galley(&atlas, galley, origin + egui::vec2(dx, dy), pixels_per_point, layout.stroke_color);
+                        }
+                    }
+                }
+                canvas.draw_galley(&atlas, galley, origin, pixels_per_point, layout.text_color);
+            }
+        }
+    });
+}
+
+/// RGBA 帧上的简单绘制（颜色为 egui 的预乘 alpha 颜色）
+struct Canvas<'a> {
+    width: usize,
+    height: usize,
+    data: &'a mut [u8],
+}
+
+impl Canvas<'_> {
+    /// 以 coverage（0.0 ~ 1.0）混合一个像素
+    fn blend(&mut self, x: i64, y: i64, color: Color32, coverage: f32) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height || coverage <= 0.0 {
+            return;
+        }
+        let index = (y as usize * self.width + x as usize) * 4;
+        let Some(pixel) = self.data.get_mut(index..index + 4) else {
+            return;
+        };
+        let coverage = coverage.min(1.0);
+        let alpha = color.a() as f32 / 255.0 * coverage;
+        for (channel, src) in pixel.iter_mut().zip([color.r(), color.g(), color.b()]) {
+            *channel = (src as f32 * coverage + *channel as f32 * (1.0 - alpha)).round().clamp(0.0, 255.0) as u8;
+        }
+        pixel[3] = 255;
+    }
+
+    fn fill_rounded_rect(&mut self, rect: Rect, radius: f32, color: Color32) {
+        let radius = radius.min(rect.width() / 2.0).min(rect.height() / 2.0).max(0.0);
+        for y in rect.top().floor() as i64..rect.bottom().ceil() as i64 {
+            for x in rect.left().floor() as i64..rect.right().ceil() as i64 {
+                // 像素中心到圆角内矩形的距离
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let dx = (rect.left() + radius - px).max(px - (rect.right() - radius)).max(0.0);
+                let dy = (rect.top() + radius - py).max(py - (rect.bottom() - radius)).max(0.0);
+                let coverage = (radius - (dx * dx + dy * dy).sqrt() + 0.5).clamp(0.0, 1.0);
+                self.blend(x, y, color, coverage);
+            }
+        }
+    }
+
+    /// 按字形在图集中的覆盖率绘制一行文本（origin 为排版区域左上角，像素坐标）
+    fn draw_galley(&mut self, atlas: &egui::FontImage, galley: &egui::Galley, origin: egui::Pos2, pixels_per_point: f32, color: Color32) {
+        for glyph in galley.rows.iter().flat_map(|row| &row.glyphs) {
+            let uv = glyph.uv_rect;
+            if uv.is_nothing() {
+                continue;
+            }
+            let left_top = origin + (glyph.pos.to_vec2() + uv.offset) * pixels_per_point;
+            let (x0, y0) = (left_top.x.round() as i64, left_top.y.round() as i64);
+            for ty in uv.min[1] as usize..uv.max[1] as usize {
+                for tx in uv.min[0] as usize..uv.max[0] as usize {
+                    let coverage = atlas.pixels.get(ty * atlas.size[0] + tx).copied().unwrap_or(0.0);
+                    let x = x0 + (tx - uv.min[0] as usize) as i64;
+                    let y = y0 + (ty - uv.min[1] as usize) as i64;
+                    self.blend(x, y, color, coverage);
+                }
+            }
+        }
+    }
+}
+
+/// 截图格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotFormat {
+    /// 无损
+    #[default]
+    Png,
+    /// 有损（质量可调，文件小）
+    Jpeg,
+    /// 无损（通常比 PNG 小）
+    WebP,
+}
+
+impl SnapshotFormat {
+    pub const ALL: [SnapshotFormat; 3] = [SnapshotFormat::Png, SnapshotFormat::Jpeg, SnapshotFormat::WebP];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SnapshotFormat::Png => "PNG（无损）",
+            SnapshotFormat::Jpeg => "JPEG",
+            SnapshotFormat::WebP => "WebP（无损）",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            SnapshotFormat::Png => "png",
+            SnapshotFormat::Jpeg => "jpg",
+            SnapshotFormat::WebP => "webp",
+        }
+    }
+}
+
+/// 截图输出设置（格式、质量、目录、文件名模板）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotOptions {
+    pub format: SnapshotFormat,
+    pub jpeg_quality: u8,
+    pub dir: PathBuf,
+    pub template: String,
+}
+
+/// 默认截图目录（用户图片目录下的 myy_player）
+pub fn default_snapshot_dir() -> PathBuf {
+    let pictures = std::env::var_os("XDG_PICTURES_DIR")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("USERPROFILE").map(|home| PathBuf::from(home).join("Pictures")))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Pictures")))
+        .unwrap_or_else(std::env::temp_dir);
+    pictures.join("myy_player")
+}
+
+/// 截图目录（未设置时为默认目录）
+pub fn snapshot_dir(configured: Option<&str>) -> PathBuf {
+    configured.filter(|dir| !dir.is_empty()).map(PathBuf::from).unwrap_or_else(default_snapshot_dir)
+}
+
+/// 文件名模板中可用的值
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateValues<'a> {
+    /// 媒体名称（本地文件为不带扩展名的文件名）
+    pub name: &'a str,
+    pub position_ms: i64,
+    /// 截图时间（Unix 时间戳，秒）
+    pub unix_secs: u64,
+    pub n: u32,
+}
+
+/// 展开文件名模板（不含扩展名，结果可直接用作文件名）
+pub fn expand_template(template: &str, values: &TemplateValues) -> String {
+    let expanded = template
+        .replace("{name}", values.name)
+        .replace("{pos}", &format_position(values.position_ms))
+        .replace("{datetime}", &format_datetime(values.unix_secs))
+        .replace("{n}", &format!("{:03}", values.n));
+    sanitize_filename(&expanded)
+}
+
+/// 去掉文件名中不允许的字符（路径分隔符、保留字符、控制字符），首尾的空格和点
+pub fn sanitize_filename(name: &str) -> String {
+    let cleaned: String = name.chars().filter(|c| !c.is_control() && !ILLEGAL_FILENAME_CHARS.contains(c)).collect();
+    let cleaned = cleaned.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    if cleaned.is_empty() {
+        "snapshot".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// 播放位置 `时-分-秒-毫秒`
+fn format_position(position_ms: i64) -> String {
+    let position_ms = position_ms.max(0);
+    format!(
+        "{:02}-{:02}-{:02}-{:03}",
+        position_ms / 3_600_000,
+        position_ms / 60_000 % 60,
+        position_ms / 1000 % 60,
+        position_ms % 1000
+    )
+}
+
+/// UTC 时间 `年-月-日_时-分-秒`
+fn format_datetime(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let secs = unix_secs % 86_400;
+
+    // 公历日期（Howard Hinnant 的 civil_from_days 算法）
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}_{:02}-{:02}-{:02}", year, month, day, secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// 当前 Unix 时间戳（秒）
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// 截图文件路径（目录下按模板命名；已存在时追加序号）
+pub fn snapshot_path(options: &SnapshotOptions, values: &TemplateValues) -> PathBuf {
+    let stem = expand_template(&options.template, values);
+    let extension = options.format.extension();
+    let mut path = options.dir.join(format!("{}.{}", stem, extension));
+    let mut n = 2;
+    while path.exists() {
+        path = options.dir.join(format!("{}_{}.{}", stem, n, extension));
+        n += 1;
+    }
+    path
+}
+
+/// 按格式编码并保存（目录不存在时创建）
+pub fn save(frame: &VideoFrame, path: &Path, format: SnapshotFormat, jpeg_quality: u8) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+    let (width, height) = (frame.width, frame.height);
+    match format {
+        SnapshotFormat::Png => {
+            image::codecs::png::PngEncoder::new(&mut writer).write_image(&frame.data, width, height, image::ColorType::Rgba8)?;
+        }
+        SnapshotFormat::Jpeg => {
+            // JPEG 没有 alpha 通道
+            let rgb: Vec<u8> = frame.data.chunks_exact(4).flat_map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect();
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut writer, jpeg_quality).encode(&rgb, width, height, image::ColorType::Rgb8)?;
+        }
+        SnapshotFormat::WebP => {
+            image::codecs::webp::WebPEncoder::new_lossless(&mut writer).encode(&frame.data, width, height, image::ColorType::Rgba8)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use myy_player::PixelFormat;
+
+    const BLUE: [u8; 4] = [20, 40, 200, 255];
+
+    fn solid_frame(width: u32, height: u32) -> VideoFrame {
+        let data = BLUE.repeat((width * height) as usize);
+        VideoFrame { pts: 0, duration: 40, width, height, format: PixelFormat::RGBA, planes: None, data: data.into() }
+    }
+
+    fn pixel(frame: &VideoFrame, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * frame.width + x) * 4) as usize;
+        frame.data[i..i + 4].try_into().unwrap()
+    }
+
+    fn layout(rect: Rect) -> SubtitleLayout {
+        SubtitleLayout {
+            font_size: 24.0,
+            line_height: 31.2,
+            stroke_width: 2.0,
+            corner_radius: 6.0,
+            boxes: vec![(rect, vec!["Hello 字幕".to_string()])],
+            background: Color32::from_black_alpha(128),
+            text_color: Color32::WHITE,
+            stroke_color: Color32::BLACK,
+        }
+    }
+
+    #[test]
+    fn test_cue_is_burned_into_expected_region() {
+        let ctx = Context::default();
+        let _ = ctx.run(Default::default(), |_| {});
+
+        let mut frame = solid_frame(320, 180);
+        let cue_rect = Rect::from_min_max(egui::pos2(40.0, 110.0), egui::pos2(280.0, 160.0));
+        burn_subtitles(&ctx, &mut frame, &layout(cue_rect));
+
+        // 字幕框外不变
+        assert_eq!(pixel(&frame, 5, 5), BLUE);
+        assert_eq!(pixel(&frame, 160, 60), BLUE);
+        assert_eq!(pixel(&frame, 160, 175), BLUE);
+        // 字幕框内：背景压暗，文字像素为白色
+        assert_eq!(pixel(&frame, 45, 150), [10, 20, 100, 255]);
+        let text_pixels = (110..160)
+            .flat_map(|y| (40..280).map(move |x| (x, y)))
+            .filter(|&(x, y)| pixel(&frame, x, y)[..3].iter().all(|&c| c > 220))
+            .count();
+        assert!(text_pixels > 50, "字幕区域内只有 {} 个文字像素", text_pixels);
+    }
+
+    #[test]
+    fn test_layout_is_mapped_to_image_pixels() {
+        // 1920x1080 的画面显示在 960x540 的区域中（偏移 100, 50）
+        let display = Rect::from_min_size(egui::pos2(100.0, 50.0), egui::vec2(960.0, 540.0));
+        let cue = Rect::from_min_max(egui::pos2(200.0, 400.0), egui::pos2(960.0, 460.0));
+        let mapped = layout(cue).to_image_space(display, 1920, 1080);
+        assert_eq!(mapped.boxes[0].0, Rect::from_min_max(egui::pos2(200.0, 700.0), egui::pos2(1720.0, 820.0)));
+        assert_eq!(mapped.font_size, 48.0);
+        assert_eq!(mapped.stroke_width, 4.0);
+
+        // 字幕在画面下方的黑边里：上移到画面内
+        let cue = Rect::from_min_max(egui::pos2(200.0, 600.0), egui::pos2(960.0, 660.0));
+        let mapped = layout(cue).to_image_space(display, 1920, 1080);
+        assert!(mapped.boxes[0].0.bottom() <= 1080.0 * (1.0 - BOTTOM_MARGIN_RATIO) + 0.01);
+        assert_eq!(mapped.boxes[0].0.height(), 120.0);
+    }
+
+    #[test]
+    fn test_rotate_frame() {
+        // 2x1：左红右绿
+        let frame = VideoFrame {
+            pts: 0,
+            duration: 0,
+            width: 2,
+            height: 1,
+            format: PixelFormat::RGBA,
+            planes: None,
+            data: vec![255, 0, 0, 255, 0, 255, 0, 255].into(),
+        };
+        let rotated = rotate_frame(&frame, 90);
+        assert_eq!((rotated.width, rotated.height), (1, 2));
+        assert_eq!(rotated.data[..4], [255, 0, 0, 255]);  // 顺时针 90°：左边转到上边
+        let rotated = rotate_frame(&frame, 270);
+        assert_eq!(rotated.data[..4], [0, 255, 0, 255]);
+        assert_eq!(*rotate_frame(&frame, 180).data, [0, 255, 0, 255, 255, 0, 0, 255]);
+        assert_eq!(rotate_frame(&frame, 0).data, frame.data);
+    }
+
+    fn values(name: &str) -> TemplateValues<'_> {
+        TemplateValues { name, position_ms: 3_723_045, unix_secs: 1_700_000_000, n: 7 }
+    }
+
+    #[test]
+    fn test_snapshot_path_does_not_overwrite() {
+        let dir = std::env::temp_dir().join(format!("myy_snapshot_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let options = SnapshotOptions {
+            format: SnapshotFormat::Png,
+            jpeg_quality: 90,
+            dir: dir.clone(),
+            template: DEFAULT_TEMPLATE.to_string(),
+        };
+
+        let first = snapshot_path(&options, &values("movie"));
+        assert_eq!(first, dir.join("movie_01-02-03-045.png"));
+        std::fs::write(&first, b"").unwrap();
+        assert_eq!(snapshot_path(&options, &values("movie")), dir.join("movie_01-02-03-045_2.png"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_each_format_round_trips_dimensions() {
+        let dir = std::env::temp_dir().join(format!("myy_snapshot_formats_{}", std::process::id()));
+        let frame = solid_frame(33, 17);
+        for format in SnapshotFormat::ALL {
+            let path = dir.join(format!("frame.{}", format.extension()));
+            save(&frame, &path, format, 85).unwrap();
+            let decoded = image::open(&path).unwrap().to_rgba8();
+            assert_eq!(decoded.dimensions(), (33, 17), "{:?}", format);
+            if format != SnapshotFormat::Jpeg {
+                assert_eq!(decoded.get_pixel(20, 10).0, BLUE, "{:?} 应为无损", format);
+            }
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_template_expansion_is_filesystem_safe() {
+        assert_eq!(
+            expand_template("{name} {pos} {datetime} #{n}", &values("movie")),
+            "movie 01-02-03-045 2023-11-14_22-13-20 #007"
+        );
+        // 名称和模板中的路径分隔符、保留字符、控制字符都被去掉
+        let expanded = expand_template("../{name}:<{n}>?", &values("a/b\\c|d*\"e\u{7}"));
+        assert_eq!(expanded, "abcde007");
+        assert!(!expanded.contains(ILLEGAL_FILENAME_CHARS));
+        // 展开为空（或只剩点和空格）时使用默认名称
+        assert_eq!(expand_template(" {name}. ", &values("//")), "snapshot");
+        assert_eq!(format_datetime(0), "1970-01-01_00-00-00");
+        assert_eq!(format_datetime(951_868_800), "2000-03-01_00-00-00");
+    }
+}
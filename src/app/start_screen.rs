@@ -0,0 +1,257 @@
+// 起始页（没有打开媒体时显示：快捷操作、最近播放、启动自检结果）
+//
+// 布局以 1280x720 为基准按窗口大小等比缩放，快捷操作为图标按钮（IconButton），所有入口都可用 Tab 切换焦点、Enter 触发。
+
+use egui::{Align2, Color32, FontId, Rect, RichText, Ui, Vec2};
+use std::path::Path;
+
+use super::ellipsis::middle_ellipsis;
+use super::icons::{Icon, IconAtlas, IconButton};
+use super::time_format::format_time;
+use myy_player::WatchState;
+
+/// 最多显示的最近播放文件数
+pub const MAX_RECENT_FILES: usize = 8;
+
+/// 布局基准尺寸
+const BASE_SIZE: Vec2 = Vec2::new(1280.0, 720.0);
+
+/// 缩放范围（800x600 时不小于 0.75，高分辨率大窗口时不超过 2.5）
+const MIN_SCALE: f32 = 0.75;
+const MAX_SCALE: f32 = 2.5;
+
+/// 最近播放网格的最大列数
+const MAX_COLUMNS: usize = 4;
+
+/// 起始页上触发的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartAction {
+    OpenFile,
+    OpenFolder,
+    OpenUrl,
+    ResumeRecent(String),
+    SetRecentFilter(RecentFilter),
+    /// 手动切换已看完标记
+    ToggleWatched(String),
+}
+
+/// 最近播放的文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentFile {
+    pub path: String,
+    pub title: Option<String>,  // 显示标题（没有时显示文件名）
+    pub position_ms: i64,
+    pub watch: WatchState,
+}
+
+/// 最近播放列表的筛选
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecentFilter {
+    #[default]
+    All,
+    Unfinished,
+    Watched,
+}
+
+impl RecentFilter {
+    pub const ALL: [RecentFilter; 3] = [RecentFilter::All, RecentFilter::Unfinished, RecentFilter::Watched];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RecentFilter::All => "全部",
+            RecentFilter::Unfinished => "未看完",
+            RecentFilter::Watched => "已看完",
+        }
+    }
+
+    pub fn matches(self, watch: WatchState) -> bool {
+        match self {
+            RecentFilter::All => true,
+            RecentFilter::Unfinished => watch != WatchState::Watched,
+            RecentFilter::Watched => watch == WatchState::Watched,
+        }
+    }
+}
+
+/// 最近播放格子中的第二行（已看完显示对勾，看了一部分时显示百分比）
+fn progress_text(file: &RecentFile) -> String {
+    match file.watch {
+        WatchState::Watched => "✔ 已看完".to_string(),
+        WatchState::Partial(fraction) => {
+            format!("上次播放到 {}（{:.0}%）", format_time(file.position_ms), fraction * 100.0)
+        }
+        WatchState::Unwatched => format!("上次播放到 {}", format_time(file.position_ms)),
+    }
+}
+
+/// 按可用区域计算缩放比例
+pub fn layout_scale(size: Vec2) -> f32 {
+    (size.x / BASE_SIZE.x).min(size.y / BASE_SIZE.y).clamp(MIN_SCALE, MAX_SCALE)
+}
+
+/// 最近播放网格的列数（每格至少 min_tile_width 宽）
+pub fn grid_columns(available_width: f32, min_tile_width: f32, count: usize) -> usize {
+    let fit = (available_width / min_tile_width).floor() as usize;
+    fit.clamp(1, MAX_COLUMNS).min(count.max(1))
+}
+
+/// 显示起始页，返回用户触发的操作（recent 为按 filter 筛选后的列表，没有任何播放记录时 filter 为 None）
+pub fn show(
+    ui: &mut Ui,
+    icons: &mut IconAtlas,
+    rect: Rect,
+    recent: &[RecentFile],
+    filter: Option<RecentFilter>,
+    capabilities: &str,
+) -> Option<StartAction> {
+    let scale = layout_scale(rect.size());
+    let mut action = None;
+
+    // 内容区宽度（窄窗口时留出边距）
+    let content_width = (760.0 * scale).min(rect.width() - 48.0 * scale);
+    let button_size = Vec2::new((content_width - 2.0 * 16.0 * scale) / 3.0, 64.0 * scale);
+    let tile_height = 52.0 * scale;
+    let columns = grid_columns(content_width, 220.0 * scale, recent.len());
+    let rows = recent.len().div_ceil(columns);
+
+    // 估算内容高度，使整体垂直居中
+    let mut content_height = 40.0 * scale + 24.0 * scale + 24.0 * scale + button_size.y;
+    if filter.is_some() {
+        content_height += 40.0 * scale + 24.0 * scale + 32.0 * scale + rows as f32 * (tile_height + 8.0 * scale);
+    }
+    let top = ((rect.height() - content_height) / 2.0).max(16.0 * scale);
+    let content_rect = Rect::from_min_size(
+        egui::pos2(rect.center().x - content_width / 2.0, rect.top() + top),
+        Vec2::new(content_width, rect.height() - top),
+    );
+
+    ui.allocate_ui_at_rect(content_rect, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new("喜洋洋播放器").size(32.0 * scale).color(Color32::LIGHT_GRAY));
+            ui.add_space(4.0 * scale);
+            ui.label(RichText::new("拖拽视频文件到此处，或选择下面的操作").size(14.0 * scale).color(Color32::GRAY));
+            ui.add_space(24.0 * scale);
+
+            // 快捷操作
+            ui.spacing_mut().item_spacing.x = 16.0 * scale;
+            ui.horizontal(|ui| {
+                for (icon, label, quick) in [
+                    (Icon::OpenFile, "打开文件", StartAction::OpenFile),
+                    (Icon::OpenFolder, "打开文件夹", StartAction::OpenFolder),
+                    (Icon::OpenUrl, "打开网络流", StartAction::OpenUrl),
+                ] {
+                    let response = IconButton::with_text(icon, button_size, 22.0 * scale, label, FontId::proportional(18.0 * scale))
+                        .rounding(8.0 * scale)
+                        .fill(Color32::from_rgb(45, 45, 45), Color32::from_rgb(60, 60, 60))
+                        .tint(Color32::from_gray(225), Color32::WHITE)
+                        .show(ui, icons);
+                    if response.clicked() {
+                        action = Some(quick);
+                    }
+                }
+            });
+
+            let Some(current_filter) = filter else {
+                return;
+            };
+
+            // 最近播放（标题右侧为筛选）
+            ui.add_space(40.0 * scale);
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("最近播放").size(16.0 * scale).color(Color32::GRAY));
+                ui.add_space(16.0 * scale);
+                for option in RecentFilter::ALL {
+                    let label = RichText::new(option.label()).size(13.0 * scale);
+                    if ui.selectable_label(option == current_filter, label).clicked() && option != current_filter {
+                        action = Some(StartAction::SetRecentFilter(option));
+                    }
+                }
+            });
+            ui.add_space(8.0 * scale);
+            if recent.is_empty() {
+                ui.label(RichText::new("没有符合条件的文件").size(13.0 * scale).color(Color32::GRAY));
+                return;
+            }
+            let tile_width = (content_width - (columns - 1) as f32 * 8.0 * scale) / columns as f32;
+            ui.spacing_mut().item_spacing = Vec2::splat(8.0 * scale);
+            for row in recent.chunks(columns) {
+                ui.horizontal(|ui| {
+                    for file in row {
+                        let name = file.title.clone().unwrap_or_else(|| {
+                            Path::new(&file.path)
+                                .file_name()
+                                .map(|name| name.to_string_lossy().to_string())
+                                .unwrap_or_else(|| file.path.clone())
+                        });
+                        // 文件名过长时从中间省略（保留开头和扩展名）
+                        let font = FontId::proportional(13.0 * scale);
+                        let name_width = tile_width - 2.0 * ui.spacing().button_padding.x;
+                        let name = middle_ellipsis(&name, name_width, &font, ui);
+                        let text = RichText::new(format!("{}\n{}", name, progress_text(file))).font(font);
+                        let tile = egui::Button::new(text).wrap(false).rounding(6.0 * scale);
+                        let response = ui.add_sized(Vec2::new(tile_width, tile_height), tile).on_hover_text(&file.path);
+                        if response.clicked() {
+                            action = Some(StartAction::ResumeRecent(file.path.clone()));
+                        }
+                        // 右键菜单：手动标记
+                        response.context_menu(|ui| {
+                            let label = if file.watch == WatchState::Watched { "标记为未看完" } else { "标记为已看完" };
+                            if ui.button(label).clicked() {
+                                action = Some(StartAction::ToggleWatched(file.path.clone()));
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                });
+            }
+        });
+    });
+
+    // 底部显示启动自检结果
+    ui.painter().text(
+        rect.center_bottom() - Vec2::new(0.0, 12.0 * scale),
+        Align2::CENTER_BOTTOM,
+        capabilities,
+        FontId::proportional(12.0 * scale),
+        Color32::from_gray(110),
+    );
+
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_scale_range() {
+        assert_eq!(layout_scale(Vec2::new(1280.0, 720.0)), 1.0);
+        assert_eq!(layout_scale(Vec2::new(800.0, 600.0)), MIN_SCALE);
+        assert_eq!(layout_scale(Vec2::new(3840.0, 2160.0)), MAX_SCALE);
+        // 按较短的一边缩放（超宽窗口不会放大到超出高度）
+        assert_eq!(layout_scale(Vec2::new(2560.0, 720.0)), 1.0);
+    }
+
+    #[test]
+    fn test_recent_filter_and_progress_text() {
+        let file = |watch| RecentFile { path: "/media/movie.mkv".to_string(), title: None, position_ms: 90_000, watch };
+        let partial = file(WatchState::Partial(0.25));
+        assert_eq!(progress_text(&partial), "上次播放到 01:30（25%）");
+        assert_eq!(progress_text(&file(WatchState::Watched)), "✔ 已看完");
+        assert_eq!(progress_text(&file(WatchState::Unwatched)), "上次播放到 01:30");
+
+        assert!(RecentFilter::Unfinished.matches(partial.watch));
+        assert!(!RecentFilter::Unfinished.matches(WatchState::Watched));
+        assert!(RecentFilter::Watched.matches(WatchState::Watched));
+        assert!(RecentFilter::All.matches(WatchState::Unwatched));
+    }
+
+    #[test]
+    fn test_grid_columns() {
+        assert_eq!(grid_columns(760.0, 220.0, 8), 3);
+        assert_eq!(grid_columns(1900.0, 220.0, 8), MAX_COLUMNS);
+        assert_eq!(grid_columns(760.0, 220.0, 2), 2);
+        assert_eq!(grid_columns(100.0, 220.0, 8), 1);
+        assert_eq!(grid_columns(760.0, 220.0, 0), 1);
+    }
+}
@@ -0,0 +1,137 @@
+// 字幕背景自适应（根据字幕区域的画面亮度调整背景不透明度，极亮时改用浅底深色字）
+//
+// 亮度变化在死区内时保持原样，深浅配色切换使用两个阈值，避免逐帧闪烁。
+
+/// 默认的背景不透明度范围
+pub const DEFAULT_MIN_ALPHA: u8 = 60;
+pub const DEFAULT_MAX_ALPHA: u8 = 200;
+
+/// 固定背景的默认不透明度（关闭自适应时使用）
+pub const DEFAULT_FIXED_ALPHA: u8 = 150;
+
+/// 亮度在此范围内线性映射到不透明度范围（以外取端点）
+const DARK_LUMA: f32 = 0.1;
+const BRIGHT_LUMA: f32 = 0.8;
+
+/// 目标不透明度与当前值相差超过该值才更新
+const ALPHA_DEADBAND: f32 = 16.0;
+
+/// 切换到浅底深色字 / 切换回深底白字的亮度阈值
+const LIGHT_SCHEME_ENTER: f32 = 0.92;
+const LIGHT_SCHEME_EXIT: f32 = 0.85;
+
+/// 字幕背景样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackdropStyle {
+    pub alpha: u8,
+    pub light_scheme: bool,  // 浅色背景 + 深色文字
+}
+
+/// 亮度 -> 背景不透明度（越亮背景越不透明）
+pub fn alpha_for_luma(luma: f32, min_alpha: u8, max_alpha: u8) -> f32 {
+    let t = ((luma - DARK_LUMA) / (BRIGHT_LUMA - DARK_LUMA)).clamp(0.0, 1.0);
+    min_alpha as f32 + (max_alpha as f32 - min_alpha as f32) * t
+}
+
+/// 自适应背景状态
+#[derive(Debug, Clone)]
+pub struct AdaptiveBackdrop {
+    min_alpha: u8,
+    max_alpha: u8,
+    alpha: Option<f32>,  // 尚未采样时为 None
+    light_scheme: bool,
+}
+
+impl Default for AdaptiveBackdrop {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_ALPHA, DEFAULT_MAX_ALPHA)
+    }
+}
+
+impl AdaptiveBackdrop {
+    pub fn new(min_alpha: u8, max_alpha: u8) -> Self {
+        Self { min_alpha: min_alpha.min(max_alpha), max_alpha: max_alpha.max(min_alpha), alpha: None, light_scheme: false }
+    }
+
+    /// 输入字幕区域的平均亮度，返回本帧使用的样式
+    pub fn update(&mut self, luma: f32) -> BackdropStyle {
+        let target = alpha_for_luma(luma, self.min_alpha, self.max_alpha);
+        let alpha = match self.alpha {
+            Some(current) if (target - current).abs() <= ALPHA_DEADBAND => current,
+            _ => target,
+        };
+        self.alpha = Some(alpha);
+
+        if self.light_scheme {
+            self.light_scheme = luma > LIGHT_SCHEME_EXIT;
+        } else {
+            self.light_scheme = luma >= LIGHT_SCHEME_ENTER;
+        }
+        self.current()
+    }
+
+    /// 当前样式（没有采样时使用固定背景的默认值）
+    pub fn current(&self) -> BackdropStyle {
+        BackdropStyle {
+            alpha: self.alpha.map(|alpha| alpha.round() as u8).unwrap_or(DEFAULT_FIXED_ALPHA),
+            light_scheme: self.light_scheme,
+        }
+    }
+
+    /// 切换媒体时清除状态
+    pub fn reset(&mut self) {
+        self.alpha = None;
+        self.light_scheme = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_luma_to_alpha_mapping() {
+        assert_eq!(alpha_for_luma(0.0, 60, 200), 60.0);
+        assert_eq!(alpha_for_luma(DARK_LUMA, 60, 200), 60.0);
+        assert_eq!(alpha_for_luma(1.0, 60, 200), 200.0);
+        let mid = alpha_for_luma((DARK_LUMA + BRIGHT_LUMA) / 2.0, 60, 200);
+        assert!((mid - 130.0).abs() < 0.01, "{}", mid);
+        // 映射单调不减
+        let samples: Vec<f32> = (0..=20).map(|i| alpha_for_luma(i as f32 / 20.0, 60, 200)).collect();
+        assert!(samples.windows(2).all(|pair| pair[1] >= pair[0]));
+    }
+
+    #[test]
+    fn test_small_changes_do_not_flicker() {
+        let mut backdrop = AdaptiveBackdrop::default();
+        let first = backdrop.update(0.5).alpha;
+        // 亮度在小范围内抖动时不透明度保持不变
+        for luma in [0.52, 0.48, 0.55, 0.45, 0.5] {
+            assert_eq!(backdrop.update(luma).alpha, first);
+        }
+        // 明显变化时立即更新
+        assert_eq!(backdrop.update(0.95).alpha, DEFAULT_MAX_ALPHA);
+    }
+
+    #[test]
+    fn test_light_scheme_hysteresis() {
+        let mut backdrop = AdaptiveBackdrop::default();
+        assert!(!backdrop.update(0.9).light_scheme);
+        assert!(backdrop.update(0.95).light_scheme);
+        // 在两个阈值之间保持当前配色
+        assert!(backdrop.update(0.88).light_scheme);
+        assert!(backdrop.update(0.91).light_scheme);
+        assert!(!backdrop.update(0.8).light_scheme);
+        assert!(!backdrop.update(0.9).light_scheme);
+    }
+
+    #[test]
+    fn test_reset_and_inverted_range() {
+        let mut backdrop = AdaptiveBackdrop::new(200, 60);
+        assert_eq!(backdrop.current().alpha, DEFAULT_FIXED_ALPHA);
+        assert_eq!(backdrop.update(0.0).alpha, 60);
+        backdrop.update(1.0);
+        backdrop.reset();
+        assert_eq!(backdrop.current(), BackdropStyle { alpha: DEFAULT_FIXED_ALPHA, light_scheme: false });
+    }
+}
@@ -0,0 +1,243 @@
+// 多条并发字幕的堆叠布局（自底向上，每条字幕在显示期间保持固定位置）
+
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// 同时显示的字幕条数上限
+pub const MAX_STACKED_CUES: usize = 4;
+
+/// 字幕在堆叠中的位置（单位：行）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CuePlacement<K> {
+    pub key: K,
+    pub offset: u32,  // 距离堆叠底部的偏移
+    pub height: u32,  // 占用高度
+}
+
+impl<K> CuePlacement<K> {
+    /// 顶部位置（不含）
+    fn top(&self) -> u32 {
+        self.offset + self.height
+    }
+
+    /// 是否与区间 [offset, offset + height) 重叠
+    fn overlaps(&self, offset: u32, height: u32) -> bool {
+        offset < self.top() && self.offset < offset + height
+    }
+}
+
+/// 字幕堆叠器
+///
+/// 新出现的字幕放在最低的空闲位置，已显示的字幕在结束前不会移动；
+/// 超出条数或高度上限时省略最早开始的字幕
+#[derive(Debug)]
+pub struct SubtitleStacker<K> {
+    placements: Vec<CuePlacement<K>>,
+    elided: HashSet<K>,  // 已被省略的字幕（结束前不再显示）
+}
+
+impl<K> Default for SubtitleStacker<K> {
+    fn default() -> Self {
+        Self {
+            placements: Vec::new(),
+            elided: HashSet::new(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> SubtitleStacker<K> {
+    /// 创建堆叠器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 根据当前活动字幕更新布局
+    ///
+    /// `cues` 为 (字幕标识, 高度)，按开始时间排序（最早的在前）；
+    /// `max_height` 为堆叠总高度上限。返回按偏移从低到高排列的布局
+    pub fn update(&mut self, cues: &[(K, u32)], max_height: u32) -> Vec<CuePlacement<K>> {
+        // 开始时间顺序（越小越早）
+        let age = |key: &K| cues.iter().position(|(k, _)| k == key).unwrap_or(usize::MAX);
+
+        // 移除已结束的字幕
+        self.placements.retain(|p| cues.iter().any(|(k, _)| k == &p.key));
+        self.elided.retain(|key| cues.iter().any(|(k, _)| k == key));
+
+        // 条数上限：省略最早开始的字幕
+        let visible: Vec<&(K, u32)> = cues.iter().filter(|(k, _)| !self.elided.contains(k)).collect();
+        if visible.len() > MAX_STACKED_CUES {
+            let excess = visible.len() - MAX_STACKED_CUES;
+            let oldest: Vec<K> = visible[..excess].iter().map(|(k, _)| k.clone()).collect();
+            for key in oldest {
+                self.elide(&key);
+            }
+        }
+
+        // 高度上限缩小（窗口缩放）后放不下时重新布局
+        if self.placements.iter().any(|p| p.top() > max_height) {
+            self.placements.clear();
+        }
+
+        // 为新字幕分配位置
+        for (key, height) in cues {
+            if self.elided.contains(key) || self.placements.iter().any(|p| &p.key == key) {
+                continue;
+            }
+
+            loop {
+                if let Some(offset) = self.find_free_offset(*height, max_height) {
+                    self.placements.push(CuePlacement { key: key.clone(), offset, height: *height });
+                    break;
+                }
+
+                // 放不下：有更早的字幕则省略它后重试，否则省略当前字幕
+                let oldest_age = self.placements.iter().map(|p| age(&p.key)).min();
+                match oldest_age {
+                    Some(oldest) if oldest < age(key) => self.elide_oldest(&age),
+                    _ if self.placements.is_empty() => {
+                        // 单条字幕超过上限时仍然显示
+                        self.placements.push(CuePlacement { key: key.clone(), offset: 0, height: *height });
+                        break;
+                    }
+                    _ => {
+                        self.elided.insert(key.clone());
+                        break;
+                    }
+                }
+            }
+        }
+
+        let mut result = self.placements.clone();
+        result.sort_by_key(|p| p.offset);
+        result
+    }
+
+    /// 清空布局（切换文件、Seek 时调用）
+    pub fn clear(&mut self) {
+        self.placements.clear();
+        self.elided.clear();
+    }
+
+    /// 查找能容纳指定高度的最低空闲位置
+    fn find_free_offset(&self, height: u32, max_height: u32) -> Option<u32> {
+        // 候选位置：底部以及每条已放置字幕的顶部
+        let mut candidates: Vec<u32> = std::iter::once(0)
+            .chain(self.placements.iter().map(|p| p.top()))
+            .collect();
+        candidates.sort_unstable();
+
+        candidates
+            .into_iter()
+            .filter(|offset| offset + height <= max_height)
+            .find(|offset| !self.placements.iter().any(|p| p.overlaps(*offset, height)))
+    }
+
+    /// 省略指定字幕
+    fn elide(&mut self, key: &K) {
+        self.placements.retain(|p| &p.key != key);
+        self.elided.insert(key.clone());
+    }
+
+    /// 省略已放置字幕中最早开始的一条
+    fn elide_oldest(&mut self, age: &impl Fn(&K) -> usize) {
+        if let Some(oldest) = self.placements.iter().min_by_key(|p| age(&p.key)).map(|p| p.key.clone()) {
+            self.elide(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offsets(placements: &[CuePlacement<&'static str>]) -> Vec<(&'static str, u32)> {
+        placements.iter().map(|p| (p.key, p.offset)).collect()
+    }
+
+    #[test]
+    fn test_simultaneous_cues_stack_in_start_order() {
+        let mut stacker = SubtitleStacker::new();
+        let result = stacker.update(&[("a", 2), ("b", 1), ("c", 2)], 20);
+        assert_eq!(offsets(&result), vec![("a", 0), ("b", 2), ("c", 3)]);
+    }
+
+    #[test]
+    fn test_cue_keeps_slot_when_lower_cue_ends() {
+        let mut stacker = SubtitleStacker::new();
+        stacker.update(&[("a", 2)], 20);
+        stacker.update(&[("a", 2), ("b", 1)], 20);
+
+        // a 结束，b 不下移
+        let result = stacker.update(&[("b", 1)], 20);
+        assert_eq!(offsets(&result), vec![("b", 2)]);
+
+        // 新字幕填入底部空位，b 保持不动
+        let result = stacker.update(&[("b", 1), ("c", 2)], 20);
+        assert_eq!(offsets(&result), vec![("c", 0), ("b", 2)]);
+    }
+
+    #[test]
+    fn test_new_cue_skips_gap_that_is_too_small() {
+        let mut stacker = SubtitleStacker::new();
+        stacker.update(&[("a", 1), ("b", 1)], 20);
+        let result = stacker.update(&[("b", 1), ("c", 2)], 20);
+        assert_eq!(offsets(&result), vec![("b", 1), ("c", 2)]);
+    }
+
+    #[test]
+    fn test_cue_keeps_slot_when_middle_cue_ends() {
+        let mut stacker = SubtitleStacker::new();
+        stacker.update(&[("a", 1), ("b", 1), ("c", 1)], 20);
+        let result = stacker.update(&[("a", 1), ("c", 1)], 20);
+        assert_eq!(offsets(&result), vec![("a", 0), ("c", 2)]);
+    }
+
+    #[test]
+    fn test_max_cue_count_elides_oldest() {
+        let mut stacker = SubtitleStacker::new();
+        stacker.update(&[("a", 1), ("b", 1), ("c", 1), ("d", 1)], 20);
+        let result = stacker.update(&[("a", 1), ("b", 1), ("c", 1), ("d", 1), ("e", 1)], 20);
+        assert_eq!(result.len(), MAX_STACKED_CUES);
+        assert_eq!(offsets(&result), vec![("e", 0), ("b", 1), ("c", 2), ("d", 3)]);
+
+        // 被省略的字幕在结束前不会重新出现
+        let result = stacker.update(&[("a", 1), ("b", 1), ("c", 1), ("e", 1)], 20);
+        assert_eq!(offsets(&result), vec![("e", 0), ("b", 1), ("c", 2)]);
+    }
+
+    #[test]
+    fn test_height_cap_elides_oldest() {
+        let mut stacker = SubtitleStacker::new();
+        stacker.update(&[("a", 2), ("b", 2)], 5);
+        let result = stacker.update(&[("a", 2), ("b", 2), ("c", 2)], 5);
+        assert_eq!(offsets(&result), vec![("c", 0), ("b", 2)]);
+    }
+
+    #[test]
+    fn test_height_cap_shrink_elides_oldest() {
+        let mut stacker = SubtitleStacker::new();
+        stacker.update(&[("a", 2), ("b", 2), ("c", 2)], 10);
+        let result = stacker.update(&[("a", 2), ("b", 2), ("c", 2)], 4);
+        assert_eq!(offsets(&result), vec![("c", 0), ("b", 2)]);
+    }
+
+    #[test]
+    fn test_single_tall_cue_is_still_shown() {
+        let mut stacker = SubtitleStacker::new();
+        let result = stacker.update(&[("a", 8)], 4);
+        assert_eq!(offsets(&result), vec![("a", 0)]);
+
+        // 更新的字幕放不下时省略的是更早的 a
+        let result = stacker.update(&[("a", 8), ("b", 1)], 4);
+        assert_eq!(offsets(&result), vec![("b", 0)]);
+    }
+
+    #[test]
+    fn test_clear_resets_layout() {
+        let mut stacker = SubtitleStacker::new();
+        stacker.update(&[("a", 1), ("b", 1)], 20);
+        stacker.clear();
+        let result = stacker.update(&[("b", 1)], 20);
+        assert_eq!(offsets(&result), vec![("b", 0)]);
+    }
+}
@@ -0,0 +1,169 @@
+// 音画同步阈值（UI 按音频时钟选择视频帧时使用）
+//
+// 默认值按片源帧时长推导：同步良好时每隔一帧时长取一帧，落后超过两帧时长时加快取帧追赶，
+// 落后超过四帧时长时丢弃过期帧直接跳到最新帧。高级设置中可以单独覆盖每个阈值。
+
+use serde::{Deserialize, Serialize};
+
+/// 帧率未知时按 25fps 推导
+const FALLBACK_FRAME_MS: f64 = 40.0;
+
+/// 追赶时取帧间隔占帧时长的比例（约 1.33 倍速追赶，几乎察觉不到）
+const CATCH_UP_RATIO: f64 = 0.75;
+
+/// 阈值可调范围（毫秒）
+pub const THRESHOLD_RANGE_MS: std::ops::RangeInclusive<i64> = 1..=1000;
+
+/// 推导出的（或覆盖后的）同步阈值（毫秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncTuning {
+    /// 同步良好时两次取帧的最小间隔
+    pub update_threshold_ms: i64,
+    /// 落后超过该值时加快取帧
+    pub catch_up_threshold_ms: i64,
+    /// 追赶时两次取帧的间隔
+    pub catch_up_interval_ms: i64,
+    /// 落后超过该值时丢弃过期帧，直接跳到最新帧（早于时钟该值以上的帧视为过期）
+    pub jump_threshold_ms: i64,
+}
+
+/// 用户覆盖的阈值（None 表示自动）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncOverrides {
+    pub update_threshold_ms: Option<i64>,
+    pub catch_up_threshold_ms: Option<i64>,
+    pub jump_threshold_ms: Option<i64>,
+}
+
+impl SyncOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for SyncTuning {
+    fn default() -> Self {
+        Self::derive(0.0)
+    }
+}
+
+impl SyncTuning {
+    /// 按片源帧率推导（帧率未知时按 25fps）
+    pub fn derive(fps: f64) -> Self {
+        let frame_ms = if fps.is_finite() && fps > 0.0 { 1000.0 / fps } else { FALLBACK_FRAME_MS };
+        // 向下取整：阈值略小于帧时长，不会因取整逐帧累积落后
+        let ms = |value: f64| (value.floor() as i64).max(1);
+        Self {
+            update_threshold_ms: ms(frame_ms),
+            catch_up_threshold_ms: ms(frame_ms * 2.0),
+            catch_up_interval_ms: ms(frame_ms * CATCH_UP_RATIO),
+            jump_threshold_ms: ms(frame_ms * 4.0),
+        }
+    }
+
+    /// 应用用户覆盖（追赶阈值不超过跳跃阈值，追赶间隔不超过更新间隔）
+    pub fn with_overrides(mut self, overrides: &SyncOverrides) -> Self {
+        let clamp = |value: i64| value.clamp(*THRESHOLD_RANGE_MS.start(), *THRESHOLD_RANGE_MS.end());
+        if let Some(value) = overrides.update_threshold_ms {
+            self.update_threshold_ms = clamp(value);
+            self.catch_up_interval_ms = self.catch_up_interval_ms.min(self.update_threshold_ms);
+        }
+        if let Some(value) = overrides.jump_threshold_ms {
+            self.jump_threshold_ms = clamp(value);
+        }
+        if let Some(value) = overrides.catch_up_threshold_ms {
+            self.catch_up_threshold_ms = clamp(value);
+        }
+        self.catch_up_threshold_ms = self.catch_up_threshold_ms.min(self.jump_threshold_ms);
+        self
+    }
+
+    /// 按播放速度缩放落后阈值（取帧间隔按媒体时间计算，不随速度变化）
+    pub fn scaled_for_speed(mut self, speed: f32) -> Self {
+        if !speed.is_finite() || speed <= 0.0 || speed == 1.0 {
+            return self;
+        }
+        let scale = |value: i64| ((value as f64 * speed as f64) as i64).max(1);
+        self.catch_up_threshold_ms = scale(self.catch_up_threshold_ms);
+        self.jump_threshold_ms = scale(self.jump_threshold_ms);
+        self
+    }
+
+    /// 视频落后 lag_ms 时取下一帧需要的最小时间差
+    pub fn update_threshold(&self, lag_ms: i64) -> i64 {
+        if self.is_severe(lag_ms) {
+            0
+        } else if lag_ms > self.catch_up_threshold_ms {
+            self.catch_up_interval_ms
+        } else {
+            self.update_threshold_ms
+        }
+    }
+
+    /// 是否严重落后（需要跳帧）
+    pub fn is_severe(&self, lag_ms: i64) -> bool {
+        lag_ms > self.jump_threshold_ms
+    }
+
+    /// 概要（日志和统计信息显示）
+    pub fn summary(&self) -> String {
+        format!(
+            "更新 {}ms / 追赶 >{}ms（间隔 {}ms）/ 跳帧 >{}ms",
+            self.update_threshold_ms, self.catch_up_threshold_ms, self.catch_up_interval_ms, self.jump_threshold_ms
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thresholds(tuning: SyncTuning) -> (i64, i64, i64, i64) {
+        (tuning.update_threshold_ms, tuning.catch_up_threshold_ms, tuning.catch_up_interval_ms, tuning.jump_threshold_ms)
+    }
+
+    #[test]
+    fn test_derive_from_frame_rate() {
+        assert_eq!(thresholds(SyncTuning::derive(24000.0 / 1001.0)), (41, 83, 31, 166));
+        assert_eq!(thresholds(SyncTuning::derive(30.0)), (33, 66, 25, 133));
+        assert_eq!(thresholds(SyncTuning::derive(60.0)), (16, 33, 12, 66));
+        assert_eq!(thresholds(SyncTuning::derive(120.0)), (8, 16, 6, 33));
+        // 帧率未知时按 25fps
+        assert_eq!(thresholds(SyncTuning::derive(0.0)), (40, 80, 30, 160));
+        assert_eq!(SyncTuning::derive(f64::NAN), SyncTuning::default());
+    }
+
+    #[test]
+    fn test_update_threshold_by_lag() {
+        let tuning = SyncTuning::derive(30.0);
+        assert_eq!(tuning.update_threshold(0), 33);
+        assert_eq!(tuning.update_threshold(66), 33);
+        assert_eq!(tuning.update_threshold(67), 25);
+        assert_eq!(tuning.update_threshold(134), 0);
+        assert!(tuning.is_severe(134) && !tuning.is_severe(133));
+    }
+
+    #[test]
+    fn test_lag_thresholds_scale_with_speed() {
+        let tuning = SyncTuning::derive(30.0);
+        assert_eq!(tuning.scaled_for_speed(1.0), tuning);
+        assert_eq!(thresholds(tuning.scaled_for_speed(2.0)), (33, 132, 25, 266));
+        assert_eq!(thresholds(tuning.scaled_for_speed(0.5)), (33, 33, 25, 66));
+        assert!(!tuning.scaled_for_speed(2.0).is_severe(200));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let auto = SyncTuning::derive(60.0);
+        assert_eq!(auto.with_overrides(&SyncOverrides::default()), auto);
+
+        let overrides = SyncOverrides { update_threshold_ms: Some(10), jump_threshold_ms: Some(20), ..Default::default() };
+        let tuning = auto.with_overrides(&overrides);
+        assert_eq!(thresholds(tuning), (10, 20, 10, 20));
+
+        // 超出范围的值被夹紧
+        let overrides = SyncOverrides { jump_threshold_ms: Some(100_000), ..Default::default() };
+        assert_eq!(auto.with_overrides(&overrides).jump_threshold_ms, 1000);
+    }
+}
@@ -0,0 +1,110 @@
+// 时间显示工具（内部时间统一使用 i64 毫秒，仅在显示边界转换）
+
+/// 未知/无效时间的显示文本
+pub const UNKNOWN_TIME: &str = "—:—";
+
+const MS_PER_SECOND: i64 = 1000;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// 格式化时间显示（毫秒）
+///
+/// - 负数视为无效，显示 "—:—"
+/// - 不足 1 小时：MM:SS
+/// - 不足 1 天：HH:MM:SS
+/// - 超过 1 天：Nd HH:MM:SS
+pub fn format_time(ms: i64) -> String {
+    if ms < 0 {
+        return UNKNOWN_TIME.to_string();
+    }
+
+    let total_seconds = ms / MS_PER_SECOND;
+    let days = total_seconds / SECONDS_PER_DAY;
+    let hours = (total_seconds % SECONDS_PER_DAY) / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let secs = total_seconds % 60;
+
+    if days > 0 {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, secs)
+    } else if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+/// 格式化总时长（时长未知或无效时显示 "—:—"）
+pub fn format_duration(duration_ms: i64) -> String {
+    if duration_ms <= 0 {
+        UNKNOWN_TIME.to_string()
+    } else {
+        format_time(duration_ms)
+    }
+}
+
+/// 毫秒转秒（负数按 0 处理）
+pub fn ms_to_secs(ms: i64) -> f64 {
+    ms.max(0) as f64 / MS_PER_SECOND as f64
+}
+
+/// 计算位置在进度条上的比例（0.0 - 1.0）
+///
+/// 时长未知时返回 0.0，位置超出范围时夹紧到两端
+pub fn slider_fraction(position_ms: i64, duration_ms: i64) -> f32 {
+    if duration_ms <= 0 {
+        return 0.0;
+    }
+    let position = position_ms.clamp(0, duration_ms);
+    (position as f64 / duration_ms as f64) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_time_basic() {
+        assert_eq!(format_time(0), "00:00");
+        assert_eq!(format_time(999), "00:00");
+        assert_eq!(format_time(65_000), "01:05");
+        assert_eq!(format_time(3_600_000), "01:00:00");
+        assert_eq!(format_time(86_399_000), "23:59:59");
+    }
+
+    #[test]
+    fn test_format_time_days() {
+        assert_eq!(format_time(86_400_000), "1d 00:00:00");
+        assert_eq!(format_time((86_400 + 2 * 3600 + 15 * 60 + 33) * 1000), "1d 02:15:33");
+        assert_eq!(format_time(i64::MAX), "106751991167d 07:12:55");
+    }
+
+    #[test]
+    fn test_format_time_invalid() {
+        assert_eq!(format_time(-1), UNKNOWN_TIME);
+        assert_eq!(format_time(i64::MIN), UNKNOWN_TIME);
+        assert_eq!(format_duration(0), UNKNOWN_TIME);
+        assert_eq!(format_duration(-5_000), UNKNOWN_TIME);
+        assert_eq!(format_duration(5_000), "00:05");
+    }
+
+    #[test]
+    fn test_ms_to_secs() {
+        assert_eq!(ms_to_secs(-2_000), 0.0);
+        assert_eq!(ms_to_secs(0), 0.0);
+        assert_eq!(ms_to_secs(1_500), 1.5);
+    }
+
+    #[test]
+    fn test_slider_fraction() {
+        assert_eq!(slider_fraction(0, 0), 0.0);
+        assert_eq!(slider_fraction(5_000, -1), 0.0);
+        assert_eq!(slider_fraction(-3_000, 10_000), 0.0);
+        assert_eq!(slider_fraction(5_000, 10_000), 0.5);
+        assert_eq!(slider_fraction(20_000, 10_000), 1.0);
+        assert_eq!(slider_fraction(i64::MAX, i64::MAX), 1.0);
+        assert_eq!(slider_fraction(i64::MAX / 2, i64::MAX), 0.5);
+
+        // 超过 24 小时的内容
+        let day = 86_400_000;
+        assert_eq!(slider_fraction(day, 2 * day), 0.5);
+    }
+}
@@ -0,0 +1,74 @@
+// 进度条悬停预览提示框
+//
+// 鼠标悬停在进度条上时，在悬停位置上方显示该位置的预览画面（由 player::hover_preview 在后台生成）、
+// 章节标题和时间。画面尚未生成时显示加载指示；没有可预览画面的媒体（音频、网络流、图像）只显示文字。
+
+use std::time::Duration;
+
+use egui::{ColorImage, Context, TextureHandle, TextureOptions};
+use myy_player::{FrameData, HoverPreviewState};
+
+/// 预览画面的显示宽度（逻辑像素）
+const PREVIEW_WIDTH: f32 = 160.0;
+
+/// 提示框与进度条的间距
+const RAIL_GAP: f32 = 10.0;
+
+/// 等待生成时的刷新间隔（取回后台线程的结果）
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 悬停预览提示框
+#[derive(Default)]
+pub struct TimelinePreview {
+    texture: Option<(FrameData, TextureHandle)>,  // 当前画面的纹理（按帧数据区分，同一画面不重复上传）
+}
+
+impl TimelinePreview {
+    /// 在悬停位置（hover_x, 进度条顶部）上方显示提示框
+    pub fn show(&mut self, ctx: &Context, hover_x: f32, rail_top: f32, state: HoverPreviewState, text: &str) {
+        let loading = matches!(state, HoverPreviewState::Loading);
+        let image = match state {
+            HoverPreviewState::Ready(frame) => {
+                let size = [frame.width as usize, frame.height as usize];
+                let uploaded = self.texture.as_ref().is_some_and(|(data, _)| FrameData::ptr_eq(data, &frame.data));
+                if !uploaded && frame.data.len() == size[0] * size[1] * 4 {
+                    let texture = ctx.load_texture(
+                        "timeline_preview",
+                        ColorImage::from_rgba_unmultiplied(size, &frame.data),
+                        TextureOptions::LINEAR,
+                    );
+                    self.texture = Some((frame.data.clone(), texture));
+                }
+                self.texture.as_ref().map(|(_, texture)| texture.clone())
+            }
+            HoverPreviewState::Loading => {
+                ctx.request_repaint_after(POLL_INTERVAL);
+                None
+            }
+            HoverPreviewState::Unavailable => None,
+        };
+
+        egui::Area::new(egui::Id::new("timeline_preview"))
+            .order(egui::Order::Tooltip)
+            .fixed_pos(egui::pos2(hover_x, rail_top - RAIL_GAP))
+            .pivot(egui::Align2::CENTER_BOTTOM)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        if let Some(texture) = &image {
+                            let size = texture.size_vec2();
+                            let height = PREVIEW_WIDTH * size.y / size.x.max(1.0);
+                            ui.image((texture.id(), egui::vec2(PREVIEW_WIDTH, height)));
+                        } else if loading {
+                            // 16:9 的占位区域，生成完成后替换为画面
+                            let (rect, _) = ui.allocate_exact_size(egui::vec2(PREVIEW_WIDTH, PREVIEW_WIDTH * 9.0 / 16.0), egui::Sense::hover());
+                            ui.painter().rect_filled(rect, 2.0, egui::Color32::from_gray(30));
+                            egui::Spinner::new().size(20.0).paint_at(ui, egui::Rect::from_center_size(rect.center(), egui::vec2(20.0, 20.0)));
+                        }
+                        ui.label(text);
+                    });
+                });
+            });
+    }
+}
@@ -0,0 +1,618 @@
+// 用户数据导出/导入（设置、播放记忆等合并为一个带版本的 JSON 文档）
+//
+// 文档结构：{ "version": 1, "sections": { "<名称>": { "version": n, "data": ... } } }
+// 每个分区独立带版本号：较新版本写入的未知分区或更高版本的分区在导入时跳过并提示，
+// 其余分区照常导入；只有文档整体格式版本过新时才拒绝导入。
+//
+// 设置另外保存在用户数据目录的 settings.json 中（启动时读取，修改后延迟自动保存，退出时再保存一次）。
+// 上次的音量、播放速度、字幕偏好和窗口尺寸也随设置保存，下次启动时恢复。
+
+use log::warn;
+use myy_player::{clamp_speed, FileMemory, PlayerError, Result, MAX_VOLUME};
+use myy_player::{DEFAULT_FIRST_FRAME_DEADLINE, DEFAULT_MAX_FRAME_DIMENSION, DEFAULT_SEAMLESS_LIMIT_MS};
+use myy_player::{DEFAULT_TIMESHIFT_MB, DEFAULT_TIMESHIFT_MINUTES};
+use super::osd::OsdAnchor;
+use super::recent_files::RecentFiles;
+use super::seek_step::SeekSteps;
+use super::settings_drawer::{SettingsSection, UiTheme};
+use super::snapshot::{SnapshotFormat, DEFAULT_TEMPLATE};
+use super::subtitle_backdrop::DEFAULT_FIXED_ALPHA;
+use super::sync_tuning::SyncOverrides;
+use super::window_size::MIN_INNER_SIZE;
+use crate::renderer::video_view::FitMode;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 文档格式版本（整体结构不兼容变化时递增）
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// 设置分区
+const SETTINGS_SECTION: &str = "settings";
+const SETTINGS_VERSION: u32 = 1;
+
+/// 字幕大小（相对自适应大小的倍数）的范围
+pub const SUBTITLE_SCALE_RANGE: RangeInclusive<f32> = 0.5..=2.0;
+
+/// 默认窗口内部尺寸
+const DEFAULT_WINDOW_SIZE: [f32; 2] = [1280.0, 720.0];
+
+/// 播放记忆分区（按文件的轨道选择、音量）
+const HISTORY_SECTION: &str = "history";
+const HISTORY_VERSION: u32 = 1;
+
+/// 用户设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    pub default_volume: f32,
+    pub restore_default_volume: bool,
+    /// 上次的音量（启动时恢复）
+    pub volume: f32,
+    pub muted: bool,
+    /// 上次的播放速度（启动时恢复）
+    pub playback_speed: f32,
+    /// 启动后第一次播放时音量渐入
+    pub startup_fade_in: bool,
+    /// 渐入时长（秒），1 ~ 3
+    pub startup_fade_in_secs: f32,
+    /// 响度均衡（按平均响度自动调整增益）
+    pub loudness_normalization: bool,
+    /// 音频输出设备名称（None 跟随系统默认设备）
+    pub audio_device: Option<String>,
+    /// 画面缩放模式（适应 / 填满 / 拉伸 / 原始大小）
+    pub fit_mode: FitMode,
+    pub progress_follows_frame: bool,
+    pub chapter_shading: bool,
+    /// 单击画面播放/暂停（双击总是切换全屏）
+    pub click_to_pause: bool,
+    pub auto_forced_subtitles: bool,
+    /// 没有轨道记忆的文件默认显示第一条字幕
+    pub subtitles_enabled: bool,
+    /// 默认字幕延迟（毫秒，打开新文件时使用）
+    pub subtitle_offset_ms: i64,
+    /// 字幕大小（相对按视频高度自适应的大小）
+    pub subtitle_scale: f32,
+    pub adaptive_subtitle_backdrop: bool,
+    pub subtitle_backdrop_alpha: u8,
+    /// 按 S 截图时烧录当前显示的字幕（Ctrl+S 总是包含字幕）
+    pub snapshot_with_subtitles: bool,
+    pub snapshot_format: SnapshotFormat,
+    /// JPEG 截图质量（50 ~ 100）
+    pub snapshot_jpeg_quality: u8,
+    /// 截图保存目录（未设置时为 图片/myy_player）
+    pub snapshot_dir: Option<String>,
+    /// 截图文件名模板（{name} {pos} {datetime} {n}）
+    pub snapshot_template: String,
+    pub watch_folder_path: Option<String>,
+    pub watch_folder_enabled: bool,
+    pub watch_folder_preempt: bool,
+    pub repeat_one: bool,
+    /// 播放列表播放到最后一项后回到第一项
+    pub repeat_playlist: bool,
+    pub seamless_loop_limit_secs: u32,
+    /// 方向键跳转的步长（← / →、Shift、Ctrl）
+    pub seek_steps: SeekSteps,
+    pub sync_overrides: SyncOverrides,
+    /// 帧尺寸上限（每个方向，超出的帧按损坏处理）
+    pub max_frame_dimension: u32,
+    /// 首帧期限（秒，正在播放的时间超过该值仍没有视频帧时提示）
+    pub first_frame_timeout_secs: u32,
+    /// 优先使用硬件解码（修改后重建播放管线生效）
+    pub hw_decode: bool,
+    /// 忽略播放历史中的硬解失败记录，打开文件时总是先尝试硬件解码
+    pub hw_decode_always_retry: bool,
+    /// 全屏播放时把显示器刷新率切换为与视频帧率匹配（Windows）
+    pub match_refresh_rate: bool,
+    pub theme: UiTheme,
+    /// 叠加层（屏幕提示、直播标记、信息面板）缩放，1.0 ~ 2.5
+    pub osd_scale: f32,
+    /// 屏幕提示的位置
+    pub osd_anchor: OsdAnchor,
+    /// 读取媒体文件旁 .nfo 文件中的标题
+    pub read_sidecar_titles: bool,
+    /// 直播时移（直播流录到临时文件，可以暂停和回看；重新打开直播时生效）
+    pub timeshift_enabled: bool,
+    /// 时移最多保留的时长（分钟）和大小（MB），超出时丢弃最旧的数据
+    pub timeshift_minutes: u32,
+    pub timeshift_max_mb: u32,
+    /// 设置抽屉中展开的分区
+    pub expanded_sections: Vec<SettingsSection>,
+    /// 最近打开的文件和网络地址（控制栏的"最近打开"菜单）
+    pub recent_files: RecentFiles,
+    /// 自定义的快捷键（按键 -> 动作名称；None 使用默认绑定，见 keymap）
+    pub key_bindings: Option<BTreeMap<String, String>>,
+    /// 上次的窗口内部尺寸（逻辑像素，不含最大化和全屏）
+    pub window_size: [f32; 2],
+    pub window_maximized: bool,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            default_volume: 1.0,
+            restore_default_volume: true,
+            volume: 1.0,
+            muted: false,
+            playback_speed: 1.0,
+            startup_fade_in: false,
+            startup_fade_in_secs: 2.0,
+            loudness_normalization: false,
+            audio_device: None,
+            fit_mode: FitMode::Fit,
+            progress_follows_frame: false,
+            chapter_shading: true,
+            click_to_pause: true,
+            auto_forced_subtitles: true,
+            subtitles_enabled: true,
+            subtitle_offset_ms: 0,
+            subtitle_scale: 1.0,
+            adaptive_subtitle_backdrop: true,
+            subtitle_backdrop_alpha: DEFAULT_FIXED_ALPHA,
+            snapshot_with_subtitles: false,
+            snapshot_format: SnapshotFormat::default(),
+            snapshot_jpeg_quality: 90,
+            snapshot_dir: None,
+            snapshot_template: DEFAULT_TEMPLATE.to_string(),
+            watch_folder_path: None,
+            watch_folder_enabled: false,
+            watch_folder_preempt: false,
+            repeat_one: false,
+            repeat_playlist: false,
+            seamless_loop_limit_secs: (DEFAULT_SEAMLESS_LIMIT_MS / 1000) as u32,
+            seek_steps: SeekSteps::default(),
+            sync_overrides: SyncOverrides::default(),
+            max_frame_dimension: DEFAULT_MAX_FRAME_DIMENSION,
+            first_frame_timeout_secs: DEFAULT_FIRST_FRAME_DEADLINE.as_secs() as u32,
+            hw_decode: true,
+            hw_decode_always_retry: false,
+            match_refresh_rate: false,
+            theme: UiTheme::default(),
+            osd_scale: 1.0,
+            osd_anchor: OsdAnchor::default(),
+            read_sidecar_titles: true,
+            timeshift_enabled: false,
+            timeshift_minutes: DEFAULT_TIMESHIFT_MINUTES,
+            timeshift_max_mb: DEFAULT_TIMESHIFT_MB,
+            expanded_sections: vec![SettingsSection::Playback],
+            recent_files: RecentFiles::default(),
+            key_bindings: None,
+            window_size: DEFAULT_WINDOW_SIZE,
+            window_maximized: false,
+        }
+    }
+}
+
+impl UserSettings {
+    /// 把超出范围的值夹紧到有效范围（手动编辑过或其他版本写入的设置文件）
+    pub fn sanitized(self) -> Self {
+        let volume = |volume: f32| if volume.is_finite() { volume.clamp(0.0, MAX_VOLUME) } else { 1.0 };
+        let window_size = if self.window_size.iter().all(|size| size.is_finite()) {
+            [self.window_size[0].max(MIN_INNER_SIZE.x), self.window_size[1].max(MIN_INNER_SIZE.y)]
+        } else {
+            DEFAULT_WINDOW_SIZE
+        };
+        Self {
+            default_volume: volume(self.default_volume),
+            volume: volume(self.volume),
+            playback_speed: if self.playback_speed.is_finite() { clamp_speed(self.playback_speed) } else { 1.0 },
+            subtitle_scale: if self.subtitle_scale.is_finite() {
+                self.subtitle_scale.clamp(*SUBTITLE_SCALE_RANGE.start(), *SUBTITLE_SCALE_RANGE.end())
+            } else {
+                1.0
+            },
+            seek_steps: self.seek_steps.sanitized(),
+            window_size,
+            ..self
+        }
+    }
+}
+
+/// 修改设置后等待多久没有新的修改再自动保存
+pub const AUTOSAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// 用户数据目录（与播放核心的播放位置记录、预览缓存在同一目录）
+pub fn user_data_dir() -> PathBuf {
+    let base = std::env::var_os("APPDATA")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("XDG_DATA_HOME").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("myy_player")
+}
+
+/// 原子写入：先写临时文件并 fsync，再 rename 覆盖目标文件
+pub fn write_atomic(file: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let dir = file.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let mut tmp_name = file.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp = PathBuf::from(tmp_name);
+    {
+        let mut out = File::create(&tmp)?;
+        out.write_all(bytes)?;
+        out.sync_all()?;
+    }
+    fs::rename(&tmp, file)?;
+
+    // 同步目录项，确保 rename 本身落盘（Windows 不支持打开目录，忽略）
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// 设置文件路径
+pub fn settings_file() -> PathBuf {
+    user_data_dir().join("settings.json")
+}
+
+/// 读取设置文件（文件不存在时返回 None）
+pub fn load_settings(file: &Path) -> Result<Option<UserSettings>> {
+    let json = match fs::read_to_string(file) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| PlayerError::ConfigError(format!("设置文件格式错误: {}", e)))
+}
+
+/// 读取启动时使用的设置（文件不存在、损坏或格式错误时使用默认设置）
+pub fn load_settings_or_default(file: &Path) -> UserSettings {
+    match load_settings(file) {
+        Ok(settings) => settings.unwrap_or_default().sanitized(),
+        Err(e) => {
+            warn!("⚠️ 读取设置失败，使用默认设置: {}", e);
+            UserSettings::default()
+        }
+    }
+}
+
+/// 保存设置文件（原子写入）
+pub fn save_settings(file: &Path, settings: &UserSettings) -> Result<()> {
+    let json = serde_json::to_vec_pretty(settings).map_err(|e| PlayerError::ConfigError(format!("序列化失败: {}", e)))?;
+    Ok(write_atomic(file, &json)?)
+}
+
+/// 设置的延迟自动保存：每次修改重新计时，停止修改 AUTOSAVE_DELAY 后保存一次（拖动滑块时不会反复写文件）
+pub struct SettingsAutoSave {
+    saved: UserSettings,
+    observed: UserSettings,
+    changed_at: Option<Instant>,
+}
+
+impl SettingsAutoSave {
+    pub fn new(saved: UserSettings) -> Self {
+        Self { observed: saved.clone(), saved, changed_at: None }
+    }
+
+    /// 每帧调用：到期且与上次保存的内容不同时返回需要保存的设置
+    pub fn poll(&mut self, current: &UserSettings, now: Instant) -> Option<UserSettings> {
+        if *current != self.observed {
+            self.observed = current.clone();
+            self.changed_at = Some(now);
+        }
+        let changed_at = self.changed_at?;
+        if now.duration_since(changed_at) < AUTOSAVE_DELAY {
+            return None;
+        }
+        self.changed_at = None;
+        if self.observed == self.saved {
+            return None;
+        }
+        self.saved = self.observed.clone();
+        Some(self.saved.clone())
+    }
+
+    /// 是否有等待保存的修改
+    pub fn is_pending(&self) -> bool {
+        self.changed_at.is_some()
+    }
+}
+
+/// 导出的用户数据
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserData {
+    pub settings: UserSettings,
+    pub history: HashMap<String, FileMemory>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Archive {
+    version: u32,
+    sections: BTreeMap<String, Section>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Section {
+    version: u32,
+    data: serde_json::Value,
+}
+
+/// 导入计划（已解析并校验，应用时不会失败）
+#[derive(Debug)]
+pub struct ImportPlan {
+    pub settings: Option<UserSettings>,
+    pub history: Option<HashMap<String, FileMemory>>,
+    pub skipped_sections: Vec<String>,  // 跳过的分区（未知或版本过新）
+    pub missing_paths: Vec<String>,     // 播放记忆中本机不存在的文件（保留，可能位于网络共享）
+}
+
+impl ImportPlan {
+    /// 导入前向用户展示的摘要（每个分区将覆盖的条目数）
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.settings.is_some() {
+            lines.push("设置：将覆盖当前设置".to_string());
+        }
+        if let Some(history) = &self.history {
+            lines.push(format!("播放记忆：{} 条（将替换现有记录）", history.len()));
+        }
+        if !self.missing_paths.is_empty() {
+            lines.push(format!("其中 {} 个文件在本机不存在（仍会保留）", self.missing_paths.len()));
+        }
+        if !self.skipped_sections.is_empty() {
+            lines.push(format!("跳过不支持的内容：{}", self.skipped_sections.join("、")));
+        }
+        if lines.is_empty() {
+            lines.push("没有可导入的内容".to_string());
+        }
+        lines
+    }
+
+    /// 是否有可导入的内容
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_none() && self.history.is_none()
+    }
+}
+
+/// 导出为 JSON 文档
+pub fn export(data: &UserData) -> Result<String> {
+    let history: BTreeMap<&String, &FileMemory> = data.history.iter().collect();
+    let mut sections = BTreeMap::new();
+    sections.insert(SETTINGS_SECTION.to_string(), Section { version: SETTINGS_VERSION, data: to_value(&data.settings)? });
+    sections.insert(HISTORY_SECTION.to_string(), Section { version: HISTORY_VERSION, data: to_value(&history)? });
+
+    let archive = Archive { version: ARCHIVE_VERSION, sections };
+    serde_json::to_string_pretty(&archive).map_err(|e| PlayerError::ConfigError(format!("序列化失败: {}", e)))
+}
+
+/// 解析并校验 JSON 文档，生成导入计划（不修改任何状态）
+pub fn parse_import(json: &str) -> Result<ImportPlan> {
+    let archive: Archive =
+        serde_json::from_str(json).map_err(|e| PlayerError::ConfigError(format!("不是有效的配置文件: {}", e)))?;
+    if archive.version > ARCHIVE_VERSION {
+        return Err(PlayerError::ConfigError(format!(
+            "配置文件版本 {} 过新（当前支持 {}），请升级播放器",
+            archive.version, ARCHIVE_VERSION
+        )));
+    }
+
+    let mut plan = ImportPlan { settings: None, history: None, skipped_sections: Vec::new(), missing_paths: Vec::new() };
+    for (name, section) in archive.sections {
+        let supported_version = match name.as_str() {
+            SETTINGS_SECTION => SETTINGS_VERSION,
+            HISTORY_SECTION => HISTORY_VERSION,
+            _ => {
+                plan.skipped_sections.push(name);
+                continue;
+            }
+        };
+        if section.version > supported_version {
+            plan.skipped_sections.push(format!("{} (v{})", name, section.version));
+            continue;
+        }
+
+        match name.as_str() {
+            SETTINGS_SECTION => plan.settings = Some(from_value(&name, section.data)?),
+            _ => plan.history = Some(from_value(&name, section.data)?),
+        }
+    }
+
+    if let Some(history) = &plan.history {
+        plan.missing_paths = history.keys().filter(|path| !Path::new(path).exists()).cloned().collect();
+        plan.missing_paths.sort();
+    }
+    Ok(plan)
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<serde_json::Value> {
+    serde_json::to_value(value).map_err(|e| PlayerError::ConfigError(format!("序列化失败: {}", e)))
+}
+
+fn from_value<T: for<'de> Deserialize<'de>>(name: &str, value: serde_json::Value) -> Result<T> {
+    serde_json::from_value(value).map_err(|e| PlayerError::ConfigError(format!("分区 {} 格式错误: {}", name, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use myy_player::TrackSource;
+    use std::path::PathBuf;
+
+    fn sample_data() -> UserData {
+        let mut history = HashMap::new();
+        history.insert(
+            "/nonexistent/movie.mkv".to_string(),
+            FileMemory { audio_stream: Some(2), subtitle: Some(None), volume: Some(1.5) },
+        );
+        history.insert(
+            env!("CARGO_MANIFEST_DIR").to_string(),
+            FileMemory {
+                audio_stream: None,
+                subtitle: Some(Some(TrackSource::External(PathBuf::from("/subs/movie.srt")))),
+                volume: None,
+            },
+        );
+        UserData {
+            settings: UserSettings {
+                default_volume: 0.8,
+                watch_folder_path: Some("/media/incoming".to_string()),
+                watch_folder_enabled: true,
+                ..Default::default()
+            },
+            history,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = sample_data();
+        let plan = parse_import(&export(&data).unwrap()).unwrap();
+        assert_eq!(plan.settings.as_ref(), Some(&data.settings));
+        assert_eq!(plan.history.as_ref(), Some(&data.history));
+        assert!(plan.skipped_sections.is_empty());
+    }
+
+    #[test]
+    fn test_subtitle_off_differs_from_unset() {
+        let plan = parse_import(&export(&sample_data()).unwrap()).unwrap();
+        let history = plan.history.unwrap();
+        assert_eq!(history["/nonexistent/movie.mkv"].subtitle, Some(None));
+        assert_eq!(history[env!("CARGO_MANIFEST_DIR")].audio_stream, None);
+    }
+
+    #[test]
+    fn test_missing_paths_are_kept_and_flagged() {
+        let plan = parse_import(&export(&sample_data()).unwrap()).unwrap();
+        assert_eq!(plan.missing_paths, vec!["/nonexistent/movie.mkv".to_string()]);
+        assert_eq!(plan.history.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_and_newer_sections_are_skipped() {
+        let json = r#"{
+            "version": 1,
+            "sections": {
+                "settings": { "version": 1, "data": { "default_volume": 0.5, "future_option": true } },
+                "history": { "version": 9, "data": "新格式" },
+                "playlists": { "version": 1, "data": [] }
+            }
+        }"#;
+        let plan = parse_import(json).unwrap();
+        assert_eq!(plan.settings.unwrap().default_volume, 0.5);
+        assert!(plan.history.is_none());
+        assert_eq!(plan.skipped_sections, vec!["history (v9)".to_string(), "playlists".to_string()]);
+    }
+
+    #[test]
+    fn test_newer_archive_version_is_rejected() {
+        let json = r#"{ "version": 2, "sections": {} }"#;
+        assert!(matches!(parse_import(json), Err(PlayerError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_malformed_section_rejects_whole_import() {
+        let json = r#"{
+            "version": 1,
+            "sections": {
+                "settings": { "version": 1, "data": { "default_volume": 0.5 } },
+                "history": { "version": 1, "data": [1, 2, 3] }
+            }
+        }"#;
+        assert!(parse_import(json).is_err());
+    }
+
+    #[test]
+    fn test_autosave_waits_for_changes_to_settle() {
+        let start = Instant::now();
+        let mut settings = UserSettings::default();
+        let mut autosave = SettingsAutoSave::new(settings.clone());
+        assert_eq!(autosave.poll(&settings, start), None);
+
+        // 拖动滑块：连续修改时不保存
+        for step in 1..=5u64 {
+            settings.default_volume = 1.0 - step as f32 * 0.1;
+            assert_eq!(autosave.poll(&settings, start + Duration::from_millis(step * 100)), None);
+        }
+        let last_change = start + Duration::from_millis(500);
+        assert_eq!(autosave.poll(&settings, last_change + AUTOSAVE_DELAY / 2), None);
+        assert_eq!(autosave.poll(&settings, last_change + AUTOSAVE_DELAY), Some(settings.clone()));
+        // 只保存一次
+        assert_eq!(autosave.poll(&settings, last_change + AUTOSAVE_DELAY * 3), None);
+
+        // 改回已保存的值不需要再写
+        settings.theme = UiTheme::Light;
+        let changed = last_change + AUTOSAVE_DELAY * 4;
+        autosave.poll(&settings, changed);
+        settings.theme = UiTheme::Dark;
+        autosave.poll(&settings, changed);
+        assert_eq!(autosave.poll(&settings, changed + AUTOSAVE_DELAY), None);
+    }
+
+    #[test]
+    fn test_settings_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("myy_player_settings_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let file = dir.join("settings.json");
+        assert!(load_settings(&file).unwrap().is_none());
+
+        let settings = UserSettings {
+            hw_decode: false,
+            theme: UiTheme::Light,
+            expanded_sections: vec![SettingsSection::Audio, SettingsSection::Advanced],
+            ..sample_data().settings
+        };
+        save_settings(&file, &settings).unwrap();
+        assert_eq!(load_settings(&file).unwrap(), Some(settings));
+
+        fs::write(&file, "{ not json").unwrap();
+        assert!(matches!(load_settings(&file), Err(PlayerError::ConfigError(_))));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_session_state_round_trip() {
+        let settings = UserSettings {
+            volume: 0.6,
+            muted: true,
+            playback_speed: 1.5,
+            subtitles_enabled: false,
+            subtitle_offset_ms: -750,
+            subtitle_scale: 1.25,
+            fit_mode: FitMode::Fill,
+            window_size: [1600.0, 900.0],
+            window_maximized: true,
+            key_bindings: Some(BTreeMap::from([("K".to_string(), "play_pause".to_string())])),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        assert_eq!(serde_json::from_str::<UserSettings>(&json).unwrap(), settings);
+
+        // 旧版本的设置文件没有这些字段：使用默认值
+        let old: UserSettings = serde_json::from_str(r#"{ "default_volume": 0.5 }"#).unwrap();
+        assert_eq!((old.volume, old.playback_speed, old.window_size), (1.0, 1.0, DEFAULT_WINDOW_SIZE));
+        assert!(old.subtitles_enabled && !old.muted);
+    }
+
+    #[test]
+    fn test_unusable_settings_fall_back_to_defaults() {
+        let dir = std::env::temp_dir().join(format!("myy_player_settings_fallback_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let file = dir.join("settings.json");
+        assert_eq!(load_settings_or_default(&file), UserSettings::default());
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&file, "{ \"volume\": ").unwrap();
+        assert_eq!(load_settings_or_default(&file), UserSettings::default());
+        fs::write(&file, r#"{ "volume": "loud" }"#).unwrap();
+        assert_eq!(load_settings_or_default(&file), UserSettings::default());
+
+        // 超出范围的值夹紧
+        fs::write(&file, r#"{ "volume": 9.0, "playback_speed": 10.0, "subtitle_scale": 0.1, "window_size": [10.0, 5000.0] }"#)
+            .unwrap();
+        let settings = load_settings_or_default(&file);
+        assert_eq!(settings.volume, MAX_VOLUME);
+        assert_eq!(settings.playback_speed, clamp_speed(10.0));
+        assert_eq!(settings.subtitle_scale, *SUBTITLE_SCALE_RANGE.start());
+        assert_eq!(settings.window_size, [MIN_INNER_SIZE.x, 5000.0]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
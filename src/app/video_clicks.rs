@@ -0,0 +1,123 @@
+// 画面上的鼠标手势：单击播放/暂停，双击切换全屏
+//
+// 双击的第一下也是一次单击：单击先记为待定，超过 DOUBLE_CLICK_DELAY 没有第二下才执行播放/暂停，
+// 双击时取消待定的单击。点击画面关闭右键菜单的那一下不算单击。单击暂停可在设置中关闭（双击全屏不受影响）。
+
+use std::time::{Duration, Instant};
+
+/// 两次单击合并为双击的最长间隔（与 egui 的双击判定一致）
+pub const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(300);
+
+/// 画面手势
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoGesture {
+    TogglePause,
+    ToggleFullscreen,
+}
+
+/// 画面区域本帧的鼠标输入
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClickInput {
+    pub clicked: bool,
+    pub double_clicked: bool,
+    pub pointer_down: bool,
+}
+
+/// 区分单击与双击
+#[derive(Debug, Default)]
+pub struct ClickTracker {
+    pending: Option<Instant>,  // 尚未确认的单击
+    suppressed: bool,  // 右键菜单打开时按下的点击（关闭菜单）不响应
+}
+
+impl ClickTracker {
+    /// 右键菜单显示中（之后关闭菜单的点击不触发手势）
+    pub fn menu_shown(&mut self) {
+        self.pending = None;
+        self.suppressed = true;
+    }
+
+    /// 每帧调用，返回确认的手势
+    pub fn update(&mut self, input: ClickInput, click_to_pause: bool, now: Instant) -> Option<VideoGesture> {
+        let ClickInput { clicked, double_clicked, pointer_down } = input;
+        if self.suppressed {
+            // 点击结束或没有按下（按 Esc 关闭了菜单）后恢复响应
+            if clicked || double_clicked || !pointer_down {
+                self.suppressed = false;
+            }
+            if clicked || double_clicked {
+                return None;
+            }
+        }
+        if double_clicked {
+            self.pending = None;
+            return Some(VideoGesture::ToggleFullscreen);
+        }
+        if clicked && click_to_pause {
+            self.pending = Some(now);
+            return None;
+        }
+        match self.pending {
+            Some(at) if now.duration_since(at) >= DOUBLE_CLICK_DELAY => {
+                self.pending = None;
+                Some(VideoGesture::TogglePause)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLICK: ClickInput = ClickInput { clicked: true, double_clicked: false, pointer_down: false };
+    const DOUBLE_CLICK: ClickInput = ClickInput { clicked: true, double_clicked: true, pointer_down: false };
+    const IDLE: ClickInput = ClickInput { clicked: false, double_clicked: false, pointer_down: false };
+
+    #[test]
+    fn test_single_click_waits_for_double_click_delay() {
+        let mut tracker = ClickTracker::default();
+        let start = Instant::now();
+        assert_eq!(tracker.update(CLICK, true, start), None);
+        assert_eq!(tracker.update(IDLE, true, start + DOUBLE_CLICK_DELAY / 2), None);
+        assert_eq!(tracker.update(IDLE, true, start + DOUBLE_CLICK_DELAY), Some(VideoGesture::TogglePause));
+        assert_eq!(tracker.update(IDLE, true, start + DOUBLE_CLICK_DELAY * 2), None);
+    }
+
+    #[test]
+    fn test_double_click_does_not_pause() {
+        let mut tracker = ClickTracker::default();
+        let start = Instant::now();
+        tracker.update(CLICK, true, start);
+        let second = start + Duration::from_millis(150);
+        assert_eq!(tracker.update(DOUBLE_CLICK, true, second), Some(VideoGesture::ToggleFullscreen));
+        assert_eq!(tracker.update(IDLE, true, second + DOUBLE_CLICK_DELAY), None);
+    }
+
+    #[test]
+    fn test_click_to_pause_can_be_disabled() {
+        let mut tracker = ClickTracker::default();
+        let start = Instant::now();
+        assert_eq!(tracker.update(CLICK, false, start), None);
+        assert_eq!(tracker.update(IDLE, false, start + DOUBLE_CLICK_DELAY), None);
+        assert_eq!(tracker.update(DOUBLE_CLICK, false, start), Some(VideoGesture::ToggleFullscreen));
+    }
+
+    #[test]
+    fn test_click_closing_context_menu_is_ignored() {
+        let mut tracker = ClickTracker::default();
+        let start = Instant::now();
+        tracker.menu_shown();
+        // 按下时关闭菜单，松开时产生的单击不响应
+        assert_eq!(tracker.update(ClickInput { pointer_down: true, ..IDLE }, true, start), None);
+        assert_eq!(tracker.update(CLICK, true, start), None);
+        assert_eq!(tracker.update(IDLE, true, start + DOUBLE_CLICK_DELAY), None);
+
+        // 按 Esc 关闭菜单后，下一次单击正常响应
+        tracker.menu_shown();
+        tracker.update(IDLE, true, start);
+        tracker.update(CLICK, true, start);
+        assert_eq!(tracker.update(IDLE, true, start + DOUBLE_CLICK_DELAY), Some(VideoGesture::TogglePause));
+    }
+}
@@ -0,0 +1,191 @@
+// 音量显示工具（内部音量为线性增益，1.0 = 100%，仅在显示边界转换为 dB）和启动时的音量渐入
+//
+// 启动时音量渐入：本次启动后的第一次播放在设置的时长内把实际增益从 0 升到目标音量，
+// 之后的暂停/继续不再渐入。渐入只按时间推进，与目标音量无关（音量为 0 时听不到，但照常完成）；
+// 用户在渐入过程中调整音量时立即结束渐入，以用户的音量为准。
+
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+/// 启动时音量渐入的时长范围（秒）
+pub const STARTUP_FADE_RANGE: RangeInclusive<f32> = 1.0..=3.0;
+
+/// 音量快捷键每次调整的量（5%）
+pub const VOLUME_STEP: f32 = 0.05;
+
+/// 线性增益转 dB（0 及以下视为静音）
+pub fn volume_to_db(volume: f32) -> f32 {
+    if volume <= 0.0 {
+        return f32::NEG_INFINITY;
+    }
+    20.0 * volume.log10()
+}
+
+/// 是否处于增益放大区间（超过 100%）
+pub fn is_boosted(volume: f32) -> bool {
+    volume > 1.0 + f32::EPSILON
+}
+
+/// 增益放大量显示文本（如 "+6.0dB"，未放大时返回 None）
+pub fn format_boost(volume: f32) -> Option<String> {
+    is_boosted(volume).then(|| format!("+{:.1}dB", volume_to_db(volume)))
+}
+
+/// 格式化音量显示（超过 100% 时附带 dB）
+pub fn format_volume(volume: f32) -> String {
+    let percent = format!("{:.0}%", volume * 100.0);
+    match format_boost(volume) {
+        Some(boost) => format!("{} ({})", percent, boost),
+        None => percent,
+    }
+}
+
+/// 启动时的音量渐入
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolumeRamp {
+    /// 等待本次启动后的第一次播放
+    Armed(Duration),
+    /// 渐入中
+    Ramping { started: Instant, duration: Duration },
+    /// 已完成、被取消或未启用
+    Done,
+}
+
+impl VolumeRamp {
+    /// 按设置创建（时长超出范围时夹紧）
+    pub fn new(enabled: bool, secs: f32) -> Self {
+        if !enabled {
+            return Self::Done;
+        }
+        let secs = if secs.is_finite() { secs.clamp(*STARTUP_FADE_RANGE.start(), *STARTUP_FADE_RANGE.end()) } else { 2.0 };
+        Self::Armed(Duration::from_secs_f32(secs))
+    }
+
+    /// 开始播放时调用（只有第一次播放开始渐入）
+    pub fn start(&mut self, now: Instant) {
+        if let Self::Armed(duration) = *self {
+            *self = Self::Ramping { started: now, duration };
+        }
+    }
+
+    /// 用户调整了音量：立即结束渐入
+    pub fn cancel(&mut self) {
+        *self = Self::Done;
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self != Self::Done
+    }
+
+    /// 渐入进度（0.0 ~ 1.0，只在渐入中返回，用于音量滑块上的动画）
+    pub fn progress(&self, now: Instant) -> Option<f32> {
+        match *self {
+            Self::Ramping { started, duration } => {
+                Some((now.saturating_duration_since(started).as_secs_f32() / duration.as_secs_f32()).min(1.0))
+            }
+            _ => None,
+        }
+    }
+
+    /// 当前增益系数（0.0 ~ 1.0，乘以目标音量得到实际音量）；渐入结束时转为 Done
+    pub fn gain(&mut self, now: Instant) -> f32 {
+        match *self {
+            Self::Armed(_) => 0.0,
+            Self::Ramping { .. } => {
+                let progress = self.progress(now).unwrap_or(1.0);
+                if progress >= 1.0 {
+                    *self = Self::Done;
+                }
+                progress
+            }
+            Self::Done => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.01, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_volume_to_db() {
+        assert_close(volume_to_db(1.0), 0.0);
+        assert_close(volume_to_db(2.0), 6.02);
+        assert_close(volume_to_db(1.5), 3.52);
+        assert_close(volume_to_db(0.5), -6.02);
+        assert_eq!(volume_to_db(0.0), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_format_volume() {
+        assert_eq!(format_volume(0.8), "80%");
+        assert_eq!(format_volume(1.0), "100%");
+        assert_eq!(format_volume(2.0), "200% (+6.0dB)");
+        assert_eq!(format_boost(1.0), None);
+        assert_eq!(format_boost(1.5).as_deref(), Some("+3.5dB"));
+    }
+
+    #[test]
+    fn test_startup_ramp_runs_once() {
+        let start = Instant::now();
+        let mut ramp = VolumeRamp::new(true, 2.0);
+        assert_eq!(ramp.gain(start), 0.0);
+
+        ramp.start(start);
+        assert_close(ramp.gain(start + Duration::from_millis(500)), 0.25);
+        assert_close(ramp.gain(start + Duration::from_secs(1)), 0.5);
+        assert_eq!(ramp.progress(start + Duration::from_secs(1)), Some(0.5));
+        assert!(ramp.is_active());
+        assert_eq!(ramp.gain(start + Duration::from_secs(2)), 1.0);
+        assert_eq!(ramp, VolumeRamp::Done);
+        assert_eq!(ramp.progress(start + Duration::from_secs(2)), None);
+
+        // 之后的播放不再渐入
+        ramp.start(start + Duration::from_secs(5));
+        assert_eq!(ramp.gain(start + Duration::from_secs(5)), 1.0);
+
+        // 未启用
+        let mut ramp = VolumeRamp::new(false, 2.0);
+        ramp.start(start);
+        assert_eq!(ramp.gain(start), 1.0);
+
+        // 时长夹紧到 1 ~ 3 秒
+        assert_eq!(VolumeRamp::new(true, 10.0), VolumeRamp::Armed(Duration::from_secs(3)));
+        assert_eq!(VolumeRamp::new(true, 0.0), VolumeRamp::Armed(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_user_input_cancels_ramp() {
+        let start = Instant::now();
+        let mut ramp = VolumeRamp::new(true, 3.0);
+        ramp.start(start);
+        assert_close(ramp.gain(start + Duration::from_secs(1)), 0.333);
+        ramp.cancel();
+        assert!(!ramp.is_active());
+        assert_eq!(ramp.gain(start + Duration::from_secs(1)), 1.0);
+        // 取消后再开始播放也不会重新渐入
+        ramp.start(start + Duration::from_secs(2));
+        assert_eq!(ramp, VolumeRamp::Done);
+
+        // 播放前取消（如先调整了音量）
+        let mut ramp = VolumeRamp::new(true, 1.0);
+        ramp.cancel();
+        ramp.start(start);
+        assert_eq!(ramp.gain(start), 1.0);
+    }
+
+    #[test]
+    fn test_ramp_completes_while_muted() {
+        let start = Instant::now();
+        let mut ramp = VolumeRamp::new(true, 1.0);
+        ramp.start(start);
+        let volume = 0.0;
+        assert_eq!(volume * ramp.gain(start + Duration::from_millis(500)), 0.0);
+        assert_eq!(volume * ramp.gain(start + Duration::from_secs(1)), 0.0);
+        assert!(!ramp.is_active());
+    }
+}
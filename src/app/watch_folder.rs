@@ -0,0 +1,405 @@
+use myy_player::{is_supported_video_file, PlayerError, Result};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::{debug, info, warn};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 文件大小保持不变多久后视为写入完成
+const STABLE_DURATION: Duration = Duration::from_secs(2);
+
+/// 监视线程轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 文件夹不存在时的重试间隔
+const MISSING_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 文件写入稳定性检测（避免打开尚未写完的文件）
+///
+/// 每个候选文件记录最近一次观察到的大小和大小变化的时刻，
+/// 大小非零且持续 `stable_for` 未变化时视为就绪
+#[derive(Debug)]
+pub struct StabilityTracker {
+    stable_for: Duration,
+    pending: HashMap<PathBuf, (u64, Instant)>,  // 路径 -> (大小, 最近一次大小变化时刻)
+}
+
+impl StabilityTracker {
+    /// 创建检测器
+    pub fn new(stable_for: Duration) -> Self {
+        Self {
+            stable_for,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// 记录一次观察结果（新文件或大小变化时重新计时）
+    pub fn observe(&mut self, path: PathBuf, size: u64, now: Instant) {
+        match self.pending.get_mut(&path) {
+            Some((last_size, since)) => {
+                if *last_size != size {
+                    *last_size = size;
+                    *since = now;
+                }
+            }
+            None => {
+                self.pending.insert(path, (size, now));
+            }
+        }
+    }
+
+    /// 移除候选文件（文件被删除或移走）
+    pub fn forget(&mut self, path: &Path) {
+        self.pending.remove(path);
+    }
+
+    /// 当前等待中的候选文件
+    pub fn pending_paths(&self) -> Vec<PathBuf> {
+        self.pending.keys().cloned().collect()
+    }
+
+    /// 取出已稳定的文件（按路径排序，每个文件只返回一次）
+    pub fn take_ready(&mut self, now: Instant) -> Vec<PathBuf> {
+        let mut ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, (size, since))| *size > 0 && now.saturating_duration_since(*since) >= self.stable_for)
+            .map(|(path, _)| path.clone())
+            .collect();
+        ready.sort();
+
+        for path in &ready {
+            self.pending.remove(path);
+        }
+        ready
+    }
+}
+
+/// 监视文件夹：检测新出现的视频文件，写入完成后通过通道发出
+pub struct WatchFolder {
+    folder: PathBuf,
+    running: Arc<AtomicBool>,
+    thread_handle: Option<thread::JoinHandle<()>>,
+    ready_rx: Receiver<PathBuf>,
+}
+
+impl WatchFolder {
+    /// 开始监视文件夹（启动时已存在的文件不会触发）
+    pub fn start(folder: &Path) -> Result<Self> {
+        if !folder.is_dir() {
+            return Err(PlayerError::Other(format!("监视文件夹不存在: {}", folder.display())));
+        }
+
+        let running = Arc::new(AtomicBool::new(true));
+        let (ready_tx, ready_rx) = unbounded();
+
+        let thread_folder = folder.to_path_buf();
+        let thread_running = running.clone();
+        let thread_handle = thread::Builder::new()
+            .name("watch-folder".to_string())
+            .spawn(move || Self::run(thread_folder, thread_running, ready_tx))
+            .map_err(|e| PlayerError::Other(format!("无法启动监视线程: {}", e)))?;
+
+        info!("👀 开始监视文件夹: {}", folder.display());
+
+        Ok(Self {
+            folder: folder.to_path_buf(),
+            running,
+            thread_handle: Some(thread_handle),
+            ready_rx,
+        })
+    }
+
+    /// 监视的文件夹
+    pub fn folder(&self) -> &Path {
+        &self.folder
+    }
+
+    /// 取出一个已写入完成的新文件（非阻塞）
+    pub fn try_recv(&self) -> Option<PathBuf> {
+        self.ready_rx.try_recv().ok()
+    }
+
+    /// 停止监视并等待线程退出
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+            info!("👀 已停止监视文件夹: {}", self.folder.display());
+        }
+    }
+
+    /// 监视线程主循环
+    fn run(folder: PathBuf, running: Arc<AtomicBool>, ready_tx: Sender<PathBuf>) {
+        let (event_tx, event_rx) = unbounded::<notify::Result<Event>>();
+        let mut watcher: Option<RecommendedWatcher> = None;
+        let mut tracker = StabilityTracker::new(STABLE_DURATION);
+        let mut known: HashSet<PathBuf> = list_video_files(&folder).into_iter().collect();
+        let mut folder_missing = false;
+        let mut last_missing_check = Instant::now();
+
+        while running.load(Ordering::SeqCst) {
+            // ========== 文件夹消失/重新出现处理 ==========
+            if watcher.is_none() {
+                if folder_missing && last_missing_check.elapsed() < MISSING_RETRY_INTERVAL {
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+                last_missing_check = Instant::now();
+
+                if !folder.is_dir() {
+                    if !folder_missing {
+                        warn!("⚠️  监视文件夹不存在，等待其重新出现: {}", folder.display());
+                        folder_missing = true;
+                    }
+                    continue;
+                }
+
+                match create_watcher(&folder, event_tx.clone()) {
+                    Ok(w) => {
+                        watcher = Some(w);
+                        if folder_missing {
+                            // 文件夹重新出现：其中的文件都是监视中断期间到达的
+                            info!("👀 监视文件夹已恢复: {}", folder.display());
+                            let now = Instant::now();
+                            for path in list_video_files(&folder) {
+                                if known.insert(path.clone()) {
+                                    observe_file(&mut tracker, &path, now);
+                                }
+                            }
+                            folder_missing = false;
+                        }
+                    }
+                    Err(e) => {
+                        warn!("⚠️  创建文件夹监视失败: {}", e);
+                        folder_missing = true;
+                        continue;
+                    }
+                }
+            }
+
+            // ========== 处理文件系统事件 ==========
+            if let Ok(result) = event_rx.recv_timeout(POLL_INTERVAL) {
+                let now = Instant::now();
+                let mut events = vec![result];
+                events.extend(event_rx.try_iter());
+
+                for event in events {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(e) => {
+                            warn!("⚠️  文件夹监视错误: {}", e);
+                            continue;
+                        }
+                    };
+
+                    match event.kind {
+                        EventKind::Create(_) | EventKind::Modify(_) => {
+                            for path in event.paths {
+                                if is_supported_video_file(&path) && path.is_file() && known.insert(path.clone()) {
+                                    debug!("👀 发现新文件: {}", path.display());
+                                    observe_file(&mut tracker, &path, now);
+                                }
+                            }
+                        }
+                        EventKind::Remove(_) => {
+                            for path in event.paths {
+                                tracker.forget(&path);
+                                known.remove(&path);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if !folder.is_dir() {
+                // 文件夹被删除：丢弃监视器，进入等待状态
+                watcher = None;
+                continue;
+            }
+
+            // ========== 刷新候选文件大小并发出就绪文件 ==========
+            let now = Instant::now();
+            for path in tracker.pending_paths() {
+                if path.is_file() {
+                    observe_file(&mut tracker, &path, now);
+                } else {
+                    tracker.forget(&path);
+                    known.remove(&path);
+                }
+            }
+
+            for path in tracker.take_ready(now) {
+                info!("👀 新文件写入完成: {}", path.display());
+                if ready_tx.send(path).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for WatchFolder {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 创建文件夹监视器（事件转发到通道）
+fn create_watcher(folder: &Path, event_tx: Sender<notify::Result<Event>>) -> notify::Result<RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let _ = event_tx.send(event);
+    })?;
+    watcher.watch(folder, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// 记录文件当前大小
+fn observe_file(tracker: &mut StabilityTracker, path: &Path, now: Instant) {
+    if let Ok(metadata) = fs::metadata(path) {
+        tracker.observe(path.to_path_buf(), metadata.len(), now);
+    }
+}
+
+/// 列出文件夹中支持的视频文件
+fn list_video_files(folder: &Path) -> Vec<PathBuf> {
+    fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && is_supported_video_file(path))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// 创建独立的临时目录
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("myy_player_watch_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 追加写入数据（模拟采集程序分段写入）
+    fn append(path: &Path, bytes: usize) {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path).unwrap();
+        file.write_all(&vec![0u8; bytes]).unwrap();
+    }
+
+    #[test]
+    fn test_slow_write_waits_until_stable() {
+        let dir = temp_dir("slow_write");
+        let path = dir.join("capture.mp4");
+        let mut tracker = StabilityTracker::new(Duration::from_secs(2));
+        let t0 = Instant::now();
+
+        // 分三次写入，每次间隔 1 秒
+        for step in 0..3u64 {
+            append(&path, 1024);
+            let now = t0 + Duration::from_secs(step);
+            observe_file(&mut tracker, &path, now);
+            assert!(tracker.take_ready(now).is_empty(), "写入过程中不应就绪");
+        }
+
+        // 最后一次写入后 1.5 秒：仍未稳定
+        let now = t0 + Duration::from_millis(3500);
+        observe_file(&mut tracker, &path, now);
+        assert!(tracker.take_ready(now).is_empty());
+
+        // 最后一次写入后 2 秒：就绪，且只返回一次
+        let now = t0 + Duration::from_secs(4);
+        observe_file(&mut tracker, &path, now);
+        assert_eq!(tracker.take_ready(now), vec![path.clone()]);
+        assert!(tracker.take_ready(now + Duration::from_secs(10)).is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_empty_file_never_ready() {
+        let dir = temp_dir("empty");
+        let path = dir.join("empty.mkv");
+        fs::File::create(&path).unwrap();
+
+        let mut tracker = StabilityTracker::new(Duration::from_secs(2));
+        let t0 = Instant::now();
+        observe_file(&mut tracker, &path, t0);
+        assert!(tracker.take_ready(t0 + Duration::from_secs(10)).is_empty());
+
+        // 开始写入后正常计时
+        append(&path, 10);
+        observe_file(&mut tracker, &path, t0 + Duration::from_secs(11));
+        assert_eq!(tracker.take_ready(t0 + Duration::from_secs(13)), vec![path.clone()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_removed_file_is_forgotten() {
+        let dir = temp_dir("removed");
+        let path = dir.join("partial.mp4");
+        append(&path, 100);
+
+        let mut tracker = StabilityTracker::new(Duration::from_secs(2));
+        let t0 = Instant::now();
+        observe_file(&mut tracker, &path, t0);
+        fs::remove_file(&path).unwrap();
+        tracker.forget(&path);
+
+        assert!(tracker.pending_paths().is_empty());
+        assert!(tracker.take_ready(t0 + Duration::from_secs(5)).is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_multiple_files_ready_in_order() {
+        let dir = temp_dir("multiple");
+        let a = dir.join("a.mp4");
+        let b = dir.join("b.mp4");
+        append(&a, 10);
+        append(&b, 10);
+
+        let mut tracker = StabilityTracker::new(Duration::from_secs(2));
+        let t0 = Instant::now();
+        observe_file(&mut tracker, &b, t0);
+        observe_file(&mut tracker, &a, t0);
+
+        // b 继续写入，a 已稳定
+        append(&b, 10);
+        observe_file(&mut tracker, &b, t0 + Duration::from_secs(1));
+        assert_eq!(tracker.take_ready(t0 + Duration::from_secs(2)), vec![a.clone()]);
+        assert_eq!(tracker.take_ready(t0 + Duration::from_secs(3)), vec![b.clone()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_list_video_files_filters_extensions() {
+        let dir = temp_dir("list");
+        append(&dir.join("movie.MP4"), 1);
+        append(&dir.join("notes.txt"), 1);
+        append(&dir.join("clip.mkv"), 1);
+
+        let mut files = list_video_files(&dir);
+        files.sort();
+        assert_eq!(files, vec![dir.join("clip.mkv"), dir.join("movie.MP4")]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
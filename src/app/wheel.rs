@@ -0,0 +1,82 @@
+// 画面上的滚轮：竖直滚动调整音量，水平滚动（或 Shift+滚轮）跳转
+//
+// 鼠标滚轮每格约 NOTCH_POINTS（egui-winit 按每行 50 点换算），触控板的平滑滚动每帧只有几点。
+// 滚动量按方向分别累积，满一格才产生一步（音量 ±VOLUME_STEP、跳转 ±WHEEL_SEEK_MS），
+// 避免平滑滚动产生大量细小的 seek；反向滚动时丢弃之前的余量。Ctrl+滚轮是画面缩放，不经过这里。
+
+use egui::Vec2;
+
+/// 一格滚轮的滚动量（点）
+pub const NOTCH_POINTS: f32 = 50.0;
+
+/// 水平滚动一格的跳转量（毫秒）
+pub const WHEEL_SEEK_MS: i64 = 5_000;
+
+/// 一帧滚动产生的步数（正值为音量增大 / 快进）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WheelSteps {
+    pub volume: i32,
+    pub seek: i32,
+}
+
+/// 滚动量累积
+#[derive(Debug, Default)]
+pub struct WheelAccumulator {
+    remainder: Vec2,
+}
+
+impl WheelAccumulator {
+    /// 加入一帧的滚动量（egui 的方向：向上滚 y 为正，向左滚 x 为正，Shift+向上滚为向左）
+    pub fn add(&mut self, delta: Vec2) -> WheelSteps {
+        WheelSteps {
+            volume: take_steps(&mut self.remainder.y, delta.y),
+            // 向右滚动（x 为负）快进
+            seek: -take_steps(&mut self.remainder.x, delta.x),
+        }
+    }
+}
+
+/// 累积一个方向的滚动量，返回满格的步数
+fn take_steps(remainder: &mut f32, delta: f32) -> i32 {
+    if delta == 0.0 || !delta.is_finite() {
+        return 0;
+    }
+    if *remainder != 0.0 && remainder.signum() != delta.signum() {
+        *remainder = 0.0;
+    }
+    *remainder += delta;
+    let steps = (*remainder / NOTCH_POINTS).trunc();
+    *remainder -= steps * NOTCH_POINTS;
+    steps as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wheel_notches_map_to_steps() {
+        let mut wheel = WheelAccumulator::default();
+        assert_eq!(wheel.add(Vec2::new(0.0, NOTCH_POINTS)), WheelSteps { volume: 1, seek: 0 });
+        assert_eq!(wheel.add(Vec2::new(0.0, -NOTCH_POINTS * 2.0)), WheelSteps { volume: -2, seek: 0 });
+        // Shift+向下滚动（向右）快进
+        assert_eq!(wheel.add(Vec2::new(-NOTCH_POINTS, 0.0)), WheelSteps { volume: 0, seek: 1 });
+    }
+
+    #[test]
+    fn test_smooth_scrolling_accumulates() {
+        let mut wheel = WheelAccumulator::default();
+        let mut steps = 0;
+        for _ in 0..24 {
+            steps += wheel.add(Vec2::new(0.0, 4.5)).volume;
+        }
+        // 108 点：两格，余量保留
+        assert_eq!(steps, 2);
+        assert_eq!(wheel.add(Vec2::new(0.0, 42.0)).volume, 1);
+
+        // 反向滚动丢弃余量
+        wheel.add(Vec2::new(0.0, 40.0));
+        assert_eq!(wheel.add(Vec2::new(0.0, -20.0)).volume, 0);
+        assert_eq!(wheel.add(Vec2::new(0.0, -30.0)).volume, -1);
+    }
+}
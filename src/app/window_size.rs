@@ -0,0 +1,97 @@
+// 窗口尺寸计算（按视频原始尺寸的固定比例调整窗口，目标是视频视口而不是整个窗口）
+
+use egui::Vec2;
+
+/// 窗口内部区域的最小尺寸
+pub const MIN_INNER_SIZE: Vec2 = Vec2::new(800.0, 600.0);
+
+/// 窗口缩放目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowScale {
+    /// 视频原始尺寸的百分比
+    Percent(u32),
+    /// 在屏幕内尽可能大
+    FitScreen,
+}
+
+impl WindowScale {
+    /// 菜单/提示中显示的名称
+    pub fn label(&self) -> String {
+        match self {
+            WindowScale::Percent(percent) => format!("{}%", percent),
+            WindowScale::FitScreen => "适合屏幕".to_string(),
+        }
+    }
+}
+
+/// 视频等比适配到视口后的缩放比例（1.0 = 原始尺寸）
+pub fn fitted_scale(display: Vec2, viewport: Vec2) -> f32 {
+    if display.x <= 0.0 || display.y <= 0.0 {
+        return 0.0;
+    }
+    (viewport.x / display.x).min(viewport.y / display.y)
+}
+
+/// 计算目标窗口内部尺寸
+///
+/// `display` 为视频原始显示尺寸，`chrome` 为窗口中视频视口以外的部分（控制栏等），
+/// `max_inner` 为屏幕可容纳的最大内部尺寸。返回 (目标尺寸, 是否被夹紧)
+pub fn target_inner_size(display: Vec2, scale: WindowScale, chrome: Vec2, max_inner: Vec2) -> (Vec2, bool) {
+    let scale = match scale {
+        WindowScale::Percent(percent) => percent as f32 / 100.0,
+        WindowScale::FitScreen => fitted_scale(display, max_inner - chrome),
+    };
+    let wanted = (display * scale).floor() + chrome;
+    let size = wanted.max(MIN_INNER_SIZE).min(max_inner.max(MIN_INNER_SIZE));
+    (size, size != wanted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Vec2 = Vec2::new(2560.0, 1400.0);
+    const CHROME: Vec2 = Vec2::new(0.0, 80.0);
+
+    #[test]
+    fn test_fitted_scale() {
+        assert_eq!(fitted_scale(Vec2::new(1920.0, 1080.0), Vec2::new(960.0, 1000.0)), 0.5);
+        assert_eq!(fitted_scale(Vec2::new(1920.0, 1080.0), Vec2::new(3840.0, 1080.0)), 1.0);
+        assert_eq!(fitted_scale(Vec2::ZERO, Vec2::new(100.0, 100.0)), 0.0);
+    }
+
+    #[test]
+    fn test_percent_targets_video_viewport() {
+        let display = Vec2::new(1280.0, 720.0);
+        let (size, clamped) = target_inner_size(display, WindowScale::Percent(100), CHROME, SCREEN);
+        assert_eq!(size, Vec2::new(1280.0, 800.0));
+        assert!(!clamped);
+
+        // 视口部分恰好是原始尺寸
+        assert_eq!(fitted_scale(display, size - CHROME), 1.0);
+    }
+
+    #[test]
+    fn test_oversized_scale_is_clamped_to_screen() {
+        let (size, clamped) = target_inner_size(Vec2::new(1920.0, 1080.0), WindowScale::Percent(200), CHROME, SCREEN);
+        assert_eq!(size, SCREEN);
+        assert!(clamped);
+    }
+
+    #[test]
+    fn test_small_scale_is_clamped_to_min_size() {
+        let (size, clamped) = target_inner_size(Vec2::new(640.0, 360.0), WindowScale::Percent(50), CHROME, SCREEN);
+        assert_eq!(size, MIN_INNER_SIZE);
+        assert!(clamped);
+    }
+
+    #[test]
+    fn test_fit_screen() {
+        let display = Vec2::new(1920.0, 1080.0);
+        let (size, clamped) = target_inner_size(display, WindowScale::FitScreen, CHROME, SCREEN);
+        assert!(!clamped);
+        assert_eq!(size.y, SCREEN.y);
+        assert!(size.x <= SCREEN.x);
+        assert!((fitted_scale(display, size - CHROME) - (SCREEN.y - CHROME.y) / 1080.0).abs() < 0.01);
+    }
+}
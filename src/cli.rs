@@ -0,0 +1,162 @@
+// 命令行参数（myy_player [选项] [媒体文件或网络地址] [外部字幕文件]）
+//
+// 参数按 OsString 解析：Windows "打开方式" 传入的路径可能不是合法的 UTF-8，std::env::args() 遇到时会直接崩溃。
+// 本地路径保持为 PathBuf，直到真正打开时才转换为字符串（无法表示时报错，不做有损转换）。
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// 用法说明（--help）
+pub const USAGE: &str = "\
+用法: myy_player [选项] [媒体文件或网络地址] [外部字幕文件]
+
+  媒体文件          本地视频、图像或 .m3u/.m3u8 播放列表
+  网络地址          http(s)://、rtsp://、rtmp:// 等网络流
+  外部字幕文件      代替自动查找的字幕（仅本地视频）
+
+选项:
+  --safe-mode      关闭硬件解码、字幕自动加载、监视文件夹和自动匹配刷新率
+  --debug-ui       启动时打开开发者面板
+  --new-instance   总是启动新窗口（默认把文件转发给已运行的播放器）
+  -h, --help       显示本说明";
+
+/// 启动后要打开的媒体
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupMedia {
+    /// 本地文件
+    File(PathBuf),
+    /// 网络地址
+    Url(String),
+}
+
+/// 解析后的命令行
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CommandLine {
+    pub help: bool,
+    pub debug_ui: bool,
+    pub safe_mode: bool,
+    pub new_instance: bool,
+    pub media: Option<StartupMedia>,
+    pub subtitle: Option<PathBuf>,
+}
+
+impl CommandLine {
+    /// 解析参数（不含程序名）；无法识别的选项和多余的参数返回错误
+    pub fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Self, String> {
+        let mut command_line = Self::default();
+        let mut positional = Vec::new();
+        for arg in args {
+            match arg.to_str() {
+                Some("-h") | Some("--help") => command_line.help = true,
+                Some("--debug-ui") => command_line.debug_ui = true,
+                Some("--safe-mode") => command_line.safe_mode = true,
+                Some("--new-instance") => command_line.new_instance = true,
+                Some(option) if option.starts_with("--") => return Err(format!("未知选项: {}", option)),
+                _ => positional.push(arg),
+            }
+        }
+        let mut positional = positional.into_iter();
+        command_line.media = positional.next().map(|arg| match arg.to_str() {
+            Some(url) if url.contains("://") => StartupMedia::Url(url.to_string()),
+            _ => StartupMedia::File(PathBuf::from(arg)),
+        });
+        command_line.subtitle = positional.next().map(PathBuf::from);
+        if let Some(extra) = positional.next() {
+            return Err(format!("多余的参数: {}", extra.to_string_lossy()));
+        }
+        Ok(command_line)
+    }
+
+    /// 检查本地文件是否存在（不存在时返回错误说明；网络地址不检查）
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(StartupMedia::File(path)) = &self.media {
+            if !path.exists() {
+                return Err(format!("文件不存在: {}", path.display()));
+            }
+        }
+        if let Some(subtitle) = &self.subtitle {
+            if !subtitle.is_file() {
+                return Err(format!("字幕文件不存在: {}", subtitle.display()));
+            }
+        }
+        Ok(())
+    }
+
+    /// 是否尝试转发给已运行的实例（安全模式和开发者面板需要新进程的启动选项，总是新开窗口）
+    pub fn forwards_to_running(&self) -> bool {
+        !(self.new_instance || self.safe_mode || self.debug_ui)
+    }
+
+    /// 转发给已运行实例的位置参数（本地路径转为绝对路径：对方的工作目录可能不同）；
+    /// 路径无法表示为 UTF-8 时返回 None
+    pub fn forwarded_args(&self) -> Option<Vec<String>> {
+        let absolute = |path: &PathBuf| std::path::absolute(path).ok()?.into_os_string().into_string().ok();
+        let mut args = Vec::new();
+        match &self.media {
+            Some(StartupMedia::Url(url)) => args.push(url.clone()),
+            Some(StartupMedia::File(path)) => args.push(absolute(path)?),
+            None => {}
+        }
+        if let Some(subtitle) = &self.subtitle {
+            args.push(absolute(subtitle)?);
+        }
+        Some(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CommandLine, String> {
+        CommandLine::parse(args.iter().map(OsString::from))
+    }
+
+    #[test]
+    fn test_parse_options_media_and_subtitle() {
+        let command_line = parse(&["--safe-mode", "/videos/a.mkv", "/videos/a.zh.srt"]).unwrap();
+        assert!(command_line.safe_mode && !command_line.debug_ui && !command_line.help);
+        assert_eq!(command_line.media, Some(StartupMedia::File(PathBuf::from("/videos/a.mkv"))));
+        assert_eq!(command_line.subtitle, Some(PathBuf::from("/videos/a.zh.srt")));
+
+        let command_line = parse(&["https://example.com/live.m3u8", "--debug-ui"]).unwrap();
+        assert_eq!(command_line.media, Some(StartupMedia::Url("https://example.com/live.m3u8".to_string())));
+        assert!(command_line.debug_ui);
+
+        assert!(parse(&["-h"]).unwrap().help);
+        assert_eq!(parse(&[]).unwrap(), CommandLine::default());
+        assert!(parse(&["--fullscreen"]).is_err());
+        assert!(parse(&["a.mkv", "a.srt", "b.mkv"]).is_err());
+    }
+
+    #[test]
+    fn test_forwarded_args_are_absolute() {
+        let command_line = parse(&["videos/a.mkv", "a.srt"]).unwrap();
+        assert!(command_line.forwards_to_running());
+        let args = command_line.forwarded_args().unwrap();
+        assert_eq!(args.len(), 2);
+        assert!(args.iter().all(|arg| std::path::Path::new(arg).is_absolute()), "{:?}", args);
+        assert!(args[0].ends_with("a.mkv"));
+        assert_eq!(parse(&["rtsp://cam/1"]).unwrap().forwarded_args().unwrap(), ["rtsp://cam/1"]);
+        assert!(!parse(&["--new-instance", "a.mkv"]).unwrap().forwards_to_running());
+        assert!(!parse(&["--safe-mode"]).unwrap().forwards_to_running());
+    }
+
+    #[test]
+    fn test_validate_checks_local_paths_only() {
+        let missing = parse(&["/definitely/missing/a.mkv"]).unwrap();
+        assert!(missing.validate().unwrap_err().contains("a.mkv"));
+        assert!(parse(&["rtmp://host/app/stream"]).unwrap().validate().is_ok());
+        let subtitle = parse(&["rtmp://host/app/stream", "/definitely/missing/a.srt"]).unwrap();
+        assert!(subtitle.validate().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_path_is_kept_intact() {
+        use std::os::unix::ffi::OsStringExt;
+        let raw = OsString::from_vec(b"/videos/\xff.mkv".to_vec());
+        let command_line = CommandLine::parse([raw.clone()]).unwrap();
+        assert_eq!(command_line.media, Some(StartupMedia::File(PathBuf::from(raw))));
+    }
+}
@@ -11,6 +11,7 @@ struct ClockInner {
     base_pts: i64,              // 基准 PTS（毫秒）
     base_instant: Instant,      // 基准时刻
     playback_rate: f64,         // 播放速率（1.0 = 正常）
+    drift_ppm: f64,             // 音频设备时钟偏差修正（ppm，见 player::clock_skew）
     paused: bool,
     paused_at: i64,             // 暂停时的位置
 }
@@ -22,6 +23,7 @@ impl PlaybackClock {
                 base_pts: 0,
                 base_instant: Instant::now(),
                 playback_rate: 1.0,
+                drift_ppm: 0.0,
                 paused: true,
                 paused_at: 0,
             })),
@@ -31,12 +33,7 @@ impl PlaybackClock {
     /// 获取当前播放时间（毫秒）
     pub fn now(&self) -> i64 {
         let inner = self.inner.lock().unwrap();
-        if inner.paused {
-            inner.paused_at
-        } else {
-            let elapsed = inner.base_instant.elapsed().as_millis() as i64;
-            inner.base_pts + (elapsed as f64 * inner.playback_rate) as i64
-        }
+        self.now_unlocked(&inner)
     }
 
     /// 设置播放位置
@@ -77,17 +74,27 @@ impl PlaybackClock {
         inner.playback_rate = rate;
     }
 
-    /// 是否暂停
-    pub fn is_paused(&self) -> bool {
-        self.inner.lock().unwrap().paused
+    /// 设置音频设备时钟偏差修正（ppm，负值表示设备比标称采样率慢，时钟相应放慢）
+    pub fn set_drift_ppm(&self, ppm: f64) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.drift_ppm == ppm {
+            return;
+        }
+        if !inner.paused {
+            let current_time = self.now_unlocked(&inner);
+            inner.base_pts = current_time;
+            inner.base_instant = Instant::now();
+        }
+        inner.drift_ppm = ppm;
     }
 
     fn now_unlocked(&self, inner: &ClockInner) -> i64 {
         if inner.paused {
             inner.paused_at
         } else {
-            let elapsed = inner.base_instant.elapsed().as_millis() as i64;
-            inner.base_pts + (elapsed as f64 * inner.playback_rate) as i64
+            let elapsed = inner.base_instant.elapsed().as_secs_f64() * 1000.0;
+            let rate = inner.playback_rate * (1.0 + inner.drift_ppm * 1e-6);
+            inner.base_pts + (elapsed * rate) as i64
         }
     }
 }
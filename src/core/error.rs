@@ -1,40 +1,216 @@
+use ffmpeg_next::util::error::{
+    EACCES, ECONNABORTED, ECONNREFUSED, ECONNRESET, EHOSTUNREACH, ENETDOWN, ENETUNREACH, ENOENT, EPERM, ETIMEDOUT,
+};
 use thiserror::Error;
 
+/// 播放器错误
 #[derive(Error, Debug)]
 pub enum PlayerError {
+    /// FFmpeg 调用失败
     #[error("FFmpeg 错误: {0}")]
     FFmpegError(#[from] ffmpeg_next::Error),
 
+    /// 文件读写失败
     #[error("IO 错误: {0}")]
     IoError(#[from] std::io::Error),
 
+    /// 无法打开媒体源
     #[error("无法打开文件: {0}")]
     OpenError(String),
 
+    /// FFmpeg 打开输入失败（保留原始错误，用于区分文件不存在、格式不支持、网络超时等）
+    #[error("无法打开{target}: {source}")]
+    OpenFailed {
+        /// 打开的对象（文件、网络流等）
+        target: &'static str,
+        /// FFmpeg 返回的错误
+        source: ffmpeg_next::Error,
+    },
+
+    /// 没有视频流
     #[error("无法找到视频流")]
     NoVideoStream,
 
+    /// 没有音频流
     #[error("无法找到音频流")]
     NoAudioStream,
 
+    /// 解码失败
     #[error("解码错误: {0}")]
     DecodeError(String),
 
+    /// 帧尺寸超出允许范围
+    #[error("帧尺寸无效: {width}x{height}")]
+    InvalidFrameSize {
+        /// 宽度（像素）
+        width: u32,
+        /// 高度（像素）
+        height: u32,
+    },
+
+    /// 渲染失败
     #[error("渲染错误: {0}")]
     RenderError(String),
 
+    /// 音频输出设备出错
     #[error("音频输出错误: {0}")]
     AudioError(String),
 
+    /// 网络流读取失败
     #[error("网络错误: {0}")]
     NetworkError(String),
 
+    /// 媒体源不支持 seek
+    #[error("该媒体不支持跳转")]
+    SeekUnsupported,
+
+    /// 设置或参数无效
+    #[error("配置错误: {0}")]
+    ConfigError(String),
+
+    /// 其他错误
     #[error("其他错误: {0}")]
     Other(String),
 
+    /// 来自 anyhow 的错误
     #[error("Anyhow 错误: {0}")]
     AnyhowError(#[from] anyhow::Error),
 }
 
+/// 播放器操作的结果
 pub type Result<T> = std::result::Result<T, PlayerError>;
 
+
+/// 面向用户的错误分类（决定提示的标题和处理建议）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// 文件不存在
+    NotFound,
+    /// 没有访问权限
+    PermissionDenied,
+    /// 不支持的封装格式或协议
+    UnsupportedFormat,
+    /// 没有对应的解码器
+    UnsupportedCodec,
+    /// 文件损坏或数据无效
+    Corrupt,
+    /// 网络连接超时
+    NetworkTimeout,
+    /// 无法连接服务器或连接中断
+    Network,
+    /// 其他错误
+    Other,
+}
+
+impl ErrorCategory {
+    /// 提示标题
+    pub fn title(self) -> &'static str {
+        match self {
+            ErrorCategory::NotFound => "文件不存在",
+            ErrorCategory::PermissionDenied => "没有访问权限",
+            ErrorCategory::UnsupportedFormat => "不支持的格式",
+            ErrorCategory::UnsupportedCodec => "不支持的编码",
+            ErrorCategory::Corrupt => "文件已损坏",
+            ErrorCategory::NetworkTimeout => "网络连接超时",
+            ErrorCategory::Network => "网络连接失败",
+            ErrorCategory::Other => "打开失败",
+        }
+    }
+
+    /// 处理建议
+    pub fn hint(self) -> &'static str {
+        match self {
+            ErrorCategory::NotFound => "请确认文件或地址没有被移动、删除或拼写错误",
+            ErrorCategory::PermissionDenied => "请检查文件权限，网络地址可能需要登录或已失效",
+            ErrorCategory::UnsupportedFormat => "该文件的封装格式或地址的协议无法识别",
+            ErrorCategory::UnsupportedCodec => "当前的 FFmpeg 没有该媒体使用的解码器",
+            ErrorCategory::Corrupt => "文件数据无效，可能未下载完整或已损坏",
+            ErrorCategory::NetworkTimeout => "服务器长时间没有响应，请检查网络后重试",
+            ErrorCategory::Network => "无法连接到服务器，请检查地址和网络",
+            ErrorCategory::Other => "请查看日志了解详细原因",
+        }
+    }
+
+    /// IO 错误的分类
+    pub fn of_io(kind: std::io::ErrorKind) -> Self {
+        use std::io::ErrorKind;
+        match kind {
+            ErrorKind::NotFound => ErrorCategory::NotFound,
+            ErrorKind::PermissionDenied => ErrorCategory::PermissionDenied,
+            ErrorKind::InvalidData | ErrorKind::UnexpectedEof => ErrorCategory::Corrupt,
+            ErrorKind::TimedOut => ErrorCategory::NetworkTimeout,
+            ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected => ErrorCategory::Network,
+            _ => ErrorCategory::Other,
+        }
+    }
+
+    /// FFmpeg 错误的分类
+    pub fn of_ffmpeg(error: &ffmpeg_next::Error) -> Self {
+        use ffmpeg_next::Error;
+        match error {
+            Error::Other { errno } => match *errno {
+                ENOENT => ErrorCategory::NotFound,
+                EACCES | EPERM => ErrorCategory::PermissionDenied,
+                ETIMEDOUT => ErrorCategory::NetworkTimeout,
+                ECONNREFUSED | ECONNRESET | ECONNABORTED | ENETDOWN | ENETUNREACH | EHOSTUNREACH => {
+                    ErrorCategory::Network
+                }
+                _ => ErrorCategory::Other,
+            },
+            Error::HttpNotFound => ErrorCategory::NotFound,
+            Error::HttpUnauthorized | Error::HttpForbidden => ErrorCategory::PermissionDenied,
+            Error::HttpBadRequest | Error::HttpOther4xx | Error::HttpServerError => ErrorCategory::Network,
+            Error::DemuxerNotFound | Error::ProtocolNotFound | Error::StreamNotFound => ErrorCategory::UnsupportedFormat,
+            Error::DecoderNotFound => ErrorCategory::UnsupportedCodec,
+            Error::InvalidData => ErrorCategory::Corrupt,
+            _ => ErrorCategory::Other,
+        }
+    }
+}
+
+impl PlayerError {
+    /// 面向用户的错误分类
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            PlayerError::FFmpegError(e) | PlayerError::OpenFailed { source: e, .. } => ErrorCategory::of_ffmpeg(e),
+            PlayerError::IoError(e) => ErrorCategory::of_io(e.kind()),
+            PlayerError::NoVideoStream | PlayerError::NoAudioStream => ErrorCategory::UnsupportedFormat,
+            PlayerError::DecodeError(_) | PlayerError::InvalidFrameSize { .. } => ErrorCategory::Corrupt,
+            PlayerError::NetworkError(_) => ErrorCategory::Network,
+            _ => ErrorCategory::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_errors_are_categorized() {
+        let open = |source| PlayerError::OpenFailed { target: "文件", source };
+        assert_eq!(open(ffmpeg_next::Error::Other { errno: ENOENT }).category(), ErrorCategory::NotFound);
+        assert_eq!(open(ffmpeg_next::Error::Other { errno: EACCES }).category(), ErrorCategory::PermissionDenied);
+        assert_eq!(open(ffmpeg_next::Error::InvalidData).category(), ErrorCategory::Corrupt);
+        assert_eq!(open(ffmpeg_next::Error::DemuxerNotFound).category(), ErrorCategory::UnsupportedFormat);
+        assert_eq!(open(ffmpeg_next::Error::Other { errno: ETIMEDOUT }).category(), ErrorCategory::NetworkTimeout);
+        assert_eq!(open(ffmpeg_next::Error::Other { errno: ECONNREFUSED }).category(), ErrorCategory::Network);
+        assert_eq!(open(ffmpeg_next::Error::HttpNotFound).category(), ErrorCategory::NotFound);
+        assert!(open(ffmpeg_next::Error::InvalidData).to_string().starts_with("无法打开文件: "));
+    }
+
+    #[test]
+    fn test_other_errors_are_categorized() {
+        assert_eq!(
+            PlayerError::FFmpegError(ffmpeg_next::Error::DecoderNotFound).category(),
+            ErrorCategory::UnsupportedCodec
+        );
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        assert_eq!(PlayerError::IoError(missing).category(), ErrorCategory::NotFound);
+        assert_eq!(PlayerError::NoVideoStream.category(), ErrorCategory::UnsupportedFormat);
+        assert_eq!(PlayerError::OpenError("x".to_string()).category(), ErrorCategory::Other);
+    }
+}
@@ -0,0 +1,565 @@
+// FFmpeg FFI 的安全封装
+//
+// 解码器和字幕代码需要直接读写 FFmpeg 的原始结构体。这些访问集中在这里，
+// 每个 unsafe 块都写明成立条件（// SAFETY:），调用方只使用安全接口：
+// - 字幕：读取结束时间和文本内容、drop 时释放解码输出
+// - 视频解码器：设置低延迟 / 错误隐藏 / 线程选项，读取帧重排序深度
+// - 硬件解码：创建设备上下文并挂到解码器上，通过 get_format 选择硬件像素格式，把硬件帧下载到内存
+// - 音频：把重采样输出按 f32 读取前检查缓冲区的实际大小和对齐（截断的流可能给出比预期短的缓冲区）
+// - 解封装：读取内嵌封面、容器记录的像素宽高比、编码参数中的码率和文件起始时间，按字节偏移 seek
+// - 版本：读取 FFmpeg 的版本字符串（启动自检显示）
+// - HDR：读取编码参数的 codec_tag 和 extradata（杜比视界配置），让 swscale 按 BT.2020 矩阵转换
+
+use super::{PlayerError, Result};
+use ffmpeg_next::codec::subtitle::{Rect, Subtitle};
+use ffmpeg_next::util::format::sample::{Sample, Type as SampleType};
+use ffmpeg_next::{codec, ffi, format, frame, software};
+use std::ffi::CStr;
+use std::ops::{Deref, DerefMut};
+use std::os::raw::c_void;
+use std::ptr;
+use std::time::Duration;
+
+/// 字幕的显示时长（相对 pts；解码器未给出时为 None）
+pub fn subtitle_end_time(subtitle: &Subtitle) -> Option<Duration> {
+    // 部分解码器用 u32::MAX 表示"显示到下一条字幕"
+    match subtitle.end() {
+        0 | u32::MAX => None,
+        ms => Some(Duration::from_millis(ms as u64)),
+    }
+}
+
+/// 以 NUL 结尾的 C 字符串（空指针为 None，非 UTF-8 的字节按替换字符处理）
+///
+/// # Safety
+/// text 为空，或指向在返回前一直有效的 NUL 结尾字符串
+unsafe fn c_string(text: *const std::os::raw::c_char) -> Option<String> {
+    if text.is_null() {
+        return None;
+    }
+    // SAFETY: text 非空，调用方保证它指向有效的 NUL 结尾字符串
+    Some(unsafe { CStr::from_ptr(text) }.to_string_lossy().into_owned())
+}
+
+/// 文本字幕矩形的内容（SRT 等为纯文本，ASS 为原始对话行；位图字幕和空矩形为 None）
+fn rect_text(rect: &ffi::AVSubtitleRect) -> Option<String> {
+    // SAFETY: text / ass 为空或由解码器分配的 NUL 结尾字符串，在 rect 的借用期间有效
+    unsafe {
+        match rect.type_ {
+            ffi::AVSubtitleType::SUBTITLE_TEXT => c_string(rect.text),
+            ffi::AVSubtitleType::SUBTITLE_ASS => c_string(rect.ass),
+            _ => None,
+        }
+    }
+}
+
+/// 解码出的字幕矩形的文本（按字节读取，不假定解码器输出的是合法 UTF-8）
+pub fn subtitle_rect_text(rect: &Rect) -> Option<String> {
+    // SAFETY: rect 引用 subtitle 持有的 AVSubtitleRect，as_ptr 只取指针不解引用
+    let raw = unsafe { rect.as_ptr() };
+    // SAFETY: raw 为空或在 rect 的借用期间有效
+    unsafe { raw.as_ref() }.and_then(rect_text)
+}
+
+/// FFmpeg 的版本字符串（如 "6.1.1-full_build-www.gyan.dev"；取不到时为空）
+pub fn ffmpeg_version() -> String {
+    // SAFETY: av_version_info 返回静态的 NUL 结尾字符串（或空指针）
+    unsafe { c_string(ffi::av_version_info()) }.unwrap_or_default()
+}
+
+/// 解码输出的字幕（drop 时用 avsubtitle_free 释放各个 rect 及其文本）
+pub struct SubtitleGuard(Subtitle);
+
+impl SubtitleGuard {
+    pub fn new() -> Self {
+        Self(Subtitle::new())
+    }
+}
+
+impl Default for SubtitleGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for SubtitleGuard {
+    type Target = Subtitle;
+
+    fn deref(&self) -> &Subtitle {
+        &self.0
+    }
+}
+
+impl DerefMut for SubtitleGuard {
+    fn deref_mut(&mut self) -> &mut Subtitle {
+        &mut self.0
+    }
+}
+
+impl Drop for SubtitleGuard {
+    fn drop(&mut self) {
+        // SAFETY: self.0 是全零初始化或由解码器填充的 AVSubtitle，rects 只由 FFmpeg 分配；
+        // avsubtitle_free 释放后把结构清零，之后不会再被访问
+        unsafe { ffi::avsubtitle_free(self.0.as_mut_ptr()) };
+    }
+}
+
+/// 视频解码器的容错和性能选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecoderOpts {
+    pub low_delay: bool,          // AV_CODEC_FLAG_LOW_DELAY
+    pub error_concealment: bool,  // 参考帧丢失时猜测运动矢量并去块
+    pub skip_loop_filter: bool,   // 跳过环路滤波（降低延迟和 CPU 占用）
+    pub thread_count: i32,        // 0 表示由 FFmpeg 自动决定
+}
+
+impl DecoderOpts {
+    /// 网络流配置：能容忍不完整的 GOP 和缺失的参考帧
+    pub const NETWORK_TOLERANT: Self = Self {
+        low_delay: true,
+        error_concealment: true,
+        skip_loop_filter: true,
+        thread_count: 4,
+    };
+}
+
+/// 把选项写入解码器上下文
+fn apply_decoder_options(ctx: &mut ffi::AVCodecContext, opts: DecoderOpts) {
+    if opts.low_delay {
+        ctx.flags |= ffi::AV_CODEC_FLAG_LOW_DELAY as i32;
+    }
+    if opts.error_concealment {
+        ctx.error_concealment = ffi::FF_EC_GUESS_MVS | ffi::FF_EC_DEBLOCK;
+    }
+    if opts.skip_loop_filter {
+        ctx.skip_loop_filter = ffi::AVDiscard::AVDISCARD_ALL;
+    }
+    if opts.thread_count > 0 {
+        ctx.thread_count = opts.thread_count;
+        ctx.thread_type = ffi::FF_THREAD_FRAME | ffi::FF_THREAD_SLICE;
+    }
+}
+
+/// 设置视频解码器选项
+pub fn configure_decoder_options(decoder: &mut codec::decoder::Video, opts: DecoderOpts) {
+    // SAFETY: decoder 独占持有 AVCodecContext，as_mut_ptr 只取指针不解引用
+    let ctx = unsafe { decoder.as_mut_ptr() };
+    if ctx.is_null() {
+        return;
+    }
+    // SAFETY: ctx 非空且在 decoder 的可变借用期间不会被其他代码访问
+    apply_decoder_options(unsafe { &mut *ctx }, opts);
+}
+
+/// 解码器的帧重排序深度（AVCodecContext.has_b_frames，解码过程中可能变大）
+pub fn decoder_reorder_depth(decoder: &codec::decoder::Video) -> usize {
+    // SAFETY: decoder 持有 AVCodecContext，as_ptr 只取指针不解引用
+    let ctx = unsafe { decoder.as_ptr() };
+    if ctx.is_null() {
+        return 0;
+    }
+    // SAFETY: ctx 非空，在 decoder 的借用期间有效，只读取一个整数字段
+    unsafe { (*ctx).has_b_frames.max(0) as usize }
+}
+
+/// 把字节缓冲区的前 expected_len 个 f32 作为切片读取（长度不足或未按 f32 对齐时返回错误）
+pub fn samples_as_f32(bytes: &[u8], expected_len: usize) -> Result<&[f32]> {
+    let needed = expected_len
+        .checked_mul(std::mem::size_of::<f32>())
+        .ok_or_else(|| PlayerError::DecodeError(format!("音频采样数溢出: {}", expected_len)))?;
+    if bytes.len() < needed {
+        return Err(PlayerError::DecodeError(format!(
+            "音频缓冲区过短: {} 字节，需要 {} 字节",
+            bytes.len(),
+            needed
+        )));
+    }
+    bytemuck::try_cast_slice(&bytes[..needed])
+        .map_err(|e| PlayerError::DecodeError(format!("音频缓冲区无法按 f32 读取: {:?}", e)))
+}
+
+/// 以 f32 读取交错格式（packed）音频帧的前 expected_len 个采样值
+pub fn audio_frame_as_f32(frame: &frame::Audio, expected_len: usize) -> Result<&[f32]> {
+    if frame.format() != Sample::F32(SampleType::Packed) {
+        return Err(PlayerError::DecodeError(format!("音频帧不是 packed f32: {:?}", frame.format())));
+    }
+    if frame.planes() == 0 {
+        return Err(PlayerError::DecodeError("音频帧没有数据".to_string()));
+    }
+    samples_as_f32(frame.data(0), expected_len)
+}
+
+/// 硬件设备上下文（av_hwdevice_ctx_create 创建，drop 时释放本方持有的引用）
+pub struct HwDevice(*mut ffi::AVBufferRef);
+
+impl HwDevice {
+    /// 打开默认的硬件设备（驱动或 FFmpeg 不支持时返回错误）
+    pub fn create(device_type: ffi::AVHWDeviceType) -> Result<Self> {
+        let mut device = ptr::null_mut();
+        // SAFETY: device 是有效的输出指针；设备名、选项为空表示使用默认设备，失败时不会写入
+        let ret = unsafe { ffi::av_hwdevice_ctx_create(&mut device, device_type, ptr::null(), ptr::null_mut(), 0) };
+        if ret < 0 || device.is_null() {
+            return Err(PlayerError::DecodeError(format!(
+                "av_hwdevice_ctx_create({:?}) 失败: {}",
+                device_type,
+                ffmpeg_next::Error::from(ret)
+            )));
+        }
+        Ok(Self(device))
+    }
+}
+
+impl Drop for HwDevice {
+    fn drop(&mut self) {
+        // SAFETY: self.0 是 av_hwdevice_ctx_create 返回的引用，只在这里释放一次（解码器持有自己的引用）
+        unsafe { ffi::av_buffer_unref(&mut self.0) };
+    }
+}
+
+/// get_format 回调：候选列表中有硬件格式（记在 opaque 中）时选择它，否则交给 FFmpeg 选择软件格式
+unsafe extern "C" fn select_hw_format(
+    ctx: *mut ffi::AVCodecContext,
+    formats: *const ffi::AVPixelFormat,
+) -> ffi::AVPixelFormat {
+    // SAFETY: FFmpeg 传入有效的 ctx 和以 AV_PIX_FMT_NONE 结尾的候选列表
+    unsafe {
+        let wanted = (*ctx).opaque as isize as i32;
+        let mut format = formats;
+        while *format != ffi::AVPixelFormat::AV_PIX_FMT_NONE {
+            if *format as i32 == wanted {
+                return *format;
+            }
+            format = format.add(1);
+        }
+        ffi::avcodec_default_get_format(ctx, formats)
+    }
+}
+
+/// 让解码器使用硬件设备解码（必须在打开解码器之前调用）
+pub fn attach_hw_device(context: &mut codec::context::Context, device: &HwDevice, hw_format: ffi::AVPixelFormat) -> Result<()> {
+    // SAFETY: context 独占持有尚未打开的 AVCodecContext，as_mut_ptr 只取指针不解引用
+    let ctx = unsafe { context.as_mut_ptr() };
+    if ctx.is_null() {
+        return Err(PlayerError::DecodeError("解码器上下文为空".to_string()));
+    }
+    // SAFETY: device.0 是有效的设备引用，av_buffer_ref 为解码器生成独立的引用（由 avcodec_free_context 释放）
+    let device_ref = unsafe { ffi::av_buffer_ref(device.0) };
+    if device_ref.is_null() {
+        return Err(PlayerError::DecodeError("av_buffer_ref 失败".to_string()));
+    }
+    // SAFETY: ctx 非空且在 context 的可变借用期间不会被其他代码访问；opaque 只由 select_hw_format 读取
+    unsafe {
+        (*ctx).hw_device_ctx = device_ref;
+        (*ctx).opaque = hw_format as i32 as isize as *mut c_void;
+        (*ctx).get_format = Some(select_hw_format);
+    }
+    Ok(())
+}
+
+/// 把硬件帧（显存中的表面）下载为内存中的帧（通常为 NV12），保留 pts 等属性
+pub fn transfer_hw_frame(hw_frame: &frame::Video) -> Result<frame::Video> {
+    let mut cpu_frame = frame::Video::empty();
+    // SAFETY: 两个帧都有效；目标帧为空时 av_hwframe_transfer_data 按硬件帧的格式分配内存
+    let ret = unsafe { ffi::av_hwframe_transfer_data(cpu_frame.as_mut_ptr(), hw_frame.as_ptr(), 0) };
+    if ret < 0 {
+        return Err(PlayerError::DecodeError(format!("av_hwframe_transfer_data 失败: {}", ffmpeg_next::Error::from(ret))));
+    }
+    // SAFETY: 同上，只复制 pts、色彩信息等属性
+    let ret = unsafe { ffi::av_frame_copy_props(cpu_frame.as_mut_ptr(), hw_frame.as_ptr()) };
+    if ret < 0 {
+        return Err(PlayerError::DecodeError(format!("av_frame_copy_props 失败: {}", ffmpeg_next::Error::from(ret))));
+    }
+    Ok(cpu_frame)
+}
+
+/// 数据包内容（data 为空或 size 不为正时为 None）
+fn packet_data(packet: &ffi::AVPacket) -> Option<&[u8]> {
+    if packet.data.is_null() || packet.size <= 0 {
+        return None;
+    }
+    // SAFETY: data 非空，FFmpeg 保证其指向 size 字节的数据包内容，在 packet 的借用期间有效
+    Some(unsafe { std::slice::from_raw_parts(packet.data, packet.size as usize) })
+}
+
+/// 流内嵌的封面图片（attached_pic 数据包内容；不是封面流或没有数据时为 None）
+pub fn attached_picture<'a>(stream: &'a format::stream::Stream) -> Option<&'a [u8]> {
+    // SAFETY: stream 持有 AVFormatContext 中的 AVStream，as_ptr 只取指针不解引用
+    let raw = unsafe { stream.as_ptr() };
+    if raw.is_null() {
+        return None;
+    }
+    // SAFETY: raw 非空，在 stream 的借用期间有效；attached_pic 在打开文件时已由 FFmpeg 读取
+    packet_data(unsafe { &(*raw).attached_pic })
+}
+
+/// 容器记录的像素宽高比（num, den；未记录时为 (0, 1)）
+pub fn stream_sample_aspect_ratio(stream: &format::stream::Stream) -> (i32, i32) {
+    // SAFETY: 同 attached_picture
+    let raw = unsafe { stream.as_ptr() };
+    if raw.is_null() {
+        return (0, 1);
+    }
+    // SAFETY: raw 非空，在 stream 的借用期间有效，只读取一个结构体字段
+    let sar = unsafe { (*raw).sample_aspect_ratio };
+    (sar.num, sar.den)
+}
+
+/// 编码参数中的码率（未知时为 None）
+pub fn codec_bit_rate(parameters: &codec::Parameters) -> Option<u64> {
+    // SAFETY: parameters 持有有效的 AVCodecParameters，as_ptr 只取指针不解引用
+    let raw = unsafe { parameters.as_ptr() };
+    if raw.is_null() {
+        return None;
+    }
+    // SAFETY: raw 非空，在 parameters 的借用期间有效，只读取一个整数字段
+    let bit_rate = unsafe { (*raw).bit_rate };
+    (bit_rate > 0).then_some(bit_rate as u64)
+}
+
+/// 文件的起始时间（毫秒；未知时为 0）
+pub fn start_time_ms(input: &format::context::Input) -> i64 {
+    // SAFETY: input 持有打开成功的 AVFormatContext，as_ptr 只取指针不解引用
+    let raw = unsafe { input.as_ptr() };
+    if raw.is_null() {
+        return 0;
+    }
+    // SAFETY: raw 非空，在 input 的借用期间有效；start_time 未知时为 AV_NOPTS_VALUE（负值）
+    let start_time = unsafe { (*raw).start_time };
+    start_time.max(0) / 1000
+}
+
+/// 封装格式是否允许按字节 seek（没有 AVFMT_NO_BYTE_SEEK 标志）
+fn input_format_allows_byte_seek(format: Option<&ffi::AVInputFormat>) -> bool {
+    format.is_some_and(|format| format.flags & ffi::AVFMT_NO_BYTE_SEEK == 0)
+}
+
+/// 封装格式是否支持按字节 seek（MP4 等依赖自身索引的格式不支持）
+pub fn supports_byte_seek(input: &format::context::Input) -> bool {
+    // SAFETY: 同 start_time_ms
+    let raw = unsafe { input.as_ptr() };
+    if raw.is_null() {
+        return false;
+    }
+    // SAFETY: raw 非空；打开成功的 AVFormatContext 的 iformat 为空或指向 FFmpeg 内部的静态结构
+    input_format_allows_byte_seek(unsafe { (*raw).iformat.as_ref() })
+}
+
+/// 按字节偏移 seek（由 FFmpeg 负责 avio_seek 并重置解封装状态）
+pub fn seek_to_byte(input: &mut format::context::Input, pos: i64) -> Result<()> {
+    // SAFETY: input 独占持有 AVFormatContext，as_mut_ptr 只取指针不解引用
+    let raw = unsafe { input.as_mut_ptr() };
+    if raw.is_null() {
+        return Err(PlayerError::Other("解封装上下文为空".to_string()));
+    }
+    // SAFETY: raw 非空，在 input 的可变借用期间不会被其他代码访问；stream_index 为 -1 表示按字节位置
+    let ret = unsafe { ffi::avformat_seek_file(raw, -1, pos, pos, pos, ffi::AVSEEK_FLAG_BYTE) };
+    if ret < 0 {
+        return Err(ffmpeg_next::Error::from(ret).into());
+    }
+    Ok(())
+}
+
+/// 编码参数中的 extradata（为空时为空切片）
+fn extradata(parameters: &ffi::AVCodecParameters) -> &[u8] {
+    if parameters.extradata.is_null() || parameters.extradata_size <= 0 {
+        return &[];
+    }
+    // SAFETY: extradata 非空，FFmpeg 保证其指向 extradata_size 字节，在 parameters 的借用期间有效
+    unsafe { std::slice::from_raw_parts(parameters.extradata, parameters.extradata_size as usize) }
+}
+
+/// 编码参数的 codec_tag 和 extradata（杜比视界等配置盒可能只出现在 extradata 中）
+pub fn codec_tag_and_extradata(parameters: &codec::Parameters) -> (u32, &[u8]) {
+    // SAFETY: parameters 持有有效的 AVCodecParameters，as_ptr 只取指针不解引用
+    let raw = unsafe { parameters.as_ptr() };
+    // SAFETY: raw 为空或在 parameters 的借用期间有效
+    match unsafe { raw.as_ref() } {
+        Some(raw) => (raw.codec_tag, extradata(raw)),
+        None => (0, &[]),
+    }
+}
+
+/// 让 swscale 按 BT.2020 矩阵把 YUV 转换为 RGB（默认按 BT.601，HDR 片源颜色会偏）
+pub fn set_bt2020_colorspace(scaler: &mut software::scaling::Context, full_range: bool) -> Result<()> {
+    // SAFETY: scaler 独占持有 SwsContext，as_mut_ptr 只取指针不解引用
+    let raw = unsafe { scaler.as_mut_ptr() };
+    if raw.is_null() {
+        return Err(PlayerError::DecodeError("缩放上下文为空".to_string()));
+    }
+    // SAFETY: sws_getCoefficients 返回 FFmpeg 内部的静态系数表，不会为空
+    let coefficients = unsafe { ffi::sws_getCoefficients(ffi::SWS_CS_BT2020 as i32) };
+    // SAFETY: raw 非空，在 scaler 的可变借用期间不会被其他代码访问；系数表在整个进程中有效
+    let ret = unsafe {
+        ffi::sws_setColorspaceDetails(raw, coefficients, full_range as i32, coefficients, 1, 0, 1 << 16, 1 << 16)
+    };
+    if ret < 0 {
+        return Err(PlayerError::DecodeError(format!("sws_setColorspaceDetails 失败: {}", ffmpeg_next::Error::from(ret))));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ffmpeg_next::util::channel_layout::ChannelLayout;
+
+    #[test]
+    fn test_subtitle_end_time() {
+        let mut subtitle = SubtitleGuard::new();
+        assert_eq!(subtitle_end_time(&subtitle), None);
+        subtitle.set_end(2_500);
+        assert_eq!(subtitle_end_time(&subtitle), Some(Duration::from_millis(2_500)));
+        subtitle.set_end(u32::MAX);
+        assert_eq!(subtitle_end_time(&subtitle), None);
+        // 空字幕也能安全释放
+        drop(subtitle);
+    }
+
+    #[test]
+    fn test_subtitle_rect_text() {
+        let text = std::ffi::CString::new("第一行\\N第二行").unwrap();
+        let ass = std::ffi::CString::new("0,0,Default,,0,0,0,,字幕").unwrap();
+        // SAFETY: AVSubtitleRect 是纯 C 结构体，全零是合法值（SUBTITLE_NONE，无文本）
+        let mut rect: ffi::AVSubtitleRect = unsafe { std::mem::zeroed() };
+        assert_eq!(rect_text(&rect), None);
+
+        rect.type_ = ffi::AVSubtitleType::SUBTITLE_TEXT;
+        assert_eq!(rect_text(&rect), None);
+        rect.text = text.as_ptr() as *mut _;
+        assert_eq!(rect_text(&rect).as_deref(), Some("第一行\\N第二行"));
+
+        rect.type_ = ffi::AVSubtitleType::SUBTITLE_ASS;
+        rect.ass = ass.as_ptr() as *mut _;
+        assert_eq!(rect_text(&rect).as_deref(), Some("0,0,Default,,0,0,0,,字幕"));
+
+        // 位图字幕没有文本
+        rect.type_ = ffi::AVSubtitleType::SUBTITLE_BITMAP;
+        assert_eq!(rect_text(&rect), None);
+    }
+
+    #[test]
+    fn test_ffmpeg_version_is_reported() {
+        assert!(!ffmpeg_version().is_empty());
+    }
+
+    #[test]
+    fn test_decoder_options_are_applied() {
+        // SAFETY: AVCodecContext 是纯 C 结构体，全零是合法值（各枚举字段的 0 都是有效取值）
+        let mut ctx: ffi::AVCodecContext = unsafe { std::mem::zeroed() };
+        apply_decoder_options(&mut ctx, DecoderOpts::NETWORK_TOLERANT);
+        assert_ne!(ctx.flags & ffi::AV_CODEC_FLAG_LOW_DELAY as i32, 0);
+        assert_eq!(ctx.error_concealment, ffi::FF_EC_GUESS_MVS | ffi::FF_EC_DEBLOCK);
+        assert_eq!(ctx.skip_loop_filter, ffi::AVDiscard::AVDISCARD_ALL);
+        assert_eq!(ctx.thread_count, 4);
+
+        // 关闭的选项保持原值
+        // SAFETY: 同上
+        let mut ctx: ffi::AVCodecContext = unsafe { std::mem::zeroed() };
+        let opts = DecoderOpts { low_delay: false, error_concealment: false, skip_loop_filter: false, thread_count: 0 };
+        apply_decoder_options(&mut ctx, opts);
+        assert_eq!((ctx.flags, ctx.error_concealment, ctx.thread_count), (0, 0, 0));
+        assert_eq!(ctx.skip_loop_filter, ffi::AVDiscard::AVDISCARD_DEFAULT);
+    }
+
+    #[test]
+    fn test_short_audio_buffers_are_rejected() {
+        let samples = [0.5f32, -0.5, 0.25, -0.25];
+        let bytes: &[u8] = bytemuck::cast_slice(&samples);
+        assert_eq!(samples_as_f32(bytes, 4).unwrap(), &samples);
+        assert_eq!(samples_as_f32(bytes, 2).unwrap(), &samples[..2]);
+        assert!(samples_as_f32(bytes, 5).is_err());
+        assert!(samples_as_f32(&bytes[..15], 4).is_err());
+        assert!(samples_as_f32(&[], 1).is_err());
+        assert!(samples_as_f32(bytes, usize::MAX).is_err());
+        // 未对齐的缓冲区
+        assert!(samples_as_f32(&bytes[1..13], 3).is_err());
+    }
+
+    #[test]
+    fn test_audio_frame_is_bounds_checked() {
+        let frame = frame::Audio::new(Sample::F32(SampleType::Packed), 1_024, ChannelLayout::STEREO);
+        assert_eq!(audio_frame_as_f32(&frame, 2_048).unwrap().len(), 2_048);
+        assert!(audio_frame_as_f32(&frame, 4_096).is_err());
+
+        let planar = frame::Audio::new(Sample::F32(SampleType::Planar), 1_024, ChannelLayout::STEREO);
+        assert!(audio_frame_as_f32(&planar, 1_024).is_err());
+        assert!(audio_frame_as_f32(&frame::Audio::empty(), 1).is_err());
+    }
+
+    #[test]
+    fn test_packet_data_checks_pointer_and_size() {
+        // SAFETY: AVPacket 是纯 C 结构体，全零是合法值（空数据包）
+        let mut packet: ffi::AVPacket = unsafe { std::mem::zeroed() };
+        assert_eq!(packet_data(&packet), None);
+
+        let mut bytes = *b"\xff\xd8cover";
+        packet.data = bytes.as_mut_ptr();
+        packet.size = 0;
+        assert_eq!(packet_data(&packet), None);
+        packet.size = -1;
+        assert_eq!(packet_data(&packet), None);
+        packet.size = bytes.len() as i32;
+        assert_eq!(packet_data(&packet), Some(&b"\xff\xd8cover"[..]));
+    }
+
+    #[test]
+    fn test_byte_seek_flag() {
+        // SAFETY: AVInputFormat 是纯 C 结构体，全零是合法值
+        let mut format: ffi::AVInputFormat = unsafe { std::mem::zeroed() };
+        assert!(input_format_allows_byte_seek(Some(&format)));
+        format.flags = ffi::AVFMT_NO_BYTE_SEEK;
+        assert!(!input_format_allows_byte_seek(Some(&format)));
+        assert!(!input_format_allows_byte_seek(None));
+    }
+
+    #[test]
+    fn test_demuxer_helpers_on_generated_assets() {
+        use crate::test_support::{cover_art_asset, video_asset};
+
+        let input = format::input(&video_asset()).unwrap();
+        assert_eq!(start_time_ms(&input), 0);
+        // MP4 依赖自身的索引，不支持按字节 seek
+        assert!(!supports_byte_seek(&input));
+        let video = input.streams().best(ffmpeg_next::media::Type::Video).unwrap();
+        assert!(attached_picture(&video).is_none());
+        let (num, den) = stream_sample_aspect_ratio(&video);
+        assert!(num == 0 || num == den, "方形像素: {}/{}", num, den);
+        assert!(codec_bit_rate(&video.parameters()).is_some_and(|bit_rate| bit_rate > 0));
+
+        let input = format::input(&cover_art_asset()).unwrap();
+        let cover = input
+            .streams()
+            .find(|stream| stream.disposition().contains(format::stream::Disposition::ATTACHED_PIC))
+            .unwrap();
+        // JPEG 以 SOI 标记开头
+        assert!(attached_picture(&cover).is_some_and(|data| data.starts_with(&[0xff, 0xd8])));
+        // MP3 可以按字节 seek
+        let mut input = input;
+        assert!(supports_byte_seek(&input));
+        assert!(seek_to_byte(&mut input, 0).is_ok());
+    }
+
+    #[test]
+    fn test_extradata_checks_pointer_and_size() {
+        // SAFETY: AVCodecParameters 是纯 C 结构体，全零是合法值（没有 extradata）
+        let mut parameters: ffi::AVCodecParameters = unsafe { std::mem::zeroed() };
+        assert!(extradata(&parameters).is_empty());
+
+        let mut bytes = *b"dvvC\x01\x00";
+        parameters.extradata = bytes.as_mut_ptr();
+        parameters.extradata_size = 0;
+        assert!(extradata(&parameters).is_empty());
+        parameters.extradata_size = bytes.len() as i32;
+        assert_eq!(extradata(&parameters), b"dvvC\x01\x00");
+    }
+
+    #[test]
+    fn test_bt2020_colorspace_is_accepted() {
+        use ffmpeg_next::format::Pixel;
+        use software::scaling::{Context, Flags};
+
+        let mut scaler = Context::get(Pixel::YUV420P10LE, 64, 36, Pixel::RGBA, 64, 36, Flags::BILINEAR).unwrap();
+        assert!(set_bt2020_colorspace(&mut scaler, false).is_ok());
+        assert!(set_bt2020_colorspace(&mut scaler, true).is_ok());
+    }
+}
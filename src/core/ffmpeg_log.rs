@@ -0,0 +1,321 @@
+// FFmpeg 日志转发（把 FFmpeg 自己打印的警告接入应用日志）
+//
+// FFmpeg 默认把损坏帧、错误隐藏、HLS 刷新失败等消息直接写到 stderr，GUI 版本看不到，
+// 而这些消息恰好能解释大多数画面问题。启动时通过 av_log_set_callback 安装回调，
+// 按级别映射为 log 记录（带上发出消息的组件名，如 h264、hls），走正常的日志过滤和输出。
+//
+// 回调会在解码线程中被并发调用：消息格式化到栈上的定长缓冲区，相同消息每秒最多输出 5 条
+// （限流表和警告计数都是定长数组，损坏严重的文件不会刷屏，也不会让内存增长）。
+
+use ffmpeg_next::ffi;
+use log::Level;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+/// 相同消息每秒最多输出的条数
+const RATE_LIMIT_PER_SECOND: u32 = 5;
+
+/// 限流表的槽位数（按消息哈希取模，冲突时后来的消息占用槽位）
+const RATE_LIMIT_SLOTS: usize = 64;
+
+/// 单条消息的最大长度（超出部分被截断）
+const LINE_CAPACITY: usize = 1024;
+
+/// 警告计数的统计窗口（秒）
+const WARNING_WINDOW_SECS: u64 = 60;
+
+/// av_log 回调中 va_list 参数的类型（x86_64 System V 上 va_list 是数组，作为参数时退化为指针）
+#[cfg(all(target_arch = "x86_64", not(target_os = "windows")))]
+type VaListArg = *mut ffi::__va_list_tag;
+#[cfg(not(all(target_arch = "x86_64", not(target_os = "windows"))))]
+type VaListArg = ffi::va_list;
+
+/// 回调是否已安装
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// 限流和警告计数（回调线程共享）
+static STATE: Mutex<LogState> = Mutex::new(LogState::new());
+
+/// 按 FFmpeg 日志级别选择 log 级别（AV_LOG_QUIET 不输出）
+///
+/// FFmpeg 的 INFO 级别包含 HLS 每次打开分片等高频消息，降为 debug。
+pub fn map_level(level: c_int) -> Option<Level> {
+    match level {
+        l if l < ffi::AV_LOG_PANIC => None,
+        l if l <= ffi::AV_LOG_ERROR => Some(Level::Error),
+        l if l <= ffi::AV_LOG_WARNING => Some(Level::Warn),
+        l if l <= ffi::AV_LOG_VERBOSE => Some(Level::Debug),
+        _ => Some(Level::Trace),
+    }
+}
+
+/// 相同消息的限流（定长表，槽位按 (哈希, 秒) 计数）
+struct RateLimiter {
+    slots: [(u64, u64, u32); RATE_LIMIT_SLOTS],  // (消息哈希, 所在秒, 已输出条数)
+}
+
+impl RateLimiter {
+    const fn new() -> Self {
+        Self { slots: [(0, 0, 0); RATE_LIMIT_SLOTS] }
+    }
+
+    /// 该消息在 second 这一秒内是否还能输出
+    fn allow(&mut self, hash: u64, second: u64) -> bool {
+        let slot = &mut self.slots[(hash % RATE_LIMIT_SLOTS as u64) as usize];
+        if slot.0 != hash || slot.1 != second {
+            *slot = (hash, second, 0);
+        }
+        slot.2 += 1;
+        slot.2 <= RATE_LIMIT_PER_SECOND
+    }
+}
+
+/// 最近一分钟的警告数（每秒一个桶，循环使用）
+struct WarningWindow {
+    buckets: [(u64, u32); WARNING_WINDOW_SECS as usize],  // (所在秒, 警告数)
+}
+
+impl WarningWindow {
+    const fn new() -> Self {
+        Self { buckets: [(0, 0); WARNING_WINDOW_SECS as usize] }
+    }
+
+    fn record(&mut self, second: u64) {
+        let bucket = &mut self.buckets[(second % WARNING_WINDOW_SECS) as usize];
+        if bucket.0 != second {
+            *bucket = (second, 0);
+        }
+        bucket.1 += 1;
+    }
+
+    /// 截至 now 这一秒（含）的最近一分钟内的警告数
+    fn total(&self, now: u64) -> u32 {
+        self.buckets
+            .iter()
+            .filter(|(second, _)| *second <= now && now - *second < WARNING_WINDOW_SECS)
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+struct LogState {
+    limiter: RateLimiter,
+    warnings: WarningWindow,
+}
+
+impl LogState {
+    const fn new() -> Self {
+        Self { limiter: RateLimiter::new(), warnings: WarningWindow::new() }
+    }
+}
+
+/// 进程启动后经过的秒数（从 1 开始，0 留给空槽位）
+fn current_second() -> u64 {
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs() + 1
+}
+
+fn lock_state() -> std::sync::MutexGuard<'static, LogState> {
+    // 回调不能 panic（会跨越 FFI 边界），锁中毒时继续使用内部数据
+    STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 消息哈希（FNV-1a，组件名和内容一起参与）
+fn message_hash(class: Option<&str>, message: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in class.unwrap_or("").bytes().chain([0]).chain(message.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// 发出消息的组件名（AVClass 的 item_name，如 "h264"、"hls"）
+///
+/// # Safety
+/// avcl 为空或指向以 AVClass 指针开头的 FFmpeg 结构体（av_log 的约定）。
+unsafe fn class_name(avcl: *mut c_void) -> Option<String> {
+    if avcl.is_null() {
+        return None;
+    }
+    let class = *(avcl as *const *const ffi::AVClass);
+    if class.is_null() {
+        return None;
+    }
+    let name = match (*class).item_name {
+        Some(item_name) => item_name(avcl),
+        None => (*class).class_name,
+    };
+    (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned())
+}
+
+/// av_log 回调
+unsafe extern "C" fn log_callback(avcl: *mut c_void, level: c_int, fmt: *const c_char, vl: VaListArg) {
+    // 高位可能带有颜色标记
+    let level = level & 0xff;
+    let Some(log_level) = map_level(level) else {
+        return;
+    };
+    let is_warning = level <= ffi::AV_LOG_WARNING;
+    // 警告即使不输出也要计数
+    if !is_warning && !log::log_enabled!(log_level) {
+        return;
+    }
+
+    let mut line = [0 as c_char; LINE_CAPACITY];
+    let mut print_prefix: c_int = 0;
+    ffi::av_log_format_line2(avcl, level, fmt, vl, line.as_mut_ptr(), LINE_CAPACITY as c_int, &mut print_prefix);
+    let message = CStr::from_ptr(line.as_ptr()).to_string_lossy();
+    let message = message.trim();
+    if message.is_empty() {
+        return;
+    }
+    let class = class_name(avcl);
+
+    let second = current_second();
+    {
+        let mut state = lock_state();
+        if is_warning {
+            state.warnings.record(second);
+        }
+        if !state.limiter.allow(message_hash(class.as_deref(), message), second) {
+            return;
+        }
+    }
+
+    match class {
+        Some(class) => log::log!(log_level, "🎞️ [{}] {}", class, message),
+        None => log::log!(log_level, "🎞️ {}", message),
+    }
+}
+
+/// 安装日志回调（FFmpeg 初始化后调用）
+pub fn install() {
+    // SAFETY: log_callback 的签名与 av_log 回调一致，且不会 panic 或跨越 FFI 边界展开
+    unsafe { ffi::av_log_set_callback(Some(log_callback)) };
+    INSTALLED.store(true, Ordering::Relaxed);
+}
+
+/// 恢复 FFmpeg 默认的 stderr 输出
+#[cfg(test)]
+pub fn uninstall() {
+    // SAFETY: 恢复为 FFmpeg 自带的回调
+    unsafe { ffi::av_log_set_callback(Some(ffi::av_log_default_callback)) };
+    INSTALLED.store(false, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+pub fn is_installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// 最近一分钟内 FFmpeg 发出的警告和错误数（包括被限流的消息）
+pub fn warnings_last_minute() -> u32 {
+    lock_state().warnings.total(current_second())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_mapping() {
+        assert_eq!(map_level(ffi::AV_LOG_QUIET), None);
+        assert_eq!(map_level(ffi::AV_LOG_PANIC), Some(Level::Error));
+        assert_eq!(map_level(ffi::AV_LOG_ERROR), Some(Level::Error));
+        assert_eq!(map_level(ffi::AV_LOG_WARNING), Some(Level::Warn));
+        assert_eq!(map_level(ffi::AV_LOG_INFO), Some(Level::Debug));
+        assert_eq!(map_level(ffi::AV_LOG_VERBOSE), Some(Level::Debug));
+        assert_eq!(map_level(ffi::AV_LOG_DEBUG), Some(Level::Trace));
+        assert_eq!(map_level(ffi::AV_LOG_TRACE), Some(Level::Trace));
+    }
+
+    #[test]
+    fn test_identical_messages_are_rate_limited() {
+        let mut limiter = RateLimiter::new();
+        let hash = message_hash(Some("h264"), "error while decoding MB 1 2");
+        let allowed = (0..20).filter(|_| limiter.allow(hash, 1)).count();
+        assert_eq!(allowed, RATE_LIMIT_PER_SECOND as usize);
+        // 其他消息不受影响，下一秒重新计数
+        assert!(limiter.allow(message_hash(Some("hls"), "reload failed"), 1));
+        assert!(limiter.allow(hash, 2));
+    }
+
+    #[test]
+    fn test_warning_window_covers_last_minute() {
+        let mut window = WarningWindow::new();
+        window.record(1);
+        window.record(30);
+        window.record(30);
+        assert_eq!(window.total(30), 3);
+        assert_eq!(window.total(60), 3);
+        assert_eq!(window.total(61), 2);
+        assert_eq!(window.total(90), 0);
+        // 桶被循环复用时旧计数清零
+        window.record(90);
+        assert_eq!(window.total(90), 1);
+    }
+
+    /// 记录带有测试标记的日志（其他测试的日志不记录）
+    struct CaptureLogger;
+
+    const MARKER: &str = "ffmpeg_log 转发测试";
+
+    static CAPTURED: Mutex<Vec<(Level, String)>> = Mutex::new(Vec::new());
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            let message = record.args().to_string();
+            if message.contains(MARKER) {
+                CAPTURED.lock().unwrap().push((record.level(), message));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// 通过 FFmpeg 的 av_log 发出一条警告
+    fn emit_warning(value: c_int) {
+        let fmt = CStr::from_bytes_with_nul("ffmpeg_log 转发测试 %d\n\0".as_bytes()).unwrap();
+        // SAFETY: 格式串以 NUL 结尾，%d 对应一个 c_int 参数；avcl 为空时不读取 AVClass
+        unsafe { ffi::av_log(std::ptr::null_mut(), ffi::AV_LOG_WARNING, fmt.as_ptr(), value) };
+    }
+
+    #[test]
+    fn test_installed_callback_forwards_and_rate_limits() {
+        static LOGGER: CaptureLogger = CaptureLogger;
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+
+        install();
+        assert!(is_installed());
+        let warnings_before = warnings_last_minute();
+        let second = current_second();
+        for _ in 0..20 {
+            emit_warning(7);
+        }
+        let forwarded = std::mem::take(&mut *CAPTURED.lock().unwrap());
+        assert!(forwarded.iter().all(|(level, message)| *level == Level::Warn && message == "🎞️ ffmpeg_log 转发测试 7"));
+        if current_second() == second {
+            assert_eq!(forwarded.len(), RATE_LIMIT_PER_SECOND as usize);
+        } else {
+            // 跨过了秒的边界：两秒各自限流
+            assert!(forwarded.len() <= 2 * RATE_LIMIT_PER_SECOND as usize);
+        }
+        // 被限流的消息也计入警告数
+        assert!(warnings_last_minute() >= warnings_before + 20);
+
+        // 卸载后恢复 FFmpeg 默认输出，不再进入 log
+        uninstall();
+        assert!(!is_installed());
+        emit_warning(8);
+        assert!(CAPTURED.lock().unwrap().is_empty());
+    }
+}
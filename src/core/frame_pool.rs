@@ -0,0 +1,245 @@
+// 视频帧像素缓冲区复用
+//
+// 每个解码帧都需要一块完整画面大小的内存（1080p RGBA 约 8MB），帧队列、重排序缓冲区和渲染器同时持有几十帧，
+// 逐帧分配、释放会让分配器每秒经手数百 MB。播放管理器持有一个 FramePool，解码器从中取缓冲区写入像素，
+// 帧数据（FrameData）的最后一个克隆释放时缓冲区回到池中，供下一帧使用。
+// 池只保留当前尺寸的缓冲区：尺寸变化（切换分辨率、换文件）时丢弃旧尺寸的空闲缓冲区，停止播放时全部释放。
+// 空闲缓冲区的总大小不超过 MAX_IDLE_BYTES（至少保留 MIN_IDLE_BUFFERS 块），多出的直接释放。
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, Weak};
+
+/// 空闲缓冲区的总大小上限（字节）
+const MAX_IDLE_BYTES: usize = 64 * 1024 * 1024;
+
+/// 不论尺寸多大，至少保留的空闲缓冲区数（4K 画面一块就超过上限）
+const MIN_IDLE_BUFFERS: usize = 2;
+
+/// 缓冲区分配统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FramePoolStats {
+    /// 新分配的缓冲区数
+    pub allocated: u64,
+    /// 从池中复用的缓冲区数
+    pub reused: u64,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    buffer_len: usize,  // 当前尺寸（字节）
+    idle: Vec<Vec<u8>>,
+    stats: FramePoolStats,
+}
+
+/// 帧缓冲区池（播放管理器和解码器共享）
+#[derive(Debug, Default)]
+pub struct FramePool {
+    state: Mutex<PoolState>,
+}
+
+impl FramePool {
+    /// 创建空的缓冲区池
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// 取一块 len 字节的缓冲区（复用的缓冲区保留上一帧的内容，调用方需要写满）
+    pub fn acquire(&self, len: usize) -> Vec<u8> {
+        let mut state = self.state.lock().unwrap();
+        if state.buffer_len != len {
+            // 尺寸变化：旧尺寸的缓冲区不会再用到
+            state.idle.clear();
+            state.buffer_len = len;
+        }
+        match state.idle.pop() {
+            Some(buffer) => {
+                state.stats.reused += 1;
+                buffer
+            }
+            None => {
+                state.stats.allocated += 1;
+                vec![0u8; len]
+            }
+        }
+    }
+
+    /// 释放所有空闲缓冲区（停止播放时调用；仍在使用的缓冲区释放时不再回到池中）
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.idle = Vec::new();
+        state.buffer_len = 0;
+    }
+
+    /// 空闲缓冲区数
+    pub fn idle_count(&self) -> usize {
+        self.state.lock().unwrap().idle.len()
+    }
+
+    /// 分配统计
+    pub fn stats(&self) -> FramePoolStats {
+        self.state.lock().unwrap().stats
+    }
+
+    /// 帧数据释放时归还缓冲区（尺寸不符或空闲缓冲区已满时直接释放）
+    fn release(&self, buffer: Vec<u8>) {
+        let mut state = self.state.lock().unwrap();
+        let limit = (MAX_IDLE_BYTES / state.buffer_len.max(1)).max(MIN_IDLE_BUFFERS);
+        if buffer.len() == state.buffer_len && state.idle.len() < limit {
+            state.idle.push(buffer);
+        }
+    }
+}
+
+/// 缓冲区和它所属的池（最后一个引用释放时归还）
+struct PooledBytes {
+    data: Vec<u8>,
+    pool: Option<Weak<FramePool>>,
+}
+
+impl Drop for PooledBytes {
+    fn drop(&mut self) {
+        if let Some(pool) = self.pool.take().and_then(|pool| pool.upgrade()) {
+            pool.release(std::mem::take(&mut self.data));
+        }
+    }
+}
+
+/// 帧像素数据（共享，克隆帧不复制像素；来自 FramePool 时最后一个克隆释放后缓冲区回到池中）
+#[derive(Clone)]
+pub struct FrameData(Arc<PooledBytes>);
+
+impl FrameData {
+    /// 使用池中取出的缓冲区（pool 为 None 时与 From<Vec<u8>> 相同）
+    pub fn pooled(data: Vec<u8>, pool: Option<&Arc<FramePool>>) -> Self {
+        Self(Arc::new(PooledBytes { data, pool: pool.map(Arc::downgrade) }))
+    }
+
+    /// 是否为同一块数据（判断画面是否已上传）
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+
+    /// 可写的像素数据（与其他帧共享时先复制一份，复制出的数据不属于任何池）
+    pub fn make_mut(&mut self) -> &mut [u8] {
+        if Arc::get_mut(&mut self.0).is_none() {
+            *self = Self::from(self.to_vec());
+        }
+        &mut Arc::get_mut(&mut self.0).unwrap().data
+    }
+}
+
+impl Deref for FrameData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0.data
+    }
+}
+
+impl AsRef<[u8]> for FrameData {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<Vec<u8>> for FrameData {
+    fn from(data: Vec<u8>) -> Self {
+        Self::pooled(data, None)
+    }
+}
+
+impl PartialEq for FrameData {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for FrameData {}
+
+impl fmt::Debug for FrameData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FrameData({} bytes)", self.len())
+    }
+}
+
+impl Serialize for FrameData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FrameData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Vec::<u8>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEN: usize = 16 * 16 * 4;
+
+    #[test]
+    fn test_buffer_returns_when_last_clone_drops() {
+        let pool = FramePool::new();
+        let data = FrameData::pooled(pool.acquire(LEN), Some(&pool));
+        let shared = data.clone();
+        drop(data);
+        assert_eq!(pool.idle_count(), 0);
+        drop(shared);
+        assert_eq!(pool.idle_count(), 1);
+
+        // 稳定播放：每帧复用同一块缓冲区，不再分配
+        for _ in 0..100 {
+            drop(FrameData::pooled(pool.acquire(LEN), Some(&pool)));
+        }
+        assert_eq!(pool.stats(), FramePoolStats { allocated: 1, reused: 100 });
+    }
+
+    #[test]
+    fn test_resolution_change_and_clear_shrink_pool() {
+        let pool = FramePool::new();
+        let old = FrameData::pooled(pool.acquire(LEN), Some(&pool));
+        drop(FrameData::pooled(pool.acquire(LEN), Some(&pool)));
+        assert_eq!(pool.idle_count(), 1);
+
+        // 切换分辨率：丢弃旧尺寸的空闲缓冲区，仍在使用的旧缓冲区释放时也不回到池中
+        let new = FrameData::pooled(pool.acquire(LEN * 4), Some(&pool));
+        assert_eq!(pool.idle_count(), 0);
+        drop(old);
+        assert_eq!(pool.idle_count(), 0);
+        drop(new);
+        assert_eq!(pool.idle_count(), 1);
+
+        pool.clear();
+        assert_eq!(pool.idle_count(), 0);
+    }
+
+    #[test]
+    fn test_idle_buffers_are_bounded() {
+        let pool = FramePool::new();
+        let len = MAX_IDLE_BYTES / 3;
+        let frames: Vec<_> = (0..5).map(|_| FrameData::pooled(pool.acquire(len), Some(&pool))).collect();
+        drop(frames);
+        assert_eq!(pool.idle_count(), 3);
+    }
+
+    #[test]
+    fn test_make_mut_copies_shared_data() {
+        let pool = FramePool::new();
+        let mut data = FrameData::pooled(pool.acquire(LEN), Some(&pool));
+        let shared = data.clone();
+        data.make_mut()[0] = 7;
+        assert_eq!((data[0], shared[0]), (7, 0));
+        assert!(!FrameData::ptr_eq(&data, &shared));
+
+        // 复制出的数据不属于池，原缓冲区在最后一个克隆释放时归还
+        drop(data);
+        assert_eq!(pool.idle_count(), 0);
+        drop(shared);
+        assert_eq!(pool.idle_count(), 1);
+    }
+}
@@ -0,0 +1,233 @@
+// 图像序列识别（从选中的文件或文件夹推断 FFmpeg image2 文件名模板）
+
+use super::error::{PlayerError, Result};
+use super::types::is_supported_image_file;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 图像序列的默认帧率
+pub const DEFAULT_SEQUENCE_FPS: u32 = 24;
+
+/// 图像序列（FFmpeg image2 模板，如 frame_%04d.png）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencePattern {
+    /// 完整路径模板
+    pub template: PathBuf,
+    /// 第一帧编号
+    pub start_number: u64,
+    /// 帧数
+    pub frame_count: usize,
+}
+
+impl SequencePattern {
+    /// 模板文件名（用于显示）
+    pub fn display_name(&self) -> String {
+        self.template
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// 文件名拆分结果：前缀 + 编号 + 后缀
+struct NumberedName<'a> {
+    prefix: &'a str,
+    digits: &'a str,
+    suffix: &'a str,
+}
+
+/// 取文件名主干中最后一段数字作为帧编号
+fn split_numbered(name: &str) -> Option<NumberedName<'_>> {
+    let stem_end = name.rfind('.').unwrap_or(name.len());
+    let stem = &name[..stem_end];
+    let digits_end = stem.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let digits_start = stem[..digits_end]
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    Some(NumberedName {
+        prefix: &name[..digits_start],
+        digits: &name[digits_start..digits_end],
+        suffix: &name[digits_end..],
+    })
+}
+
+/// 根据同目录下的文件名推断序列（selected 为选中的文件名）
+///
+/// 文件名不含编号或只有一张同名图像时返回 None（按单张图像处理）；
+/// 编号不连续或位数不一致时返回错误，说明缺少哪一帧
+pub fn infer_from_names(dir: &Path, names: &[String], selected: &str) -> Result<Option<SequencePattern>> {
+    let Some(base) = split_numbered(selected) else {
+        return Ok(None);
+    };
+
+    let mut frames: Vec<&str> = names
+        .iter()
+        .filter_map(|name| split_numbered(name))
+        .filter(|n| n.prefix == base.prefix && n.suffix == base.suffix)
+        .map(|n| n.digits)
+        .collect();
+    frames.sort_by_key(|digits| (digits.len(), *digits));
+    frames.dedup();
+
+    if frames.len() < 2 {
+        return Ok(None);
+    }
+
+    // 编号宽度：全部等宽且存在前导零 -> 补零（%0Nd）；否则不补零（%d），此时不允许出现前导零
+    let width = frames[0].len();
+    let same_width = frames.iter().all(|digits| digits.len() == width);
+    let has_leading_zero = frames.iter().any(|digits| digits.len() > 1 && digits.starts_with('0'));
+    let padded = same_width && has_leading_zero;
+    if has_leading_zero && !padded {
+        return Err(PlayerError::OpenError(format!(
+            "{}*{} 的编号位数不一致（{} 与 {}），无法作为一个序列播放",
+            base.prefix,
+            base.suffix,
+            frames[0],
+            frames[frames.len() - 1]
+        )));
+    }
+
+    let mut sorted: Vec<u64> = frames
+        .iter()
+        .map(|digits| digits.parse::<u64>())
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| PlayerError::OpenError(format!("{}*{} 的帧编号过大", base.prefix, base.suffix)))?;
+    sorted.sort_unstable();
+
+    if let Some(pair) = sorted.windows(2).find(|pair| pair[1] != pair[0] + 1) {
+        let missing = pair[0] + 1;
+        let missing_name = if padded {
+            format!("{}{:0width$}{}", base.prefix, missing, base.suffix, width = width)
+        } else {
+            format!("{}{}{}", base.prefix, missing, base.suffix)
+        };
+        let missing_count = pair[1] - pair[0] - 1;
+        return Err(PlayerError::OpenError(format!(
+            "图像序列不连续：缺少 {}{}",
+            missing_name,
+            if missing_count > 1 { format!(" 等 {} 帧", missing_count) } else { String::new() }
+        )));
+    }
+
+    // FFmpeg 模板中的 % 需要转义
+    let number = if padded { format!("%0{}d", width) } else { "%d".to_string() };
+    let template = format!("{}{}{}", base.prefix.replace('%', "%%"), number, base.suffix.replace('%', "%%"));
+
+    Ok(Some(SequencePattern {
+        template: dir.join(template),
+        start_number: sorted[0],
+        frame_count: sorted.len(),
+    }))
+}
+
+/// 从选中的图像文件推断序列（规则同 infer_from_names）
+pub fn infer_sequence(selected: &Path) -> Result<Option<SequencePattern>> {
+    let dir = selected.parent().unwrap_or_else(|| Path::new("."));
+    let selected_name = selected
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| PlayerError::OpenError(format!("无效的文件路径: {}", selected.display())))?;
+    infer_from_names(dir, &list_image_names(dir)?, &selected_name)
+}
+
+/// 从文件夹中查找图像序列（以排序后第一张带编号的图像为基准）
+pub fn find_sequence_in_folder(dir: &Path) -> Result<SequencePattern> {
+    let names = list_image_names(dir)?;
+    let first = names
+        .iter()
+        .find(|name| split_numbered(name).is_some())
+        .ok_or_else(|| PlayerError::OpenError(format!("文件夹中没有带编号的图像: {}", dir.display())))?;
+    infer_from_names(dir, &names, first)?
+        .ok_or_else(|| PlayerError::OpenError(format!("文件夹中只有一张带编号的图像: {}", dir.display())))
+}
+
+/// 列出目录中的图像文件名（已排序）
+fn list_image_names(dir: &Path) -> Result<Vec<String>> {
+    let mut names: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_supported_image_file(path))
+        .filter_map(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn infer(list: &[&str], selected: &str) -> Result<SequencePattern> {
+        infer_from_names(Path::new("/renders"), &names(list), selected).map(|seq| seq.expect("应识别为序列"))
+    }
+
+    #[test]
+    fn test_zero_padded_sequence() {
+        let seq = infer(&["frame_0001.png", "frame_0002.png", "frame_0003.png", "notes.txt"], "frame_0002.png").unwrap();
+        assert_eq!(seq.template, Path::new("/renders/frame_%04d.png"));
+        assert_eq!(seq.start_number, 1);
+        assert_eq!(seq.frame_count, 3);
+    }
+
+    #[test]
+    fn test_non_padded_sequence_across_digit_counts() {
+        let list: Vec<String> = (8..=12).map(|i| format!("shot{}.jpg", i)).collect();
+        let list: Vec<&str> = list.iter().map(|s| s.as_str()).collect();
+        let seq = infer(&list, "shot8.jpg").unwrap();
+        assert_eq!(seq.template, Path::new("/renders/shot%d.jpg"));
+        assert_eq!(seq.start_number, 8);
+        assert_eq!(seq.frame_count, 5);
+    }
+
+    #[test]
+    fn test_only_matching_prefix_and_extension_are_used() {
+        let seq = infer(
+            &["a_001.png", "a_002.png", "b_001.png", "b_002.png", "b_003.png", "a_003.exr"],
+            "a_001.png",
+        )
+        .unwrap();
+        assert_eq!(seq.template, Path::new("/renders/a_%03d.png"));
+        assert_eq!(seq.frame_count, 2);
+    }
+
+    #[test]
+    fn test_digits_in_prefix_use_last_number() {
+        let seq = infer(&["v2_take3.0010.tif", "v2_take3.0011.tif"], "v2_take3.0011.tif").unwrap();
+        assert_eq!(seq.template, Path::new("/renders/v2_take3.%04d.tif"));
+        assert_eq!(seq.start_number, 10);
+    }
+
+    #[test]
+    fn test_gap_is_reported_with_missing_frame() {
+        let err = infer(&["frame_0001.png", "frame_0002.png", "frame_0005.png"], "frame_0001.png").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("不连续"), "{}", message);
+        assert!(message.contains("frame_0003.png"), "{}", message);
+        assert!(message.contains("2 帧"), "{}", message);
+    }
+
+    #[test]
+    fn test_mixed_padding_is_rejected() {
+        let err = infer(&["f_01.png", "f_02.png", "f_100.png"], "f_01.png").unwrap_err();
+        assert!(err.to_string().contains("位数不一致"));
+    }
+
+    #[test]
+    fn test_single_or_unnumbered_image_is_not_a_sequence() {
+        let dir = Path::new("/renders");
+        assert_eq!(infer_from_names(dir, &names(&["poster_1.png", "other.png"]), "poster_1.png").unwrap(), None);
+        assert_eq!(infer_from_names(dir, &names(&["poster.png", "poster2.png"]), "poster.png").unwrap(), None);
+    }
+
+    #[test]
+    fn test_percent_in_name_is_escaped() {
+        let seq = infer(&["100%_1.png", "100%_2.png"], "100%_1.png").unwrap();
+        assert_eq!(seq.template, Path::new("/renders/100%%_%d.png"));
+    }
+}
@@ -1,8 +1,15 @@
 // 核心数据结构和类型定义
 
-pub mod types;
-pub mod clock;
-pub mod error;
+pub(crate) mod types;
+pub(crate) mod clock;
+pub(crate) mod error;
+pub(crate) mod frame_pool;
+pub(crate) mod image_sequence;
+pub(crate) mod runtime_flags;
+pub(crate) mod yuv;
+pub mod ffmpeg_log;
+pub mod ffi_util;
+pub mod text;
 
 // 重新导出常用类型
 pub use types::{VideoFrame, AudioFrame, SubtitleFrame};
@@ -10,4 +17,9 @@ pub use types::{VideoFrame, AudioFrame, SubtitleFrame};
 pub use types::*;
 pub use clock::*;
 pub use error::*;
+pub use runtime_flags::RuntimeFlags;
+pub use yuv::{YuvMatrix, YuvPlanes};
+pub use frame_pool::{FrameData, FramePool, FramePoolStats};
+pub use image_sequence::{find_sequence_in_folder, infer_sequence, SequencePattern, DEFAULT_SEQUENCE_FPS};
+pub use text::truncate_chars;
 
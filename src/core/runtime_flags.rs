@@ -0,0 +1,49 @@
+// 运行时功能开关（安全模式：关闭容易引发崩溃或显示异常的功能，用于排查问题文件）
+//
+// 各功能在启用处查询 RuntimeFlags，而不是各自维护开关：
+// - 硬件解码：创建视频解码器时（设置中的硬件解码开关仍然有效，两者都开启才尝试硬解）
+// - 自动加载字幕：打开文件时不自动选择内嵌或外部字幕（仍可手动选择）
+// - 监视文件夹、自动匹配刷新率：界面启动监视 / 切换显示模式前
+// 界面渲染仍使用 wgpu（视频渲染器依赖 wgpu 纹理），中文字体照常加载（否则界面文字无法显示）。
+
+/// 功能开关
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeFlags {
+    /// 硬件解码
+    pub hw_decode: bool,
+    /// 打开文件时自动加载字幕
+    pub subtitle_autoload: bool,
+    /// 监视文件夹
+    pub watch_folder: bool,
+    /// 自动匹配刷新率
+    pub refresh_rate_switching: bool,
+}
+
+impl RuntimeFlags {
+    /// 正常模式（全部开启，是否实际启用由各自的设置决定）
+    pub const NORMAL: RuntimeFlags = RuntimeFlags {
+        hw_decode: true,
+        subtitle_autoload: true,
+        watch_folder: true,
+        refresh_rate_switching: true,
+    };
+
+    /// 安全模式（全部关闭）
+    pub const SAFE_MODE: RuntimeFlags = RuntimeFlags {
+        hw_decode: false,
+        subtitle_autoload: false,
+        watch_folder: false,
+        refresh_rate_switching: false,
+    };
+
+    /// 是否为安全模式（全部关闭）
+    pub fn is_safe_mode(&self) -> bool {
+        *self == Self::SAFE_MODE
+    }
+}
+
+impl Default for RuntimeFlags {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
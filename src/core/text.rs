@@ -0,0 +1,31 @@
+// 文本截断工具（按字素簇处理，中英文混排和 emoji 都不会被截在字符中间）
+//
+// 直接按字节下标切片（如 &name[..10]）遇到多字节字符会 panic，按 char 截断又可能把
+// emoji 组合序列（肤色、ZWJ 家庭 emoji、国旗）拆开。truncate_chars 按字素簇（用户看到的一个字符）
+// 保留开头的 n 个字符；界面上从中间省略文件名的工具在 app/ellipsis.rs 中。
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// 保留开头的 max_chars 个字符（按字素簇计数）
+pub fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.grapheme_indices(true).nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_multibyte_and_emoji() {
+        assert_eq!(truncate_chars("喜洋洋与灰太狼第一季第01集", 10), "喜洋洋与灰太狼第一季");
+        assert_eq!(truncate_chars("短名", 10), "短名");
+        assert_eq!(truncate_chars("", 3), "");
+        // 组合 emoji 不会被拆开
+        let family = "👨\u{200d}👩\u{200d}👧";
+        assert_eq!(truncate_chars(&format!("{}{}abc", family, family), 1), family);
+        assert_eq!(truncate_chars("e\u{301}tude", 1), "e\u{301}");
+    }
+}
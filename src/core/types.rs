@@ -1,5 +1,9 @@
+use super::image_sequence::SequencePattern;
+use super::frame_pool::FrameData;
+use super::yuv::YuvPlanes;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 /// 媒体源类型
 #[derive(Debug, Clone)]
@@ -9,9 +13,22 @@ pub enum MediaSource {
     
     /// 网络流 URL
     NetworkStream {
+        /// 流地址
         url: String,
+        /// 流协议
         protocol: StreamProtocol,
     },
+
+    /// 图像序列（按固定帧率作为无声视频播放）
+    ImageSequence {
+        /// 文件名模式
+        pattern: SequencePattern,
+        /// 播放帧率
+        fps: u32,
+    },
+
+    /// 单张图像（静止显示，不推进时钟）
+    Image(PathBuf),
 }
 
 impl MediaSource {
@@ -63,6 +80,7 @@ pub enum StreamProtocol {
 }
 
 impl StreamProtocol {
+    /// 协议名称（显示用）
     pub fn as_str(&self) -> &'static str {
         match self {
             StreamProtocol::RTSP => "RTSP",
@@ -83,71 +101,58 @@ pub enum StreamState {
     Connecting,
     
     /// 已连接，缓冲中
-    Buffering { 
-        progress: f32  // 0.0 - 1.0
+    Buffering {
+        /// 缓冲进度（0.0 - 1.0）
+        progress: f32,
     },
     
     /// 播放中
     Playing,
     
     /// 重新连接中
-    Reconnecting { 
-        attempt: u32 
+    Reconnecting {
+        /// 第几次尝试
+        attempt: u32,
     },
     
     /// 连接失败
-    Failed { 
-        reason: String 
+    Failed {
+        /// 失败原因
+        reason: String,
     },
 }
 
-/// 缓冲状态信息（用于监控和调试）
-#[derive(Debug, Clone, Default)]
-pub struct BufferStatus {
-    /// 视频数据包队列长度
-    pub video_packets: usize,
-    
-    /// 音频数据包队列长度
-    pub audio_packets: usize,
-    
-    /// 视频帧队列长度
-    pub video_frames: usize,
-    
-    /// 音频帧队列长度
-    pub audio_frames: usize,
-    
-    /// 是否正在缓冲
-    pub is_buffering: bool,
-    
-    /// 缓冲进度 (0.0 - 1.0)
-    pub buffer_progress: f32,
-}
-
 /// 像素格式
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PixelFormat {
+    /// 8 位 RGBA，单一平面
     RGBA,
+    /// 8 位 RGB，单一平面
     RGB,
+    /// 8 位 YUV 4:2:0，Y、U、V 三个平面
     YUV420P,
+    /// 8 位 YUV 4:2:0，Y 平面和交错的 UV 平面
     NV12,
 }
 
-/// 音频采样格式
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SampleFormat {
-    F32,
-    I16,
-}
-
 /// 视频帧数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VideoFrame {
-    pub pts: i64,           // 显示时间戳（毫秒）
-    pub duration: i64,      // 帧持续时间（毫秒）
+    /// 显示时间戳（毫秒）
+    pub pts: i64,
+    /// 帧持续时间（毫秒）
+    pub duration: i64,
+    /// 宽度（像素）
     pub width: u32,
+    /// 高度（像素）
     pub height: u32,
+    /// 像素格式
     pub format: PixelFormat,
-    pub data: Vec<u8>,      // CPU 内存数据
+    /// YUV 帧的平面布局与色彩参数（RGBA 帧为 None）
+    #[serde(default)]
+    pub planes: Option<YuvPlanes>,
+    /// CPU 内存数据（共享，克隆帧不复制像素；解码帧的缓冲区来自帧缓冲区池）
+    pub data: FrameData,
 }
 
 /// 音频帧数据
@@ -156,44 +161,305 @@ pub struct AudioFrame {
     pub pts: i64,           // 显示时间戳（毫秒）
     pub sample_rate: u32,
     pub channels: u16,
-    pub format: SampleFormat,
     pub data: Vec<f32>,     // 统一使用 f32 格式
 }
 
 /// 字幕帧数据
 #[derive(Debug, Clone)]
 pub struct SubtitleFrame {
-    pub pts: i64,           // 开始显示时间戳（毫秒）
-    pub duration: i64,      // 显示持续时间（毫秒）
-    pub text: String,        // 字幕文本
-    pub end_pts: i64,       // 结束显示时间戳（毫秒）
+    /// 开始显示时间戳（毫秒）
+    pub pts: i64,
+    /// 显示持续时间（毫秒）
+    pub duration: i64,
+    /// 字幕文本
+    pub text: String,
+    /// 结束显示时间戳（毫秒）
+    pub end_pts: i64,
+}
+
+/// 已呈现视频帧信息（由 UI 在实际更新画面后上报）
+#[derive(Debug, Clone, Copy)]
+pub struct PresentedFrameInfo {
+    /// 呈现帧的时间戳（毫秒）
+    pub pts: i64,
+    /// 呈现时刻（墙上时间）
+    pub presented_at: Instant,
+}
+
+/// 支持的视频文件扩展名（文件对话框与监视文件夹共用）
+pub const SUPPORTED_VIDEO_EXTENSIONS: &[&str] = &["mp4", "avi", "mkv", "mov", "wmv", "flv"];
+
+/// 判断是否为支持的视频文件（按扩展名，不区分大小写）
+pub fn is_supported_video_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            let ext = ext.to_lowercase();
+            SUPPORTED_VIDEO_EXTENSIONS.contains(&ext.as_str())
+        })
+        .unwrap_or(false)
+}
+
+/// 支持的图像文件扩展名（单张图像或图像序列）
+pub const SUPPORTED_IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "tif", "tiff", "webp"];
+
+/// 判断是否为支持的图像文件（按扩展名，不区分大小写）
+pub fn is_supported_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SUPPORTED_IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// 支持的外部字幕文件扩展名
+pub const SUPPORTED_SUBTITLE_EXTENSIONS: &[&str] = &["srt", "ass", "ssa", "vtt"];
+
+/// 判断是否为支持的外部字幕文件（按扩展名，不区分大小写）
+pub fn is_subtitle_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SUPPORTED_SUBTITLE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// 用户数据目录（播放位置记录、关键帧索引缓存等）
+pub fn user_data_dir() -> PathBuf {
+    let base = std::env::var_os("APPDATA")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("XDG_DATA_HOME").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("myy_player")
+}
+
+/// 轨道来源
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrackSource {
+    /// 内嵌流（流索引）
+    Embedded(usize),
+    /// 外部字幕文件
+    External(PathBuf),
+}
+
+/// 流元数据（来自流的 metadata 字典、disposition 标志和编码参数）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamMeta {
+    /// 语言代码（ISO 639-2，如 jpn）
+    pub language: Option<String>,
+    /// 轨道标题（Matroska title 标签或 MP4 handler_name）
+    pub title: Option<String>,
+    /// 编解码器名称
+    pub codec: String,
+    /// 声道数（字幕为 0）
+    pub channels: u16,
+    /// 采样率（字幕为 0）
+    pub sample_rate: u32,
+    /// 码率（bps，未知时为 None）
+    pub bit_rate: Option<u64>,
+    /// 默认轨道
+    pub is_default: bool,
+    /// 强制字幕（只翻译外语对白/标牌）
+    pub forced: bool,
+    /// 听障字幕（SDH）
+    pub hearing_impaired: bool,
+    /// 解说音轨
+    pub commentary: bool,
+}
+
+/// 媒体轨道信息（音频/字幕）
+#[derive(Debug, Clone)]
+pub struct TrackInfo {
+    /// 轨道来源
+    pub source: TrackSource,
+    /// 流元数据
+    pub meta: StreamMeta,
+}
+
+impl TrackInfo {
+    /// 显示名称，如 "日语 · AAC 5.1 · 320kbps · 默认"
+    pub fn display_name(&self) -> String {
+        let meta = &self.meta;
+        let name = match (&meta.language, &meta.title, &self.source) {
+            (Some(lang), _, _) => language_display_name(lang),
+            (None, Some(title), _) => title.clone(),
+            (None, None, TrackSource::External(path)) => path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "外部字幕".to_string()),
+            (None, None, TrackSource::Embedded(index)) => format!("轨道 {}", index),
+        };
+
+        let codec = meta.codec.to_uppercase();
+        let detail = match meta.channels {
+            0 => codec,
+            1 => format!("{} 1.0", codec),
+            2 => format!("{} 2.0", codec),
+            6 => format!("{} 5.1", codec),
+            8 => format!("{} 7.1", codec),
+            n => format!("{} {}ch", codec, n),
+        };
+
+        let mut parts = vec![name];
+        if !detail.is_empty() {
+            parts.push(detail);
+        }
+        if let Some(bit_rate) = meta.bit_rate {
+            parts.push(format!("{}kbps", (bit_rate + 500) / 1000));
+        }
+        if meta.is_default {
+            parts.push("默认".to_string());
+        }
+        parts.join(" · ")
+    }
+
+    /// 轨道标记（强制/听障/解说），菜单中以徽标显示
+    pub fn badges(&self) -> Vec<&'static str> {
+        [
+            (self.meta.forced, "强制"),
+            (self.meta.hearing_impaired, "SDH"),
+            (self.meta.commentary, "解说"),
+        ]
+        .into_iter()
+        .filter_map(|(flag, badge)| flag.then_some(badge))
+        .collect()
+    }
+}
+
+/// 两个语言代码是否表示同一种语言（兼容 ISO 639-1/639-2 与 B/T 两种写法）
+pub fn same_language(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b) || language_display_name(a) == language_display_name(b)
+}
+
+/// 选择与音频语言一致的强制字幕（用户关闭字幕时仍显示外语对白翻译）
+pub fn pick_forced_subtitle(subtitles: &[TrackInfo], audio_language: Option<&str>) -> Option<TrackSource> {
+    let audio_language = audio_language.filter(|lang| !lang.eq_ignore_ascii_case("und"))?;
+    subtitles
+        .iter()
+        .find(|track| {
+            track.meta.forced
+                && track
+                    .meta
+                    .language
+                    .as_deref()
+                    .is_some_and(|lang| same_language(lang, audio_language))
+        })
+        .map(|track| track.source.clone())
+}
+
+/// 常见语言代码转中文名称（未知代码原样返回）
+pub fn language_display_name(code: &str) -> String {
+    let name = match code.to_lowercase().as_str() {
+        "chi" | "zho" | "zh" => "中文",
+        "eng" | "en" => "英语",
+        "jpn" | "ja" => "日语",
+        "kor" | "ko" => "韩语",
+        "fre" | "fra" | "fr" => "法语",
+        "ger" | "deu" | "de" => "德语",
+        "spa" | "es" => "西班牙语",
+        "rus" | "ru" => "俄语",
+        "ita" | "it" => "意大利语",
+        "por" | "pt" => "葡萄牙语",
+        "und" => "未知语言",
+        _ => return code.to_string(),
+    };
+    name.to_string()
+}
+
+/// 章节信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    /// 开始时间（毫秒）
+    pub start_ms: i64,
+    /// 结束时间（毫秒，不含）
+    pub end_ms: i64,
+    /// 章节标题（为空时为 "第 N 章"）
+    pub title: String,
+}
+
+/// 查找指定时间所在的章节（章节需按开始时间排序，落在章节间隙中时返回 None）
+pub fn chapter_at(chapters: &[Chapter], time_ms: i64) -> Option<usize> {
+    let index = chapters.partition_point(|c| c.start_ms <= time_ms).checked_sub(1)?;
+    (time_ms < chapters[index].end_ms).then_some(index)
+}
+
+/// 跳到上一章时：本章已播放超过该时长则回到本章开头，否则跳到上一章（毫秒）
+pub const CHAPTER_RESTART_MS: i64 = 3_000;
+
+/// seek 可能落在章节开头之前一点（关键帧），判断当前章节时容许的提前量（毫秒）
+const CHAPTER_SNAP_MS: i64 = 500;
+
+/// 上一章 / 下一章的索引（没有章节、已是第一章 / 最后一章时返回 None；落在章节间隙中时按之前的章节计算）
+pub fn adjacent_chapter(chapters: &[Chapter], time_ms: i64, forward: bool) -> Option<usize> {
+    let started = chapters.partition_point(|c| c.start_ms <= time_ms + CHAPTER_SNAP_MS);
+    if forward {
+        return (started < chapters.len()).then_some(started);
+    }
+    let current = started.checked_sub(1)?;
+    if time_ms - chapters[current].start_ms > CHAPTER_RESTART_MS {
+        Some(current)
+    } else {
+        current.checked_sub(1)
+    }
 }
 
 /// 播放状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PlaybackState {
+    /// 没有打开媒体
     Idle,
+    /// 打开中
     Opening,
+    /// 播放中
     Playing,
+    /// 已暂停
     Paused,
+    /// seek 中
     Seeking,
+    /// 缓冲中
     Buffering,
+    /// 已停止
     Stopped,
+    /// 播放完毕（读到文件末尾且帧队列已播完，时钟停在末尾）
+    Ended,
+    /// 出错停止
     Error,
 }
 
 /// 媒体信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaInfo {
-    pub duration: i64,          // 总时长（毫秒）
+    /// 总时长（毫秒）
+    pub duration: i64,
+    /// 视频宽度（像素，没有视频时为 0）
     pub width: u32,
+    /// 视频高度（像素，没有视频时为 0）
     pub height: u32,
+    /// 像素宽高比（SAR，1.0 为方形像素）
+    pub pixel_aspect: f64,
+    /// 显示时顺时针旋转角度（0/90/180/270）
+    pub rotation: u32,
+    /// 视频帧率
     pub fps: f64,
+    /// 视频编解码器名称
     pub video_codec: String,
+    /// 音频编解码器名称
     pub audio_codec: String,
+    /// 音频采样率（没有音频时为 0）
     pub sample_rate: u32,
+    /// 音频声道数（没有音频时为 0）
     pub channels: u16,
+    /// 直播流（没有固定时长，不能任意 seek）
+    pub is_live: bool,
+    /// 杜比视界 / HDR10+ 兼容性
+    #[serde(default)]
+    pub hdr_compat: HdrCompatibility,
+    /// 容器的全局标题标签（Matroska / MP4 title）
+    #[serde(default)]
+    pub title: Option<String>,
+    /// 色彩空间说明（如 "BT.2020 PQ (HDR, tone mapped)"；没有视频时为空）
+    #[serde(default)]
+    pub color_space: String,
 }
 
 impl Default for MediaInfo {
@@ -202,22 +468,91 @@ impl Default for MediaInfo {
             duration: 0,
             width: 0,
             height: 0,
+            pixel_aspect: 1.0,
+            rotation: 0,
             fps: 0.0,
             video_codec: String::new(),
             audio_codec: String::new(),
             sample_rate: 0,
             channels: 0,
+            is_live: false,
+            hdr_compat: HdrCompatibility::Standard,
+            title: None,
+            color_space: String::new(),
         }
     }
 }
 
+impl MediaInfo {
+    /// 画面原始显示尺寸（应用像素宽高比与旋转后）
+    pub fn display_size(&self) -> (f64, f64) {
+        display_size(self.width, self.height, self.pixel_aspect, self.rotation)
+    }
+}
+
+/// HDR 格式兼容性（杜比视界 / HDR10+ 检测结果）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HdrCompatibility {
+    /// SDR 或普通 HDR10，按常规管线显示
+    #[default]
+    Standard,
+    /// HDR10+：忽略动态元数据，按 HDR10 显示
+    Hdr10Plus,
+    /// 带兼容基础层的杜比视界（如 Profile 8.1）：播放基础层，增强数据被忽略
+    DolbyVisionBaseLayer {
+        /// 杜比视界 Profile
+        profile: u8,
+        /// 基础层兼容性 ID
+        compatibility: u8,
+    },
+    /// 没有兼容基础层的杜比视界（如 Profile 5，IPT-PQ 数据）：颜色无法正确显示
+    DolbyVisionUnsupported {
+        /// 杜比视界 Profile（配置记录缺失时为 None）
+        profile: Option<u8>,
+    },
+}
+
+impl HdrCompatibility {
+    /// 能否正确显示颜色
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, HdrCompatibility::DolbyVisionUnsupported { .. })
+    }
+
+    /// 向用户说明的提示（普通片源没有提示）
+    pub fn notice(&self) -> Option<String> {
+        match self {
+            HdrCompatibility::Standard => None,
+            HdrCompatibility::Hdr10Plus => Some("HDR10+：已忽略动态元数据，按 HDR10 显示".to_string()),
+            HdrCompatibility::DolbyVisionBaseLayer { profile, compatibility } => {
+                Some(format!("杜比视界 Profile {}.{}：播放兼容基础层，已忽略增强数据", profile, compatibility))
+            }
+            HdrCompatibility::DolbyVisionUnsupported { profile: Some(profile) } => {
+                Some(format!("此文件为杜比视界 Profile {}，当前无法正确显示颜色", profile))
+            }
+            HdrCompatibility::DolbyVisionUnsupported { profile: None } => {
+                Some("此文件为杜比视界（无兼容基础层），当前无法正确显示颜色".to_string())
+            }
+        }
+    }
+}
+
+/// 计算画面显示尺寸：宽度按像素宽高比拉伸，旋转 90/270 度时交换宽高
+pub fn display_size(width: u32, height: u32, pixel_aspect: f64, rotation: u32) -> (f64, f64) {
+    let pixel_aspect = if pixel_aspect.is_finite() && pixel_aspect > 0.0 { pixel_aspect } else { 1.0 };
+    let (w, h) = (width as f64 * pixel_aspect, height as f64);
+    if rotation % 180 == 90 { (h, w) } else { (w, h) }
+}
+
+/// 最大音量（线性增益，2.0 = 200%）
+pub const MAX_VOLUME: f32 = 2.0;
+
 /// 播放器状态信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerState {
     pub state: PlaybackState,
     pub position: i64,          // 当前位置（毫秒）
     pub duration: i64,          // 总时长（毫秒）
-    pub volume: f32,            // 音量 0.0 - 1.0
+    pub volume: f32,            // 音量 0.0 - MAX_VOLUME（1.0 = 100%）
     pub media_info: Option<MediaInfo>,
 }
 
@@ -233,3 +568,144 @@ impl Default for PlayerState {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(start_ms: i64, end_ms: i64) -> Chapter {
+        Chapter { start_ms, end_ms, title: String::new() }
+    }
+
+    #[test]
+    fn test_chapter_at_adjacent() {
+        let chapters = vec![chapter(0, 1_000), chapter(1_000, 5_000), chapter(5_000, 9_000)];
+        assert_eq!(chapter_at(&chapters, 0), Some(0));
+        assert_eq!(chapter_at(&chapters, 999), Some(0));
+        assert_eq!(chapter_at(&chapters, 1_000), Some(1));
+        assert_eq!(chapter_at(&chapters, 4_999), Some(1));
+        assert_eq!(chapter_at(&chapters, 5_000), Some(2));
+        assert_eq!(chapter_at(&chapters, 9_000), None);
+        assert_eq!(chapter_at(&chapters, -1), None);
+    }
+
+    #[test]
+    fn test_chapter_at_gapped() {
+        let chapters = vec![chapter(2_000, 4_000), chapter(6_000, 8_000)];
+        assert_eq!(chapter_at(&chapters, 1_999), None);
+        assert_eq!(chapter_at(&chapters, 2_000), Some(0));
+        assert_eq!(chapter_at(&chapters, 4_000), None);
+        assert_eq!(chapter_at(&chapters, 5_999), None);
+        assert_eq!(chapter_at(&chapters, 6_000), Some(1));
+        assert_eq!(chapter_at(&chapters, 7_999), Some(1));
+        assert_eq!(chapter_at(&chapters, 8_000), None);
+    }
+
+    #[test]
+    fn test_adjacent_chapter_boundaries() {
+        let chapters = vec![chapter(0, 60_000), chapter(60_000, 120_000), chapter(120_000, 180_000)];
+        assert_eq!(adjacent_chapter(&chapters, 10_000, true), Some(1));
+        // seek 落在章节开头之前一点：仍视为已在该章
+        assert_eq!(adjacent_chapter(&chapters, 59_960, true), Some(2));
+        assert_eq!(adjacent_chapter(&chapters, 130_000, true), None);
+        // 本章播放超过 3 秒回到本章开头，否则跳到上一章
+        assert_eq!(adjacent_chapter(&chapters, 70_000, false), Some(1));
+        assert_eq!(adjacent_chapter(&chapters, 61_000, false), Some(0));
+        assert_eq!(adjacent_chapter(&chapters, 59_960, false), Some(0));
+        assert_eq!(adjacent_chapter(&chapters, 1_000, false), None);
+        assert_eq!(adjacent_chapter(&chapters, 5_000, false), Some(0));
+        assert_eq!(adjacent_chapter(&[], 5_000, true), None);
+        assert_eq!(adjacent_chapter(&[], 5_000, false), None);
+
+        // 第一章之前的间隙
+        let gapped = vec![chapter(10_000, 20_000)];
+        assert_eq!(adjacent_chapter(&gapped, 0, true), Some(0));
+        assert_eq!(adjacent_chapter(&gapped, 0, false), None);
+    }
+
+    #[test]
+    fn test_chapter_at_empty() {
+        assert_eq!(chapter_at(&[], 0), None);
+        assert_eq!(chapter_at(&[chapter(0, 0)], 0), None);
+    }
+
+    #[test]
+    fn test_display_size_applies_sar_and_rotation() {
+        assert_eq!(display_size(1920, 1080, 1.0, 0), (1920.0, 1080.0));
+        // DVD 16:9 变形画面（720x480，SAR 32:27）
+        let (width, height) = display_size(720, 480, 32.0 / 27.0, 0);
+        assert!((width - 853.33).abs() < 0.01 && height == 480.0);
+        assert_eq!(display_size(1920, 1080, 1.0, 90), (1080.0, 1920.0));
+        assert_eq!(display_size(1920, 1080, 1.0, 180), (1920.0, 1080.0));
+        assert_eq!(display_size(1440, 1080, 4.0 / 3.0, 270), (1080.0, 1920.0));
+        // 未知的像素宽高比按方形像素处理
+        assert_eq!(display_size(640, 480, 0.0, 0), (640.0, 480.0));
+    }
+
+    fn track(index: usize, language: Option<&str>, forced: bool) -> TrackInfo {
+        TrackInfo {
+            source: TrackSource::Embedded(index),
+            meta: StreamMeta {
+                language: language.map(str::to_string),
+                codec: "subrip".to_string(),
+                forced,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_track_display_name() {
+        let audio = TrackInfo {
+            source: TrackSource::Embedded(1),
+            meta: StreamMeta {
+                language: Some("jpn".to_string()),
+                codec: "aac".to_string(),
+                channels: 6,
+                sample_rate: 48_000,
+                bit_rate: Some(320_000),
+                is_default: true,
+                commentary: true,
+                ..Default::default()
+            },
+        };
+        assert_eq!(audio.display_name(), "日语 · AAC 5.1 · 320kbps · 默认");
+        assert_eq!(audio.badges(), vec!["解说"]);
+
+        let external = TrackInfo {
+            source: TrackSource::External(PathBuf::from("/movies/movie.srt")),
+            meta: StreamMeta { codec: "srt".to_string(), ..Default::default() },
+        };
+        assert_eq!(external.display_name(), "movie.srt · SRT");
+        assert!(external.badges().is_empty());
+    }
+
+    #[test]
+    fn test_same_language() {
+        assert!(same_language("jpn", "ja"));
+        assert!(same_language("ger", "deu"));
+        assert!(same_language("ENG", "eng"));
+        assert!(!same_language("eng", "jpn"));
+        assert!(same_language("tha", "THA"));
+    }
+
+    #[test]
+    fn test_pick_forced_subtitle_matches_audio_language() {
+        let subtitles = vec![
+            track(2, Some("eng"), false),
+            track(3, Some("jpn"), true),
+            track(4, Some("eng"), true),
+        ];
+        assert_eq!(pick_forced_subtitle(&subtitles, Some("en")), Some(TrackSource::Embedded(4)));
+        assert_eq!(pick_forced_subtitle(&subtitles, Some("jpn")), Some(TrackSource::Embedded(3)));
+    }
+
+    #[test]
+    fn test_pick_forced_subtitle_requires_known_language() {
+        let subtitles = vec![track(2, Some("eng"), true), track(3, None, true)];
+        assert_eq!(pick_forced_subtitle(&subtitles, Some("fre")), None);
+        assert_eq!(pick_forced_subtitle(&subtitles, None), None);
+        assert_eq!(pick_forced_subtitle(&subtitles, Some("und")), None);
+        assert_eq!(pick_forced_subtitle(&[track(2, Some("eng"), false)], Some("eng")), None);
+    }
+}
@@ -0,0 +1,180 @@
+// YUV 帧（YUV420P / NV12）的平面布局与 YUV -> RGB 转换系数
+//
+// GPU 渲染时各平面直接上传为纹理，由着色器按同一组系数转换；截图、兼容渲染模式等需要 RGBA 的场合
+// 用 VideoFrame::to_rgba 在 CPU 上转换（色度取最近的样本，只用于偶尔的单帧转换）。
+
+use super::types::{PixelFormat, VideoFrame};
+use serde::{Deserialize, Serialize};
+
+/// YUV -> RGB 的色彩矩阵
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum YuvMatrix {
+    /// ITU-R BT.601（标清）
+    Bt601,
+    /// ITU-R BT.709（高清）
+    Bt709,
+}
+
+impl YuvMatrix {
+    /// 红、蓝分量的亮度权重 (Kr, Kb)
+    fn weights(self) -> (f32, f32) {
+        match self {
+            YuvMatrix::Bt601 => (0.299, 0.114),
+            YuvMatrix::Bt709 => (0.2126, 0.0722),
+        }
+    }
+
+    /// 未标注色彩矩阵时按分辨率推断（高清用 BT.709，标清用 BT.601）
+    pub fn guess(width: u32, height: u32) -> Self {
+        if width >= 1280 || height > 576 {
+            YuvMatrix::Bt709
+        } else {
+            YuvMatrix::Bt601
+        }
+    }
+}
+
+/// YUV 帧的平面布局与色彩参数（VideoFrame::planes）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct YuvPlanes {
+    /// 各平面在 data 中的起始位置（NV12 只有两个平面，第二个为交错的 UV）
+    pub offsets: [usize; 3],
+    /// 各平面每行字节数（可能大于平面宽度）
+    pub strides: [usize; 3],
+    /// 色彩矩阵
+    pub matrix: YuvMatrix,
+    /// 全范围（0-255），否则为有限范围（亮度 16-235，色度 16-240）
+    pub full_range: bool,
+    /// 色度样本水平方向与左侧的亮度样本对齐（MPEG-2 / H.264 默认），否则位于两个亮度样本中间
+    pub chroma_left: bool,
+}
+
+impl YuvPlanes {
+    /// 4:2:0 色度平面的尺寸（奇数尺寸向上取整）
+    pub fn chroma_size(width: u32, height: u32) -> (u32, u32) {
+        (width.div_ceil(2), height.div_ceil(2))
+    }
+
+    /// 归一化的 (Y, U, V, 1)（8-bit 值 / 255）到 RGB 的系数，每行对应 R / G / B
+    pub fn rgb_from_yuv(&self) -> [[f32; 4]; 3] {
+        let (kr, kb) = self.matrix.weights();
+        let kg = 1.0 - kr - kb;
+        // y' = Y * y_scale + y_offset，色度同理（色度中心为 128）
+        let (y_scale, y_offset, c_scale, c_offset) = if self.full_range {
+            (1.0, 0.0, 1.0, -128.0 / 255.0)
+        } else {
+            (255.0 / 219.0, -16.0 / 219.0, 255.0 / 224.0, -128.0 / 224.0)
+        };
+        let (cr_r, cb_b) = (2.0 * (1.0 - kr), 2.0 * (1.0 - kb));
+        let (cb_g, cr_g) = (2.0 * kb * (1.0 - kb) / kg, 2.0 * kr * (1.0 - kr) / kg);
+        [
+            [y_scale, 0.0, cr_r * c_scale, y_offset + cr_r * c_offset],
+            [y_scale, -cb_g * c_scale, -cr_g * c_scale, y_offset - (cb_g + cr_g) * c_offset],
+            [y_scale, cb_b * c_scale, 0.0, y_offset + cb_b * c_offset],
+        ]
+    }
+
+    /// 色度纹理坐标 = 画面纹理坐标 * (x, y) + (z, w)：按色度样本的实际位置对齐
+    pub fn chroma_transform(&self, width: u32, height: u32) -> [f32; 4] {
+        let (chroma_width, chroma_height) = Self::chroma_size(width, height);
+        let scale_x = width as f32 / (2 * chroma_width) as f32;
+        let scale_y = height as f32 / (2 * chroma_height) as f32;
+        // 左对齐时色度样本比居中位置偏左半个亮度像素，即采样坐标右移四分之一个色度像素
+        let offset_x = if self.chroma_left { 0.25 / chroma_width as f32 } else { 0.0 };
+        [scale_x, scale_y, offset_x, 0.0]
+    }
+}
+
+impl VideoFrame {
+    /// 转换为 RGBA 帧（已经是 RGBA 时只复制引用）
+    pub fn to_rgba(&self) -> VideoFrame {
+        let Some(planes) = self.planes else {
+            return self.clone();
+        };
+        let (width, height) = (self.width as usize, self.height as usize);
+        let coefficients = planes.rgb_from_yuv();
+        let byte = |index: usize| self.data.get(index).copied().unwrap_or(0) as f32 / 255.0;
+
+        let mut data = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            let luma_row = planes.offsets[0] + y * planes.strides[0];
+            let chroma_row = y / 2 * planes.strides[1];
+            for x in 0..width {
+                let (u, v) = match self.format {
+                    PixelFormat::NV12 => {
+                        let index = planes.offsets[1] + chroma_row + x / 2 * 2;
+                        (byte(index), byte(index + 1))
+                    }
+                    _ => (
+                        byte(planes.offsets[1] + chroma_row + x / 2),
+                        byte(planes.offsets[2] + y / 2 * planes.strides[2] + x / 2),
+                    ),
+                };
+                let yuv = [byte(luma_row + x), u, v, 1.0];
+                for row in &coefficients {
+                    let value: f32 = row.iter().zip(&yuv).map(|(c, v)| c * v).sum();
+                    data.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+                }
+                data.push(255);
+            }
+        }
+        VideoFrame { format: PixelFormat::RGBA, planes: None, data: data.into(), ..self.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn planes(matrix: YuvMatrix, full_range: bool) -> YuvPlanes {
+        YuvPlanes { offsets: [0, 4, 5], strides: [2, 1, 1], matrix, full_range, chroma_left: true }
+    }
+
+    /// 2x2 的 YUV420P 帧（四个亮度样本共用一组色度）
+    fn frame(y: u8, u: u8, v: u8, planes: YuvPlanes) -> VideoFrame {
+        VideoFrame {
+            pts: 0,
+            duration: 0,
+            width: 2,
+            height: 2,
+            format: PixelFormat::YUV420P,
+            planes: Some(planes),
+            data: vec![y, y, y, y, u, v].into(),
+        }
+    }
+
+    #[test]
+    fn test_limited_range_levels() {
+        let planes = planes(YuvMatrix::Bt709, false);
+        assert_eq!(frame(16, 128, 128, planes).to_rgba().data[..4], [0, 0, 0, 255]);
+        assert_eq!(frame(235, 128, 128, planes).to_rgba().data[..4], [255, 255, 255, 255]);
+        // BT.709 纯红：Y=63, Cb=102, Cr=240
+        let red = frame(63, 102, 240, planes).to_rgba();
+        assert!(red.data[0] >= 254 && red.data[1] <= 1 && red.data[2] <= 1, "{:?}", &red.data[..4]);
+        assert_eq!(red.format, PixelFormat::RGBA);
+        assert_eq!(red.data.len(), 16);
+    }
+
+    #[test]
+    fn test_matrix_and_full_range() {
+        // 同一组 YUV 值在 BT.601 与 BT.709 下得到不同的绿色分量
+        let bt601 = frame(128, 90, 200, planes(YuvMatrix::Bt601, true)).to_rgba();
+        let bt709 = frame(128, 90, 200, planes(YuvMatrix::Bt709, true)).to_rgba();
+        assert_ne!(bt601.data[1], bt709.data[1]);
+        assert_eq!(frame(0, 128, 128, planes(YuvMatrix::Bt601, true)).to_rgba().data[..3], [0, 0, 0]);
+        assert_eq!(frame(255, 128, 128, planes(YuvMatrix::Bt601, true)).to_rgba().data[..3], [255, 255, 255]);
+        assert_eq!(YuvMatrix::guess(1920, 1080), YuvMatrix::Bt709);
+        assert_eq!(YuvMatrix::guess(720, 576), YuvMatrix::Bt601);
+    }
+
+    #[test]
+    fn test_chroma_siting() {
+        // 1920 宽：左对齐时向右偏移四分之一个色度像素
+        let mut planes = planes(YuvMatrix::Bt709, false);
+        assert_eq!(planes.chroma_transform(1920, 1080), [1.0, 1.0, 0.25 / 960.0, 0.0]);
+        planes.chroma_left = false;
+        // 奇数宽度：色度平面多出半个像素
+        let [scale_x, scale_y, offset_x, _] = planes.chroma_transform(5, 4);
+        assert_eq!((scale_x, scale_y, offset_x), (5.0 / 6.0, 1.0, 0.0));
+    }
+}
@@ -0,0 +1,815 @@
+// 播放器的完整控制接口（播放器应用使用）
+//
+// mod.rs 中是嵌入用的精简接口；这里按功能分组列出其余的播放控制、状态查询和设置，
+// 均直接转发给 PlaybackManager。关联函数对应所有播放器共享的全局设置（硬件解码、帧尺寸上限等）。
+
+use super::{Player, PlayerEvent};
+use crate::core::{
+    ffi_util, ffmpeg_log, Chapter, FramePoolStats, MediaInfo, MediaSource, PresentedFrameInfo, Result, RuntimeFlags, StreamState,
+    SubtitleFrame, TrackInfo, TrackSource, VideoFrame,
+};
+use crate::player::ab_loop::AbLoopMark;
+use crate::player::clock_skew::AudioClockSkew;
+use crate::player::crash_marker::{self, CrashMarkers};
+use crate::player::debug_commands::{DebugCommand, DEMUX_STALL, MAX_DECODE_DELAY};
+use crate::player::decoder::{self, DecoderStats};
+use crate::player::decoder_fallback::HwFallbackReason;
+use crate::player::demux_end::DemuxEvent;
+use crate::player::first_frame::FirstFrameDiagnosis;
+use crate::player::hover_preview::HoverPreviewState;
+use crate::player::hw_decoder::HWAccelType;
+use crate::player::live::{self, LiveResume, LiveStatus};
+use crate::player::manager::{BufferingPolicy, FileMemory, PlaybackManager};
+use crate::player::packet_inspector::{StreamPackets, PACKETS_PER_STREAM};
+use crate::player::playlist::{Playlist, RepeatMode};
+use crate::player::position_history::PositionHistory;
+use crate::player::preview_cache::PreviewCache;
+use crate::player::seek_filter::SeekMode;
+use crate::player::seek_status::SeekResult;
+use crate::player::stall_watchdog::StallEvent;
+use crate::player::timeshift::TimeshiftLimits;
+use crate::player::{DemuxerCreationResult, DemuxerFactory};
+use crossbeam_channel::{bounded, TryRecvError};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+impl Player {
+    // ==================== 打开与播放控制 ====================
+
+    /// 打开媒体源（文件或网络流；与 [`Player::open`] 相同，但不发送事件，由调用方处理返回值）
+    pub fn open_media_source(&mut self, source: MediaSource) -> Result<MediaInfo> {
+        self.finished = false;
+        self.manager.open_media_source(source)
+    }
+
+    /// 在子线程中打开媒体源（网络流打开耗时较长，不阻塞界面），用 [`Player::poll_open`] 取回结果
+    ///
+    /// 上一次异步打开还没有结果时放弃它，只保留这一次。
+    pub fn open_async(&mut self, source: MediaSource) {
+        // 网络流先预读一段数据再开始播放，本地文件立即就绪
+        let policy = if matches!(source, MediaSource::NetworkStream { .. }) {
+            BufferingPolicy::Prefill
+        } else {
+            BufferingPolicy::Immediate
+        };
+        let (result_tx, result_rx) = bounded(1);
+        DemuxerFactory::create_async(source, result_tx);
+        self.pending_open = Some((result_rx, policy));
+    }
+
+    /// 取回 [`Player::open_async`] 的结果（没有进行中的打开或还没有结果时为 None）
+    ///
+    /// 返回源的地址和打开结果；成功时已切换到新的媒体源，处于暂停状态。
+    pub fn poll_open(&mut self) -> Option<(String, Result<MediaInfo>)> {
+        let (result_rx, policy) = self.pending_open.as_ref()?;
+        let result = match result_rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => {
+                self.pending_open = None;
+                return None;
+            }
+        };
+        let policy = *policy;
+        self.pending_open = None;
+        let (url, opened) = match result {
+            DemuxerCreationResult::Success { demuxer, url } => {
+                self.finished = false;
+                (url, self.manager.attach_demuxer(demuxer, policy))
+            }
+            DemuxerCreationResult::Failed { url, error } => (url, Err(error)),
+        };
+        match &opened {
+            Ok(info) => self.emit(PlayerEvent::Opened(info.clone())),
+            Err(e) => self.emit(PlayerEvent::Error(e.to_string())),
+        }
+        Some((url, opened))
+    }
+
+    /// 当前源是否为单张图像（没有时间轴）
+    pub fn is_still_image(&self) -> bool {
+        self.manager.is_still_image()
+    }
+
+    /// 跳转到指定位置（毫秒，按 mode 选择快速或精确跳转）
+    pub fn seek_with(&mut self, position_ms: i64, mode: SeekMode) {
+        self.finished = false;
+        self.manager.seek(position_ms, mode);
+    }
+
+    /// 跳转到指定位置（秒）
+    pub fn seek_to_seconds(&mut self, position: f64, mode: SeekMode) -> Result<()> {
+        self.finished = false;
+        self.manager.seek_to_seconds(position, mode)
+    }
+
+    /// 跳到上一章 / 下一章的开头，返回跳到的章节索引（没有可跳的章节时不 seek）
+    pub fn seek_chapter(&self, forward: bool) -> Option<usize> {
+        self.manager.seek_chapter(forward)
+    }
+
+    /// 跳到上一个 / 下一个关键帧，返回关键帧的时间（本地文件的关键帧索引在后台建立，完成前返回 None，不 seek）
+    pub fn seek_keyframe(&self, forward: bool) -> Option<i64> {
+        self.manager.seek_keyframe(forward)
+    }
+
+    /// 前进一帧（播放中先暂停）：取帧队列中当前画面之后的下一帧交给界面，时钟对齐到该帧（字幕随之更新）
+    pub fn step_frame_forward(&self) -> bool {
+        self.manager.step_frame_forward()
+    }
+
+    /// 后退一帧（播放中先暂停）：seek 到上一帧之前，解码线程向前解码到上一帧后交给界面。返回是否开始步进
+    pub fn step_frame_backward(&self) -> bool {
+        self.manager.step_frame_backward()
+    }
+
+    /// 挂起（切换到其他会话时调用），返回挂起前是否正在播放
+    pub fn suspend(&mut self) -> bool {
+        self.manager.suspend()
+    }
+
+    /// 恢复挂起的会话（position_ms 为 None 时从挂起位置继续）
+    pub fn resume(&mut self, position_ms: Option<i64>, play: bool) {
+        self.manager.resume(position_ms, play)
+    }
+
+    /// 以当前位置重建播放管线（应用需要重新创建解码器的设置，如硬件解码）；没有打开本地文件时不做任何事
+    pub fn rebuild_pipeline(&mut self) -> Result<()> {
+        self.manager.rebuild_pipeline()
+    }
+
+    /// 只播放音频：视频解码线程不再解码，只丢弃数据包（当前管线有效）
+    pub fn continue_audio_only(&self) {
+        self.manager.continue_audio_only()
+    }
+
+    // ==================== 音频 ====================
+
+    /// 切换静音，返回切换后是否静音（静音期间音量照常记录，取消静音时恢复；切换文件后保持）
+    pub fn toggle_mute(&self) -> bool {
+        self.manager.toggle_mute()
+    }
+
+    /// 设置静音（启动时恢复上次的状态）
+    pub fn set_muted(&self, muted: bool) {
+        self.manager.set_muted(muted)
+    }
+
+    /// 是否静音
+    pub fn is_muted(&self) -> bool {
+        self.manager.is_muted()
+    }
+
+    /// 设置播放速度（0.5x - 2.0x，音频变速不变调，时钟按同一速度推进）
+    pub fn set_speed(&mut self, speed: f32) {
+        self.manager.set_speed(speed)
+    }
+
+    /// 当前播放速度
+    pub fn speed(&self) -> f32 {
+        self.manager.speed()
+    }
+
+    /// 开启/关闭响度均衡
+    pub fn set_loudness_normalization(&mut self, enabled: bool) {
+        self.manager.set_loudness_normalization(enabled)
+    }
+
+    /// 可用的音频输出设备名称
+    pub fn audio_devices() -> Vec<String> {
+        PlaybackManager::audio_devices()
+    }
+
+    /// 把音频输出交给切换到的会话（当前会话挂起后调用，整个应用只保持一个输出设备流）
+    pub fn hand_over_audio_output(&mut self, to: &mut Player) {
+        self.manager.hand_over_audio_output(&mut to.manager)
+    }
+
+    /// 选择音频输出设备（None 跟随系统默认设备），正在播放时立即切换，视频不中断
+    pub fn set_audio_device(&mut self, name: Option<String>) -> Result<()> {
+        self.manager.set_audio_device(name)
+    }
+
+    /// 记住当前文件的音量（重新打开同一文件时恢复）
+    pub fn remember_volume(&mut self, volume: f32) {
+        self.manager.remember_volume(volume)
+    }
+
+    /// 当前文件记忆的音量（没有调整过时返回 None）
+    pub fn remembered_volume(&self) -> Option<f32> {
+        self.manager.remembered_volume()
+    }
+
+    /// 更新音频输出（从队列中取出帧并写入）
+    pub fn update_audio(&mut self) {
+        self.manager.update_audio()
+    }
+
+    // ==================== 取帧与播放状态 ====================
+
+    /// 获取媒体信息
+    pub fn get_media_info(&self) -> Option<MediaInfo> {
+        self.manager.get_media_info()
+    }
+
+    /// 当前媒体的显示标题：容器标题标签优先，其次 .nfo 的 <title>（都没有时为 None，由界面显示文件名）
+    pub fn media_title(&self) -> Option<String> {
+        self.manager.media_title()
+    }
+
+    /// 获取纯音频文件的封面画面
+    pub fn cover_art(&self) -> Option<&VideoFrame> {
+        self.manager.cover_art()
+    }
+
+    /// 获取当前视频帧（简单版本，直接取队列中的第一个）
+    pub fn get_current_frame(&self) -> Option<VideoFrame> {
+        self.manager.get_current_frame()
+    }
+
+    /// 取出不晚于当前时间的最新一帧，中间帧直接合并丢弃（高帧率源使用）
+    pub fn take_newest_frame_until(&self, current_time_ms: i64) -> (Option<VideoFrame>, usize) {
+        self.manager.take_newest_frame_until(current_time_ms)
+    }
+
+    /// 取出暂停状态下 seek 的目标帧（不比较时钟，取出后时钟对齐到该帧 PTS）
+    pub fn take_paused_seek_frame(&self) -> Option<VideoFrame> {
+        self.manager.take_paused_seek_frame()
+    }
+
+    /// 是否在等待暂停状态下 seek 的目标帧（等待期间界面不从帧队列取帧）
+    pub fn is_paused_seek_pending(&self) -> bool {
+        self.manager.is_paused_seek_pending()
+    }
+
+    /// 上报 UI 实际呈现的视频帧（每次更新纹理后调用）
+    pub fn notify_frame_presented(&self, frame: &VideoFrame) {
+        self.manager.notify_frame_presented(frame)
+    }
+
+    /// 获取最近一次呈现的视频帧信息
+    pub fn get_presented_frame(&self) -> Option<PresentedFrameInfo> {
+        self.manager.get_presented_frame()
+    }
+
+    /// 获取媒体总时长（毫秒，未知时为 0）
+    pub fn get_duration_ms(&self) -> i64 {
+        self.manager.get_duration_ms()
+    }
+
+    /// 获取当前播放位置（毫秒，负 PTS 起点按 0 处理；无缝循环时为文件内的位置）
+    pub fn get_position_ms(&self) -> i64 {
+        self.manager.get_position_ms()
+    }
+
+    /// 播放时钟（内部时间轴，无缝循环时单调递增，用于选择视频帧和内嵌字幕）
+    pub fn get_clock_ms(&self) -> i64 {
+        self.manager.get_clock_ms()
+    }
+
+    /// 检查是否正在播放
+    pub fn is_playing(&self) -> bool {
+        self.manager.is_playing()
+    }
+
+    /// 是否没有打开中或播放中的媒体（未打开、已停止或打开失败）
+    pub fn is_idle(&self) -> bool {
+        self.manager.is_idle()
+    }
+
+    /// 当前管线是否已改为只播放音频
+    pub fn is_audio_only(&self) -> bool {
+        self.manager.is_audio_only()
+    }
+
+    /// 轨道切换是否仍在进行（新画面尚未呈现）
+    pub fn is_switching_track(&self) -> bool {
+        self.manager.is_switching_track()
+    }
+
+    /// 是否已播放完毕（解封装线程报告读到末尾且帧队列已耗尽；读取出错不算播放完毕）
+    pub fn is_source_exhausted(&self) -> bool {
+        self.manager.is_source_exhausted()
+    }
+
+    /// 播放中的源已播放完毕时进入 Ended 状态：时钟暂停在时长处（每帧调用，返回是否刚进入）
+    pub fn update_ended(&self) -> bool {
+        self.manager.update_ended()
+    }
+
+    /// 处理解封装线程报告的结束原因（每帧调用），返回需要界面处理的事件
+    pub fn poll_demux_end(&mut self) -> Option<DemuxEvent> {
+        self.manager.poll_demux_end()
+    }
+
+    /// 获取网络流状态（供 UI 使用）
+    pub fn get_stream_state(&self) -> Option<StreamState> {
+        self.manager.get_stream_state()
+    }
+
+    /// 检查是否正在播放网络流
+    pub fn is_network_stream(&self) -> bool {
+        self.manager.is_network_stream()
+    }
+
+    /// 网络流已缓冲到的位置（毫秒，播放时钟时间轴；本地文件、尚未读包或缓冲已耗尽时为 None）
+    pub fn get_buffered_position_ms(&self) -> Option<i64> {
+        self.manager.get_buffered_position_ms()
+    }
+
+    /// 解封装线程当前的预读量（毫秒；没有在播放或尚未读包时为 None）
+    pub fn read_ahead_ms(&self) -> Option<i64> {
+        self.manager.read_ahead_ms()
+    }
+
+    /// 最近一次 seek 的结果（进行中、成功、失败或不支持；尚未 seek 时为 None）
+    pub fn get_last_seek_result(&self) -> Option<SeekResult> {
+        self.manager.get_last_seek_result()
+    }
+
+    /// 取出尚未提示过的 seek 失败（每次失败只返回一次）
+    pub fn poll_seek_failure(&self) -> Option<SeekResult> {
+        self.manager.poll_seek_failure()
+    }
+
+    /// 视频轨道是否刚被判定为损坏（连续的帧尺寸无效，视频已停止解码）；读取后清除
+    pub fn take_video_corrupt_notice(&self) -> bool {
+        self.manager.take_video_corrupt_notice()
+    }
+
+    /// 音频解码器是否刚被判定失效（已切换为无声播放）；读取后清除
+    pub fn take_audio_failure_notice(&self) -> bool {
+        self.manager.take_audio_failure_notice()
+    }
+
+    /// 首帧检测：视频流一直没有解码出帧时返回原因（缓冲、暂停时不计时；每个管线最多报告一次）
+    pub fn poll_first_frame(&self) -> Option<FirstFrameDiagnosis> {
+        self.manager.poll_first_frame()
+    }
+
+    /// 刚发生的硬解回退（界面显示屏幕提示）；读取后清除
+    pub fn poll_hw_fallback(&self) -> Option<HwFallbackReason> {
+        self.manager.poll_hw_fallback()
+    }
+
+    /// 当前管线改用软件解码的原因（信息面板显示；使用硬件解码时为 None）
+    pub fn hw_fallback_reason(&self) -> Option<HwFallbackReason> {
+        self.manager.hw_fallback_reason()
+    }
+
+    /// 解封装停滞 / 恢复（界面每帧调用）
+    pub fn poll_stall(&self) -> Option<StallEvent> {
+        self.manager.poll_stall()
+    }
+
+    // ==================== 打开文件时的设置 ====================
+
+    /// 设置运行时开关（下次打开文件时生效）
+    pub fn set_runtime_flags(&mut self, flags: RuntimeFlags) {
+        self.manager.set_runtime_flags(flags)
+    }
+
+    /// 设置是否在字幕关闭时自动选择强制字幕（下次打开文件时生效）
+    pub fn set_auto_forced_subtitles(&mut self, enabled: bool) {
+        self.manager.set_auto_forced_subtitles(enabled)
+    }
+
+    /// 设置没有轨道记忆的文件是否默认显示字幕（下次打开文件时生效）
+    pub fn set_subtitles_by_default(&mut self, enabled: bool) {
+        self.manager.set_subtitles_by_default(enabled)
+    }
+
+    /// 设置打开新文件时的字幕延迟（毫秒，超出范围时夹紧）
+    pub fn set_default_subtitle_offset_ms(&mut self, offset_ms: i64) {
+        self.manager.set_default_subtitle_offset_ms(offset_ms)
+    }
+
+    /// 开启/关闭读取 .nfo 侧车文件中的标题
+    pub fn set_read_sidecar_titles(&mut self, enabled: bool) {
+        self.manager.set_read_sidecar_titles(enabled)
+    }
+
+    /// 设置是否忽略播放历史中的硬解失败记录（下次创建解码器时生效）
+    pub fn set_hw_decode_always_retry(&mut self, enabled: bool) {
+        self.manager.set_hw_decode_always_retry(enabled)
+    }
+
+    /// 设置首帧期限（正在播放的时间超过期限仍没有视频帧时报告）
+    pub fn set_first_frame_deadline(&self, deadline: Duration) {
+        self.manager.set_first_frame_deadline(deadline)
+    }
+
+    /// 开启/关闭硬件解码（所有播放器共享，对之后创建的解码器生效）
+    pub fn set_hw_decode_enabled(enabled: bool) {
+        decoder::set_hw_decode_enabled(enabled);
+    }
+
+    /// 开启/关闭 YUV 输出（所有播放器共享；渲染器能在着色器中转换颜色时开启）
+    pub fn set_yuv_output_enabled(enabled: bool) {
+        decoder::set_yuv_output_enabled(enabled);
+    }
+
+    /// 修改帧尺寸上限（所有播放器共享，每个方向，不低于 [`MIN_FRAME_DIMENSION`](crate::MIN_FRAME_DIMENSION)）
+    pub fn set_max_frame_dimension(max: u32) {
+        decoder::set_max_frame_dimension(max);
+    }
+
+    // ==================== 章节、轨道与字幕 ====================
+
+    /// 获取章节列表（按开始时间排序）
+    pub fn get_chapters(&self) -> &[Chapter] {
+        self.manager.get_chapters()
+    }
+
+    /// 获取音频轨道列表
+    pub fn get_audio_tracks(&self) -> &[TrackInfo] {
+        self.manager.get_audio_tracks()
+    }
+
+    /// 获取字幕轨道列表（内嵌字幕在前，外部字幕文件在后）
+    pub fn get_subtitle_tracks(&self) -> &[TrackInfo] {
+        self.manager.get_subtitle_tracks()
+    }
+
+    /// 当前音频流索引
+    pub fn current_audio_stream(&self) -> Option<usize> {
+        self.manager.current_audio_stream()
+    }
+
+    /// 当前选择的字幕（None 表示关闭）
+    pub fn current_subtitle_track(&self) -> Option<&TrackSource> {
+        self.manager.current_subtitle_track()
+    }
+
+    /// 当前音轨之后的下一条音轨（循环查找；没有其他音轨时为 None）
+    pub fn next_audio_track(&self) -> Option<usize> {
+        self.manager.next_audio_track()
+    }
+
+    /// 选择音频轨道（需要重建播放管线）
+    pub fn select_audio_track(&mut self, stream_index: usize) -> Result<()> {
+        self.manager.select_audio_track(stream_index)
+    }
+
+    /// 选择字幕轨道（None 表示关闭字幕）
+    pub fn select_subtitle_track(&mut self, track: Option<TrackSource>) -> Result<()> {
+        self.manager.select_subtitle_track(track)
+    }
+
+    /// 加载用户指定的外部字幕文件（如拖入的 .srt）并切换到该字幕
+    pub fn add_external_subtitle(&mut self, subtitle_file: &Path) -> Result<()> {
+        self.manager.add_external_subtitle(subtitle_file)
+    }
+
+    /// 设置字幕延迟（毫秒，正值表示字幕推后显示，负值提前；超出范围时夹紧）
+    pub fn set_subtitle_offset_ms(&mut self, offset_ms: i64) {
+        self.manager.set_subtitle_offset_ms(offset_ms)
+    }
+
+    /// 当前字幕延迟（毫秒）
+    pub fn subtitle_offset_ms(&self) -> i64 {
+        self.manager.subtitle_offset_ms()
+    }
+
+    /// 获取当前时间所有活动的字幕（重叠字幕同时显示，按开始时间排序，最早的在前）
+    pub fn get_current_subtitles(&self, current_time_ms: i64) -> Vec<SubtitleFrame> {
+        self.manager.get_current_subtitles(current_time_ms)
+    }
+
+    /// 待显示的内嵌字幕数
+    pub fn subtitle_cue_count(&self) -> usize {
+        self.manager.subtitle_cue_count()
+    }
+
+    // ==================== 循环与播放列表 ====================
+
+    /// 开启/关闭单曲循环
+    pub fn set_repeat_one(&self, enabled: bool) {
+        self.manager.set_repeat_one(enabled)
+    }
+
+    /// 是否开启了单曲循环
+    pub fn repeat_one(&self) -> bool {
+        self.manager.repeat_one()
+    }
+
+    /// 设置无缝循环的时长上限（更长的本地文件和网络流循环时按普通 seek 回到开头）
+    pub fn set_seamless_loop_limit_ms(&self, limit_ms: i64) {
+        self.manager.set_seamless_loop_limit_ms(limit_ms)
+    }
+
+    /// 已完成的循环遍数（按播放时钟计算）
+    pub fn loop_count(&self) -> u64 {
+        self.manager.loop_count()
+    }
+
+    /// 内部时间轴上的时间戳折回到文件内的位置（用于显示已呈现帧的位置）
+    pub fn loop_position(&self, pts_ms: i64) -> i64 {
+        self.manager.loop_position(pts_ms)
+    }
+
+    /// 非无缝循环：文件已读完且帧队列已播完时 seek 回开头（返回是否执行了 seek）
+    pub fn restart_loop_if_due(&self) -> bool {
+        self.manager.restart_loop_if_due()
+    }
+
+    /// 在当前位置标记 A 点（A/B 循环生效时清除循环）
+    pub fn mark_ab_loop_a(&mut self) -> AbLoopMark {
+        self.manager.mark_ab_loop_a()
+    }
+
+    /// 在当前位置标记 B 点（A/B 循环生效时清除循环；B 点必须在 A 点之后）
+    pub fn mark_ab_loop_b(&mut self) -> Result<AbLoopMark> {
+        self.manager.mark_ab_loop_b()
+    }
+
+    /// 设置或清除 A/B 循环范围（毫秒，B 必须在 A 之后）
+    pub fn set_loop_range(&mut self, range: Option<(i64, i64)>) -> Result<()> {
+        self.manager.set_loop_range(range)
+    }
+
+    /// 生效中的 A/B 循环范围
+    pub fn loop_range(&self) -> Option<(i64, i64)> {
+        self.manager.loop_range()
+    }
+
+    /// 已标记、等待标记 B 点的 A 点
+    pub fn ab_loop_pending_a(&self) -> Option<i64> {
+        self.manager.ab_loop_pending_a()
+    }
+
+    /// 播放越过 B 点时 seek 回 A 点（每帧调用，返回是否执行了 seek）
+    pub fn update_ab_loop(&mut self) -> bool {
+        self.manager.update_ab_loop()
+    }
+
+    /// 设置播放列表（替换原有条目；当前打开的文件在列表中时从它继续）
+    pub fn set_playlist(&mut self, items: Vec<String>) {
+        self.manager.set_playlist(items)
+    }
+
+    /// 追加到播放列表末尾
+    pub fn append_to_playlist(&mut self, item: String) {
+        self.manager.append_to_playlist(item)
+    }
+
+    /// 播放列表
+    pub fn playlist(&self) -> &Playlist {
+        self.manager.playlist()
+    }
+
+    /// 播放到列表末尾后停止或回到第一项
+    pub fn set_playlist_repeat(&mut self, repeat: RepeatMode) {
+        self.manager.set_playlist_repeat(repeat)
+    }
+
+    /// 移动到播放列表的下一项并返回其路径（由调用方打开；末尾且不循环时为 None）
+    pub fn next_playlist_item(&mut self) -> Option<String> {
+        self.manager.next_playlist_item()
+    }
+
+    /// 移动到播放列表的上一项并返回其路径（由调用方打开；第一项且不循环时为 None）
+    pub fn previous_playlist_item(&mut self) -> Option<String> {
+        self.manager.previous_playlist_item()
+    }
+
+    /// 打开并播放下一项（末尾且不循环时停止播放，返回 false）
+    pub fn play_next(&mut self) -> Result<bool> {
+        self.finished = false;
+        self.manager.play_next()
+    }
+
+    /// 打开并播放上一项（第一项且不循环时回到开头，返回 false）
+    pub fn play_previous(&mut self) -> Result<bool> {
+        self.finished = false;
+        self.manager.play_previous()
+    }
+
+    /// 当前项播放完毕且列表中还有下一项（单曲循环时不自动前进）
+    pub fn playlist_advance_due(&self) -> bool {
+        self.manager.playlist_advance_due()
+    }
+
+    /// 当前项接近结尾时在后台预读播放列表的下一项（每帧调用；下一项变化或离开结尾附近时取消预读）
+    pub fn update_playlist_prefetch(&mut self) {
+        self.manager.update_playlist_prefetch()
+    }
+
+    // ==================== 直播 ====================
+
+    /// 是否为直播流
+    pub fn is_live(&self) -> bool {
+        self.manager.is_live()
+    }
+
+    /// 直播状态（直播边缘、可回看窗口；非直播源为 None）
+    pub fn live_status(&self) -> Option<LiveStatus> {
+        self.manager.live_status()
+    }
+
+    /// 直播暂停 paused_for 后继续播放的方式（有可回看窗口时原地继续，否则暂停超过缓冲时长后回到直播）
+    pub fn live_resume_action(&self, paused_for: Duration) -> LiveResume {
+        live::resume_action(paused_for, self.manager.live_status().is_some_and(|status| status.window.is_some()))
+    }
+
+    /// 在可回看窗口内跳到直播边缘（没有窗口时返回 false，需要重新连接才能回到直播）
+    pub fn jump_to_live(&self) -> bool {
+        self.manager.jump_to_live()
+    }
+
+    /// 设置直播时移（None 关闭）；下次打开直播时生效
+    pub fn set_timeshift(&mut self, limits: Option<TimeshiftLimits>) {
+        self.manager.set_timeshift(limits)
+    }
+
+    /// 直播时移设置（None 未开启）
+    pub fn timeshift(&self) -> Option<TimeshiftLimits> {
+        self.manager.timeshift()
+    }
+
+    /// 当前直播是否在时移模式（暂停期间继续录制，可在录制范围内回看）
+    pub fn is_timeshifting(&self) -> bool {
+        self.manager.is_timeshifting()
+    }
+
+    // ==================== 播放记录与预览 ====================
+
+    /// 获取按文件记忆的轨道选择和音量（导出配置使用）
+    pub fn file_memory(&self) -> &HashMap<String, FileMemory> {
+        self.manager.file_memory()
+    }
+
+    /// 替换按文件记忆的轨道选择和音量（导入配置使用）
+    pub fn set_file_memory(&mut self, memory: HashMap<String, FileMemory>) {
+        self.manager.set_file_memory(memory)
+    }
+
+    /// 启用播放位置记录
+    pub fn enable_position_history(&mut self, history: Arc<PositionHistory>) {
+        self.manager.enable_position_history(history)
+    }
+
+    /// 共享的播放位置记录（新建会话时复用同一个写盘线程）
+    pub fn position_history(&self) -> Option<Arc<PositionHistory>> {
+        self.manager.position_history()
+    }
+
+    /// 关闭播放位置记录（写入未保存的位置，最后一个共享者关闭时等待写盘完成，退出时调用）
+    pub fn close_position_history(&mut self) {
+        self.manager.close_position_history()
+    }
+
+    /// 播放中定期记录位置（每帧调用，按 CHECKPOINT_INTERVAL 节流，写盘由后台线程负责）
+    pub fn checkpoint_position(&mut self) {
+        self.manager.checkpoint_position()
+    }
+
+    /// 播放超过时长的 90% 或播放结束时把当前文件标记为已看完（每帧调用；直播流和网络流不标记）
+    pub fn update_watched(&mut self) {
+        self.manager.update_watched()
+    }
+
+    /// 当前文件上次记录的位置（距开头或结尾过近时返回 None）
+    pub fn resume_position(&self) -> Option<i64> {
+        self.manager.resume_position()
+    }
+
+    /// 设置预览缓存（与胶片视图共享）
+    pub fn set_preview_cache(&mut self, cache: Arc<PreviewCache>) {
+        self.manager.set_preview_cache(cache)
+    }
+
+    /// 预览缓存（新建标签页时共享）
+    pub fn preview_cache(&self) -> Arc<PreviewCache> {
+        self.manager.preview_cache()
+    }
+
+    /// 进度条悬停在 time_ms 处的预览（只支持本地视频文件，第一次悬停时启动预览线程）
+    pub fn hover_preview(&self, time_ms: i64) -> HoverPreviewState {
+        self.manager.hover_preview(time_ms)
+    }
+
+    /// 鼠标离开进度条
+    pub fn hover_preview_leave(&self) {
+        self.manager.hover_preview_leave()
+    }
+
+    /// 安装解码崩溃记录：解码线程 panic 时把正在解码的文件记入 markers（启动时调用一次）
+    pub fn install_crash_hook(markers: CrashMarkers) {
+        crash_marker::install_panic_hook(markers);
+    }
+
+    /// 取出最近一次解码线程崩溃的文件
+    pub fn take_decode_crash() -> Option<PathBuf> {
+        crash_marker::take_decode_crash()
+    }
+
+    // ==================== 开发者工具 ====================
+
+    /// 开发者面板模拟解封装停滞的时长
+    pub const DEBUG_DEMUX_STALL: Duration = DEMUX_STALL;
+
+    /// 逐帧解码延迟的上限
+    pub const MAX_DEBUG_DECODE_DELAY: Duration = MAX_DECODE_DELAY;
+
+    /// 数据包记录中每个流保留的最近包数
+    pub const PACKETS_PER_STREAM: usize = PACKETS_PER_STREAM;
+
+    /// 开启/关闭开发者工具（数据包记录和调试命令；关闭时解封装线程不做任何记录，各线程不读取命令通道）
+    pub fn set_developer_tools_enabled(&self, enabled: bool) {
+        self.manager.packet_inspector().set_enabled(enabled);
+        self.manager.set_debug_commands_enabled(enabled);
+    }
+
+    /// 开发者工具是否已开启
+    pub fn developer_tools_enabled(&self) -> bool {
+        self.manager.packet_inspector().is_enabled()
+    }
+
+    /// 每个流最近的数据包记录（按流索引排序）
+    pub fn packet_snapshot(&self) -> Vec<StreamPackets> {
+        self.manager.packet_inspector().snapshot()
+    }
+
+    /// 清空数据包记录
+    pub fn clear_packet_records(&self) {
+        self.manager.packet_inspector().clear()
+    }
+
+    /// 调试：清空视频帧队列（前后的队列深度和时钟记录在日志中，下同）
+    pub fn debug_flush_video_queue(&self) {
+        self.manager.debug_command(DebugCommand::FlushVideoQueue)
+    }
+
+    /// 调试：清空音频帧队列
+    pub fn debug_flush_audio_queue(&self) {
+        self.manager.debug_command(DebugCommand::FlushAudioQueue)
+    }
+
+    /// 调试：强制 flush 视频和音频解码器
+    pub fn debug_flush_decoders(&self) {
+        self.manager.debug_command(DebugCommand::FlushDecoders)
+    }
+
+    /// 调试：播放时钟跳变（毫秒，正数向前）
+    pub fn debug_clock_jump(&self, delta_ms: i64) {
+        self.manager.debug_command(DebugCommand::ClockJump(delta_ms))
+    }
+
+    /// 调试：解封装线程停止读包 [`Player::DEBUG_DEMUX_STALL`]（验证停滞检测和恢复）
+    pub fn debug_stall_demuxer(&self) {
+        self.manager.debug_command(DebugCommand::StallDemuxer(DEMUX_STALL))
+    }
+
+    /// 调试：解码线程每解码一个包额外等待的时间（0 为关闭，不超过 [`Player::MAX_DEBUG_DECODE_DELAY`]）
+    pub fn set_debug_decode_delay(&self, delay: Duration) {
+        self.manager.debug_command(DebugCommand::DecodeDelay(delay.min(MAX_DECODE_DELAY)))
+    }
+
+    /// 当前逐帧解码延迟
+    pub fn debug_decode_delay(&self) -> Duration {
+        self.manager.debug_decode_delay()
+    }
+
+    /// 最近一次测量的音画偏差（毫秒，正值表示画面领先声音；没有在播放的音频时为 None）
+    pub fn av_offset_ms(&self) -> Option<i64> {
+        self.manager.av_offset_ms()
+    }
+
+    /// 帧缓冲区分配统计（累计）
+    pub fn frame_pool_stats(&self) -> FramePoolStats {
+        self.manager.frame_pool_stats()
+    }
+
+    /// 音频设备时钟偏差（没有音频输出时为 None）
+    pub fn audio_clock_skew(&self) -> Option<AudioClockSkew> {
+        self.manager.audio_clock_skew()
+    }
+
+    /// 当前管线硬件 / 软件解码出的视频帧数（信息面板显示）
+    pub fn decoder_stats(&self) -> DecoderStats {
+        self.manager.decoder_stats()
+    }
+
+    /// 视频帧乱序校正次数（当前管线）
+    pub fn reorder_corrections(&self) -> u64 {
+        self.manager.reorder_corrections()
+    }
+
+    /// 优先使用的硬件解码方式（如 "D3D11VA"；系统不支持硬件解码时为 None，启动自检显示）
+    pub fn preferred_hw_decoder() -> Option<&'static str> {
+        HWAccelType::detect_available().into_iter().find(|hw| *hw != HWAccelType::None).map(|hw| hw.name())
+    }
+
+    /// FFmpeg 的版本字符串（如 "6.1.1-full_build-www.gyan.dev"；取不到时为空）
+    pub fn ffmpeg_version() -> String {
+        ffi_util::ffmpeg_version()
+    }
+
+    /// 最近一分钟内 FFmpeg 发出的警告和错误数（所有播放器共享）
+    pub fn ffmpeg_warnings_last_minute() -> u32 {
+        ffmpeg_log::warnings_last_minute()
+    }
+}
@@ -0,0 +1,321 @@
+// 播放门面（供其他应用嵌入播放核心）
+//
+// Player 包装 PlaybackManager，嵌入时只需要打开、播放控制、取帧和事件这几类稳定接口：
+// - 命令通道：任意线程发送 PlayerCommand，在 update() 中按顺序执行
+// - 事件通道：update() 把状态变化、播放完毕、读取失败等报告为 PlayerEvent
+// - 取帧：frame_at() 按播放时钟取出应显示的帧（与播放器界面相同的取帧规则），
+//   next_frame() 按解码顺序取帧（无界面处理、测试等场景）
+// 宿主应用每帧（或定期）调用 update()，音频输出和网络重连都在其中完成。
+// 播放器应用需要的其余控制（轨道、字幕、循环、播放列表、播放记录、开发者工具等）在 controls.rs 中。
+
+use crate::core::{ffmpeg_log, MediaInfo, MediaSource, PixelFormat, PlaybackState, Result, VideoFrame};
+use crate::player::demux_end::DemuxEvent;
+use crate::player::manager::{BufferingPolicy, PlaybackManager};
+use crate::player::seek_filter::SeekMode;
+use crate::player::DemuxerCreationResult;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::{error, info, warn};
+
+mod controls;
+
+/// 播放命令（通过 [`Player::commands`] 的发送端从任意线程发送）
+#[derive(Debug, Clone)]
+pub enum PlayerCommand {
+    /// 打开媒体源（打开后处于暂停状态）
+    Open(MediaSource),
+    /// 开始或继续播放
+    Play,
+    /// 暂停
+    Pause,
+    /// 跳转到指定位置（毫秒）
+    Seek(i64),
+    /// 设置音量（0.0 为静音，1.0 为原始音量）
+    SetVolume(f32),
+    /// 停止播放
+    Stop,
+}
+
+/// 播放事件（通过 [`Player::events`] 的接收端读取）
+#[derive(Debug, Clone)]
+pub enum PlayerEvent {
+    /// 媒体源已打开
+    Opened(MediaInfo),
+    /// 播放状态变化
+    StateChanged(PlaybackState),
+    /// 网络中断，正在重新连接（第 attempt 次）
+    Reconnecting {
+        /// 短时间内的第几次重连
+        attempt: u32,
+    },
+    /// 播放完毕（数据已读完且帧队列已耗尽）
+    Finished,
+    /// 打开失败或播放中断（错误信息）
+    Error(String),
+}
+
+/// 一帧视频画面（与渲染方式无关，宿主应用自行上传到纹理或保存）
+#[derive(Debug, Clone)]
+pub struct VideoFrameView {
+    frame: VideoFrame,
+}
+
+impl VideoFrameView {
+    /// 显示时间戳（毫秒）
+    pub fn pts_ms(&self) -> i64 {
+        self.frame.pts
+    }
+
+    /// 帧持续时间（毫秒，未知时为 0）
+    pub fn duration_ms(&self) -> i64 {
+        self.frame.duration
+    }
+
+    /// 画面宽度（像素）
+    pub fn width(&self) -> u32 {
+        self.frame.width
+    }
+
+    /// 画面高度（像素）
+    pub fn height(&self) -> u32 {
+        self.frame.height
+    }
+
+    /// 像素格式（解码器输出 RGBA）
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.frame.format
+    }
+
+    /// 像素数据（逐行紧密排列，没有行尾填充）
+    pub fn data(&self) -> &[u8] {
+        &self.frame.data
+    }
+
+    /// 取出内部的视频帧（交给本库的渲染器时使用）
+    pub fn into_frame(self) -> VideoFrame {
+        self.frame
+    }
+}
+
+impl From<VideoFrame> for VideoFrameView {
+    fn from(frame: VideoFrame) -> Self {
+        Self { frame }
+    }
+}
+
+/// 播放器（播放核心的稳定接口）
+pub struct Player {
+    manager: PlaybackManager,
+    command_tx: Sender<PlayerCommand>,
+    command_rx: Receiver<PlayerCommand>,
+    event_tx: Sender<PlayerEvent>,
+    event_rx: Receiver<PlayerEvent>,
+    last_state: PlaybackState,
+    finished: bool,
+    pending_open: Option<(Receiver<DemuxerCreationResult>, BufferingPolicy)>,  // 进行中的异步打开和起播缓冲方式
+}
+
+impl Player {
+    /// 初始化 FFmpeg 并把 FFmpeg 自己的警告转入日志（应用启动时调用一次；只用 [`Player::new`] 时可以不调用）
+    pub fn init() -> Result<()> {
+        ffmpeg_next::init()?;
+        ffmpeg_log::install();
+        Ok(())
+    }
+
+    /// 创建播放器（初始化 FFmpeg，可重复调用）
+    pub fn new() -> Result<Self> {
+        ffmpeg_next::init()?;
+        let (command_tx, command_rx) = unbounded();
+        let (event_tx, event_rx) = unbounded();
+        Ok(Self {
+            manager: PlaybackManager::new(),
+            command_tx,
+            command_rx,
+            event_tx,
+            event_rx,
+            last_state: PlaybackState::Idle,
+            finished: false,
+            pending_open: None,
+        })
+    }
+
+    /// 命令发送端（可克隆后交给其他线程）
+    pub fn commands(&self) -> Sender<PlayerCommand> {
+        self.command_tx.clone()
+    }
+
+    /// 事件接收端（多个接收端之间竞争读取同一事件）
+    pub fn events(&self) -> Receiver<PlayerEvent> {
+        self.event_rx.clone()
+    }
+
+    /// 打开媒体源（打开后处于暂停状态，调用 [`Player::play`] 开始播放）
+    pub fn open(&mut self, source: MediaSource) -> Result<MediaInfo> {
+        self.finished = false;
+        match self.manager.open_media_source(source) {
+            Ok(info) => {
+                self.emit(PlayerEvent::Opened(info.clone()));
+                Ok(info)
+            }
+            Err(e) => {
+                self.emit(PlayerEvent::Error(e.to_string()));
+                Err(e)
+            }
+        }
+    }
+
+    /// 开始或继续播放
+    pub fn play(&mut self) -> Result<()> {
+        self.manager.play()
+    }
+
+    /// 暂停（暂停期间跳转会立即送出目标位置的帧）
+    pub fn pause(&self) {
+        self.manager.pause();
+    }
+
+    /// 跳转到指定位置（毫秒）
+    pub fn seek(&mut self, position_ms: i64) {
+        self.seek_with(position_ms, SeekMode::Accurate);
+    }
+
+    /// 设置音量（0.0 为静音，1.0 为原始音量）
+    pub fn set_volume(&self, volume: f32) {
+        self.manager.set_volume(volume);
+    }
+
+    /// 停止播放并释放解码线程
+    pub fn stop(&mut self) {
+        self.manager.stop();
+    }
+
+    /// 当前播放状态
+    pub fn state(&self) -> PlaybackState {
+        self.manager.get_state().state
+    }
+
+    /// 当前媒体信息（未打开时为 None）
+    pub fn media_info(&self) -> Option<MediaInfo> {
+        self.manager.get_media_info()
+    }
+
+    /// 播放位置（毫秒，单曲循环时为当前一遍内的位置）
+    pub fn position_ms(&self) -> i64 {
+        self.manager.get_position_ms()
+    }
+
+    /// 播放时钟（毫秒，传给 [`Player::frame_at`] 选择应显示的帧）
+    pub fn clock_ms(&self) -> i64 {
+        self.manager.get_clock_ms()
+    }
+
+    /// 是否已播放完毕
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// 执行命令、更新音频输出并报告事件（宿主应用每帧调用）
+    pub fn update(&mut self) {
+        while let Ok(command) = self.command_rx.try_recv() {
+            self.execute(command);
+        }
+
+        self.manager.update_audio();
+
+        if let Some(event) = self.manager.poll_demux_end() {
+            self.handle_demux_event(event);
+        }
+        self.manager.update_ended();
+        if !self.finished && self.manager.is_source_exhausted() {
+            self.finished = true;
+            self.emit(PlayerEvent::Finished);
+        }
+
+        let state = self.state();
+        if state != self.last_state {
+            self.last_state = state;
+            self.emit(PlayerEvent::StateChanged(state));
+        }
+    }
+
+    /// 取出时钟 clock_ms 时应显示的帧（没有新帧时为 None，继续显示上一帧）
+    ///
+    /// 暂停状态下跳转后立即返回目标位置的帧；播放中返回不晚于时钟的最新帧，过期的中间帧直接丢弃。
+    pub fn frame_at(&self, clock_ms: i64) -> Option<VideoFrameView> {
+        if let Some(frame) = self.manager.take_paused_seek_frame() {
+            return Some(frame.into());
+        }
+        if self.manager.is_paused_seek_pending() {
+            return None;
+        }
+        self.manager.take_newest_frame_until(clock_ms).0.map(VideoFrameView::from)
+    }
+
+    /// 按解码顺序取出下一帧（不按时钟同步）
+    pub fn next_frame(&self) -> Option<VideoFrameView> {
+        self.manager
+            .take_paused_seek_frame()
+            .or_else(|| self.manager.get_current_frame())
+            .map(VideoFrameView::from)
+    }
+
+    /// 上报实际显示的帧（宿主应用显示后调用，用于进度跟随画面和会话挂起）
+    pub fn notify_presented(&self, frame: &VideoFrameView) {
+        self.manager.notify_frame_presented(&frame.frame);
+    }
+
+    fn execute(&mut self, command: PlayerCommand) {
+        let result = match command {
+            PlayerCommand::Open(source) => self.open(source).map(|_| ()),
+            PlayerCommand::Play => self.play(),
+            PlayerCommand::Pause => {
+                self.pause();
+                Ok(())
+            }
+            PlayerCommand::Seek(position_ms) => {
+                self.seek(position_ms);
+                Ok(())
+            }
+            PlayerCommand::SetVolume(volume) => {
+                self.set_volume(volume);
+                Ok(())
+            }
+            PlayerCommand::Stop => {
+                self.stop();
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            error!("❌ 播放命令执行失败: {}", e);
+        }
+    }
+
+    /// 解封装结束：网络中断时从中断位置重新打开流，其余情况报告给宿主应用
+    fn handle_demux_event(&mut self, event: DemuxEvent) {
+        match event {
+            DemuxEvent::Finished => {}
+            DemuxEvent::Reconnect { url, position_ms, attempt } => {
+                self.emit(PlayerEvent::Reconnecting { attempt });
+                let reopened = MediaSource::from_url(&url)
+                    .map_err(|e| e.to_string())
+                    .and_then(|source| self.manager.open_media_source(source).map_err(|e| e.to_string()));
+                match reopened {
+                    Ok(_) => {
+                        info!("📡 重新连接成功，从 {}ms 继续播放", position_ms);
+                        self.manager.seek(position_ms, SeekMode::Accurate);
+                        if let Err(e) = self.manager.play() {
+                            warn!("重新连接后播放失败: {}", e);
+                        }
+                    }
+                    Err(message) => self.emit(PlayerEvent::Error(message)),
+                }
+            }
+            DemuxEvent::Failed(message) => self.emit(PlayerEvent::Error(message)),
+        }
+    }
+
+    fn emit(&self, event: PlayerEvent) {
+        // 接收端与发送端同在 Player 内，发送不会失败
+        let _ = self.event_tx.send(event);
+    }
+}
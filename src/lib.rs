@@ -0,0 +1,71 @@
+// 播放核心库（解封装、解码、音画同步）
+//
+// 对外只通过 Player（播放门面）使用播放核心：嵌入到其他应用时通过命令 / 事件通道控制播放，按时钟取出
+// VideoFrameView 自行渲染；本项目的播放器应用同样只使用 Player 的方法和下面导出的类型，
+// 视频渲染、播放列表文件、后台任务登记等界面功能在应用（main.rs）中。
+// core / player 模块是库内部实现（pub(crate)），应用需要的每一项能力都由 Player 的方法提供，
+// 这里只导出方法的参数和返回值用到的类型，以及构造媒体源和设置取值范围用到的函数和常量。
+//
+// 所有 unsafe 块都必须写明成立条件（// SAFETY:），FFI 访问集中在 core::ffi_util
+//! 播放核心库：通过 Player 打开媒体、控制播放，按时钟取出视频帧
+
+#![deny(missing_docs)]
+#![warn(clippy::undocumented_unsafe_blocks)]
+
+pub(crate) mod core;
+pub(crate) mod player;
+mod facade;
+#[cfg(test)]
+mod test_support;
+
+pub use crate::core::{MediaInfo, MediaSource, PlaybackState, PlayerError, Result};
+pub use facade::{Player, PlayerCommand, PlayerEvent, VideoFrameView};
+
+// 媒体、轨道与帧
+pub use crate::core::{
+    chapter_at, Chapter, ErrorCategory, FrameData, FramePoolStats, HdrCompatibility, PixelFormat, PresentedFrameInfo,
+    RuntimeFlags, StreamMeta, StreamProtocol, StreamState, SubtitleFrame, TrackInfo, TrackSource, VideoFrame, YuvMatrix,
+    YuvPlanes, MAX_VOLUME,
+};
+// 可打开的文件类型与图像序列
+pub use crate::core::{
+    find_sequence_in_folder, infer_sequence, is_subtitle_file, is_supported_image_file, is_supported_video_file,
+    SequencePattern, DEFAULT_SEQUENCE_FPS, SUPPORTED_IMAGE_EXTENSIONS, SUPPORTED_VIDEO_EXTENSIONS,
+};
+
+// 播放控制与状态
+pub use crate::player::ab_loop::AbLoopMark;
+pub use crate::player::audio_sync::RESYNC_THRESHOLD_MS;
+pub use crate::player::audio_tempo::clamp_speed;
+pub use crate::player::clock_skew::AudioClockSkew;
+pub use crate::player::decoder::{DecoderStats, DEFAULT_MAX_FRAME_DIMENSION, MIN_FRAME_DIMENSION};
+pub use crate::player::decoder_fallback::HwFallbackReason;
+pub use crate::player::demux_end::DemuxEvent;
+pub use crate::player::first_frame::{DecodeCounts, FirstFrameDiagnosis, DEFAULT_FIRST_FRAME_DEADLINE};
+pub use crate::player::live::{LiveResume, LiveStatus};
+pub use crate::player::manager::FileMemory;
+pub use crate::player::playlist::{Playlist, RepeatMode};
+pub use crate::player::seamless_loop::DEFAULT_SEAMLESS_LIMIT_MS;
+pub use crate::player::seek_filter::SeekMode;
+pub use crate::player::seek_status::{SeekOutcome, SeekResult};
+pub use crate::player::stall_watchdog::StallEvent;
+pub use crate::player::timeshift::{TimeshiftLimits, DEFAULT_TIMESHIFT_MB, DEFAULT_TIMESHIFT_MINUTES};
+
+// 播放记录、预览与开发者面板
+pub use crate::player::crash_marker::CrashMarkers;
+pub use crate::player::hover_preview::HoverPreviewState;
+pub use crate::player::packet_inspector::{PacketRecord, StreamPackets, StreamSummary};
+pub use crate::player::position_history::{PositionHistory, PositionRecord, WatchState};
+pub use crate::player::preview_cache::PreviewCache;
+pub use crate::player::thumbnailer::{Thumbnail, Thumbnailer};
+
+// 基准示例（examples/frame_copy_bench.rs、preview_bench.rs）直接测量内部实现，启用 internals 特性时才导出
+#[cfg(feature = "internals")]
+#[doc(hidden)]
+pub mod internals {
+    pub use crate::core::FramePool;
+    pub use crate::player::decoder::copy_rgba_plane;
+    pub use crate::player::preview::{decode_first_frame, PreviewDecoder, PreviewPurpose};
+    pub use crate::player::thumbnailer::downscale;
+    pub use crate::player::{Demuxer, VideoDecoder};
+}
@@ -0,0 +1,247 @@
+// 日志过滤（可在运行时按组件调整日志级别）
+//
+// env_logger 的过滤规则在初始化后无法修改，因此由 ReloadableLogger 负责过滤，
+// 通过过滤的记录再交给 env_logger 输出（保留原有的输出格式）。过滤规则按模块路径前缀匹配，最长前缀优先；
+// 调整级别时整体替换规则（写锁只在替换时持有），解码线程并发打日志时只取读锁。
+
+use log::{LevelFilter, Log, Metadata, Record};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// 默认日志级别（未设置 RUST_LOG 时）
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// 始终压低的第三方模块（wgpu 的警告日志噪音很大）
+const NOISY_MODULES: &[(&str, LevelFilter)] = &[
+    ("wgpu_hal", LevelFilter::Error),
+    ("wgpu_core", LevelFilter::Error),
+];
+
+/// 可单独调整日志级别的组件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogComponent {
+    App,
+    Manager,
+    Demuxer,
+    Decoder,
+    Renderer,
+    Audio,
+}
+
+impl LogComponent {
+    pub const ALL: [LogComponent; 6] = [
+        LogComponent::App,
+        LogComponent::Manager,
+        LogComponent::Demuxer,
+        LogComponent::Decoder,
+        LogComponent::Renderer,
+        LogComponent::Audio,
+    ];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogComponent::App => "app",
+            LogComponent::Manager => "player::manager",
+            LogComponent::Demuxer => "player::demuxer",
+            LogComponent::Decoder => "player::decoder",
+            LogComponent::Renderer => "renderer",
+            LogComponent::Audio => "audio",
+        }
+    }
+
+    /// 对应的模块路径前缀（与日志记录的 target 比较）
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            LogComponent::App => concat!(env!("CARGO_CRATE_NAME"), "::app"),
+            LogComponent::Manager => concat!(env!("CARGO_CRATE_NAME"), "::player::manager"),
+            LogComponent::Demuxer => concat!(env!("CARGO_CRATE_NAME"), "::player::demuxer"),
+            LogComponent::Decoder => concat!(env!("CARGO_CRATE_NAME"), "::player::decoder"),
+            LogComponent::Renderer => concat!(env!("CARGO_CRATE_NAME"), "::renderer"),
+            LogComponent::Audio => concat!(env!("CARGO_CRATE_NAME"), "::player::audio_output"),
+        }
+    }
+}
+
+/// 菜单中可选的级别
+pub const SELECTABLE_LEVELS: [LevelFilter; 5] = [
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+/// 过滤规则（默认级别 + 按模块前缀的级别）
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterSpec {
+    default: LevelFilter,
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl FilterSpec {
+    pub fn new(default: LevelFilter) -> Self {
+        Self { default, directives: Vec::new() }
+    }
+
+    /// 默认规则 + RUST_LOG（语法同 env_logger：`info,myy_player::player=debug`，不支持正则过滤），
+    /// 第三方噪音模块的压制始终生效
+    pub fn from_env(rust_log: Option<&str>) -> Self {
+        let mut spec = Self::new(DEFAULT_LEVEL);
+        for directive in rust_log.unwrap_or("").split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let directive = directive.split('/').next().unwrap_or("");
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    if let Ok(level) = level.trim().parse() {
+                        spec.set(module.trim(), level);
+                    }
+                }
+                // 只有级别：修改默认级别；只有模块名：该模块全部输出
+                None => match directive.parse() {
+                    Ok(level) => spec.default = level,
+                    Err(_) => spec.set(directive, LevelFilter::Trace),
+                },
+            }
+        }
+        for (module, level) in NOISY_MODULES {
+            spec.set(module, *level);
+        }
+        spec
+    }
+
+    /// 设置模块前缀的级别（已存在时替换）
+    pub fn set(&mut self, prefix: &str, level: LevelFilter) {
+        match self.directives.iter_mut().find(|(p, _)| p == prefix) {
+            Some(directive) => directive.1 = level,
+            None => self.directives.push((prefix.to_string(), level)),
+        }
+    }
+
+    /// target 适用的级别（最长的匹配前缀优先，没有匹配时使用默认级别）
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    /// 所有规则中最详细的级别（用于 log::set_max_level，避免格式化一定会被丢弃的记录）
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives.iter().map(|(_, level)| *level).fold(self.default, std::cmp::max)
+    }
+}
+
+/// 可在运行时替换过滤规则的日志器
+struct ReloadableLogger {
+    spec: Arc<RwLock<FilterSpec>>,
+    output: env_logger::Logger,
+}
+
+impl Log for ReloadableLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.spec.read().level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.output.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.output.flush();
+    }
+}
+
+/// 日志级别控制句柄（供开发者面板使用）
+#[derive(Clone)]
+pub struct LogControl {
+    spec: Arc<RwLock<FilterSpec>>,
+    initial: FilterSpec,
+}
+
+impl LogControl {
+    /// 组件当前生效的级别
+    pub fn level(&self, component: LogComponent) -> LevelFilter {
+        self.spec.read().level_for(component.prefix())
+    }
+
+    /// 修改组件的级别（立即生效）
+    pub fn set_level(&self, component: LogComponent, level: LevelFilter) {
+        let mut spec = self.spec.write();
+        spec.set(component.prefix(), level);
+        log::set_max_level(spec.max_level());
+    }
+
+    /// 恢复启动时的级别
+    pub fn reset(&self) {
+        let mut spec = self.spec.write();
+        *spec = self.initial.clone();
+        log::set_max_level(spec.max_level());
+    }
+}
+
+/// 初始化全局日志器（级别来自 RUST_LOG 和默认值）
+pub fn init() -> LogControl {
+    let initial = FilterSpec::from_env(std::env::var("RUST_LOG").ok().as_deref());
+    let spec = Arc::new(RwLock::new(initial.clone()));
+    // 输出端不再过滤，由 ReloadableLogger 统一判断
+    let output = env_logger::Builder::new().filter_level(LevelFilter::Trace).build();
+    let logger = ReloadableLogger { spec: spec.clone(), output };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(initial.max_level());
+    }
+    LogControl { spec, initial }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let mut spec = FilterSpec::new(LevelFilter::Info);
+        spec.set("myy_player::player", LevelFilter::Warn);
+        spec.set("myy_player::player::demuxer", LevelFilter::Trace);
+        spec.set("myy_player", LevelFilter::Debug);
+
+        assert_eq!(spec.level_for("myy_player::player::demuxer"), LevelFilter::Trace);
+        assert_eq!(spec.level_for("myy_player::player::demuxer_thread"), LevelFilter::Trace);
+        assert_eq!(spec.level_for("myy_player::player::decoder"), LevelFilter::Warn);
+        assert_eq!(spec.level_for("myy_player::app"), LevelFilter::Debug);
+        assert_eq!(spec.level_for("eframe::native"), LevelFilter::Info);
+
+        // 替换已有规则而不是追加
+        spec.set("myy_player::player", LevelFilter::Error);
+        assert_eq!(spec.level_for("myy_player::player::manager"), LevelFilter::Error);
+        assert_eq!(spec.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_env_directives_keep_noise_filter() {
+        let spec = FilterSpec::from_env(Some("debug, myy_player::renderer=trace ,wgpu_core=info,bogus=loud"));
+        assert_eq!(spec.level_for("myy_player::app"), LevelFilter::Debug);
+        assert_eq!(spec.level_for("myy_player::renderer::shader"), LevelFilter::Trace);
+        assert_eq!(spec.level_for("bogus"), LevelFilter::Debug);
+        // wgpu 噪音过滤不会被 RUST_LOG 覆盖
+        assert_eq!(spec.level_for("wgpu_core::device"), LevelFilter::Error);
+        assert_eq!(spec.level_for("wgpu_hal::vulkan"), LevelFilter::Error);
+
+        let spec = FilterSpec::from_env(None);
+        assert_eq!(spec.level_for("myy_player::player::decoder"), DEFAULT_LEVEL);
+    }
+
+    #[test]
+    fn test_component_prefixes_match_module_paths() {
+        let spec = {
+            let mut spec = FilterSpec::new(LevelFilter::Info);
+            spec.set(LogComponent::Decoder.prefix(), LevelFilter::Trace);
+            spec
+        };
+        assert_eq!(spec.level_for(module_path!()), LevelFilter::Info);
+        assert!(module_path!().starts_with(env!("CARGO_CRATE_NAME")));
+        assert_eq!(spec.level_for(&format!("{}::player::decoder", env!("CARGO_CRATE_NAME"))), LevelFilter::Trace);
+    }
+}
@@ -1,33 +1,90 @@
+// 播放器应用（界面与视频渲染；解码、同步等播放核心见 lib.rs，只通过 Player 使用）
+//
+// 所有 unsafe 块都必须写明成立条件（// SAFETY:），系统 API 访问集中在 platform 模块
+#![warn(clippy::undocumented_unsafe_blocks)]
+
 use anyhow::Result;
-use log::info;
+use log::{error, info, warn};
 
-mod core;
-mod player;
-mod renderer;
 mod app;
+mod cli;
+mod logging;
+mod platform;
+mod renderer;
+mod single_instance;
 
-use app::VideoPlayerApp;
+use app::{load_settings_or_default, settings_file, VideoPlayerApp, MIN_INNER_SIZE};
+use cli::CommandLine;
+use single_instance::Claim;
+use myy_player::{CrashMarkers, Player, RuntimeFlags};
 
 fn main() -> Result<()> {
-    // 初始化日志
-    env_logger::Builder::from_default_env()
-        .filter_level(log::LevelFilter::Info)
-        // 过滤掉 wgpu_hal 和 wgpu_core 的警告日志，减少日志噪音
-        .filter_module("wgpu_hal", log::LevelFilter::Error)
-        .filter_module("wgpu_core", log::LevelFilter::Error)
-        .init();
+    // 命令行参数（按 OsString 解析，非 UTF-8 路径不会导致崩溃）
+    let command_line = match CommandLine::parse(std::env::args_os().skip(1)) {
+        Ok(command_line) => command_line,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if command_line.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    // 初始化日志（级别来自 RUST_LOG，可在开发者面板中按组件调整；wgpu 的警告日志始终过滤）
+    let log_control = logging::init();
 
     info!("🎬 MYY Player - egui 版本启动");
 
-    // 初始化 FFmpeg
-    ffmpeg_next::init().map_err(|e| anyhow::anyhow!("FFmpeg 初始化失败: {}", e))?;
+    // 崩溃时恢复被自动匹配刷新率修改的显示模式
+    platform::display_mode::install_panic_restore();
+    // 解码线程崩溃时记录文件（下次打开时提示以安全模式打开）
+    Player::install_crash_hook(CrashMarkers::new(CrashMarkers::default_file()));
+
+    // --debug-ui：启动时打开开发者面板（数据包检查）
+    let debug_ui = command_line.debug_ui;
+    // --safe-mode：关闭硬件解码、字幕自动加载、监视文件夹和自动匹配刷新率（排查崩溃或显示异常）
+    let runtime_flags = if command_line.safe_mode { RuntimeFlags::SAFE_MODE } else { RuntimeFlags::NORMAL };
+
+    // 单实例：已有播放器在运行时把文件转发过去并退出（--new-instance 总是新开窗口）
+    let instance_server = if command_line.forwards_to_running() {
+        let lock_file = single_instance::default_lock_file();
+        match single_instance::claim(lock_file, command_line.forwarded_args().as_deref()) {
+            Ok(Claim::Forwarded) => return Ok(()),
+            Ok(Claim::Primary(server)) => Some(server),
+            Err(e) => {
+                warn!("⚠️ 单实例监听失败，其他实例将各自打开: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // 位置参数：启动后打开的媒体（文件、.m3u/.m3u8 播放列表或网络地址）和外部字幕；不存在时只打开空窗口
+    let startup = match command_line.validate() {
+        Ok(()) => command_line.media.map(|media| (media, command_line.subtitle)),
+        Err(e) => {
+            eprintln!("{}", e);
+            error!("❌ 命令行参数无效: {}", e);
+            None
+        }
+    };
+
+    // 初始化 FFmpeg（FFmpeg 自己的警告，如损坏帧、HLS 刷新失败等，转入应用日志）
+    Player::init().map_err(|e| anyhow::anyhow!("FFmpeg 初始化失败: {}", e))?;
     info!("✅ FFmpeg 初始化成功");
 
+    // 读取用户设置（窗口按上次的尺寸和最大化状态创建；文件不存在或损坏时使用默认设置）
+    let settings = load_settings_or_default(&settings_file());
+
     // 启动 egui 应用
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1280.0, 720.0])
-            .with_min_inner_size([800.0, 600.0])
+            .with_inner_size(settings.window_size)
+            .with_maximized(settings.window_maximized)
+            .with_min_inner_size(MIN_INNER_SIZE)
             .with_title("喜洋洋播放器")
             .with_decorations(true), // 使用系统原生标题栏（避免拖动抖动）
         renderer: eframe::Renderer::Wgpu, // 使用 wgpu 后端获得最佳性能
@@ -37,7 +94,16 @@ fn main() -> Result<()> {
     eframe::run_native(
         "喜洋洋播放器",
         options,
-        Box::new(|cc| Box::new(VideoPlayerApp::new(cc))),
+        Box::new(move |cc| {
+            let mut app = VideoPlayerApp::new(cc, settings, debug_ui, runtime_flags, log_control);
+            if let Some((media, subtitle)) = startup {
+                app.open_on_start(media, subtitle);
+            }
+            if let Some(server) = instance_server {
+                app.listen_for_forwarded(server, &cc.egui_ctx);
+            }
+            Box::new(app)
+        }),
     )
     .map_err(|e| anyhow::anyhow!("应用启动失败: {}", e))?;
 
@@ -0,0 +1,355 @@
+// 自动匹配刷新率（全屏播放时把显示器切换到与视频帧率匹配的刷新率）
+//
+// 23.976 fps 的电影在 60Hz 桌面上按 3:2 重复帧显示，会有规律的顿挫；显示器切到 24Hz（或 23Hz）后消失。
+// - ContentRate：把视频帧率映射为一组可用的刷新率（优先精确匹配，其次整数倍）
+// - RefreshRateSwitch：切换全屏窗口所在显示器的刷新率，drop 时恢复原来的模式
+// - 切换使用 CDS_FULLSCREEN（临时修改，不写入注册表），恢复时让系统回到注册表中的模式；
+//   程序 panic 时由 install_panic_restore 安装的钩子恢复，不会把显示器留在修改后的模式
+//
+// - DisplayRefresh：查询窗口所在显示器当前的刷新率（界面据此判断视频帧率是否高于显示刷新率）
+//
+// 目前只支持 Windows（ChangeDisplaySettingsEx / EnumDisplaySettings），其他平台返回"不支持"，
+// 刷新率查询按 60Hz 处理。
+
+use myy_player::{PlayerError, Result};
+use std::time::{Duration, Instant};
+
+/// 当前平台是否支持切换刷新率
+pub const SUPPORTED: bool = cfg!(target_os = "windows");
+
+/// 帧率匹配的误差
+const FPS_TOLERANCE: f64 = 0.01;
+
+/// 可以匹配刷新率的视频帧率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentRate {
+    Film23976,
+    Film24,
+    Pal25,
+    Ntsc2997,
+    Ntsc30,
+    Pal50,
+    Ntsc5994,
+    Ntsc60,
+}
+
+impl ContentRate {
+    const ALL: [(f64, ContentRate); 8] = [
+        (24_000.0 / 1_001.0, ContentRate::Film23976),
+        (24.0, ContentRate::Film24),
+        (25.0, ContentRate::Pal25),
+        (30_000.0 / 1_001.0, ContentRate::Ntsc2997),
+        (30.0, ContentRate::Ntsc30),
+        (50.0, ContentRate::Pal50),
+        (60_000.0 / 1_001.0, ContentRate::Ntsc5994),
+        (60.0, ContentRate::Ntsc60),
+    ];
+
+    /// 视频帧率对应的类型（不在支持列表中时为 None）
+    pub fn from_fps(fps: f64) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(rate, _)| (fps - rate).abs() < FPS_TOLERANCE)
+            .map(|&(_, content)| content)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ContentRate::Film23976 => "23.976",
+            ContentRate::Film24 => "24",
+            ContentRate::Pal25 => "25",
+            ContentRate::Ntsc2997 => "29.97",
+            ContentRate::Ntsc30 => "30",
+            ContentRate::Pal50 => "50",
+            ContentRate::Ntsc5994 => "59.94",
+            ContentRate::Ntsc60 => "60",
+        }
+    }
+
+    /// 匹配的刷新率（按优先顺序；Windows 把 23.976 / 29.97 / 59.94 Hz 报告为 23 / 29 / 59）
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))] // 供 Windows 实现使用
+    fn candidates(self) -> &'static [u32] {
+        match self {
+            ContentRate::Film23976 => &[23, 24],
+            ContentRate::Film24 => &[24, 48],
+            ContentRate::Pal25 => &[25, 50],
+            ContentRate::Ntsc2997 => &[29, 59, 30, 60],
+            ContentRate::Ntsc30 => &[30, 60],
+            ContentRate::Pal50 => &[50],
+            ContentRate::Ntsc5994 => &[59, 60],
+            ContentRate::Ntsc60 => &[60],
+        }
+    }
+
+    /// 从显示器支持的刷新率中选出最匹配的一个
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))] // 供 Windows 实现使用
+    pub fn pick(self, available: &[u32]) -> Option<u32> {
+        self.candidates().iter().copied().find(|hz| available.contains(hz))
+    }
+}
+
+/// 无法查询显示器刷新率时使用的刷新率
+pub const FALLBACK_REFRESH_HZ: f64 = 60.0;
+
+/// 重新查询刷新率的间隔（窗口可能被拖到另一台显示器，刷新率也可能被切换）
+const REFRESH_PROBE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 系统报告的整数刷新率换算为实际刷新率（Windows 把 59.94Hz 等 NTSC 刷新率报告为 59，按 60 × 1000/1001 还原）
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))] // 供 Windows 实现使用
+fn actual_refresh_hz(reported: u32) -> Option<f64> {
+    // 0 和 1 表示"硬件默认"，不是实际刷新率
+    if reported <= 1 {
+        return None;
+    }
+    if (reported + 1).is_multiple_of(6) {
+        Some((reported + 1) as f64 * 1000.0 / 1001.0)
+    } else {
+        Some(reported as f64)
+    }
+}
+
+/// 窗口所在显示器当前的刷新率（定期重新查询，查询失败时按 FALLBACK_REFRESH_HZ 处理）
+#[derive(Debug, Default)]
+pub struct DisplayRefresh {
+    hz: Option<f64>,
+    probed_at: Option<Instant>,
+}
+
+impl DisplayRefresh {
+    /// 距上次查询超过间隔时重新查询，刷新率变化时返回新值
+    pub fn update(&mut self, frame: &eframe::Frame) -> Option<f64> {
+        if self.probed_at.is_some_and(|at| at.elapsed() < REFRESH_PROBE_INTERVAL) {
+            return None;
+        }
+        self.probed_at = Some(Instant::now());
+        let hz = query_refresh_hz(frame);
+        if hz == self.hz {
+            return None;
+        }
+        self.hz = hz;
+        Some(self.hz())
+    }
+
+    /// 下一帧重新查询（切换或恢复刷新率之后）
+    pub fn invalidate(&mut self) {
+        self.probed_at = None;
+    }
+
+    /// 当前刷新率（Hz）
+    pub fn hz(&self) -> f64 {
+        self.hz.unwrap_or(FALLBACK_REFRESH_HZ)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn query_refresh_hz(frame: &eframe::Frame) -> Option<f64> {
+    let device = win32::window_monitor(frame).ok()?;
+    let current = win32::current_mode(&device).ok()?;
+    actual_refresh_hz(current.dmDisplayFrequency)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn query_refresh_hz(_frame: &eframe::Frame) -> Option<f64> {
+    None
+}
+
+/// 已切换的刷新率（drop 时恢复原来的模式）
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))] // 非 Windows 平台不会创建
+pub struct RefreshRateSwitch {
+    #[cfg(target_os = "windows")]
+    device: win32::Device,
+    pub original_hz: u32,
+    pub target_hz: u32,
+}
+
+impl RefreshRateSwitch {
+    /// 把窗口所在显示器切换到与视频帧率匹配的刷新率
+    ///
+    /// 当前刷新率已经匹配时返回 None；显示器没有匹配的模式时返回错误（说明可用的刷新率）。
+    #[cfg(target_os = "windows")]
+    pub fn start(frame: &eframe::Frame, content: ContentRate) -> Result<Option<Self>> {
+        let device = win32::window_monitor(frame)?;
+        let current = win32::current_mode(&device)?;
+        let available = win32::refresh_rates(&device, &current);
+        let target_hz = content.pick(&available).ok_or_else(|| {
+            PlayerError::Other(format!(
+                "显示器不支持与 {} fps 匹配的刷新率（当前分辨率可用: {:?} Hz）",
+                content.label(),
+                available
+            ))
+        })?;
+        let original_hz = current.dmDisplayFrequency;
+        if original_hz == target_hz {
+            return Ok(None);
+        }
+        win32::set_refresh_rate(&device, &current, target_hz)?;
+        *ACTIVE_DEVICE.lock().unwrap() = Some(device);
+        Ok(Some(Self { device, original_hz, target_hz }))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn start(_frame: &eframe::Frame, _content: ContentRate) -> Result<Option<Self>> {
+        Err(PlayerError::Other("当前平台不支持切换刷新率".to_string()))
+    }
+}
+
+impl Drop for RefreshRateSwitch {
+    fn drop(&mut self) {
+        #[cfg(target_os = "windows")]
+        {
+            ACTIVE_DEVICE.lock().unwrap().take();
+            win32::restore(&self.device);
+        }
+    }
+}
+
+/// 已切换刷新率的显示器（panic 钩子从这里恢复）
+#[cfg(target_os = "windows")]
+static ACTIVE_DEVICE: std::sync::Mutex<Option<win32::Device>> = std::sync::Mutex::new(None);
+
+/// 安装 panic 钩子：程序崩溃时恢复被修改的显示模式（之后交给原来的钩子）
+pub fn install_panic_restore() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        #[cfg(target_os = "windows")]
+        if let Ok(mut active) = ACTIVE_DEVICE.try_lock() {
+            if let Some(device) = active.take() {
+                win32::restore(&device);
+            }
+        }
+        previous(info);
+    }));
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use super::*;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Gdi::{
+        ChangeDisplaySettingsExW, EnumDisplaySettingsW, GetMonitorInfoW, MonitorFromWindow, CDS_FULLSCREEN, CDS_TYPE,
+        DEVMODEW, DISP_CHANGE_SUCCESSFUL, DM_DISPLAYFREQUENCY, DM_PELSHEIGHT, DM_PELSWIDTH, ENUM_CURRENT_SETTINGS,
+        ENUM_DISPLAY_SETTINGS_MODE, MONITORINFO, MONITORINFOEXW, MONITOR_DEFAULTTONEAREST,
+    };
+
+    /// 显示器设备名（以 0 结尾的 UTF-16，如 \\.\DISPLAY1）
+    pub type Device = [u16; 32];
+
+    /// 窗口所在的显示器
+    pub fn window_monitor(frame: &eframe::Frame) -> Result<Device> {
+        use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+        let handle = frame
+            .window_handle()
+            .map_err(|e| PlayerError::Other(format!("无法获取窗口句柄: {}", e)))?;
+        let RawWindowHandle::Win32(handle) = handle.as_raw() else {
+            return Err(PlayerError::Other("不是 Win32 窗口".to_string()));
+        };
+        let hwnd = HWND(handle.hwnd.get() as isize);
+        // SAFETY: hwnd 是 eframe 当前窗口的有效句柄；MONITOR_DEFAULTTONEAREST 保证返回一个显示器
+        let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        // SAFETY: cbSize 设置为 MONITORINFOEXW 的大小，GetMonitorInfoW 按扩展结构写入 info
+        let ok = unsafe { GetMonitorInfoW(monitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO) };
+        if !ok.as_bool() {
+            return Err(PlayerError::Other("无法获取显示器信息".to_string()));
+        }
+        Ok(info.szDevice)
+    }
+
+    fn empty_mode() -> DEVMODEW {
+        DEVMODEW { dmSize: std::mem::size_of::<DEVMODEW>() as u16, ..Default::default() }
+    }
+
+    /// 读取第 index 个显示模式（index 为 ENUM_CURRENT_SETTINGS 时读取当前模式）
+    fn enum_mode(device: &Device, index: ENUM_DISPLAY_SETTINGS_MODE) -> Option<DEVMODEW> {
+        let mut mode = empty_mode();
+        // SAFETY: device 是以 0 结尾的设备名，在调用期间有效；mode.dmSize 已设置为结构大小
+        let ok = unsafe { EnumDisplaySettingsW(PCWSTR(device.as_ptr()), index, &mut mode) };
+        ok.as_bool().then_some(mode)
+    }
+
+    pub fn current_mode(device: &Device) -> Result<DEVMODEW> {
+        enum_mode(device, ENUM_CURRENT_SETTINGS).ok_or_else(|| PlayerError::Other("无法读取当前显示模式".to_string()))
+    }
+
+    /// 与当前分辨率、色深相同的显示模式支持的刷新率
+    pub fn refresh_rates(device: &Device, current: &DEVMODEW) -> Vec<u32> {
+        let mut rates: Vec<u32> = (0..)
+            .map_while(|index| enum_mode(device, ENUM_DISPLAY_SETTINGS_MODE(index)))
+            .filter(|mode| {
+                mode.dmPelsWidth == current.dmPelsWidth
+                    && mode.dmPelsHeight == current.dmPelsHeight
+                    && mode.dmBitsPerPel == current.dmBitsPerPel
+            })
+            .map(|mode| mode.dmDisplayFrequency)
+            .collect();
+        rates.sort_unstable();
+        rates.dedup();
+        rates
+    }
+
+    pub fn set_refresh_rate(device: &Device, current: &DEVMODEW, hz: u32) -> Result<()> {
+        let mut mode = *current;
+        mode.dmDisplayFrequency = hz;
+        mode.dmFields = DM_PELSWIDTH | DM_PELSHEIGHT | DM_DISPLAYFREQUENCY;
+        // SAFETY: device 以 0 结尾，mode 是完整初始化的 DEVMODEW，两者在调用期间有效
+        let result = unsafe {
+            ChangeDisplaySettingsExW(PCWSTR(device.as_ptr()), Some(&mode as *const DEVMODEW), HWND(0), CDS_FULLSCREEN, None)
+        };
+        if result != DISP_CHANGE_SUCCESSFUL {
+            return Err(PlayerError::Other(format!("切换到 {} Hz 失败 (ChangeDisplaySettingsEx: {})", hz, result.0)));
+        }
+        Ok(())
+    }
+
+    /// 恢复注册表中的显示模式（CDS_FULLSCREEN 的修改没有写入注册表）
+    pub fn restore(device: &Device) {
+        // SAFETY: device 以 0 结尾且在调用期间有效；传入空模式表示恢复注册表中的设置
+        unsafe {
+            ChangeDisplaySettingsExW(PCWSTR(device.as_ptr()), None, HWND(0), CDS_TYPE(0), None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_rate_from_fps() {
+        assert_eq!(ContentRate::from_fps(23.976), Some(ContentRate::Film23976));
+        assert_eq!(ContentRate::from_fps(24_000.0 / 1_001.0), Some(ContentRate::Film23976));
+        assert_eq!(ContentRate::from_fps(24.0), Some(ContentRate::Film24));
+        assert_eq!(ContentRate::from_fps(29.97), Some(ContentRate::Ntsc2997));
+        assert_eq!(ContentRate::from_fps(59.94), Some(ContentRate::Ntsc5994));
+        assert_eq!(ContentRate::from_fps(15.0), None);
+        assert_eq!(ContentRate::from_fps(0.0), None);
+    }
+
+    #[test]
+    fn test_pick_prefers_exact_rate() {
+        let desktop = [50, 59, 60, 75, 144];
+        assert_eq!(ContentRate::Film23976.pick(&desktop), None);
+        assert_eq!(ContentRate::Film23976.pick(&[23, 24, 60]), Some(23));
+        assert_eq!(ContentRate::Film23976.pick(&[24, 60]), Some(24));
+        assert_eq!(ContentRate::Ntsc2997.pick(&desktop), Some(59));
+        assert_eq!(ContentRate::Pal25.pick(&desktop), Some(50));
+        assert_eq!(ContentRate::Ntsc60.pick(&desktop), Some(60));
+    }
+
+    #[test]
+    fn test_actual_refresh_hz() {
+        assert_eq!(actual_refresh_hz(0), None);
+        assert_eq!(actual_refresh_hz(1), None);
+        assert_eq!(actual_refresh_hz(60), Some(60.0));
+        assert_eq!(actual_refresh_hz(144), Some(144.0));
+        assert_eq!(actual_refresh_hz(165), Some(165.0));
+        // NTSC 刷新率：59.94 的视频不算高于 59Hz 的显示器
+        let ntsc = actual_refresh_hz(59).unwrap();
+        assert!((ntsc - 59.94).abs() < 0.01);
+        assert!((actual_refresh_hz(23).unwrap() - 23.976).abs() < 0.01);
+        assert!((actual_refresh_hz(119).unwrap() - 119.88).abs() < 0.01);
+    }
+}
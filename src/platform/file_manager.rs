@@ -0,0 +1,21 @@
+// 在系统文件管理器中打开文件夹
+
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// 各平台的文件管理器启动命令
+#[cfg(target_os = "windows")]
+const OPENER: &str = "explorer";
+#[cfg(target_os = "macos")]
+const OPENER: &str = "open";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const OPENER: &str = "xdg-open";
+
+/// 在文件管理器中打开文件夹（不等待文件管理器退出）
+pub fn open_folder(path: &Path) -> io::Result<()> {
+    let mut child = Command::new(OPENER).arg(path).spawn()?;
+    // 在后台回收子进程
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,5 @@
+// 平台相关功能（系统 API 封装；不支持的平台提供报告"不支持"的桩实现）
+
+pub mod display_mode;
+pub mod file_manager;
+pub mod title_bar;
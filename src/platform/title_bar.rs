@@ -0,0 +1,58 @@
+// 系统标题栏颜色（Windows 11 的 DWM 窗口属性，与界面主题一致的深色标题栏）
+//
+// 其他平台由窗口管理器决定标题栏样式，不提供这些函数。
+
+/// RGB -> Win32 COLORREF（0x00BBGGRR）
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))] // 只有 Windows 使用
+fn colorref(rgb: [u8; 3]) -> u32 {
+    rgb[0] as u32 | (rgb[1] as u32) << 8 | (rgb[2] as u32) << 16
+}
+
+/// 写入一个 u32 类型的 DWM 窗口属性
+#[cfg(target_os = "windows")]
+fn set_dwm_attribute(
+    hwnd: windows::Win32::Foundation::HWND,
+    attribute: i32,
+    value: u32,
+) -> windows::core::Result<()> {
+    use windows::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWINDOWATTRIBUTE};
+
+    // SAFETY: value 在调用期间有效，传入的大小与其类型一致；无效的 hwnd 由 DWM 以错误码返回
+    unsafe {
+        DwmSetWindowAttribute(
+            hwnd,
+            DWMWINDOWATTRIBUTE(attribute),
+            &value as *const u32 as *const _,
+            std::mem::size_of::<u32>() as u32,
+        )
+    }
+}
+
+/// 启用深色标题栏（DWMWA_USE_IMMERSIVE_DARK_MODE，Windows 11）
+#[cfg(target_os = "windows")]
+pub fn set_win32_dark_mode(hwnd: windows::Win32::Foundation::HWND, enabled: bool) -> windows::core::Result<()> {
+    set_dwm_attribute(hwnd, 20, enabled as u32)
+}
+
+/// 设置标题栏背景色（DWMWA_CAPTION_COLOR，Windows 11 Build 22621+）
+#[cfg(target_os = "windows")]
+pub fn set_win32_caption_color(hwnd: windows::Win32::Foundation::HWND, rgb: [u8; 3]) -> windows::core::Result<()> {
+    set_dwm_attribute(hwnd, 35, colorref(rgb))
+}
+
+/// 设置窗口边框颜色（DWMWA_BORDER_COLOR，标题栏颜色不可用时的备选）
+#[cfg(target_os = "windows")]
+pub fn set_win32_border_color(hwnd: windows::Win32::Foundation::HWND, rgb: [u8; 3]) -> windows::core::Result<()> {
+    set_dwm_attribute(hwnd, 34, colorref(rgb))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colorref_is_bgr() {
+        assert_eq!(colorref([29, 29, 29]), 0x001d_1d1d);
+        assert_eq!(colorref([0x12, 0x34, 0x56]), 0x0056_3412);
+    }
+}
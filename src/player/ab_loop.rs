@@ -0,0 +1,129 @@
+// A/B 循环（在用户标记的两个时间点之间反复播放，如语言学习时反复听一句）
+//
+// 先标记 A 点，再标记 B 点（必须在 A 点之后），之后播放时钟越过 B 点时 seek 回 A 点；循环生效后再次标记清除循环。
+// 只在时钟从 B 点之前连续播放越过 B 点时触发：每次 seek（包括拖动进度条和循环本身）之后重新开始判断，
+// 因此用户跳到 B 点之后不会被拉回 A 点，界面的 seek 防抖也不会和循环的 seek 互相干扰。
+
+use crate::core::{PlayerError, Result};
+
+/// 标记 A / B 点的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbLoopMark {
+    /// 已标记 A 点（毫秒）
+    PointA(i64),
+    /// 循环生效 (A, B)
+    Range(i64, i64),
+    /// 已清除循环
+    Cleared,
+}
+
+/// A/B 循环状态
+#[derive(Debug, Clone, Default)]
+pub struct AbLoop {
+    point_a: Option<i64>,
+    range: Option<(i64, i64)>,
+    last_check: Option<(u64, i64)>,  // 上次检查时的 (seek 次数, 位置)
+}
+
+impl AbLoop {
+    /// 标记 A 点（循环已生效时清除循环）
+    pub fn mark_a(&mut self, position_ms: i64) -> AbLoopMark {
+        if self.range.is_some() {
+            self.clear();
+            return AbLoopMark::Cleared;
+        }
+        self.point_a = Some(position_ms);
+        AbLoopMark::PointA(position_ms)
+    }
+
+    /// 标记 B 点（循环已生效时清除循环；没有 A 点或不在 A 点之后时返回错误）
+    pub fn mark_b(&mut self, position_ms: i64) -> Result<AbLoopMark> {
+        if self.range.is_some() {
+            self.clear();
+            return Ok(AbLoopMark::Cleared);
+        }
+        let a = self.point_a.ok_or_else(|| PlayerError::Other("请先标记 A 点".to_string()))?;
+        self.set_range(a, position_ms)?;
+        Ok(AbLoopMark::Range(a, position_ms))
+    }
+
+    /// 直接设置循环范围（B 必须在 A 之后）
+    pub fn set_range(&mut self, a: i64, b: i64) -> Result<()> {
+        if b <= a {
+            return Err(PlayerError::Other("B 点必须在 A 点之后".to_string()));
+        }
+        self.point_a = Some(a);
+        self.range = Some((a, b));
+        self.last_check = None;
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 生效中的循环范围
+    pub fn range(&self) -> Option<(i64, i64)> {
+        self.range
+    }
+
+    /// 已标记、尚未标记 B 点的 A 点
+    pub fn pending_a(&self) -> Option<i64> {
+        self.point_a.filter(|_| self.range.is_none())
+    }
+
+    /// 检查当前位置（seeks 为累计 seek 次数），从 B 点之前越过 B 点时返回要 seek 到的 A 点
+    pub fn check(&mut self, seeks: u64, position_ms: i64) -> Option<i64> {
+        let (a, b) = self.range?;
+        match self.last_check.replace((seeks, position_ms)) {
+            Some((last_seeks, last_position)) if last_seeks == seeks && last_position < b && position_ms >= b => Some(a),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks_require_b_after_a_and_third_mark_clears() {
+        let mut ab = AbLoop::default();
+        assert!(ab.mark_b(5_000).is_err());
+        assert_eq!(ab.mark_a(5_000), AbLoopMark::PointA(5_000));
+        assert!(ab.mark_b(5_000).is_err());
+        assert!(ab.mark_b(4_000).is_err());
+        assert_eq!(ab.pending_a(), Some(5_000));
+        // 重新标记 A 点
+        assert_eq!(ab.mark_a(2_000), AbLoopMark::PointA(2_000));
+        assert_eq!(ab.mark_b(4_000).unwrap(), AbLoopMark::Range(2_000, 4_000));
+        assert_eq!(ab.range(), Some((2_000, 4_000)));
+        assert_eq!(ab.pending_a(), None);
+        assert_eq!(ab.mark_a(3_000), AbLoopMark::Cleared);
+        assert_eq!(ab.range(), None);
+        assert_eq!(ab.pending_a(), None);
+
+        ab.set_range(1_000, 2_000).unwrap();
+        assert_eq!(ab.mark_b(1_500).unwrap(), AbLoopMark::Cleared);
+        assert!(ab.set_range(2_000, 2_000).is_err());
+    }
+
+    #[test]
+    fn test_loops_only_when_playback_crosses_b() {
+        let mut ab = AbLoop::default();
+        ab.set_range(2_000, 4_000).unwrap();
+        assert_eq!(ab.check(0, 3_900), None);
+        assert_eq!(ab.check(0, 4_010), Some(2_000));
+        // 循环的 seek 之后重新开始判断
+        assert_eq!(ab.check(1, 2_000), None);
+        assert_eq!(ab.check(1, 3_999), None);
+        assert_eq!(ab.check(1, 4_000), Some(2_000));
+
+        // 用户 seek 到 B 点之后：不拉回 A 点
+        assert_eq!(ab.check(2, 6_000), None);
+        assert_eq!(ab.check(2, 6_100), None);
+        // seek 从 B 点之前跳到之后（同一次检查内 seek 次数已变化）也不触发
+        assert_eq!(ab.check(3, 1_000), None);
+        assert_eq!(ab.check(4, 5_000), None);
+    }
+}
@@ -1,9 +1,33 @@
-use crate::core::{AudioFrame, PlayerError, Result};
+use super::clock_skew::{AudioClockSkew, SkewEstimator};
+use crate::core::{AudioFrame, PlayerError, Result, MAX_VOLUME};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Device, Stream, StreamConfig, SupportedStreamConfigRange};
 use crossbeam::queue::SegQueue;
 use log::{debug, info, warn};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// 设备消耗样本的进度（音频回调中更新）
+#[derive(Debug, Clone, Copy, Default)]
+struct DeviceProgress {
+    frames: u64,
+    at: Option<Instant>,
+}
+
+/// 软限幅的起始电平（以下保持线性）
+const SOFT_CLIP_KNEE: f32 = 0.8;
+
+/// 软限幅：超过起始电平的部分平滑压缩，输出不超过满幅
+pub(crate) fn soft_clip(value: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= SOFT_CLIP_KNEE {
+        return value;
+    }
+    let headroom = 1.0 - SOFT_CLIP_KNEE;
+    let compressed = SOFT_CLIP_KNEE + headroom * ((magnitude - SOFT_CLIP_KNEE) / headroom).tanh();
+    compressed.copysign(value)
+}
 
 /// 音频输出 - 使用 cpal 播放音频
 pub struct AudioOutput {
@@ -12,21 +36,60 @@ pub struct AudioOutput {
     stream: Option<Stream>,
     buffer: Arc<SegQueue<f32>>,
     volume: Arc<Mutex<f32>>,
+    /// 设备已消耗的帧数和最近一次回调的时刻（包括缓冲区空时输出的静音）
+    progress: Arc<Mutex<DeviceProgress>>,
+    /// 回调从缓冲区取出并实际播放的样本数（不含缓冲区空时输出的静音，音频流启动时清零）
+    played: Arc<AtomicU64>,
+    /// 设备时钟偏差估计（音频流启动时重新开始）
+    skew: SkewEstimator,
+    started_at: Option<Instant>,
+    /// 音频流报告了错误（设备被拔出等），需要换用其他设备
+    failed: Arc<AtomicBool>,
 }
 
 // cpal::Stream 本身不是 Send，但在 PlaybackManager 中我们确保它只在创建它的线程中使用
 // PlaybackManager 在 Tauri 的主线程中创建和使用，不会跨线程传递
+// SAFETY: AudioOutput 只在创建它的线程中访问 cpal::Stream（见上）
 unsafe impl Send for AudioOutput {}
 
 impl AudioOutput {
-    /// 创建音频输出（支持非标准配置自动回退）
-    pub fn new(sample_rate: u32, channels: u16) -> Result<Self> {
+    /// 可用的音频输出设备名称
+    pub fn list_devices() -> Vec<String> {
+        match cpal::default_host().output_devices() {
+            Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+            Err(e) => {
+                warn!("⚠️  无法枚举音频输出设备: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// 系统默认音频输出设备的名称
+    pub fn default_device_name() -> Option<String> {
+        cpal::default_host().default_output_device()?.name().ok()
+    }
+
+    /// 创建音频输出（name 为 None 或设备不存在时使用系统默认设备；支持非标准配置自动回退）
+    pub fn new_with_device(name: Option<&str>, sample_rate: u32, channels: u16) -> Result<Self> {
         info!("初始化音频输出: {} Hz, {} 声道", sample_rate, channels);
 
         let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .ok_or_else(|| PlayerError::AudioError("无法找到音频输出设备".to_string()))?;
+        let named = name.and_then(|name| {
+            let device = host
+                .output_devices()
+                .ok()?
+                .find(|device| device.name().is_ok_and(|device_name| device_name == name));
+            if device.is_none() {
+                warn!("⚠️  找不到音频设备 \"{}\"，使用系统默认设备", name);
+            }
+            device
+        });
+        let device = match named {
+            Some(device) => device,
+            None => host
+                .default_output_device()
+                .ok_or_else(|| PlayerError::AudioError("无法找到音频输出设备".to_string()))?,
+        };
 
         debug!("使用音频设备: {}", device.name().unwrap_or_default());
 
@@ -98,13 +161,28 @@ impl AudioOutput {
 
         Ok(Self {
             device,
+            skew: SkewEstimator::new(config.sample_rate.0),
             config,
             stream: None,
             buffer: Arc::new(SegQueue::new()),
             volume: Arc::new(Mutex::new(1.0)),
+            progress: Arc::new(Mutex::new(DeviceProgress::default())),
+            played: Arc::new(AtomicU64::new(0)),
+            started_at: None,
+            failed: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    /// 设备名称
+    pub fn device_name(&self) -> String {
+        self.device.name().unwrap_or_default()
+    }
+
+    /// 音频流是否报告过错误（设备已不可用）
+    pub fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+
     /// 检查配置是否兼容
     fn is_config_compatible(config: &StreamConfig, supported: &SupportedStreamConfigRange) -> bool {
         let rate_in_range = config.sample_rate.0 >= supported.min_sample_rate().0
@@ -123,23 +201,39 @@ impl AudioOutput {
 
         let buffer = self.buffer.clone();
         let volume = self.volume.clone();
+        let progress = self.progress.clone();
+        let played = self.played.clone();
+        let channels = self.config.channels.max(1) as u64;
+        let failed = self.failed.clone();
 
         let stream = self
             .device
             .build_output_stream(
                 &self.config,
                 move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                    {
+                        let mut progress = progress.lock().unwrap();
+                        progress.frames += data.len() as u64 / channels;
+                        progress.at = Some(Instant::now());
+                    }
                     let vol = *volume.lock().unwrap();
+                    let mut taken = 0;
                     for sample in data.iter_mut() {
                         if let Some(value) = buffer.pop() {
-                            *sample = value * vol;
+                            // 放大超过 100% 时软限幅，避免硬削波的刺耳失真
+                            *sample = soft_clip(value * vol);
+                            taken += 1;
                         } else {
                             *sample = 0.0;
                         }
                     }
+                    played.fetch_add(taken, Ordering::Relaxed);
                 },
                 move |err| {
-                    eprintln!("音频流错误: {}", err);
+                    warn!("⚠️  音频流错误: {}", err);
+                    if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                        failed.store(true, Ordering::Relaxed);
+                    }
                 },
                 None,
             )
@@ -150,6 +244,10 @@ impl AudioOutput {
             .map_err(|e| PlayerError::AudioError(format!("启动音频流失败: {}", e)))?;
 
         self.stream = Some(stream);
+        *self.progress.lock().unwrap() = DeviceProgress::default();
+        self.played.store(0, Ordering::Relaxed);
+        self.skew = SkewEstimator::new(self.config.sample_rate.0);
+        self.started_at = Some(Instant::now());
         info!("音频输出已启动");
 
         Ok(())
@@ -159,20 +257,31 @@ impl AudioOutput {
     pub fn stop(&mut self) {
         if let Some(stream) = self.stream.take() {
             drop(stream);
+            self.started_at = None;
             info!("音频输出已停止");
         }
     }
 
     /// 写入音频帧
     pub fn write_frame(&self, frame: &AudioFrame) {
-        for sample in &frame.data {
+        self.write_samples(&frame.data);
+    }
+
+    /// 写入交错排列的样本（格式必须与输出配置一致）
+    pub fn write_samples(&self, samples: &[f32]) {
+        for sample in samples {
             self.buffer.push(*sample);
         }
     }
 
-    /// 设置音量 (0.0 - 1.0)
+    /// 取出尚未播放的样本（切换设备时转移到新的输出，保持音画同步）
+    pub fn take_buffered(&self) -> Vec<f32> {
+        std::iter::from_fn(|| self.buffer.pop()).collect()
+    }
+
+    /// 设置音量 (0.0 - MAX_VOLUME)
     pub fn set_volume(&self, volume: f32) {
-        *self.volume.lock().unwrap() = volume.clamp(0.0, 1.0);
+        *self.volume.lock().unwrap() = volume.clamp(0.0, MAX_VOLUME);
     }
 
     /// 获取缓冲区大小（采样数）
@@ -180,11 +289,45 @@ impl AudioOutput {
         self.buffer.len()
     }
 
+    /// 音频流启动以来实际播放的时长（毫秒，不含缓冲区空时输出的静音）
+    pub fn played_duration_ms(&self) -> f64 {
+        self.samples_to_ms(self.played.load(Ordering::Relaxed) as usize)
+    }
+
+    /// 缓冲区中尚未播放的时长（毫秒）
+    pub fn queued_duration_ms(&self) -> f64 {
+        self.samples_to_ms(self.buffer.len())
+    }
+
+    /// 交错样本数换算为播放时长（毫秒）
+    fn samples_to_ms(&self, samples: usize) -> f64 {
+        let frames = samples as f64 / self.config.channels.max(1) as f64;
+        frames * 1000.0 / self.config.sample_rate.0.max(1) as f64
+    }
+
     /// 清空缓冲区
     pub fn clear_buffer(&self) {
         while self.buffer.pop().is_some() {}
     }
     
+    /// 按设备已消耗的帧数更新时钟偏差估计，返回播放时钟应使用的修正量（ppm，音频流未启动时为 0）
+    pub fn measure_clock_skew(&mut self) -> f64 {
+        let Some(started_at) = self.started_at else {
+            return 0.0;
+        };
+        let DeviceProgress { frames, at } = *self.progress.lock().unwrap();
+        match at {
+            // 以回调时刻配对消耗的帧数，避免按界面刷新时刻取样带来的周期量化误差
+            Some(at) => self.skew.observe(at.saturating_duration_since(started_at).as_secs_f64(), frames),
+            None => self.skew.skew().applied_ppm,
+        }
+    }
+
+    /// 设备时钟偏差（测量值和当前修正量）
+    pub fn clock_skew(&self) -> AudioClockSkew {
+        self.skew.skew()
+    }
+
     /// 获取实际使用的音频配置
     pub fn get_config(&self) -> (u32, u16) {
         (self.config.sample_rate.0, self.config.channels)
@@ -197,3 +340,20 @@ impl Drop for AudioOutput {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_soft_clip_is_linear_below_knee_and_bounded() {
+        assert_eq!(soft_clip(0.5), 0.5);
+        assert_eq!(soft_clip(-0.8), -0.8);
+        // 单调、平滑且不超过满幅
+        let values: Vec<f32> = (0..=400).map(|i| soft_clip(i as f32 / 100.0)).collect();
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(values.iter().all(|&value| value <= 1.0));
+        assert!(soft_clip(1.0) > 0.9);
+        assert_eq!(soft_clip(-2.0), -soft_clip(2.0));
+    }
+}
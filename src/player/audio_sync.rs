@@ -0,0 +1,97 @@
+// 按实际播放的音频校正播放时钟
+//
+// 播放时钟在音频首帧处对齐后按墙上时间推进（速率按设备时钟偏差修正）。输出回调卡顿、缓冲区欠载，
+// 或 update_audio 一次向输出缓冲区写入大量样本时，墙上时间与实际听到的声音会逐渐错开。
+// 这里记录最近写入输出的音频结束在哪个媒体时间，减去输出缓冲区中尚未播放的样本时长（按播放速度换算），
+// 得到正在播放的音频位置；与播放时钟相差超过 RESYNC_THRESHOLD_MS 时把时钟拉回该位置。
+// 阈值以内不调整：回调按周期成批消耗样本，逐次对齐会让时钟按回调周期抖动。
+// 输出缓冲区已经播空（音轨比视频短、数据暂时不足）时不测量，时钟继续按墙上时间推进，画面不会卡在最后一段声音上。
+
+/// 播放时钟与实际播放的音频相差超过这么多时重新对齐（毫秒）
+pub const RESYNC_THRESHOLD_MS: i64 = 40;
+
+/// 音画偏差测量（播放管理器在写入音频输出时更新）
+#[derive(Debug, Default)]
+pub struct AudioSync {
+    written_end_ms: Option<i64>,  // 已写入输出的音频的结束位置（媒体时间）
+    offset_ms: Option<i64>,  // 最近一次测量的偏差（正值表示画面领先声音）
+}
+
+impl AudioSync {
+    /// 输出缓冲区被清空（seek、暂停）后重新开始测量
+    pub fn reset(&mut self) {
+        self.written_end_ms = None;
+        self.offset_ms = None;
+    }
+
+    /// 记录写入输出的一帧音频（结束位置为媒体时间）
+    pub fn record_written(&mut self, end_ms: i64) {
+        self.written_end_ms = Some(end_ms);
+    }
+
+    /// 按输出缓冲区中尚未播放的时长（输出时间）和播放速度测量偏差，需要对齐时返回正在播放的音频位置
+    pub fn update(&mut self, clock_ms: i64, queued_ms: f64, speed: f64) -> Option<i64> {
+        let written_end_ms = self.written_end_ms?;
+        if queued_ms <= 0.0 {
+            self.offset_ms = None;
+            return None;
+        }
+        let audible_ms = written_end_ms - (queued_ms * speed).round() as i64;
+        let offset_ms = clock_ms - audible_ms;
+        self.offset_ms = Some(offset_ms);
+        (offset_ms.abs() > RESYNC_THRESHOLD_MS).then_some(audible_ms)
+    }
+
+    /// 最近一次测量的音画偏差（正值表示画面领先声音；没有在播放的音频时为 None）
+    pub fn offset_ms(&self) -> Option<i64> {
+        self.offset_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_offset_keeps_clock() {
+        let mut sync = AudioSync::default();
+        assert_eq!(sync.update(1_000, 200.0, 1.0), None);
+        assert_eq!(sync.offset_ms(), None);
+
+        // 写到 1500ms，缓冲区中还有 480ms：正在播放 1020ms
+        sync.record_written(1_500);
+        assert_eq!(sync.update(1_000, 480.0, 1.0), None);
+        assert_eq!(sync.offset_ms(), Some(-20));
+    }
+
+    #[test]
+    fn test_stalled_output_pulls_clock_back() {
+        let mut sync = AudioSync::default();
+        sync.record_written(2_000);
+        // 设备停顿：缓冲区没有被消耗，墙上时间继续推进
+        assert_eq!(sync.update(1_100, 1_000.0, 1.0), Some(1_000));
+        assert_eq!(sync.offset_ms(), Some(100));
+    }
+
+    #[test]
+    fn test_queued_output_scales_with_speed() {
+        let mut sync = AudioSync::default();
+        sync.record_written(10_000);
+        // 2 倍速：缓冲区中 500ms 的输出对应 1 秒媒体时间
+        assert_eq!(sync.update(9_000, 500.0, 2.0), None);
+        assert_eq!(sync.update(9_500, 500.0, 2.0), Some(9_000));
+    }
+
+    #[test]
+    fn test_drained_output_is_not_measured() {
+        let mut sync = AudioSync::default();
+        sync.record_written(5_000);
+        assert_eq!(sync.update(5_000, 100.0, 1.0), Some(4_900));
+        // 音轨已播完：时钟按墙上时间继续推进
+        assert_eq!(sync.update(8_000, 0.0, 1.0), None);
+        assert_eq!(sync.offset_ms(), None);
+
+        sync.reset();
+        assert_eq!(sync.update(8_000, 100.0, 1.0), None);
+    }
+}
@@ -0,0 +1,230 @@
+// 变速不变调（WSOLA：波形相似重叠相加）
+//
+// 按固定长度的片段输出音频，每个片段在输入中的位置按播放速度推进；片段开头与上一片段的尾部做交叉淡化，
+// 并在标称位置附近的搜索窗口内挑选与上一片段尾部最相似的位置，避免拼接处相位不连续产生的"咔哒"声和颤音。
+// 输出时长为输入时长 / 速度，音高不变。1.0 倍速时直接透传，不引入额外延迟。
+
+use crate::core::AudioFrame;
+
+/// 支持的速度范围
+pub const SPEED_RANGE: std::ops::RangeInclusive<f32> = 0.5..=2.0;
+
+/// 片段长度（毫秒）
+const SEQUENCE_MS: u32 = 40;
+
+/// 交叉淡化长度（毫秒）
+const OVERLAP_MS: u32 = 10;
+
+/// 最相似位置的搜索窗口（毫秒）
+const SEEK_WINDOW_MS: u32 = 15;
+
+/// 粗搜索步长（帧，找到后在附近逐帧细化）
+const COARSE_STEP: usize = 4;
+
+/// 夹紧到支持的速度范围（非有限值视为 1.0）
+pub fn clamp_speed(speed: f32) -> f32 {
+    if speed.is_finite() {
+        speed.clamp(*SPEED_RANGE.start(), *SPEED_RANGE.end())
+    } else {
+        1.0
+    }
+}
+
+/// 变速处理器（输入输出均为交错排列的 f32 样本）
+#[derive(Debug, Clone)]
+pub struct AudioTempo {
+    speed: f32,
+    format: Option<(u32, u16)>,  // 当前输入的 (采样率, 声道数)
+    input: Vec<f32>,             // 尚未消耗的输入样本
+    base_pts: i64,               // 缓存开始时第一个样本的时间戳（毫秒）
+    consumed: u64,               // 此后已消耗的输入帧数
+    tail: Vec<f32>,              // 上一片段的尾部（与下一片段开头交叉淡化）
+    position_fract: f64,         // 输入推进量的小数部分（帧）
+}
+
+impl Default for AudioTempo {
+    fn default() -> Self {
+        Self { speed: 1.0, format: None, input: Vec::new(), base_pts: 0, consumed: 0, tail: Vec::new(), position_fract: 0.0 }
+    }
+}
+
+impl AudioTempo {
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// 设置速度（丢弃尚未输出的样本）
+    pub fn set_speed(&mut self, speed: f32) {
+        let speed = clamp_speed(speed);
+        if speed != self.speed {
+            self.speed = speed;
+            self.reset();
+        }
+    }
+
+    /// 丢弃缓存的样本（seek、切换文件后使用）
+    pub fn reset(&mut self) {
+        self.format = None;
+        self.input.clear();
+        self.tail.clear();
+        self.position_fract = 0.0;
+    }
+
+    /// 处理一帧输入，返回已经可以输出的样本（缓存不足一个片段时返回 None）
+    pub fn process(&mut self, frame: AudioFrame) -> Option<AudioFrame> {
+        if self.speed == 1.0 || frame.channels == 0 || frame.sample_rate == 0 {
+            return Some(frame);
+        }
+        let format = (frame.sample_rate, frame.channels);
+        if self.format != Some(format) {
+            self.reset();
+            self.format = Some(format);
+        }
+        if self.input.is_empty() {
+            self.base_pts = frame.pts;
+            self.consumed = 0;
+        }
+        self.input.extend_from_slice(&frame.data);
+
+        let output_pts = self.base_pts + (self.consumed * 1000 / frame.sample_rate as u64) as i64;
+        let output = self.run(frame.sample_rate, frame.channels as usize);
+        (!output.is_empty()).then_some(AudioFrame { pts: output_pts, data: output, ..frame })
+    }
+
+    /// 按片段生成输出，直到剩余输入不足一个片段
+    fn run(&mut self, sample_rate: u32, channels: usize) -> Vec<f32> {
+        let frames = |ms: u32| (sample_rate as usize * ms as usize / 1000).max(1);
+        let (sequence, overlap, seek_window) = (frames(SEQUENCE_MS), frames(OVERLAP_MS), frames(SEEK_WINDOW_MS));
+        let step = sequence - overlap;
+        let nominal_advance = step as f64 * self.speed as f64;
+        let required = (sequence + seek_window).max(nominal_advance.ceil() as usize + 1);
+
+        let mut output = Vec::new();
+        while self.input.len() / channels >= required {
+            let offset = if self.tail.is_empty() { 0 } else { self.best_offset(channels, overlap, seek_window) };
+            let segment = &self.input[offset * channels..(offset + sequence) * channels];
+
+            // 开头与上一片段尾部交叉淡化
+            let (head, rest) = segment.split_at(overlap * channels);
+            if self.tail.is_empty() {
+                output.extend_from_slice(head);
+            } else {
+                for (i, (&new, &old)) in head.iter().zip(&self.tail).enumerate() {
+                    let t = (i / channels) as f32 / overlap as f32;
+                    output.push(old * (1.0 - t) + new * t);
+                }
+            }
+            let (middle, tail) = rest.split_at(rest.len() - overlap * channels);
+            output.extend_from_slice(middle);
+            self.tail = tail.to_vec();
+
+            // 输入按速度推进（与搜索到的偏移无关，偏移不会累积）
+            let advance = nominal_advance + self.position_fract;
+            let whole = advance.floor() as usize;
+            self.position_fract = advance - whole as f64;
+            self.input.drain(..whole * channels);
+            self.consumed += whole as u64;
+        }
+        output
+    }
+
+    /// 搜索窗口内与上一片段尾部最相似的位置（归一化互相关，先粗搜后细化）
+    fn best_offset(&self, channels: usize, overlap: usize, seek_window: usize) -> usize {
+        let score = |offset: usize| {
+            let candidate = &self.input[offset * channels..(offset + overlap) * channels];
+            let (mut dot, mut energy) = (0.0f32, 0.0f32);
+            for (&a, &b) in candidate.iter().zip(&self.tail) {
+                dot += a * b;
+                energy += a * a;
+            }
+            dot / energy.sqrt().max(1e-6)
+        };
+        let best_in = |range: &mut dyn Iterator<Item = usize>| {
+            range.map(|offset| (offset, score(offset))).max_by(|a, b| a.1.total_cmp(&b.1)).map_or(0, |(offset, _)| offset)
+        };
+        let coarse = best_in(&mut (0..seek_window).step_by(COARSE_STEP));
+        let start = coarse.saturating_sub(COARSE_STEP - 1);
+        let end = (coarse + COARSE_STEP).min(seek_window);
+        best_in(&mut (start..end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 48_000;
+
+    /// 立体声正弦波，按 10ms 一帧切分
+    fn sine_frames(freq: f32, duration_ms: u32) -> Vec<AudioFrame> {
+        let frame_len = (RATE / 100) as usize;
+        let total = (RATE * duration_ms / 1000) as usize;
+        (0..total / frame_len)
+            .map(|index| {
+                let data = (0..frame_len)
+                    .flat_map(|i| {
+                        let t = (index * frame_len + i) as f32 / RATE as f32;
+                        let value = (t * freq * std::f32::consts::TAU).sin() * 0.5;
+                        [value, value]
+                    })
+                    .collect();
+                AudioFrame { pts: index as i64 * 10, sample_rate: RATE, channels: 2, data }
+            })
+            .collect()
+    }
+
+    fn run(tempo: &mut AudioTempo, frames: Vec<AudioFrame>) -> Vec<AudioFrame> {
+        frames.into_iter().filter_map(|frame| tempo.process(frame)).collect()
+    }
+
+    /// 过零点数量（估计音高）
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples.iter().step_by(2).collect::<Vec<_>>().windows(2).filter(|pair| (*pair[0] < 0.0) != (*pair[1] < 0.0)).count()
+    }
+
+    #[test]
+    fn test_normal_speed_passes_through() {
+        let mut tempo = AudioTempo::default();
+        let frames = sine_frames(440.0, 100);
+        let output = run(&mut tempo, frames.clone());
+        assert_eq!(output.len(), frames.len());
+        assert_eq!(output[3].data, frames[3].data);
+        assert_eq!(clamp_speed(3.0), 2.0);
+        assert_eq!(clamp_speed(f32::NAN), 1.0);
+    }
+
+    #[test]
+    fn test_duration_scales_and_pitch_is_kept() {
+        for speed in [0.5, 0.75, 1.5, 2.0] {
+            let mut tempo = AudioTempo::default();
+            tempo.set_speed(speed);
+            let output = run(&mut tempo, sine_frames(440.0, 2_000));
+            let samples: Vec<f32> = output.iter().flat_map(|frame| frame.data.iter().copied()).collect();
+            let seconds = samples.len() as f32 / 2.0 / RATE as f32;
+            // 输出时长约为 2 秒 / 速度（末尾有一个片段留在缓存中）
+            let expected = 2.0 / speed;
+            assert!((seconds - expected).abs() < 0.1, "{}x: {}s", speed, seconds);
+            // 音高不变：每秒过零点约 880 次
+            let crossings_per_second = zero_crossings(&samples) as f32 / seconds;
+            assert!((crossings_per_second - 880.0).abs() < 30.0, "{}x: {}", speed, crossings_per_second);
+            // 输出时间戳单调递增
+            assert!(output.windows(2).all(|pair| pair[0].pts <= pair[1].pts));
+        }
+    }
+
+    #[test]
+    fn test_reset_and_format_change_drop_buffered_samples() {
+        let mut tempo = AudioTempo::default();
+        tempo.set_speed(1.5);
+        // 不足一个片段：暂不输出
+        assert!(tempo.process(sine_frames(440.0, 10).remove(0)).is_none());
+        tempo.reset();
+        // 旧样本已丢弃：第一段输出从 reset 之后的第一帧开始
+        let outputs: Vec<_> = sine_frames(440.0, 60)
+            .into_iter()
+            .filter_map(|frame| tempo.process(AudioFrame { pts: frame.pts + 5_000, ..frame }))
+            .collect();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].pts, 5_000);
+    }
+}
@@ -0,0 +1,151 @@
+// 线程间交接数据包 / 帧的队列（生产者在队列满时阻塞，消费者在队列空时阻塞）
+//
+// 原先的 SegQueue 没有通知机制，解封装 / 解码线程只能在队列满或空时 sleep(1~15ms) 轮询，
+// 既空耗 CPU，又给交接增加了最多一个轮询周期的延迟。这里用互斥锁保护的 VecDeque 加条件变量：
+// 每次入队 / 出队 / 清空都唤醒等待者，等待方在 wait_while 中重新检查自己的条件（队列长度、运行标志、
+// seek 命令等）。队列本身不设上限，限流阈值由各线程按媒体类型决定。
+// 除队列变化以外的条件（停止播放、新的 seek 命令）由 wake() 通知，单次等待带超时作为兜底。
+
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// 单次等待的上限（条件由队列以外的状态决定且没有被唤醒时，最多延迟这么久重新检查）
+const WAIT_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// 带阻塞等待的队列
+#[derive(Debug)]
+pub struct BlockingQueue<T> {
+    items: Mutex<VecDeque<T>>,
+    changed: Condvar,
+}
+
+impl<T> Default for BlockingQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> BlockingQueue<T> {
+    pub fn new() -> Self {
+        Self { items: Mutex::new(VecDeque::new()), changed: Condvar::new() }
+    }
+
+    /// 入队（唤醒等待数据的消费者）
+    pub fn push(&self, item: T) {
+        self.items.lock().unwrap().push_back(item);
+        self.changed.notify_all();
+    }
+
+    /// 出队（唤醒等待空位的生产者）
+    pub fn pop(&self) -> Option<T> {
+        let item = self.items.lock().unwrap().pop_front();
+        if item.is_some() {
+            self.changed.notify_all();
+        }
+        item
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.lock().unwrap().is_empty()
+    }
+
+    /// 清空队列，返回丢弃的数量
+    pub fn clear(&self) -> usize {
+        let cleared = std::mem::take(&mut *self.items.lock().unwrap()).len();
+        self.changed.notify_all();
+        cleared
+    }
+
+    /// 在锁内整理队列（筛选过期帧等），整理期间生产者不会插入新的元素
+    pub fn with_items<R>(&self, f: impl FnOnce(&mut VecDeque<T>) -> R) -> R {
+        let result = f(&mut self.items.lock().unwrap());
+        self.changed.notify_all();
+        result
+    }
+
+    /// 唤醒所有等待者重新检查条件（停止播放等队列以外的变化）
+    pub fn wake(&self) {
+        let _items = self.items.lock().unwrap();
+        self.changed.notify_all();
+    }
+
+    /// blocked(队列长度) 成立时等待，直到条件不成立或超过 WAIT_TIMEOUT；调用方在循环中重新检查
+    pub fn wait_while(&self, blocked: impl Fn(usize) -> bool) {
+        let deadline = Instant::now() + WAIT_TIMEOUT;
+        let mut items = self.items.lock().unwrap();
+        while blocked(items.len()) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return;
+            }
+            items = self.changed.wait_timeout(items, remaining).unwrap().0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_consumer_wakes_when_item_arrives() {
+        let queue = Arc::new(BlockingQueue::new());
+        let consumer_queue = queue.clone();
+        let consumer = thread::spawn(move || {
+            let started = Instant::now();
+            consumer_queue.wait_while(|len| len == 0);
+            (consumer_queue.pop(), started.elapsed())
+        });
+        thread::sleep(Duration::from_millis(30));
+        queue.push(7);
+        let (item, waited) = consumer.join().unwrap();
+        assert_eq!(item, Some(7));
+        // 入队时立即唤醒，不等到超时
+        assert!(waited < WAIT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_producer_blocks_until_below_limit() {
+        let queue = Arc::new(BlockingQueue::new());
+        for i in 0..4 {
+            queue.push(i);
+        }
+        let producer_queue = queue.clone();
+        let producer = thread::spawn(move || {
+            while producer_queue.len() >= 4 {
+                producer_queue.wait_while(|len| len >= 4);
+            }
+            producer_queue.push(4);
+        });
+        thread::sleep(Duration::from_millis(30));
+        assert_eq!(queue.len(), 4);
+        assert_eq!(queue.pop(), Some(0));
+        producer.join().unwrap();
+        assert_eq!(queue.with_items(|items| items.iter().copied().collect::<Vec<_>>()), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_wake_releases_waiter_for_external_condition() {
+        let queue = Arc::new(BlockingQueue::<i32>::new());
+        let running = Arc::new(AtomicBool::new(true));
+        let (waiter_queue, waiter_running) = (queue.clone(), running.clone());
+        let waiter = thread::spawn(move || {
+            let started = Instant::now();
+            waiter_queue.wait_while(|len| len == 0 && waiter_running.load(Ordering::SeqCst));
+            started.elapsed()
+        });
+        thread::sleep(Duration::from_millis(30));
+        running.store(false, Ordering::SeqCst);
+        queue.wake();
+        assert!(waiter.join().unwrap() < WAIT_TIMEOUT);
+        assert_eq!(queue.clear(), 0);
+    }
+}
@@ -0,0 +1,173 @@
+// 音频设备时钟偏差补偿
+//
+// 播放时钟按墙上时间推进，音频设备按自己的采样时钟消耗样本。廉价 USB 声卡的实际采样率可能比标称值
+// 慢 0.05% 左右，两小时后声音会落后画面数秒。音频输出回调累计设备消耗的样本帧数（包括缓冲区空时输出的静音）
+// 并记录回调时刻，这里对滑动 60 秒窗口内的 (回调时刻, 消耗帧数) 做线性回归，得到设备的实际速率，偏差以 ppm 表示。
+// 播放时钟的推进速率按偏差修正，音频仍然是主时钟，画面和外挂字幕跟随音频。
+// 修正量限制在 ±2000 ppm（0.2%）以内，每秒最多变化 50 ppm，平滑收敛；窗口不足 10 秒时不修正。
+
+use std::collections::VecDeque;
+
+/// 测量窗口（秒）
+const WINDOW_SECS: f64 = 60.0;
+
+/// 开始修正前至少需要的测量时长（秒）
+const MIN_WINDOW_SECS: f64 = 10.0;
+
+/// 两个测量点之间的最小间隔（秒，回调按周期批量消耗样本，过密的点没有意义）
+const SAMPLE_INTERVAL_SECS: f64 = 0.25;
+
+/// 修正量上限（ppm）
+pub const MAX_CORRECTION_PPM: f64 = 2000.0;
+
+/// 修正量每秒最多变化（ppm）
+const SLEW_PPM_PER_SEC: f64 = 50.0;
+
+/// 音频设备时钟偏差（负值表示设备比标称采样率慢）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioClockSkew {
+    /// 测量到的偏差（ppm，测量时长不足时为 None）
+    pub measured_ppm: Option<f64>,
+    /// 播放时钟当前使用的修正量（ppm）
+    pub applied_ppm: f64,
+}
+
+/// 设备速率估计（输入墙上时间和设备消耗的帧数，输出时钟修正量）
+#[derive(Debug, Clone)]
+pub struct SkewEstimator {
+    nominal_rate: f64,
+    samples: VecDeque<(f64, f64)>,  // (墙上时间秒, 消耗帧数)
+    measured_ppm: Option<f64>,
+    applied_ppm: f64,
+    last_update: Option<f64>,
+}
+
+impl SkewEstimator {
+    /// nominal_rate 为音频输出的标称采样率
+    pub fn new(nominal_rate: u32) -> Self {
+        Self {
+            nominal_rate: nominal_rate.max(1) as f64,
+            samples: VecDeque::new(),
+            measured_ppm: None,
+            applied_ppm: 0.0,
+            last_update: None,
+        }
+    }
+
+    /// 记录一次测量（wall_secs 单调递增），返回修正后的时钟偏差（ppm）
+    pub fn observe(&mut self, wall_secs: f64, device_frames: u64) -> f64 {
+        let is_due = !self.samples.back().is_some_and(|&(last, _)| wall_secs - last < SAMPLE_INTERVAL_SECS);
+        if is_due {
+            self.samples.push_back((wall_secs, device_frames as f64));
+            while self.samples.front().is_some_and(|&(t, _)| wall_secs - t > WINDOW_SECS) {
+                self.samples.pop_front();
+            }
+            self.measured_ppm = self.regress();
+        }
+
+        // 修正量按限速向测量值靠拢
+        let dt = self.last_update.map_or(0.0, |last| (wall_secs - last).max(0.0));
+        self.last_update = Some(wall_secs);
+        if let Some(target) = self.measured_ppm {
+            let target = target.clamp(-MAX_CORRECTION_PPM, MAX_CORRECTION_PPM);
+            let max_step = SLEW_PPM_PER_SEC * dt;
+            self.applied_ppm += (target - self.applied_ppm).clamp(-max_step, max_step);
+        }
+        self.applied_ppm
+    }
+
+    /// 当前的测量值和修正量
+    pub fn skew(&self) -> AudioClockSkew {
+        AudioClockSkew { measured_ppm: self.measured_ppm, applied_ppm: self.applied_ppm }
+    }
+
+    /// 窗口内消耗帧数对墙上时间的最小二乘斜率，换算为相对标称采样率的偏差
+    fn regress(&self) -> Option<f64> {
+        let (&(first, _), &(last, _)) = (self.samples.front()?, self.samples.back()?);
+        if last - first < MIN_WINDOW_SECS {
+            return None;
+        }
+        let n = self.samples.len() as f64;
+        let mean_t = self.samples.iter().map(|&(t, _)| t - first).sum::<f64>() / n;
+        let mean_f = self.samples.iter().map(|&(_, f)| f).sum::<f64>() / n;
+        let (mut cov, mut var) = (0.0, 0.0);
+        for &(t, f) in &self.samples {
+            let dt = t - first - mean_t;
+            cov += dt * (f - mean_f);
+            var += dt * dt;
+        }
+        if var <= 0.0 {
+            return None;
+        }
+        let rate = cov / var;
+        Some((rate / self.nominal_rate - 1.0) * 1e6)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 模拟 10 分钟播放：设备按 10ms 周期批量消耗样本，界面每 1/60 秒读取最近一次回调的进度并更新时钟
+    /// 返回 (结束时的音画偏差毫秒, 估计器)
+    fn simulate(skew_ppm: f64, minutes: f64, compensate: bool) -> (f64, SkewEstimator) {
+        const RATE: u32 = 48_000;
+        const PERIOD_FRAMES: u64 = 480;
+        let device_rate = RATE as f64 * (1.0 + skew_ppm * 1e-6);
+        let mut estimator = SkewEstimator::new(RATE);
+        let step = 1.0 / 60.0;
+        let steps = (minutes * 60.0 / step) as u64;
+
+        let mut video_clock = 0.0;
+        let mut applied = 0.0;
+        let mut previous_applied = 0.0;
+        for i in 1..=steps {
+            let wall = i as f64 * step;
+            // 最近一次回调：已消耗的帧数和回调时刻
+            let consumed = (wall * device_rate) as u64 / PERIOD_FRAMES * PERIOD_FRAMES;
+            let callback_at = consumed as f64 / device_rate;
+            video_clock += step * (1.0 + applied * 1e-6);
+            if compensate {
+                applied = estimator.observe(callback_at, consumed);
+                // 修正量的变化受限速约束
+                assert!((applied - previous_applied).abs() <= SLEW_PPM_PER_SEC * 2.0 * step);
+                previous_applied = applied;
+            }
+        }
+        let wall = steps as f64 * step;
+        let audio_position = wall * device_rate / RATE as f64;
+        ((video_clock - audio_position) * 1000.0, estimator)
+    }
+
+    #[test]
+    fn test_compensates_known_device_skew() {
+        // 不修正：500ppm 的设备 10 分钟后画面领先约 300ms
+        let (drift, _) = simulate(-500.0, 10.0, false);
+        assert!(drift > 250.0, "drift = {drift}");
+
+        let (drift, estimator) = simulate(-500.0, 10.0, true);
+        assert!(drift.abs() < 20.0, "修正后仍有 {drift:.1}ms 偏差");
+        let skew = estimator.skew();
+        let measured = skew.measured_ppm.unwrap();
+        assert!((measured + 500.0).abs() < 10.0, "measured = {measured}");
+        assert!((skew.applied_ppm + 500.0).abs() < 10.0, "applied = {}", skew.applied_ppm);
+    }
+
+    #[test]
+    fn test_waits_for_window_and_clamps_correction() {
+        let mut estimator = SkewEstimator::new(48_000);
+        for i in 0..=36 {
+            let t = i as f64 * 0.25;
+            assert_eq!(estimator.observe(t, (t * 48_000.0 * 0.99) as u64), 0.0);
+        }
+        assert_eq!(estimator.skew().measured_ppm, None);
+
+        // 偏差 -10000ppm 远超上限：修正量最多到 -2000ppm
+        for i in 37..=2000 {
+            let t = i as f64 * 0.25;
+            estimator.observe(t, (t * 48_000.0 * 0.99) as u64);
+        }
+        assert!(estimator.skew().measured_ppm.unwrap() < -9000.0);
+        assert!((estimator.skew().applied_ppm + MAX_CORRECTION_PPM).abs() < 1e-6);
+    }
+}
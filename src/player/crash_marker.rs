@@ -0,0 +1,173 @@
+// 解码崩溃标记（解码线程 panic 时记录文件，下次打开该文件时提示以安全模式打开）
+//
+// 解码线程启动时进入 DecodeScope（线程局部变量记录正在解码的文件）。panic 钩子在 panic 的线程上运行，
+// 据此把文件写入崩溃标记：release 构建中 panic 会直接中止进程，钩子是唯一的记录机会；
+// 可以展开的构建中线程结束、进程继续运行，界面通过 take_decode_crash 立即提示。
+// 标记保存在用户数据目录的 crash_markers.json 中，用户选择继续以正常模式播放时清除。
+
+use crate::core::{user_data_dir, PlayerError, Result};
+use crate::player::position_history::write_atomic;
+use log::{error, info};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+thread_local! {
+    /// 当前线程正在解码的文件
+    static DECODING: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// 最近一次解码线程崩溃的文件（等待界面取走）
+static LAST_CRASH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// 崩溃标记（按文件路径）
+#[derive(Debug, Clone)]
+pub struct CrashMarkers {
+    file: PathBuf,
+}
+
+impl CrashMarkers {
+    /// 使用指定的标记文件
+    pub fn new(file: PathBuf) -> Self {
+        Self { file }
+    }
+
+    /// 默认保存位置（用户数据目录）
+    pub fn default_file() -> PathBuf {
+        user_data_dir().join("crash_markers.json")
+    }
+
+    /// 该文件是否有崩溃标记
+    pub fn contains(&self, media: &Path) -> bool {
+        self.load().contains(&key(media))
+    }
+
+    /// 记录崩溃
+    pub fn record(&self, media: &Path) -> Result<()> {
+        let mut markers = self.load();
+        if markers.insert(key(media)) {
+            self.save(&markers)?;
+        }
+        Ok(())
+    }
+
+    /// 清除标记
+    pub fn clear(&self, media: &Path) -> Result<()> {
+        let mut markers = self.load();
+        if markers.remove(&key(media)) {
+            self.save(&markers)?;
+        }
+        Ok(())
+    }
+
+    /// 读取标记（文件不存在或损坏时为空）
+    fn load(&self) -> BTreeSet<String> {
+        fs::read(&self.file)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, markers: &BTreeSet<String>) -> Result<()> {
+        let json = serde_json::to_vec_pretty(markers).map_err(|e| PlayerError::Other(format!("序列化崩溃标记失败: {}", e)))?;
+        write_atomic(&self.file, &json)
+    }
+}
+
+fn key(media: &Path) -> String {
+    media.to_string_lossy().into_owned()
+}
+
+/// 解码线程的崩溃记录范围（离开时清除）
+pub struct DecodeScope(());
+
+impl DecodeScope {
+    /// 当前线程开始解码 media（None 表示不记录，如没有文件路径的来源）
+    pub fn enter(media: Option<PathBuf>) -> Self {
+        DECODING.with(|decoding| *decoding.borrow_mut() = media);
+        Self(())
+    }
+}
+
+impl Drop for DecodeScope {
+    fn drop(&mut self) {
+        // 线程因 panic 展开时也会执行：崩溃记录在此之前已由钩子写入
+        let _ = DECODING.try_with(|decoding| decoding.borrow_mut().take());
+    }
+}
+
+/// 安装 panic 钩子：解码线程 panic 时记录崩溃标记（之后交给原来的钩子）
+pub fn install_panic_hook(markers: CrashMarkers) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let media = DECODING
+            .try_with(|decoding| decoding.try_borrow().ok().and_then(|media| media.clone()))
+            .ok()
+            .flatten();
+        if let Some(media) = media {
+            error!("💥 解码线程崩溃: {}", media.display());
+            if let Err(e) = markers.record(&media) {
+                error!("记录崩溃标记失败: {}", e);
+            }
+            if let Ok(mut last) = LAST_CRASH.lock() {
+                *last = Some(media);
+            }
+        }
+        previous(info);
+    }));
+    info!("🛡️ 已安装解码崩溃记录");
+}
+
+/// 取出最近一次解码线程崩溃的文件
+pub fn take_decode_crash() -> Option<PathBuf> {
+    LAST_CRASH.lock().unwrap().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_markers(name: &str) -> CrashMarkers {
+        let dir = std::env::temp_dir().join(format!("myy_crash_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        CrashMarkers::new(dir.join("crash_markers.json"))
+    }
+
+    #[test]
+    fn test_markers_round_trip() {
+        let markers = temp_markers("round_trip");
+        let media = Path::new("/videos/broken.mkv");
+        assert!(!markers.contains(media));
+        markers.record(media).unwrap();
+        markers.record(media).unwrap();
+        assert!(markers.contains(media));
+        assert!(!markers.contains(Path::new("/videos/fine.mkv")));
+        markers.clear(media).unwrap();
+        assert!(!markers.contains(media));
+    }
+
+    #[test]
+    fn test_panic_in_decode_scope_records_marker() {
+        let markers = temp_markers("hook");
+        install_panic_hook(markers.clone());
+        let media = PathBuf::from("/videos/crashes_decoder.mp4");
+
+        // 解码范围外的 panic 不记录
+        let result = std::thread::spawn(|| panic!("测试：普通线程 panic")).join();
+        assert!(result.is_err());
+        assert!(!markers.contains(&media));
+
+        let scoped = media.clone();
+        let result = std::thread::spawn(move || {
+            let _scope = DecodeScope::enter(Some(scoped));
+            panic!("测试：解码线程 panic");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(markers.contains(&media));
+        assert_eq!(take_decode_crash(), Some(media));
+        assert_eq!(take_decode_crash(), None);
+    }
+}
@@ -0,0 +1,172 @@
+// 调试命令（开发者面板中复现音画同步问题的手动操作）
+//
+// 清空帧队列、时钟跳变由播放管理器直接执行；强制 flush 解码器、模拟解封装停滞、
+// 逐帧解码延迟需要在对应线程里执行，通过命令通道送到解封装 / 解码线程。
+// 各线程只在调试模式开启时才读取通道：未开启时 poll() 只读取一个原子标志。
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// 模拟解封装停滞的时长
+pub const DEMUX_STALL: Duration = Duration::from_secs(2);
+
+/// 逐帧解码延迟上限
+pub const MAX_DECODE_DELAY: Duration = Duration::from_millis(100);
+
+/// 调试命令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugCommand {
+    /// 清空视频帧队列
+    FlushVideoQueue,
+    /// 清空音频帧队列
+    FlushAudioQueue,
+    /// 播放时钟跳变（毫秒，正数向前）
+    ClockJump(i64),
+    /// 强制 flush 解码器（视频和音频）
+    FlushDecoders,
+    /// 解封装线程停止读包一段时间
+    StallDemuxer(Duration),
+    /// 解码线程每解码一个包额外等待的时间（0 为关闭）
+    DecodeDelay(Duration),
+}
+
+/// 命令的执行线程
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugTarget {
+    Demuxer,
+    VideoDecoder,
+    AudioDecoder,
+}
+
+impl DebugCommand {
+    /// 需要送到哪些线程执行（由播放管理器直接执行的命令为空）
+    pub fn targets(&self) -> &'static [DebugTarget] {
+        match self {
+            Self::FlushVideoQueue | Self::FlushAudioQueue | Self::ClockJump(_) => &[],
+            Self::StallDemuxer(_) => &[DebugTarget::Demuxer],
+            Self::FlushDecoders | Self::DecodeDelay(_) => &[DebugTarget::VideoDecoder, DebugTarget::AudioDecoder],
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Routes {
+    ports: Vec<(DebugTarget, Sender<DebugCommand>)>,
+    decode_delay: Duration,  // 新管线的解码线程沿用当前延迟
+}
+
+/// 调试命令分发（可克隆，克隆之间共享开关和通道）
+#[derive(Debug, Clone, Default)]
+pub struct DebugCommands {
+    enabled: Arc<AtomicBool>,
+    routes: Arc<Mutex<Routes>>,
+}
+
+impl DebugCommands {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// 开启/关闭调试模式（关闭后线程不再读取通道，逐帧解码延迟也不再生效）
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// 当前逐帧解码延迟
+    pub fn decode_delay(&self) -> Duration {
+        self.routes.lock().unwrap().decode_delay
+    }
+
+    /// 为一个线程创建命令接收端（每次启动管线时调用）
+    pub fn port(&self, target: DebugTarget) -> DebugPort {
+        let (tx, rx) = unbounded();
+        let mut routes = self.routes.lock().unwrap();
+        routes.ports.push((target, tx));
+        DebugPort { enabled: self.enabled.clone(), rx, decode_delay: routes.decode_delay }
+    }
+
+    /// 把命令送到对应线程，返回送达的线程数（已退出线程的通道顺便移除）
+    pub fn send(&self, command: DebugCommand) -> usize {
+        let mut routes = self.routes.lock().unwrap();
+        if let DebugCommand::DecodeDelay(delay) = command {
+            routes.decode_delay = delay;
+        }
+        let targets = command.targets();
+        let mut delivered = 0;
+        routes.ports.retain(|(target, tx)| {
+            if !targets.contains(target) {
+                return true;
+            }
+            let alive = tx.send(command).is_ok();
+            delivered += usize::from(alive);
+            alive
+        });
+        delivered
+    }
+}
+
+/// 单个线程的命令接收端
+#[derive(Debug)]
+pub struct DebugPort {
+    enabled: Arc<AtomicBool>,
+    rx: Receiver<DebugCommand>,
+    decode_delay: Duration,
+}
+
+impl DebugPort {
+    /// 取出下一条命令（调试模式关闭时直接返回 None，不读取通道）
+    pub fn poll(&mut self) -> Option<DebugCommand> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+        let command = self.rx.try_recv().ok()?;
+        if let DebugCommand::DecodeDelay(delay) = command {
+            self.decode_delay = delay;
+        }
+        Some(command)
+    }
+
+    /// 逐帧解码延迟（调试模式关闭后不再生效）
+    pub fn delay_frame(&self) {
+        if !self.decode_delay.is_zero() && self.enabled.load(Ordering::Relaxed) {
+            thread::sleep(self.decode_delay);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_reach_only_their_threads() {
+        let commands = DebugCommands::default();
+        let mut demuxer = commands.port(DebugTarget::Demuxer);
+        let mut video = commands.port(DebugTarget::VideoDecoder);
+        let mut audio = commands.port(DebugTarget::AudioDecoder);
+
+        // 调试模式关闭时线程读不到命令
+        assert_eq!(commands.send(DebugCommand::StallDemuxer(DEMUX_STALL)), 1);
+        assert_eq!(demuxer.poll(), None);
+        commands.set_enabled(true);
+        assert_eq!(demuxer.poll(), Some(DebugCommand::StallDemuxer(DEMUX_STALL)));
+        assert_eq!(video.poll(), None);
+
+        assert_eq!(commands.send(DebugCommand::FlushDecoders), 2);
+        assert_eq!(commands.send(DebugCommand::ClockJump(500)), 0);
+        assert_eq!(video.poll(), Some(DebugCommand::FlushDecoders));
+        assert_eq!(audio.poll(), Some(DebugCommand::FlushDecoders));
+        assert_eq!(audio.poll(), None);
+
+        // 线程退出后通道被移除；新线程沿用当前解码延迟
+        let delay = Duration::from_millis(40);
+        drop(video);
+        assert_eq!(commands.send(DebugCommand::DecodeDelay(delay)), 1);
+        assert_eq!(commands.port(DebugTarget::VideoDecoder).decode_delay, delay);
+        assert_eq!(audio.poll(), Some(DebugCommand::DecodeDelay(delay)));
+        assert_eq!(audio.decode_delay, delay);
+    }
+}
@@ -0,0 +1,137 @@
+// 解码器中途失效时的降级播放
+//
+// 损坏的音轨（如 E-AC3 中的一段坏数据）可能让解码器对之后的每个数据包都返回错误。解码线程统计
+// 连续的致命错误（EAGAIN / EOF 不计入，成功解码一次即清零），达到上限后判定该轨道的解码器失效：
+// 线程不再解码，只取出并丢弃数据包（解封装线程不会因队列满而阻塞），并通知管理器。
+//
+// - 音频失效：释放音频输出，时钟按墙上时间继续推进（不再由音频帧校准），视频和字幕照常播放，
+//   界面提示"音频解码失败，已切换为无声播放"，有其他音轨时可一键切换
+// - 视频失效：停止视频解码，画面停在最后呈现的一帧，音频继续播放（与视频轨道损坏的处理相同）
+//
+// 失效状态只对当前管线有效，切换音轨或重新打开文件时重置。
+
+use crate::core::PlayerError;
+use ffmpeg_next as ffmpeg;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 连续多少个数据包解码失败时，认为解码器已失效
+pub const FATAL_DECODE_ERROR_LIMIT: u32 = 25;
+
+/// 是否为致命解码错误（EAGAIN / EOF 是正常的解码器状态；尺寸无效的视频帧由损坏检测单独处理）
+pub fn is_fatal(error: &PlayerError) -> bool {
+    !matches!(
+        error,
+        PlayerError::FFmpegError(ffmpeg::Error::Eof)
+            | PlayerError::FFmpegError(ffmpeg::Error::Other { errno: 11 })
+            | PlayerError::InvalidFrameSize { .. }
+    )
+}
+
+/// 连续致命错误计数（每个解码线程一个）
+#[derive(Debug, Default)]
+pub struct DecodeHealth {
+    consecutive_errors: u32,
+    failed: bool,
+}
+
+impl DecodeHealth {
+    /// 解码成功，清零计数
+    pub fn record_success(&mut self) {
+        self.consecutive_errors = 0;
+    }
+
+    /// 记录一次解码错误；解码器刚被判定失效时返回 true（只返回一次）
+    pub fn record_error(&mut self, error: &PlayerError) -> bool {
+        if self.failed || !is_fatal(error) {
+            return false;
+        }
+        self.consecutive_errors += 1;
+        self.failed = self.consecutive_errors >= FATAL_DECODE_ERROR_LIMIT;
+        self.failed
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.failed
+    }
+}
+
+/// 解码器失效状态（解码线程写入，管理器和界面读取）
+#[derive(Debug, Clone, Default)]
+pub struct DecoderFailure {
+    failed: Arc<AtomicBool>,
+    notice: Arc<AtomicBool>,  // 等待界面提示
+}
+
+impl DecoderFailure {
+    pub fn mark_failed(&self) {
+        self.failed.store(true, Ordering::SeqCst);
+        self.notice.store(true, Ordering::SeqCst);
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::SeqCst)
+    }
+
+    /// 是否刚被判定失效；读取后清除
+    pub fn take_notice(&self) -> bool {
+        self.notice.swap(false, Ordering::SeqCst)
+    }
+
+    /// 新管线启动时重置
+    pub fn reset(&self) {
+        self.failed.store(false, Ordering::SeqCst);
+        self.notice.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corrupt() -> PlayerError {
+        PlayerError::FFmpegError(ffmpeg::Error::InvalidData)
+    }
+
+    #[test]
+    fn test_consecutive_fatal_errors_fail_decoder() {
+        let mut health = DecodeHealth::default();
+        for _ in 1..FATAL_DECODE_ERROR_LIMIT {
+            assert!(!health.record_error(&corrupt()));
+        }
+        // EAGAIN / EOF 不计入
+        assert!(!health.record_error(&PlayerError::FFmpegError(ffmpeg::Error::Other { errno: 11 })));
+        assert!(!health.record_error(&PlayerError::FFmpegError(ffmpeg::Error::Eof)));
+        assert!(!health.has_failed());
+
+        assert!(health.record_error(&PlayerError::DecodeError("bitstream".to_string())));
+        assert!(health.has_failed());
+        // 只通知一次
+        assert!(!health.record_error(&corrupt()));
+    }
+
+    #[test]
+    fn test_success_resets_error_count() {
+        let mut health = DecodeHealth::default();
+        for _ in 0..FATAL_DECODE_ERROR_LIMIT * 3 {
+            for _ in 1..FATAL_DECODE_ERROR_LIMIT {
+                assert!(!health.record_error(&corrupt()));
+            }
+            health.record_success();
+        }
+        assert!(!health.has_failed());
+        assert!(!health.record_error(&PlayerError::InvalidFrameSize { width: 0, height: 0 }));
+    }
+
+    #[test]
+    fn test_failure_notice_is_taken_once() {
+        let failure = DecoderFailure::default();
+        assert!(!failure.take_notice());
+        failure.clone().mark_failed();
+        assert!(failure.has_failed());
+        assert!(failure.take_notice());
+        assert!(!failure.take_notice());
+        failure.reset();
+        assert!(!failure.has_failed());
+    }
+}
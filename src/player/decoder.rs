@@ -1,16 +1,43 @@
-use crate::core::{AudioFrame, PixelFormat, SampleFormat, SubtitleFrame, VideoFrame, Result};
+use crate::core::{AudioFrame, FrameData, FramePool, PixelFormat, PlayerError, SubtitleFrame, VideoFrame, YuvMatrix, YuvPlanes, Result};
+use crate::core::ffi_util::{self, audio_frame_as_f32, decoder_reorder_depth, subtitle_end_time, SubtitleGuard};
+use crate::player::decoder_fallback::SoftwareFallback;
+use crate::player::demuxer::CoverArt;
 use crate::player::hw_decoder::HWVideoDecoder;
+use crate::player::preview;
+use crate::player::tone_map::{self, HdrTransfer};
 use ffmpeg_next as ffmpeg;
 use ffmpeg_next::{codec, format, software, util};
 use log::{debug, error, info, warn};
-use std::ffi::CStr;
-use ffmpeg_next::ffi::AVSubtitleType;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use ffmpeg_next::codec::subtitle::Rect;
 
 /// 视频解码器（支持硬件加速和软件解码）
 pub struct VideoDecoder {
     inner: DecoderType,
+    source: Option<StreamSource>,  // 硬件解码时保留流参数，播放中出错时据此重建软件解码器
+    stats: DecoderStats,
+    pool: Option<Arc<FramePool>>,  // 帧缓冲区池（播放解码使用，预览等一次性解码为 None）
 }
 
+/// 硬件 / 软件解码出的帧数（播放中改用软件解码后两者都可能不为 0）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecoderStats {
+    /// 硬件解码出的帧数
+    pub hardware_frames: u64,
+    /// 软件解码出的帧数
+    pub software_frames: u64,
+}
+
+/// 重建解码器所需的视频流参数
+struct StreamSource {
+    parameters: codec::Parameters,
+    time_base: f64,
+}
+
+// SAFETY: parameters 是从流参数复制出的独立副本（不引用 AVFormatContext），只在持有解码器的线程中访问
+unsafe impl Send for StreamSource {}
+
 /// 解码器内部类型
 enum DecoderType {
     Hardware(HWVideoDecoder),
@@ -22,25 +49,34 @@ struct SoftwareVideoDecoder {
     decoder: codec::decoder::Video,
     scaler: Option<software::scaling::Context>,
     time_base: f64,
+    preview_width: Option<u32>,  // 预览解码：缩放时直接输出不超过该宽度的画面
+    pool: Option<Arc<FramePool>>,
 }
 
 // SwsContext 本身不是 Send，但我们确保只在单个线程中使用它
-// 这是安全的，因为每个解码器实例只会在一个线程中使用
+// SAFETY: 每个解码器实例只会在一个线程中使用，移动到解码线程后不再被其他线程访问
 unsafe impl Send for SoftwareVideoDecoder {}
 
 impl VideoDecoder {
     /// 从视频流创建解码器（自动选择硬件加速，失败则使用软件解码）
     pub fn from_stream(stream: format::stream::Stream) -> Result<Self> {
+        if !hw_decode_enabled() {
+            return Err(PlayerError::DecodeError("硬件解码已在设置中关闭".to_string()));
+        }
         info!("创建视频解码器（优先硬件加速）...");
         
         // 尝试硬件解码
         // 注意：HWVideoDecoder::from_stream_auto 会消耗 stream 的所有权
         // 如果硬件解码失败，我们需要重新获取流
+        let source = StreamSource { parameters: stream.parameters().clone(), time_base: stream_time_base(&stream) };
         match HWVideoDecoder::from_stream_auto(stream) {
             Ok(hw_decoder) => {
                 info!("✓ 使用硬件解码: {}", hw_decoder.info());
                 Ok(Self {
                     inner: DecoderType::Hardware(hw_decoder),
+                    source: Some(source),
+                    stats: DecoderStats::default(),
+                    pool: None,
                 })
             }
             Err(e) => {
@@ -54,25 +90,68 @@ impl VideoDecoder {
     /// 强制使用软件解码
     pub fn from_stream_software(stream: format::stream::Stream) -> Result<Self> {
         info!("创建软件视频解码器...");
-        let sw_decoder = SoftwareVideoDecoder::from_stream(stream)?;
+        let sw_decoder = SoftwareVideoDecoder::from_parameters(stream.parameters(), stream_time_base(&stream))?;
+        Ok(Self {
+            inner: DecoderType::Software(sw_decoder),
+            source: None,
+            stats: DecoderStats::default(),
+            pool: None,
+        })
+    }
+
+    /// 预览解码（软件解码，转换为 RGBA 时直接缩小到 max_width 宽；HDR 画面做亮度近似映射）
+    pub fn from_stream_preview(stream: format::stream::Stream, max_width: u32) -> Result<Self> {
+        debug!("创建预览视频解码器（最大宽度 {}）...", max_width);
+        let mut sw_decoder = SoftwareVideoDecoder::from_parameters(stream.parameters(), stream_time_base(&stream))?;
+        sw_decoder.preview_width = Some(max_width.max(1));
         Ok(Self {
             inner: DecoderType::Software(sw_decoder),
+            source: None,
+            stats: DecoderStats::default(),
+            pool: None,
         })
     }
 
     /// 解码数据包
     pub fn decode(&mut self, packet: &ffmpeg::Packet) -> Result<Vec<VideoFrame>> {
-        match &mut self.inner {
+        let result = match &mut self.inner {
             DecoderType::Hardware(decoder) => decoder.decode(packet),
             DecoderType::Software(decoder) => decoder.decode(packet),
-        }
+        };
+        self.count_frames(&result);
+        result
     }
 
     /// 刷新解码器（获取缓冲的帧）
     pub fn flush(&mut self) -> Result<Vec<VideoFrame>> {
-        match &mut self.inner {
+        let result = match &mut self.inner {
             DecoderType::Hardware(decoder) => decoder.flush(),
             DecoderType::Software(decoder) => decoder.flush(),
+        };
+        self.count_frames(&result);
+        result
+    }
+
+    fn count_frames(&mut self, result: &Result<Vec<VideoFrame>>) {
+        let Ok(frames) = result else {
+            return;
+        };
+        match self.inner {
+            DecoderType::Hardware(_) => self.stats.hardware_frames += frames.len() as u64,
+            DecoderType::Software(_) => self.stats.software_frames += frames.len() as u64,
+        }
+    }
+
+    /// 硬件 / 软件解码出的帧数
+    pub fn stats(&self) -> DecoderStats {
+        self.stats
+    }
+
+    /// 帧重排序深度（解码器为 B 帧缓存的帧数）
+    pub fn reorder_depth(&self) -> usize {
+        match &self.inner {
+            DecoderType::Hardware(decoder) => decoder.reorder_depth(),
+            DecoderType::Software(decoder) => decoder_reorder_depth(&decoder.decoder),
         }
     }
 
@@ -88,19 +167,70 @@ impl VideoDecoder {
     pub fn is_hardware_accelerated(&self) -> bool {
         matches!(self.inner, DecoderType::Hardware(_))
     }
+
+    /// 解码帧的像素缓冲区从池中取（改用软件解码后继续使用同一个池）
+    pub fn set_frame_pool(&mut self, pool: Arc<FramePool>) {
+        match &mut self.inner {
+            DecoderType::Hardware(decoder) => decoder.set_frame_pool(pool.clone()),
+            DecoderType::Software(decoder) => decoder.pool = Some(pool.clone()),
+        }
+        self.pool = Some(pool);
+    }
+}
+
+impl SoftwareFallback for VideoDecoder {
+    fn is_hardware(&self) -> bool {
+        self.is_hardware_accelerated()
+    }
+
+    fn switch_to_software(&mut self) -> Result<()> {
+        let source = self
+            .source
+            .take()
+            .ok_or_else(|| PlayerError::DecodeError("没有可用于重建解码器的流参数".to_string()))?;
+        info!("创建软件视频解码器（替换硬件解码器）...");
+        let mut decoder = SoftwareVideoDecoder::from_parameters(source.parameters, source.time_base)?;
+        decoder.pool = self.pool.clone();
+        self.inner = DecoderType::Software(decoder);
+        Ok(())
+    }
+}
+
+/// 流的时间基（秒）
+fn stream_time_base(stream: &format::stream::Stream) -> f64 {
+    let time_base = stream.time_base();
+    time_base.numerator() as f64 / time_base.denominator() as f64
+}
+
+/// 一次性解码内嵌封面为 RGBA 帧
+pub fn decode_cover_art(cover: &CoverArt) -> Result<VideoFrame> {
+    let codec = codec::decoder::find(cover.codec_id)
+        .ok_or_else(|| PlayerError::DecodeError(format!("不支持的封面格式: {:?}", cover.codec_id)))?;
+    let decoder = codec::context::Context::new_with_codec(codec).decoder().video()?;
+    let mut decoder = SoftwareVideoDecoder {
+        decoder,
+        scaler: None,
+        time_base: 0.0,
+        preview_width: None,
+        pool: None,
+    };
+
+    let mut frames = decoder.decode(&ffmpeg::Packet::copy(&cover.data))?;
+    frames.extend(decoder.flush()?);
+    frames
+        .into_iter()
+        .next()
+        .ok_or_else(|| PlayerError::DecodeError("封面解码没有输出画面".to_string()))
 }
 
 // ============= 软件解码器实现 =============
 
 impl SoftwareVideoDecoder {
-    /// 从视频流创建软件解码器
-    fn from_stream(stream: format::stream::Stream) -> Result<Self> {
-        let context = codec::context::Context::from_parameters(stream.parameters())?;
+    /// 从视频流参数创建软件解码器
+    fn from_parameters(parameters: codec::Parameters, time_base: f64) -> Result<Self> {
+        let context = codec::context::Context::from_parameters(parameters)?;
         let decoder = context.decoder().video()?;
 
-        let time_base = stream.time_base();
-        let time_base = time_base.numerator() as f64 / time_base.denominator() as f64;
-
         debug!(
             "软件解码器: {}x{}, 格式: {:?}",
             decoder.width(),
@@ -112,12 +242,15 @@ impl SoftwareVideoDecoder {
             decoder,
             scaler: None,
             time_base,
+            preview_width: None,
+            pool: None,
         })
     }
 
     /// 解码数据包
     fn decode(&mut self, packet: &ffmpeg::Packet) -> Result<Vec<VideoFrame>> {
         let mut frames = Vec::new();
+        let mut rejected = None;
 
         match self.decoder.send_packet(packet) {
             Ok(()) => {}
@@ -132,11 +265,7 @@ impl SoftwareVideoDecoder {
         loop {
             let mut decoded_frame = util::frame::Video::empty();
             match self.decoder.receive_frame(&mut decoded_frame) {
-                Ok(_) => {
-                    if let Some(frame) = self.convert_frame(decoded_frame)? {
-                        frames.push(frame);
-                    }
-                }
+                Ok(_) => collect_converted(self.convert_frame(decoded_frame), &mut frames, &mut rejected)?,
                 Err(ffmpeg::Error::Other { errno: 11 }) => break, // EAGAIN
                 Err(ffmpeg::Error::Eof) => break,
                 Err(e) => {
@@ -147,23 +276,20 @@ impl SoftwareVideoDecoder {
             }
         }
 
-        Ok(frames)
+        finish_decode(frames, rejected)
     }
 
     /// 刷新解码器
     fn flush(&mut self) -> Result<Vec<VideoFrame>> {
         let mut frames = Vec::new();
+        let mut rejected = None;
 
         self.decoder.send_eof()?;
 
         loop {
             let mut decoded_frame = util::frame::Video::empty();
             match self.decoder.receive_frame(&mut decoded_frame) {
-                Ok(_) => {
-                    if let Some(frame) = self.convert_frame(decoded_frame)? {
-                        frames.push(frame);
-                    }
-                }
+                Ok(_) => collect_converted(self.convert_frame(decoded_frame), &mut frames, &mut rejected)?,
                 Err(_) => break,
             }
         }
@@ -173,59 +299,268 @@ impl SoftwareVideoDecoder {
         Ok(frames)
     }
 
-    /// 转换帧格式为 RGBA
+    /// 转换帧格式（显示用 YUV 或 RGBA，预览为缩小的 RGBA）
     fn convert_frame(&mut self, frame: util::frame::Video) -> Result<Option<VideoFrame>> {
-        let width = frame.width();
-        let height = frame.height();
-
-        // 初始化 scaler（YUV -> RGBA）
-        if self.scaler.is_none() {
-            self.scaler = Some(
-                software::scaling::Context::get(
-                    frame.format(),
-                    width,
-                    height,
-                    util::format::Pixel::RGBA,
-                    width,
-                    height,
-                    software::scaling::Flags::BILINEAR,
-                )?,
-            );
+        let Some(max_width) = self.preview_width else {
+            return convert_for_display(&mut self.scaler, &frame, self.time_base, self.pool.as_ref()).map(Some);
+        };
+        let mut converted = convert_to_rgba_scaled(&mut self.scaler, &frame, self.time_base, Some(max_width), None)?;
+        if frame.color_transfer_characteristic() == util::color::TransferCharacteristic::SMPTE2084 {
+            preview::tone_map_pq_luma(&mut converted);
         }
+        Ok(Some(converted))
+    }
+}
 
-        let mut rgba_frame = util::frame::Video::empty();
-        self.scaler.as_mut().unwrap().run(&frame, &mut rgba_frame)?;
+/// 是否优先使用硬件解码（所有会话共享，关闭时 from_stream 直接失败，调用方回退到软件解码）
+static HW_DECODE_ENABLED: AtomicBool = AtomicBool::new(true);
 
-        // 计算 PTS（毫秒）
-        let pts = if let Some(timestamp) = frame.timestamp() {
-            (timestamp as f64 * self.time_base * 1000.0) as i64
-        } else {
-            0
-        };
+/// 开启/关闭硬件解码（对之后创建的解码器生效）
+pub fn set_hw_decode_enabled(enabled: bool) {
+    HW_DECODE_ENABLED.store(enabled, Ordering::Relaxed);
+}
 
-        // 复制数据到连续内存
-        let data_size = (width * height * 4) as usize;
-        let mut data = vec![0u8; data_size];
+pub fn hw_decode_enabled() -> bool {
+    HW_DECODE_ENABLED.load(Ordering::Relaxed)
+}
+
+/// 渲染器是否接受 YUV 帧（所有会话共享；关闭时解码器始终输出 RGBA）
+static YUV_OUTPUT_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 开启/关闭 YUV 输出（GPU 渲染器可用时开启，颜色转换由着色器完成；对之后解码的帧生效）
+pub fn set_yuv_output_enabled(enabled: bool) {
+    YUV_OUTPUT_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn yuv_output_enabled() -> bool {
+    YUV_OUTPUT_ENABLED.load(Ordering::Relaxed)
+}
+
+/// 帧尺寸下限（每个方向，像素）
+pub const MIN_FRAME_DIMENSION: u32 = 16;
+
+/// 帧尺寸上限的默认值（每个方向，像素）
+pub const DEFAULT_MAX_FRAME_DIMENSION: u32 = 8192;
+
+/// 当前的帧尺寸上限（所有解码器共享）
+static MAX_FRAME_DIMENSION: AtomicU32 = AtomicU32::new(DEFAULT_MAX_FRAME_DIMENSION);
+
+/// 修改帧尺寸上限（不低于下限）
+pub fn set_max_frame_dimension(max: u32) {
+    MAX_FRAME_DIMENSION.store(max.max(MIN_FRAME_DIMENSION), Ordering::Relaxed);
+}
 
-        let stride = rgba_frame.stride(0);
-        let frame_data = rgba_frame.data(0);
+/// 当前的帧尺寸上限
+pub fn max_frame_dimension() -> u32 {
+    MAX_FRAME_DIMENSION.load(Ordering::Relaxed)
+}
+
+/// 检查帧（或视频流）的尺寸：损坏的文件可能报告 0x0 或 65535x65535 这样的尺寸
+pub fn validate_frame_dimensions(width: u32, height: u32) -> Result<()> {
+    let range = MIN_FRAME_DIMENSION..=max_frame_dimension();
+    if range.contains(&width) && range.contains(&height) {
+        Ok(())
+    } else {
+        Err(PlayerError::InvalidFrameSize { width, height })
+    }
+}
 
-        for y in 0..height as usize {
-            let src_offset = y * stride;
-            let dst_offset = y * (width as usize * 4);
-            let row_size = width as usize * 4;
-            data[dst_offset..dst_offset + row_size]
-                .copy_from_slice(&frame_data[src_offset..src_offset + row_size]);
+/// 把解码帧转换为 RGBA（软件/硬件解码器共用）
+///
+/// 尺寸无效的帧在创建 scaler 和分配内存之前就被拒绝。
+#[cfg(test)]
+pub(crate) fn convert_to_rgba(
+    scaler: &mut Option<software::scaling::Context>,
+    frame: &util::frame::Video,
+    time_base: f64,
+) -> Result<VideoFrame> {
+    convert_to_rgba_scaled(scaler, frame, time_base, None, None)
+}
+
+/// 把解码帧转换为显示用的帧：开启 YUV 输出且格式支持时直接复制 YUV 平面，否则转换为 RGBA（pool 不为 None 时像素缓冲区从中取）
+pub(crate) fn convert_for_display(
+    scaler: &mut Option<software::scaling::Context>,
+    frame: &util::frame::Video,
+    time_base: f64,
+    pool: Option<&Arc<FramePool>>,
+) -> Result<VideoFrame> {
+    if yuv_output_enabled() {
+        validate_frame_dimensions(frame.width(), frame.height())?;
+        if let Some(converted) = copy_yuv_planes(frame, time_base, pool) {
+            return Ok(converted);
         }
+    }
+    convert_to_rgba_scaled(scaler, frame, time_base, None, pool)
+}
 
-        Ok(Some(VideoFrame {
-            pts,
-            duration: 0,
+/// 复制 YUV420P / NV12 帧的平面（其他格式、HDR 以及 BT.601 / BT.709 以外的色彩矩阵返回 None，走 RGBA 转换）
+fn copy_yuv_planes(frame: &util::frame::Video, time_base: f64, pool: Option<&Arc<FramePool>>) -> Option<VideoFrame> {
+    use util::color::{Range, Space};
+    let (format, full_range) = match frame.format() {
+        util::format::Pixel::YUV420P => (PixelFormat::YUV420P, frame.color_range() == Range::JPEG),
+        util::format::Pixel::YUVJ420P => (PixelFormat::YUV420P, true),
+        util::format::Pixel::NV12 => (PixelFormat::NV12, frame.color_range() == Range::JPEG),
+        _ => return None,
+    };
+    if HdrTransfer::detect(frame.color_transfer_characteristic()).is_some() {
+        return None;
+    }
+    let (width, height) = (frame.width(), frame.height());
+    let matrix = match frame.color_space() {
+        Space::BT709 => YuvMatrix::Bt709,
+        Space::BT470BG | Space::SMPTE170M => YuvMatrix::Bt601,
+        Space::Unspecified => YuvMatrix::guess(width, height),
+        _ => return None,
+    };
+    let chroma_left = !matches!(
+        frame.chroma_location(),
+        util::chroma::Location::Center | util::chroma::Location::Top | util::chroma::Location::Bottom
+    );
+
+    let plane_count = if format == PixelFormat::NV12 { 2 } else { 3 };
+    let (_, chroma_height) = YuvPlanes::chroma_size(width, height);
+    let mut offsets = [0; 3];
+    let mut strides = [0; 3];
+    let mut planes = [&[][..]; 3];
+    let mut len = 0;
+    for index in 0..plane_count {
+        let rows = if index == 0 { height } else { chroma_height } as usize;
+        let stride = frame.stride(index);
+        planes[index] = frame.data(index).get(..stride.checked_mul(rows)?)?;
+        offsets[index] = len;
+        strides[index] = stride;
+        len += planes[index].len();
+    }
+    let mut data = pool.map_or_else(|| vec![0u8; len], |pool| pool.acquire(len));
+    for (plane, offset) in planes.iter().zip(offsets).take(plane_count) {
+        data[offset..offset + plane.len()].copy_from_slice(plane);
+    }
+
+    Some(VideoFrame {
+        pts: frame_pts_ms(frame, time_base),
+        duration: 0,
+        width,
+        height,
+        format,
+        planes: Some(YuvPlanes { offsets, strides, matrix, full_range, chroma_left }),
+        data: FrameData::pooled(data, pool),
+    })
+}
+
+/// 帧的显示时间戳（毫秒，没有时间戳时为 0）
+fn frame_pts_ms(frame: &util::frame::Video, time_base: f64) -> i64 {
+    frame.timestamp().map_or(0, |timestamp| (timestamp as f64 * time_base * 1000.0) as i64)
+}
+
+/// 把解码帧转换为 RGBA，max_width 不为 None 时同时缩小到该宽度以内（预览解码）
+///
+/// HDR (PQ / HLG) 画面先转换为 16-bit RGB 再色调映射（预览解码除外，预览只做近似的亮度压缩）
+fn convert_to_rgba_scaled(
+    scaler: &mut Option<software::scaling::Context>,
+    frame: &util::frame::Video,
+    time_base: f64,
+    max_width: Option<u32>,
+    pool: Option<&Arc<FramePool>>,
+) -> Result<VideoFrame> {
+    validate_frame_dimensions(frame.width(), frame.height())?;
+    let (width, height) = match max_width {
+        Some(max_width) => preview::scaled_size(frame.width(), frame.height(), max_width),
+        None => (frame.width(), frame.height()),
+    };
+    let flags = if width < frame.width() { software::scaling::Flags::AREA } else { software::scaling::Flags::BILINEAR };
+    let hdr = HdrTransfer::detect(frame.color_transfer_characteristic()).filter(|_| max_width.is_none());
+    let output_format = if hdr.is_some() { util::format::Pixel::RGBA64LE } else { util::format::Pixel::RGBA };
+
+    // 初始化 scaler（YUV -> RGBA；流中途切换 HDR / SDR 时重建）
+    if scaler.as_ref().is_some_and(|scaler| scaler.output().format != output_format) {
+        *scaler = None;
+    }
+    if scaler.is_none() {
+        let mut context = software::scaling::Context::get(
+            frame.format(),
+            frame.width(),
+            frame.height(),
+            output_format,
             width,
             height,
-            format: PixelFormat::RGBA,
-            data,
-        }))
+            flags,
+        )?;
+        if let Some(hdr) = hdr {
+            info!("🎨 HDR 画面（{}），色调映射到 SDR", hdr.label());
+            if let Err(e) = ffi_util::set_bt2020_colorspace(&mut context, frame.color_range() == util::color::Range::JPEG) {
+                warn!("⚠️ 无法设置 BT.2020 色彩矩阵，HDR 画面颜色可能偏差: {}", e);
+            }
+        }
+        *scaler = Some(context);
+    }
+
+    let mut rgba_frame = util::frame::Video::empty();
+    scaler.as_mut().unwrap().run(frame, &mut rgba_frame)?;
+
+    Ok(VideoFrame {
+        pts: frame_pts_ms(frame, time_base),
+        duration: 0,
+        width,
+        height,
+        format: PixelFormat::RGBA,
+        planes: None,
+        data: FrameData::pooled(
+            match hdr {
+                Some(hdr) => tone_map::tone_map_rgba64(hdr, rgba_frame.data(0), rgba_frame.stride(0), width, height, pool.map(Arc::as_ref)),
+                None => copy_rgba_plane(rgba_frame.data(0), rgba_frame.stride(0), width, height, pool.map(Arc::as_ref)),
+            },
+            pool,
+        ),
+    })
+}
+
+/// 把 RGBA 平面复制到连续内存（不超出 FFmpeg 实际给出的平面数据，缺失的部分为 0；pool 不为 None 时从中取缓冲区）
+///
+/// 行宽正好是 stride（没有对齐填充，常见情况）时整块复制，否则逐行复制。
+pub fn copy_rgba_plane(plane: &[u8], stride: usize, width: u32, height: u32, pool: Option<&FramePool>) -> Vec<u8> {
+    let row_size = width as usize * 4;
+    if row_size == 0 || height == 0 {
+        return Vec::new();
+    }
+    let len = row_size * height as usize;
+    let mut data = pool.map_or_else(|| vec![0u8; len], |pool| pool.acquire(len));
+    if let Some(src) = plane.get(..len).filter(|_| stride == row_size) {
+        data.copy_from_slice(src);
+        return data;
+    }
+    for (y, row) in data.chunks_exact_mut(row_size).enumerate() {
+        let src = y.checked_mul(stride).and_then(|offset| plane.get(offset..)).unwrap_or_default();
+        let copied = row_size.min(src.len());
+        row[..copied].copy_from_slice(&src[..copied]);
+        // 复用的缓冲区里是上一帧的内容
+        row[copied..].fill(0);
+    }
+    data
+}
+
+/// 收集转换后的帧：尺寸无效的帧跳过并记下错误，其他错误照常返回
+pub(crate) fn collect_converted(
+    result: Result<Option<VideoFrame>>,
+    frames: &mut Vec<VideoFrame>,
+    rejected: &mut Option<PlayerError>,
+) -> Result<()> {
+    match result {
+        Ok(Some(frame)) => frames.push(frame),
+        Ok(None) => {}
+        Err(e @ PlayerError::InvalidFrameSize { .. }) => {
+            debug!("丢弃尺寸无效的视频帧: {}", e);
+            *rejected = Some(e);
+        }
+        Err(e) => return Err(e),
+    }
+    Ok(())
+}
+
+/// 一个数据包没有解码出帧且有错误（帧因尺寸无效被丢弃、硬件解码出错）时返回该错误（交给解码线程计数），否则返回正常的帧
+pub(crate) fn finish_decode(frames: Vec<VideoFrame>, rejected: Option<PlayerError>) -> Result<Vec<VideoFrame>> {
+    match rejected {
+        Some(e) if frames.is_empty() => Err(e),
+        _ => Ok(frames),
     }
 }
 
@@ -239,30 +574,6 @@ pub struct AudioDecoder {
 }
 
 impl AudioDecoder {
-    /// 从音频流创建解码器（使用默认输出配置）
-    pub fn from_stream(stream: format::stream::Stream) -> Result<Self> {
-        let context = codec::context::Context::from_parameters(stream.parameters())?;
-        let decoder = context.decoder().audio()?;
-
-        let time_base = stream.time_base();
-        let time_base = time_base.numerator() as f64 / time_base.denominator() as f64;
-
-        debug!(
-            "音频解码器: {} Hz, {} 声道, 格式: {:?}",
-            decoder.rate(),
-            decoder.channels(),
-            decoder.format()
-        );
-
-        Ok(Self {
-            decoder,
-            resampler: None,
-            time_base,
-            target_channels: 2,      // 默认立体声
-            target_sample_rate: 48000, // 默认 48kHz
-        })
-    }
-    
     /// 从音频流创建解码器（指定目标配置）
     pub fn from_stream_with_config(
         stream: format::stream::Stream,
@@ -397,17 +708,19 @@ impl AudioDecoder {
         let data_size = samples * self.target_channels as usize;
         let mut data = vec![0f32; data_size];
 
-        let frame_data = resampled.data(0);
-        let byte_slice = unsafe {
-            std::slice::from_raw_parts(frame_data.as_ptr() as *const f32, data_size)
-        };
-        data.copy_from_slice(byte_slice);
+        match audio_frame_as_f32(&resampled, data_size) {
+            Ok(samples) => data.copy_from_slice(samples),
+            Err(e) => {
+                // 截断的流可能让重采样输出比预期短，丢弃该帧而不是越界读取
+                warn!("⚠️  丢弃音频帧: {}", e);
+                return Ok(None);
+            }
+        }
 
         Ok(Some(AudioFrame {
             pts,
             sample_rate: self.target_sample_rate,
             channels: self.target_channels,
-            format: SampleFormat::F32,
             data,
         }))
     }
@@ -436,7 +749,8 @@ impl SubtitleDecoder {
     /// 解码数据包 → 输出 0~n 条字幕帧
     pub fn decode(&mut self, packet: &ffmpeg::Packet) -> Result<Vec<SubtitleFrame>> {
         let mut frames = Vec::new();
-        let mut subtitle = ffmpeg::codec::subtitle::Subtitle::default();
+        // ✅ 解码输出在离开作用域时释放，否则泄漏
+        let mut subtitle = SubtitleGuard::new();
 
         if let Err(e) = self.decoder.decode(packet, &mut subtitle) {
             // EAGAIN 时不视为错误
@@ -451,53 +765,24 @@ impl SubtitleDecoder {
         let pts = subtitle.pts().unwrap_or(0) as f64 * self.time_base * 1000.0;
         let start_pts = pts as i64;
 
-        // 尝试从 FFmpeg subtitle 获取结束时间
-        // AVSubtitle 结构中有 end_display_time 字段（以毫秒为单位）
-        let duration = unsafe {
-            let raw_subtitle = subtitle.as_ptr();
-            let end_display_time_ms = (*raw_subtitle).end_display_time;
-            if end_display_time_ms > 0 {
-                end_display_time_ms as i64
-            } else {
-                3000 // 默认 3 秒
-            }
-        };
+        // 尝试从 FFmpeg subtitle 获取结束时间（默认 3 秒）
+        let duration = subtitle_end_time(&subtitle).map_or(3000, |end| end.as_millis() as i64);
         let end_pts = start_pts + duration;
 
         // 解析字幕内容
         let mut text = String::new();
 
         for rect in subtitle.rects() {
-            unsafe {
-                let raw = rect.as_ptr();
-                match (*raw).type_ {
-                    AVSubtitleType::SUBTITLE_TEXT => {
-                        if !(*raw).text.is_null() {
-                            let s = CStr::from_ptr((*raw).text).to_string_lossy().into_owned();
-                            text.push_str(&s);
-                            text.push('\n');
-                        }
-                    }
-                    AVSubtitleType::SUBTITLE_ASS => {
-                        if !(*raw).ass.is_null() {
-                            let s = CStr::from_ptr((*raw).ass).to_string_lossy().into_owned();
-                            text.push_str(&s);
-                            text.push('\n');
-                        }
-                    }
-                    AVSubtitleType::SUBTITLE_BITMAP => {
-                        // TODO: 后续可处理位图字幕
-                        debug!("跳过位图字幕（当前仅支持文本字幕）");
-                    }
-                    _ => {}
+            match ffi_util::subtitle_rect_text(&rect) {
+                Some(s) => {
+                    text.push_str(&s);
+                    text.push('\n');
                 }
+                // TODO: 后续可处理位图字幕
+                None if matches!(rect, Rect::Bitmap(_)) => debug!("跳过位图字幕（当前仅支持文本字幕）"),
+                None => {}
             }
         }
-        
-        // ✅ 必须释放 FFmpeg subtitle，否则泄漏
-        unsafe {
-            ffmpeg_next::ffi::avsubtitle_free(subtitle.as_mut_ptr());
-        }
 
         if !text.trim().is_empty() {
             frames.push(SubtitleFrame {
@@ -636,3 +921,158 @@ impl SubtitleDecoder {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造只有尺寸、没有像素数据的帧（模拟损坏文件报告的尺寸）
+    fn crafted_frame(width: u32, height: u32) -> util::frame::Video {
+        let mut frame = util::frame::Video::empty();
+        frame.set_format(util::format::Pixel::YUV420P);
+        frame.set_width(width);
+        frame.set_height(height);
+        frame
+    }
+
+    #[test]
+    fn test_invalid_dimensions_rejected_before_allocation() {
+        for (width, height) in [(0, 0), (1, 1), (0, 1080), (1920, 0), (65535, 65535), (u32::MAX, 16)] {
+            let mut scaler = None;
+            // 拒绝发生在创建 scaler 和分配内存之前（65535x65535 若分配会需要 17 GB）
+            match convert_to_rgba(&mut scaler, &crafted_frame(width, height), 0.001) {
+                Err(PlayerError::InvalidFrameSize { width: w, height: h }) => assert_eq!((w, h), (width, height)),
+                other => panic!("{}x{} 没有被拒绝: {:?}", width, height, other.map(|frame| frame.data.len())),
+            }
+            assert!(scaler.is_none());
+        }
+    }
+
+    #[test]
+    fn test_dimension_limits() {
+        assert!(validate_frame_dimensions(16, 16).is_ok());
+        assert!(validate_frame_dimensions(8192, 4320).is_ok());
+        assert!(validate_frame_dimensions(15, 1080).is_err());
+        assert!(validate_frame_dimensions(8193, 1080).is_err());
+
+        set_max_frame_dimension(1920);
+        assert!(validate_frame_dimensions(1920, 1080).is_ok());
+        assert!(validate_frame_dimensions(1921, 1080).is_err());
+        // 上限不会低于下限
+        set_max_frame_dimension(0);
+        assert_eq!(max_frame_dimension(), MIN_FRAME_DIMENSION);
+        set_max_frame_dimension(DEFAULT_MAX_FRAME_DIMENSION);
+    }
+
+    #[test]
+    fn test_row_copy_clamped_to_plane() {
+        // 完整的平面（每行带 8 字节填充）
+        let stride = 16 * 4 + 8;
+        let plane: Vec<u8> = (0..stride * 16).map(|i| (i % 251) as u8).collect();
+        let data = copy_rgba_plane(&plane, stride, 16, 16, None);
+        assert_eq!(data.len(), 16 * 16 * 4);
+        assert_eq!(&data[16 * 4..16 * 8], &plane[stride..stride + 16 * 4]);
+
+        // FFmpeg 给出的平面比报告的尺寸小：只复制存在的数据，其余保持为 0
+        let data = copy_rgba_plane(&plane[..stride * 3 + 10], stride, 16, 16, None);
+        assert_eq!(data.len(), 16 * 16 * 4);
+        assert_eq!(&data[16 * 4 * 3..16 * 4 * 3 + 10], &plane[stride * 3..stride * 3 + 10]);
+        assert!(data[16 * 4 * 3 + 10..].iter().all(|&byte| byte == 0));
+
+        // 空平面和零尺寸都不会越界
+        assert_eq!(copy_rgba_plane(&[], 0, 16, 16, None), vec![0u8; 16 * 16 * 4]);
+        assert!(copy_rgba_plane(&plane, stride, 0, 0, None).is_empty());
+        assert!(copy_rgba_plane(&plane, stride, 1, 0, None).is_empty());
+    }
+
+    #[test]
+    fn test_row_copy_overwrites_reused_buffer() {
+        let stride = 16 * 4;
+        let pool = FramePool::new();
+        let full = FrameData::pooled(copy_rgba_plane(&vec![0xff; stride * 16], stride, 16, 16, Some(&pool)), Some(&pool));
+        drop(full);
+
+        // 复用上一帧的缓冲区：平面不完整时缺失的部分仍为 0，不残留上一帧的像素
+        let data = copy_rgba_plane(&vec![7; stride * 2], stride, 16, 16, Some(&pool));
+        assert_eq!(pool.stats().reused, 1);
+        assert!(data[..stride * 2].iter().all(|&byte| byte == 7));
+        assert!(data[stride * 2..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_unpadded_plane_copied_whole() {
+        let stride = 16 * 4;
+        let plane: Vec<u8> = (0..stride * 16).map(|i| (i % 251) as u8).collect();
+        assert_eq!(copy_rgba_plane(&plane, stride, 16, 16, None), plane);
+        // 平面比报告的尺寸小时仍逐行复制，缺失的部分为 0
+        let data = copy_rgba_plane(&plane[..stride * 5], stride, 16, 16, None);
+        assert_eq!(&data[..stride * 5], &plane[..stride * 5]);
+        assert!(data[stride * 5..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_valid_frame_converts() {
+        let frame = util::frame::Video::new(util::format::Pixel::YUV420P, 64, 48);
+        let mut scaler = None;
+        let converted = convert_to_rgba(&mut scaler, &frame, 0.001).unwrap();
+        assert_eq!((converted.width, converted.height), (64, 48));
+        assert_eq!(converted.data.len(), 64 * 48 * 4);
+    }
+
+    #[test]
+    fn test_odd_width_frame_drops_row_padding() {
+        // 63 像素宽：swscale 输出的每行带对齐填充，复制后每行紧密排列（纯色画面的每个像素都相同，不夹带填充字节）
+        let mut frame = util::frame::Video::new(util::format::Pixel::YUV420P, 63, 35);
+        frame.data_mut(0).fill(235);
+        frame.data_mut(1).fill(128);
+        frame.data_mut(2).fill(128);
+        let mut scaler = None;
+        let converted = convert_to_rgba(&mut scaler, &frame, 0.001).unwrap();
+        assert_eq!(converted.data.len(), 63 * 35 * 4);
+        let first = &converted.data[..4];
+        assert!(converted.data.chunks_exact(4).all(|pixel| pixel == first));
+    }
+
+    #[test]
+    fn test_yuv_planes_are_copied_with_strides() {
+        // 奇数尺寸的 YUV420P 帧：亮度 235（白），色度居中
+        let mut frame = util::frame::Video::new(util::format::Pixel::YUV420P, 63, 35);
+        frame.data_mut(0).fill(235);
+        frame.data_mut(1).fill(128);
+        frame.data_mut(2).fill(128);
+        let converted = copy_yuv_planes(&frame, 0.001, None).unwrap();
+        let planes = converted.planes.unwrap();
+        assert_eq!(converted.format, PixelFormat::YUV420P);
+        assert_eq!(planes.strides, [frame.stride(0), frame.stride(1), frame.stride(2)]);
+        assert_eq!(planes.offsets[1], frame.stride(0) * 35);
+        assert_eq!(converted.data.len(), planes.offsets[2] + frame.stride(2) * 18);
+        assert_eq!((planes.matrix, planes.full_range), (YuvMatrix::Bt601, false));
+        let rgba = converted.to_rgba();
+        assert_eq!(rgba.data.len(), 63 * 35 * 4);
+        assert!(rgba.data.iter().all(|&value| value == 255));
+
+        // 其他格式走 RGBA 转换
+        let rgba_frame = util::frame::Video::new(util::format::Pixel::RGBA, 64, 48);
+        assert!(copy_yuv_planes(&rgba_frame, 0.001, None).is_none());
+    }
+
+    #[test]
+    fn test_packet_with_only_rejected_frames_reports_error() {
+        let mut frames = Vec::new();
+        let mut rejected = None;
+        collect_converted(Err(PlayerError::InvalidFrameSize { width: 0, height: 0 }), &mut frames, &mut rejected).unwrap();
+        assert!(matches!(finish_decode(frames, rejected), Err(PlayerError::InvalidFrameSize { .. })));
+
+        // 同一个包里有正常帧时返回正常帧
+        let mut frames = Vec::new();
+        let mut rejected = None;
+        let frame = VideoFrame { pts: 0, duration: 0, width: 16, height: 16, format: PixelFormat::RGBA, planes: None, data: vec![0; 1024].into() };
+        collect_converted(Ok(Some(frame)), &mut frames, &mut rejected).unwrap();
+        collect_converted(Err(PlayerError::InvalidFrameSize { width: 1, height: 1 }), &mut frames, &mut rejected).unwrap();
+        assert_eq!(finish_decode(frames, rejected).unwrap().len(), 1);
+
+        // 其他错误照常返回
+        let mut frames = Vec::new();
+        assert!(collect_converted(Err(PlayerError::DecodeError("x".into())), &mut frames, &mut None).is_err());
+    }
+}
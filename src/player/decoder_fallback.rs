@@ -0,0 +1,282 @@
+// 硬件解码回退到软件解码（原因提示、按文件记录、播放中重建解码器）
+//
+// 硬件解码器创建失败（驱动问题、不支持的编码配置）时改用软件解码，过去是静默的：用户只会发现
+// 某些文件播放时 CPU 占用高、风扇响，却不知道原因。现在：
+// - 回退原因通过 HwFallbackState 通知界面：屏幕提示一次，信息面板持续显示"软解 (原因)"
+// - 回退原因按文件记入播放历史，再次打开同一文件时直接使用软件解码（设置中可选择总是重试硬解）
+// - 硬解开始正常、播放一段后连续出错时，视频解码线程把解码器原地重建为软件解码，
+//   管理器在当前位置重新 seek（清空并 flush 解码器），一秒内恢复画面，播放不中断
+
+use crate::core::{PlayerError, Result};
+use crate::player::decode_failure::is_fatal;
+use log::{error, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 硬件解码器连续多少个数据包解码失败时改用软件解码（小于判定解码器失效的上限）
+pub const HW_FALLBACK_ERROR_LIMIT: u32 = 5;
+
+/// 改用软件解码的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HwFallbackReason {
+    /// 硬件解码器创建失败
+    InitFailed(String),
+    /// 播放中硬件解码连续出错
+    MidStream(String),
+    /// 播放历史中记录过硬解失败，直接使用软件解码
+    Remembered(String),
+}
+
+impl HwFallbackReason {
+    /// 失败说明（记入播放历史）
+    pub fn description(&self) -> String {
+        match self {
+            Self::InitFailed(reason) => format!("硬解初始化失败: {}", reason),
+            Self::MidStream(reason) => format!("硬解播放中出错: {}", reason),
+            Self::Remembered(description) => description.clone(),
+        }
+    }
+
+    /// 信息面板中显示的标签
+    pub fn label(&self) -> String {
+        format!("软解 ({})", self.description())
+    }
+
+    /// 是否为本次播放中刚发生的回退（需要屏幕提示并记入播放历史）
+    pub fn is_new(&self) -> bool {
+        !matches!(self, Self::Remembered(_))
+    }
+}
+
+/// 回退状态（解码线程和管理器写入，界面读取）
+#[derive(Debug, Clone, Default)]
+pub struct HwFallbackState {
+    reason: Arc<Mutex<Option<HwFallbackReason>>>,
+    notice: Arc<AtomicBool>,  // 等待界面提示 / 管理器处理
+}
+
+impl HwFallbackState {
+    pub fn report(&self, reason: HwFallbackReason) {
+        let is_new = reason.is_new();
+        *self.reason.lock().unwrap() = Some(reason);
+        if is_new {
+            self.notice.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// 当前管线的回退原因（使用硬件解码或硬解已关闭时为 None）
+    pub fn reason(&self) -> Option<HwFallbackReason> {
+        self.reason.lock().unwrap().clone()
+    }
+
+    /// 刚发生的回退；读取后清除
+    pub fn take_notice(&self) -> Option<HwFallbackReason> {
+        if self.notice.swap(false, Ordering::SeqCst) {
+            self.reason()
+        } else {
+            None
+        }
+    }
+
+    /// 新管线启动时重置
+    pub fn reset(&self) {
+        *self.reason.lock().unwrap() = None;
+        self.notice.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 选择视频解码器：硬解开启且此前没有失败记录时先尝试硬件解码，失败时回退到软件解码
+///
+/// 返回解码器和回退原因（使用硬件解码或硬解已在设置中关闭时为 None）。
+pub fn select_decoder<D>(
+    hw_enabled: bool,
+    remembered_failure: Option<String>,
+    hardware: impl FnOnce() -> Result<D>,
+    software: impl FnOnce() -> Result<D>,
+) -> Result<(D, Option<HwFallbackReason>)> {
+    if !hw_enabled {
+        return Ok((software()?, None));
+    }
+    if let Some(description) = remembered_failure {
+        return Ok((software()?, Some(HwFallbackReason::Remembered(description))));
+    }
+    match hardware() {
+        Ok(decoder) => Ok((decoder, None)),
+        Err(e) => {
+            warn!("⚠️ 硬件解码不可用: {}，回退到软件解码", e);
+            Ok((software()?, Some(HwFallbackReason::InitFailed(e.to_string()))))
+        }
+    }
+}
+
+/// 可在播放中改用软件解码的解码器
+pub trait SoftwareFallback {
+    fn is_hardware(&self) -> bool;
+
+    /// 丢弃硬件解码器，按同一视频流重建软件解码器
+    fn switch_to_software(&mut self) -> Result<()>;
+}
+
+/// 硬件解码连续出错的监视（每个视频解码线程一个）
+#[derive(Debug, Default)]
+pub struct HwErrorWatch {
+    consecutive_errors: u32,
+    gave_up: bool,  // 重建软件解码器失败，之后按普通解码错误处理
+}
+
+impl HwErrorWatch {
+    pub fn record_success(&mut self) {
+        self.consecutive_errors = 0;
+    }
+
+    /// 记录一次解码错误；硬件解码器连续出错达到上限时切换为软件解码，返回回退原因
+    pub fn record_error(&mut self, decoder: &mut impl SoftwareFallback, error: &PlayerError) -> Option<HwFallbackReason> {
+        if self.gave_up || !decoder.is_hardware() || !is_fatal(error) {
+            return None;
+        }
+        self.consecutive_errors += 1;
+        if self.consecutive_errors < HW_FALLBACK_ERROR_LIMIT {
+            return None;
+        }
+        self.consecutive_errors = 0;
+        match decoder.switch_to_software() {
+            Ok(()) => {
+                warn!("⚠️ 硬件解码连续 {} 次失败（{}），已切换为软件解码", HW_FALLBACK_ERROR_LIMIT, error);
+                Some(HwFallbackReason::MidStream(error.to_string()))
+            }
+            Err(e) => {
+                error!("❌ 重建软件解码器失败: {}", e);
+                self.gave_up = true;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ffmpeg_next as ffmpeg;
+
+    /// 模拟解码器：硬件模式下解码 ok_frames 个包后开始出错，软件模式始终成功
+    struct MockDecoder {
+        hardware: bool,
+        ok_frames: usize,
+        decoded: usize,
+        rebuild_fails: bool,
+    }
+
+    impl MockDecoder {
+        fn decode(&mut self) -> Result<usize> {
+            self.decoded += 1;
+            if self.hardware && self.decoded > self.ok_frames {
+                Err(PlayerError::DecodeError("hwaccel frame transfer failed".to_string()))
+            } else {
+                Ok(self.decoded)
+            }
+        }
+    }
+
+    impl SoftwareFallback for MockDecoder {
+        fn is_hardware(&self) -> bool {
+            self.hardware
+        }
+
+        fn switch_to_software(&mut self) -> Result<()> {
+            if self.rebuild_fails {
+                return Err(PlayerError::DecodeError("no codec".to_string()));
+            }
+            self.hardware = false;
+            Ok(())
+        }
+    }
+
+    fn hw_failure() -> Result<&'static str> {
+        Err(PlayerError::DecodeError("D3D11VA device lost".to_string()))
+    }
+
+    #[test]
+    fn test_init_failure_falls_back_to_software() {
+        let (decoder, reason) = select_decoder(true, None, || Ok("hw"), || Ok("sw")).unwrap();
+        assert_eq!((decoder, reason), ("hw", None));
+
+        let (decoder, reason) = select_decoder(true, None, hw_failure, || Ok("sw")).unwrap();
+        assert_eq!(decoder, "sw");
+        let reason = reason.unwrap();
+        assert_eq!(reason.label(), "软解 (硬解初始化失败: 解码错误: D3D11VA device lost)");
+        assert!(reason.is_new());
+
+        // 有失败记录时不再尝试硬解
+        let (decoder, reason) = select_decoder(true, Some(reason.description()), || -> Result<&str> {
+            panic!("不应尝试硬件解码")
+        }, || Ok("sw")).unwrap();
+        assert_eq!(decoder, "sw");
+        assert!(!reason.unwrap().is_new());
+
+        // 硬解已关闭：不算回退
+        assert_eq!(select_decoder(false, None, hw_failure, || Ok("sw")).unwrap(), ("sw", None));
+        assert!(select_decoder(true, None, hw_failure, hw_failure).is_err());
+    }
+
+    #[test]
+    fn test_mid_stream_errors_switch_to_software() {
+        let mut decoder = MockDecoder { hardware: true, ok_frames: 30, decoded: 0, rebuild_fails: false };
+        let mut watch = HwErrorWatch::default();
+        let mut fallback = None;
+        let mut frames = 0;
+        for _ in 0..60 {
+            match decoder.decode() {
+                Ok(_) => {
+                    watch.record_success();
+                    frames += 1;
+                }
+                Err(e) => {
+                    if let Some(reason) = watch.record_error(&mut decoder, &e) {
+                        assert!(fallback.is_none(), "只应切换一次");
+                        fallback = Some(reason);
+                    }
+                }
+            }
+        }
+        assert!(matches!(fallback, Some(HwFallbackReason::MidStream(_))));
+        assert!(!decoder.is_hardware());
+        assert_eq!(frames, 60 - HW_FALLBACK_ERROR_LIMIT as usize);
+
+        // EAGAIN 和偶发错误不触发切换
+        let mut decoder = MockDecoder { hardware: true, ok_frames: 0, decoded: 0, rebuild_fails: false };
+        let mut watch = HwErrorWatch::default();
+        let eagain = PlayerError::FFmpegError(ffmpeg::Error::Other { errno: 11 });
+        for _ in 0..HW_FALLBACK_ERROR_LIMIT * 2 {
+            assert_eq!(watch.record_error(&mut decoder, &eagain), None);
+            let error = decoder.decode().unwrap_err();
+            assert_eq!(watch.record_error(&mut decoder, &error), None);
+            watch.record_success();
+        }
+        assert!(decoder.is_hardware());
+    }
+
+    #[test]
+    fn test_failed_rebuild_gives_up() {
+        let mut decoder = MockDecoder { hardware: true, ok_frames: 0, decoded: 0, rebuild_fails: true };
+        let mut watch = HwErrorWatch::default();
+        for _ in 0..HW_FALLBACK_ERROR_LIMIT * 3 {
+            let error = decoder.decode().unwrap_err();
+            assert_eq!(watch.record_error(&mut decoder, &error), None);
+        }
+        assert!(decoder.is_hardware());
+    }
+
+    #[test]
+    fn test_notice_is_taken_once() {
+        let state = HwFallbackState::default();
+        state.report(HwFallbackReason::Remembered("硬解初始化失败: x".to_string()));
+        assert_eq!(state.take_notice(), None);
+        assert!(state.reason().is_some());
+
+        state.clone().report(HwFallbackReason::MidStream("x".to_string()));
+        assert_eq!(state.take_notice(), Some(HwFallbackReason::MidStream("x".to_string())));
+        assert_eq!(state.take_notice(), None);
+        state.reset();
+        assert_eq!(state.reason(), None);
+    }
+}
@@ -0,0 +1,98 @@
+// 解封装结束原因
+//
+// 解封装线程（DemuxerThread 与本地文件的解封装线程）退出或读到末尾时，先通过专用通道把结束原因
+// 交给播放管理器，再关闭数据包通道。管理器据此区分正常播放完毕、网络中断（重新连接）和读取错误
+// （停止并提示），而不是把所有情况都当作文件结束。
+
+use crate::core::error::PlayerError;
+use ffmpeg_next as ffmpeg;
+use ffmpeg_next::util::error::{
+    ECONNABORTED, ECONNRESET, EHOSTUNREACH, ENETDOWN, ENETRESET, ENETUNREACH, ENOTCONN, EPIPE, ETIMEDOUT,
+};
+
+/// 视为网络中断（可重新连接）的错误码
+const NETWORK_ERRNOS: [i32; 9] = [
+    ETIMEDOUT, ECONNRESET, ECONNABORTED, ENETDOWN, ENETRESET, ENETUNREACH, EHOSTUNREACH, ENOTCONN, EPIPE,
+];
+
+/// 解封装结束原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DemuxEnd {
+    /// 读到文件末尾
+    Eof,
+    /// 网络超时或连接中断
+    NetworkTimeout,
+    /// 读取错误（错误信息）
+    IoError(String),
+    /// 被停止（切换文件、关闭会话等）
+    Cancelled,
+}
+
+impl DemuxEnd {
+    /// 根据读包错误判断结束原因
+    pub fn from_error(error: &PlayerError) -> Self {
+        match error {
+            PlayerError::FFmpegError(ffmpeg::Error::Eof) => Self::Eof,
+            PlayerError::FFmpegError(ffmpeg::Error::Other { errno }) if NETWORK_ERRNOS.contains(errno) => {
+                Self::NetworkTimeout
+            }
+            PlayerError::IoError(e) if is_network_interruption(e.kind()) => Self::NetworkTimeout,
+            PlayerError::NetworkError(_) => Self::NetworkTimeout,
+            _ => Self::IoError(error.to_string()),
+        }
+    }
+}
+
+fn is_network_interruption(kind: std::io::ErrorKind) -> bool {
+    use std::io::ErrorKind;
+    matches!(
+        kind,
+        ErrorKind::TimedOut
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+    )
+}
+
+/// 解封装结束后需要界面处理的事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DemuxEvent {
+    /// 播放完毕（帧队列耗尽后按播放结束处理）
+    Finished,
+    /// 网络中断，需要重新打开流（地址、中断时的位置、第几次尝试）
+    Reconnect {
+        /// 流地址
+        url: String,
+        /// 中断时的位置（毫秒）
+        position_ms: i64,
+        /// 第几次尝试
+        attempt: u32,
+    },
+    /// 读取失败，播放已停止（错误信息）
+    Failed(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_read_errors() {
+        assert_eq!(DemuxEnd::from_error(&PlayerError::FFmpegError(ffmpeg::Error::Eof)), DemuxEnd::Eof);
+        assert_eq!(
+            DemuxEnd::from_error(&PlayerError::FFmpegError(ffmpeg::Error::Other { errno: ETIMEDOUT })),
+            DemuxEnd::NetworkTimeout
+        );
+        assert_eq!(
+            DemuxEnd::from_error(&PlayerError::IoError(std::io::ErrorKind::ConnectionReset.into())),
+            DemuxEnd::NetworkTimeout
+        );
+        assert_eq!(DemuxEnd::from_error(&PlayerError::NetworkError("超时".into())), DemuxEnd::NetworkTimeout);
+
+        let error = PlayerError::FFmpegError(ffmpeg::Error::InvalidData);
+        assert_eq!(DemuxEnd::from_error(&error), DemuxEnd::IoError(error.to_string()));
+        let error = PlayerError::IoError(std::io::ErrorKind::PermissionDenied.into());
+        assert_eq!(DemuxEnd::from_error(&error), DemuxEnd::IoError(error.to_string()));
+    }
+}
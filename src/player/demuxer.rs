@@ -1,8 +1,28 @@
-use crate::core::{MediaInfo, PlayerError, Result};
+use crate::core::ffi_util;
+use crate::core::{Chapter, MediaInfo, PlayerError, Result, SequencePattern, StreamMeta, TrackInfo, TrackSource};
 use crate::player::demuxer_source::{DemuxerSource, MediaPacket, PacketType};
+use crate::player::keyframe_index::{Keyframe, KeyframeIndexer, SharedKeyframeIndex};
+use crate::player::decoder::validate_frame_dimensions;
+use crate::player::hdr;
+use crate::player::tone_map;
+use crate::player::live::is_live_source;
 use ffmpeg_next as ffmpeg;
-use ffmpeg_next::{format, media};
-use log::{debug, info};
+use ffmpeg_next::util::error::EAGAIN;
+use ffmpeg_next::{codec, format, media};
+use log::{debug, info, warn};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// 连续跳过的损坏数据包上限（超过后按读取错误结束解封装）
+const MAX_SKIPPED_INVALID_PACKETS: u32 = 100;
+
+/// 内嵌封面（音频文件中 ATTACHED_PIC 流携带的图片）
+#[derive(Debug, Clone)]
+pub struct CoverArt {
+    pub codec_id: codec::Id,  // 图片编码（通常为 MJPEG 或 PNG）
+    pub data: Vec<u8>,        // 图片数据包内容
+}
 
 /// 解封装器 - 负责读取媒体文件并分离音视频流
 pub struct Demuxer {
@@ -12,6 +32,8 @@ pub struct Demuxer {
     subtitle_stream_index: Option<usize>,
     media_info: MediaInfo,  // 缓存媒体信息
     source_path: String,    // 媒体源路径（用于描述）
+    cover_art: Option<CoverArt>,  // 内嵌封面（不作为视频流播放）
+    keyframe_index: Option<KeyframeIndexer>,  // 后台关键帧索引（本地文件）
 }
 
 impl Demuxer {
@@ -35,11 +57,7 @@ impl Demuxer {
         }
 
         // 判断是否为网络流
-        let is_network = path.starts_with("http://") 
-            || path.starts_with("https://")
-            || path.starts_with("rtsp://")
-            || path.starts_with("rtmp://")
-            || path.contains(".m3u8");
+        let is_network = is_network_path(path);
         
         // 为网络流设置选项
         let input_ctx = if is_network {
@@ -92,16 +110,46 @@ impl Demuxer {
             }
             
             format::input_with_dictionary(&path, options)
-                .map_err(|e| PlayerError::OpenError(format!("无法打开网络流: {}", e)))?
+                .map_err(|source| PlayerError::OpenFailed { target: "网络流", source })?
         } else {
             format::input(&path)
-                .map_err(|e| PlayerError::OpenError(format!("无法打开文件: {}", e)))?
+                .map_err(|source| PlayerError::OpenFailed { target: "文件", source })?
         };
 
-        // 查找视频流和音频流
+        let mut demuxer = Self::from_input(input_ctx, path)?;
+        demuxer.media_info.is_live = is_live_source(path, demuxer.media_info.duration);
+        if demuxer.media_info.is_live {
+            info!("🔴 直播流（无固定时长）");
+        }
+        Ok(demuxer)
+    }
+
+    /// 打开图像序列（FFmpeg image2 解封装器，按指定帧率作为无声视频播放）
+    pub fn open_image_sequence(pattern: &SequencePattern, fps: u32) -> Result<Self> {
+        let template = pattern.template.to_string_lossy().to_string();
+        info!("正在打开图像序列: {} ({} 帧, {} fps)", template, pattern.frame_count, fps);
+
+        let mut options = ffmpeg::Dictionary::new();
+        options.set("framerate", &fps.max(1).to_string());
+        options.set("start_number", &pattern.start_number.to_string());
+        let input_ctx = format::input_with_dictionary(&template, options)
+            .map_err(|source| PlayerError::OpenFailed { target: "图像序列", source })?;
+
+        Self::from_input(input_ctx, &template)
+    }
+
+    /// 从已打开的输入上下文创建解封装器（选择流、读取封面和媒体信息）
+    fn from_input(input_ctx: format::context::Input, path: &str) -> Result<Self> {
+        // 查找视频流和音频流（内嵌封面也是视频流，但只有一帧，不能作为主视频）
         let video_stream_index = input_ctx
             .streams()
             .best(media::Type::Video)
+            .filter(|s| !is_attached_pic(s))
+            .or_else(|| {
+                input_ctx
+                    .streams()
+                    .find(|s| s.parameters().medium() == media::Type::Video && !is_attached_pic(s))
+            })
             .map(|s| s.index());
 
         let audio_stream_index = input_ctx
@@ -116,10 +164,19 @@ impl Demuxer {
             .next()
             .map(|s| s.index());
 
-        if video_stream_index.is_none() {
+        // 纯音频文件可以播放，音视频都没有时才报错
+        if video_stream_index.is_none() && audio_stream_index.is_none() {
             return Err(PlayerError::NoVideoStream);
         }
 
+        let cover_art = input_ctx.streams().find(is_attached_pic).and_then(|stream| {
+            let data = ffi_util::attached_picture(&stream)?.to_vec();
+            Some(CoverArt { codec_id: stream.parameters().id(), data })
+        });
+        if let Some(cover) = &cover_art {
+            debug!("内嵌封面: {:?}, {} 字节", cover.codec_id, cover.data.len());
+        }
+
         debug!("视频流索引: {:?}", video_stream_index);
         debug!("音频流索引: {:?}", audio_stream_index);
         debug!("字幕流索引: {:?}", subtitle_stream_index);
@@ -131,38 +188,33 @@ impl Demuxer {
             subtitle_stream_index,
             media_info: MediaInfo::default(),  // 临时默认值
             source_path: path.to_string(),
+            cover_art,
+            keyframe_index: None,
         };
         
-        // 获取并缓存媒体信息
-        demuxer.media_info = demuxer.extract_media_info()?;
+        // 获取并缓存媒体信息（视频流尺寸无效时只播放音频）
+        demuxer.media_info = match demuxer.extract_media_info() {
+            Err(PlayerError::InvalidFrameSize { width, height }) if demuxer.audio_stream_index.is_some() => {
+                warn!("⚠️  视频流尺寸无效 ({}x{})，忽略视频流，只播放音频", width, height);
+                demuxer.video_stream_index = None;
+                demuxer.extract_media_info()?
+            }
+            result => result?,
+        };
         
         Ok(demuxer)
     }
 
     /// 提取媒体信息（内部使用）
     fn extract_media_info(&self) -> Result<MediaInfo> {
-        let video_stream = self
+        // 微秒转毫秒（未知时长为 AV_NOPTS_VALUE 等负值，统一按 0 处理）
+        let duration = (self.input_ctx.duration() / 1000).max(0);
+        let title = self
             .input_ctx
-            .stream(self.video_stream_index.unwrap())
-            .ok_or(PlayerError::NoVideoStream)?;
-
-        let video_codec = video_stream.parameters();
-        
-        // 先获取编解码器名称（在 video_codec 被移动前）
-        let video_codec_name = video_codec
-            .id()
-            .name()
-            .to_string();
-        
-        let decoder = ffmpeg::codec::context::Context::from_parameters(video_codec)?;
-        let video_decoder = decoder.decoder().video()?;
-
-        let width = video_decoder.width();
-        let height = video_decoder.height();
-        let fps = video_stream.avg_frame_rate();
-        let fps = fps.numerator() as f64 / fps.denominator() as f64;
-
-        let duration = self.input_ctx.duration() / 1000; // 微秒转毫秒
+            .metadata()
+            .get("title")
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
 
         let (audio_codec_name, sample_rate, channels) = if let Some(audio_idx) = self.audio_stream_index {
             let audio_stream = self.input_ctx.stream(audio_idx).unwrap();
@@ -183,15 +235,84 @@ impl Demuxer {
             ("none".to_string(), 0, 0)
         };
 
+        // 纯音频源（包括只带封面的音频文件）不报告视频尺寸
+        let Some(video_idx) = self.video_stream_index else {
+            return Ok(MediaInfo {
+                duration,
+                video_codec: "none".to_string(),
+                audio_codec: audio_codec_name,
+                sample_rate,
+                channels,
+                title,
+                ..MediaInfo::default()
+            });
+        };
+        let video_stream = self
+            .input_ctx
+            .stream(video_idx)
+            .ok_or(PlayerError::NoVideoStream)?;
+
+        let video_codec = video_stream.parameters();
+        
+        // 先获取编解码器名称（在 video_codec 被移动前）
+        let video_codec_name = video_codec
+            .id()
+            .name()
+            .to_string();
+        
+        let decoder = ffmpeg::codec::context::Context::from_parameters(video_codec)?;
+        let video_decoder = decoder.decoder().video()?;
+
+        let width = video_decoder.width();
+        let height = video_decoder.height();
+        validate_frame_dimensions(width, height)?;
+        
+        // 像素宽高比：容器记录的优先（如 Matroska 的显示尺寸、MP4 的 pasp），其次是码流中的，都未知时按方形像素处理
+        let codec_sar = video_decoder.aspect_ratio();
+        let pixel_aspect = pixel_aspect_ratio(
+            ffi_util::stream_sample_aspect_ratio(&video_stream),
+            (codec_sar.numerator(), codec_sar.denominator()),
+        );
+        
+        // 旋转角度（来自显示矩阵，常见于手机拍摄的竖屏视频）
+        let rotation = video_stream
+            .side_data()
+            .find(|data| data.kind() == ffmpeg::codec::packet::side_data::Type::DisplayMatrix)
+            .and_then(|data| display_matrix_rotation(data.data()))
+            .unwrap_or(0);
+        if pixel_aspect != 1.0 || rotation != 0 {
+            debug!("像素宽高比: {:.3}，旋转: {}°", pixel_aspect, rotation);
+        }
+        let fps = video_stream.avg_frame_rate();
+        let fps = fps.numerator() as f64 / fps.denominator() as f64;
+        
+        // 杜比视界 / HDR10+（Profile 5 等无法正确显示颜色的格式由 UI 提示）
+        let hdr_compat = hdr::detect(&video_stream);
+        if let Some(notice) = hdr_compat.notice() {
+            if hdr_compat.is_supported() {
+                info!("🎨 {}", notice);
+            } else {
+                warn!("⚠️ {}", notice);
+            }
+        }
+        let color_space = tone_map::describe_color(video_decoder.color_primaries(), video_decoder.color_transfer_characteristic());
+        debug!("色彩空间: {}", color_space);
+
         Ok(MediaInfo {
             duration,
             width,
             height,
+            pixel_aspect,
+            rotation,
             fps,
             video_codec: video_codec_name,
             audio_codec: audio_codec_name,
             sample_rate,
             channels,
+            is_live: false,
+            hdr_compat,
+            title,
+            color_space,
         })
     }
 
@@ -217,6 +338,11 @@ impl Demuxer {
             .map(|idx| self.input_ctx.stream(idx).unwrap())
     }
 
+    /// 获取内嵌封面（纯音频文件的专辑封面等）
+    pub fn cover_art(&self) -> Option<CoverArt> {
+        self.cover_art.clone()
+    }
+
     /// 获取字幕流索引
     pub fn subtitle_stream_index(&self) -> Option<usize> {
         self.subtitle_stream_index
@@ -228,39 +354,250 @@ impl Demuxer {
             .map(|idx| self.input_ctx.stream(idx).unwrap())
     }
 
+    /// 获取所有音频轨道（按流索引排序）
+    pub fn audio_tracks(&self) -> Vec<TrackInfo> {
+        self.tracks_of(media::Type::Audio)
+    }
+
+    /// 获取所有内嵌字幕轨道（按流索引排序）
+    pub fn subtitle_tracks(&self) -> Vec<TrackInfo> {
+        self.tracks_of(media::Type::Subtitle)
+    }
+
+    /// 获取章节列表（按开始时间排序，标题为空时使用 "第 N 章"）
+    pub fn chapters(&self) -> Vec<Chapter> {
+        let mut chapters: Vec<Chapter> = self
+            .input_ctx
+            .chapters()
+            .map(|chapter| {
+                let time_base = chapter.time_base();
+                let time_base = time_base.numerator() as f64 / time_base.denominator() as f64;
+                let to_ms = |ts: i64| (ts as f64 * time_base * 1000.0) as i64;
+                let title = chapter
+                    .metadata()
+                    .get("title")
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .unwrap_or_else(|| format!("第 {} 章", chapter.index() + 1));
+
+                Chapter {
+                    start_ms: to_ms(chapter.start()),
+                    end_ms: to_ms(chapter.end()),
+                    title,
+                }
+            })
+            .collect();
+        chapters.sort_by_key(|c| c.start_ms);
+        chapters
+    }
+
+    /// 枚举指定类型的流（封面等内嵌图片除外）
+    fn tracks_of(&self, medium: media::Type) -> Vec<TrackInfo> {
+        self.input_ctx
+            .streams()
+            .filter(|s| s.parameters().medium() == medium && !is_attached_pic(s))
+            .map(|stream| {
+                let mut meta = stream_meta_from_tags(stream.metadata().iter(), stream.disposition());
+                let parameters = stream.parameters();
+                meta.codec = parameters.id().name().to_string();
+
+                // 容器/编码参数给出的码率优先（Matroska 通常只有统计标签）
+                if let Some(bit_rate) = ffi_util::codec_bit_rate(&parameters) {
+                    meta.bit_rate = Some(bit_rate);
+                }
+
+                // 音频声道数和采样率需要通过解码器上下文获取
+                if medium == media::Type::Audio {
+                    if let Ok(decoder) = ffmpeg::codec::context::Context::from_parameters(parameters)
+                        .and_then(|ctx| ctx.decoder().audio())
+                    {
+                        meta.channels = decoder.channels();
+                        meta.sample_rate = decoder.rate();
+                    }
+                }
+
+                TrackInfo {
+                    source: TrackSource::Embedded(stream.index()),
+                    meta,
+                }
+            })
+            .collect()
+    }
+
+    /// 选择音频流（需在启动播放线程前调用）
+    pub fn select_audio_stream(&mut self, index: usize) -> Result<()> {
+        let is_audio = self
+            .input_ctx
+            .stream(index)
+            .map(|s| s.parameters().medium() == media::Type::Audio)
+            .unwrap_or(false);
+        if !is_audio {
+            return Err(PlayerError::Other(format!("流 {} 不是音频流", index)));
+        }
+
+        self.audio_stream_index = Some(index);
+        self.media_info = self.extract_media_info()?;
+        info!("🔊 选择音频流: {}", index);
+        Ok(())
+    }
+
+    /// 选择字幕流（None 表示不解码内嵌字幕）
+    pub fn select_subtitle_stream(&mut self, index: Option<usize>) -> Result<()> {
+        if let Some(idx) = index {
+            let is_subtitle = self
+                .input_ctx
+                .stream(idx)
+                .map(|s| s.parameters().medium() == media::Type::Subtitle)
+                .unwrap_or(false);
+            if !is_subtitle {
+                return Err(PlayerError::Other(format!("流 {} 不是字幕流", idx)));
+            }
+        }
+
+        self.subtitle_stream_index = index;
+        info!("📝 选择字幕流: {:?}", index);
+        Ok(())
+    }
+
     /// 读取下一个数据包
     /// 返回 (packet, is_video, is_subtitle)
     pub fn read_packet(&mut self) -> Result<Option<(ffmpeg::Packet, bool, bool)>> {
-        match self.input_ctx.packets().next() {
-            Some((stream, packet)) => {
-                let is_video = Some(stream.index()) == self.video_stream_index;
-                let is_audio = Some(stream.index()) == self.audio_stream_index;
-                let is_subtitle = Some(stream.index()) == self.subtitle_stream_index;
-
-                if is_video || is_audio || is_subtitle {
-                    Ok(Some((packet, is_video, is_subtitle)))
-                } else {
-                    // 跳过其他流
-                    self.read_packet()
+        while let Some(packet) = self.next_packet()? {
+            let is_video = Some(packet.stream()) == self.video_stream_index;
+            let is_audio = Some(packet.stream()) == self.audio_stream_index;
+            let is_subtitle = Some(packet.stream()) == self.subtitle_stream_index;
+
+            if is_video || is_audio || is_subtitle {
+                return Ok(Some((packet, is_video, is_subtitle)));
+            }
+            // 跳过其他流
+        }
+        Ok(None)
+    }
+
+    /// 读取任意流的下一个数据包（末尾返回 None）
+    /// 不用 packets() 迭代器：它会吞掉 EOF 以外的错误并一直重试，网络超时等错误无法传给解封装线程
+    pub(crate) fn next_packet(&mut self) -> Result<Option<ffmpeg::Packet>> {
+        let mut skipped_invalid = 0;
+        loop {
+            let mut packet = ffmpeg::Packet::empty();
+            match packet.read(&mut self.input_ctx) {
+                Ok(()) => return Ok(Some(packet)),
+                Err(ffmpeg::Error::Eof) => return Ok(None),
+                // 暂时没有数据（部分网络协议），稍后重试
+                Err(ffmpeg::Error::Other { errno: EAGAIN }) => thread::sleep(Duration::from_millis(10)),
+                // 个别损坏的数据包直接跳过，连续过多时按读取错误处理
+                Err(ffmpeg::Error::InvalidData) if skipped_invalid < MAX_SKIPPED_INVALID_PACKETS => {
+                    skipped_invalid += 1;
+                    debug!("跳过损坏的数据包（连续 {} 个）", skipped_invalid);
                 }
+                Err(e) => return Err(e.into()),
             }
-            None => Ok(None),
         }
     }
 
+    /// 数据包的结束时间（微秒，PTS + 时长）
+    pub fn packet_end_us(&self, packet: &ffmpeg::Packet) -> Option<i64> {
+        let pts = packet.pts().or(packet.dts())?;
+        let time_base = self.input_ctx.stream(packet.stream())?.time_base();
+        ticks_to_us(pts + packet.duration().max(0), time_base)
+    }
+
+    /// 流的起始时间（微秒，未知时为 0）
+    pub fn stream_start_us(&self, index: usize) -> i64 {
+        self.input_ctx
+            .stream(index)
+            .filter(|stream| stream.start_time() != ffmpeg::ffi::AV_NOPTS_VALUE)
+            .and_then(|stream| ticks_to_us(stream.start_time(), stream.time_base()))
+            .unwrap_or(0)
+    }
+
+    /// 给数据包的时间戳加上偏移（微秒，单曲循环时使内部时间轴连续）
+    pub fn shift_packet(&self, packet: &mut ffmpeg::Packet, offset_us: i64) {
+        let Some(stream) = self.input_ctx.stream(packet.stream()) else {
+            return;
+        };
+        let time_base = stream.time_base();
+        if offset_us == 0 || time_base.numerator() <= 0 {
+            return;
+        }
+        // 四舍五入到时间基单位
+        let unit = 1_000_000 * time_base.numerator() as i128;
+        let offset = ((offset_us as i128 * time_base.denominator() as i128 + unit / 2) / unit) as i64;
+        packet.set_pts(packet.pts().map(|pts| pts + offset));
+        packet.set_dts(packet.dts().map(|dts| dts + offset));
+    }
+
     /// Seek 到指定位置（毫秒）
     fn seek_internal(&mut self, timestamp_ms: i64) -> Result<()> {
         let timestamp = timestamp_ms * 1000; // 毫秒转微秒
+        // max_ts 为目标：总是定位到目标之前（或正好在目标上）的关键帧，精确 seek 再由解码线程丢弃多余的帧
         self.input_ctx
             .seek(timestamp, ..timestamp)?;
         Ok(())
     }
     
+
     /// Seek 到指定位置（毫秒）- 公开接口
+    /// 
+    /// 关键帧索引完成后按字节偏移直接跳到目标前的关键帧，否则（或失败时）按时间戳查找；
+    /// 不可 seek 的源（没有可回看窗口的直播）返回 SeekUnsupported
     pub fn seek(&mut self, timestamp_ms: i64) -> Result<()> {
+        if !self.is_seekable() {
+            return Err(PlayerError::SeekUnsupported);
+        }
+        if let Some(keyframe) = self.seek_keyframe(timestamp_ms) {
+            // 定位到关键帧数据包所在位置（MP4 等依赖自身索引的格式不支持按字节 Seek）
+            if ffi_util::supports_byte_seek(&self.input_ctx) {
+                match ffi_util::seek_to_byte(&mut self.input_ctx, keyframe.pos) {
+                    Ok(()) => {
+                        debug!("按关键帧索引 Seek: {}ms -> 关键帧 {}ms @ {} 字节", timestamp_ms, keyframe.pts_ms, keyframe.pos);
+                        return Ok(());
+                    }
+                    Err(e) => debug!("按字节 Seek 失败，改用时间戳: {}", e),
+                }
+            }
+        }
         self.seek_internal(timestamp_ms)
     }
     
+    /// 在后台建立关键帧索引（仅本地可 Seek 文件；cache_dir 为 None 时不使用磁盘缓存）
+    pub fn start_keyframe_index(&mut self, cache_dir: Option<PathBuf>) {
+        if self.video_stream_index.is_none() || self.keyframe_index.is_some() {
+            return;
+        }
+        let path = PathBuf::from(&self.source_path);
+        if !path.is_file() {
+            return;
+        }
+        self.keyframe_index = Some(KeyframeIndexer::spawn(path, cache_dir));
+    }
+    
+    /// 不晚于 pts_ms 的最近关键帧（索引完成前返回 None）
+    pub fn nearest_keyframe(&self, pts_ms: i64) -> Option<Keyframe> {
+        self.keyframe_index.as_ref()?.get()?.nearest(pts_ms)
+    }
+
+    /// 共享的关键帧索引（解封装器交给解封装线程后，管理器据此做关键帧导航；没有建立索引时为 None）
+    pub fn shared_keyframe_index(&self) -> Option<SharedKeyframeIndex> {
+        self.keyframe_index.as_ref().map(KeyframeIndexer::shared)
+    }
+
+    /// 按字节 Seek 到 pts_ms 时使用的关键帧：不晚于 pts_ms 的最近关键帧
+    /// （索引未完成、被采样稀释或该关键帧没有字节偏移时返回 None）
+    pub(crate) fn seek_keyframe(&self, pts_ms: i64) -> Option<Keyframe> {
+        let complete = self.keyframe_index.as_ref()?.get()?.is_complete();
+        self.nearest_keyframe(pts_ms).filter(|keyframe| complete && keyframe.pos >= 0)
+    }
+    
+    /// 等待关键帧索引完成（测试使用）
+    #[cfg(test)]
+    pub fn wait_keyframe_index(&mut self) {
+        if let Some(indexer) = &mut self.keyframe_index {
+            indexer.wait();
+        }
+    }
+    
     /// 获取媒体信息（公开接口）
     pub fn get_media_info(&self) -> Result<MediaInfo> {
         Ok(self.media_info.clone())
@@ -276,9 +613,9 @@ impl Demuxer {
 impl DemuxerSource for Demuxer {
     fn read_packet(&mut self) -> Result<Option<MediaPacket>> {
         loop {
-            match self.input_ctx.packets().next() {
-                Some((stream, packet)) => {
-                    let stream_index = stream.index();
+            match self.next_packet()? {
+                Some(packet) => {
+                    let stream_index = packet.stream();
                     
                     // 判断包类型
                     if Some(stream_index) == self.video_stream_index {
@@ -308,7 +645,7 @@ impl DemuxerSource for Demuxer {
     }
     
     fn seek(&mut self, timestamp_ms: i64) -> Result<()> {
-        self.seek_internal(timestamp_ms)
+        Demuxer::seek(self, timestamp_ms)
     }
     
     fn get_media_info(&self) -> &MediaInfo {
@@ -323,13 +660,47 @@ impl DemuxerSource for Demuxer {
         self.audio_stream_index
     }
     
-    fn subtitle_stream_index(&self) -> Option<usize> {
-        self.subtitle_stream_index
+    fn is_network(&self) -> bool {
+        is_network_path(&self.source_path)
     }
     
     fn is_seekable(&self) -> bool {
-        // 本地文件和大多数网络流都支持 seek
-        true
+        // 本地文件和大多数网络流都支持 seek；直播只能在可回看窗口内 seek
+        !self.media_info.is_live || self.seekable_window().is_some()
+    }
+    
+    fn packet_time_ms(&self, packet: &MediaPacket) -> Option<i64> {
+        let pts = packet.packet.pts().or(packet.packet.dts())?;
+        let time_base = self.input_ctx.stream(packet.stream_index)?.time_base();
+        if time_base.denominator() == 0 {
+            return None;
+        }
+        Some(pts * 1000 * time_base.numerator() as i64 / time_base.denominator() as i64)
+    }
+    
+    fn seekable_window(&self) -> Option<(i64, i64)> {
+        // 直播流开始报告时长时（如 EVENT 类型的 HLS 播放列表），时长范围即可回看的窗口
+        if !self.media_info.is_live {
+            return None;
+        }
+        let duration_ms = self.input_ctx.duration() / 1000;
+        if duration_ms <= 0 {
+            return None;
+        }
+        let start_ms = ffi_util::start_time_ms(&self.input_ctx);
+        Some((start_ms, start_ms + duration_ms))
+    }
+    
+    fn packet_end_us(&self, packet: &MediaPacket) -> Option<i64> {
+        Demuxer::packet_end_us(self, &packet.packet)
+    }
+    
+    fn stream_start_us(&self, index: usize) -> i64 {
+        Demuxer::stream_start_us(self, index)
+    }
+    
+    fn shift_packet(&self, packet: &mut MediaPacket, offset_us: i64) {
+        Demuxer::shift_packet(self, &mut packet.packet, offset_us)
     }
     
     fn description(&self) -> String {
@@ -337,3 +708,231 @@ impl DemuxerSource for Demuxer {
     }
 }
 
+/// 是否为网络流地址
+fn is_network_path(path: &str) -> bool {
+    path.starts_with("http://")
+        || path.starts_with("https://")
+        || path.starts_with("rtsp://")
+        || path.starts_with("rtmp://")
+        || path.contains(".m3u8")
+}
+
+/// 流时间基单位 -> 微秒（时间基无效时返回 None）
+fn ticks_to_us(ticks: i64, time_base: ffmpeg::Rational) -> Option<i64> {
+    if time_base.denominator() <= 0 {
+        return None;
+    }
+    Some((ticks as i128 * 1_000_000 * time_base.numerator() as i128 / time_base.denominator() as i128) as i64)
+}
+
+/// 是否为内嵌图片流（封面），这类流只有一个数据包，不参与播放
+fn is_attached_pic(stream: &format::stream::Stream) -> bool {
+    stream.disposition().contains(format::stream::Disposition::ATTACHED_PIC)
+}
+
+/// 封装器自动写入的 MP4 handler_name（不是真正的轨道标题）
+const GENERIC_HANDLER_NAMES: &[&str] = &["core media", "iso media", "gpac", "l-smash", "mainconcept"];
+
+/// 从流的元数据标签和 disposition 提取语言、标题、码率和轨道标记
+///
+/// Matroska 的标题在 title 标签中，码率只在 mkvmerge 写入的 BPS 统计标签中；
+/// MP4 没有 title，标题来自 handler_name，但大多数封装器只写入 "SoundHandler" 之类的默认值
+fn stream_meta_from_tags<'a>(tags: impl Iterator<Item = (&'a str, &'a str)>, disposition: format::stream::Disposition) -> StreamMeta {
+    let mut meta = StreamMeta::default();
+    let mut handler_name = None;
+    for (key, value) in tags {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match key.to_lowercase().as_str() {
+            "language" => meta.language = Some(value.to_string()),
+            "title" => meta.title = Some(value.to_string()),
+            "handler_name" => handler_name = Some(value.to_string()),
+            "bps" | "bps-eng" => meta.bit_rate = value.parse().ok().filter(|&bps| bps > 0),
+            _ => {}
+        }
+    }
+    if meta.title.is_none() {
+        meta.title = handler_name.filter(|name| {
+            let name = name.to_lowercase();
+            !name.contains("handler") && !GENERIC_HANDLER_NAMES.iter().any(|generic| name.contains(generic))
+        });
+    }
+
+    // 很多文件没有设置 disposition，只在标题中注明
+    let title = meta.title.as_deref().unwrap_or_default().to_lowercase();
+    let has = |flag| disposition.contains(flag);
+    meta.is_default = has(format::stream::Disposition::DEFAULT);
+    meta.forced = has(format::stream::Disposition::FORCED) || title.contains("forced") || title.contains("强制");
+    meta.hearing_impaired = has(format::stream::Disposition::HEARING_IMPAIRED) || title.contains("sdh");
+    meta.commentary = has(format::stream::Disposition::COMMENT) || title.contains("commentary") || title.contains("解说");
+    meta
+}
+
+/// 像素宽高比（与 av_guess_sample_aspect_ratio 一致：容器的值有效时优先，其次为码流的值，都无效时为 1.0）
+fn pixel_aspect_ratio(stream: (i32, i32), codec: (i32, i32)) -> f64 {
+    [stream, codec]
+        .into_iter()
+        .find(|&(num, den)| num > 0 && den > 0)
+        .map_or(1.0, |(num, den)| num as f64 / den as f64)
+}
+
+/// 从显示矩阵（9 个 i32，16.16 定点数）解析显示时的顺时针旋转角度（取整到 90 度，与 ffplay 一致）
+fn display_matrix_rotation(data: &[u8]) -> Option<u32> {
+    if data.len() < 9 * 4 {
+        return None;
+    }
+    let value = |i: usize| {
+        let bytes = [data[i * 4], data[i * 4 + 1], data[i * 4 + 2], data[i * 4 + 3]];
+        i32::from_ne_bytes(bytes) as f64 / 65536.0
+    };
+    let scale_x = value(0).hypot(value(3));
+    let scale_y = value(1).hypot(value(4));
+    if scale_x == 0.0 || scale_y == 0.0 {
+        return None;
+    }
+    let degrees = (value(1) / scale_y).atan2(value(0) / scale_x).to_degrees();
+    let quarter_turns = (degrees / 90.0).round() as i64;
+    Some((quarter_turns.rem_euclid(4) * 90) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{display_matrix_rotation, pixel_aspect_ratio, stream_meta_from_tags, Demuxer};
+    use crate::player::decode_cover_art;
+    use crate::test_support::{
+        assert_golden_frame, cover_art_asset, decode_frame_at, video_asset, COVER_COLOR, COVER_SIZE, FRAME_DURATION_MS,
+    };
+    use ffmpeg_next::codec;
+    use ffmpeg_next::format::stream::Disposition;
+
+    /// 按 av_display_rotation_set 的方式构造显示矩阵
+    fn rotation_matrix(angle: f64) -> Vec<u8> {
+        let radians = -angle.to_radians();
+        let fixed = |v: f64| (v * 65536.0).round() as i32;
+        let matrix = [
+            fixed(radians.cos()), fixed(-radians.sin()), 0,
+            fixed(radians.sin()), fixed(radians.cos()), 0,
+            0, 0, 1 << 30,
+        ];
+        matrix.iter().flat_map(|v| v.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn test_pixel_aspect_prefers_container_value() {
+        // PAL DVD 16:9（720x576，SAR 64:45）：容器记录了显示比例
+        assert_eq!(pixel_aspect_ratio((64, 45), (16, 11)), 64.0 / 45.0);
+        // 容器未记录（0:1）时使用码流中的值
+        assert_eq!(pixel_aspect_ratio((0, 1), (16, 11)), 16.0 / 11.0);
+        assert_eq!(pixel_aspect_ratio((0, 1), (0, 1)), 1.0);
+        assert_eq!(pixel_aspect_ratio((1, 0), (-1, 1)), 1.0);
+    }
+
+    #[test]
+    fn test_display_matrix_rotation() {
+        assert_eq!(display_matrix_rotation(&rotation_matrix(0.0)), Some(0));
+        assert_eq!(display_matrix_rotation(&rotation_matrix(90.0)), Some(90));
+        assert_eq!(display_matrix_rotation(&rotation_matrix(-90.0)), Some(270));
+        assert_eq!(display_matrix_rotation(&rotation_matrix(180.0)), Some(180));
+        assert_eq!(display_matrix_rotation(&[0; 8]), None);
+    }
+
+    #[test]
+    fn test_stream_meta_from_matroska_tags() {
+        let tags = [
+            ("language", "eng"),
+            ("title", "Director's Commentary"),
+            ("BPS-eng", "192000"),
+            ("DURATION-eng", "01:42:00.000000000"),
+        ];
+        let meta = stream_meta_from_tags(tags.into_iter(), Disposition::empty());
+        assert_eq!(meta.language.as_deref(), Some("eng"));
+        assert_eq!(meta.title.as_deref(), Some("Director's Commentary"));
+        assert_eq!(meta.bit_rate, Some(192_000));
+        assert!(meta.commentary);
+        assert!(!meta.is_default && !meta.forced);
+    }
+
+    #[test]
+    fn test_stream_meta_from_mp4_handler_name() {
+        // 封装器默认的 handler_name 不作为标题
+        for generic in ["SoundHandler", "SubtitleHandler", "Core Media Audio", "GPAC ISO Audio Handler"] {
+            let tags = [("language", "jpn"), ("handler_name", generic)];
+            let meta = stream_meta_from_tags(tags.into_iter(), Disposition::DEFAULT);
+            assert_eq!(meta.title, None, "{}", generic);
+            assert!(meta.is_default);
+        }
+
+        let tags = [("language", "jpn"), ("handler_name", "日本語 5.1")];
+        let meta = stream_meta_from_tags(tags.into_iter(), Disposition::empty());
+        assert_eq!(meta.title.as_deref(), Some("日本語 5.1"));
+    }
+
+    #[test]
+    fn test_stream_meta_flags() {
+        let meta = stream_meta_from_tags(std::iter::empty(), Disposition::FORCED | Disposition::HEARING_IMPAIRED);
+        assert!(meta.forced && meta.hearing_impaired);
+
+        // 只在标题中注明的强制/SDH 字幕
+        let meta = stream_meta_from_tags([("title", "English (Forced)")].into_iter(), Disposition::empty());
+        assert!(meta.forced && !meta.hearing_impaired);
+        let meta = stream_meta_from_tags([("title", "English SDH")].into_iter(), Disposition::empty());
+        assert!(meta.hearing_impaired && !meta.forced);
+    }
+
+    #[test]
+    fn test_mp4_audio_track_meta() {
+        let demuxer = Demuxer::open(&video_asset().to_string_lossy()).unwrap();
+        let tracks = demuxer.audio_tracks();
+        assert_eq!(tracks.len(), 1);
+
+        let meta = &tracks[0].meta;
+        assert_eq!(meta.title, None);  // 封装器写入的 SoundHandler
+        assert_eq!(meta.codec, "aac");
+        assert_eq!((meta.channels, meta.sample_rate), (1, 48_000));
+        assert!(meta.bit_rate.is_some_and(|bps| (64_000..=192_000).contains(&bps)), "{:?}", meta.bit_rate);
+    }
+
+    #[test]
+    fn test_seek_lands_within_one_frame() {
+        // 包含关键帧位置和关键帧之间的位置
+        for target_ms in [0, 1_000, 2_520, 4_040, 6_999, 9_000] {
+            let frame = decode_frame_at(video_asset(), target_ms);
+            assert!(
+                (frame.pts - target_ms).abs() <= FRAME_DURATION_MS,
+                "Seek 到 {}ms 后首帧 PTS={}ms",
+                target_ms,
+                frame.pts
+            );
+            assert_golden_frame(&frame, (target_ms / FRAME_DURATION_MS) as u32, 1);
+        }
+    }
+
+    #[test]
+    fn test_cover_art_is_not_treated_as_video() {
+        let demuxer = Demuxer::open(&cover_art_asset().to_string_lossy()).unwrap();
+        assert_eq!(demuxer.video_stream_index(), None);
+        assert!(demuxer.audio_stream_index().is_some());
+
+        let info = demuxer.get_media_info().unwrap();
+        assert_eq!((info.width, info.height), (0, 0));
+        assert_eq!(info.video_codec, "none");
+        assert!((info.duration - 2_000).abs() < 200, "时长 {}ms", info.duration);
+    }
+
+    #[test]
+    fn test_cover_art_decodes_once() {
+        let demuxer = Demuxer::open(&cover_art_asset().to_string_lossy()).unwrap();
+        let cover = demuxer.cover_art().expect("应当识别出内嵌封面");
+        assert_eq!(cover.codec_id, codec::Id::MJPEG);
+
+        let frame = decode_cover_art(&cover).unwrap();
+        assert_eq!((frame.width, frame.height), (COVER_SIZE, COVER_SIZE));
+        let center = ((COVER_SIZE / 2 * COVER_SIZE + COVER_SIZE / 2) * 4) as usize;
+        for (channel, expected) in COVER_COLOR.iter().enumerate() {
+            let actual = frame.data[center + channel] as i32;
+            assert!((actual - *expected as i32).abs() <= 16, "通道 {}: {} != {}", channel, actual, expected);
+        }
+    }
+}
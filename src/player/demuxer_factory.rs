@@ -1,10 +1,11 @@
-use crate::core::MediaSource;
+use crate::core::{MediaSource, PlayerError};
 use crate::player::Demuxer;
 use crossbeam_channel::Sender;
 use log::{error, info};
 use std::thread;
 
 /// Demuxer 创建结果
+#[allow(clippy::large_enum_variant)] // 每次打开只经通道传递一次，Demuxer 直接返回不装箱
 pub enum DemuxerCreationResult {
     /// 创建成功
     Success {
@@ -14,7 +15,7 @@ pub enum DemuxerCreationResult {
     /// 创建失败
     Failed {
         url: String,
-        error: String,
+        error: PlayerError,  // 保留原始错误，界面据此分类提示
     },
 }
 
@@ -29,7 +30,7 @@ pub enum DemuxerCreationResult {
 /// if let Ok(result) = rx.try_recv() {
 ///     match result {
 ///         DemuxerCreationResult::Success { demuxer, .. } => {
-///             manager.attach_demuxer(demuxer)?;
+///             manager.attach_demuxer(demuxer, BufferingPolicy::Immediate)?;
 ///         }
 ///         DemuxerCreationResult::Failed { error, .. } => {
 ///             error!("创建失败: {}", error);
@@ -64,10 +65,25 @@ impl DemuxerFactory {
                         },
                         Err(e) => DemuxerCreationResult::Failed {
                             url: path_str,
-                            error: e.to_string(),
+                            error: e,
                         },
                     }
                 }
+                MediaSource::ImageSequence { pattern, fps } => {
+                    let template = pattern.template.to_string_lossy().to_string();
+                    match Demuxer::open_image_sequence(&pattern, fps) {
+                        Ok(demuxer) => DemuxerCreationResult::Success { demuxer, url: template },
+                        Err(e) => DemuxerCreationResult::Failed { url: template, error: e },
+                    }
+                }
+                MediaSource::Image(path) => {
+                    // 单张图像由 FFmpeg 作为单帧视频打开
+                    let path_str = path.to_string_lossy().to_string();
+                    match Demuxer::open(&path_str) {
+                        Ok(demuxer) => DemuxerCreationResult::Success { demuxer, url: path_str },
+                        Err(e) => DemuxerCreationResult::Failed { url: path_str, error: e },
+                    }
+                }
                 MediaSource::NetworkStream { url, protocol } => {
                     info!("🌐 创建网络流 Demuxer: {} ({})", url, protocol.as_str());
                     
@@ -79,7 +95,7 @@ impl DemuxerFactory {
                         },
                         Err(e) => DemuxerCreationResult::Failed {
                             url: url.clone(),
-                            error: e.to_string(),
+                            error: e,
                         },
                     }
                 }
@@ -18,6 +18,7 @@ pub struct MediaPacket {
 }
 
 // 实现 Send，允许跨线程传递
+// SAFETY: Packet 独占持有自己的 AVPacket 缓冲区（引用计数由 FFmpeg 线程安全地维护）
 unsafe impl Send for MediaPacket {}
 
 /// Demuxer 数据源抽象接口
@@ -45,14 +46,39 @@ pub trait DemuxerSource: Send {
     /// 获取音频流索引
     fn audio_stream_index(&self) -> Option<usize>;
     
-    /// 获取字幕流索引
-    fn subtitle_stream_index(&self) -> Option<usize>;
+    /// 是否为网络源（决定默认的预读窗口）
+    fn is_network(&self) -> bool {
+        false
+    }
     
     /// 是否支持 seek
     fn is_seekable(&self) -> bool {
         true
     }
     
+    /// 数据包的时间戳（毫秒，无法换算时为 None）
+    fn packet_time_ms(&self, _packet: &MediaPacket) -> Option<i64> {
+        None
+    }
+    
+    /// 直播流当前可回看的范围（毫秒；不是直播或不能回看时为 None）
+    fn seekable_window(&self) -> Option<(i64, i64)> {
+        None
+    }
+    
+    /// 数据包的结束时间（微秒，文件时间；单曲循环据此计算一遍的时长）
+    fn packet_end_us(&self, _packet: &MediaPacket) -> Option<i64> {
+        None
+    }
+    
+    /// 流的起始时间（微秒，未知时为 0）
+    fn stream_start_us(&self, _index: usize) -> i64 {
+        0
+    }
+    
+    /// 给数据包的时间戳加上偏移（微秒，单曲循环时使内部时间轴连续）
+    fn shift_packet(&self, _packet: &mut MediaPacket, _offset_us: i64) {}
+    
     /// 获取描述信息（用于调试）
     fn description(&self) -> String;
 }
@@ -1,8 +1,19 @@
 use crate::core::Result;
-use crate::player::demuxer_source::DemuxerSource;
-use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use crate::player::demux_end::DemuxEnd;
+use crate::player::debug_commands::{DebugCommand, DebugPort};
+use crate::player::demuxer_source::{DemuxerSource, PacketType};
+use crate::player::live::LiveTracker;
+use crate::player::read_ahead::{self, ReadAhead};
+use crate::player::seamless_loop::{LoopControl, LoopTimeline};
+use crate::player::seek_status::{SeekOutcome, SeekStatus};
+use crate::player::stall_watchdog::StallWatchdog;
+use crate::player::timeshift::{TimeshiftBuffer, TimeshiftLimits, TimeshiftRead};
+use crate::player::PacketInspector;
+use crossbeam_channel::{bounded, unbounded, Receiver, RecvError, RecvTimeoutError, SendError, Sender, TryRecvError};
 use ffmpeg_next as ffmpeg;
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
 use std::process;
@@ -11,33 +22,157 @@ fn log_ctx() -> String {
     format!("[pid:{} tid:{:?}]", process::id(), thread::current().id())
 }
 
+/// 预读超出窗口时，两次检查预读量之间的等待时间（期间收到命令会立即唤醒）
+const READ_AHEAD_POLL: Duration = Duration::from_millis(50);
+
+/// 读到末尾后，两次重新读包之间的等待时间（期间收到命令会立即唤醒）
+const END_POLL: Duration = Duration::from_millis(100);
+
+/// 时移：读到录制末端后等待录制线程写入的最长时间（之后先处理命令）
+const TIMESHIFT_POLL: Duration = Duration::from_millis(50);
+
 /// Demuxer 线程命令
 pub enum DemuxerCommand {
     Seek(i64), // ms
     Stop,
 }
 
+/// Seek 栅栏：区分通道里 seek 之前送出的旧包
+///
+/// 播放管理器发出 seek 时 requested 加一；解封装线程执行 seek 时先记下每个通道已送出的包数，再把 applied 加一。
+/// 解码线程收到包时：seek 还没有执行（applied < requested），或序号不超过记录值，都是旧位置的数据，直接丢弃。
+#[derive(Debug, Default)]
+struct SeekFence {
+    requested: AtomicU64,
+    applied: AtomicU64,
+}
+
+/// 解封装线程一侧的包通道（记录送出的包数）
+struct PacketSender {
+    tx: Sender<ffmpeg::Packet>,
+    sent: u64,
+    stale_through: Arc<AtomicU64>,
+}
+
+impl PacketSender {
+    fn send(&mut self, packet: ffmpeg::Packet) -> std::result::Result<(), SendError<ffmpeg::Packet>> {
+        self.tx.send(packet)?;
+        self.sent += 1;
+        Ok(())
+    }
+
+    /// Seek 时调用：已送出的包都是旧位置的数据
+    fn mark_stale(&self) {
+        self.stale_through.store(self.sent, Ordering::SeqCst);
+    }
+
+    fn len(&self) -> usize {
+        self.tx.len()
+    }
+}
+
+/// 解码线程一侧的包通道（跳过 seek 之前的旧包）
+pub struct PacketReceiver {
+    rx: Receiver<ffmpeg::Packet>,
+    received: u64,
+    stale_through: Arc<AtomicU64>,
+    fence: Arc<SeekFence>,
+}
+
+impl PacketReceiver {
+    /// 阻塞等待下一个包；发送端全部关闭时返回 Err
+    pub fn recv(&mut self) -> std::result::Result<ffmpeg::Packet, RecvError> {
+        loop {
+            let packet = self.rx.recv()?;
+            if !self.is_stale() {
+                return Ok(packet);
+            }
+        }
+    }
+
+    /// 取出下一个包（没有包时立即返回 Empty）
+    pub fn try_recv(&mut self) -> std::result::Result<ffmpeg::Packet, TryRecvError> {
+        loop {
+            let packet = self.rx.try_recv()?;
+            if !self.is_stale() {
+                return Ok(packet);
+            }
+        }
+    }
+
+    /// 刚收到的包是否为 seek 之前的旧包（先读 applied 再读 requested：此后才发出的 seek 也视为未执行）
+    fn is_stale(&mut self) -> bool {
+        self.received += 1;
+        let applied = self.fence.applied.load(Ordering::SeqCst);
+        applied < self.fence.requested.load(Ordering::SeqCst) || self.received <= self.stale_through.load(Ordering::SeqCst)
+    }
+}
+
+/// 创建一个有界包通道
+fn packet_channel(capacity: usize, fence: &Arc<SeekFence>) -> (PacketSender, PacketReceiver) {
+    let (tx, rx) = bounded::<ffmpeg::Packet>(capacity);
+    let stale_through = Arc::new(AtomicU64::new(0));
+    (
+        PacketSender { tx, sent: 0, stale_through: stale_through.clone() },
+        PacketReceiver { rx, received: 0, stale_through, fence: fence.clone() },
+    )
+}
+
+/// 解码线程使用的包接收端（视频、音频、字幕）
+pub struct PacketReceivers {
+    pub video: PacketReceiver,
+    pub audio: PacketReceiver,
+    pub subtitle: PacketReceiver,
+}
+
 /// Demuxer 线程管理器
 /// - packet 的传递从无界 SegQueue 改为有界 channel (Sender/Receiver)
-/// - start() 返回的结构体保留接收端 (Receiver)，供解码线程使用
+/// - start() 返回的结构体保留接收端，由 take_receivers() 交给解码线程
+/// - 除通道容量外，还按媒体时间限制预读（见 read_ahead 模块）
+/// - 单曲循环：短的本地文件读到末尾后在线程内 seek 回开头继续读包（见 seamless_loop 模块）
+/// - 直播时移：录制线程读包写入临时文件，本线程从录制中取包（见 timeshift 模块）
 pub struct DemuxerThread {
     thread_handle: Option<JoinHandle<()>>,
     command_tx: Sender<DemuxerCommand>,
 
-    // 保留发送端的 clone，stop() 会 drop 它们以让接收端退出
+    // 保留发送端的 clone，stop() 会 drop 它们以让接收端退出；也用于查询通道中的包数
     video_packet_tx: Option<Sender<ffmpeg::Packet>>,
     audio_packet_tx: Option<Sender<ffmpeg::Packet>>,
+    subtitle_packet_tx: Option<Sender<ffmpeg::Packet>>,
+
+    // 接收端，供解码线程使用（使用 Option 以便可以取出）
+    receivers: Option<PacketReceivers>,
+
+    // Seek 栅栏（seek() 发出请求，线程执行 seek 后放行新包）
+    fence: Arc<SeekFence>,
+
+    // 直播状态（直播边缘、可回看窗口），非直播源为 None
+    live: Option<LiveTracker>,
 
-    // 外部读包端（接收端），供解码线程使用（替代原先的 SegQueue）
-    // 使用 Option 以便可以取出
-    pub video_packet_queue: Option<Receiver<ffmpeg::Packet>>,
-    pub audio_packet_queue: Option<Receiver<ffmpeg::Packet>>,
+    // 直播时移是否开启（录制成功创建时）
+    timeshift: bool,
+
+    // 预读状态（解码线程写入播放位置，统计面板读取预读量）
+    read_ahead: ReadAhead,
+
+    // Seek 结果（线程执行 seek 后上报，播放管理器读取）
+    seek_status: SeekStatus,
+
+    // 结束原因接收端（线程在关闭 packet 通道前发送），供播放管理器取走
+    end_rx: Option<Receiver<DemuxEnd>>,
 }
 
 impl DemuxerThread {
-    /// 启动 Demuxer 线程
+    /// 启动 Demuxer 线程（timeshift 只对直播源生效）
     /// VIDEO_CAPACITY / AUDIO_CAPACITY 可调：根据目标缓冲时间（秒）与典型 bitrate 估算 packet 数
-    pub fn start(mut demuxer_source: Box<dyn DemuxerSource>) -> Self {
+    pub fn start(
+        mut demuxer_source: Box<dyn DemuxerSource>,
+        inspector: PacketInspector,
+        watchdog: StallWatchdog,
+        mut debug: DebugPort,
+        loops: LoopControl,
+        timeshift: Option<TimeshiftLimits>,
+    ) -> Self {
         // 命令通道（unbounded 足够）
         let (command_tx, command_rx) = unbounded::<DemuxerCommand>();
 
@@ -45,19 +180,82 @@ impl DemuxerThread {
         // 优化：减小容量，让背压更早生效，避免过度缓冲
         // 视频：200 packets ≈ 8秒（25fps），足够缓冲且及时背压
         // 音频：150 packets ≈ 3秒（48kHz），足够缓冲且及时背压
+        // 字幕：数据包稀疏，容量只是防止字幕解码线程卡住时无限堆积
         const VIDEO_CAPACITY: usize = 200;
         const AUDIO_CAPACITY: usize = 150;
+        const SUBTITLE_CAPACITY: usize = 100;
 
-        let (video_tx, video_rx) = bounded::<ffmpeg::Packet>(VIDEO_CAPACITY);
-        let (audio_tx, audio_rx) = bounded::<ffmpeg::Packet>(AUDIO_CAPACITY);
+        let fence = Arc::new(SeekFence::default());
+        let (video_tx, video_rx) = packet_channel(VIDEO_CAPACITY, &fence);
+        let (audio_tx, audio_rx) = packet_channel(AUDIO_CAPACITY, &fence);
+        let (subtitle_tx, subtitle_rx) = packet_channel(SUBTITLE_CAPACITY, &fence);
+        let (end_tx, end_rx) = unbounded::<DemuxEnd>();
 
         // 为了在 stop() 时可以 drop 发送端，我们在结构体里保留一份 Sender clone
-        let video_tx_clone_for_struct = video_tx.clone();
-        let audio_tx_clone_for_struct = audio_tx.clone();
+        let video_tx_clone_for_struct = video_tx.tx.clone();
+        let audio_tx_clone_for_struct = audio_tx.tx.clone();
+        let subtitle_tx_clone_for_struct = subtitle_tx.tx.clone();
+
+        // 直播源：读包时记录直播边缘
+        let live = demuxer_source.get_media_info().is_live.then(LiveTracker::new);
+        let live_for_thread = live.clone();
 
-        // 启动线程：把 Sender (video_tx, audio_tx) 移动到线程中作为写端
-        let thread_handle = thread::spawn(move || {
-            Self::demux_loop(&mut *demuxer_source, command_rx, video_tx, audio_tx);
+        // 直播时移：录到临时目录（无法创建时按普通直播播放）
+        let has_video = demuxer_source.video_stream_index().is_some();
+        let timeshift = timeshift.filter(|_| live.is_some()).and_then(|limits| {
+            match TimeshiftBuffer::create(limits, has_video) {
+                Ok(buffer) => {
+                    info!("{} ⏺ 直播时移已开启（保留 {} 分钟 / {} MB）", log_ctx(), limits.max_duration.as_secs() / 60, limits.max_bytes / 1024 / 1024);
+                    Some(Arc::new(buffer))
+                }
+                Err(e) => {
+                    warn!("{} ⚠️ 无法创建时移录制，按普通直播播放: {}", log_ctx(), e);
+                    None
+                }
+            }
+        });
+        let timeshift_enabled = timeshift.is_some();
+
+        // 按媒体时间限制预读：网络点播 60 秒，本地文件 10 秒，直播不限制
+        let read_ahead = ReadAhead::new(read_ahead::default_window(
+            demuxer_source.get_media_info().is_live,
+            demuxer_source.is_network(),
+        ));
+        let read_ahead_for_thread = read_ahead.clone();
+        let seek_status = SeekStatus::default();
+        let seek_status_for_thread = seek_status.clone();
+        let fence_for_thread = fence.clone();
+
+        // 启动线程：把发送端移动到线程中作为写端
+        let thread_handle = thread::spawn(move || match (timeshift, live_for_thread) {
+            (Some(buffer), Some(live)) => Self::timeshift_loop(
+                demuxer_source,
+                buffer,
+                command_rx,
+                [video_tx, audio_tx, subtitle_tx],
+                end_tx,
+                &fence_for_thread,
+                inspector,
+                live,
+                &read_ahead_for_thread,
+                &seek_status_for_thread,
+                watchdog,
+                debug,
+            ),
+            (_, live) => Self::demux_loop(
+                &mut *demuxer_source,
+                command_rx,
+                [video_tx, audio_tx, subtitle_tx],
+                end_tx,
+                &fence_for_thread,
+                &inspector,
+                live.as_ref(),
+                &read_ahead_for_thread,
+                &seek_status_for_thread,
+                &watchdog,
+                &loops,
+                &mut debug,
+            ),
         });
 
         Self {
@@ -65,8 +263,14 @@ impl DemuxerThread {
             command_tx,
             video_packet_tx: Some(video_tx_clone_for_struct),
             audio_packet_tx: Some(audio_tx_clone_for_struct),
-            video_packet_queue: Some(video_rx),
-            audio_packet_queue: Some(audio_rx),
+            subtitle_packet_tx: Some(subtitle_tx_clone_for_struct),
+            receivers: Some(PacketReceivers { video: video_rx, audio: audio_rx, subtitle: subtitle_rx }),
+            fence,
+            live,
+            timeshift: timeshift_enabled,
+            read_ahead,
+            seek_status,
+            end_rx: Some(end_rx),
         }
     }
 
@@ -75,43 +279,102 @@ impl DemuxerThread {
     /// 关键点：
     /// - 使用 send() 将 packet 发到有界通道。当通道满时 send() 会阻塞，从而自然背压。
     /// - 处理命令使用 try_recv()（非阻塞），以保证尽快响应 Seek/Stop。
+    /// - 预读量超过窗口时在命令通道上等待（recv_timeout），预读量回落或收到命令时继续。
+    /// - Seek 时把通道里剩下的包标记为旧包（解码线程收到时丢弃），不需要在这里清空通道。
+    /// - 读到末尾时发送 DemuxEnd::Eof 后继续等待命令（单曲循环时回到开头继续读包，或等待 UI seek 回开头）；
+    ///   退出前先发送结束原因，再 drop packet 发送端。
+    #[allow(clippy::too_many_arguments)]
     fn demux_loop(
         demuxer: &mut dyn DemuxerSource,
         command_rx: Receiver<DemuxerCommand>,
-        video_tx: Sender<ffmpeg::Packet>,
-        audio_tx: Sender<ffmpeg::Packet>,
+        senders: [PacketSender; 3],
+        end_tx: Sender<DemuxEnd>,
+        fence: &SeekFence,
+        inspector: &PacketInspector,
+        live: Option<&LiveTracker>,
+        read_ahead: &ReadAhead,
+        seek_status: &SeekStatus,
+        watchdog: &StallWatchdog,
+        loops: &LoopControl,
+        debug: &mut DebugPort,
     ) {
         info!("{} 🎬 Demuxer 线程启动: {}", log_ctx(), demuxer.description());
 
+        let [mut video_tx, mut audio_tx, mut subtitle_tx] = senders;
         let mut running = true;
+        let mut end = DemuxEnd::Cancelled;
+        let mut eof_reported = false;
+        let mut at_end = false;
         let mut packet_count: usize = 0;
         let mut video_packet_count: usize = 0;
         let mut audio_packet_count: usize = 0;
+        let mut subtitle_packet_count: usize = 0;
+
+        // 主时钟流（有音频时为音频）的时间线，用于无缝循环
+        let master_stream = demuxer.audio_stream_index().or(demuxer.video_stream_index());
+        let mut timeline = LoopTimeline::new(master_stream.map(|index| demuxer.stream_start_us(index)).unwrap_or(0));
+        let duration_ms = demuxer.get_media_info().duration.max(0);
 
         // 阈值（仅用于日志 & startup buffering 判断）
         const LOG_FIRST_N: usize = 5;
+        // 直播流每读取这么多个包刷新一次可回看窗口
+        const LIVE_WINDOW_REFRESH_PACKETS: usize = 100;
+
+        let mut throttled = false;
+
+        if let Some(window) = read_ahead.window() {
+            info!("{} ⏳ 预读窗口: {}s", log_ctx(), window.as_secs());
+        }
 
         while running {
-            // 优先处理所有命令（非阻塞）
+            // 优先处理所有命令（非阻塞）；预读超出窗口或已读到末尾时在这里等待，Seek/Stop 会立即唤醒
             loop {
-                match command_rx.try_recv() {
+                let ahead = read_ahead.is_ahead();
+                if ahead != throttled {
+                    throttled = ahead;
+                    match read_ahead.lead_ms() {
+                        Some(lead) if ahead => debug!("{} ⏸ 预读 {}ms 超出窗口，暂停读包", log_ctx(), lead),
+                        _ => debug!("{} ▶ 预读量回落，继续读包", log_ctx()),
+                    }
+                }
+                let received = if ahead || at_end {
+                    match command_rx.recv_timeout(if ahead { READ_AHEAD_POLL } else { END_POLL }) {
+                        Ok(cmd) => Ok(cmd),
+                        Err(RecvTimeoutError::Timeout) if ahead => continue,
+                        // 末尾：重新读包（单曲循环的设置可能已改变）
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => {
+                            running = false;
+                            break;
+                        }
+                    }
+                } else {
+                    command_rx.try_recv().map_err(|_| ())
+                };
+                match received {
                     Ok(cmd) => {
                         match cmd {
                             DemuxerCommand::Seek(timestamp_ms) => {
                                 info!("{} ⏩ Demuxer 线程收到 Seek 命令: {}ms", log_ctx(), timestamp_ms);
-                                
-                                // 清空 packet channel 中的旧包，避免解码线程处理旧数据
-                                // 注意：这里只能清空发送端，接收端会在解码线程中自然消费完
-                                // 实际的清空需要通过背压机制：让 channel 阻塞，然后在解码线程中跳过旧包
-                                // 更好的方法是：在 Seek 后，解码线程会跳过旧包，这里只需要执行 seek
-                                
-                                if let Err(e) = demuxer.seek(timestamp_ms) {
+                                let result = demuxer.seek(timestamp_ms);
+                                seek_status.finish(timestamp_ms, SeekOutcome::of(&result));
+                                if let Err(e) = result {
                                     error!("{} ❌ Seek 失败: {}", log_ctx(), e);
                                 } else {
-                                    info!("{} 🧹 Seek 成功（Demuxer 已 Seek），请在解码端清空并 flush 解码器", log_ctx());
-                                    // 注意：packet channel 中的旧包会在解码线程中被跳过（通过 seek_pos 过滤）
-                                    // 不需要在这里清空 channel，因为 channel 是有界的，新包会自然填充
+                                    // 通道里剩下的都是旧位置的包：解码线程收到时丢弃
+                                    for tx in [&video_tx, &audio_tx, &subtitle_tx] {
+                                        tx.mark_stale();
+                                    }
+                                    eof_reported = false;
+                                    at_end = false;
+                                    read_ahead.reset(timestamp_ms);
+                                    // 回到文件时间轴（UI 已按折回后的位置 seek）
+                                    timeline.reset_offset();
+                                    loops.set_waiting_restart(false);
+                                    info!("{} 🧹 Seek 成功（通道中 视频{} 音频{} 字幕{} 个旧包将被丢弃）", log_ctx(), video_tx.len(), audio_tx.len(), subtitle_tx.len());
                                 }
+                                // 放行之后送出的包（seek 失败时继续读原来位置的包）
+                                fence.applied.fetch_add(1, Ordering::SeqCst);
                             }
                             DemuxerCommand::Stop => {
                                 info!("{} ⏹ Demuxer 线程收到停止命令", log_ctx());
@@ -131,13 +394,40 @@ impl DemuxerThread {
                 break;
             }
 
-            // 读取包（阻塞返回 None 表示 EOF）
-            match demuxer.read_packet() {
-                Ok(Some(media_packet)) => {
+            // 读取包（阻塞返回 None 表示 EOF）；读包阶段由停滞监视计时
+            watchdog.begin_read();
+            if let Some(DebugCommand::StallDemuxer(stall)) = debug.poll() {
+                info!("{} 🧪 调试：模拟解封装停滞 {}ms（包队列 视频{} 音频{}）", log_ctx(), stall.as_millis(), video_tx.len(), audio_tx.len());
+                thread::sleep(stall);
+                info!("{} 🧪 调试：解封装停滞结束（包队列 视频{} 音频{}）", log_ctx(), video_tx.len(), audio_tx.len());
+            }
+            let read = demuxer.read_packet();
+            watchdog.end_read();
+            match read {
+                Ok(Some(mut media_packet)) => {
                     packet_count += 1;
+                    at_end = false;
+                    inspector.record(&media_packet.packet);
+                    // 单曲循环：按文件时间记录一遍的长度，之后的包加上已循环的偏移
+                    if Some(media_packet.stream_index) == master_stream {
+                        if let Some(end_us) = demuxer.packet_end_us(&media_packet) {
+                            timeline.observe(end_us);
+                        }
+                    }
+                    demuxer.shift_packet(&mut media_packet, timeline.offset_us());
+                    let pts_ms = demuxer.packet_time_ms(&media_packet);
+                    if let Some(live) = live {
+                        if let Some(pts_ms) = pts_ms {
+                            live.observe_packet(pts_ms);
+                        }
+                        if packet_count % LIVE_WINDOW_REFRESH_PACKETS == 1 {
+                            live.set_window(demuxer.seekable_window());
+                        }
+                    }
 
-                    match media_packet.packet_type {
-                        crate::player::demuxer_source::PacketType::Video => {
+                    let packet_type = media_packet.packet_type;
+                    match packet_type {
+                        PacketType::Video => {
                             video_packet_count += 1;
                             if video_packet_count <= LOG_FIRST_N || video_packet_count % 100 == 0 {
                                 info!("{} 📦 Demuxer 读取视频包 #{}（total packets {}）", log_ctx(), video_packet_count, packet_count);
@@ -148,8 +438,11 @@ impl DemuxerThread {
                                 error!("{} ❌ 发送视频包失败，接收端可能已关闭", log_ctx());
                                 break;
                             }
+                            if let Some(pts_ms) = pts_ms {
+                                read_ahead.record_sent(packet_type, pts_ms);
+                            }
                         }
-                        crate::player::demuxer_source::PacketType::Audio => {
+                        PacketType::Audio => {
                             audio_packet_count += 1;
                             if audio_packet_count <= LOG_FIRST_N || audio_packet_count % 100 == 0 {
                                 info!("{} 🔊 Demuxer 读取音频包 #{}（total packets {}）", log_ctx(), audio_packet_count, packet_count);
@@ -159,49 +452,266 @@ impl DemuxerThread {
                                 error!("{} ❌ 发送音频包失败，接收端可能已关闭", log_ctx());
                                 break;
                             }
+                            if let Some(pts_ms) = pts_ms {
+                                read_ahead.record_sent(packet_type, pts_ms);
+                            }
                         }
-                        _ => {
-                            // 忽略字幕/数据包
+                        PacketType::Subtitle => {
+                            // 没有字幕解码线程时（字幕解码器创建失败）接收端已关闭，丢弃字幕包继续播放
+                            if subtitle_tx.send(media_packet.packet).is_ok() {
+                                subtitle_packet_count += 1;
+                            }
                         }
                     }
                 }
+                Ok(None) if loops.repeat_one() => {
+                    // 短的本地文件：seek 回开头继续读包，时间轴累加一遍的时长（解码器和时钟都不重置）
+                    let seamless = !demuxer.is_network() && loops.allows_seamless(duration_ms);
+                    if seamless && !loops.is_waiting_restart() {
+                        if let Some(span_us) = timeline.span_us() {
+                            match demuxer.seek(0) {
+                                Ok(()) => {
+                                    timeline.wrap();
+                                    loops.set_timeline(timeline.start_us(), span_us);
+                                    info!("{} 🔁 无缝循环: 回到开头（一遍 {} ms，共处理 {} 个包）", log_ctx(), span_us / 1000, packet_count);
+                                    continue;
+                                }
+                                Err(e) => warn!("{} ⚠️  无缝循环 seek 失败，改为普通循环: {}", log_ctx(), e),
+                            }
+                        }
+                    }
+                    // 普通循环：等待帧队列播完后由 UI seek 回开头
+                    if !loops.is_waiting_restart() {
+                        info!("{} 🔁 文件读取完毕（单曲循环），等待回到开头", log_ctx());
+                        loops.set_waiting_restart(true);
+                    }
+                    at_end = true;
+                }
                 Ok(None) => {
-                    // 到达 EOF：保持线程存活，等待 Seek/Stop
-                    info!("{} 📄 Demuxer 到达文件末尾，等待命令（Seek/Stop）...", log_ctx());
-                    // 不忙等：短睡眠，避免 CPU 空转
-                    thread::sleep(Duration::from_millis(100));
-                    continue;
+                    // 到达 EOF：通知管理器（每次读到末尾只通知一次），保持线程存活，等待 Seek/Stop
+                    if !eof_reported {
+                        info!("{} 📄 Demuxer 到达文件末尾，等待命令（Seek/Stop）...", log_ctx());
+                        loops.set_waiting_restart(false);
+                        let _ = end_tx.send(DemuxEnd::Eof);
+                        eof_reported = true;
+                    }
+                    at_end = true;
                 }
                 Err(e) => {
-                    error!("{} ❌ 读取包失败: {}", log_ctx(), e);
+                    end = DemuxEnd::from_error(&e);
+                    error!("{} ❌ 读取包失败: {}（{:?}）", log_ctx(), e, end);
                     break;
                 }
             }
         }
 
-        info!("{} 🛑 Demuxer 线程退出（共读取 {} 个包：{} 视频，{} 音频）",
+        info!("{} 🛑 Demuxer 线程退出（共读取 {} 个包：{} 视频，{} 音频，{} 字幕）",
               log_ctx(),
-              packet_count, video_packet_count, audio_packet_count);
-        // 当退出时，发送端 (video_tx/audio_tx) 会被 drop（线程作用域结束），
+              packet_count, video_packet_count, audio_packet_count, subtitle_packet_count);
+        // 先发送结束原因，管理器在解码线程看到通道关闭前就能拿到
+        let _ = end_tx.send(end);
+        // 当退出时，发送端会被 drop（线程作用域结束），
         // 这样接收端的 recv() 会返回 Err，相关解码线程可以退出。
     }
 
-    /// 发送 Seek 命令
-    pub fn seek(&self, timestamp_ms: i64) -> Result<()> {
-        self.command_tx
-            .send(DemuxerCommand::Seek(timestamp_ms))
-            .map_err(|e| crate::core::error::PlayerError::Other(format!("发送 Seek 命令失败: {}", e)))
+    /// 时移模式的解封装循环（开启时移的直播流）
+    ///
+    /// - 录制线程持续读包写入时移缓冲，不受通道背压影响（暂停期间继续录制）
+    /// - 本线程从读取位置取出录制的包送给解码线程，通道满时 send() 阻塞，读取位置随之落后于录制末端
+    /// - Seek 在录制范围内移动读取位置（从目标之前的关键帧开始），不会 seek 直播流本身
+    /// - 录制结束后读完剩余的数据再上报结束原因；直播结束（EOF）后仍可以在录制中 seek
+    #[allow(clippy::too_many_arguments)]
+    fn timeshift_loop(
+        demuxer: Box<dyn DemuxerSource>,
+        buffer: Arc<TimeshiftBuffer>,
+        command_rx: Receiver<DemuxerCommand>,
+        senders: [PacketSender; 3],
+        end_tx: Sender<DemuxEnd>,
+        fence: &SeekFence,
+        inspector: PacketInspector,
+        live: LiveTracker,
+        read_ahead: &ReadAhead,
+        seek_status: &SeekStatus,
+        watchdog: StallWatchdog,
+        debug: DebugPort,
+    ) {
+        info!("{} 🎬 Demuxer 线程启动（时移）: {}", log_ctx(), demuxer.description());
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let recorder = {
+            let buffer = buffer.clone();
+            let stop = stop.clone();
+            thread::spawn(move || Self::record_loop(demuxer, &buffer, &stop, &inspector, &live, &watchdog, debug))
+        };
+
+        let [mut video_tx, mut audio_tx, mut subtitle_tx] = senders;
+        let mut cursor = 0;
+        let mut running = true;
+        let mut end = DemuxEnd::Cancelled;
+        let mut eof_reported = false;
+        let mut sent_count: usize = 0;
+
+        while running {
+            // 处理所有命令；直播已结束且录制已读完时在这里等待
+            loop {
+                let command = if eof_reported {
+                    match command_rx.recv_timeout(END_POLL) {
+                        Ok(cmd) => Some(cmd),
+                        Err(RecvTimeoutError::Timeout) => None,
+                        Err(RecvTimeoutError::Disconnected) => Some(DemuxerCommand::Stop),
+                    }
+                } else {
+                    command_rx.try_recv().ok()
+                };
+                match command {
+                    Some(DemuxerCommand::Seek(timestamp_ms)) => {
+                        info!("{} ⏩ Demuxer 线程收到 Seek 命令（时移）: {}ms", log_ctx(), timestamp_ms);
+                        match buffer.seek_index(timestamp_ms) {
+                            Some(index) => {
+                                cursor = index;
+                                // 通道里剩下的都是旧位置的包：解码线程收到时丢弃
+                                for tx in [&video_tx, &audio_tx, &subtitle_tx] {
+                                    tx.mark_stale();
+                                }
+                                eof_reported = false;
+                                read_ahead.reset(timestamp_ms);
+                                seek_status.finish(timestamp_ms, SeekOutcome::Succeeded);
+                                info!("{} ⏪ 时移: 从录制中的关键帧继续（窗口 {:?}）", log_ctx(), buffer.window());
+                            }
+                            None => {
+                                warn!("{} ⚠️ 时移录制中还没有关键帧，无法 seek", log_ctx());
+                                seek_status.finish(timestamp_ms, SeekOutcome::Failed("时移录制中还没有关键帧".to_string()));
+                            }
+                        }
+                        // 放行之后送出的包
+                        fence.applied.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Some(DemuxerCommand::Stop) => {
+                        info!("{} ⏹ Demuxer 线程收到停止命令", log_ctx());
+                        running = false;
+                        break;
+                    }
+                    None => break,
+                }
+            }
+
+            if !running {
+                break;
+            }
+
+            match buffer.read(&mut cursor, TIMESHIFT_POLL) {
+                Ok(TimeshiftRead::Packet(media_packet, time_ms)) => {
+                    sent_count += 1;
+                    let packet_type = media_packet.packet_type;
+                    // send 会在通道满时阻塞（暂停时读取位置停住，录制线程继续录制）
+                    let sent = match packet_type {
+                        PacketType::Video => video_tx.send(media_packet.packet).is_ok(),
+                        PacketType::Audio => audio_tx.send(media_packet.packet).is_ok(),
+                        // 没有字幕解码线程时接收端已关闭，丢弃字幕包继续播放
+                        PacketType::Subtitle => {
+                            let _ = subtitle_tx.send(media_packet.packet);
+                            true
+                        }
+                    };
+                    if !sent {
+                        error!("{} ❌ 发送数据包失败，接收端可能已关闭", log_ctx());
+                        break;
+                    }
+                    if let (Some(time_ms), PacketType::Video | PacketType::Audio) = (time_ms, packet_type) {
+                        read_ahead.record_sent(packet_type, time_ms);
+                    }
+                }
+                Ok(TimeshiftRead::Waiting) => {}
+                Ok(TimeshiftRead::Ended(DemuxEnd::Eof)) => {
+                    if !eof_reported {
+                        info!("{} 📄 直播已结束，时移录制已读完，等待命令（Seek/Stop）...", log_ctx());
+                        let _ = end_tx.send(DemuxEnd::Eof);
+                        eof_reported = true;
+                    }
+                }
+                Ok(TimeshiftRead::Ended(reason)) => {
+                    end = reason;
+                    break;
+                }
+                Err(e) => {
+                    end = DemuxEnd::from_error(&e);
+                    error!("{} ❌ 读取时移录制失败: {}（{:?}）", log_ctx(), e, end);
+                    break;
+                }
+            }
+        }
+
+        info!("{} 🛑 Demuxer 线程退出（时移，共送出 {} 个包）", log_ctx(), sent_count);
+        stop.store(true, Ordering::SeqCst);
+        let _ = end_tx.send(end);
+        // 先关闭包通道让解码线程退出，再等待录制线程读完当前的包
+        drop([video_tx, audio_tx, subtitle_tx]);
+        let _ = recorder.join();
     }
 
-    /// 暂停读取（占位：若要在 demux 保存 paused 状态，可实现 Pause 命令）
-    pub fn pause(&self) -> Result<()> {
-        // TODO: 实现 pause/resume 命令处理
-        Ok(())
+    /// 时移录制线程：持续读取直播流写入时移缓冲，更新直播边缘和可回看窗口
+    fn record_loop(
+        mut demuxer: Box<dyn DemuxerSource>,
+        buffer: &TimeshiftBuffer,
+        stop: &AtomicBool,
+        inspector: &PacketInspector,
+        live: &LiveTracker,
+        watchdog: &StallWatchdog,
+        mut debug: DebugPort,
+    ) {
+        let mut packet_count: usize = 0;
+        while !stop.load(Ordering::SeqCst) {
+            watchdog.begin_read();
+            if let Some(DebugCommand::StallDemuxer(stall)) = debug.poll() {
+                info!("{} 🧪 调试：模拟解封装停滞 {}ms（时移录制）", log_ctx(), stall.as_millis());
+                thread::sleep(stall);
+            }
+            let read = demuxer.read_packet();
+            watchdog.end_read();
+            match read {
+                Ok(Some(media_packet)) => {
+                    packet_count += 1;
+                    inspector.record(&media_packet.packet);
+                    let time_ms = demuxer.packet_time_ms(&media_packet);
+                    if let Some(time_ms) = time_ms {
+                        live.observe_packet(time_ms);
+                    }
+                    if let Err(e) = buffer.record(&media_packet, time_ms) {
+                        error!("{} ❌ 写入时移录制失败: {}", log_ctx(), e);
+                        buffer.finish(DemuxEnd::IoError(e.to_string()));
+                        break;
+                    }
+                    live.set_window(buffer.window());
+                }
+                Ok(None) => {
+                    info!("{} 📄 直播流结束，停止时移录制", log_ctx());
+                    buffer.finish(DemuxEnd::Eof);
+                    break;
+                }
+                Err(e) => {
+                    let end = DemuxEnd::from_error(&e);
+                    error!("{} ❌ 读取直播流失败: {}（{:?}）", log_ctx(), e, end);
+                    buffer.finish(end);
+                    break;
+                }
+            }
+        }
+        info!("{} 🛑 时移录制线程退出（共录制 {} 个包）", log_ctx(), packet_count);
     }
 
-    /// 恢复读取（占位）
-    pub fn resume(&self) -> Result<()> {
-        Ok(())
+    /// 发送 Seek 命令（之后解码线程丢弃通道中的旧包，直到线程执行 seek）
+    pub fn seek(&self, timestamp_ms: i64) -> Result<()> {
+        self.fence.requested.fetch_add(1, Ordering::SeqCst);
+        self.command_tx.send(DemuxerCommand::Seek(timestamp_ms)).map_err(|e| {
+            self.fence.requested.fetch_sub(1, Ordering::SeqCst);
+            crate::core::error::PlayerError::Other(format!("发送 Seek 命令失败: {}", e))
+        })
+    }
+
+    /// 通道中等待解码的包数（视频, 音频），用于网络流的起播缓冲
+    pub fn queued_packets(&self) -> (usize, usize) {
+        let len = |tx: &Option<Sender<ffmpeg::Packet>>| tx.as_ref().map_or(0, Sender::len);
+        (len(&self.video_packet_tx), len(&self.audio_packet_tx))
     }
 
     /// 停止线程（可被外部调用）
@@ -215,19 +725,42 @@ impl DemuxerThread {
         // drop the packet senders so receivers get disconnected and recv() returns Err
         self.video_packet_tx.take();
         self.audio_packet_tx.take();
+        self.subtitle_packet_tx.take();
 
         if let Some(handle) = self.thread_handle.take() {
             let _ = handle.join();
         }
     }
     
+    /// 直播状态（非直播源为 None）
+    pub fn live_tracker(&self) -> Option<&LiveTracker> {
+        self.live.as_ref()
+    }
+
+    /// 是否在时移模式（直播流录到临时文件，从录制中播放）
+    pub fn is_timeshifting(&self) -> bool {
+        self.timeshift
+    }
+
+    /// 预读状态（解码线程写入播放位置）
+    pub fn read_ahead(&self) -> &ReadAhead {
+        &self.read_ahead
+    }
+
+    /// Seek 结果（播放管理器发出 seek 时记为进行中）
+    pub fn seek_status(&self) -> &SeekStatus {
+        &self.seek_status
+    }
+
+    /// 取出结束原因接收端（交给播放管理器）
+    pub fn take_end_receiver(&mut self) -> Option<Receiver<DemuxEnd>> {
+        self.end_rx.take()
+    }
+
     /// 取出接收端（用于传递给解码线程）
-    /// 注意：调用此方法后，DemuxerThread 将不再持有 Receiver
-    pub fn take_receivers(&mut self) -> (Receiver<ffmpeg::Packet>, Receiver<ffmpeg::Packet>) {
-        (
-            self.video_packet_queue.take().expect("video_packet_queue already taken"),
-            self.audio_packet_queue.take().expect("audio_packet_queue already taken"),
-        )
+    /// 注意：调用此方法后，DemuxerThread 将不再持有接收端；没有解码线程的流应 drop 对应的接收端
+    pub fn take_receivers(&mut self) -> PacketReceivers {
+        self.receivers.take().expect("packet receivers already taken")
     }
 }
 
@@ -240,6 +773,7 @@ impl Drop for DemuxerThread {
             // drop senders
             self.video_packet_tx.take();
             self.audio_packet_tx.take();
+            self.subtitle_packet_tx.take();
 
             if let Some(handle) = self.thread_handle.take() {
                 let _ = handle.join();
@@ -247,3 +781,304 @@ impl Drop for DemuxerThread {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{MediaInfo, PlayerError};
+    use crate::player::debug_commands::{DebugCommands, DebugTarget};
+    use crate::player::demuxer_source::{MediaPacket, PacketType};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    const PACKET_MS: i64 = 40;
+
+    /// 限速的点播源：每个视频包 40ms，读一个包耗时 1ms，记录读取的包数
+    struct ThrottledSource {
+        media_info: MediaInfo,
+        next_pts: i64,
+        reads: Arc<AtomicUsize>,
+        seekable: bool,
+    }
+
+    impl DemuxerSource for ThrottledSource {
+        fn read_packet(&mut self) -> Result<Option<MediaPacket>> {
+            thread::sleep(Duration::from_millis(1));
+            self.next_pts += PACKET_MS;
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(MediaPacket { packet: ffmpeg::Packet::empty(), packet_type: PacketType::Video, stream_index: 0 }))
+        }
+        fn seek(&mut self, timestamp_ms: i64) -> Result<()> {
+            if !self.seekable {
+                return Err(PlayerError::SeekUnsupported);
+            }
+            self.next_pts = timestamp_ms - PACKET_MS;
+            Ok(())
+        }
+        fn get_media_info(&self) -> &MediaInfo {
+            &self.media_info
+        }
+        fn video_stream_index(&self) -> Option<usize> {
+            Some(0)
+        }
+        fn audio_stream_index(&self) -> Option<usize> {
+            None
+        }
+        fn is_network(&self) -> bool {
+            true
+        }
+        fn packet_time_ms(&self, _packet: &MediaPacket) -> Option<i64> {
+            Some(self.next_pts)
+        }
+        fn description(&self) -> String {
+            "throttled mock".to_string()
+        }
+    }
+
+    /// 视频包和字幕包交替的短文件：共 SUBTITLE_PACKETS 组，读完后返回 EOF
+    struct SubtitledSource {
+        media_info: MediaInfo,
+        next: usize,
+    }
+
+    const SUBTITLE_PACKETS: usize = 5;
+
+    impl DemuxerSource for SubtitledSource {
+        fn read_packet(&mut self) -> Result<Option<MediaPacket>> {
+            if self.next >= SUBTITLE_PACKETS * 2 {
+                return Ok(None);
+            }
+            let (packet_type, stream_index) = if self.next.is_multiple_of(2) { (PacketType::Video, 0) } else { (PacketType::Subtitle, 1) };
+            self.next += 1;
+            Ok(Some(MediaPacket { packet: ffmpeg::Packet::empty(), packet_type, stream_index }))
+        }
+        fn seek(&mut self, _timestamp_ms: i64) -> Result<()> {
+            self.next = 0;
+            Ok(())
+        }
+        fn get_media_info(&self) -> &MediaInfo {
+            &self.media_info
+        }
+        fn video_stream_index(&self) -> Option<usize> {
+            Some(0)
+        }
+        fn audio_stream_index(&self) -> Option<usize> {
+            None
+        }
+        fn is_network(&self) -> bool {
+            true
+        }
+        fn packet_time_ms(&self, _packet: &MediaPacket) -> Option<i64> {
+            Some(self.next as i64 * PACKET_MS)
+        }
+        fn description(&self) -> String {
+            "subtitled mock".to_string()
+        }
+    }
+
+    /// 纯音频的直播源：每个包 20ms，读一个包耗时 1ms（远快于实时），记录读取的包数
+    struct LiveSource {
+        media_info: MediaInfo,
+        next_pts: i64,
+        reads: Arc<AtomicUsize>,
+    }
+
+    const LIVE_PACKET_MS: i64 = 20;
+
+    impl DemuxerSource for LiveSource {
+        fn read_packet(&mut self) -> Result<Option<MediaPacket>> {
+            thread::sleep(Duration::from_millis(1));
+            self.next_pts += LIVE_PACKET_MS;
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(MediaPacket { packet: ffmpeg::Packet::empty(), packet_type: PacketType::Audio, stream_index: 0 }))
+        }
+        fn seek(&mut self, _timestamp_ms: i64) -> Result<()> {
+            Err(PlayerError::SeekUnsupported)
+        }
+        fn get_media_info(&self) -> &MediaInfo {
+            &self.media_info
+        }
+        fn video_stream_index(&self) -> Option<usize> {
+            None
+        }
+        fn audio_stream_index(&self) -> Option<usize> {
+            Some(0)
+        }
+        fn is_network(&self) -> bool {
+            true
+        }
+        fn packet_time_ms(&self, _packet: &MediaPacket) -> Option<i64> {
+            Some(self.next_pts)
+        }
+        fn description(&self) -> String {
+            "live mock".to_string()
+        }
+    }
+
+    /// 取出通道中当前所有的包，返回包数
+    fn drain(receiver: &mut PacketReceiver) -> usize {
+        std::iter::from_fn(|| receiver.try_recv().ok()).count()
+    }
+
+    /// 等待下一次 EOF 通知
+    fn wait_for_eof(end_rx: &Receiver<DemuxEnd>) {
+        assert_eq!(end_rx.recv_timeout(Duration::from_secs(5)).unwrap(), DemuxEnd::Eof);
+    }
+
+    #[test]
+    fn test_subtitle_packets_are_routed_and_dropped_after_seek() {
+        let source = SubtitledSource { media_info: MediaInfo::default(), next: 0 };
+        let mut demuxer_thread = DemuxerThread::start(
+            Box::new(source),
+            PacketInspector::new(),
+            StallWatchdog::default(),
+            DebugCommands::default().port(DebugTarget::Demuxer),
+            LoopControl::default(),
+            None,
+        );
+        let end_rx = demuxer_thread.take_end_receiver().unwrap();
+        let mut receivers = demuxer_thread.take_receivers();
+
+        // 字幕包进入字幕通道，不混入视频通道
+        wait_for_eof(&end_rx);
+        assert_eq!(drain(&mut receivers.subtitle), SUBTITLE_PACKETS);
+        assert_eq!(drain(&mut receivers.video), SUBTITLE_PACKETS);
+
+        // 读完后再积压一遍，seek 之后只收到 seek 之后读出的字幕包
+        demuxer_thread.seek(0).unwrap();
+        wait_for_eof(&end_rx);
+        demuxer_thread.seek(0).unwrap();
+        wait_for_eof(&end_rx);
+        assert_eq!(drain(&mut receivers.subtitle), SUBTITLE_PACKETS);
+        assert_eq!(drain(&mut receivers.video), SUBTITLE_PACKETS);
+
+        demuxer_thread.stop();
+    }
+
+    /// 等待读包数稳定下来，返回稳定后的包数
+    fn wait_for_plateau(reads: &AtomicUsize) -> usize {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut last = reads.load(Ordering::SeqCst);
+        loop {
+            thread::sleep(Duration::from_millis(150));
+            let now = reads.load(Ordering::SeqCst);
+            if now == last || Instant::now() > deadline {
+                return now;
+            }
+            last = now;
+        }
+    }
+
+    #[test]
+    fn test_read_ahead_plateaus_until_playback_advances() {
+        let reads = Arc::new(AtomicUsize::new(0));
+        let source = ThrottledSource { media_info: MediaInfo::default(), next_pts: -PACKET_MS, reads: reads.clone(), seekable: true };
+        let mut demuxer_thread = DemuxerThread::start(
+            Box::new(source),
+            PacketInspector::new(),
+            StallWatchdog::default(),
+            DebugCommands::default().port(DebugTarget::Demuxer),
+            LoopControl::default(),
+            None,
+        );
+        let read_ahead = demuxer_thread.read_ahead().clone();
+        assert_eq!(read_ahead.window(), Some(read_ahead::NETWORK_READ_AHEAD));
+        read_ahead.set_window(Some(Duration::from_secs(2)));
+
+        // 播放位置不动时只读取约 2 秒的数据（远小于通道容量），之后停止下载
+        let plateau = wait_for_plateau(&reads);
+        let window_packets = (2_000 / PACKET_MS) as usize;
+        assert!((window_packets..window_packets + 5).contains(&plateau), "读取了 {} 个包", plateau);
+        assert!(read_ahead.lead_ms().is_some_and(|lead| lead > 2_000));
+
+        // 播放推进 1 秒后继续读取约 1 秒的数据
+        read_ahead.set_position(1_000);
+        let advanced = wait_for_plateau(&reads);
+        assert!((plateau + 20..plateau + 30).contains(&advanced), "读取了 {} 个包", advanced);
+
+        // Seek 立即唤醒读包并从目标位置重新计算预读量
+        demuxer_thread.seek(600_000).unwrap();
+        let after_seek = wait_for_plateau(&reads);
+        assert!((advanced + window_packets..advanced + window_packets + 5).contains(&after_seek), "读取了 {} 个包", after_seek);
+        assert!(read_ahead.lead_ms().is_some_and(|lead| (2_000..2_200).contains(&lead)));
+
+        demuxer_thread.stop();
+    }
+
+    #[test]
+    fn test_timeshift_keeps_recording_while_paused_and_seeks_back() {
+        let reads = Arc::new(AtomicUsize::new(0));
+        let media_info = MediaInfo { is_live: true, ..MediaInfo::default() };
+        let source = LiveSource { media_info, next_pts: -LIVE_PACKET_MS, reads: reads.clone() };
+        let mut demuxer_thread = DemuxerThread::start(
+            Box::new(source),
+            PacketInspector::new(),
+            StallWatchdog::default(),
+            DebugCommands::default().port(DebugTarget::Demuxer),
+            LoopControl::default(),
+            Some(TimeshiftLimits::default()),
+        );
+        assert!(demuxer_thread.is_timeshifting());
+        let live = demuxer_thread.live_tracker().unwrap().clone();
+        let read_ahead = demuxer_thread.read_ahead().clone();
+        let mut receivers = demuxer_thread.take_receivers();
+
+        // 不取包（暂停）：通道满了之后仍继续录制直播流，可回看窗口随之增长
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while reads.load(Ordering::SeqCst) < 600 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let status = live.status();
+        let (start_ms, end_ms) = status.window.unwrap();
+        assert_eq!(start_ms, 0);
+        assert!(end_ms >= 599 * LIVE_PACKET_MS, "{}", end_ms);
+
+        // 在录制范围内 seek：不 seek 直播流，从录制中继续送包
+        let seek_status = demuxer_thread.seek_status().clone();
+        seek_status.begin(6_000);
+        demuxer_thread.seek(6_000).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut fresh = 0;
+        while fresh < 10 && Instant::now() < deadline {
+            match receivers.audio.try_recv() {
+                Ok(_) => fresh += 1,
+                Err(_) => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        assert_eq!(seek_status.last().unwrap().outcome, SeekOutcome::Succeeded);
+        let buffered_ms = read_ahead.buffered_to_ms().unwrap();
+        assert!((6_000..10_000).contains(&buffered_ms), "{}", buffered_ms);
+        assert!(live.status().edge_ms.unwrap() > buffered_ms);
+
+        drop(receivers);
+        demuxer_thread.stop();
+    }
+
+    #[test]
+    fn test_seek_result_is_reported() {
+        for (seekable, expected) in [(true, SeekOutcome::Succeeded), (false, SeekOutcome::Unsupported)] {
+            let reads = Arc::new(AtomicUsize::new(0));
+            let source = ThrottledSource { media_info: MediaInfo::default(), next_pts: -PACKET_MS, reads, seekable };
+            let mut demuxer_thread = DemuxerThread::start(
+                Box::new(source),
+                PacketInspector::new(),
+                StallWatchdog::default(),
+                DebugCommands::default().port(DebugTarget::Demuxer),
+                LoopControl::default(),
+                None,
+            );
+            // 播放管理器发出 seek 时记为进行中，线程执行后上报结果
+            let status = demuxer_thread.seek_status().clone();
+            status.begin(30_000);
+            demuxer_thread.seek(30_000).unwrap();
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while status.last().is_some_and(|result| result.outcome == SeekOutcome::Pending) && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(5));
+            }
+            let result = status.last().unwrap();
+            assert_eq!((result.target_ms, result.outcome), (30_000, expected));
+            demuxer_thread.stop();
+        }
+    }
+}
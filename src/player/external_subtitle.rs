@@ -1,4 +1,4 @@
-use crate::core::{Result, SubtitleFrame};
+use crate::core::{truncate_chars, Result, SubtitleFrame, SUPPORTED_SUBTITLE_EXTENSIONS};
 use log::{info, warn};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -18,11 +18,8 @@ impl ExternalSubtitleParser {
             if let Some(file_stem) = video_path.file_stem() {
                 let file_stem = file_stem.to_string_lossy();
                 
-                // 支持的字幕文件扩展名
-                let subtitle_extensions = ["srt", "ass", "ssa", "vtt"];
-                
                 // 方法1: 精确匹配 - video_name.srt, video_name.ass 等
-                for ext in &subtitle_extensions {
+                for ext in SUPPORTED_SUBTITLE_EXTENSIONS {
                     let subtitle_path = parent_dir.join(format!("{}.{}", file_stem, ext));
                     if subtitle_path.exists() {
                         info!("找到精确匹配字幕文件: {}", subtitle_path.display());
@@ -33,7 +30,7 @@ impl ExternalSubtitleParser {
                 // 方法2: 语言标识匹配 - video_name.zh.srt, video_name.en.srt
                 let language_codes = ["zh", "en", "chs", "cht", "zh-cn", "zh-tw", "ja", "ko", "chs-eng"];
                 for lang in &language_codes {
-                    for ext in &subtitle_extensions {
+                    for ext in SUPPORTED_SUBTITLE_EXTENSIONS {
                         let subtitle_path = parent_dir.join(format!("{}.{}.{}", file_stem, lang, ext));
                         if subtitle_path.exists() {
                             info!("找到语言标识字幕文件: {}", subtitle_path.display());
@@ -51,7 +48,7 @@ impl ExternalSubtitleParser {
                         for entry in entries.flatten() {
                             if let Some(entry_name) = entry.file_name().to_str() {
                                 // 检查是否是字幕文件
-                                let is_subtitle = subtitle_extensions.iter().any(|ext| {
+                                let is_subtitle = SUPPORTED_SUBTITLE_EXTENSIONS.iter().any(|ext| {
                                     entry_name.to_lowercase().ends_with(&format!(".{}", ext))
                                 });
                                 
@@ -121,9 +118,10 @@ impl ExternalSubtitleParser {
             }
         }
         
-        // 如果关键词太少，添加原始文件名的前几个字符
-        if keywords.len() < 2 && filename.len() > 10 {
-            keywords.push(filename[..10.min(filename.len())].to_string());
+        // 如果关键词太少，添加原始文件名的前几个字符（按字符截断，中文文件名不能按字节下标切片）
+        let prefix = truncate_chars(filename, 10);
+        if keywords.len() < 2 && prefix.len() < filename.len() {
+            keywords.push(prefix.to_string());
         }
         
         keywords
@@ -492,6 +490,15 @@ mod tests {
         assert_eq!(ExternalSubtitleParser::parse_srt_timestamp("01:23:45,123"), Some(5025123));
     }
 
+    #[test]
+    fn test_extract_keywords_multibyte_prefix() {
+        // 第 10 个字节落在中文字符中间（过去按字节切片会 panic）
+        let keywords = ExternalSubtitleParser::extract_keywords("喜洋洋与灰太狼第一季");
+        assert_eq!(keywords, vec!["喜洋洋与灰太狼第一季".to_string()]);
+        let keywords = ExternalSubtitleParser::extract_keywords("喜洋洋与灰太狼之羊羊运动会");
+        assert_eq!(keywords.last().map(String::as_str), Some("喜洋洋与灰太狼之羊羊"));
+    }
+
     #[test]
     fn test_parse_ass_timestamp() {
         assert_eq!(ExternalSubtitleParser::parse_ass_timestamp("0:01:30.50"), Some(90500));
@@ -0,0 +1,296 @@
+// 首帧检测（视频流存在但始终解码不出画面）
+//
+// DRM 加密的 m4v 占位文件、截断的下载等文件能正常打开并报告视频流，却一帧也解码不出来，
+// 界面只会一直显示空白。视频解码线程统计收到的包数、出错的包数和解码出的帧数；播放开始后，
+// 正在播放的墙上时间（缓冲、暂停、挂起时不计时）超过期限仍没有任何视频帧时报告诊断：
+// - NoPackets：没有收到视频包（视频流为空或数据被截断）
+// - DecodeErrors：每个视频包都解码出错
+// - NoFrames：收到了视频包、解码器也没有报错，但始终没有输出画面
+// 只在管线包含视频解码器时启用（纯音频源不检测）；每个管线最多报告一次。
+
+use crate::core::{PlayerError, Result};
+use crate::player::decode_failure::is_fatal;
+use crate::player::decoder::DecoderStats;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 默认的首帧期限
+pub const DEFAULT_FIRST_FRAME_DEADLINE: Duration = Duration::from_secs(5);
+
+/// 视频解码统计（某一时刻的快照）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecodeCounts {
+    /// 送入解码器的包
+    pub packets: u64,
+    /// 解码出错的包（EAGAIN / EOF 不计）
+    pub errors: u64,
+    /// 解码出的帧
+    pub frames: u64,
+}
+
+/// 视频解码统计（解码线程写入，管理器读取）
+#[derive(Debug, Clone, Default)]
+pub struct VideoDecodeCounters {
+    packets: Arc<AtomicU64>,
+    errors: Arc<AtomicU64>,
+    frames: Arc<AtomicU64>,
+    hardware_frames: Arc<AtomicU64>,
+    software_frames: Arc<AtomicU64>,
+}
+
+impl VideoDecodeCounters {
+    /// 记录一个包的解码结果
+    pub fn record<T>(&self, result: &Result<Vec<T>>) {
+        self.packets.fetch_add(1, Ordering::Relaxed);
+        match result {
+            Ok(frames) => {
+                self.frames.fetch_add(frames.len() as u64, Ordering::Relaxed);
+            }
+            Err(e) if is_fatal(e) || matches!(e, PlayerError::InvalidFrameSize { .. }) => {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// 记录解码器的硬件 / 软件解码帧数（累计值）
+    pub fn record_decoder(&self, stats: DecoderStats) {
+        self.hardware_frames.store(stats.hardware_frames, Ordering::Relaxed);
+        self.software_frames.store(stats.software_frames, Ordering::Relaxed);
+    }
+
+    pub fn decoder_stats(&self) -> DecoderStats {
+        DecoderStats {
+            hardware_frames: self.hardware_frames.load(Ordering::Relaxed),
+            software_frames: self.software_frames.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn snapshot(&self) -> DecodeCounts {
+        DecodeCounts {
+            packets: self.packets.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            frames: self.frames.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 计入另一组计数的包 / 帧 / 错误（接管预读的开头时计入预读时的解码）
+    pub fn add(&self, other: &VideoDecodeCounters) {
+        let counts = other.snapshot();
+        self.packets.fetch_add(counts.packets, Ordering::Relaxed);
+        self.errors.fetch_add(counts.errors, Ordering::Relaxed);
+        self.frames.fetch_add(counts.frames, Ordering::Relaxed);
+    }
+
+    /// 新管线启动时重置
+    pub fn reset(&self) {
+        self.packets.store(0, Ordering::Relaxed);
+        self.errors.store(0, Ordering::Relaxed);
+        self.frames.store(0, Ordering::Relaxed);
+        self.hardware_frames.store(0, Ordering::Relaxed);
+        self.software_frames.store(0, Ordering::Relaxed);
+    }
+}
+
+/// 期限内没有解码出视频帧的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirstFrameDiagnosis {
+    /// 没有收到视频包
+    NoPackets,
+    /// 收到了视频包，解码器没有输出画面
+    NoFrames {
+        /// 收到的视频包数
+        packets: u64,
+    },
+    /// 每个视频包都解码出错
+    DecodeErrors {
+        /// 收到的视频包数
+        packets: u64,
+    },
+}
+
+impl FirstFrameDiagnosis {
+    /// 按解码统计判断原因（已有视频帧时为 None）
+    pub fn classify(counts: DecodeCounts) -> Option<Self> {
+        if counts.frames > 0 {
+            return None;
+        }
+        Some(if counts.packets == 0 {
+            Self::NoPackets
+        } else if counts.errors >= counts.packets {
+            Self::DecodeErrors { packets: counts.packets }
+        } else {
+            Self::NoFrames { packets: counts.packets }
+        })
+    }
+
+    /// 提示文字
+    pub fn message(&self) -> String {
+        match self {
+            Self::NoPackets => "视频流没有数据，文件可能不完整或已损坏".to_string(),
+            Self::NoFrames { packets } => {
+                format!("视频流存在但无法解码，文件可能受 DRM 保护或已损坏（{} 个视频包没有解码出画面）", packets)
+            }
+            Self::DecodeErrors { packets } => {
+                format!("视频流存在但无法解码，文件可能受 DRM 保护或已损坏（{} 个视频包全部解码出错）", packets)
+            }
+        }
+    }
+}
+
+/// 首帧期限计时（每个播放管理器一个，新管线启动时重新开始）
+#[derive(Debug)]
+pub struct FirstFrameWatch {
+    deadline: Duration,
+    armed: bool,
+    active_time: Duration,       // 已计时的播放时间
+    last_poll: Option<Instant>,
+}
+
+impl Default for FirstFrameWatch {
+    fn default() -> Self {
+        Self { deadline: DEFAULT_FIRST_FRAME_DEADLINE, armed: false, active_time: Duration::ZERO, last_poll: None }
+    }
+}
+
+impl FirstFrameWatch {
+    pub fn set_deadline(&mut self, deadline: Duration) {
+        self.deadline = deadline;
+    }
+
+    /// 新管线启动时调用；没有视频解码器时不检测
+    pub fn restart(&mut self, has_video: bool) {
+        self.armed = has_video;
+        self.active_time = Duration::ZERO;
+        self.last_poll = None;
+    }
+
+    /// 停止检测（已经报告或用户改为只播放音频）
+    pub fn disarm(&mut self) {
+        self.armed = false;
+    }
+
+    /// 检查是否超过期限；active 为当前是否正在播放（缓冲、暂停时不计时）
+    pub fn poll(&mut self, counts: DecodeCounts, active: bool, now: Instant) -> Option<FirstFrameDiagnosis> {
+        if !self.armed {
+            return None;
+        }
+        if counts.frames > 0 {
+            self.armed = false;
+            return None;
+        }
+        let last_poll = self.last_poll.replace(now);
+        if let (true, Some(last_poll)) = (active, last_poll) {
+            self.active_time += now.saturating_duration_since(last_poll);
+        }
+        if self.active_time < self.deadline {
+            return None;
+        }
+        self.armed = false;
+        FirstFrameDiagnosis::classify(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ffmpeg_next as ffmpeg;
+
+    /// 模拟的视频解码器行为
+    enum MockDecoder {
+        Working,
+        Silent,     // 始终 EAGAIN（需要更多数据），从不输出帧
+        Encrypted,  // 每个包都出错
+    }
+
+    impl MockDecoder {
+        fn decode(&self) -> Result<Vec<u32>> {
+            match self {
+                Self::Working => Ok(vec![1]),
+                Self::Silent => Err(PlayerError::FFmpegError(ffmpeg::Error::Other { errno: 11 })),
+                Self::Encrypted => Err(PlayerError::DecodeError("Invalid data found when processing input".to_string())),
+            }
+        }
+    }
+
+    /// 按 100ms 一步模拟播放：每步送入 packets_per_step 个包，返回第一次报告的诊断和时间
+    fn run(decoder: MockDecoder, packets_per_step: usize, active: impl Fn(u32) -> bool) -> Option<(FirstFrameDiagnosis, u32)> {
+        let counters = VideoDecodeCounters::default();
+        let mut watch = FirstFrameWatch::default();
+        watch.restart(true);
+        let start = Instant::now();
+        let mut reported = None;
+        for step in 0..200 {
+            for _ in 0..packets_per_step {
+                counters.record(&decoder.decode());
+            }
+            let now = start + Duration::from_millis(100 * step as u64);
+            if let Some(diagnosis) = watch.poll(counters.snapshot(), active(step), now) {
+                assert!(reported.is_none(), "只应报告一次");
+                reported = Some((diagnosis, step));
+            }
+        }
+        reported
+    }
+
+    #[test]
+    fn test_each_cause_is_classified() {
+        assert_eq!(run(MockDecoder::Working, 1, |_| true), None);
+        assert_eq!(run(MockDecoder::Working, 0, |_| true), Some((FirstFrameDiagnosis::NoPackets, 50)));
+        assert_eq!(run(MockDecoder::Silent, 2, |_| true), Some((FirstFrameDiagnosis::NoFrames { packets: 102 }, 50)));
+        assert_eq!(run(MockDecoder::Encrypted, 1, |_| true), Some((FirstFrameDiagnosis::DecodeErrors { packets: 51 }, 50)));
+
+        // 偶尔出错但大多数包没有输出：按解码器没有输出处理
+        let counts = DecodeCounts { packets: 40, errors: 3, frames: 0 };
+        assert_eq!(FirstFrameDiagnosis::classify(counts), Some(FirstFrameDiagnosis::NoFrames { packets: 40 }));
+        assert!(FirstFrameDiagnosis::DecodeErrors { packets: 51 }.message().contains("DRM"));
+    }
+
+    #[test]
+    fn test_buffering_and_pause_do_not_count() {
+        // 前 3 秒在缓冲，之后开始计时
+        assert_eq!(run(MockDecoder::Encrypted, 1, |step| step >= 30), Some((FirstFrameDiagnosis::DecodeErrors { packets: 80 }, 79)));
+        // 一直缓冲 / 暂停：不报告
+        assert_eq!(run(MockDecoder::Silent, 1, |_| false), None);
+    }
+
+    #[test]
+    fn test_decoder_stats_follow_the_pipeline() {
+        let counters = VideoDecodeCounters::default();
+        counters.record_decoder(DecoderStats { hardware_frames: 120, software_frames: 0 });
+        // 播放中改用软件解码：硬解帧数保留，软解帧数继续增长
+        counters.record_decoder(DecoderStats { hardware_frames: 120, software_frames: 30 });
+        assert_eq!(counters.clone().decoder_stats(), DecoderStats { hardware_frames: 120, software_frames: 30 });
+        counters.reset();
+        assert_eq!(counters.decoder_stats(), DecoderStats::default());
+    }
+
+    #[test]
+    fn test_prefetched_counts_are_added() {
+        // 预读时解码了 3 个包（其中 1 个出错），接管后解码线程继续计数
+        let prefetched = VideoDecodeCounters::default();
+        for decoder in [MockDecoder::Working, MockDecoder::Encrypted, MockDecoder::Working] {
+            prefetched.record(&decoder.decode());
+        }
+        let counters = VideoDecodeCounters::default();
+        counters.add(&prefetched);
+        counters.record(&MockDecoder::Working.decode());
+        assert_eq!(counters.snapshot(), DecodeCounts { packets: 4, errors: 1, frames: 3 });
+    }
+
+    #[test]
+    fn test_audio_only_pipeline_is_not_checked() {
+        let mut watch = FirstFrameWatch::default();
+        watch.restart(false);
+        let start = Instant::now();
+        for step in 0..100 {
+            let now = start + Duration::from_millis(100 * step);
+            assert_eq!(watch.poll(DecodeCounts::default(), true, now), None);
+        }
+        // 用户选择只播放音频后不再报告
+        watch.restart(true);
+        watch.disarm();
+        assert_eq!(watch.poll(DecodeCounts::default(), true, start + Duration::from_secs(60)), None);
+    }
+}
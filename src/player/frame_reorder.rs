@@ -0,0 +1,228 @@
+// 视频帧按显示顺序送出（B 帧重排序校正）
+//
+// 解码器送出的帧通常已经是显示顺序，但 open GOP 的 x264 编码在 seek 后，关键帧之前的前导 B 帧
+// 可能晚于关键帧送出。界面按"PTS ≤ 时钟的最新帧"选帧，这样的帧会让画面瞬间回跳。
+// 解码线程把帧先放入一个小的重排序缓冲区（深度取解码器的 has_b_frames），按 PTS 从小到大送出：
+// - 收到比已收到的帧更早的帧时计一次校正，并把缓冲区加深一帧（不超过 MAX_REORDER_DEPTH）
+// - 其中比已送出的帧还早的帧已经无法排序，直接丢弃
+// - 比已送出的帧早超过 DISCONTINUITY_MS 时视为时间戳不连续（直播流时间戳重置），重新开始计序
+// seek / flush 时清空缓冲区；输入中断（文件末尾或数据暂时不足）时送出缓冲区内的全部帧。
+
+use crate::core::VideoFrame;
+use log::debug;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// 重排序缓冲区的最大深度（H.264 / HEVC 的最大重排序帧数）
+pub const MAX_REORDER_DEPTH: usize = 16;
+
+/// 超过该时长的 PTS 回退视为时间戳不连续，而不是帧乱序
+const DISCONTINUITY_MS: i64 = 1000;
+
+/// 视频帧重排序缓冲区（每个视频解码线程一个）
+pub struct FrameReorder {
+    pending: VecDeque<VideoFrame>,  // 按 PTS 升序
+    depth: usize,
+    last_emitted: Option<i64>,      // 最后送出的 PTS
+    newest: Option<i64>,            // 已收到的最大 PTS
+    corrections: Arc<AtomicU64>,    // 乱序校正次数（统计面板显示）
+}
+
+impl FrameReorder {
+    pub fn new(depth: usize, corrections: Arc<AtomicU64>) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            depth: depth.min(MAX_REORDER_DEPTH),
+            last_emitted: None,
+            newest: None,
+            corrections,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// 解码器报告的重排序深度（解码过程中可能变大；缓冲区只加深不变浅）
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = self.depth.max(depth.min(MAX_REORDER_DEPTH));
+    }
+
+    /// 放入一帧，返回可以送出的帧（PTS 递增）
+    pub fn push(&mut self, frame: VideoFrame) -> Vec<VideoFrame> {
+        let mut ready = Vec::new();
+        if self.last_emitted.is_some_and(|last| frame.pts < last - DISCONTINUITY_MS) {
+            debug!("🎬 视频 PTS 不连续: {:?}ms -> {}ms，重新开始计序", self.last_emitted, frame.pts);
+            ready.extend(self.drain());
+            self.last_emitted = None;
+            self.newest = None;
+        }
+        if self.newest.is_some_and(|newest| frame.pts < newest) {
+            self.record_correction();
+            self.depth = (self.depth + 1).min(MAX_REORDER_DEPTH);
+            if let Some(last) = self.last_emitted.filter(|&last| frame.pts < last) {
+                debug!("🎬 丢弃乱序视频帧: PTS={}ms < 已送出 {}ms，缓冲区深度 {}", frame.pts, last, self.depth);
+                return ready;
+            }
+            debug!("🎬 视频帧乱序: PTS={}ms，缓冲区深度 {}", frame.pts, self.depth);
+        }
+        self.newest = Some(self.newest.map_or(frame.pts, |newest| newest.max(frame.pts)));
+
+        let index = self.pending.partition_point(|pending| pending.pts <= frame.pts);
+        self.pending.insert(index, frame);
+        while self.pending.len() > self.depth {
+            let Some(frame) = self.pending.pop_front() else {
+                break;
+            };
+            self.last_emitted = Some(frame.pts);
+            ready.push(frame);
+        }
+        ready
+    }
+
+    /// 送出缓冲区内的全部帧（输入中断时调用）
+    pub fn drain(&mut self) -> Vec<VideoFrame> {
+        let frames: Vec<VideoFrame> = self.pending.drain(..).collect();
+        if let Some(frame) = frames.last() {
+            self.last_emitted = Some(frame.pts);
+        }
+        self.newest = self.last_emitted;
+        frames
+    }
+
+    /// 丢弃缓冲区内的帧并重新开始计序（seek / flush 时调用）
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.last_emitted = None;
+        self.newest = None;
+    }
+
+    fn record_correction(&self) {
+        self.corrections.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::PixelFormat;
+    use crate::player::{Demuxer, VideoDecoder};
+    use crate::test_support::{assert_golden_frame, open_gop_asset, FRAME_DURATION_MS};
+
+    fn frame(pts: i64) -> VideoFrame {
+        VideoFrame { pts, duration: 40, width: 2, height: 2, format: PixelFormat::RGBA, planes: None, data: vec![0; 16].into() }
+    }
+
+    fn push_all(reorder: &mut FrameReorder, pts: &[i64]) -> Vec<i64> {
+        let mut out: Vec<i64> = pts.iter().flat_map(|&pts| reorder.push(frame(pts))).map(|f| f.pts).collect();
+        out.extend(reorder.drain().into_iter().map(|f| f.pts));
+        out
+    }
+
+    #[test]
+    fn test_frames_are_emitted_in_pts_order() {
+        let corrections = Arc::new(AtomicU64::new(0));
+        let mut reorder = FrameReorder::new(2, corrections.clone());
+        assert_eq!(push_all(&mut reorder, &[0, 120, 40, 80, 240, 160, 200]), vec![0, 40, 80, 120, 160, 200, 240]);
+        assert_eq!(corrections.load(Ordering::Relaxed), 4);
+
+        // 深度为 0 时按原样立即送出
+        let mut reorder = FrameReorder::new(0, Arc::new(AtomicU64::new(0)));
+        assert_eq!(reorder.push(frame(0)).len(), 1);
+        assert_eq!(reorder.push(frame(40)).len(), 1);
+    }
+
+    #[test]
+    fn test_late_frames_are_dropped_and_depth_grows() {
+        let corrections = Arc::new(AtomicU64::new(0));
+        let mut reorder = FrameReorder::new(0, corrections.clone());
+        // 前导 B 帧晚于关键帧到达：已经送出了更晚的帧，只能丢弃
+        assert_eq!(push_all(&mut reorder, &[200, 120, 160, 240, 280]), vec![200, 240, 280]);
+        assert_eq!(corrections.load(Ordering::Relaxed), 2);
+
+        // 深度为 0 时乱序帧只能丢弃，之后的乱序由加深的缓冲区校正
+        let mut reorder = FrameReorder::new(0, corrections.clone());
+        assert_eq!(reorder.push(frame(0)).len(), 1);
+        assert_eq!(reorder.push(frame(80)).len(), 1);
+        assert!(reorder.push(frame(40)).is_empty());
+        assert_eq!(reorder.depth(), 1);
+        assert!(reorder.push(frame(160)).is_empty());
+        assert!(reorder.push(frame(120)).is_empty());
+        assert_eq!(reorder.depth(), 2);
+        assert_eq!(reorder.drain().iter().map(|f| f.pts).collect::<Vec<_>>(), vec![120, 160]);
+
+        reorder.set_depth(MAX_REORDER_DEPTH * 2);
+        assert_eq!(reorder.depth(), MAX_REORDER_DEPTH);
+        reorder.set_depth(1);
+        assert_eq!(reorder.depth(), MAX_REORDER_DEPTH);
+    }
+
+    #[test]
+    fn test_clear_and_discontinuity_restart_ordering() {
+        let corrections = Arc::new(AtomicU64::new(0));
+        let mut reorder = FrameReorder::new(1, corrections.clone());
+        assert_eq!(push_all(&mut reorder, &[5_000, 5_040, 5_080]), vec![5_000, 5_040, 5_080]);
+
+        // 向后 seek：清空后从新位置开始
+        reorder.push(frame(9_000));
+        reorder.clear();
+        assert_eq!(push_all(&mut reorder, &[2_000, 2_040]), vec![2_000, 2_040]);
+
+        // 时间戳重置（直播流）：不丢帧
+        assert_eq!(push_all(&mut reorder, &[0, 40, 80]), vec![0, 40, 80]);
+        assert_eq!(corrections.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_open_gop_seek_yields_monotonic_pts() {
+        let path = open_gop_asset();
+        let mut demuxer = Demuxer::open(&path.to_string_lossy()).expect("无法打开测试视频");
+        let mut decoder = VideoDecoder::from_stream_software(demuxer.video_stream().expect("测试视频缺少视频流"))
+            .expect("无法创建视频解码器");
+
+        // 从头播放一段后 seek 到 GOP 中间，跨越 seek 边界检查送出的 PTS
+        let corrections = Arc::new(AtomicU64::new(0));
+        let mut reorder = FrameReorder::new(decoder.reorder_depth(), corrections.clone());
+        let mut before_seek = Vec::new();
+        while before_seek.len() < 30 {
+            let Some((packet, is_video, _)) = demuxer.read_packet().expect("读取数据包失败") else {
+                break;
+            };
+            if is_video {
+                let frames = decoder.decode(&packet).expect("视频解码失败");
+                reorder.set_depth(decoder.reorder_depth());
+                before_seek.extend(frames.into_iter().flat_map(|frame| reorder.push(frame)).map(|frame| frame.pts));
+            }
+        }
+
+        let target_ms = 4_500;
+        demuxer.seek(target_ms).expect("Seek 失败");
+        decoder.flush().expect("视频解码器 flush 失败");
+        reorder.clear();
+
+        let mut after_seek = Vec::new();
+        while after_seek.len() < 60 {
+            let Some((packet, is_video, _)) = demuxer.read_packet().expect("读取数据包失败") else {
+                break;
+            };
+            if is_video {
+                let frames = decoder.decode(&packet).expect("视频解码失败");
+                reorder.set_depth(decoder.reorder_depth());
+                after_seek.extend(frames.into_iter().flat_map(|frame| reorder.push(frame)));
+            }
+        }
+        after_seek.extend(reorder.drain());
+
+        assert!(before_seek.windows(2).all(|w| w[0] < w[1]), "seek 前 PTS 非递增: {:?}", before_seek);
+        let pts: Vec<i64> = after_seek.iter().map(|frame| frame.pts).collect();
+        assert!(pts.windows(2).all(|w| w[0] < w[1]), "seek 后 PTS 非递增: {:?}", pts);
+
+        // 到达 seek 目标后的画面与帧序号一致
+        let target = after_seek
+            .iter()
+            .find(|frame| frame.pts >= target_ms - FRAME_DURATION_MS / 2)
+            .expect("seek 后没有到达目标位置的帧");
+        assert_golden_frame(target, (target.pts / FRAME_DURATION_MS) as u32, 1);
+    }
+}
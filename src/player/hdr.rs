@@ -0,0 +1,209 @@
+// HDR 格式兼容性检测（杜比视界 / HDR10+）
+//
+// 杜比视界 Profile 5 没有兼容基础层，解码出的是 IPT-PQ 数据，按普通 YUV 管线显示会变成洋红/绿色，
+// 只能检测出来提示用户；Profile 8.x 等带兼容基础层的文件按基础层正常播放，增强数据被忽略。
+// 配置记录依次从流的 DOVI_CONF 附加数据（FFmpeg 已解析的结构体）、extradata 中的
+// dvcC / dvvC / dvwC 配置盒读取，都没有时按 dvhe / dvh1 编码标签推断。
+
+use ffmpeg_next as ffmpeg;
+use ffmpeg_next::codec::packet::side_data::Type as SideDataType;
+
+use crate::core::ffi_util;
+use crate::core::HdrCompatibility;
+
+/// 杜比视界配置记录（dvcC / dvvC / dvwC 配置盒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DoviConfig {
+    pub profile: u8,
+    pub level: u8,
+    pub rpu_present: bool,
+    pub el_present: bool,
+    pub bl_present: bool,
+    /// 基础层信号兼容性（0 = 不兼容，1 = HDR10，2 = SDR，4 = HLG，6 = 蓝光 HDR10）
+    pub bl_compatibility_id: u8,
+}
+
+/// 配置盒类型（dvvC 用于 Profile 8~10，dvwC 用于 Profile 11 及以上）
+const DOVI_BOX_TYPES: [&[u8; 4]; 3] = [b"dvcC", b"dvvC", b"dvwC"];
+
+/// 没有兼容基础层的杜比视界采样条目（编码标签）
+const DOVI_CODEC_TAGS: [&[u8; 4]; 2] = [b"dvhe", b"dvh1"];
+
+impl DoviConfig {
+    /// 解析配置盒内容（不含盒头）：
+    /// dv_version_major(8) dv_version_minor(8) dv_profile(7) dv_level(6)
+    /// rpu_present_flag(1) el_present_flag(1) bl_present_flag(1) dv_bl_signal_compatibility_id(4) ...
+    pub fn parse_box(payload: &[u8]) -> Option<Self> {
+        let &[major, _minor, b2, b3, b4, ..] = payload else {
+            return None;
+        };
+        if major == 0 {
+            return None;
+        }
+        Some(Self {
+            profile: b2 >> 1,
+            level: ((b2 & 0x01) << 5) | (b3 >> 3),
+            rpu_present: b3 & 0x04 != 0,
+            el_present: b3 & 0x02 != 0,
+            bl_present: b3 & 0x01 != 0,
+            bl_compatibility_id: b4 >> 4,
+        })
+    }
+
+    /// 解析 FFmpeg 的 AVDOVIDecoderConfigurationRecord（每个字段一个字节）
+    pub fn parse_side_data(data: &[u8]) -> Option<Self> {
+        let &[major, _minor, profile, level, rpu, el, bl, compatibility, ..] = data else {
+            return None;
+        };
+        if major == 0 {
+            return None;
+        }
+        Some(Self {
+            profile,
+            level,
+            rpu_present: rpu != 0,
+            el_present: el != 0,
+            bl_present: bl != 0,
+            bl_compatibility_id: compatibility,
+        })
+    }
+
+    /// 在 extradata 中查找配置盒（盒头为 4 字节大小 + 4 字节类型）
+    pub fn find_in_extradata(extradata: &[u8]) -> Option<Self> {
+        extradata.windows(4).enumerate().find_map(|(offset, kind)| {
+            if offset < 4 || !DOVI_BOX_TYPES.iter().any(|box_type| kind == box_type.as_slice()) {
+                return None;
+            }
+            let size = u32::from_be_bytes(extradata[offset - 4..offset].try_into().ok()?) as usize;
+            let payload = extradata.get(offset + 4..(offset - 4).checked_add(size)?)?;
+            Self::parse_box(payload)
+        })
+    }
+}
+
+/// 根据检测到的信息判断兼容性
+pub fn classify(dovi: Option<DoviConfig>, codec_tag: u32, has_hdr10_plus: bool) -> HdrCompatibility {
+    match dovi {
+        // 没有兼容基础层（Profile 5）：解码出的画面无法按常规管线显示
+        Some(config) if config.bl_compatibility_id == 0 => {
+            HdrCompatibility::DolbyVisionUnsupported { profile: Some(config.profile) }
+        }
+        Some(config) => HdrCompatibility::DolbyVisionBaseLayer {
+            profile: config.profile,
+            compatibility: config.bl_compatibility_id,
+        },
+        None if DOVI_CODEC_TAGS.iter().any(|tag| codec_tag.to_le_bytes() == **tag) => {
+            HdrCompatibility::DolbyVisionUnsupported { profile: None }
+        }
+        None if has_hdr10_plus => HdrCompatibility::Hdr10Plus,
+        None => HdrCompatibility::Standard,
+    }
+}
+
+/// 检测视频流的 HDR 兼容性
+pub fn detect(stream: &ffmpeg::format::stream::Stream) -> HdrCompatibility {
+    let mut dovi = None;
+    let mut has_hdr10_plus = false;
+    for data in stream.side_data() {
+        match data.kind() {
+            SideDataType::DOVI_CONF => dovi = dovi.or_else(|| DoviConfig::parse_side_data(data.data())),
+            SideDataType::DYNAMIC_HDR10_PLUS => has_hdr10_plus = true,
+            _ => {}
+        }
+    }
+
+    let parameters = stream.parameters();
+    let (codec_tag, extradata) = ffi_util::codec_tag_and_extradata(&parameters);
+    if dovi.is_none() {
+        dovi = DoviConfig::find_in_extradata(extradata);
+    }
+    classify(dovi, codec_tag, has_hdr10_plus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// dvcC 配置盒（杜比视界 Profile 5，level 6，只有 RPU + 基础层，不兼容）
+    const PROFILE_5_BOX: [u8; 24] = [
+        0x01, 0x00, 0x0a, 0x35, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    /// dvvC 配置盒（杜比视界 Profile 8.1，level 6，基础层兼容 HDR10）
+    const PROFILE_8_1_BOX: [u8; 24] = [
+        0x01, 0x00, 0x10, 0x35, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    /// 普通 HDR10 的 hvcC（HEVC Main 10，没有杜比视界配置盒）
+    const HDR10_HVCC: [u8; 23] = [
+        0x01, 0x02, 0x20, 0x00, 0x00, 0x00, 0x90, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x99, 0xf0, 0x00, 0xfc, 0xfd, 0xfa, 0xfa, 0x00, 0x00, 0x0f, 0x00,
+    ];
+
+    /// 盒头（大小 + 类型）+ 内容
+    fn with_box_header(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    fn tag(fourcc: &[u8; 4]) -> u32 {
+        u32::from_le_bytes(*fourcc)
+    }
+
+    #[test]
+    fn test_parse_profile_5_box() {
+        let config = DoviConfig::parse_box(&PROFILE_5_BOX).unwrap();
+        assert_eq!(
+            config,
+            DoviConfig { profile: 5, level: 6, rpu_present: true, el_present: false, bl_present: true, bl_compatibility_id: 0 }
+        );
+        assert_eq!(classify(Some(config), tag(b"dvh1"), false), HdrCompatibility::DolbyVisionUnsupported { profile: Some(5) });
+    }
+
+    #[test]
+    fn test_parse_profile_8_1_box() {
+        let config = DoviConfig::parse_box(&PROFILE_8_1_BOX).unwrap();
+        assert_eq!((config.profile, config.level, config.bl_compatibility_id), (8, 6, 1));
+        let compat = classify(Some(config), tag(b"hvc1"), false);
+        assert_eq!(compat, HdrCompatibility::DolbyVisionBaseLayer { profile: 8, compatibility: 1 });
+        assert!(compat.is_supported());
+        assert_eq!(compat.notice().unwrap(), "杜比视界 Profile 8.1：播放兼容基础层，已忽略增强数据");
+    }
+
+    #[test]
+    fn test_find_box_in_extradata() {
+        let mut extradata = HDR10_HVCC.to_vec();
+        extradata.extend(with_box_header(b"dvvC", &PROFILE_8_1_BOX));
+        assert_eq!(DoviConfig::find_in_extradata(&extradata), DoviConfig::parse_box(&PROFILE_8_1_BOX));
+
+        // 截断的配置盒不解析
+        let truncated = &extradata[..extradata.len() - 20];
+        assert_eq!(DoviConfig::find_in_extradata(truncated), None);
+    }
+
+    #[test]
+    fn test_plain_hdr10_is_standard() {
+        assert_eq!(DoviConfig::find_in_extradata(&HDR10_HVCC), None);
+        let compat = classify(None, tag(b"hvc1"), false);
+        assert_eq!(compat, HdrCompatibility::Standard);
+        assert_eq!(compat.notice(), None);
+        assert_eq!(classify(None, tag(b"hvc1"), true), HdrCompatibility::Hdr10Plus);
+    }
+
+    #[test]
+    fn test_side_data_record_and_codec_tag_fallback() {
+        // AVDOVIDecoderConfigurationRecord：major, minor, profile, level, rpu, el, bl, compatibility
+        let record = [1, 0, 5, 6, 1, 0, 1, 0];
+        assert_eq!(DoviConfig::parse_side_data(&record), DoviConfig::parse_box(&PROFILE_5_BOX));
+        assert_eq!(DoviConfig::parse_side_data(&record[..4]), None);
+
+        // 没有配置记录时按编码标签推断
+        let compat = classify(None, tag(b"dvhe"), false);
+        assert_eq!(compat, HdrCompatibility::DolbyVisionUnsupported { profile: None });
+        assert!(!compat.is_supported());
+    }
+}
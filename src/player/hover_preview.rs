@@ -0,0 +1,298 @@
+// 进度条悬停预览
+//
+// 鼠标悬停在进度条上时，在提示框中显示该位置的画面。预览由独立的后台线程生成：线程自己打开一个解封装器和
+// 软件预览解码器（经预览管线缩小到 160px 宽，见 preview），不影响播放中的解码。请求通过通道发送，线程每次
+// 只处理最新的请求。鼠标移动时不发送请求：悬停位置（按 HOVER_STEP_MS 取整）保持 DEBOUNCE 不变后才请求。
+// 生成的画面按取整后的时间保存在 LRU 缓存中，回到看过的位置时直接显示。
+
+use crate::core::{PlayerError, VideoFrame};
+use crate::player::preview::{PreviewPipeline, PreviewPurpose, PreviewRequest};
+use crate::player::preview_cache::PreviewCache;
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+use log::{debug, info, warn};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 悬停位置的取整步长（毫秒）
+pub const HOVER_STEP_MS: i64 = 2_000;
+
+/// 悬停位置保持不变多久后才请求预览
+pub const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// LRU 缓存保留的预览数
+const CACHE_CAPACITY: usize = 64;
+
+/// 后台线程检查取消标志的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 悬停时间取整到最近的步长（缓存键）
+pub fn hover_key(time_ms: i64) -> i64 {
+    (time_ms.max(0) + HOVER_STEP_MS / 2) / HOVER_STEP_MS * HOVER_STEP_MS
+}
+
+/// 悬停位置的预览状态
+#[derive(Debug, Clone)]
+pub enum HoverPreviewState {
+    /// 已生成的画面
+    Ready(VideoFrame),
+    /// 等待鼠标停下或正在生成
+    Loading,
+    /// 该位置（或整个文件）没有可用的画面
+    Unavailable,
+}
+
+/// 后台线程的生成结果（取整后的时间，None 表示该位置没有解码出画面）
+type PreviewResult = (i64, Option<VideoFrame>);
+
+/// 悬停预览服务（drop 时取消并等待线程退出）
+pub struct HoverPreview {
+    source: PathBuf,
+    requests: Option<Sender<i64>>,
+    results: Receiver<PreviewResult>,
+    cache: VecDeque<PreviewResult>,  // 最近使用的在队尾
+    requested: Option<i64>,  // 已发送、尚未返回的请求
+    hover: Option<(i64, Instant)>,  // 当前悬停位置及开始停留的时间
+    cancel: Arc<AtomicBool>,
+    thread_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl HoverPreview {
+    /// 为本地文件启动预览线程
+    pub fn spawn(source: PathBuf, cache: Arc<PreviewCache>) -> Self {
+        let (request_tx, request_rx) = unbounded();
+        let (result_tx, result_rx) = unbounded();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let thread_source = source.clone();
+        let thread_cancel = cancel.clone();
+        let thread_handle = thread::Builder::new()
+            .name("hover-preview".to_string())
+            .spawn(move || {
+                let pipeline = PreviewPipeline::new(cache);
+                serve(pipeline, &thread_source, &request_rx, &result_tx, &thread_cancel);
+                debug!("🖼️ 悬停预览线程退出 ({})", thread_source.display());
+            })
+            .ok();
+        if thread_handle.is_none() {
+            warn!("⚠️  无法启动悬停预览线程");
+        }
+
+        Self {
+            source,
+            requests: Some(request_tx),
+            results: result_rx,
+            cache: VecDeque::new(),
+            requested: None,
+            hover: None,
+            cancel,
+            thread_handle,
+        }
+    }
+
+    /// 预览对应的文件
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// 悬停在 time_ms 处（每帧调用）：返回该位置的预览，鼠标停留足够久后才请求生成
+    pub fn hover(&mut self, time_ms: i64, now: Instant) -> HoverPreviewState {
+        self.collect_results();
+        let key = hover_key(time_ms);
+        if let Some(index) = self.cache.iter().position(|(cached, _)| *cached == key) {
+            // 移到队尾（最近使用）
+            let entry = self.cache.remove(index).expect("index from position");
+            let state = match &entry.1 {
+                Some(frame) => HoverPreviewState::Ready(frame.clone()),
+                None => HoverPreviewState::Unavailable,
+            };
+            self.cache.push_back(entry);
+            return state;
+        }
+        if self.thread_handle.as_ref().is_none_or(|handle| handle.is_finished()) {
+            return HoverPreviewState::Unavailable;
+        }
+
+        let since = match self.hover {
+            Some((hovered, since)) if hovered == key => since,
+            _ => {
+                self.hover = Some((key, now));
+                now
+            }
+        };
+        if now.duration_since(since) >= DEBOUNCE && self.requested != Some(key) {
+            if let Some(requests) = &self.requests {
+                if requests.send(key).is_ok() {
+                    self.requested = Some(key);
+                }
+            }
+        }
+        HoverPreviewState::Loading
+    }
+
+    /// 鼠标离开进度条（下次悬停重新计时）
+    pub fn leave(&mut self) {
+        self.hover = None;
+    }
+
+    /// 取出后台线程生成的预览放入缓存
+    fn collect_results(&mut self) {
+        for (key, frame) in self.results.try_iter() {
+            if self.requested == Some(key) {
+                self.requested = None;
+            }
+            self.cache.retain(|(cached, _)| *cached != key);
+            self.cache.push_back((key, frame));
+            while self.cache.len() > CACHE_CAPACITY {
+                self.cache.pop_front();
+            }
+        }
+    }
+}
+
+impl Drop for HoverPreview {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.requests = None;
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 后台线程：等待请求，只处理最新的一个（鼠标快速移动时跳过中间的位置）
+fn serve(
+    mut pipeline: PreviewPipeline,
+    source: &Path,
+    requests: &Receiver<i64>,
+    results: &Sender<PreviewResult>,
+    cancel: &AtomicBool,
+) {
+    while !cancel.load(Ordering::Relaxed) {
+        let key = match requests.recv_timeout(POLL_INTERVAL) {
+            Ok(key) => requests.try_iter().last().unwrap_or(key),
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        let started = Instant::now();
+        let request = PreviewRequest::new(source, key, PreviewPurpose::Hover);
+        let frame = match pipeline.render(&request, cancel) {
+            Ok(frame) => frame,
+            Err(e @ (PlayerError::NoVideoStream | PlayerError::OpenError(_) | PlayerError::OpenFailed { .. })) => {
+                info!("🖼️ 文件没有可预览的画面，停止悬停预览: {}", e);
+                return;
+            }
+            Err(e) => {
+                debug!("🖼️ {}ms 处的悬停预览生成失败: {}", key, e);
+                None
+            }
+        };
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        debug!("🖼️ 悬停预览 {}ms 生成耗时 {}ms", key, started.elapsed().as_millis());
+        if results.send((key, frame)).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::PixelFormat;
+
+    fn frame(pts: i64) -> VideoFrame {
+        VideoFrame { pts, duration: 0, width: 2, height: 1, format: PixelFormat::RGBA, planes: None, data: vec![0; 8].into() }
+    }
+
+    /// 用空闲线程代替后台线程的服务（由 results 通道模拟生成结果）
+    fn service() -> (HoverPreview, Receiver<i64>, Sender<PreviewResult>) {
+        let (request_tx, request_rx) = unbounded();
+        let (result_tx, result_rx) = unbounded();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let idle_cancel = cancel.clone();
+        let idle = thread::spawn(move || {
+            while !idle_cancel.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+        let preview = HoverPreview {
+            source: PathBuf::from("/videos/a.mkv"),
+            requests: Some(request_tx),
+            results: result_rx,
+            cache: VecDeque::new(),
+            requested: None,
+            hover: None,
+            cancel,
+            thread_handle: Some(idle),
+        };
+        (preview, request_rx, result_tx)
+    }
+
+    #[test]
+    fn test_hover_key_rounds_to_step() {
+        assert_eq!(hover_key(0), 0);
+        assert_eq!(hover_key(999), 0);
+        assert_eq!(hover_key(1_000), 2_000);
+        assert_eq!(hover_key(61_234), 62_000);
+        assert_eq!(hover_key(-500), 0);
+    }
+
+    #[test]
+    fn test_requests_are_debounced_while_moving() {
+        let (mut preview, requests, results) = service();
+        let start = Instant::now();
+        // 鼠标在进度条上移动：每帧都换位置，不发送请求
+        for i in 0..30 {
+            let now = start + Duration::from_millis(16 * i);
+            assert!(matches!(preview.hover(i as i64 * 4_000, now), HoverPreviewState::Loading));
+        }
+        assert!(requests.try_recv().is_err());
+
+        // 停下后超过 DEBOUNCE 才请求一次
+        let stop = start + Duration::from_secs(1);
+        preview.hover(60_500, stop);
+        preview.hover(60_700, stop + DEBOUNCE / 2);
+        assert!(requests.try_recv().is_err());
+        preview.hover(60_700, stop + DEBOUNCE);
+        preview.hover(60_900, stop + DEBOUNCE * 2);
+        assert_eq!(requests.try_iter().collect::<Vec<_>>(), vec![60_000]);
+
+        // 生成完成后直接显示缓存
+        results.send((60_000, Some(frame(60_000)))).unwrap();
+        assert!(matches!(preview.hover(59_500, stop + DEBOUNCE * 3), HoverPreviewState::Ready(frame) if frame.pts == 60_000));
+        results.send((8_000, None)).unwrap();
+        assert!(matches!(preview.hover(8_000, stop), HoverPreviewState::Unavailable));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let (mut preview, _requests, results) = service();
+        let now = Instant::now();
+        for i in 0..CACHE_CAPACITY as i64 {
+            results.send((i * HOVER_STEP_MS, Some(frame(i)))).unwrap();
+        }
+        // 访问最早的条目，使其成为最近使用
+        assert!(matches!(preview.hover(0, now), HoverPreviewState::Ready(_)));
+        let newest = CACHE_CAPACITY as i64 * HOVER_STEP_MS;
+        results.send((newest, Some(frame(newest)))).unwrap();
+        preview.hover(0, now);
+        assert_eq!(preview.cache.len(), CACHE_CAPACITY);
+        assert!(preview.cache.iter().any(|(key, _)| *key == 0));
+        assert!(!preview.cache.iter().any(|(key, _)| *key == HOVER_STEP_MS));
+    }
+
+    #[test]
+    fn test_finished_worker_reports_unavailable() {
+        let (mut preview, _requests, _results) = service();
+        preview.cancel.store(true, Ordering::Relaxed);
+        if let Some(handle) = preview.thread_handle.take() {
+            handle.join().unwrap();
+        }
+        assert!(matches!(preview.hover(10_000, Instant::now()), HoverPreviewState::Unavailable));
+    }
+}
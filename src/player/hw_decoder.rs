@@ -1,9 +1,15 @@
-use crate::core::{PixelFormat, VideoFrame, PlayerError, Result};
+use crate::core::{FramePool, VideoFrame, PlayerError, Result};
+use crate::core::ffi_util::{attach_hw_device, configure_decoder_options, decoder_reorder_depth, transfer_hw_frame, DecoderOpts, HwDevice};
+use crate::player::decoder::{collect_converted, convert_for_display, finish_decode};
 use ffmpeg_next as ffmpeg;
+use ffmpeg_next::ffi::{AVHWDeviceType, AVPixelFormat};
 use ffmpeg_next::{codec, format, software, util};
 use log::{debug, info, warn};
+use std::sync::Arc;
 
 /// 硬件解码器类型
+#[allow(dead_code)] // 每个平台只构造自己的类型
+#[allow(clippy::upper_case_acronyms)] // 与 FFmpeg 的设备类型名称一致
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HWAccelType {
     None,           // CPU 软解
@@ -98,31 +104,42 @@ impl HWAccelType {
             return true;
         }
 
-        // 尝试获取对应的 FFmpeg 硬件类型
+        // FFmpeg 编译了该硬件加速且驱动能打开设备才算支持
         match hw_type.to_ffmpeg_type() {
-            Some(ffmpeg_type) => {
-                // 检查 FFmpeg 是否编译了该硬件加速支持
-                // 这里简化处理，实际应该检查 av_hwdevice_ctx_create 是否成功
-                debug!("检查硬件类型: {:?}", ffmpeg_type);
-                true // 简化版本，假设编译支持
-            }
+            Some(ffmpeg_type) => match HwDevice::create(ffmpeg_type) {
+                Ok(_) => true,
+                Err(e) => {
+                    debug!("{} 不可用: {}", hw_type.name(), e);
+                    false
+                }
+            },
             None => false,
         }
     }
 
     /// 转换为 FFmpeg 硬件设备类型
-    pub fn to_ffmpeg_type(&self) -> Option<i32> {
-        // 注意：ffmpeg-next 6.1 可能没有 codec::hardware 模块
-        // 这里简化处理，返回硬件类型的整数表示
-        // 实际应该使用 AVHWDeviceType 枚举值
+    pub fn to_ffmpeg_type(self) -> Option<AVHWDeviceType> {
         match self {
             HWAccelType::None => None,
-            HWAccelType::DXVA2 => Some(3),       // AV_HWDEVICE_TYPE_DXVA2
-            HWAccelType::D3D11VA => Some(4),     // AV_HWDEVICE_TYPE_D3D11VA
-            HWAccelType::VAAPI => Some(2),       // AV_HWDEVICE_TYPE_VAAPI
-            HWAccelType::VideoToolbox => Some(6), // AV_HWDEVICE_TYPE_VIDEOTOOLBOX
-            HWAccelType::CUDA => Some(1),        // AV_HWDEVICE_TYPE_CUDA
-            HWAccelType::QSV => Some(5),         // AV_HWDEVICE_TYPE_QSV
+            HWAccelType::DXVA2 => Some(AVHWDeviceType::AV_HWDEVICE_TYPE_DXVA2),
+            HWAccelType::D3D11VA => Some(AVHWDeviceType::AV_HWDEVICE_TYPE_D3D11VA),
+            HWAccelType::VAAPI => Some(AVHWDeviceType::AV_HWDEVICE_TYPE_VAAPI),
+            HWAccelType::VideoToolbox => Some(AVHWDeviceType::AV_HWDEVICE_TYPE_VIDEOTOOLBOX),
+            HWAccelType::CUDA => Some(AVHWDeviceType::AV_HWDEVICE_TYPE_CUDA),
+            HWAccelType::QSV => Some(AVHWDeviceType::AV_HWDEVICE_TYPE_QSV),
+        }
+    }
+
+    /// 解码器输出的硬件帧像素格式（帧数据在显存中，需要下载后才能使用）
+    pub fn hw_pixel_format(&self) -> Option<AVPixelFormat> {
+        match self {
+            HWAccelType::None => None,
+            HWAccelType::DXVA2 => Some(AVPixelFormat::AV_PIX_FMT_DXVA2_VLD),
+            HWAccelType::D3D11VA => Some(AVPixelFormat::AV_PIX_FMT_D3D11),
+            HWAccelType::VAAPI => Some(AVPixelFormat::AV_PIX_FMT_VAAPI),
+            HWAccelType::VideoToolbox => Some(AVPixelFormat::AV_PIX_FMT_VIDEOTOOLBOX),
+            HWAccelType::CUDA => Some(AVPixelFormat::AV_PIX_FMT_CUDA),
+            HWAccelType::QSV => Some(AVPixelFormat::AV_PIX_FMT_QSV),
         }
     }
 }
@@ -131,14 +148,16 @@ impl HWAccelType {
 pub struct HWVideoDecoder {
     decoder: codec::decoder::Video,
     hw_type: HWAccelType,
+    hw_format: Option<format::Pixel>,  // 硬件帧的像素格式（get_format 未选中硬件格式时解码器输出普通帧）
     scaler: Option<software::scaling::Context>,
     time_base: f64,
     width: u32,
     height: u32,
+    pool: Option<Arc<FramePool>>,  // 帧缓冲区池（下载到内存的帧转换后的像素缓冲区）
 }
 
 // SwsContext 本身不是 Send，但我们确保只在单个线程中使用它
-// 这是安全的，因为每个解码器实例只会在一个线程中使用
+// SAFETY: 每个解码器实例只会在一个线程中使用，移动到解码线程后不再被其他线程访问
 unsafe impl Send for HWVideoDecoder {}
 
 impl HWVideoDecoder {
@@ -171,55 +190,29 @@ impl HWVideoDecoder {
         stream: format::stream::Stream,
         hw_type: HWAccelType,
     ) -> Result<Self> {
-        let context = codec::context::Context::from_parameters(stream.parameters())?;
+        let mut context = codec::context::Context::from_parameters(stream.parameters())?;
+
+        // 硬件设备上下文必须在打开解码器之前挂上
+        let hw_format = hw_type.hw_pixel_format();
+        if let (Some(ffmpeg_type), Some(hw_format)) = (hw_type.to_ffmpeg_type(), hw_format) {
+            let device = HwDevice::create(ffmpeg_type)
+                .map_err(|e| PlayerError::DecodeError(format!("创建硬件设备上下文失败: {}", e)))?;
+            attach_hw_device(&mut context, &device, hw_format)?;
+            debug!("硬件设备上下文创建成功: {:?}", ffmpeg_type);
+        }
+
         let mut decoder = context.decoder().video()?;
         
         // 🔧 关键优化：设置解码器选项以提高网络流兼容性
         // 这些选项对于处理不完整的 GOP 和缺失参考帧至关重要
-        unsafe {
-            use ffmpeg_next::ffi;
-            let codec_ctx = decoder.as_mut_ptr();
-            
-            // 1. 启用低延迟模式（跳过循环滤波器以加速）
-            (*codec_ctx).flags |= ffi::AV_CODEC_FLAG_LOW_DELAY as i32;
-            
-            // 2. 启用错误隐藏（当参考帧丢失时尝试恢复）
-            (*codec_ctx).error_concealment = ffi::FF_EC_GUESS_MVS | ffi::FF_EC_DEBLOCK;
-            
-            // 3. 跳过循环滤波器（减少延迟，提高速度）
-            (*codec_ctx).skip_loop_filter = ffi::AVDiscard::AVDISCARD_ALL;
-            
-            // 4. 设置线程数（提高解码速度）
-            (*codec_ctx).thread_count = 4;
-            (*codec_ctx).thread_type = ffi::FF_THREAD_FRAME | ffi::FF_THREAD_SLICE;
-            
-            debug!("✓ 已设置低延迟和容错选项");
-        }
+        configure_decoder_options(&mut decoder, DecoderOpts::NETWORK_TOLERANT);
+        debug!("✓ 已设置低延迟和容错选项");
         
         let decoder = decoder;
 
         let width = decoder.width();
         let height = decoder.height();
 
-        // 如果是硬件加速，尝试设置硬件上下文
-        if hw_type != HWAccelType::None {
-            if let Some(ffmpeg_type) = hw_type.to_ffmpeg_type() {
-                // 尝试创建硬件设备上下文
-                match Self::create_hw_device_context(ffmpeg_type) {
-                    Ok(_) => {
-                        debug!("硬件设备上下文创建成功");
-                        // 注意：实际的硬件上下文设置需要更复杂的 FFmpeg API 调用
-                        // 这里简化处理，假设解码器会自动使用硬件加速
-                    }
-                    Err(e) => {
-                        return Err(PlayerError::DecodeError(
-                            format!("创建硬件设备上下文失败: {}", e)
-                        ));
-                    }
-                }
-            }
-        }
-
         let time_base = stream.time_base();
         let time_base = time_base.numerator() as f64 / time_base.denominator() as f64;
 
@@ -234,24 +227,22 @@ impl HWVideoDecoder {
         Ok(Self {
             decoder,
             hw_type,
+            hw_format: hw_format.map(format::Pixel::from),
             scaler: None,
             time_base,
             width,
             height,
+            pool: None,
         })
     }
 
-    /// 创建硬件设备上下文
-    fn create_hw_device_context(hw_type: i32) -> Result<()> {
-        // 这里需要调用 FFmpeg 的 av_hwdevice_ctx_create
-        // 由于 ffmpeg-next 的 API 限制，这里简化处理
-        debug!("尝试创建硬件设备上下文: {}", hw_type);
-        Ok(())
-    }
-
     /// 解码数据包
+    ///
+    /// 数据包没有解码出帧且出现了解码 / 传输错误时返回该错误，由解码线程计数（连续出错时改用软件解码）。
     pub fn decode(&mut self, packet: &ffmpeg::Packet) -> Result<Vec<VideoFrame>> {
         let mut frames = Vec::new();
+        let mut rejected = None;
+        let mut failed = None;
 
         match self.decoder.send_packet(packet) {
             Ok(()) => {}
@@ -274,6 +265,7 @@ impl HWVideoDecoder {
                             Ok(frame) => frame,
                             Err(e) => {
                                 warn!("硬件帧传输失败: {}, 跳过该帧", e);
+                                failed = Some(e);
                                 continue;
                             }
                         }
@@ -281,9 +273,7 @@ impl HWVideoDecoder {
                         decoded_frame
                     };
 
-                    if let Some(frame) = self.convert_frame(cpu_frame)? {
-                        frames.push(frame);
-                    }
+                    collect_converted(self.convert_frame(cpu_frame), &mut frames, &mut rejected)?;
                 }
                 Err(ffmpeg::Error::Other { errno: 11 }) => break, // EAGAIN
                 Err(ffmpeg::Error::Eof) => break,
@@ -291,17 +281,19 @@ impl HWVideoDecoder {
                     // 对于网络流，某些解码错误是可以容忍的（如参考帧丢失）
                     // 记录警告但继续处理，而不是直接返回错误
                     warn!("解码错误（已跳过）: {}", e);
+                    failed = Some(e.into());
                     break;
                 }
             }
         }
 
-        Ok(frames)
+        finish_decode(frames, failed.or(rejected))
     }
 
     /// 刷新解码器缓冲区
     pub fn flush(&mut self) -> Result<Vec<VideoFrame>> {
         let mut frames = Vec::new();
+        let mut rejected = None;
 
         self.decoder.send_eof()?;
 
@@ -315,9 +307,7 @@ impl HWVideoDecoder {
                         decoded_frame
                     };
 
-                    if let Some(frame) = self.convert_frame(cpu_frame)? {
-                        frames.push(frame);
-                    }
+                    collect_converted(self.convert_frame(cpu_frame), &mut frames, &mut rejected)?;
                 }
                 Err(_) => break,
             }
@@ -328,85 +318,29 @@ impl HWVideoDecoder {
         Ok(frames)
     }
 
-    /// 检查是否是硬件帧
-    fn is_hw_frame(&self, _frame: &util::frame::Video) -> bool {
-        // 硬件帧的像素格式通常是特殊的硬件格式
-        // 例如：NV12 (D3D11), VIDEOTOOLBOX, VAAPI 等
-        // 这里简化判断：如果使用了硬件加速，假设是硬件帧
-        self.hw_type != HWAccelType::None
+    /// 检查是否是硬件帧（像素格式为硬件格式，数据在显存中）
+    fn is_hw_frame(&self, frame: &util::frame::Video) -> bool {
+        self.hw_format.is_some_and(|hw_format| frame.format() == hw_format)
     }
 
-    /// 将硬件帧传输到 CPU 内存
+    /// 将硬件帧传输到 CPU 内存（av_hwframe_transfer_data，通常得到 NV12）
     fn transfer_to_cpu(&self, hw_frame: &util::frame::Video) -> Result<util::frame::Video> {
-        // 在实际实现中，需要调用 av_hwframe_transfer_data
-        // 这里简化处理：直接返回原帧（如果 FFmpeg 自动处理了传输）
-        // 或者创建一个新的 CPU 帧并复制数据
-        
-        debug!("执行硬件帧到 CPU 传输");
-        
-        // 简化版本：假设 FFmpeg 已经处理了硬件帧的传输
-        // 实际项目中需要更复杂的处理
-        Ok(hw_frame.clone())
+        transfer_hw_frame(hw_frame)
     }
 
-    /// 转换帧格式为 RGBA
+    /// 转换帧格式（显示用 YUV 或 RGBA）
     fn convert_frame(&mut self, frame: util::frame::Video) -> Result<Option<VideoFrame>> {
-        let width = frame.width();
-        let height = frame.height();
-
-        // 初始化 scaler（YUV -> RGBA）
-        if self.scaler.is_none() {
-            self.scaler = Some(
-                software::scaling::Context::get(
-                    frame.format(),
-                    width,
-                    height,
-                    util::format::Pixel::RGBA,
-                    width,
-                    height,
-                    software::scaling::Flags::BILINEAR,
-                )?,
-            );
-        }
-
-        let mut rgba_frame = util::frame::Video::empty();
-        self.scaler.as_mut().unwrap().run(&frame, &mut rgba_frame)?;
-
-        // 计算 PTS（毫秒）
-        let pts = if let Some(timestamp) = frame.timestamp() {
-            (timestamp as f64 * self.time_base * 1000.0) as i64
-        } else {
-            0
-        };
-
-        // 复制数据到连续内存
-        let data_size = (width * height * 4) as usize;
-        let mut data = vec![0u8; data_size];
-
-        let stride = rgba_frame.stride(0);
-        let frame_data = rgba_frame.data(0);
-
-        for y in 0..height as usize {
-            let src_offset = y * stride;
-            let dst_offset = y * (width as usize * 4);
-            let row_size = width as usize * 4;
-            data[dst_offset..dst_offset + row_size]
-                .copy_from_slice(&frame_data[src_offset..src_offset + row_size]);
-        }
+        convert_for_display(&mut self.scaler, &frame, self.time_base, self.pool.as_ref()).map(Some)
+    }
 
-        Ok(Some(VideoFrame {
-            pts,
-            duration: 0,
-            width,
-            height,
-            format: PixelFormat::RGBA,
-            data,
-        }))
+    /// 解码帧的像素缓冲区从池中取
+    pub fn set_frame_pool(&mut self, pool: Arc<FramePool>) {
+        self.pool = Some(pool);
     }
 
-    /// 获取当前使用的硬件加速类型
-    pub fn hw_type(&self) -> HWAccelType {
-        self.hw_type
+    /// 帧重排序深度
+    pub fn reorder_depth(&self) -> usize {
+        decoder_reorder_depth(&self.decoder)
     }
 
     /// 获取解码器信息
@@ -0,0 +1,403 @@
+// 关键帧索引（本地文件打开后在后台扫描数据包，不解码）
+//
+// 记录视频关键帧的 PTS 和字节偏移，供 Seek 按字节定位、关键帧导航（Shift+方向键）和缩略图查找使用。
+// 索引按 路径 + 文件大小 + 修改时间 缓存到磁盘，文件未变化时再次打开直接加载。
+// 索引完成前 Seek 走原有的时间戳查找，播放行为不受影响。
+
+use crate::core::{PlayerError, Result};
+use crate::player::position_history::write_atomic;
+use ffmpeg_next::{format, media};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// 索引条目上限（超过后隔一取一，保证内存和缓存文件大小有界）
+pub const MAX_ENTRIES: usize = 100_000;
+
+/// 扫描时每读取多少个包让出一次 CPU（低优先级，不与播放线程争抢）
+const YIELD_EVERY_PACKETS: usize = 512;
+
+/// 缓存格式版本（结构变化时递增，旧缓存自动失效）
+const CACHE_VERSION: u32 = 1;
+
+/// 后台扫描完成后写入一次的索引（解封装器与管理器共享）
+pub type SharedKeyframeIndex = Arc<OnceLock<KeyframeIndex>>;
+
+/// 关键帧
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub pts_ms: i64,  // 显示时间（毫秒）
+    pub pos: i64,     // 数据包在文件中的字节偏移（-1 表示未知）
+}
+
+/// 关键帧索引（按 PTS 升序）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyframeIndex {
+    entries: Vec<Keyframe>,
+    stride: usize,  // 采样间隔（1 表示包含全部关键帧）
+    #[serde(skip)]
+    skip: usize,    // 扫描中距下一个采样点还需跳过的关键帧数
+}
+
+impl KeyframeIndex {
+    pub fn new() -> Self {
+        Self { entries: Vec::new(), stride: 1, skip: 0 }
+    }
+
+    /// 按扫描顺序追加关键帧（超过上限时减半采样）
+    pub fn push(&mut self, keyframe: Keyframe) {
+        if self.skip > 0 {
+            self.skip -= 1;
+            return;
+        }
+        self.entries.push(keyframe);
+        self.stride = self.stride.max(1);
+        if self.entries.len() > MAX_ENTRIES {
+            // 保留偶数位置（包括刚加入的最后一条），采样间隔加倍
+            let mut position = 0;
+            self.entries.retain(|_| {
+                position += 1;
+                position % 2 == 1
+            });
+            self.stride *= 2;
+        }
+        self.skip = self.stride - 1;
+    }
+
+    /// 扫描完成后按 PTS 排序（个别封装的关键帧包不是严格按 PTS 顺序存放的）
+    fn finish(&mut self) {
+        self.entries.sort_by_key(|keyframe| keyframe.pts_ms);
+        self.entries.dedup_by_key(|keyframe| keyframe.pts_ms);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否包含全部关键帧（未被采样稀释，可直接用于 Seek）
+    pub fn is_complete(&self) -> bool {
+        self.stride <= 1
+    }
+
+    /// 不晚于 pts_ms 的最后一个关键帧（早于第一个关键帧时返回第一个）
+    pub fn nearest(&self, pts_ms: i64) -> Option<Keyframe> {
+        let after = self.entries.partition_point(|keyframe| keyframe.pts_ms <= pts_ms);
+        self.entries.get(after.saturating_sub(1)).copied()
+    }
+
+    /// 晚于 pts_ms 的第一个关键帧
+    pub fn next_after(&self, pts_ms: i64) -> Option<Keyframe> {
+        let after = self.entries.partition_point(|keyframe| keyframe.pts_ms <= pts_ms);
+        self.entries.get(after).copied()
+    }
+
+    /// 早于 pts_ms 的最后一个关键帧
+    pub fn previous_before(&self, pts_ms: i64) -> Option<Keyframe> {
+        let before = self.entries.partition_point(|keyframe| keyframe.pts_ms < pts_ms);
+        before.checked_sub(1).and_then(|last| self.entries.get(last)).copied()
+    }
+
+    /// 扫描文件中的视频关键帧（只读包，不解码；cancel 置位时中止）
+    pub fn scan(path: &Path, cancel: &AtomicBool) -> Result<Self> {
+        let mut input = format::input(&path).map_err(|source| PlayerError::OpenFailed { target: "文件", source })?;
+        let stream = input
+            .streams()
+            .best(media::Type::Video)
+            .filter(|s| !s.disposition().contains(format::stream::Disposition::ATTACHED_PIC))
+            .ok_or(PlayerError::NoVideoStream)?;
+        let stream_index = stream.index();
+        let time_base = stream.time_base();
+        let time_base = time_base.numerator() as f64 / time_base.denominator() as f64;
+
+        let mut index = Self::new();
+        for (packet_count, (stream, packet)) in input.packets().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(PlayerError::Other("关键帧索引已取消".to_string()));
+            }
+            if packet_count % YIELD_EVERY_PACKETS == YIELD_EVERY_PACKETS - 1 {
+                thread::sleep(Duration::from_millis(1));
+            }
+            if stream.index() != stream_index || !packet.is_key() {
+                continue;
+            }
+            if let Some(pts) = packet.pts().or(packet.dts()) {
+                index.push(Keyframe {
+                    pts_ms: (pts as f64 * time_base * 1000.0) as i64,
+                    pos: packet.position() as i64,
+                });
+            }
+        }
+        index.finish();
+        Ok(index)
+    }
+}
+
+/// 缓存文件内容（键不匹配时视为失效）
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    path: String,
+    size: u64,
+    modified_ms: u64,
+    index: KeyframeIndex,
+}
+
+/// 缓存键：路径 + 文件大小 + 修改时间
+fn cache_key(path: &Path) -> Result<(String, u64, u64)> {
+    let metadata = fs::metadata(path)?;
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    Ok((path.to_string_lossy().to_string(), metadata.len(), modified_ms))
+}
+
+/// 缓存文件路径（按缓存键的 FNV-1a 哈希命名）
+fn cache_file(dir: &Path, key: &(String, u64, u64)) -> PathBuf {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let bytes = key.0.bytes().chain(key.1.to_le_bytes()).chain(key.2.to_le_bytes());
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    dir.join(format!("{:016x}.json", hash))
+}
+
+/// 读取缓存的索引（文件已变化或缓存损坏时返回 None）
+pub fn load_cached(dir: &Path, path: &Path) -> Option<KeyframeIndex> {
+    let key = cache_key(path).ok()?;
+    let json = fs::read_to_string(cache_file(dir, &key)).ok()?;
+    let cache: CacheFile = serde_json::from_str(&json).ok()?;
+    (cache.version == CACHE_VERSION && (cache.path.as_str(), cache.size, cache.modified_ms) == (key.0.as_str(), key.1, key.2))
+        .then_some(cache.index)
+}
+
+/// 写入索引缓存
+pub fn save_cached(dir: &Path, path: &Path, index: &KeyframeIndex) -> Result<()> {
+    let (path, size, modified_ms) = cache_key(path)?;
+    let file = cache_file(dir, &(path.clone(), size, modified_ms));
+    let cache = CacheFile { version: CACHE_VERSION, path, size, modified_ms, index: index.clone() };
+    let json = serde_json::to_vec(&cache).map_err(|e| PlayerError::ConfigError(format!("序列化关键帧索引失败: {}", e)))?;
+    write_atomic(&file, &json)
+}
+
+/// 后台索引任务（drop 时取消并等待线程退出）
+pub struct KeyframeIndexer {
+    index: SharedKeyframeIndex,
+    cancel: Arc<AtomicBool>,
+    thread_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl KeyframeIndexer {
+    /// 启动后台扫描（cache_dir 为 None 时不读写缓存）
+    pub fn spawn(path: PathBuf, cache_dir: Option<PathBuf>) -> Self {
+        let index = Arc::new(OnceLock::new());
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let thread_index = index.clone();
+        let thread_cancel = cancel.clone();
+        let thread_handle = thread::Builder::new()
+            .name("keyframe-index".to_string())
+            .spawn(move || {
+                if let Some(cached) = cache_dir.as_deref().and_then(|dir| load_cached(dir, &path)) {
+                    debug!("🔑 加载关键帧索引缓存: {} 条 ({})", cached.len(), path.display());
+                    let _ = thread_index.set(cached);
+                    return;
+                }
+
+                let started = Instant::now();
+                match KeyframeIndex::scan(&path, &thread_cancel) {
+                    Ok(index) => {
+                        info!(
+                            "🔑 关键帧索引完成: {} 条，耗时 {}ms ({})",
+                            index.len(),
+                            started.elapsed().as_millis(),
+                            path.display()
+                        );
+                        if let Some(dir) = &cache_dir {
+                            if let Err(e) = save_cached(dir, &path, &index) {
+                                warn!("⚠️  关键帧索引缓存写入失败: {}", e);
+                            }
+                        }
+                        let _ = thread_index.set(index);
+                    }
+                    Err(e) => debug!("🔑 关键帧索引未完成: {}", e),
+                }
+            })
+            .ok();
+        if thread_handle.is_none() {
+            warn!("⚠️  无法启动关键帧索引线程，Seek 使用时间戳查找");
+        }
+
+        Self { index, cancel, thread_handle }
+    }
+
+    /// 已完成的索引（扫描中返回 None）
+    pub fn get(&self) -> Option<&KeyframeIndex> {
+        self.index.get()
+    }
+
+    /// 共享的索引（扫描完成后可见，任务本身被 drop 后仍可读取）
+    pub fn shared(&self) -> SharedKeyframeIndex {
+        self.index.clone()
+    }
+
+    /// 等待扫描结束（测试使用）
+    #[cfg(test)]
+    pub fn wait(&mut self) -> Option<&KeyframeIndex> {
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+        self.index.get()
+    }
+}
+
+impl Drop for KeyframeIndexer {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 关键帧索引缓存目录
+pub fn default_cache_dir() -> PathBuf {
+    crate::core::user_data_dir().join("keyframes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Demuxer;
+    use crate::test_support::video_asset;
+
+    fn index_of(pts: &[i64]) -> KeyframeIndex {
+        let mut index = KeyframeIndex::new();
+        for (i, &pts_ms) in pts.iter().enumerate() {
+            index.push(Keyframe { pts_ms, pos: i as i64 * 100 });
+        }
+        index.finish();
+        index
+    }
+
+    #[test]
+    fn test_nearest_next_and_previous() {
+        let index = index_of(&[0, 1_000, 2_000, 3_000]);
+        assert_eq!(index.nearest(-5).map(|k| k.pts_ms), Some(0));
+        assert_eq!(index.nearest(0).map(|k| k.pts_ms), Some(0));
+        assert_eq!(index.nearest(1_999).map(|k| k.pts_ms), Some(1_000));
+        assert_eq!(index.nearest(2_000).map(|k| k.pts_ms), Some(2_000));
+        assert_eq!(index.nearest(99_000).map(|k| k.pts_ms), Some(3_000));
+        assert_eq!(KeyframeIndex::new().nearest(0), None);
+
+        assert_eq!(index.next_after(1_000).map(|k| k.pts_ms), Some(2_000));
+        assert_eq!(index.next_after(-5).map(|k| k.pts_ms), Some(0));
+        assert_eq!(index.next_after(3_000), None);
+        assert_eq!(index.previous_before(2_000).map(|k| k.pts_ms), Some(1_000));
+        assert_eq!(index.previous_before(2_001).map(|k| k.pts_ms), Some(2_000));
+        assert_eq!(index.previous_before(0), None);
+    }
+
+    #[test]
+    fn test_entries_are_capped() {
+        let mut index = KeyframeIndex::new();
+        for i in 0..(MAX_ENTRIES as i64 * 3) {
+            index.push(Keyframe { pts_ms: i * 40, pos: i });
+        }
+        index.finish();
+        assert!(index.len() <= MAX_ENTRIES);
+        assert!(index.len() > MAX_ENTRIES / 2);
+        assert!(!index.is_complete());
+        assert_eq!(index.nearest(0).map(|k| k.pts_ms), Some(0));
+        // 采样后条目仍均匀覆盖整个时间轴
+        let last = index.nearest(i64::MAX).unwrap().pts_ms;
+        assert!(last > (MAX_ENTRIES as i64 * 3 - 8) * 40, "最后一条 {}ms", last);
+    }
+
+    #[test]
+    fn test_cache_is_keyed_by_size_and_mtime() {
+        let dir = std::env::temp_dir().join(format!("myy_player_keyframes_{}", std::process::id()));
+        let media = dir.join("media.bin");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&media, b"first").unwrap();
+
+        let index = index_of(&[0, 2_000]);
+        save_cached(&dir, &media, &index).unwrap();
+        assert_eq!(load_cached(&dir, &media).map(|cached| cached.entries), Some(index.entries.clone()));
+
+        // 文件内容变化（大小不同）后缓存失效
+        fs::write(&media, b"changed content").unwrap();
+        assert!(load_cached(&dir, &media).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_cancelled_scan_stops() {
+        let cancel = AtomicBool::new(true);
+        assert!(KeyframeIndex::scan(video_asset(), &cancel).is_err());
+    }
+
+    #[test]
+    fn test_index_matches_seek_results() {
+        let cancel = AtomicBool::new(false);
+        let index = KeyframeIndex::scan(video_asset(), &cancel).unwrap();
+        // 测试素材每秒一个关键帧，共 10 秒
+        assert_eq!(index.len(), 10);
+        assert!(index.is_complete());
+
+        for target_ms in [0, 1_000, 2_520, 4_040, 6_999, 9_000] {
+            let expected = index.nearest(target_ms).unwrap();
+            assert!(expected.pts_ms <= target_ms);
+
+            // 按时间戳向后 Seek 后读到的第一个视频包就是该关键帧
+            let mut demuxer = Demuxer::open(&video_asset().to_string_lossy()).unwrap();
+            demuxer.seek(target_ms).unwrap();
+            let time_base = demuxer.video_stream().unwrap().time_base();
+            let time_base = time_base.numerator() as f64 / time_base.denominator() as f64;
+            let packet = std::iter::from_fn(|| demuxer.read_packet().unwrap())
+                .find_map(|(packet, is_video, _)| is_video.then_some(packet))
+                .unwrap();
+            assert!(packet.is_key());
+            assert_eq!((packet.pts().unwrap() as f64 * time_base * 1000.0) as i64, expected.pts_ms, "Seek 到 {}ms", target_ms);
+
+            // 关键帧导航：下一个 / 上一个关键帧与从该关键帧之后一点 Seek 的结果一致
+            for step in [index.next_after(target_ms), index.previous_before(expected.pts_ms)].into_iter().flatten() {
+                demuxer.seek(step.pts_ms + 20).unwrap();
+                let packet = std::iter::from_fn(|| demuxer.read_packet().unwrap())
+                    .find_map(|(packet, is_video, _)| is_video.then_some(packet))
+                    .unwrap();
+                assert_eq!((packet.pts().unwrap() as f64 * time_base * 1000.0) as i64, step.pts_ms, "关键帧 {}ms", step.pts_ms);
+            }
+        }
+    }
+
+    #[test]
+    fn test_demuxer_uses_background_index() {
+        let mut demuxer = Demuxer::open(&video_asset().to_string_lossy()).unwrap();
+        assert_eq!(demuxer.nearest_keyframe(2_520), None);  // 索引完成前没有结果
+
+        demuxer.start_keyframe_index(None);
+        let shared = demuxer.shared_keyframe_index().unwrap();
+        demuxer.wait_keyframe_index();
+        assert_eq!(demuxer.nearest_keyframe(2_520).map(|k| k.pts_ms), Some(2_000));
+        assert_eq!(demuxer.seek_keyframe(2_520).map(|k| k.pts_ms), Some(2_000));
+        assert_eq!(shared.get().and_then(|index| index.next_after(2_000)).map(|k| k.pts_ms), Some(3_000));
+
+        // 索引完成后 Seek 仍然落在同一个关键帧
+        demuxer.seek(2_520).unwrap();
+        let time_base = demuxer.video_stream().unwrap().time_base();
+        let packet = std::iter::from_fn(|| demuxer.read_packet().unwrap())
+            .find_map(|(packet, is_video, _)| is_video.then_some(packet))
+            .unwrap();
+        let pts_ms = packet.pts().unwrap() * 1000 * time_base.numerator() as i64 / time_base.denominator() as i64;
+        assert_eq!(pts_ms, 2_000);
+    }
+}
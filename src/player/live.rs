@@ -0,0 +1,172 @@
+// 直播流状态（直播识别、直播边缘、可回看窗口、暂停后的恢复策略）
+//
+// 解封装线程读包时更新直播边缘（已收到的最新时间戳）和可回看窗口（DVR），
+// UI 据此显示直播延迟，或在出现可回看窗口时恢复有界的进度条。
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 直播协议（没有时长概念的实时流）
+const LIVE_SCHEMES: &[&str] = &["rtsp://", "rtmp://", "udp://", "rtp://", "srt://"];
+
+/// 距直播边缘在此范围内视为"正在直播"
+pub const LIVE_EDGE_TOLERANCE_MS: i64 = 3_000;
+
+/// 回到直播时在可回看窗口末端预留的余量（避免卡在尚未下载完成的分片上）
+pub const LIVE_EDGE_MARGIN_MS: i64 = 2_000;
+
+/// 直播暂停超过该时长后恢复时回到直播（解封装通道约能缓冲这么久，之后的数据已被服务器丢弃）
+pub const LIVE_PAUSE_BUFFER: Duration = Duration::from_secs(8);
+
+/// 是否为直播源（实时协议，或网络流且没有时长）
+pub fn is_live_source(path: &str, duration_ms: i64) -> bool {
+    let lower = path.to_ascii_lowercase();
+    if LIVE_SCHEMES.iter().any(|scheme| lower.starts_with(scheme)) {
+        return true;
+    }
+    let is_network = lower.starts_with("http://") || lower.starts_with("https://");
+    is_network && duration_ms <= 0
+}
+
+/// 直播暂停后的恢复方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveResume {
+    /// 从暂停处继续（数据仍在缓冲或可回看窗口内）
+    InPlace,
+    /// 回到直播边缘
+    JumpToLive,
+}
+
+/// 直播暂停策略：没有可回看窗口时，暂停超过缓冲时长后回到直播
+pub fn resume_action(paused_for: Duration, has_window: bool) -> LiveResume {
+    if has_window || paused_for < LIVE_PAUSE_BUFFER {
+        LiveResume::InPlace
+    } else {
+        LiveResume::JumpToLive
+    }
+}
+
+/// 直播状态快照（供 UI 使用）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiveStatus {
+    /// 加入直播以来的时间
+    pub joined_ms: i64,
+    /// 已收到的最新时间戳
+    pub edge_ms: Option<i64>,
+    /// 可回看窗口（开始, 结束）
+    pub window: Option<(i64, i64)>,
+}
+
+impl LiveStatus {
+    /// 播放位置落后直播边缘的时间（尚未收到数据时为 None）
+    pub fn latency_ms(&self, position_ms: i64) -> Option<i64> {
+        self.edge_ms.map(|edge| (edge - position_ms).max(0))
+    }
+
+    /// 是否处于直播边缘
+    pub fn is_at_edge(&self, position_ms: i64) -> bool {
+        !matches!(self.latency_ms(position_ms), Some(latency) if latency > LIVE_EDGE_TOLERANCE_MS)
+    }
+
+    /// 回到直播的目标位置（只有可回看窗口时才能通过 seek 实现）
+    pub fn live_seek_target(&self) -> Option<i64> {
+        self.window.map(|(start, end)| (end - LIVE_EDGE_MARGIN_MS).max(start))
+    }
+}
+
+struct TrackerState {
+    joined_at: Instant,
+    edge_ms: Option<i64>,
+    window: Option<(i64, i64)>,
+}
+
+/// 直播状态跟踪（解封装线程写入，UI 读取）
+#[derive(Clone)]
+pub struct LiveTracker {
+    state: Arc<Mutex<TrackerState>>,
+}
+
+impl Default for LiveTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveTracker {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(TrackerState { joined_at: Instant::now(), edge_ms: None, window: None })),
+        }
+    }
+
+    /// 记录读到的数据包时间戳
+    pub fn observe_packet(&self, pts_ms: i64) {
+        let mut state = self.state.lock().unwrap();
+        state.edge_ms = Some(state.edge_ms.map_or(pts_ms, |edge| edge.max(pts_ms)));
+    }
+
+    /// 更新可回看窗口（直播流后来公开了可 seek 的范围时出现）
+    pub fn set_window(&self, window: Option<(i64, i64)>) {
+        let window = window.filter(|(start, end)| end > start);
+        let mut state = self.state.lock().unwrap();
+        if let Some((_, end)) = window {
+            state.edge_ms = Some(state.edge_ms.map_or(end, |edge| edge.max(end)));
+        }
+        state.window = window;
+    }
+
+    /// 当前状态快照
+    pub fn status(&self) -> LiveStatus {
+        let state = self.state.lock().unwrap();
+        LiveStatus {
+            joined_ms: state.joined_at.elapsed().as_millis() as i64,
+            edge_ms: state.edge_ms,
+            window: state.window,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_source_detection() {
+        assert!(is_live_source("rtsp://192.168.1.10/stream1", 0));
+        assert!(is_live_source("RTMP://live.example.com/app/key", 120_000));
+        assert!(is_live_source("https://example.com/live/index.m3u8", 0));
+        assert!(!is_live_source("https://example.com/vod/index.m3u8", 3_600_000));
+        assert!(!is_live_source("/movies/clip.mkv", 0));
+    }
+
+    #[test]
+    fn test_resume_policy() {
+        assert_eq!(resume_action(Duration::from_secs(2), false), LiveResume::InPlace);
+        assert_eq!(resume_action(LIVE_PAUSE_BUFFER, false), LiveResume::JumpToLive);
+        assert_eq!(resume_action(Duration::from_secs(600), true), LiveResume::InPlace);
+    }
+
+    #[test]
+    fn test_edge_and_window() {
+        let tracker = LiveTracker::new();
+        assert_eq!(tracker.status().latency_ms(0), None);
+        assert!(tracker.status().is_at_edge(0));
+
+        tracker.observe_packet(10_000);
+        tracker.observe_packet(9_500);  // 乱序的包不会让边缘后退
+        let status = tracker.status();
+        assert_eq!(status.edge_ms, Some(10_000));
+        assert_eq!(status.latency_ms(6_000), Some(4_000));
+        assert!(!status.is_at_edge(6_000));
+        assert_eq!(status.live_seek_target(), None);
+
+        tracker.set_window(Some((0, 60_000)));
+        let status = tracker.status();
+        assert_eq!(status.edge_ms, Some(60_000));
+        assert_eq!(status.live_seek_target(), Some(60_000 - LIVE_EDGE_MARGIN_MS));
+
+        // 无效窗口视为没有窗口
+        tracker.set_window(Some((5_000, 5_000)));
+        assert_eq!(tracker.status().window, None);
+    }
+}
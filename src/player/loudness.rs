@@ -0,0 +1,178 @@
+// 响度均衡（让对白为主和动作场面为主的文件听起来音量相近）
+//
+// 按帧测量均方根（RMS）并做指数平均（约 3 秒窗口），根据平均响度与目标响度的差距计算增益。
+// 增益缓慢调整：降低较快（避免突然的大声），升高较慢（避免安静段落把底噪抬高形成"呼吸"声）；
+// 静音帧不参与测量，增益限制在 ±12dB 之内，并保证当前帧的峰值不会被放大到超过满幅。
+// 增益在一帧之内线性过渡，避免逐帧跳变产生的杂音。
+
+use crate::core::AudioFrame;
+
+/// 目标响度（RMS，约 -20 dBFS）
+const TARGET_RMS: f32 = 0.1;
+
+/// 低于该 RMS 的帧视为静音，不参与测量（约 -50 dBFS）
+const SILENCE_RMS: f32 = 0.003;
+
+/// 增益范围（-12dB ~ +12dB）
+const MIN_GAIN: f32 = 0.25;
+const MAX_GAIN: f32 = 4.0;
+
+/// 响度平均的时间常数（秒）
+const MEASURE_SECS: f32 = 3.0;
+
+/// 增益降低 / 升高的时间常数（秒）
+const ATTACK_SECS: f32 = 0.5;
+const RELEASE_SECS: f32 = 4.0;
+
+/// 响度均衡器
+#[derive(Debug, Clone)]
+pub struct LoudnessNormalizer {
+    enabled: bool,
+    mean_square: Option<f32>,  // 平均响度（均方值；还没有测量到非静音帧时为 None）
+    gain: f32,
+}
+
+impl Default for LoudnessNormalizer {
+    fn default() -> Self {
+        Self { enabled: false, mean_square: None, gain: 1.0 }
+    }
+}
+
+impl LoudnessNormalizer {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 开启 / 关闭（关闭时恢复原始音量）
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled != self.enabled {
+            self.enabled = enabled;
+            self.reset();
+        }
+    }
+
+    /// 当前增益（线性）
+    #[cfg(test)]
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// 清除测量结果（切换文件时使用）
+    pub fn reset(&mut self) {
+        self.mean_square = None;
+        self.gain = 1.0;
+    }
+
+    /// 测量一帧并应用增益
+    pub fn process(&mut self, frame: &mut AudioFrame) {
+        if !self.enabled || frame.data.is_empty() || frame.sample_rate == 0 || frame.channels == 0 {
+            return;
+        }
+        let frames = frame.data.len() / frame.channels as usize;
+        let secs = frames as f32 / frame.sample_rate as f32;
+
+        let (mut sum, mut peak) = (0.0f32, 0.0f32);
+        for &sample in &frame.data {
+            sum += sample * sample;
+            peak = peak.max(sample.abs());
+        }
+        let frame_mean_square = sum / frame.data.len() as f32;
+        if frame_mean_square.sqrt() >= SILENCE_RMS {
+            let mean_square = self.mean_square.get_or_insert(frame_mean_square);
+            *mean_square += (frame_mean_square - *mean_square) * smoothing(secs, MEASURE_SECS);
+        }
+
+        let target = match self.mean_square {
+            Some(mean_square) => (TARGET_RMS / mean_square.sqrt()).clamp(MIN_GAIN, MAX_GAIN),
+            None => self.gain,
+        };
+        let time_constant = if target < self.gain { ATTACK_SECS } else { RELEASE_SECS };
+        let mut gain = self.gain + (target - self.gain) * smoothing(secs, time_constant);
+        // 峰值不超过满幅（需要时立即降低）
+        if peak > 0.0 {
+            gain = gain.min(1.0 / peak);
+        }
+
+        let start = self.gain;
+        let channels = frame.channels as usize;
+        for (i, chunk) in frame.data.chunks_mut(channels).enumerate() {
+            let t = (i + 1) as f32 / frames as f32;
+            let g = start + (gain - start) * t;
+            for sample in chunk {
+                *sample *= g;
+            }
+        }
+        self.gain = gain;
+    }
+}
+
+/// 指数平均的系数（时长为 secs 的一帧，时间常数为 tau）
+fn smoothing(secs: f32, tau: f32) -> f32 {
+    1.0 - (-secs / tau).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 48_000;
+
+    /// 10ms 的立体声正弦波
+    fn frame(amplitude: f32, index: usize) -> AudioFrame {
+        let len = (RATE / 100) as usize;
+        let data = (0..len)
+            .flat_map(|i| {
+                let t = (index * len + i) as f32 / RATE as f32;
+                let value = (t * 440.0 * std::f32::consts::TAU).sin() * amplitude;
+                [value, value]
+            })
+            .collect();
+        AudioFrame { pts: index as i64 * 10, sample_rate: RATE, channels: 2, data }
+    }
+
+    fn rms(frame: &AudioFrame) -> f32 {
+        (frame.data.iter().map(|s| s * s).sum::<f32>() / frame.data.len() as f32).sqrt()
+    }
+
+    /// 播放 secs 秒，返回最后一帧的 RMS
+    fn settle(normalizer: &mut LoudnessNormalizer, amplitude: f32, secs: usize) -> f32 {
+        let mut last = 0.0;
+        for index in 0..secs * 100 {
+            let mut frame = frame(amplitude, index);
+            normalizer.process(&mut frame);
+            assert!(frame.data.iter().all(|s| s.abs() <= 1.0 + 1e-4));
+            last = rms(&frame);
+        }
+        last
+    }
+
+    #[test]
+    fn test_quiet_and_loud_sources_converge() {
+        let mut quiet = LoudnessNormalizer::default();
+        quiet.set_enabled(true);
+        let mut loud = LoudnessNormalizer::default();
+        loud.set_enabled(true);
+        let quiet_rms = settle(&mut quiet, 0.05, 30);
+        let loud_rms = settle(&mut loud, 0.6, 30);
+        assert!((quiet_rms - TARGET_RMS).abs() < 0.02, "{}", quiet_rms);
+        assert!((loud_rms - TARGET_RMS).abs() < 0.02, "{}", loud_rms);
+    }
+
+    #[test]
+    fn test_disabled_and_silence_leave_gain_alone() {
+        let mut normalizer = LoudnessNormalizer::default();
+        let mut original = frame(0.05, 0);
+        let expected = original.data.clone();
+        normalizer.process(&mut original);
+        assert_eq!(original.data, expected);
+
+        normalizer.set_enabled(true);
+        settle(&mut normalizer, 0.0001, 5);
+        assert_eq!(normalizer.gain(), 1.0);
+        // 增益上限 +12dB
+        settle(&mut normalizer, 0.005, 60);
+        assert!((normalizer.gain() - MAX_GAIN).abs() < 0.01, "{}", normalizer.gain());
+        normalizer.set_enabled(false);
+        assert_eq!(normalizer.gain(), 1.0);
+    }
+}
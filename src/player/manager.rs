@@ -1,13 +1,46 @@
-use crate::core::{AudioFrame, MediaInfo, PlaybackClock, PlaybackState, PlayerState, Result, SubtitleFrame, VideoFrame};
-use crate::core::{MediaSource, StreamProtocol, StreamState};
-use crate::player::{AudioDecoder, AudioOutput, Demuxer, SubtitleDecoder, VideoDecoder, ExternalSubtitleParser};
-use crate::player::NetworkStreamManager;
-use crossbeam::queue::SegQueue;
-use crossbeam_channel::{Receiver, Sender, unbounded};
+use crate::core::{AudioFrame, FramePool, FramePoolStats, MediaInfo, PixelFormat, PlaybackClock, PlaybackState, PlayerError, PlayerState, Result, SubtitleFrame, VideoFrame};
+use crate::core::{adjacent_chapter, is_supported_image_file, pick_forced_subtitle, Chapter, RuntimeFlags, MediaSource, SequencePattern, MAX_VOLUME, PresentedFrameInfo, StreamMeta, StreamProtocol, StreamState, TrackInfo, TrackSource};
+use crate::player::{decode_cover_art, AudioDecoder, AudioOutput, Demuxer, SubtitleDecoder, VideoDecoder, ExternalSubtitleParser};
+use crate::player::{DemuxerThread, NetworkStreamManager, PacketInspector};
+use crate::player::ab_loop::{AbLoop, AbLoopMark};
+use crate::player::audio_sync::AudioSync;
+use crate::player::audio_tempo::{self, AudioTempo};
+use crate::player::loudness::LoudnessNormalizer;
+use crate::player::clock_skew::AudioClockSkew;
+use crate::player::crash_marker::DecodeScope;
+use crate::player::debug_commands::{DebugCommand, DebugCommands, DebugTarget};
+use crate::player::decode_failure::{DecodeHealth, DecoderFailure, FATAL_DECODE_ERROR_LIMIT};
+use crate::player::decoder::{hw_decode_enabled, DecoderStats};
+use crate::player::decoder_fallback::{self, HwErrorWatch, HwFallbackReason, HwFallbackState};
+use crate::player::demux_end::{DemuxEnd, DemuxEvent};
+use crate::player::demuxer_source::DemuxerSource;
+use crate::player::first_frame::{FirstFrameDiagnosis, FirstFrameWatch, VideoDecodeCounters};
+use crate::player::blocking_queue::BlockingQueue;
+use crate::player::subtitle_queue::SubtitleQueue;
+use crate::player::paused_seek::{self, PausedSeek};
+use crate::player::pause_gate::PauseGate;
+use crate::player::seek_filter::{SeekFilter, SeekMode, SeekRequest};
+use crate::player::seek_status::{SeekOutcome, SeekResult, SeekStatus};
+use crate::player::playlist::{Playlist, RepeatMode};
+use crate::player::playlist_prefetch::{PlaylistPrefetch, PrefetchedFrames, PrefetchedStart, SelectedVideoDecoder, PREFETCH_LEAD_MS};
+use crate::player::frame_reorder::FrameReorder;
+use crate::player::hover_preview::{HoverPreview, HoverPreviewState};
+use crate::player::keyframe_index::{self, SharedKeyframeIndex};
+use crate::player::live::LiveStatus;
+use crate::player::media_title;
+use crate::player::position_history::{is_watched, PositionHistory, CHECKPOINT_INTERVAL};
+use crate::player::preview_cache::PreviewCache;
+use crate::player::seamless_loop::{AudioSplicer, LoopControl};
+use crate::player::stall_watchdog::{StallEvent, StallWatchdog};
+use crate::player::timeshift::{TimeshiftLimits, TIMESHIFT_EDGE_MARGIN_MS};
+use crossbeam_channel::Receiver;
 use ffmpeg_next as ffmpeg;
 use log::{debug, error, info, warn};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc, Mutex, RwLock,
 };
 use std::thread;
@@ -18,33 +51,232 @@ fn log_ctx() -> String {
     format!("[pid:{}-tid:{:?}]", process::id(), thread::current().id())
 }
 
+/// 解码单张图像为 RGBA 帧
+fn decode_image_file(path: &Path) -> Result<VideoFrame> {
+    let image = image::open(path)
+        .map_err(|e| PlayerError::OpenError(format!("无法解码图像 {}: {}", path.display(), e)))?
+        .to_rgba8();
+    Ok(VideoFrame {
+        pts: 0,
+        duration: 0,
+        width: image.width(),
+        height: image.height(),
+        format: PixelFormat::RGBA,
+        planes: None,
+        data: image.into_raw().into(),
+    })
+}
+
+/// 网络中断后自动重新连接的最多次数（RECONNECT_WINDOW 内累计）
+const MAX_RECONNECT_ATTEMPTS: u32 = 3;
+
+/// 重新连接次数的累计窗口（上次中断超过这么久后重新计数）
+const RECONNECT_WINDOW: Duration = Duration::from_secs(30);
+
+/// 检查音频设备变化的间隔
+const AUDIO_DEVICE_POLL: Duration = Duration::from_secs(2);
+
+/// 帧队列上限的参考帧率（高于此帧率时按比例放大队列）
+const REFERENCE_FPS: f64 = 30.0;
+
+/// 视频帧队列的内存预算（RGBA 帧）
+const VIDEO_QUEUE_BYTE_BUDGET: usize = 256 * 1024 * 1024;
+
+/// 根据帧率和分辨率计算视频帧队列上限
+/// 
+/// 高帧率源按帧率放大队列以覆盖相同的时长，但总内存不超过预算
+fn scaled_video_queue_limit(base: usize, fps: f64, width: u32, height: u32) -> usize {
+    let scale = if fps.is_finite() && fps > REFERENCE_FPS { fps / REFERENCE_FPS } else { 1.0 };
+    let by_fps = (base as f64 * scale).round() as usize;
+    let frame_bytes = (width as usize * height as usize * 4).max(1);
+    let by_budget = VIDEO_QUEUE_BYTE_BUDGET / frame_bytes;
+    by_fps.min(by_budget).max(base)
+}
+
+/// 字幕延迟的可调范围（毫秒，正负各一分钟）
+const SUBTITLE_OFFSET_LIMIT_MS: i64 = 60_000;
+
+/// 轨道切换超时（超时后不再视为切换中）
+const TRACK_SWITCH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 续播位置距开头/结尾的最小距离（过于接近时从头播放）
+const RESUME_MARGIN_MS: i64 = 10_000;
+
+/// 连续多少个数据包只解码出尺寸无效的帧时，认为视频轨道已损坏
+const CORRUPT_VIDEO_FRAME_LIMIT: u32 = 30;
+
+/// 后退到上一个关键帧时，当前位置之前这么久以内的关键帧视为刚经过（毫秒）
+const KEYFRAME_STEP_GRACE_MS: i64 = 500;
+
+/// 记录一次尺寸无效的解码结果，达到上限时判定视频轨道损坏并通知界面
+fn count_rejected_frame(rejected_frames: &mut u32, corrupt_notice: &AtomicBool, width: u32, height: u32) {
+    *rejected_frames += 1;
+    debug!("🎬 跳过尺寸无效的视频帧 {}x{}（连续 {} 次）", width, height, rejected_frames);
+    if *rejected_frames == CORRUPT_VIDEO_FRAME_LIMIT {
+        error!(
+            "{} ❌ 连续 {} 个视频包只解码出尺寸无效的帧 ({}x{})，视频轨道可能已损坏，停止视频解码（音频继续播放）",
+            log_ctx(), CORRUPT_VIDEO_FRAME_LIMIT, width, height
+        );
+        corrupt_notice.store(true, Ordering::SeqCst);
+    }
+}
+
+/// 视频解码器连续失败：停止视频解码，画面停在最后一帧，音频继续播放
+fn report_video_decoder_failure(corrupt_notice: &AtomicBool) {
+    error!(
+        "{} ❌ 连续 {} 个视频包解码失败，视频解码器已失效，停止视频解码（音频继续播放）",
+        log_ctx(), FATAL_DECODE_ERROR_LIMIT
+    );
+    corrupt_notice.store(true, Ordering::SeqCst);
+}
+
+/// 音频解码器连续失败：切换为无声播放，视频和字幕继续
+fn report_audio_decoder_failure(audio_failure: &DecoderFailure) {
+    error!(
+        "{} ❌ 连续 {} 个音频包解码失败，音频解码器已失效，切换为无声播放（视频和字幕继续）",
+        log_ctx(), FATAL_DECODE_ERROR_LIMIT
+    );
+    audio_failure.mark_failed();
+}
+
+/// 主时钟流在播放开始或 seek 后的第一帧：时钟对齐到该帧 PTS（之后按墙上时间推进）
+fn start_clock_at_frame(awaiting: &AtomicBool, clock: &PlaybackClock, pts: i64, stream: &str) {
+    if awaiting.compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+        info!("{} 🕐 {}时钟已初始化（首帧 PTS: {} ms）", log_ctx(), stream, pts);
+        clock.set_time(pts);
+    }
+}
+
+/// 起播缓冲方式（attach_demuxer 使用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferingPolicy {
+    /// 立即就绪（本地文件）
+    Immediate,
+    /// 先预读一段数据再就绪，减少起播后的卡顿（网络流）
+    Prefill,
+}
+
+/// 单个文件的播放记忆（轨道选择、音量）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileMemory {
+    /// 选择的音频流索引
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_stream: Option<usize>,
+    /// 选择的字幕（Some(None) 表示关闭字幕）
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_some")]
+    pub subtitle: Option<Option<TrackSource>>,
+    /// 调整过的音量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f32>,
+}
+
+/// 反序列化时区分"字段缺失"（None）与"显式为 null"（Some(None)）
+fn deserialize_some<'de, T, D>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
 /// 播放管理器 - 整体控制播放流程
 pub struct PlaybackManager {
     state: Arc<Mutex<PlayerState>>,
     clock: PlaybackClock,
     running: Arc<AtomicBool>,
-    is_first_audio_frame: Arc<AtomicBool>,  // 跟踪是否是第一个音频帧
-    seek_position: Arc<Mutex<Option<(i64, Instant)>>>,  // Seek 目标位置和时间戳（用于防止首次音频帧覆盖时钟）
+    suspended: Arc<AtomicBool>,  // 会话挂起（切换到其他标签页时解封装/解码线程停止工作）
+    awaiting_clock_frame: Arc<AtomicBool>,  // 等待主时钟流的第一帧初始化时钟（有音频输出时为音频，否则为视频）
+    seek_position: Arc<Mutex<Option<SeekRequest>>>,  // 当前 seek 请求（解码线程据此丢弃目标之前的帧）
     need_flush_decoders: Arc<AtomicBool>,  // 标记是否需要 flush 解码器（Seek 后使用）
+    video_corrupt_notice: Arc<AtomicBool>,  // 视频轨道已判定损坏，等待界面提示
+    audio_failure: DecoderFailure,  // 音频解码器中途失效（当前管线无声播放）
+    hw_fallback: HwFallbackState,  // 硬件解码回退到软件解码的原因（当前管线）
+    video_counters: VideoDecodeCounters,  // 视频解码统计（首帧检测）
+    first_frame: Mutex<FirstFrameWatch>,  // 首帧期限计时（当前管线）
+    video_disabled: Arc<AtomicBool>,  // 用户选择只播放音频（视频解码线程丢弃数据包）
+    paused_seek: PausedSeek,  // 暂停状态下的 seek：等待解码线程送出目标位置的帧
+    pause_gate: PauseGate,  // 暂停时挂起的解封装/解码线程休眠（不再轮询挂起标志）
+    hw_decode_always_retry: bool,  // 忽略播放历史中的硬解失败记录，总是先尝试硬件解码
+    video_source: Option<String>,  // 视频解码器对应的媒体路径（记录硬解失败）
+    reorder_corrections: Arc<AtomicU64>,  // 视频帧乱序校正次数（统计面板显示）
     current_file_path: Arc<Mutex<Option<String>>>,  // 当前打开的文件路径（用于停止后重新播放）
-    demux_thread: Option<thread::JoinHandle<()>>,
+    demux_end_rx: Option<Receiver<DemuxEnd>>,  // 解封装结束原因（DemuxerThread 发送）
+    demux_end: Mutex<Option<DemuxEnd>>,  // 已收到的结束原因（seek 时可能清除）
+    network_interruptions: Option<(Instant, u32)>,  // 最近一次网络中断的时间和累计重连次数
     video_decode_thread: Option<thread::JoinHandle<()>>,
     audio_decode_thread: Option<thread::JoinHandle<()>>,
     audio_output: Option<AudioOutput>,
-    audio_frame_queue: Arc<SegQueue<AudioFrame>>,
-    video_frame_queue: Arc<SegQueue<VideoFrame>>,
-    subtitle_frame_queue: Arc<SegQueue<SubtitleFrame>>,  // 字幕帧队列
+    idle_audio_output: Option<AudioOutput>,  // 当前管线没有使用的音频输出（其他会话交来的，恢复或打开文件时绑定）
+    pipeline_audio_config: Option<(u32, u16)>,  // 当前管线的音频解码器按此输出格式重采样（管线不输出音频时为 None）
+    audio_frame_queue: Arc<BlockingQueue<AudioFrame>>,
+    video_frame_queue: Arc<BlockingQueue<VideoFrame>>,
+    video_lookahead: Mutex<Option<VideoFrame>>,  // 已取出但尚未到显示时间的帧（高帧率合并显示使用）
+    subtitle_frame_queue: Arc<SubtitleQueue>,  // 内嵌字幕（按开始时间排序）
     subtitle_decode_thread: Option<thread::JoinHandle<()>>,  // 字幕解码线程
     external_subtitle_frames: Arc<Mutex<Vec<SubtitleFrame>>>,  // 外部字幕帧缓存
-    seek_tx: Option<Sender<i64>>,  // Seek 命令发送端
+    presented_frame: Arc<Mutex<Option<PresentedFrameInfo>>>,  // UI 最近一次实际呈现的视频帧
+    last_presented: Mutex<Option<VideoFrame>>,  // UI 正在显示的帧（会话挂起时保留为海报帧）
+    poster_frame: Option<VideoFrame>,  // 挂起时保留的一帧（恢复时先显示，避免黑屏）
+    transition_from: Arc<Mutex<Option<(i64, Instant)>>>,  // 切换文件前最后呈现的帧（PTS, 时刻），用于测量切换间隔
+    
+    // 轨道选择
+    audio_tracks: Vec<TrackInfo>,  // 音频轨道列表（按流索引排序）
+    selected_audio_stream: Option<usize>,  // 当前音频流索引
+    subtitle_tracks: Vec<TrackInfo>,  // 字幕轨道列表（内嵌在前，外部文件在后）
+    selected_subtitle: Option<TrackSource>,  // 当前字幕（None 表示关闭）
+    subtitle_offset_ms: i64,  // 字幕延迟（正值推后显示，内嵌和外部字幕共用；切换到其他文件时恢复为默认延迟）
+    default_subtitle_offset_ms: i64,  // 打开新文件时的字幕延迟
+    chapters: Vec<Chapter>,  // 章节列表
+    keyframe_index: Option<SharedKeyframeIndex>,  // 当前文件的关键帧索引（后台建立，关键帧导航使用）
+    cover_art: Option<VideoFrame>,  // 纯音频文件的内嵌封面或单张图像（已解码，代替视频画面显示）
+    still_image: bool,  // 当前源是单张图像（静止显示，时钟不推进）
+    image_sequence: Option<(SequencePattern, u32)>,  // 当前源是图像序列（模板, 帧率），停止后重新打开使用
+    file_memory: HashMap<String, FileMemory>,  // 按文件记忆的轨道选择和音量
+    auto_forced_subtitles: bool,  // 字幕关闭时自动选择与音频语言一致的强制字幕
+    subtitles_by_default: bool,  // 没有轨道记忆的文件默认选择第一条字幕
+    runtime_flags: RuntimeFlags,  // 运行时开关（安全模式下关闭硬件解码和字幕自动加载）
+    read_sidecar_titles: bool,  // 读取 .nfo 侧车文件中的标题
+    media_title: Mutex<Option<(String, Option<String>)>>,  // 显示标题缓存（路径, 标题）
+    position_history: Option<Arc<PositionHistory>>,  // 播放位置记录（断电安全的定期检查点，多个会话共享）
+    last_checkpoint: Option<Instant>,  // 上次记录播放位置的时间
+    watched_path: Option<String>,  // 本次播放已标记为已看完的文件（避免重复标记）
+    track_switch_started: Option<Instant>,  // 轨道切换开始时间（用于防抖）
+    seek_status: SeekStatus,  // 最近一次 seek 的结果（解封装线程上报）
     
     // 网络流支持
     network_stream: Option<NetworkStreamManager>,  // 网络流管理器
     stream_state: Arc<RwLock<Option<StreamState>>>,  // 网络流状态（供 UI 读取）
     is_network_source: Arc<AtomicBool>,  // 标记当前是否为网络源（用于动态调整缓冲策略）
     
-    // 新架构：DemuxerThread（用于网络流异步处理）
-    demuxer_thread_handle: Option<crate::player::DemuxerThread>,  // 保存 DemuxerThread，防止被 drop
+    // 解封装线程（本地文件和网络流共用）
+    demuxer_thread_handle: Option<DemuxerThread>,  // 保存 DemuxerThread，防止被 drop
+    
+    // 开发者工具
+    packet_inspector: PacketInspector,  // 数据包检查器
+    debug_commands: DebugCommands,  // 调试命令（送到解封装 / 解码线程）
+    stall_watchdog: StallWatchdog,  // 解封装停滞监视
+
+    // 单曲循环
+    loop_control: LoopControl,  // 循环设置与状态（解封装、音频解码线程共享）
+    playlist: Playlist,  // 播放列表（打开文件时按路径同步当前项）
+    prefetch: Option<PlaylistPrefetch>,  // 播放列表下一项的预读（当前项接近结尾时启动，打开该项时接管）
+    ab_loop: AbLoop,  // A/B 循环（切换到其他文件时清除）
+    seek_count: AtomicU64,  // 累计 seek 次数（A/B 循环据此区分 seek 和连续播放）
+    timeshift: Option<TimeshiftLimits>,  // 直播时移的保留上限（None 不开启，打开直播时生效）
+
+    // 变速播放
+    tempo: AudioTempo,  // 音频变速不变调（时钟按同一速度推进）
+    tempo_seeks: u64,  // 变速处理器上次复位时的 seek 次数（seek 后丢弃旧位置的缓存样本）
+    audio_sync: AudioSync,  // 按实际播放的音频校正时钟（记录写入输出的位置和测量到的音画偏差）
+    frame_pool: Arc<FramePool>,  // 解码帧的像素缓冲区池（帧释放后缓冲区回到池中供下一帧使用）
+    loudness: LoudnessNormalizer,  // 响度均衡（可选，写入输出前调整增益）
+    muted: AtomicBool,  // 静音（不修改记录的音量）
+    audio_device: Option<String>,  // 选择的音频输出设备（None 跟随系统默认设备）
+    audio_device_checked: Instant,  // 上次检查音频设备变化的时间
+
+    // 进度条悬停预览
+    preview_cache: Arc<PreviewCache>,  // 预览缓存（与胶片视图共享）
+    hover_preview: Mutex<Option<HoverPreview>>,  // 悬停预览服务（第一次悬停时启动，stop 时关闭）
 }
 
 impl PlaybackManager {
@@ -54,24 +286,85 @@ impl PlaybackManager {
             state: Arc::new(Mutex::new(PlayerState::default())),
             clock: PlaybackClock::new(),
             running: Arc::new(AtomicBool::new(false)),
-            is_first_audio_frame: Arc::new(AtomicBool::new(true)),
+            suspended: Arc::new(AtomicBool::new(false)),
+            awaiting_clock_frame: Arc::new(AtomicBool::new(true)),
             seek_position: Arc::new(Mutex::new(None)),
             need_flush_decoders: Arc::new(AtomicBool::new(false)),
+            video_corrupt_notice: Arc::new(AtomicBool::new(false)),
+            audio_failure: DecoderFailure::default(),
+            hw_fallback: HwFallbackState::default(),
+            video_counters: VideoDecodeCounters::default(),
+            first_frame: Mutex::new(FirstFrameWatch::default()),
+            video_disabled: Arc::new(AtomicBool::new(false)),
+            paused_seek: PausedSeek::default(),
+            pause_gate: PauseGate::default(),
+            hw_decode_always_retry: false,
+            video_source: None,
+            reorder_corrections: Arc::new(AtomicU64::new(0)),
             current_file_path: Arc::new(Mutex::new(None)),
-            demux_thread: None,
+            demux_end_rx: None,
+            demux_end: Mutex::new(None),
+            network_interruptions: None,
             video_decode_thread: None,
             audio_decode_thread: None,
             audio_output: None,
-            audio_frame_queue: Arc::new(SegQueue::new()),
-            video_frame_queue: Arc::new(SegQueue::new()),
-            subtitle_frame_queue: Arc::new(SegQueue::new()),
+            idle_audio_output: None,
+            pipeline_audio_config: None,
+            audio_frame_queue: Arc::new(BlockingQueue::new()),
+            video_frame_queue: Arc::new(BlockingQueue::new()),
+            video_lookahead: Mutex::new(None),
+            subtitle_frame_queue: Arc::new(SubtitleQueue::new()),
             subtitle_decode_thread: None,
             external_subtitle_frames: Arc::new(Mutex::new(Vec::new())),
-            seek_tx: None,
+            presented_frame: Arc::new(Mutex::new(None)),
+            last_presented: Mutex::new(None),
+            poster_frame: None,
+            transition_from: Arc::new(Mutex::new(None)),
+            audio_tracks: Vec::new(),
+            selected_audio_stream: None,
+            subtitle_tracks: Vec::new(),
+            selected_subtitle: None,
+            subtitle_offset_ms: 0,
+            default_subtitle_offset_ms: 0,
+            chapters: Vec::new(),
+            keyframe_index: None,
+            cover_art: None,
+            still_image: false,
+            image_sequence: None,
+            file_memory: HashMap::new(),
+            auto_forced_subtitles: true,
+            subtitles_by_default: true,
+            runtime_flags: RuntimeFlags::NORMAL,
+            read_sidecar_titles: true,
+            media_title: Mutex::new(None),
+            position_history: None,
+            last_checkpoint: None,
+            watched_path: None,
+            track_switch_started: None,
+            seek_status: SeekStatus::default(),
             network_stream: None,
             stream_state: Arc::new(RwLock::new(None)),
             is_network_source: Arc::new(AtomicBool::new(false)),
             demuxer_thread_handle: None,
+            packet_inspector: PacketInspector::new(),
+            debug_commands: DebugCommands::default(),
+            stall_watchdog: StallWatchdog::default(),
+            loop_control: LoopControl::new(),
+            playlist: Playlist::default(),
+            prefetch: None,
+            ab_loop: AbLoop::default(),
+            timeshift: None,
+            seek_count: AtomicU64::new(0),
+            tempo: AudioTempo::default(),
+            tempo_seeks: 0,
+            audio_sync: AudioSync::default(),
+            frame_pool: FramePool::new(),
+            loudness: LoudnessNormalizer::default(),
+            muted: AtomicBool::new(false),
+            audio_device: None,
+            audio_device_checked: Instant::now(),
+            preview_cache: Arc::new(PreviewCache::memory_only()),
+            hover_preview: Mutex::new(None),
         };
         info!("{} ✅ 播放管理器创建完成", log_ctx());
         manager
@@ -91,37 +384,105 @@ impl PlaybackManager {
             MediaSource::NetworkStream { url, protocol } => {
                 self.open_stream(&url, protocol)
             }
+            MediaSource::ImageSequence { pattern, fps } => {
+                self.open_image_sequence(pattern, fps)
+            }
+            MediaSource::Image(path) => {
+                self.open_image(&path)
+            }
+        }
+    }
+
+    /// 打开图像序列（作为无声视频播放，支持 Seek 和进度条）
+    pub fn open_image_sequence(&mut self, pattern: SequencePattern, fps: u32) -> Result<MediaInfo> {
+        let template = pattern.template.to_string_lossy().to_string();
+        let media_info = self.open_with(template, |_| Ok((Demuxer::open_image_sequence(&pattern, fps)?, None)))?;
+        self.image_sequence = Some((pattern, fps));
+        Ok(media_info)
+    }
+
+    /// 打开单张图像（解码一次后静止显示，时钟不推进）
+    pub fn open_image(&mut self, path: &Path) -> Result<MediaInfo> {
+        info!("{} 🖼️ 打开图像: {}", log_ctx(), path.display());
+        self.stop();
+        self.image_sequence = None;
+        self.is_network_source.store(false, Ordering::SeqCst);
+        self.packet_inspector.clear();
+
+        let frame = decode_image_file(path)?;
+        let media_info = MediaInfo {
+            width: frame.width,
+            height: frame.height,
+            video_codec: "image".to_string(),
+            audio_codec: "none".to_string(),
+            ..MediaInfo::default()
+        };
+
+        self.audio_tracks.clear();
+        self.selected_audio_stream = None;
+        self.subtitle_tracks.clear();
+        self.selected_subtitle = None;
+        self.chapters.clear();
+        self.cover_art = Some(frame);
+        self.still_image = true;
+        *self.current_file_path.lock().unwrap() = Some(path.to_string_lossy().to_string());
+        self.playlist.select(&path.to_string_lossy());
+        {
+            let mut state = self.state.lock().unwrap();
+            state.duration = 0;
+            state.media_info = Some(media_info.clone());
+            state.state = PlaybackState::Paused;
         }
+
+        info!("{} ✅ 图像已加载: {}x{}", log_ctx(), media_info.width, media_info.height);
+        Ok(media_info)
+    }
+
+    /// 当前源是否为单张图像（没有时间轴）
+    pub fn is_still_image(&self) -> bool {
+        self.still_image
     }
     
-    /// 使用已创建的 Demuxer 启动播放（新架构）
+    /// 使用已创建的 Demuxer 启动播放
     /// 
-    /// 这个方法接收外部创建的 Demuxer，避免在主线程中阻塞创建过程
+    /// 这个方法接收外部创建的 Demuxer（通常在子线程中创建），避免在主线程中阻塞创建过程；
+    /// 本地文件和网络流都在 DemuxerThread 中读包，只有起播前的缓冲方式不同
     /// 
     /// 参数：
-    /// - demuxer: 已创建的 Demuxer（通常在子线程中创建）
+    /// - demuxer: 已创建的 Demuxer
+    /// - policy: 起播缓冲方式（网络流先预读一段数据，本地文件立即就绪）
     /// 
     /// 返回：
     /// - MediaInfo: 媒体信息
-    pub fn attach_demuxer(&mut self, demuxer: crate::player::Demuxer) -> Result<MediaInfo> {
-        info!("{} 📎 附加 Demuxer", log_ctx());
+    pub fn attach_demuxer(&mut self, demuxer: Demuxer, policy: BufferingPolicy) -> Result<MediaInfo> {
+        info!("{} 📎 附加 Demuxer（{:?}）", log_ctx(), policy);
         
-        // 停止当前播放
+        // 停止当前播放（stop 会停止所有线程并 join）
         self.stop();
         
         // 获取媒体信息
         let media_info = demuxer.get_media_info()?;
+        self.update_track_lists(&demuxer, &[]);
         
         // 判断是否为网络源（根据路径判断）
         let source_path = demuxer.description();
-        let is_network = source_path.contains("rtsp://") 
-            || source_path.contains("rtmp://")
-            || source_path.contains("http://")
-            || source_path.contains("https://");
+        let is_network = DemuxerSource::is_network(&demuxer);
         self.is_network_source.store(is_network, Ordering::SeqCst);
+        self.packet_inspector.clear();
+        
+        if self.current_file_path.lock().unwrap().as_deref() != Some(source_path.as_str()) {
+            self.ab_loop.clear();
+            self.subtitle_offset_ms = self.default_subtitle_offset_ms;
+        }
+        // 本地文件记录路径（用于停止后重新播放与轨道切换）
+        if !is_network {
+            let mut file_path = self.current_file_path.lock().unwrap();
+            *file_path = Some(source_path.clone());
+        }
+        self.playlist.select(&source_path);
         
-        // 重置首次音频帧标志
-        self.is_first_audio_frame.store(true, Ordering::SeqCst);
+        // 重置首帧时钟标志
+        self.awaiting_clock_frame.store(true, Ordering::SeqCst);
         
         // 重置 seek 位置
         {
@@ -137,90 +498,16 @@ impl PlaybackManager {
             state.media_info = Some(media_info.clone());
         }
         
-        info!("{} 媒体信息: {:?}", log_ctx(), media_info);
-        
-        // 创建视频解码器（自动选择硬件加速）
-        let video_decoder = if let Some(stream) = demuxer.video_stream() {
-            let decoder = match VideoDecoder::from_stream(stream) {
-                Ok(decoder) => {
-                    info!("视频解码器: {}", decoder.info());
-                    if decoder.is_hardware_accelerated() {
-                        info!("✓ 硬件加速已启用");
-                    }
-                    decoder
-                }
-                Err(e) => {
-                    info!("硬件解码不可用: {}, 回退到软件解码", e);
-                    let stream = demuxer.video_stream().unwrap();
-                    let decoder = VideoDecoder::from_stream_software(stream)?;
-                    info!("✓ 使用软件解码");
-                    decoder
-                }
-            };
-            Some(decoder)
-        } else {
-            None
-        };
-        
-        // 创建音频输出（先创建，获取实际配置）
-        self.audio_output = if media_info.audio_codec != "none" {
-            match AudioOutput::new(media_info.sample_rate, media_info.channels) {
-                Ok(mut output) => {
-                    output.start()?;
-                    Some(output)
-                }
-                Err(e) => {
-                    error!("{} 创建音频输出失败: {}", log_ctx(), e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
-        
-        // 获取音频输出的实际配置（用于解码器）
-        let (actual_sample_rate, actual_channels) = if let Some(ref output) = self.audio_output {
-            output.get_config()
-        } else {
-            (48000, 2) // 默认配置
-        };
-        
-        // 创建音频解码器（使用音频输出的实际配置）
-        let audio_decoder = if let Some(stream) = demuxer.audio_stream() {
-            Some(AudioDecoder::from_stream_with_config(
-                stream,
-                actual_sample_rate,
-                actual_channels,
-            )?)
-        } else {
-            None
-        };
+        info!("{} 📎 媒体信息: {:?}", log_ctx(), media_info);
         
-        // 创建字幕解码器
-        let subtitle_decoder = if let Some(stream) = demuxer.subtitle_stream() {
-            match SubtitleDecoder::from_stream(stream) {
-                Ok(decoder) => {
-                    info!("{} 字幕解码器创建成功", log_ctx());
-                    Some(decoder)
-                }
-                Err(e) => {
-                    warn!("{} 创建字幕解码器失败: {}，继续播放（无字幕）", log_ctx(), e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
+        // 创建解码器，启动 DemuxerThread 和解码线程
+        self.start_pipeline(demuxer, None)?;
         
-        // 启动播放线程
-        self.start_playback_threads(
-            demuxer,
-            video_decoder,
-            audio_decoder,
-            subtitle_decoder,
-        );
+        if policy == BufferingPolicy::Prefill {
+            self.prefill();
+        }
         
-        // 更新状态为暂停
+        // 更新状态为暂停，外部 UI 可以触发 Play
         {
             let mut state = self.state.lock().unwrap();
             state.state = PlaybackState::Paused;
@@ -229,172 +516,70 @@ impl PlaybackManager {
         Ok(media_info)
     }
     
-    /// 使用已创建的 Demuxer 启动播放（网络流专用 - 使用 DemuxerThread 异步模式）
-    /// 
-    /// 这个方法专门用于网络流，使用 DemuxerThread 在独立线程中运行 Demuxer
-    /// 
-    /// 参数：
-    /// - demuxer: 已创建的 Demuxer（通常在子线程中创建）
-    /// 
-    /// 返回：
-    /// - MediaInfo: 媒体信息
-    pub fn attach_demuxer_async(&mut self, demuxer: crate::player::Demuxer) -> Result<MediaInfo> {
-        use crate::player::DemuxerThread;
-        
-        info!("{} 📎 附加 Demuxer（异步模式 - 网络流）", log_ctx());
-        
-            // 停止当前播放（注意 stop 应该能停止所有线程并 join）
-    self.stop();
-
-    // 获取媒体信息
-    let media_info = demuxer.get_media_info()?;
-
-    // 标记为网络源
-    self.is_network_source.store(true, Ordering::SeqCst);
-    // 重置首次音频帧标志
-    self.is_first_audio_frame.store(true, Ordering::SeqCst);
-    // 重置 seek 位置
-    {
-        let mut seek_pos = self.seek_position.lock().unwrap();
-        *seek_pos = None;
-    }
+    /// 起播缓冲（Buffering）：等待包通道积累到阈值或超时
+    fn prefill(&self) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.state = PlaybackState::Buffering;
+        }
 
-    // 更新状态（Opening）
-    {
-        let mut state = self.state.lock().unwrap();
-        state.state = PlaybackState::Opening;
-        state.duration = media_info.duration;
-        state.media_info = Some(media_info.clone());
-    }
+        // 缓冲目标：可根据网络/分辨率动态调整。这里使用 packet 数量阈值示例。
+        const TARGET_VIDEO_PACKETS: usize = 40; // 例如约 1-2 秒数据，需自行调试
+        const TARGET_AUDIO_PACKETS: usize = 80;
+        const BUFFER_TIMEOUT_MS: u64 = 8000; // 最长等待 8 秒
 
-    info!("{} 📎 媒体信息: {:?}", log_ctx(), media_info);
+        let start = Instant::now();
+        let mut buffered = false;
 
-    // 创建解码器（保持你现有逻辑）
-    let video_decoder = if let Some(stream) = demuxer.video_stream() {
-        let decoder = match VideoDecoder::from_stream(stream) {
-            Ok(decoder) => {
-                info!("{} 📎 视频解码器: {}", log_ctx(), decoder.info());
-                if decoder.is_hardware_accelerated() {
-                    info!("{} ✓ 硬件加速已启用", log_ctx());
+        while start.elapsed() < Duration::from_millis(BUFFER_TIMEOUT_MS) {
+            if let Some(ref demux_thread) = self.demuxer_thread_handle {
+                let (vlen, alen) = demux_thread.queued_packets();
+                if vlen >= TARGET_VIDEO_PACKETS && alen >= TARGET_AUDIO_PACKETS {
+                    buffered = true;
+                    break;
                 }
-                decoder
-            }
-            Err(e) => {
-                info!("{} 硬件解码不可用: {}, 回退到软件解码", log_ctx(), e);
-                let stream = demuxer.video_stream().unwrap();
-                let decoder = VideoDecoder::from_stream_software(stream)?;
-                info!("{} ✓ 使用软件解码", log_ctx());
-                decoder
-            }
-        };
-        Some(decoder)
-    } else {
-        None
-    };
-
-    // 创建音频输出
-    self.audio_output = if media_info.audio_codec != "none" {
-        match AudioOutput::new(media_info.sample_rate, media_info.channels) {
-            Ok(mut output) => {
-                output.start()?;
-                Some(output)
-            }
-            Err(e) => {
-                error!("{} ❌ 创建音频输出失败: {}", log_ctx(), e);
-                None
             }
+            thread::sleep(Duration::from_millis(20));
         }
-    } else {
-        None
-    };
-
-    // 获取实际音频输出配置
-    let (actual_sample_rate, actual_channels) = if let Some(ref output) = self.audio_output {
-        output.get_config()
-    } else {
-        (48000, 2)
-    };
 
-    // 创建音频解码器
-    let audio_decoder = if let Some(stream) = demuxer.audio_stream() {
-        Some(AudioDecoder::from_stream_with_config(stream, actual_sample_rate, actual_channels)?)
-    } else {
-        None
-    };
-
-    // 创建字幕解码器（保持原逻辑）
-    let subtitle_decoder = if let Some(stream) = demuxer.subtitle_stream() {
-        match SubtitleDecoder::from_stream(stream) {
-            Ok(decoder) => {
-                info!("{} 📎 字幕解码器创建成功", log_ctx());
-                Some(decoder)
-            }
-            Err(e) => {
-                warn!("{} ❌ 创建字幕解码器失败: {}，继续播放（无字幕）", log_ctx(), e);
-                None
-            }
+        if buffered {
+            info!("{} ✅ 缓冲完成：开始播放", log_ctx());
+        } else {
+            warn!("{} ❌ 缓冲超时（{}ms），将尽量开始播放以避免长时间等待", log_ctx(), BUFFER_TIMEOUT_MS);
         }
-    } else {
-        None
-    };
-
-    // 启动 DemuxerThread（使用新实现）
-    info!("{} 🚀 启动 DemuxerThread", log_ctx());
-    let demuxer_thread = DemuxerThread::start(Box::new(demuxer));
-
-    // 启动播放线程（使用 DemuxerThread）
-    self.start_playback_threads_with_demuxer_thread(
-        demuxer_thread,
-        video_decoder,
-        audio_decoder,
-        subtitle_decoder,
-    );
-
-    // 进入缓冲阶段（Buffering），直到 packet 队列满足阈值或超时
-    {
-        let mut state = self.state.lock().unwrap();
-        state.state = PlaybackState::Buffering;
     }
 
-    // 缓冲目标：可根据网络/分辨率动态调整。这里使用 packet 数量阈值示例。
-    const TARGET_VIDEO_PACKETS: usize = 40; // 例如约 1-2 秒数据，需自行调试
-    const TARGET_AUDIO_PACKETS: usize = 80;
-    const BUFFER_TIMEOUT_MS: u64 = 8000; // 最长等待 8 秒
-
-    let start = Instant::now();
-    let mut buffered = false;
-
-    // 获取 Receiver.len() 方法（crossbeam::channel::Receiver 有 len()）
-    while start.elapsed() < Duration::from_millis(BUFFER_TIMEOUT_MS) {
-        if let Some(ref demux_thread) = self.demuxer_thread_handle {
-            let vlen = demux_thread.video_packet_queue.as_ref().map(|r| r.len()).unwrap_or(0);
-            let alen = demux_thread.audio_packet_queue.as_ref().map(|r| r.len()).unwrap_or(0);
-            if vlen >= TARGET_VIDEO_PACKETS && alen >= TARGET_AUDIO_PACKETS {
-                buffered = true;
-                break;
+    /// 打开媒体文件
+    pub fn open(&mut self, path: String) -> Result<MediaInfo> {
+        self.image_sequence = None;
+        // 播放列表切换到已预读的下一项：接管预读的解封装器、解码器和开头的帧（预读还没完成时不等待，直接打开）
+        let prefetched = self
+            .prefetch
+            .take()
+            .filter(|prefetch| prefetch.path() == path)
+            .and_then(PlaylistPrefetch::try_finish);
+        self.open_with(path, |path| match prefetched {
+            Some(item) => Ok((item.demuxer, Some(item.start))),
+            None => {
+                let mut demuxer = Demuxer::open(path)?;
+                demuxer.start_keyframe_index(Some(keyframe_index::default_cache_dir()));
+                Ok((demuxer, None))
             }
-        }
-        thread::sleep(Duration::from_millis(20));
-    }
-
-    if buffered {
-        info!("{} ✅ 缓冲完成：开始播放", log_ctx());
-    } else {
-        warn!("{} ❌ 缓冲超时（{}ms），将尽量开始播放以避免长时间等待", log_ctx(), BUFFER_TIMEOUT_MS);
-    }
-
-    // 将状态设为 Paused（与原逻辑一致），外部 UI 可以触发 Play
-    {
-        let mut state = self.state.lock().unwrap();
-        state.state = PlaybackState::Paused;
-    }
-
-    Ok(media_info)
+        })
     }
 
-    /// 打开媒体文件
-    pub fn open(&mut self, path: String) -> Result<MediaInfo> {
+    /// 打开本地媒体（open_demuxer 负责创建解封装器并给出预读的开头，其余流程与普通文件相同）
+    fn open_with(
+        &mut self,
+        path: String,
+        open_demuxer: impl FnOnce(&str) -> Result<(Demuxer, Option<PrefetchedStart>)>,
+    ) -> Result<MediaInfo> {
         info!("{} � 打开媒体文件: {}", log_ctx(), path);
+        
+        // 记录切换前最后呈现的帧（stop 会清除），用于测量切换间隔
+        *self.transition_from.lock().unwrap() = self
+            .get_presented_frame()
+            .map(|info| (info.pts, info.presented_at));
 
         // 停止当前播放
         self.stop();
@@ -402,8 +587,8 @@ impl PlaybackManager {
         // 标记为本地文件（非网络源）
         self.is_network_source.store(false, Ordering::SeqCst);
         
-        // 重置首次音频帧标志
-        self.is_first_audio_frame.store(true, Ordering::SeqCst);
+        // 重置首帧时钟标志
+        self.awaiting_clock_frame.store(true, Ordering::SeqCst);
         
         // 重置 seek 位置（避免旧文件的 seek 位置影响新文件）
         {
@@ -417,14 +602,69 @@ impl PlaybackManager {
             state.state = PlaybackState::Opening;
         }
 
+        self.packet_inspector.clear();
+        self.loop_control.reset();
+        self.video_corrupt_notice.store(false, Ordering::SeqCst);
+
         // 保存文件路径（用于停止后重新播放）
         {
             let mut file_path = self.current_file_path.lock().unwrap();
+            if file_path.as_deref() != Some(path.as_str()) {
+                self.ab_loop.clear();
+                self.subtitle_offset_ms = self.default_subtitle_offset_ms;
+            }
             *file_path = Some(path.clone());
         }
+        self.playlist.select(&path);
         
         // 打开解封装器
-        let demuxer = Demuxer::open(&path)?;
+        let (mut demuxer, prefetched) = open_demuxer(&path)?;
+        
+        // 应用该文件记忆的轨道选择
+        let memory = self.file_memory.get(&path).cloned().unwrap_or_default();
+        if let Some(index) = memory.audio_stream {
+            if let Err(e) = demuxer.select_audio_stream(index) {
+                warn!("{} ⚠️  恢复音频轨道失败: {}", log_ctx(), e);
+            }
+        }
+        
+        let external_subtitles = ExternalSubtitleParser::find_subtitle_files(&path);
+        self.update_track_lists(&demuxer, &external_subtitles);
+        
+        // 字幕：优先使用记忆，否则默认第一条内嵌字幕，其次第一个外部字幕（安全模式下不自动加载字幕，
+        // 关闭"默认显示字幕"时只恢复记忆）
+        let mut subtitle = if self.runtime_flags.subtitle_autoload {
+            memory.subtitle.unwrap_or_else(|| {
+                self.subtitle_tracks
+                    .first()
+                    .filter(|_| self.subtitles_by_default)
+                    .map(|track| track.source.clone())
+            })
+        } else {
+            info!("{} 🛡️ 安全模式：不自动加载字幕", log_ctx());
+            None
+        };
+        
+        // 字幕关闭时仍显示与音频语言一致的强制字幕（外语对白、标牌翻译）
+        if subtitle.is_none() && self.auto_forced_subtitles && self.runtime_flags.subtitle_autoload {
+            let audio_language = demuxer
+                .audio_stream_index()
+                .and_then(|index| self.audio_tracks.iter().find(|track| track.source == TrackSource::Embedded(index)))
+                .and_then(|track| track.meta.language.clone());
+            subtitle = pick_forced_subtitle(&self.subtitle_tracks, audio_language.as_deref());
+            if let Some(track) = &subtitle {
+                info!("{} 📝 自动选择强制字幕: {:?}", log_ctx(), track);
+            }
+        }
+        let embedded_subtitle = match subtitle {
+            Some(TrackSource::Embedded(index)) => Some(index),
+            _ => None,
+        };
+        if let Err(e) = demuxer.select_subtitle_stream(embedded_subtitle) {
+            warn!("{} ⚠️  恢复字幕轨道失败: {}", log_ctx(), e);
+        }
+        self.selected_subtitle = subtitle;
+        
         let media_info = demuxer.get_media_info()?;
 
         info!("{} 📎 媒体信息: {:?}", log_ctx(), media_info);
@@ -437,91 +677,13 @@ impl PlaybackManager {
             state.state = PlaybackState::Paused;
         }
 
-        // 创建视频解码器（自动选择硬件加速）
-        let video_decoder = if let Some(stream) = demuxer.video_stream() {
-            // 先尝试硬件解码
-            let decoder = match VideoDecoder::from_stream(stream) {
-                Ok(decoder) => {
-            info!("{} 📎 视频解码器: {}", log_ctx(), decoder.info());
-            if decoder.is_hardware_accelerated() {
-                info!("{} ✓ 硬件加速已启用", log_ctx());
-                    }
-                    decoder
-                }
-                Err(e) => {
-                    info!("{} 硬件解码不可用: {}, 回退到软件解码", log_ctx(), e);
-                    // 硬件解码失败，使用软件解码
-                    let stream = demuxer.video_stream().unwrap();
-                    let decoder = VideoDecoder::from_stream_software(stream)?;
-                    info!("{} ✓ 使用软件解码", log_ctx());
-                    decoder
-                }
-            };
-            Some(decoder)
-        } else {
-            None
-        };
-
-        // 创建音频输出（先创建，获取实际配置）
-        self.audio_output = if media_info.audio_codec != "none" {
-            match AudioOutput::new(media_info.sample_rate, media_info.channels) {
-                Ok(mut output) => {
-                    output.start()?;
-                    Some(output)
-                }
-                Err(e) => {
-                    error!("{} ❌ 创建音频输出失败: {}", log_ctx(), e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
-        
-        // 获取音频输出的实际配置（用于解码器）
-        let (actual_sample_rate, actual_channels) = if let Some(ref output) = self.audio_output {
-            output.get_config()
-        } else {
-            (48000, 2) // 默认配置
-        };
-
-        // 创建音频解码器（使用音频输出的实际配置）
-        let audio_decoder = if let Some(stream) = demuxer.audio_stream() {
-            Some(AudioDecoder::from_stream_with_config(
-                stream,
-                actual_sample_rate,
-                actual_channels,
-            )?)
-        } else {
-            None
-        };
-
-        // 创建字幕解码器
-        let subtitle_decoder = if let Some(stream) = demuxer.subtitle_stream() {
-            match SubtitleDecoder::from_stream(stream) {
-                Ok(decoder) => {
-                    info!("{} 📎 字幕解码器创建成功", log_ctx());
-                    Some(decoder)
-                }
-                Err(e) => {
-                    warn!("{} ❌ 创建字幕解码器失败: {}，继续播放（无字幕）", log_ctx(), e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
-
         // 加载外部字幕文件
-        self.load_external_subtitles(&path);
-
-        // 启动播放线程
-        self.start_playback_threads(
-            demuxer,
-            video_decoder,
-            audio_decoder,
-            subtitle_decoder,
-        );
+        if let Some(TrackSource::External(subtitle_file)) = self.selected_subtitle.clone() {
+            self.load_external_subtitles(&subtitle_file);
+        }
+
+        // 创建解码器（已预读时接管预读的解码器），启动 DemuxerThread 和解码线程
+        self.start_pipeline(demuxer, prefetched)?;
 
         Ok(media_info)
     }
@@ -533,8 +695,8 @@ impl PlaybackManager {
             state.state
         };
         
-        // 如果处于停止状态，需要重新打开文件
-        if current_state == PlaybackState::Stopped {
+        // 如果处于停止状态或已播放完毕（解封装线程已退出），需要重新打开文件（从头播放）
+        if matches!(current_state, PlaybackState::Stopped | PlaybackState::Ended) {
             // 先获取文件路径并释放锁
             let file_path = {
                 let file_path_guard = self.current_file_path.lock().unwrap();
@@ -542,17 +704,30 @@ impl PlaybackManager {
             };
             
             if let Some(path) = file_path {
-                info!("{} 从停止状态恢复播放，重新打开文件: {}", log_ctx(), path);
+                info!("{} 从{}状态恢复播放，重新打开文件: {}", log_ctx(), if current_state == PlaybackState::Ended { "播放完毕" } else { "停止" }, path);
                 // 重新打开文件（这会重新启动线程）
-                self.open_file(&path)?;
+                if let Some((pattern, fps)) = self.image_sequence.clone() {
+                    self.open_image_sequence(pattern, fps)?;
+                } else if is_supported_image_file(Path::new(&path)) {
+                    self.open_image(Path::new(&path))?;
+                } else {
+                    self.open_file(&path)?;
+                }
                 // 打开后状态是 Paused，继续执行下面的 play 逻辑
             } else {
                 return Err(crate::core::PlayerError::Other("没有打开的文件，无法播放".to_string()).into());
             }
         }
         
+        // 单张图像没有时间轴，保持暂停
+        if self.still_image {
+            return Ok(());
+        }
+        
         info!("{} 🎬 播放", log_ctx());
+        self.paused_seek.cancel();
         self.clock.play();
+        self.pause_gate.set_paused(false);
         let mut state = self.state.lock().unwrap();
         state.state = PlaybackState::Playing;
         Ok(())
@@ -565,6 +740,10 @@ impl PlaybackManager {
     /// - 清空音频缓冲区：立即停止声音输出
     /// - 更新播放状态：标记为暂停
     pub fn pause(&self) {
+        // 播放完毕后保持 Ended（再次播放时从头开始）
+        if self.is_ended() {
+            return;
+        }
         info!("{} 🎬 暂停", log_ctx());
         
         // ========== 暂停时钟 ==========
@@ -578,15 +757,137 @@ impl PlaybackManager {
             debug!("{} ✓ 暂停时清空音频输出缓冲区", log_ctx());
         }
         
+        // ========== 通知播放线程进入暂停 ==========
+        self.pause_gate.set_paused(true);
+        
         // ========== 更新播放状态 ==========
         let mut state = self.state.lock().unwrap();
         state.state = PlaybackState::Paused;
     }
 
-    /// ==================== 音画同步核心: Seek 跳转 ====================
+    /// 挂起（切换到其他会话时调用），返回挂起前是否正在播放
     /// 
-    /// # 功能说明
-    /// 跳转到指定播放位置，确保音画同步
+    /// - 暂停在当前呈现的帧（时钟对齐到该帧 PTS）
+    /// - 解封装/解码线程停止工作，视频帧队列只保留一帧
+    /// - 释放音频设备流，保证同一时刻只有活动会话输出声音
+    pub fn suspend(&mut self) -> bool {
+        // 没有运行中的播放线程（未打开文件或已停止）时无需挂起
+        if !self.running.load(Ordering::SeqCst) {
+            return false;
+        }
+        let was_playing = self.is_playing();
+        self.pause();
+        if let Some(presented) = self.get_presented_frame() {
+            self.clock.set_time(presented.pts);
+        }
+        self.suspended.store(true, Ordering::SeqCst);
+
+        // 正在显示的帧（video_lookahead 中是下一帧，还没有显示）
+        let poster = self.last_presented.lock().unwrap().clone();
+        self.video_lookahead.lock().unwrap().take();
+        let released = self.video_frame_queue.clear();
+        self.audio_frame_queue.clear();
+        self.poster_frame = poster;
+
+        if let Some(ref mut output) = self.audio_output {
+            output.stop();
+        }
+        info!("{} 💤 会话挂起于 {}ms，释放 {} 个视频帧", log_ctx(), self.clock.now(), released);
+        was_playing
+    }
+
+    /// 把音频输出交给另一个会话（切换会话时在挂起后调用）：同一时刻只有活动会话持有音频设备，
+    /// 对方恢复或打开文件时绑定，格式不同时重新协商
+    pub fn hand_over_audio_output(&mut self, to: &mut PlaybackManager) {
+        let Some(mut output) = self.audio_output.take().or_else(|| self.idle_audio_output.take()) else {
+            return;
+        };
+        output.stop();
+        output.clear_buffer();
+        info!("{} 🔈 音频输出交给切换到的会话", log_ctx());
+        to.idle_audio_output = Some(output);
+    }
+
+    /// 管线需要音频输出但挂起期间交给了其他会话：绑定交来的输出（格式与管线不同时按管线的格式重新打开设备）。
+    /// 设备不支持管线的格式或无法打开时返回 false，由调用方重建管线
+    fn rebind_audio_output(&mut self) -> bool {
+        let Some(config) = self.pipeline_audio_config.filter(|_| self.audio_output.is_none()) else {
+            return true;
+        };
+        let output = match self.idle_audio_output.take() {
+            Some(output) if output.get_config() == config && !output.has_failed() => output,
+            idle => {
+                // 先释放交来的输出，再按本会话的格式打开设备
+                drop(idle);
+                match AudioOutput::new_with_device(self.audio_device.as_deref(), config.0, config.1) {
+                    Ok(output) if output.get_config() == config => {
+                        info!("{} 🔈 音频输出按 {} Hz / {} 声道重新协商", log_ctx(), config.0, config.1);
+                        output
+                    }
+                    Ok(output) => {
+                        self.idle_audio_output = Some(output);
+                        return false;
+                    }
+                    Err(e) => {
+                        error!("{} ❌ 重新打开音频输出失败: {}", log_ctx(), e);
+                        return false;
+                    }
+                }
+            }
+        };
+        output.set_volume(self.output_volume());
+        self.audio_output = Some(output);
+        true
+    }
+
+    /// 恢复挂起的会话（position_ms 为 None 时从挂起位置继续）
+    pub fn resume(&mut self, position_ms: Option<i64>, play: bool) {
+        if !self.suspended.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        if !self.rebind_audio_output() {
+            // 音频解码器按原来的格式输出：以挂起位置重建播放管线（按设备的实际格式重新创建音频解码器）
+            warn!("{} 🔈 音频设备不支持本会话的音频格式，重建播放管线", log_ctx());
+            self.poster_frame = None;
+            if let Some(position_ms) = position_ms {
+                self.clock.set_time(position_ms);
+            }
+            if let Err(e) = self.rebuild_for_track_switch() {
+                error!("{} ❌ 重建播放管线失败: {}", log_ctx(), e);
+            }
+            if play {
+                if let Err(e) = self.play() {
+                    error!("{} ❌ 恢复播放失败: {}", log_ctx(), e);
+                }
+            }
+            return;
+        }
+        if let Some(ref mut output) = self.audio_output {
+            if let Err(e) = output.start() {
+                error!("{} ❌ 恢复音频输出失败: {}", log_ctx(), e);
+            }
+        }
+
+        // 解码线程挂起期间丢弃了队列，从挂起位置重新定位
+        let poster = self.poster_frame.take();
+        let target = position_ms.unwrap_or_else(|| self.loop_control.wrap(self.clock.now()).1);
+        self.seek(target, SeekMode::Accurate);
+        if position_ms.is_none() {
+            *self.video_lookahead.lock().unwrap() = poster;
+        }
+        info!("{} ▶️  会话恢复于 {}ms", log_ctx(), target);
+
+        if play {
+            if let Err(e) = self.play() {
+                error!("{} ❌ 恢复播放失败: {}", log_ctx(), e);
+            }
+        }
+    }
+
+    /// ==================== 音画同步核心: Seek 跳转 ====================
+    /// 
+    /// # 功能说明
+    /// 跳转到指定播放位置，确保音画同步
     /// 
     /// # 音画同步机制
     /// 
@@ -597,15 +898,14 @@ impl PlaybackManager {
     /// 
     /// ## Seek 步骤（7步流程）
     /// 
-    /// ### 1. 设置 seek 标记
-    /// - 通知音视频解码线程跳过不合适的旧帧
-    /// - 音频阈值：目标 ±500ms（音频帧密集）
-    /// - 视频阈值：目标 ±1000ms（视频帧稀疏，24fps ≈ 42ms/帧）
-    /// - 附带时间戳用于超时检测（2秒后强制清除，防止卡住）
+    /// ### 1. 设置 seek 请求
+    /// - 精确 seek：解码线程丢弃目标之前的帧，音频裁掉第一帧中目标之前的采样（见 seek_filter）
+    /// - 快速 seek：从目标之前的关键帧开始送出
+    /// - 附带时间戳用于超时检测（2秒后不再筛选，防止卡住）
     /// 
     /// ### 2. 重置首次音频帧标志
-    /// - 确保音频解码线程将下一个有效帧视为"新的开始"
-    /// - 但不会覆盖 seek 设置的时钟（时钟已在步骤5预设）
+    /// - 音频解码线程以 seek 后送出的第一个音频帧校准时钟
+    /// - 精确 seek 时该帧正好从目标位置开始，与步骤5预设的时钟一致
     /// 
     /// ### 3. 清空音频输出缓冲区
     /// - 立即停止播放旧音频，避免"拖尾"现象
@@ -627,21 +927,25 @@ impl PlaybackManager {
     /// - 发送 seek 命令，从文件新位置开始读取
     /// - 使用阻塞发送（send），确保命令不会丢失
     /// - 解封装线程会合并多个 seek 命令，只执行最后一个
-    pub fn seek(&self, position_ms: i64) {
-        info!("{} 🎯 Seek 到: {} ms", log_ctx(), position_ms);
+    pub fn seek(&self, position_ms: i64, mode: SeekMode) {
+        if self.still_image {
+            return;
+        }
+        info!("{} 🎯 Seek 到: {} ms（{:?}）", log_ctx(), position_ms, mode);
+        self.seek_count.fetch_add(1, Ordering::SeqCst);
         
-        // ========== 步骤1: 设置 seek 标记 ==========
-        // 让音视频解码线程知道需要跳过不合适的旧帧
-        // 附带时间戳，用于2秒超时检测（防止卡在 seek 状态）
+        // ========== 步骤1: 设置 seek 请求 ==========
+        // 音视频解码线程按请求筛选帧（精确 seek 丢弃目标之前的帧，快速 seek 从关键帧开始）
+        // 附带时间戳，用于超时检测（防止卡在 seek 状态）
         {
             let mut seek_pos = self.seek_position.lock().unwrap();
-            *seek_pos = Some((position_ms, Instant::now()));
+            *seek_pos = Some(SeekRequest::new(position_ms, mode));
         }
         
-        // ========== 步骤2: 重置首次音频帧标志 ==========
-        // 让音频解码线程将下一个有效帧视为"新的开始"
-        // 注意：不会覆盖步骤5预设的时钟值
-        self.is_first_audio_frame.store(true, Ordering::SeqCst);
+        // ========== 步骤2: 重置首帧时钟标志 ==========
+        // 让主时钟流（有音频输出时为音频，否则为视频）的解码线程将下一个有效帧视为"新的开始"
+        // 注意：不会覆盖步骤6预设的时钟值
+        self.awaiting_clock_frame.store(true, Ordering::SeqCst);
         
         // ========== 步骤3: 清空音频输出缓冲区 ==========
         // 立即停止播放旧音频，避免"拖尾"
@@ -657,20 +961,12 @@ impl PlaybackManager {
         
         // ========== 步骤5: 清空所有帧队列 ==========
         // 丢弃所有已解码但未消费的旧帧（关键：seek后必须立即清空，避免显示旧帧）
-        let mut video_count = 0;
-        while self.video_frame_queue.pop().is_some() {
+        let mut video_count = self.video_frame_queue.clear();
+        if self.video_lookahead.lock().unwrap().take().is_some() {
             video_count += 1;
         }
-        
-        let mut audio_count = 0;
-        while self.audio_frame_queue.pop().is_some() {
-            audio_count += 1;
-        }
-
-        let mut subtitle_count = 0;
-        while self.subtitle_frame_queue.pop().is_some() {
-            subtitle_count += 1;
-        }
+        let audio_count = self.audio_frame_queue.clear();
+        let subtitle_count = self.subtitle_frame_queue.clear();
         
         if video_count > 0 || audio_count > 0 || subtitle_count > 0 {
             info!("{} 🧹 Seek 清空帧队列: {} 视频帧, {} 音频帧, {} 字幕帧", log_ctx(), video_count, audio_count, subtitle_count);
@@ -681,63 +977,76 @@ impl PlaybackManager {
         // 实际时钟会在第一个音频帧到达时微调确认
         self.clock.set_time(position_ms);
         
+        // 旧位置的已呈现帧不再代表画面进度，等待新帧上报
+        *self.presented_frame.lock().unwrap() = None;
+        
         // ========== 步骤7: 更新播放状态 ==========
         // 记录新位置（供日志、统计使用）
+        // 暂停状态下：视频解码线程解码到目标位置后送出一帧，界面立即显示（保持暂停）
         {
             let mut state = self.state.lock().unwrap();
             state.position = position_ms;
+            // DemuxerThread 读到末尾后仍在等待命令：播放完毕后 seek 回到暂停状态
+            if state.state == PlaybackState::Ended && self.demuxer_thread_handle.is_some() {
+                state.state = PlaybackState::Paused;
+            }
+            if state.state == PlaybackState::Paused {
+                self.paused_seek.request(position_ms);
+                debug!("{} ⏸️ 暂停状态下 seek，等待目标位置的帧: {}ms", log_ctx(), position_ms);
+            } else {
+                self.paused_seek.cancel();
+            }
         }
         
         // ========== 步骤8: 通知解封装线程执行文件级 seek ==========
-        // 分两种情况：
-        // 1. DemuxerThread 模式：直接调用 DemuxerThread 的 seek 方法，并立即清空包队列
-        // 2. 旧架构模式：通过 seek_tx channel 发送命令
+        // 包通道里的旧包由解码线程丢弃（DemuxerThread 的 seek 栅栏），直到解封装线程执行完 seek
         if let Some(ref demuxer_thread) = self.demuxer_thread_handle {
-            // DemuxerThread 模式
-            // 注意：Receiver 不能直接在主线程中清空，因为它在解码线程中使用
-            // Seek 时，解码线程会继续接收包，但会在解码时丢弃旧的包
-            // 清空操作应该在解码线程中处理，或者在 demuxer 线程 seek 后自动清空
-            // 这里我们只发送 seek 命令
-            
+            self.seek_status.begin(position_ms);
             if let Err(e) = demuxer_thread.seek(position_ms) {
                 error!("{} ❌ 发送 seek 命令到 DemuxerThread 失败: {}", log_ctx(), e);
+                self.seek_status.finish(position_ms, SeekOutcome::Failed(e.to_string()));
             } else {
-                info!("{} ✅ Seek 命令已发送到 DemuxerThread: {}ms（队列清空由 demuxer 线程处理）", log_ctx(), position_ms);
-            }
-        } else if let Some(ref tx) = self.seek_tx {
-            // 旧架构模式：通过 channel 发送
-            if let Err(e) = tx.send(position_ms) {
-                error!("{} ❌ 发送 seek 命令失败: {}", log_ctx(), e);
-            } else {
-                debug!("{} ✓ Seek 命令已发送到 demuxer 线程", log_ctx());
+                // DemuxerThread 读到末尾后仍在等待命令，seek 后会继续读包（再次读到末尾时重新通知）
+                let mut demux_end = self.demux_end.lock().unwrap();
+                if *demux_end == Some(DemuxEnd::Eof) {
+                    *demux_end = None;
+                }
+                info!("{} ✅ Seek 命令已发送到 DemuxerThread: {}ms", log_ctx(), position_ms);
             }
         } else {
-            warn!("{} ⚠️  Seek 命令无法发送：既没有 DemuxerThread 也没有 seek_tx", log_ctx());
+            warn!("{} ⚠️  Seek 命令无法发送：没有运行中的 DemuxerThread", log_ctx());
         }
         
+        // 暂停中休眠的线程处理 seek、解码出新位置的帧后重新休眠
+        self.pause_gate.wake();
+        
         info!("{} ✅ Seek 准备完成: {}ms", log_ctx(), position_ms);
     }
 
     /// 停止播放
     pub fn stop(&mut self) {
         info!("{} ⏹️  停止播放", log_ctx());
+        self.save_position();
+        // 取消下一项的预读（打开该项时已先取出）
+        self.prefetch = None;
         self.running.store(false, Ordering::SeqCst);
+        // 唤醒休眠和等待队列的线程使其看到退出标志；新打开的文件从未暂停状态开始
+        self.pause_gate.set_paused(false);
+        self.pause_gate.wake();
+        self.video_frame_queue.wake();
+        self.audio_frame_queue.wake();
 
         // 等待线程结束（对于打开新文件时正确重置状态很重要）
         // 线程应该在收到 running=false 后很快退出，因为它们在循环中检查这个标志
         
-        // 停止 DemuxerThread（新架构）
+        // 停止 DemuxerThread（关闭包通道，阻塞在 recv() 的解码线程随之退出）
         if let Some(mut demuxer_thread) = self.demuxer_thread_handle.take() {
             info!("{} ⏹️  停止 DemuxerThread", log_ctx());
             demuxer_thread.stop();
             info!("{} ✅ DemuxerThread 已停止", log_ctx());
         }
-        
-        // 等待解封装线程结束
-        if let Some(thread) = self.demux_thread.take() {
-            let _ = thread.join();
-            info!("{} ✅ 解封装线程已结束", log_ctx());
-        }
+        self.demux_end_rx = None;
+        *self.demux_end.lock().unwrap() = None;
         
         // 等待视频解码线程结束
         if let Some(thread) = self.video_decode_thread.take() {
@@ -757,23 +1066,35 @@ impl PlaybackManager {
             info!("{} ✅ 字幕解码线程已结束", log_ctx());
         }
         
-        // 停止并清理音频输出
+        // 关闭悬停预览线程（下次悬停时为新文件重新启动）
+        let hover_preview = self.hover_preview.lock().unwrap().take();
+        if let Some(preview) = hover_preview {
+            drop(preview);
+            info!("{} ✅ 悬停预览线程已结束", log_ctx());
+        }
+        
+        // 释放空闲的帧缓冲区（队列和画面中仍在使用的帧释放时不再回到池中）
+        self.frame_pool.clear();
+
+        // 停止音频输出（保留给下一个打开的文件复用）
         if let Some(mut output) = self.audio_output.take() {
             info!("{} 🔊 停止音频输出", log_ctx());
             output.stop();
+            output.clear_buffer();
+            self.idle_audio_output = Some(output);
         }
+        self.pipeline_audio_config = None;
 
         // 清空帧队列
-        let mut audio_count = 0;
-        while self.audio_frame_queue.pop().is_some() {
-            audio_count += 1;
-        }
+        let audio_count = self.audio_frame_queue.clear();
         if audio_count > 0 {
             info!("{} 🗑️  清空音频帧队列: {} 帧", log_ctx(), audio_count);
         }
+        self.tempo.reset();
+        self.loudness.reset();
         
-        let mut video_count = 0;
-        while self.video_frame_queue.pop().is_some() {
+        let mut video_count = self.video_frame_queue.clear();
+        if self.video_lookahead.lock().unwrap().take().is_some() {
             video_count += 1;
         }
         if video_count > 0 {
@@ -781,10 +1102,7 @@ impl PlaybackManager {
         }
 
         // 清空字幕帧队列
-        let mut subtitle_count = 0;
-        while self.subtitle_frame_queue.pop().is_some() {
-            subtitle_count += 1;
-        }
+        let subtitle_count = self.subtitle_frame_queue.clear();
         if subtitle_count > 0 {
             info!("{} 🗑️  清空字幕帧队列: {} 帧", log_ctx(), subtitle_count);
         }
@@ -802,11 +1120,23 @@ impl PlaybackManager {
         // 重置播放时钟（重要：打开新文件前必须重置时钟）
         self.clock.set_time(0);
         
-        // 重置 seek 通道（清理旧通道）
-        self.seek_tx = None;
+        // 清除封面/静止图像
+        self.cover_art = None;
+        self.still_image = false;
+        
+        // 清除已呈现帧记录
+        *self.presented_frame.lock().unwrap() = None;
+        *self.last_presented.lock().unwrap() = None;
+        
+        // 解除挂起（新文件的线程需要正常工作）
+        self.suspended.store(false, Ordering::SeqCst);
+        self.poster_frame = None;
+        
+        self.seek_status.clear();
         
         // 重置 flush 标志
         self.need_flush_decoders.store(false, Ordering::SeqCst);
+        self.paused_seek.cancel();
         
         // 重置状态
         let mut state = self.state.lock().unwrap();
@@ -819,13 +1149,155 @@ impl PlaybackManager {
     /// 设置音量
     pub fn set_volume(&self, volume: f32) {
         let mut state = self.state.lock().unwrap();
-        state.volume = volume.clamp(0.0, 1.0);
+        state.volume = volume.clamp(0.0, MAX_VOLUME);
+    }
+
+    /// 切换静音，返回切换后是否静音（静音期间音量照常记录，取消静音时恢复；切换文件后保持）
+    pub fn toggle_mute(&self) -> bool {
+        let muted = !self.muted.fetch_xor(true, Ordering::SeqCst);
+        info!("{} {} {}", log_ctx(), if muted { "🔇" } else { "🔊" }, if muted { "静音" } else { "取消静音" });
+        // 立即作用到输出（音频回调每次读取音量），不等待下一次 update_audio
+        if let Some(ref output) = self.audio_output {
+            output.set_volume(self.output_volume());
+        }
+        muted
+    }
+
+    /// 设置静音（启动时恢复上次的状态）
+    pub fn set_muted(&self, muted: bool) {
+        if muted != self.is_muted() {
+            self.toggle_mute();
+        }
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted.load(Ordering::SeqCst)
+    }
+
+    /// 实际输出的音量（静音时为 0）
+    fn output_volume(&self) -> f32 {
+        if self.is_muted() {
+            0.0
+        } else {
+            self.state.lock().unwrap().volume
+        }
+    }
+
+    /// 设置播放速度（0.5x - 2.0x，音频变速不变调，时钟按同一速度推进）
+    pub fn set_speed(&mut self, speed: f32) {
+        let speed = audio_tempo::clamp_speed(speed);
+        if speed == self.tempo.speed() {
+            return;
+        }
+        info!("{} ⏩ 播放速度: {}x", log_ctx(), speed);
+        self.clock.set_rate(speed as f64);
+        self.tempo.set_speed(speed);
+        // 输出缓冲区中是旧速度的样本，丢弃后按新速度重新填充
+        if let Some(ref output) = self.audio_output {
+            output.clear_buffer();
+        }
+    }
+
+    /// 当前播放速度
+    pub fn speed(&self) -> f32 {
+        self.tempo.speed()
+    }
+
+    /// 开启/关闭响度均衡
+    pub fn set_loudness_normalization(&mut self, enabled: bool) {
+        if enabled != self.loudness.is_enabled() {
+            info!("{} 🔊 响度均衡: {}", log_ctx(), if enabled { "开启" } else { "关闭" });
+            self.loudness.set_enabled(enabled);
+        }
+    }
+
+    /// 可用的音频输出设备名称
+    pub fn audio_devices() -> Vec<String> {
+        AudioOutput::list_devices()
+    }
+
+    /// 选择音频输出设备（None 跟随系统默认设备），正在播放时立即切换，视频不中断
+    pub fn set_audio_device(&mut self, name: Option<String>) -> Result<()> {
+        if name == self.audio_device {
+            return Ok(());
+        }
+        info!("{} 🔈 音频输出设备: {}", log_ctx(), name.as_deref().unwrap_or("系统默认"));
+        self.audio_device = name;
+        // 空闲的输出还开在旧设备上
+        self.idle_audio_output = None;
+        self.switch_audio_output()
+    }
+
+    /// 在当前选择的设备上重建音频输出（未播放的样本转移到新输出，保持音画同步）；
+    /// 新设备不支持当前的输出格式时以当前位置重建播放管线（音频解码器按新格式重采样）
+    fn switch_audio_output(&mut self) -> Result<()> {
+        let Some(old) = self.audio_output.as_ref() else {
+            return Ok(());
+        };
+        let (sample_rate, channels) = old.get_config();
+        let mut output = AudioOutput::new_with_device(self.audio_device.as_deref(), sample_rate, channels)?;
+        if output.get_config() != (sample_rate, channels) {
+            info!("{} 🔈 新设备不支持 {} Hz / {} 声道，重建播放管线", log_ctx(), sample_rate, channels);
+            self.audio_output = None;
+            return self.rebuild_for_track_switch();
+        }
+        output.write_samples(&old.take_buffered());
+        output.set_volume(self.output_volume());
+        if self.is_playing() {
+            output.start()?;
+        }
+        info!("{} ✅ 音频输出已切换到 {}", log_ctx(), output.device_name());
+        self.audio_output = Some(output);
+        Ok(())
+    }
+
+    /// 检查音频设备是否已断开或系统默认设备已改变（每 2 秒一次），需要时切换输出
+    fn check_audio_device(&mut self) {
+        if self.audio_device_checked.elapsed() < AUDIO_DEVICE_POLL {
+            return;
+        }
+        self.audio_device_checked = Instant::now();
+        let Some(output) = self.audio_output.as_ref() else {
+            return;
+        };
+        let current = output.device_name();
+        let reason = if output.has_failed() {
+            "设备已断开"
+        } else if let Some(chosen) = self.audio_device.as_ref() {
+            // 选择的设备断开后回退到了默认设备：重新连接时切换回来
+            if *chosen == current || !AudioOutput::list_devices().contains(chosen) {
+                return;
+            }
+            "选择的设备已重新连接"
+        } else if AudioOutput::default_device_name().is_some_and(|name| name != current) {
+            "系统默认设备已改变"
+        } else {
+            return;
+        };
+        warn!("{} 🔈 {}，切换音频输出", log_ctx(), reason);
+        if let Err(e) = self.switch_audio_output() {
+            error!("{} ❌ 切换音频输出失败: {}", log_ctx(), e);
+        }
+    }
+
+    /// 记住当前文件的音量（重新打开同一文件时恢复）
+    pub fn remember_volume(&mut self, volume: f32) {
+        let Some(path) = self.current_file_path.lock().unwrap().clone() else {
+            return;
+        };
+        self.file_memory.entry(path).or_default().volume = Some(volume.clamp(0.0, MAX_VOLUME));
+    }
+
+    /// 当前文件记忆的音量（没有调整过时返回 None）
+    pub fn remembered_volume(&self) -> Option<f32> {
+        let path = self.current_file_path.lock().unwrap().clone()?;
+        self.file_memory.get(&path).and_then(|memory| memory.volume)
     }
 
     /// 获取当前状态
     pub fn get_state(&self) -> PlayerState {
         let mut state = self.state.lock().unwrap();
-        state.position = self.clock.now();
+        state.position = self.loop_control.wrap(self.clock.now()).1;
         state.clone()
     }
 
@@ -836,6 +1308,28 @@ impl PlaybackManager {
     /// - **仅在播放状态下更新音频**：暂停时不从队列取帧
     /// - 避免暂停后音频继续播放的问题
     pub fn update_audio(&mut self) {
+        // ========== 音频解码器已失效 ==========
+        // 释放音频输出，时钟按墙上时间继续推进（视频和字幕照常播放）
+        if self.audio_failure.has_failed() {
+            if let Some(output) = self.audio_output.take() {
+                output.clear_buffer();
+                self.pipeline_audio_config = None;
+                self.clock.set_drift_ppm(0.0);
+                info!("{} 🔇 音频解码器已失效，释放音频输出（视频和字幕继续播放）", log_ctx());
+            }
+            self.audio_frame_queue.clear();
+            self.audio_sync.reset();
+            return;
+        }
+
+        // ========== 音频设备变化（拔出耳机、切换系统默认设备） ==========
+        self.check_audio_device();
+        
+        // ========== 音频设备时钟偏差补偿 ==========
+        // 设备暂停时也在消耗（静音）样本，测量不受播放状态影响
+        let drift_ppm = self.audio_output.as_mut().map_or(0.0, |output| output.measure_clock_skew());
+        self.clock.set_drift_ppm(drift_ppm);
+        
         // ========== 检查播放状态 ==========
         // 仅在播放状态下更新音频，暂停/停止时不处理
         let is_playing = {
@@ -844,17 +1338,33 @@ impl PlaybackManager {
         };
         
         if !is_playing {
+            // 暂停时输出缓冲区已清空，继续播放后重新测量
+            self.audio_sync.reset();
             return;  // 暂停或停止状态，不更新音频
         }
         
         // ========== 从队列取出音频帧并写入输出 ==========
+        let seeks = self.seek_count.load(Ordering::SeqCst);
+        if seeks != self.tempo_seeks {
+            self.tempo_seeks = seeks;
+            self.tempo.reset();
+            self.audio_sync.reset();
+        }
+        let speed = self.tempo.speed() as f64;
         if let Some(ref mut output) = self.audio_output {
-            // 处理所有可用的音频帧
+            // 处理所有可用的音频帧（非 1.0 倍速时先变速，缓存不足一个片段时暂不输出；开启响度均衡时调整增益）
             while let Some(frame) = self.audio_frame_queue.pop() {
-                output.write_frame(&frame);
+                if let Some(mut frame) = self.tempo.process(frame) {
+                    self.loudness.process(&mut frame);
+                    output.write_frame(&frame);
+                    // 变速后的帧按输出时长计，换算回媒体时间
+                    let channels = frame.channels.max(1) as f64;
+                    let frame_ms = frame.data.len() as f64 / channels * 1000.0 / frame.sample_rate.max(1) as f64;
+                    self.audio_sync.record_written(frame.pts + (frame_ms * speed).round() as i64);
+                }
                 
-                // 更新音量
-                let vol = self.state.lock().unwrap().volume;
+                // 更新音量（静音时为 0）
+                let vol = if self.muted.load(Ordering::SeqCst) { 0.0 } else { self.state.lock().unwrap().volume };
                 output.set_volume(vol);
                 
                 // 限制缓冲区大小，避免延迟过大
@@ -862,15 +1372,34 @@ impl PlaybackManager {
                     break;
                 }
             }
+
+            // ========== 按实际播放的音频校正时钟 ==========
+            // 设备开始消耗样本之前不校正（设备一直不回调时画面不会被卡住）
+            if output.played_duration_ms() > 0.0 {
+                let clock_ms = self.clock.now();
+                if let Some(audible_ms) = self.audio_sync.update(clock_ms, output.queued_duration_ms(), speed) {
+                    debug!("{} 🎚️ 音画偏差 {}ms，时钟对齐到正在播放的音频: {}ms", log_ctx(), clock_ms - audible_ms, audible_ms);
+                    self.clock.set_time(audible_ms);
+                }
+            }
         }
     }
 
-    /// 获取当前视频帧
-    /// 返回最新的视频帧用于渲染
-    pub fn get_video_frame(&self) -> Option<VideoFrame> {
-        self.video_frame_queue.pop()
+    /// 最近一次测量的音画偏差（毫秒，正值表示画面领先声音；没有在播放的音频时为 None）
+    pub fn av_offset_ms(&self) -> Option<i64> {
+        self.audio_sync.offset_ms()
     }
-    
+
+    /// 帧缓冲区分配统计（累计）
+    pub fn frame_pool_stats(&self) -> FramePoolStats {
+        self.frame_pool.stats()
+    }
+
+    /// 音频设备时钟偏差（没有音频输出时为 None）
+    pub fn audio_clock_skew(&self) -> Option<AudioClockSkew> {
+        self.audio_output.as_ref().map(AudioOutput::clock_skew)
+    }
+
     /// 获取媒体信息
     pub fn get_media_info(&self) -> Option<MediaInfo> {
         let state = self.state.lock().unwrap();
@@ -881,763 +1410,1446 @@ impl PlaybackManager {
     /// 注意：这个方法不做时间同步，只是简单地取出队列中的第一个帧
     /// 同时会清理队列中过期的帧
     pub fn get_current_frame(&self) -> Option<VideoFrame> {
+        // 优先返回之前预取的帧（保持顺序）
+        if let Some(frame) = self.video_lookahead.lock().unwrap().take() {
+            return Some(frame);
+        }
+        
         // 如果队列过大，先清理过期帧
         let queue_len = self.video_frame_queue.len();
         if queue_len > 80 {
-            let clock = self.clock.clone();
-            let current_time = clock.now();
+            let current_time = self.clock.now();
             const DROP_THRESHOLD_MS: i64 = 1000; // 丢弃1秒前的帧
             const MAX_KEEP: usize = 50; // 最多保留50帧
             
-            let mut kept_frames = Vec::new();
-            let mut processed = 0;
-            const MAX_PROCESS: usize = 300; // 限制处理数量
-            
-            // 清理过期帧，保留最新的帧
-            while processed < MAX_PROCESS {
-                if let Some(frame) = self.video_frame_queue.pop() {
-                    processed += 1;
-                    // 只保留未过期且最近的帧
-                    if frame.pts >= current_time - DROP_THRESHOLD_MS {
-                        if kept_frames.len() < MAX_KEEP {
-                            kept_frames.push(frame);
-                        }
-                        // 超出保留数量的帧也丢弃
-                    }
-                    // 过期帧直接丢弃
-                } else {
-                    break;
-                }
-            }
-            
-            // 按PTS排序并放回（最新的在前）
-            kept_frames.sort_by_key(|f| f.pts);
-            for frame in kept_frames {
-                self.video_frame_queue.push(frame);
-            }
+            // 在队列锁内清理（解码线程这期间不会插入新帧，保留的帧不会排到新帧后面）
+            self.video_frame_queue.with_items(|frames| {
+                frames.retain(|frame| frame.pts >= current_time - DROP_THRESHOLD_MS);
+                frames.make_contiguous().sort_by_key(|f| f.pts);
+                frames.truncate(MAX_KEEP);
+            });
         }
         
         self.video_frame_queue.pop()
     }
 
-    /// 获取当前字幕（根据播放时间）
+    /// 取出不晚于当前时间的最新一帧，中间帧直接合并丢弃（高帧率源使用）
     /// 
-    /// 算法说明：
-    /// 1. 遍历字幕队列，查找所有在当前时间范围内的字幕
-    /// 2. 选择时间戳最新的字幕（用于处理重叠字幕）
-    /// 3. 保留未到时间和未使用的字幕回队列
-    /// 4. 丢弃过期字幕以避免内存泄漏
-    pub fn get_current_subtitle(&self, current_time_ms: i64) -> Option<SubtitleFrame> {
-        let mut best_subtitle: Option<SubtitleFrame> = None;
-        let mut pending_frames = Vec::new();
-        let mut checked_count = 0;
-        const MAX_CHECK_COUNT: usize = 100; // 限制检查数量，防止无限循环
-
-        // 遍历队列查找合适的字幕
-        while let Some(frame) = self.subtitle_frame_queue.pop() {
-            checked_count += 1;
+    /// 单次调用即可追上时钟，避免 UI 每次刷新只取一帧导致永久落后
+    /// 
+    /// 返回：(最新帧, 被合并跳过的帧数)
+    pub fn take_newest_frame_until(&self, current_time_ms: i64) -> (Option<VideoFrame>, usize) {
+        const MAX_POP_PER_CALL: usize = 64; // 限制单次处理数量，避免阻塞 UI
+        
+        let mut lookahead = self.video_lookahead.lock().unwrap();
+        let mut newest: Option<VideoFrame> = None;
+        let mut merged = 0;
+        
+        for _ in 0..MAX_POP_PER_CALL {
+            let frame = match lookahead.take().or_else(|| self.video_frame_queue.pop()) {
+                Some(frame) => frame,
+                None => break,
+            };
             
-            // 防止无限循环（队列可能很大）
-            if checked_count > MAX_CHECK_COUNT {
-                // 将剩余帧放回队列
-                pending_frames.push(frame);
+            if frame.pts > current_time_ms {
+                // 未到显示时间，留待下次
+                *lookahead = Some(frame);
                 break;
             }
             
-            if current_time_ms >= frame.pts && current_time_ms < frame.end_pts {
-                // 找到匹配的字幕（在当前时间范围内）
-                // 选择时间戳最新的字幕（处理重叠字幕的情况）
-                if best_subtitle.as_ref().map(|b| frame.pts > b.pts).unwrap_or(true) {
-                    // 如果之前有候选字幕，将其放回队列
-                    if let Some(old) = best_subtitle.take() {
-                        pending_frames.push(old);
-                    }
-                    best_subtitle = Some(frame.clone());
-                    // 当前帧也要放回队列，因为它可能还需要继续显示
-                    pending_frames.push(frame);
-                } else {
-                    // 这个字幕不如当前最佳字幕，放回队列
-                    pending_frames.push(frame);
+            if newest.replace(frame).is_some() {
+                merged += 1;
+            }
+        }
+        
+        (newest, merged)
+    }
+
+    /// 取出暂停状态下 seek 的目标帧（不比较时钟，取出后时钟对齐到该帧 PTS）
+    pub fn take_paused_seek_frame(&self) -> Option<VideoFrame> {
+        let frame = self.paused_seek.take_frame()?;
+        self.clock.set_time(frame.pts);
+        info!("{} ⏸️ 暂停状态下 seek 完成，显示目标帧 PTS={}ms", log_ctx(), frame.pts);
+        Some(frame)
+    }
+
+    /// 是否在等待暂停状态下 seek 的目标帧（等待期间界面不从帧队列取帧）
+    pub fn is_paused_seek_pending(&self) -> bool {
+        self.paused_seek.is_active()
+    }
+
+    /// 前进一帧（播放中先暂停）：取帧队列中当前画面之后的下一帧交给界面，时钟对齐到该帧（字幕随之更新）
+    ///
+    /// 暂停时时钟不前进，按时钟取帧的逻辑不会更新画面，因此和暂停状态下的 seek 一样由界面直接显示。
+    /// 帧队列为空时按帧时长 seek 到下一帧。返回是否开始步进
+    pub fn step_frame_forward(&self) -> bool {
+        let Some(current_pts) = self.prepare_frame_step() else {
+            return false;
+        };
+        loop {
+            let frame = self.video_lookahead.lock().unwrap().take().or_else(|| self.video_frame_queue.pop());
+            match frame {
+                // 暂停前已解码、早于当前画面的帧
+                Some(frame) if frame.pts <= current_pts => continue,
+                Some(frame) => {
+                    debug!("{} ⏭️ 前进一帧: {}ms -> {}ms", log_ctx(), current_pts, frame.pts);
+                    self.paused_seek.present(frame);
+                    return true;
                 }
-            } else if current_time_ms < frame.pts {
-                // 未到时间的字幕，保留
-                pending_frames.push(frame);
-            } else {
-                // 过期字幕（current_time_ms >= frame.end_pts）直接丢弃，避免内存泄漏
-                // 不放入 pending_frames，让它被回收
-            }
-        }
-
-        // 将未使用的字幕放回队列
-        // 注意：如果找到了最佳字幕，它也在 pending_frames 中，会被放回队列
-        // 这样可以支持字幕在时间范围内持续显示
-        for frame in pending_frames {
-            // 如果是最佳字幕，或者不是最佳字幕且未过期，则放回队列
-            let should_keep = best_subtitle.as_ref()
-                .map(|best| {
-                    // 如果是最佳字幕本身，保留
-                    frame.pts == best.pts
-                    // 或者不是最佳字幕，但是未到时间的字幕
-                    || (current_time_ms < frame.pts)
-                })
-                .unwrap_or(true);
-            
-            if should_keep {
-                self.subtitle_frame_queue.push(frame);
+                None => break,
             }
         }
+        let target = self.loop_position(current_pts) + self.frame_duration_ms();
+        debug!("{} ⏭️ 帧队列为空，seek 到下一帧: {}ms", log_ctx(), target);
+        self.seek(target, SeekMode::Accurate);
+        true
+    }
+
+    /// 后退一帧（播放中先暂停）：seek 到上一帧之前，解码线程向前解码到上一帧后交给界面。返回是否开始步进
+    pub fn step_frame_backward(&self) -> bool {
+        let Some(current_pts) = self.prepare_frame_step() else {
+            return false;
+        };
+        let current_pts = self.loop_position(current_pts);
+        if current_pts <= 0 {
+            return false;
+        }
+        let target = paused_seek::step_back_target(current_pts, self.frame_duration_ms());
+        debug!("{} ⏮️ 后退一帧: {}ms，seek 到 {}ms", log_ctx(), current_pts, target);
+        self.seek(target, SeekMode::Accurate);
+        true
+    }
 
-        // 如果没有找到内嵌字幕，尝试外部字幕
-        if best_subtitle.is_none() {
-            best_subtitle = self.get_external_subtitle(current_time_ms);
+    /// 步进前的准备：播放中先暂停；不能步进（没有视频、单张图像、上一步尚未显示）时返回 None，
+    /// 否则返回当前画面的 PTS（内部时间轴）
+    fn prepare_frame_step(&self) -> Option<i64> {
+        if self.still_image || self.cover_art.is_some() || self.paused_seek.is_active() {
+            return None;
+        }
+        match self.get_state().state {
+            PlaybackState::Playing => self.pause(),
+            PlaybackState::Paused => {}
+            _ => return None,
         }
+        Some(self.get_presented_frame().map_or_else(|| self.clock.now(), |frame| frame.pts))
+    }
 
-        best_subtitle
+    /// 当前视频的帧时长（毫秒）
+    fn frame_duration_ms(&self) -> i64 {
+        let fps = self.state.lock().unwrap().media_info.as_ref().map_or(0.0, |info| info.fps);
+        paused_seek::frame_duration_ms(fps)
     }
 
-    /// 加载外部字幕文件
-    fn load_external_subtitles(&self, video_path: &str) {
-        info!("🔍 查找外部字幕文件: {}", video_path);
+    /// 上报 UI 实际呈现的视频帧（每次更新纹理后调用）
+    pub fn notify_frame_presented(&self, frame: &VideoFrame) {
+        let now = Instant::now();
+        let pts = frame.pts;
+        *self.last_presented.lock().unwrap() = Some(frame.clone());
         
-        // 查找同目录下的字幕文件
-        let subtitle_files = ExternalSubtitleParser::find_subtitle_files(video_path);
-        
-        if subtitle_files.is_empty() {
-            info!("未找到外部字幕文件");
-            return;
+        // 切换文件后的首帧：记录上一文件末帧到新文件首帧的墙上时间间隔
+        if let Some((last_pts, last_at)) = self.transition_from.lock().unwrap().take() {
+            info!(
+                "{} ⏱️  切换间隔: {}ms（上一文件末帧 PTS={}ms → 新文件首帧 PTS={}ms）",
+                log_ctx(),
+                now.duration_since(last_at).as_millis(),
+                last_pts,
+                pts
+            );
         }
+        
+        let mut presented = self.presented_frame.lock().unwrap();
+        *presented = Some(PresentedFrameInfo { pts, presented_at: now });
+    }
 
-        let mut all_frames = Vec::new();
+    /// 获取最近一次呈现的视频帧信息
+    pub fn get_presented_frame(&self) -> Option<PresentedFrameInfo> {
+        *self.presented_frame.lock().unwrap()
+    }
+
+    /// 根据 Demuxer 和外部字幕文件刷新轨道列表（章节列表、关键帧索引、封面一并刷新）
+    fn update_track_lists(&mut self, demuxer: &Demuxer, external_subtitles: &[PathBuf]) {
+        self.chapters = demuxer.chapters();
+        self.keyframe_index = demuxer.shared_keyframe_index();
         
-        // 解析所有找到的字幕文件（优先级：第一个找到的）
-        for subtitle_file in subtitle_files.iter().take(1) { // 目前只加载第一个字幕文件
-            info!("📝 加载外部字幕文件: {}", subtitle_file.display());
-            
-            match ExternalSubtitleParser::parse_subtitle_file(subtitle_file) {
-                Ok(frames) => {
-                    info!("✅ 成功解析外部字幕，共 {} 条", frames.len());
-                    all_frames.extend(frames);
-                    break; // 成功加载一个就够了
+        // 只有纯音频文件才显示封面（有视频流时封面不参与显示）
+        self.cover_art = match demuxer.cover_art() {
+            Some(cover) if demuxer.video_stream_index().is_none() => match decode_cover_art(&cover) {
+                Ok(frame) => {
+                    info!("{} 🖼️ 内嵌封面: {}x{}", log_ctx(), frame.width, frame.height);
+                    Some(frame)
                 }
                 Err(e) => {
-                    error!("{} ❌ 解析外部字幕文件失败: {} - {}", log_ctx(), subtitle_file.display(), e);
+                    warn!("{} 封面解码失败: {}", log_ctx(), e);
+                    None
                 }
-            }
+            },
+            _ => None,
+        };
+        self.audio_tracks = demuxer.audio_tracks();
+        self.selected_audio_stream = demuxer.audio_stream_index();
+        self.subtitle_tracks = demuxer.subtitle_tracks();
+        self.subtitle_tracks.extend(external_subtitles.iter().map(|path| TrackInfo {
+            source: TrackSource::External(path.clone()),
+            meta: StreamMeta {
+                codec: path
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                ..Default::default()
+            },
+        }));
+        self.selected_subtitle = demuxer.subtitle_stream_index().map(TrackSource::Embedded);
+        
+        debug!(
+            "{} 轨道列表: {} 条音频, {} 条字幕, {} 个章节",
+            log_ctx(),
+            self.audio_tracks.len(),
+            self.subtitle_tracks.len(),
+            self.chapters.len()
+        );
+    }
+
+    /// 设置运行时开关（下次打开文件时生效）
+    pub fn set_runtime_flags(&mut self, flags: RuntimeFlags) {
+        self.runtime_flags = flags;
+    }
+
+    /// 解码线程崩溃时记录的文件（网络流不记录）
+    fn crash_scope_path(&self) -> Option<PathBuf> {
+        if self.is_network_source.load(Ordering::SeqCst) {
+            return None;
         }
+        self.current_file_path.lock().unwrap().as_ref().map(PathBuf::from)
+    }
 
-        // 按时间戳排序
-        all_frames.sort_by_key(|frame| frame.pts);
+    /// 是否允许使用硬件解码（全局设置开启且不在安全模式下）
+    pub fn hw_decode_allowed(&self) -> bool {
+        hw_decode_enabled() && self.runtime_flags.hw_decode
+    }
 
-        // 存储到外部字幕缓存
-        {
-            let mut external_frames = self.external_subtitle_frames.lock().unwrap();
-            *external_frames = all_frames;
-            info!("{} 📝 外部字幕加载完成，共 {} 条字幕", log_ctx(), external_frames.len());
+    /// 设置是否在字幕关闭时自动选择强制字幕（下次打开文件时生效）
+    pub fn set_auto_forced_subtitles(&mut self, enabled: bool) {
+        self.auto_forced_subtitles = enabled;
+    }
+
+    /// 设置没有轨道记忆的文件是否默认显示字幕（下次打开文件时生效）
+    pub fn set_subtitles_by_default(&mut self, enabled: bool) {
+        self.subtitles_by_default = enabled;
+    }
+
+    /// 设置打开新文件时的字幕延迟（毫秒，超出范围时夹紧）
+    pub fn set_default_subtitle_offset_ms(&mut self, offset_ms: i64) {
+        self.default_subtitle_offset_ms = offset_ms.clamp(-SUBTITLE_OFFSET_LIMIT_MS, SUBTITLE_OFFSET_LIMIT_MS);
+    }
+
+    /// 开启/关闭读取 .nfo 侧车文件中的标题
+    pub fn set_read_sidecar_titles(&mut self, enabled: bool) {
+        if enabled != self.read_sidecar_titles {
+            self.read_sidecar_titles = enabled;
+            *self.media_title.lock().unwrap() = None;
         }
     }
 
-    /// 从外部字幕中获取当前时间应显示的字幕
-    fn get_external_subtitle(&self, current_time_ms: i64) -> Option<SubtitleFrame> {
-        let external_frames = self.external_subtitle_frames.lock().unwrap();
-        
-        // 查找当前时间范围内的字幕
-        for frame in external_frames.iter() {
-            if current_time_ms >= frame.pts && current_time_ms < frame.end_pts {
-                return Some(frame.clone());
-            }
-            
-            // 如果字幕还没到时间，后面的也不会到时间（已排序）
-            if current_time_ms < frame.pts {
-                break;
+    /// 设置是否忽略播放历史中的硬解失败记录（下次创建解码器时生效）
+    pub fn set_hw_decode_always_retry(&mut self, enabled: bool) {
+        self.hw_decode_always_retry = enabled;
+    }
+
+    /// 创建视频解码器（优先硬件解码，失败时回退到软件解码；播放历史中记录过硬解失败时直接使用软件解码）
+    fn create_video_decoder(&mut self, demuxer: &Demuxer) -> Result<Option<VideoDecoder>> {
+        let selected = self.video_decoder_selector()(demuxer)?;
+        Ok(self.adopt_video_decoder(demuxer.description(), selected))
+    }
+
+    /// 按当前设置选择视频解码器的函数（可在预读线程中调用；没有视频流时选出 None）
+    fn video_decoder_selector(&self) -> impl FnOnce(&Demuxer) -> Result<Option<SelectedVideoDecoder>> + Send + 'static {
+        let hw_allowed = self.hw_decode_allowed();
+        let history = self.position_history.clone().filter(|_| !self.hw_decode_always_retry);
+        let frame_pool = self.frame_pool.clone();
+        move |demuxer: &Demuxer| {
+            if demuxer.video_stream().is_none() {
+                return Ok(None);
             }
+            let remembered = history.and_then(|history| history.hw_decode_failure(&demuxer.description()));
+            let (mut decoder, fallback) = decoder_fallback::select_decoder(
+                hw_allowed,
+                remembered,
+                || VideoDecoder::from_stream(demuxer.video_stream().ok_or(PlayerError::NoVideoStream)?),
+                || VideoDecoder::from_stream_software(demuxer.video_stream().ok_or(PlayerError::NoVideoStream)?),
+            )?;
+            decoder.set_frame_pool(frame_pool);
+            Ok(Some((decoder, fallback)))
         }
-        
-        None
     }
 
-    /// 根据播放时钟获取应该显示的视频帧（音视频同步）
-    /// 返回 PTS <= 当前播放时间的最近一帧
-    /// 
-    /// 优化：限制检查数量，避免一次性处理所有帧导致内存爆炸
-    pub fn get_frame_for_time(&self, current_time_ms: i64) -> Option<VideoFrame> {
-        // 从队列中找到最接近但不超过当前时间的帧
-        let mut best_frame: Option<VideoFrame> = None;
-        let mut frames_to_keep = Vec::new();
-        let mut future_frames = Vec::new();
-        
-        // 限制检查数量，防止队列过大时内存爆炸
-        const MAX_CHECK_COUNT: usize = 200; // 最多检查200帧
-        const MAX_FUTURE_FRAMES: usize = 30; // 最多保留30个未来帧（减少）
-        let mut checked_count = 0;
-        let mut discarded_old_frames = 0;
-        
-        // 丢弃阈值：如果帧的 PTS 比当前时间早 1 秒，直接丢弃（更激进）
-        const DROP_THRESHOLD_MS: i64 = 1000;
-        
-        // 第一遍：收集帧（限制数量）
-        while checked_count < MAX_CHECK_COUNT {
-            if let Some(frame) = self.video_frame_queue.pop() {
-                checked_count += 1;
-                
-                // 丢弃过期的帧（PTS 远小于当前时间）
-                if frame.pts < current_time_ms - DROP_THRESHOLD_MS {
-                    discarded_old_frames += 1;
-                    continue; // 直接丢弃，不保留
+    /// 采用选出的视频解码器：记录硬解回退原因和播放历史中的硬解失败记录
+    fn adopt_video_decoder(&mut self, path: String, selected: Option<SelectedVideoDecoder>) -> Option<VideoDecoder> {
+        self.hw_fallback.reset();
+        let Some((decoder, fallback)) = selected else {
+            self.video_source = None;
+            return None;
+        };
+        let history = self.position_history.clone();
+
+        info!("{} 📎 视频解码器: {}", log_ctx(), decoder.info());
+        match fallback {
+            Some(reason) => {
+                info!("{} 🎞️ {}", log_ctx(), reason.label());
+                if let (true, Some(history)) = (reason.is_new(), &history) {
+                    history.set_hw_decode_failure(&path, Some(&reason.description()));
                 }
-                
-                if frame.pts <= current_time_ms {
-                    // 这个帧的时间戳合适，保留它（如果有更好的就替换）
-                    if best_frame.as_ref().map(|f| f.pts < frame.pts).unwrap_or(true) {
-                        // 丢弃之前的best_frame（如果时间戳更早）
-                        if let Some(old) = best_frame.take() {
-                            frames_to_keep.push(old);
-                        }
-                        best_frame = Some(frame);
-                    } else {
-                        // 这个帧不如best_frame好，保留它到队列
-                        frames_to_keep.push(frame);
-                    }
-                } else {
-                    // 这个帧的时间戳太新，暂时保留
-                    // 但限制未来帧的数量
-                    if future_frames.len() < MAX_FUTURE_FRAMES {
-                        future_frames.push(frame);
-                    } else {
-                        // 未来帧已满，丢弃最旧的未来帧
-                        discarded_old_frames += 1;
-                    }
+                self.hw_fallback.report(reason);
+            }
+            None if decoder.is_hardware_accelerated() => {
+                info!("{} ✓ 硬件加速已启用", log_ctx());
+                // 总是重试硬解时成功了：清除失败记录
+                if let Some(history) = &history {
+                    history.set_hw_decode_failure(&path, None);
                 }
-            } else {
-                // 队列为空
-                break;
             }
+            None => {}
         }
-        
-        if discarded_old_frames > 0 {
-            debug!("🗑️ 丢弃了 {} 个过期视频帧", discarded_old_frames);
-        }
-        
-        // 将未使用的帧放回队列
-        // 先放回过去的帧（按PTS排序），然后放回未来的帧（按PTS排序）
-        frames_to_keep.sort_by_key(|f| f.pts);
-        future_frames.sort_by_key(|f| f.pts);
-        
-        for frame in frames_to_keep {
-            self.video_frame_queue.push(frame);
-        }
-        for frame in future_frames {
-            self.video_frame_queue.push(frame);
-        }
-        
-        best_frame
+        self.video_source = Some(path);
+        Some(decoder)
     }
 
-    /// 获取播放时长（秒）
-    pub fn get_duration(&self) -> Result<f64> {
-        let state = self.state.lock().unwrap();
-        if let Some(info) = &state.media_info {
-            // duration 是毫秒，转换为秒
-            Ok(info.duration as f64 / 1000.0)
-        } else {
-            Ok(0.0)
+    /// 当前媒体的显示标题：容器标题标签优先，其次 .nfo 的 <title>（都没有时为 None，由界面显示文件名）
+    pub fn media_title(&self) -> Option<String> {
+        let path = self.current_file_path.lock().unwrap().clone()?;
+        let mut cache = self.media_title.lock().unwrap();
+        if let Some((cached_path, title)) = cache.as_ref() {
+            if *cached_path == path {
+                return title.clone();
+            }
+        }
+        // 媒体信息就绪后再解析（打开过程中不缓存）
+        let info = self.get_media_info()?;
+        let title = media_title::resolve_title(info.title.as_deref(), &path, self.read_sidecar_titles);
+        if let Some(title) = &title {
+            info!("{} 🏷️ 显示标题: {}", log_ctx(), title);
         }
+        *cache = Some((path, title.clone()));
+        title
     }
 
-    /// 获取当前播放位置（秒）
-    pub fn get_position(&self) -> Result<f64> {
-        // clock.now() 返回毫秒，转换为秒
-        Ok(self.clock.now() as f64 / 1000.0)
+    /// 获取纯音频文件的封面画面
+    pub fn cover_art(&self) -> Option<&VideoFrame> {
+        self.cover_art.as_ref()
     }
 
-    /// 跳转到指定位置（秒）
-    pub fn seek_to_seconds(&mut self, position: f64) -> Result<()> {
-        info!("{} ⏩ 跳转到位置: {:.2}s", log_ctx(), position);
-        // 转换为毫秒
-        let position_ms = (position * 1000.0) as i64;
-        self.seek(position_ms);
-        Ok(())
+    /// 获取章节列表（按开始时间排序）
+    pub fn get_chapters(&self) -> &[Chapter] {
+        &self.chapters
     }
 
-    /// 检查是否正在播放
-    pub fn is_playing(&self) -> bool {
-        let state = self.state.lock().unwrap();
-        matches!(state.state, PlaybackState::Playing)
+    /// 跳到上一章 / 下一章的开头，返回跳到的章节索引（没有可跳的章节时不 seek）
+    pub fn seek_chapter(&self, forward: bool) -> Option<usize> {
+        let index = adjacent_chapter(&self.chapters, self.get_position_ms(), forward)?;
+        self.seek(self.chapters[index].start_ms, SeekMode::Accurate);
+        Some(index)
     }
 
-    /// 启动播放线程
-    fn start_playback_threads(
-        &mut self,
-        mut demuxer: Demuxer,
-        video_decoder: Option<VideoDecoder>,
-        audio_decoder: Option<AudioDecoder>,
-        subtitle_decoder: Option<SubtitleDecoder>,
-    ) {
-        self.running.store(true, Ordering::SeqCst);
+    /// 跳到上一个 / 下一个关键帧，返回关键帧的时间（关键帧索引尚未完成或已没有可跳的关键帧时不 seek）
+    ///
+    /// 后退时跳过当前位置之前 KEYFRAME_STEP_GRACE_MS 内的关键帧：播放中刚经过的关键帧不算上一个，连续按键不会停在原地
+    pub fn seek_keyframe(&self, forward: bool) -> Option<i64> {
+        let index = self.keyframe_index.as_ref()?.get()?;
+        let position = self.get_position_ms();
+        let keyframe = if forward {
+            index.next_after(position)
+        } else {
+            index.previous_before(position - KEYFRAME_STEP_GRACE_MS)
+        }?;
+        self.seek(keyframe.pts_ms, SeekMode::Accurate);
+        Some(keyframe.pts_ms)
+    }
 
-        // 创建数据包队列
-        let video_packet_queue = Arc::new(SegQueue::new());
-        let audio_packet_queue = Arc::new(SegQueue::new());
-        let subtitle_packet_queue = Arc::new(SegQueue::new());
+    /// 获取按文件记忆的轨道选择和音量（导出配置使用）
+    pub fn file_memory(&self) -> &HashMap<String, FileMemory> {
+        &self.file_memory
+    }
 
-        // 使用 manager 的视频、音频和字幕帧队列
-        let video_frame_queue = self.video_frame_queue.clone();
-        let audio_frame_queue = self.audio_frame_queue.clone();
-        let subtitle_frame_queue = self.subtitle_frame_queue.clone();
+    /// 替换按文件记忆的轨道选择和音量（导入配置使用）
+    pub fn set_file_memory(&mut self, memory: HashMap<String, FileMemory>) {
+        info!("{} 📥 导入文件记忆: {} 条", log_ctx(), memory.len());
+        self.file_memory = memory;
+    }
 
-        let running = self.running.clone();
-        let clock = self.clock.clone();
-        let is_first_audio_frame = self.is_first_audio_frame.clone();
-
-        // 创建 seek 通道
-        let (seek_tx, seek_rx): (Sender<i64>, Receiver<i64>) = unbounded();
-        self.seek_tx = Some(seek_tx);
-
-        // 解封装线程
-        let video_pq = video_packet_queue.clone();
-        let audio_pq = audio_packet_queue.clone();
-        let subtitle_pq = subtitle_packet_queue.clone();
-        let demux_running = running.clone();
-        let is_network = self.is_network_source.clone();
-
-        self.demux_thread = Some(thread::spawn(move || {
-            info!("解封装线程启动");
-            let mut packet_count = 0;
-            while demux_running.load(Ordering::SeqCst) {
-                // 检查是否有 seek 命令（处理所有待处理的seek命令，只执行最后一个）
-                let mut last_seek_pos: Option<i64> = None;
-                while let Ok(seek_pos_ms) = seek_rx.try_recv() {
-                    // 如果有多个seek命令堆积，只记录最后一个
-                    if let Some(old_pos) = last_seek_pos {
-                        debug!("跳过旧的 seek 命令: {} ms", old_pos);
-                    }
-                    last_seek_pos = Some(seek_pos_ms);
+    /// 启用播放位置记录
+    pub fn enable_position_history(&mut self, history: Arc<PositionHistory>) {
+        self.position_history = Some(history);
+    }
+
+    /// 共享的播放位置记录（新建会话时复用同一个写盘线程）
+    pub fn position_history(&self) -> Option<Arc<PositionHistory>> {
+        self.position_history.clone()
+    }
+
+    /// 关闭播放位置记录（写入未保存的位置，最后一个共享者关闭时等待写盘完成，退出时调用）
+    pub fn close_position_history(&mut self) {
+        self.save_position();
+        self.position_history = None;
+    }
+
+    /// 播放中定期记录位置（每帧调用，按 CHECKPOINT_INTERVAL 节流，写盘由后台线程负责）
+    pub fn checkpoint_position(&mut self) {
+        if self.position_history.is_none() || !self.is_playing() {
+            return;
+        }
+        if self.last_checkpoint.is_some_and(|at| at.elapsed() < CHECKPOINT_INTERVAL) {
+            return;
+        }
+        self.last_checkpoint = Some(Instant::now());
+        if let (Some(history), Some(path)) = (&self.position_history, self.local_file_path()) {
+            self.record_position(history, &path);
+        }
+    }
+
+    /// 记录当前位置、时长和显示标题
+    fn record_position(&self, history: &PositionHistory, path: &str) {
+        history.checkpoint(path, self.get_position_ms(), self.get_duration_ms());
+        if let Some(title) = self.media_title() {
+            history.set_title(path, &title);
+        }
+    }
+
+    /// 播放超过时长的 90% 或播放结束时把当前文件标记为已看完（每帧调用；直播流和网络流不标记）
+    pub fn update_watched(&mut self) {
+        if self.position_history.is_none() || self.is_live() {
+            return;
+        }
+        let Some(path) = self.local_file_path() else {
+            return;
+        };
+        if self.watched_path.as_deref() == Some(path.as_str()) {
+            return;
+        }
+        let finished = self.is_source_exhausted();
+        if !finished && !self.is_playing() {
+            return;
+        }
+        if !is_watched(self.get_position_ms(), self.get_duration_ms(), finished) {
+            return;
+        }
+        if let Some(history) = &self.position_history {
+            self.record_position(history, &path);
+            history.set_watched(&path, true);
+        }
+        self.watched_path = Some(path);
+    }
+
+    /// 记录当前位置并立即写盘（停止、切换文件时）
+    fn save_position(&mut self) {
+        self.last_checkpoint = None;
+        let active = matches!(
+            self.state.lock().unwrap().state,
+            PlaybackState::Playing | PlaybackState::Paused | PlaybackState::Seeking | PlaybackState::Buffering
+        );
+        if !active {
+            return;
+        }
+        if let (Some(history), Some(path)) = (&self.position_history, self.local_file_path()) {
+            self.record_position(history, &path);
+            history.flush();
+        }
+    }
+
+    /// 当前文件上次记录的位置（距开头或结尾过近时返回 None）
+    pub fn resume_position(&self) -> Option<i64> {
+        let path = self.local_file_path()?;
+        let position_ms = self.position_history.as_ref()?.position(&path)?;
+        let duration_ms = self.get_duration_ms();
+        let near_end = duration_ms > 0 && position_ms > duration_ms - RESUME_MARGIN_MS;
+        (position_ms > RESUME_MARGIN_MS && !near_end).then_some(position_ms)
+    }
+
+    /// 当前打开的本地文件路径（网络流不记录位置）
+    fn local_file_path(&self) -> Option<String> {
+        if self.is_network_source.load(Ordering::SeqCst) {
+            return None;
+        }
+        self.current_file_path.lock().unwrap().clone()
+    }
+
+    /// 获取数据包检查器（开发者面板使用）
+    pub fn packet_inspector(&self) -> &PacketInspector {
+        &self.packet_inspector
+    }
+
+    /// 开启/关闭调试命令（开发者面板打开时开启；关闭时各线程不读取命令通道）
+    pub fn set_debug_commands_enabled(&self, enabled: bool) {
+        self.debug_commands.set_enabled(enabled);
+    }
+
+    /// 当前逐帧解码延迟（开发者面板显示）
+    pub fn debug_decode_delay(&self) -> Duration {
+        self.debug_commands.decode_delay()
+    }
+
+    /// 执行调试命令（开发者面板使用），前后记录帧队列深度和时钟
+    ///
+    /// 清空帧队列和时钟跳变在这里直接执行；其余命令送到解封装 / 解码线程，由线程记录执行结果。
+    pub fn debug_command(&self, command: DebugCommand) {
+        if !self.debug_commands.is_enabled() {
+            warn!("{} ⚠️ 调试模式未开启，忽略调试命令 {:?}", log_ctx(), command);
+            return;
+        }
+        let snapshot = || {
+            format!(
+                "视频帧队列 {} 音频帧队列 {} 时钟 {}ms",
+                self.video_frame_queue.len(),
+                self.audio_frame_queue.len(),
+                self.clock.now()
+            )
+        };
+        info!("{} 🧪 调试命令 {:?}，执行前: {}", log_ctx(), command, snapshot());
+        match command {
+            DebugCommand::FlushVideoQueue => {
+                let mut dropped = self.video_frame_queue.clear();
+                if self.video_lookahead.lock().unwrap().take().is_some() {
+                    dropped += 1;
                 }
-                
-                if let Some(seek_pos_ms) = last_seek_pos {
-                    info!("🎯 Demuxer 收到 seek 命令: {} ms，清空队列并执行 seek", seek_pos_ms);
-                    
-                    // 清空所有包队列（确保没有旧数据）
-                    let mut cleared_video = 0;
-                    let mut cleared_audio = 0;
-                    let mut cleared_subtitle = 0;
-                    while video_pq.pop().is_some() { cleared_video += 1; }
-                    while audio_pq.pop().is_some() { cleared_audio += 1; }
-                    while subtitle_pq.pop().is_some() { cleared_subtitle += 1; }
-                    
-                    if cleared_video > 0 || cleared_audio > 0 || cleared_subtitle > 0 {
-                        debug!("清空包队列: 视频{} 音频{} 字幕{}", cleared_video, cleared_audio, cleared_subtitle);
-                    }
-                    
-                    // 执行 seek
-                    if let Err(e) = demuxer.seek(seek_pos_ms) {
-                        error!("{} ❌ Demuxer seek 失败: {}", log_ctx(), e);
-                    } else {
-                        info!("✅ Demuxer seek 成功: {} ms", seek_pos_ms);
-                    }
-                    packet_count = 0; // 重置计数
-                    
-                    // 短暂等待，确保队列被其他线程清空
-                    thread::sleep(Duration::from_millis(10));
-                    continue;
+                info!("{} 🧪 丢弃 {} 个视频帧", log_ctx(), dropped);
+            }
+            DebugCommand::FlushAudioQueue => {
+                let dropped = self.audio_frame_queue.clear();
+                info!("{} 🧪 丢弃 {} 个音频帧", log_ctx(), dropped);
+            }
+            DebugCommand::ClockJump(offset_ms) => {
+                self.clock.set_time(self.clock.now() + offset_ms);
+            }
+            DebugCommand::FlushDecoders | DebugCommand::StallDemuxer(_) | DebugCommand::DecodeDelay(_) => {
+                let delivered = self.debug_commands.send(command);
+                if delivered == 0 {
+                    warn!("{} ⚠️ 调试命令 {:?} 没有送达任何线程（当前没有播放）", log_ctx(), command);
+                } else {
+                    info!("{} 🧪 调试命令已送达 {} 个线程", log_ctx(), delivered);
                 }
-                
-                match demuxer.read_packet() {
-                    Ok(Some((packet, is_video, is_subtitle))) => {
-                        packet_count += 1;
-                        if is_video {
-                            video_pq.push(packet);
-                            if packet_count % 100 == 0 {
-                                debug!("解封装视频包: {} (队列: {})", packet_count, video_pq.len());
-                            }
-                        } else if is_subtitle {
-                            // 字幕包推入字幕队列
-                            subtitle_pq.push(packet);
-                        } else {
-                            audio_pq.push(packet);
-                        }
-                    }
-                    Ok(None) => {
-                        info!("文件读取完毕，共处理 {} 个包", packet_count);
-                        break;
-                    }
-                    Err(e) => {
-                        error!("{} 读取数据包失败: {} (已处理 {} 个包)", log_ctx(), e, packet_count);
-                        break;
+                return;
+            }
+        }
+        info!("{} 🧪 调试命令 {:?}，执行后: {}", log_ctx(), command, snapshot());
+    }
+
+    /// 解封装停滞 / 恢复（界面每帧调用）
+    pub fn poll_stall(&self) -> Option<StallEvent> {
+        let event = self.stall_watchdog.poll(Instant::now())?;
+        match event {
+            StallEvent::Stalled { waited } => warn!(
+                "{} ⏳ 解封装停滞：读包 {}ms 未返回（视频帧队列 {} 音频帧队列 {}）",
+                log_ctx(),
+                waited.as_millis(),
+                self.video_frame_queue.len(),
+                self.audio_frame_queue.len()
+            ),
+            StallEvent::Recovered { stalled_for } => {
+                info!("{} ✅ 解封装恢复：共停滞 {}ms", log_ctx(), stalled_for.as_millis())
+            }
+        }
+        Some(event)
+    }
+
+    /// 获取音频轨道列表
+    pub fn get_audio_tracks(&self) -> &[TrackInfo] {
+        &self.audio_tracks
+    }
+
+    /// 获取字幕轨道列表（内嵌字幕在前，外部字幕文件在后）
+    pub fn get_subtitle_tracks(&self) -> &[TrackInfo] {
+        &self.subtitle_tracks
+    }
+
+    /// 当前音频流索引
+    pub fn current_audio_stream(&self) -> Option<usize> {
+        self.selected_audio_stream
+    }
+
+    /// 当前选择的字幕（None 表示关闭）
+    pub fn current_subtitle_track(&self) -> Option<&TrackSource> {
+        self.selected_subtitle.as_ref()
+    }
+
+    /// 轨道切换是否仍在进行（新画面尚未呈现）
+    pub fn is_switching_track(&self) -> bool {
+        match self.track_switch_started {
+            Some(started) => {
+                started.elapsed() < TRACK_SWITCH_TIMEOUT && self.get_presented_frame().is_none()
+            }
+            None => false,
+        }
+    }
+
+    /// 选择音频轨道（需要重建播放管线）
+    pub fn select_audio_track(&mut self, stream_index: usize) -> Result<()> {
+        if !self.audio_tracks.iter().any(|t| t.source == TrackSource::Embedded(stream_index)) {
+            return Err(crate::core::PlayerError::Other(format!("音频轨道不存在: {}", stream_index)));
+        }
+        
+        let path = self.current_track_path()?;
+        self.file_memory.entry(path).or_default().audio_stream = Some(stream_index);
+        info!("{} 🔊 切换音频轨道: 流 {}", log_ctx(), stream_index);
+        self.rebuild_for_track_switch()
+    }
+
+    /// 选择字幕轨道（None 表示关闭字幕）
+    pub fn select_subtitle_track(&mut self, track: Option<TrackSource>) -> Result<()> {
+        if let Some(ref source) = track {
+            if !self.subtitle_tracks.iter().any(|t| &t.source == source) {
+                return Err(crate::core::PlayerError::Other(format!("字幕轨道不存在: {:?}", source)));
+            }
+        }
+        
+        let path = self.current_track_path()?;
+        self.file_memory.entry(path).or_default().subtitle = Some(track.clone());
+        info!("{} 📝 切换字幕轨道: {:?}", log_ctx(), track);
+        
+        // 内嵌字幕流变化时需要重建管线（字幕解码器绑定在流上）
+        let old_embedded = match self.selected_subtitle {
+            Some(TrackSource::Embedded(index)) => Some(index),
+            _ => None,
+        };
+        let new_embedded = match track {
+            Some(TrackSource::Embedded(index)) => Some(index),
+            _ => None,
+        };
+        if old_embedded != new_embedded && new_embedded.is_some() {
+            return self.rebuild_for_track_switch();
+        }
+        
+        // 外部字幕或关闭：直接切换，无需重建
+        {
+            let mut external_frames = self.external_subtitle_frames.lock().unwrap();
+            external_frames.clear();
+        }
+        // 已解码的内嵌字幕不再显示，丢弃
+        if old_embedded.is_some() {
+            self.discard_embedded_subtitles();
+        }
+        if let Some(TrackSource::External(ref subtitle_file)) = track {
+            self.load_external_subtitles(subtitle_file);
+        }
+        self.selected_subtitle = track;
+        Ok(())
+    }
+
+    /// 加载用户指定的外部字幕文件（如拖入的 .srt）并切换到该字幕
+    pub fn add_external_subtitle(&mut self, subtitle_file: &Path) -> Result<()> {
+        self.current_track_path()?;
+        let source = TrackSource::External(subtitle_file.to_path_buf());
+        if !self.subtitle_tracks.iter().any(|track| track.source == source) {
+            info!("{} 📝 添加外部字幕: {}", log_ctx(), subtitle_file.display());
+            self.subtitle_tracks.push(TrackInfo {
+                source: source.clone(),
+                meta: StreamMeta {
+                    codec: subtitle_file
+                        .extension()
+                        .map(|ext| ext.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    ..Default::default()
+                },
+            });
+        }
+        self.select_subtitle_track(Some(source))
+    }
+
+    /// 设置字幕延迟（毫秒，正值表示字幕推后显示，负值提前；超出范围时夹紧）
+    pub fn set_subtitle_offset_ms(&mut self, offset_ms: i64) {
+        let offset_ms = offset_ms.clamp(-SUBTITLE_OFFSET_LIMIT_MS, SUBTITLE_OFFSET_LIMIT_MS);
+        if offset_ms != self.subtitle_offset_ms {
+            info!("{} 📝 字幕延迟: {:+}ms", log_ctx(), offset_ms);
+            self.subtitle_offset_ms = offset_ms;
+        }
+    }
+
+    /// 当前字幕延迟（毫秒）
+    pub fn subtitle_offset_ms(&self) -> i64 {
+        self.subtitle_offset_ms
+    }
+
+    /// 获取可切换轨道的本地文件路径
+    fn current_track_path(&self) -> Result<String> {
+        if self.is_network_source.load(Ordering::SeqCst) {
+            return Err(crate::core::PlayerError::Other("网络流暂不支持切换轨道".to_string()));
+        }
+        self.current_file_path
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| crate::core::PlayerError::Other("没有打开的文件".to_string()))
+    }
+
+    /// 以当前位置重建播放管线（应用需要重新创建解码器的设置，如硬件解码）；没有打开本地文件时不做任何事
+    pub fn rebuild_pipeline(&mut self) -> Result<()> {
+        if self.is_idle() || self.is_network_source.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        info!("{} 🔧 重建播放管线以应用新设置", log_ctx());
+        self.rebuild_for_track_switch()
+    }
+
+    /// 以当前位置重建播放管线（应用新的轨道选择）
+    fn rebuild_for_track_switch(&mut self) -> Result<()> {
+        let path = self.current_track_path()?;
+        let position_ms = self.get_position_ms();
+        let was_playing = self.is_playing();
+        
+        self.track_switch_started = Some(Instant::now());
+        self.open(path)?;
+        if position_ms > 0 {
+            self.seek(position_ms, SeekMode::Accurate);
+        }
+        if was_playing {
+            self.play()?;
+        }
+        Ok(())
+    }
+
+    /// 获取当前时间所有活动的字幕（重叠字幕同时显示，按开始时间排序，最早的在前）
+    pub fn get_current_subtitles(&self, current_time_ms: i64) -> Vec<SubtitleFrame> {
+        // 字幕延迟：按推后（或提前）后的时间比较字幕的显示范围
+        let current_time_ms = current_time_ms - self.subtitle_offset_ms;
+        match self.selected_subtitle {
+            None => {
+                self.discard_embedded_subtitles();
+                return Vec::new();
+            }
+            Some(TrackSource::External(_)) => {
+                self.discard_embedded_subtitles();
+                return self.get_external_subtitles(self.loop_control.wrap(current_time_ms).1);
+            }
+            Some(TrackSource::Embedded(_)) => {}
+        }
+
+        self.subtitle_frame_queue.active_at(current_time_ms)
+    }
+
+    /// 待显示的内嵌字幕数
+    pub fn subtitle_cue_count(&self) -> usize {
+        self.subtitle_frame_queue.len()
+    }
+
+    /// 丢弃内嵌字幕帧（关闭字幕或使用外部字幕时字幕解码线程仍在送出帧，不丢弃会一直堆积）
+    fn discard_embedded_subtitles(&self) {
+        self.subtitle_frame_queue.clear();
+    }
+
+    /// 加载外部字幕文件
+    fn load_external_subtitles(&self, subtitle_file: &Path) {
+        let mut all_frames = Vec::new();
+        
+        info!("📝 加载外部字幕文件: {}", subtitle_file.display());
+        match ExternalSubtitleParser::parse_subtitle_file(subtitle_file) {
+            Ok(frames) => {
+                info!("✅ 成功解析外部字幕，共 {} 条", frames.len());
+                all_frames.extend(frames);
+            }
+            Err(e) => {
+                error!("{} ❌ 解析外部字幕文件失败: {} - {}", log_ctx(), subtitle_file.display(), e);
+            }
+        }
+
+        // 按时间戳排序
+        all_frames.sort_by_key(|frame| frame.pts);
+
+        // 存储到外部字幕缓存
+        {
+            let mut external_frames = self.external_subtitle_frames.lock().unwrap();
+            *external_frames = all_frames;
+            info!("{} 📝 外部字幕加载完成，共 {} 条字幕", log_ctx(), external_frames.len());
+        }
+    }
+
+    /// 从外部字幕中获取当前时间应显示的所有字幕
+    fn get_external_subtitles(&self, current_time_ms: i64) -> Vec<SubtitleFrame> {
+        let external_frames = self.external_subtitle_frames.lock().unwrap();
+        
+        external_frames
+            .iter()
+            // 已按开始时间排序：之后的字幕都还没到时间
+            .take_while(|frame| current_time_ms >= frame.pts)
+            .filter(|frame| current_time_ms < frame.end_pts)
+            .cloned()
+            .collect()
+    }
+
+    /// 获取媒体总时长（毫秒，未知时为 0）
+    pub fn get_duration_ms(&self) -> i64 {
+        let state = self.state.lock().unwrap();
+        state
+            .media_info
+            .as_ref()
+            .map(|info| info.duration.max(0))
+            .unwrap_or(0)
+    }
+
+    /// 获取当前播放位置（毫秒，负 PTS 起点按 0 处理；无缝循环时为文件内的位置）
+    pub fn get_position_ms(&self) -> i64 {
+        self.loop_control.wrap(self.clock.now()).1.max(0)
+    }
+
+    /// 播放时钟（内部时间轴，无缝循环时单调递增，用于选择视频帧和内嵌字幕）
+    pub fn get_clock_ms(&self) -> i64 {
+        self.clock.now().max(0)
+    }
+
+    /// 跳转到指定位置（秒）
+    pub fn seek_to_seconds(&mut self, position: f64, mode: SeekMode) -> Result<()> {
+        info!("{} ⏩ 跳转到位置: {:.2}s", log_ctx(), position);
+        // 转换为毫秒
+        let position_ms = (position.max(0.0) * 1000.0) as i64;
+        self.seek(position_ms, mode);
+        Ok(())
+    }
+
+    /// 是否已播放完毕（解封装线程报告读到末尾且帧队列已耗尽；读取出错不算播放完毕）
+    pub fn is_source_exhausted(&self) -> bool {
+        *self.demux_end.lock().unwrap() == Some(DemuxEnd::Eof) && self.video_frame_queue.is_empty() && self.audio_frame_queue.is_empty()
+    }
+
+    /// 播放中的源已播放完毕时进入 Ended 状态：时钟暂停在时长处（每帧调用，返回是否刚进入）
+    ///
+    /// 单曲循环时由 restart_loop_if_due 回到开头，不进入 Ended
+    pub fn update_ended(&self) -> bool {
+        if self.loop_control.repeat_one() || !self.is_playing() || !self.is_source_exhausted() {
+            return false;
+        }
+        let duration_ms = self.get_duration_ms();
+        self.clock.pause();
+        // 最后一帧的 PTS 通常略早于时长，进度停在末尾
+        if duration_ms > 0 {
+            self.clock.set_time(duration_ms);
+        }
+        self.state.lock().unwrap().state = PlaybackState::Ended;
+        info!("{} 🏁 播放完毕", log_ctx());
+        true
+    }
+
+    /// 是否处于播放完毕状态
+    pub fn is_ended(&self) -> bool {
+        self.state.lock().unwrap().state == PlaybackState::Ended
+    }
+
+    /// 处理解封装线程报告的结束原因（每帧调用），返回需要界面处理的事件
+    /// - Eof：按播放完毕处理（帧队列耗尽后 is_source_exhausted 为真）
+    /// - NetworkTimeout：进入缓冲状态，由界面重新打开流（短时间内重连次数过多则按失败处理）
+    /// - IoError：停止播放并提示
+    pub fn poll_demux_end(&mut self) -> Option<DemuxEvent> {
+        let end = self.demux_end_rx.as_ref()?.try_recv().ok()?;
+        info!("{} 📭 解封装结束: {:?}", log_ctx(), end);
+        *self.demux_end.lock().unwrap() = Some(end.clone());
+        match end {
+            DemuxEnd::Eof => Some(DemuxEvent::Finished),
+            DemuxEnd::Cancelled => None,
+            DemuxEnd::NetworkTimeout => {
+                let url = self.current_file_path.lock().unwrap().clone();
+                let attempt = self.next_reconnect_attempt();
+                match url {
+                    Some(url) if self.is_network_source.load(Ordering::SeqCst) && attempt <= MAX_RECONNECT_ATTEMPTS => {
+                        warn!("{} 📡 网络中断，准备重新连接（第 {} 次）", log_ctx(), attempt);
+                        self.state.lock().unwrap().state = PlaybackState::Buffering;
+                        *self.stream_state.write().unwrap() = Some(StreamState::Reconnecting { attempt });
+                        Some(DemuxEvent::Reconnect { url, position_ms: self.get_position_ms(), attempt })
                     }
+                    _ => Some(self.fail_playback("网络连接中断".to_string())),
                 }
+            }
+            DemuxEnd::IoError(message) => Some(self.fail_playback(message)),
+        }
+    }
 
-                // 智能缓冲策略：根据媒体源类型动态调整队列大小
-                // 本地文件: 磁盘 I/O 快速稳定，使用较小缓冲节省内存
-                // 网络流: 网络 I/O 不稳定，使用较大缓冲应对抖动
-                let is_network_source = is_network.load(Ordering::SeqCst);
-                let max_queue_size = if is_network_source {
-                    1000  // 网络流: 1000 包（约 20-40 秒，应对网络抖动）
-                } else {
-                    300   // 本地文件: 300 包（约 6-12 秒，足够流畅）
-                };
-                
-                while (video_pq.len() > max_queue_size || audio_pq.len() > max_queue_size)
-                    && demux_running.load(Ordering::SeqCst)
-                {
-                    if video_pq.len() > max_queue_size || audio_pq.len() > max_queue_size {
-                        debug!("队列满，等待消费 (视频: {}/{}, 音频: {}/{}, 类型: {})", 
-                               video_pq.len(), max_queue_size, audio_pq.len(), max_queue_size,
-                               if is_network_source { "网络流" } else { "本地文件" });
-                    }
-                    thread::sleep(Duration::from_millis(10));
+    /// 本次网络中断是短时间内的第几次
+    fn next_reconnect_attempt(&mut self) -> u32 {
+        let attempt = match self.network_interruptions {
+            Some((at, attempts)) if at.elapsed() < RECONNECT_WINDOW => attempts + 1,
+            _ => 1,
+        };
+        self.network_interruptions = Some((Instant::now(), attempt));
+        attempt
+    }
+
+    /// 读取失败：停止播放并进入错误状态
+    fn fail_playback(&mut self, message: String) -> DemuxEvent {
+        error!("{} ❌ 读取失败，停止播放: {}", log_ctx(), message);
+        self.stop();
+        self.state.lock().unwrap().state = PlaybackState::Error;
+        if self.is_network_source.load(Ordering::SeqCst) {
+            *self.stream_state.write().unwrap() = Some(StreamState::Failed { reason: message.clone() });
+        }
+        DemuxEvent::Failed(message)
+    }
+
+    /// 视频轨道是否刚被判定为损坏（连续的帧尺寸无效，视频已停止解码）；读取后清除
+    pub fn take_video_corrupt_notice(&self) -> bool {
+        self.video_corrupt_notice.swap(false, Ordering::SeqCst)
+    }
+
+    /// 设置首帧期限（正在播放的时间超过期限仍没有视频帧时报告）
+    pub fn set_first_frame_deadline(&self, deadline: Duration) {
+        self.first_frame.lock().unwrap().set_deadline(deadline);
+    }
+
+    /// 首帧检测：视频流一直没有解码出帧时返回原因（缓冲、暂停时不计时；每个管线最多报告一次）
+    pub fn poll_first_frame(&self) -> Option<FirstFrameDiagnosis> {
+        let active = self.state.lock().unwrap().state == PlaybackState::Playing && !self.suspended.load(Ordering::SeqCst);
+        let counts = self.video_counters.snapshot();
+        let diagnosis = self.first_frame.lock().unwrap().poll(counts, active, Instant::now())?;
+        warn!(
+            "{} ⚠️ 期限内没有解码出视频帧: {:?}（视频包 {}，解码出错 {}）",
+            log_ctx(), diagnosis, counts.packets, counts.errors
+        );
+        Some(diagnosis)
+    }
+
+    /// 只播放音频：视频解码线程不再解码，只丢弃数据包（当前管线有效）
+    pub fn continue_audio_only(&self) {
+        info!("{} 🔈 视频流无法解码，改为只播放音频", log_ctx());
+        self.video_disabled.store(true, Ordering::SeqCst);
+        self.first_frame.lock().unwrap().disarm();
+    }
+
+    /// 当前管线是否已改为只播放音频
+    pub fn is_audio_only(&self) -> bool {
+        self.video_disabled.load(Ordering::SeqCst)
+    }
+
+    /// 视频帧乱序校正次数（当前管线）
+    pub fn reorder_corrections(&self) -> u64 {
+        self.reorder_corrections.load(Ordering::Relaxed)
+    }
+
+    /// 解封装线程当前的预读量（毫秒；没有在播放或尚未读包时为 None）
+    pub fn read_ahead_ms(&self) -> Option<i64> {
+        self.demuxer_thread_handle.as_ref()?.read_ahead().lead_ms()
+    }
+
+    /// 网络流已缓冲到的位置（毫秒，播放时钟时间轴；本地文件、尚未读包或缓冲已耗尽时为 None）
+    pub fn get_buffered_position_ms(&self) -> Option<i64> {
+        if !self.is_network_source.load(Ordering::SeqCst) {
+            return None;
+        }
+        let buffered_ms = self.demuxer_thread_handle.as_ref()?.read_ahead().buffered_to_ms()?;
+        (buffered_ms > self.get_clock_ms()).then_some(buffered_ms)
+    }
+
+    /// 音频解码器是否刚被判定失效（已切换为无声播放）；读取后清除
+    pub fn take_audio_failure_notice(&self) -> bool {
+        self.audio_failure.take_notice()
+    }
+
+    /// 当前管线改用软件解码的原因（信息面板显示；使用硬件解码时为 None）
+    pub fn hw_fallback_reason(&self) -> Option<HwFallbackReason> {
+        self.hw_fallback.reason()
+    }
+
+    /// 当前管线硬件 / 软件解码出的视频帧数（信息面板显示）
+    pub fn decoder_stats(&self) -> DecoderStats {
+        self.video_counters.decoder_stats()
+    }
+
+    /// 刚发生的硬解回退（界面显示屏幕提示）；读取后清除
+    ///
+    /// 播放中的回退在这里记入播放历史，并在当前位置重新 seek：解码线程已把解码器重建为软件解码，
+    /// seek 让新解码器从关键帧开始解码。
+    pub fn poll_hw_fallback(&self) -> Option<HwFallbackReason> {
+        let reason = self.hw_fallback.take_notice()?;
+        if let HwFallbackReason::MidStream(_) = reason {
+            if let (Some(history), Some(path)) = (&self.position_history, &self.video_source) {
+                history.set_hw_decode_failure(path, Some(&reason.description()));
+            }
+            let position_ms = self.get_position_ms();
+            info!("{} 🎞️ 已切换为软件解码，在 {}ms 处重新定位", log_ctx(), position_ms);
+            self.seek(position_ms, SeekMode::Accurate);
+        }
+        Some(reason)
+    }
+
+    /// 当前音轨之后的下一条音轨（循环查找；没有其他音轨时为 None）
+    pub fn next_audio_track(&self) -> Option<usize> {
+        let streams: Vec<usize> = self
+            .audio_tracks
+            .iter()
+            .filter_map(|track| match track.source {
+                TrackSource::Embedded(index) => Some(index),
+                TrackSource::External(_) => None,
+            })
+            .collect();
+        let current = streams.iter().position(|&index| Some(index) == self.selected_audio_stream);
+        let next = match current {
+            Some(position) => streams[(position + 1) % streams.len()],
+            None => *streams.first()?,
+        };
+        (Some(next) != self.selected_audio_stream).then_some(next)
+    }
+
+    /// 开启/关闭单曲循环
+    pub fn set_repeat_one(&self, enabled: bool) {
+        if enabled != self.loop_control.repeat_one() {
+            info!("{} 🔁 单曲循环: {}", log_ctx(), if enabled { "开启" } else { "关闭" });
+        }
+        self.loop_control.set_repeat_one(enabled);
+    }
+
+    pub fn repeat_one(&self) -> bool {
+        self.loop_control.repeat_one()
+    }
+
+    /// 设置无缝循环的时长上限（更长的本地文件和网络流循环时按普通 seek 回到开头）
+    pub fn set_seamless_loop_limit_ms(&self, limit_ms: i64) {
+        self.loop_control.set_seamless_limit_ms(limit_ms);
+    }
+
+    /// 已完成的循环遍数（按播放时钟计算）
+    pub fn loop_count(&self) -> u64 {
+        self.loop_control.wrap(self.clock.now()).0
+    }
+
+    /// 内部时间轴上的时间戳折回到文件内的位置（用于显示已呈现帧的位置）
+    pub fn loop_position(&self, pts_ms: i64) -> i64 {
+        self.loop_control.wrap(pts_ms).1
+    }
+
+    /// 非无缝循环：文件已读完且帧队列已播完时 seek 回开头（返回是否执行了 seek）
+    pub fn restart_loop_if_due(&self) -> bool {
+        let due = self.loop_control.repeat_one()
+            && self.loop_control.is_waiting_restart()
+            && self.video_frame_queue.is_empty()
+            && self.audio_frame_queue.is_empty();
+        if due {
+            info!("{} 🔁 单曲循环: 回到开头", log_ctx());
+            // 解封装线程处理 seek 前不再重复触发
+            self.loop_control.set_waiting_restart(false);
+            self.seek(0, SeekMode::Fast);
+        }
+        due
+    }
+
+    /// 在当前位置标记 A 点（A/B 循环生效时清除循环）
+    pub fn mark_ab_loop_a(&mut self) -> AbLoopMark {
+        let mark = self.ab_loop.mark_a(self.get_position_ms());
+        info!("{} 🔂 A/B 循环: {:?}", log_ctx(), mark);
+        mark
+    }
+
+    /// 在当前位置标记 B 点（A/B 循环生效时清除循环；B 点必须在 A 点之后）
+    pub fn mark_ab_loop_b(&mut self) -> Result<AbLoopMark> {
+        let mark = self.ab_loop.mark_b(self.get_position_ms())?;
+        info!("{} 🔂 A/B 循环: {:?}", log_ctx(), mark);
+        Ok(mark)
+    }
+
+    /// 设置或清除 A/B 循环范围（毫秒，B 必须在 A 之后）
+    pub fn set_loop_range(&mut self, range: Option<(i64, i64)>) -> Result<()> {
+        match range {
+            Some((a, b)) => self.ab_loop.set_range(a, b)?,
+            None => self.ab_loop.clear(),
+        }
+        info!("{} 🔂 A/B 循环: {:?}", log_ctx(), range);
+        Ok(())
+    }
+
+    /// 生效中的 A/B 循环范围
+    pub fn loop_range(&self) -> Option<(i64, i64)> {
+        self.ab_loop.range()
+    }
+
+    /// 已标记、等待标记 B 点的 A 点
+    pub fn ab_loop_pending_a(&self) -> Option<i64> {
+        self.ab_loop.pending_a()
+    }
+
+    /// 播放越过 B 点时 seek 回 A 点（每帧调用，返回是否执行了 seek）
+    pub fn update_ab_loop(&mut self) -> bool {
+        let seeks = self.seek_count.load(Ordering::SeqCst);
+        let Some(a) = self.ab_loop.check(seeks, self.get_position_ms()) else {
+            return false;
+        };
+        info!("{} 🔂 A/B 循环: 回到 A 点 {}ms", log_ctx(), a);
+        self.seek(a, SeekMode::Accurate);
+        true
+    }
+
+    /// 设置播放列表（替换原有条目；当前打开的文件在列表中时从它继续）
+    pub fn set_playlist(&mut self, items: Vec<String>) {
+        info!("{} 📃 设置播放列表: {} 项", log_ctx(), items.len());
+        self.playlist.set(items);
+        if let Some(path) = self.current_file_path.lock().unwrap().clone() {
+            self.playlist.select(&path);
+        }
+    }
+
+    /// 追加到播放列表末尾
+    pub fn append_to_playlist(&mut self, item: String) {
+        info!("{} 📃 加入播放列表: {}", log_ctx(), item);
+        self.playlist.push(item);
+    }
+
+    pub fn playlist(&self) -> &Playlist {
+        &self.playlist
+    }
+
+    /// 播放到列表末尾后停止或回到第一项
+    pub fn set_playlist_repeat(&mut self, repeat: RepeatMode) {
+        if repeat != self.playlist.repeat() {
+            info!("{} 🔁 列表循环: {}", log_ctx(), if repeat == RepeatMode::All { "开启" } else { "关闭" });
+        }
+        self.playlist.set_repeat(repeat);
+    }
+
+    /// 移动到播放列表的下一项并返回其路径（由调用方打开；末尾且不循环时为 None）
+    pub fn next_playlist_item(&mut self) -> Option<String> {
+        self.playlist.advance()
+    }
+
+    /// 移动到播放列表的上一项并返回其路径（由调用方打开；第一项且不循环时为 None）
+    pub fn previous_playlist_item(&mut self) -> Option<String> {
+        self.playlist.retreat()
+    }
+
+    /// 当前项播放完毕且列表中还有下一项（单曲循环时不自动前进）
+    pub fn playlist_advance_due(&self) -> bool {
+        !self.loop_control.repeat_one() && self.playlist.next_index().is_some() && self.is_source_exhausted()
+    }
+
+    /// 当前项接近结尾时在后台预读播放列表的下一项（每帧调用；下一项变化或离开结尾附近时取消预读）
+    pub fn update_playlist_prefetch(&mut self) {
+        let target = self.playlist_prefetch_target();
+        if self.prefetch.as_ref().map(PlaylistPrefetch::path) == target.as_deref() {
+            return;
+        }
+        if let Some(prefetch) = self.prefetch.take() {
+            info!("{} ⏭️ 取消下一项的预读: {}", log_ctx(), prefetch.path());
+        }
+        let Some(path) = target else {
+            return;
+        };
+        // 打开方式不同的项（网络流、图像）和会从上次位置继续播放的文件不预读
+        let resumes = self
+            .position_history
+            .as_ref()
+            .and_then(|history| history.position(&path))
+            .is_some_and(|position_ms| position_ms > RESUME_MARGIN_MS);
+        if !Path::new(&path).is_file() || is_supported_image_file(Path::new(&path)) || resumes {
+            return;
+        }
+        info!("{} ⏭️ 预读播放列表下一项: {}", log_ctx(), path);
+        let audio_stream = self.file_memory.get(&path).and_then(|memory| memory.audio_stream);
+        self.prefetch = Some(PlaylistPrefetch::spawn(path, audio_stream, self.video_decoder_selector()));
+    }
+
+    /// 需要预读的下一项：本地文件播放到距结尾 PREFETCH_LEAD_MS 以内且播放完毕后会自动前进
+    fn playlist_prefetch_target(&self) -> Option<String> {
+        if self.is_idle() || self.still_image || self.image_sequence.is_some() || self.loop_control.repeat_one() {
+            return None;
+        }
+        if self.is_network_source.load(Ordering::SeqCst) {
+            return None;
+        }
+        let duration_ms = self.get_duration_ms();
+        if duration_ms <= 0 || duration_ms - self.get_position_ms() > PREFETCH_LEAD_MS {
+            return None;
+        }
+        self.playlist.items().get(self.playlist.next_index()?).cloned()
+    }
+
+    /// 打开并播放下一项（末尾且不循环时停止播放，返回 false）
+    pub fn play_next(&mut self) -> Result<bool> {
+        match self.next_playlist_item() {
+            Some(path) => self.open_and_play(path),
+            None => {
+                info!("{} 📃 已是播放列表最后一项", log_ctx());
+                self.stop();
+                Ok(false)
+            }
+        }
+    }
+
+    /// 打开并播放上一项（第一项且不循环时回到开头，返回 false）
+    pub fn play_previous(&mut self) -> Result<bool> {
+        match self.previous_playlist_item() {
+            Some(path) => self.open_and_play(path),
+            None => {
+                info!("{} 📃 已是播放列表第一项", log_ctx());
+                self.seek(0, SeekMode::Fast);
+                Ok(false)
+            }
+        }
+    }
+
+    fn open_and_play(&mut self, path: String) -> Result<bool> {
+        info!("{} ▶️  播放列表第 {}/{} 项: {}", log_ctx(), self.playlist.current().map_or(0, |index| index + 1), self.playlist.len(), path);
+        self.open_file(&path)?;
+        self.play()?;
+        Ok(true)
+    }
+
+    /// 检查是否正在播放
+    pub fn is_playing(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        matches!(state.state, PlaybackState::Playing)
+    }
+
+    /// 是否没有打开中或播放中的媒体（未打开、已停止或打开失败）
+    pub fn is_idle(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        matches!(state.state, PlaybackState::Idle | PlaybackState::Stopped | PlaybackState::Error)
+    }
+
+    /// 创建解码器和音频输出，在 DemuxerThread 中运行解封装器并启动解码线程（本地文件和网络流共用）
+    ///
+    /// prefetched 为播放列表预读的开头：接管预读的解码器，预读的帧放入帧队列，解封装线程从预读停下的位置继续读包
+    fn start_pipeline(&mut self, mut demuxer: Demuxer, prefetched: Option<PrefetchedStart>) -> Result<()> {
+        let media_info = demuxer.get_media_info()?;
+
+        // 创建音频输出（先创建，获取实际配置）；上一个文件或其他会话留下的输出格式相同时直接复用，不同时重新打开设备
+        self.audio_output = if media_info.audio_codec != "none" {
+            let idle = self
+                .idle_audio_output
+                .take()
+                .filter(|output| output.get_config() == (media_info.sample_rate, media_info.channels) && !output.has_failed());
+            let output = match idle {
+                Some(output) => {
+                    info!("{} 🔈 复用音频输出: {}", log_ctx(), output.device_name());
+                    Ok(output)
+                }
+                None => AudioOutput::new_with_device(self.audio_device.as_deref(), media_info.sample_rate, media_info.channels),
+            };
+            match output {
+                Ok(mut output) => {
+                    output.start()?;
+                    Some(output)
+                }
+                Err(e) => {
+                    error!("{} ❌ 创建音频输出失败: {}，无声播放（由视频帧驱动时钟）", log_ctx(), e);
+                    None
                 }
             }
-            info!("解封装线程结束");
-        }));
+        } else {
+            None
+        };
 
-        // 视频解码线程
-        if let Some(mut decoder) = video_decoder {
-            let video_pq = video_packet_queue.clone();
-            let video_fq = video_frame_queue.clone();
-            let decode_running = running.clone();
-            let _video_clock = clock.clone();
-            let seek_pos = self.seek_position.clone();
-            let is_network = self.is_network_source.clone();
+        self.pipeline_audio_config = self.audio_output.as_ref().map(AudioOutput::get_config);
 
-            self.video_decode_thread = Some(thread::spawn(move || {
-                info!("🎬 视频解码线程启动");
-                // ==================== 视频解码线程：跟随音频时钟 ====================
-                // 职责：
-                // 1. 解码视频包为视频帧
-                // 2. 跟随音频时钟，不主动控制播放节奏
-                // 3. Seek后跳过不合适的旧帧
-                // 4. 提前解码帧以保证播放流畅
-                while decode_running.load(Ordering::SeqCst) {
-                    // ========== 队列限流：防止过度解码 ==========
-                    // 智能缓冲策略：根据媒体源类型调整视频帧缓冲
-                    // 本地文件模式：更激进的队列控制，提前减速
-                    let is_network_source = is_network.load(Ordering::SeqCst);
-                    
-                    if !is_network_source {
-                        // 本地文件：提前减速，避免队列过大
-                        let queue_len = video_fq.len();
-                        const LOCAL_MAX_FRAMES: usize = 20;  // 本地文件最大帧数（从15增加到20，但提前控制）
-                        const LOCAL_HIGH_WATER: usize = 12;  // 高水位：开始减速
-                        
-                        if queue_len > LOCAL_MAX_FRAMES {
-                            // 队列过大，减速解码
-                            thread::sleep(Duration::from_millis(10));
-                            continue;
-                        } else if queue_len > LOCAL_HIGH_WATER {
-                            // 接近上限，轻微减速
-                            thread::sleep(Duration::from_millis(2));
-                        }
-                    } else {
-                        // 网络流：使用更大的缓冲（在网络流模式中处理，这里不做特殊处理）
-                        let max_video_frames = 30;  // 网络流: 30帧
-                        if video_fq.len() > max_video_frames {
-                            thread::sleep(Duration::from_millis(5));
-                            continue;
-                        }
-                    }
+        // 获取音频输出的实际配置（用于解码器）
+        let (actual_sample_rate, actual_channels) = if let Some(ref output) = self.audio_output {
+            output.get_config()
+        } else {
+            (48000, 2) // 默认配置
+        };
 
-                    if let Some(packet) = video_pq.pop() {
-                        match decoder.decode(&packet) {
-                            Ok(frames) => {
-                                for frame in frames {
-                                    // ========== Seek 后帧过滤逻辑 ==========
-                                    // 目的：跳过不合适的旧帧，快速定位到 seek 目标位置
-                                    // 返回：should_skip（是否跳过当前帧）
-                                    let should_skip = {
-                                        let mut seek_pos_guard = seek_pos.lock().unwrap();
-                                        if let Some((seek_target, seek_time)) = *seek_pos_guard {
-                                            // --- 超时检测：防止卡在 seek 状态 ---
-                                            if seek_time.elapsed() > Duration::from_secs(2) {
-                                                warn!("{} 🎬 Seek 超时（2秒），强制清除视频seek标志", log_ctx());
-                                                *seek_pos_guard = None;
-                                                false  // 不跳过
-                                            } else {
-                                                // --- 帧 PTS 范围检查 ---
-                                                // 太旧的帧：PTS < 目标 - 1000ms
-                                                // 比音频阈值更宽松，因为视频帧间隔更大（24fps ≈ 42ms/帧）
-                                                if frame.pts < seek_target - 1000 {
-                                                    debug!("🎬 跳过旧视频帧: PTS={}ms < Seek目标={}ms", frame.pts, seek_target);
-                                                    true  // 跳过
-                                                }
-                                                // 太新的帧：PTS > 目标 + 10s（可能是旧的残留帧）
-                                                else if frame.pts > seek_target + 10000 {
-                                                    debug!("🎬 跳过异常视频帧: PTS={}ms > Seek目标+10s={}ms", frame.pts, seek_target + 10000);
-                                                    true  // 跳过
-                                                } else {
-                                                    false  // 在合理范围内，不跳过
-                                                }
-                                            }
-                                        } else {
-                                            false  // 没有 seek，正常处理
-                                        }
-                                    };
-                                    
-                                    // 在释放锁后再执行 continue（避免持有锁时跳转）
-                                    if should_skip {
-                                        continue;
-                                    }
-                                    
-                                    // ========== 推入视频帧队列 ==========
-                                    // 供 UI 线程消费（根据音频时钟选择合适的帧显示）
-                                    debug!("🎬 解码视频帧: PTS={}ms", frame.pts);
-                                    video_fq.push(frame);
-                                }
-                            }
-                            Err(e) => {
-                                match e {
-                                    crate::core::error::PlayerError::FFmpegError(ffmpeg::Error::Eof) => {
-                                        debug!("{} 🎬 解码器返回 EOF（视频），忽略", log_ctx());
-                                    }
-                                    crate::core::error::PlayerError::FFmpegError(ffmpeg::Error::Other { errno: 11 }) => {
-                                        debug!("{} 🎬 解码器返回 EAGAIN（视频），忽略", log_ctx());
-                                    }
-                                    _ => {
-                                        error!("{} ❌ 视频解码失败: {}", log_ctx(), e);
-                                    }
-                                }
-                            }
-                        }
-                    } else {
-                        // 没有包时稍微休眠，避免空转消耗 CPU
-                        thread::sleep(Duration::from_millis(1));
-                    }
-                }
-                info!("🎬 视频解码线程结束");
-            }));
-        }
+        // 预读时的音频流或解码配置与现在不一致（切换了音轨、音频设备的实际配置不同）：回到开头按普通方式解码
+        let output_config = self.audio_output.as_ref().map(|output| output.get_config());
+        let mut prefetched = match prefetched {
+            Some(start) if start.matches(demuxer.audio_stream_index(), output_config) => Some(start),
+            Some(_) => {
+                warn!("{} ⚠️ 预读的音频与当前输出配置不一致，从头重新解码", log_ctx());
+                demuxer.seek(0)?;
+                None
+            }
+            None => None,
+        };
 
-        // 音频解码线程
-        if let Some(mut decoder) = audio_decoder {
-            let audio_pq = audio_packet_queue.clone();
-            let audio_fq = audio_frame_queue.clone();
-            let decode_running = running.clone();
-            let audio_clock = clock.clone();
-            let first_audio_flag = is_first_audio_frame.clone();
-            let seek_pos = self.seek_position.clone();
-            let is_network = self.is_network_source.clone();
+        // 创建视频解码器（自动选择硬件加速；已预读时接管预读的解码器）
+        let video_decoder = match prefetched.as_mut() {
+            Some(start) => self.adopt_video_decoder(demuxer.description(), start.video_decoder.take()),
+            None => self.create_video_decoder(&demuxer)?,
+        };
 
-            self.audio_decode_thread = Some(thread::spawn(move || {
-                info!("🔊 音频解码线程启动");
-                // ==================== 音频解码线程：主时钟源 ====================
-                // 职责：
-                // 1. 解码音频包为音频帧
-                // 2. 作为主时钟源，控制整个播放节奏
-                // 3. Seek后跳过不合适的旧帧
-                // 4. 设置初始音频时钟基准
-                while decode_running.load(Ordering::SeqCst) {
-                    if let Some(packet) = audio_pq.pop() {
-                        debug!("🔊 音频解码线程获取到包，队列剩余: {}", audio_pq.len());
-                        match decoder.decode(&packet) {
-                            Ok(frames) => {
-                                for frame in frames {
-                                    // ========== Seek 后帧过滤逻辑 ==========
-                                    // 目的：跳过不合适的旧帧，快速定位到 seek 目标位置
-                                    // 返回：(should_skip, is_first_valid_frame)
-                                    let (should_skip, is_first_valid_frame) = {
-                                        let mut seek_pos_guard = seek_pos.lock().unwrap();
-                                        if let Some((seek_target, seek_time)) = *seek_pos_guard {
-                                            // --- 超时检测：防止卡在 seek 状态 ---
-                                            if seek_time.elapsed() > Duration::from_secs(2) {
-                                                warn!("{} 🔊 Seek 超时（2秒），强制清除seek标志", log_ctx());
-                                                *seek_pos_guard = None;
-                                                (false, false)  // 不跳过，不是首个有效帧
-                                            } else {
-                                                // --- 帧 PTS 范围检查 ---
-                                                // 太旧的帧：PTS < 目标 - 500ms
-                                                if frame.pts < seek_target - 500 {
-                                                    debug!("🔊 跳过旧音频帧: PTS={}ms < Seek目标={}ms", frame.pts, seek_target);
-                                                    (true, false)  // 跳过
-                                                }
-                                                // 太新的帧：PTS > 目标 + 10s（可能是旧的残留帧）
-                                                else if frame.pts > seek_target + 10000 {
-                                                    debug!("🔊 跳过异常音频帧: PTS={}ms > Seek目标+10s={}ms", frame.pts, seek_target + 10000);
-                                                    (true, false)  // 跳过
-                                                } 
-                                                // 合适的帧：在目标 ±500ms 范围内
-                                                else {
-                                                    info!("🔊 找到 Seek 后的首个有效音频帧: PTS={}ms (目标={}ms)", frame.pts, seek_target);
-                                                    *seek_pos_guard = None;  // 清除 seek 标志
-                                                    (false, true)  // 不跳过，是首个有效帧
-                                                }
-                                            }
-                                        } else {
-                                            (false, false)  // 没有 seek，正常处理
-                                        }
-                                    };
-                                    
-                                    // 在释放锁后再执行 continue（避免持有锁时跳转）
-                                    if should_skip {
-                                        continue;
-                                    }
-                                    
-                                    // ========== 音频时钟基准设置 ==========
-                                    // 分两种场景：
-                                    // 1. Seek 后：时钟已在 seek() 中预设，这里只需清除标志
-                                    // 2. 正常播放开始：使用第一个音频帧的 PTS 作为时钟基准
-                                    
-                                    if is_first_valid_frame {
-                                        // --- Seek 场景 ---
-                                        // seek() 已经设置好时钟，这里只需清除 first_audio_flag
-                                        // 防止后续帧再次设置时钟（避免时钟跳动）
-                                        first_audio_flag.store(false, Ordering::SeqCst);
-                                        debug!("🔊 Seek 后首个有效帧，时钟已由 seek() 设置，清除 first_audio_flag");
-                                    }
-                                    else if first_audio_flag.compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
-                                        // --- 正常播放场景 ---
-                                        // 第一个音频帧，使用其 PTS 作为时钟基准
-                                        // 音频作为主时钟，视频会跟随音频时钟
-                                        info!("🔊 首次音频帧: 设置音频时钟基准 PTS={}ms", frame.pts);
-                                        audio_clock.set_time(frame.pts);
-                                    }
-                                    
-                                    // ========== 推入音频帧队列 ==========
-                                    // 供音频输出线程消费
-                                    audio_fq.push(frame.clone());
-                                    debug!("🔊 音频帧推入队列: PTS={}ms, 队列长度={}", frame.pts, audio_fq.len());
-                                }
-                            }
-                            Err(e) => {
-                                match e {
-                                    crate::core::error::PlayerError::FFmpegError(ffmpeg::Error::Eof) => {
-                                        debug!("{} 🔊 解码器返回 EOF（音频），忽略", log_ctx());
-                                    }
-                                    crate::core::error::PlayerError::FFmpegError(ffmpeg::Error::Other { errno: 11 }) => {
-                                        debug!("{} 🔊 解码器返回 EAGAIN（音频），忽略", log_ctx());
-                                    }
-                                    _ => {
-                                        error!("{} ❌ 音频解码失败: {}", log_ctx(), e);
-                                    }
-                                }
-                            }
-                        }
-                    } else {
-                        debug!("🔊 音频解码线程: 没有包可处理，音频队列长度: {}", audio_pq.len());
-                        thread::sleep(Duration::from_millis(5));
-                    }
+        // 创建音频解码器（使用音频输出的实际配置）
+        let audio_decoder = if let Some(decoder) = prefetched.as_mut().and_then(|start| start.audio_decoder.take()) {
+            Some(decoder)
+        } else if let Some(stream) = demuxer.audio_stream() {
+            Some(AudioDecoder::from_stream_with_config(stream, actual_sample_rate, actual_channels)?)
+        } else {
+            None
+        };
 
-                    // 控制帧队列大小：智能缓冲策略
-                    // 本地文件模式：提前减速，避免队列过大
-                    let is_network_source = is_network.load(Ordering::SeqCst);
-                    
-                    if !is_network_source {
-                        // 本地文件：提前减速控制
-                        let queue_len = audio_fq.len();
-                        const LOCAL_MAX_AUDIO_FRAMES: usize = 80;  // 本地文件最大音频帧（从150降到80）
-                        const LOCAL_AUDIO_HIGH_WATER: usize = 50;  // 高水位：开始减速
-                        
-                        if queue_len > LOCAL_MAX_AUDIO_FRAMES {
-                            // 队列过大，减速解码
-                            thread::sleep(Duration::from_millis(15));
-                        } else if queue_len > LOCAL_AUDIO_HIGH_WATER {
-                            // 接近上限，轻微减速
-                            thread::sleep(Duration::from_millis(5));
-                        }
-                    } else {
-                        // 网络流：使用更大的缓冲
-                        let max_audio_frames = 300;  // 网络流: 300帧（约 6-7 秒，应对网络抖动）
-                        while audio_fq.len() > max_audio_frames && decode_running.load(Ordering::SeqCst) {
-                            thread::sleep(Duration::from_millis(10));
-                        }
-                    }
+        // 创建字幕解码器（失败时继续播放，解封装线程丢弃字幕包）
+        let mut subtitle_decoder = if let Some(stream) = demuxer.subtitle_stream() {
+            match SubtitleDecoder::from_stream(stream) {
+                Ok(decoder) => {
+                    info!("{} 📎 字幕解码器创建成功", log_ctx());
+                    Some(decoder)
                 }
-                info!("🔊 音频解码线程结束");
-            }));
-        }
+                Err(e) => {
+                    warn!("{} ❌ 创建字幕解码器失败: {}，继续播放（无字幕）", log_ctx(), e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
 
-        // 字幕解码线程
-        if let Some(mut decoder) = subtitle_decoder {
-            let subtitle_pq = subtitle_packet_queue.clone();
-            let subtitle_fq = subtitle_frame_queue.clone();
-            let decode_running = running.clone();
+        // 预读的开头交给解码线程（解码线程先处理预读的帧，再接着解码后面的包）
+        let subtitle_stream = demuxer.subtitle_stream_index();
+        let prefetched_frames = match prefetched {
+            Some(start) => self.splice_prefetched(start, subtitle_stream, subtitle_decoder.as_mut()),
+            None => PrefetchedFrames::default(),
+        };
 
-            self.subtitle_decode_thread = Some(thread::spawn(move || {
-                info!("📝 字幕解码线程启动");
-                while decode_running.load(Ordering::SeqCst) {
-                    if let Some(packet) = subtitle_pq.pop() {
-                        debug!("📝 字幕解码线程获取到包，队列剩余: {}", subtitle_pq.len());
-                        match decoder.decode(&packet) {
-                            Ok(frames) => {
-                                for frame in frames {
-                                    subtitle_fq.push(frame.clone());
-                                    debug!("📝 字幕帧推入队列: PTS={}ms, 文本=\"{}\"", frame.pts, frame.text);
-                                }
-                            }
-                            Err(e) => {
-                                error!("{} ❌ 字幕解码失败: {}", log_ctx(), e);
-                            }
-                        }
-                    } else {
-                        thread::sleep(Duration::from_millis(10));
-                    }
+        // 启动 DemuxerThread
+        info!("{} 🚀 启动 DemuxerThread", log_ctx());
+        self.stall_watchdog.reset();
+        let demuxer_thread = DemuxerThread::start(
+            Box::new(demuxer),
+            self.packet_inspector.clone(),
+            self.stall_watchdog.clone(),
+            self.debug_commands.port(DebugTarget::Demuxer),
+            self.loop_control.clone(),
+            self.timeshift,
+        );
+
+        // 启动解码线程
+        self.start_playback_threads(demuxer_thread, video_decoder, audio_decoder, subtitle_decoder, prefetched_frames);
+        Ok(())
+    }
+
+    /// 接管预读的开头：选中字幕流的包交给字幕解码器，返回预读的视频 / 音频帧（由解码线程按解码出的帧处理）
+    fn splice_prefetched(
+        &mut self,
+        start: PrefetchedStart,
+        subtitle_stream: Option<usize>,
+        subtitle_decoder: Option<&mut SubtitleDecoder>,
+    ) -> PrefetchedFrames {
+        info!(
+            "{} ⏭️ 接管预读的开头: 视频 {} 帧 / 音频 {} 帧 / 字幕包 {} 个",
+            log_ctx(),
+            start.frames.video.len(),
+            start.frames.audio.len(),
+            start.subtitle_packets.len()
+        );
+        if let (Some(decoder), Some(index)) = (subtitle_decoder, subtitle_stream) {
+            for packet in start.subtitle_packets.iter().filter(|packet| packet.stream() == index) {
+                match decoder.decode(packet) {
+                    Ok(frames) => frames.into_iter().for_each(|frame| self.subtitle_frame_queue.push(frame)),
+                    Err(e) => error!("{} ❌ 字幕解码失败: {}", log_ctx(), e),
                 }
-                info!("📝 字幕解码线程结束");
-            }));
-        }
-        
-        // 音频输出说明：
-        // AudioOutput 包含 cpal::Stream，不是 Send，无法跨线程传递
-        // 因此音频输出必须在主线程中处理，通过定期调用 update_audio() 方法
-        // 来从 audio_frame_queue 中取出帧并写入 AudioOutput
-        if self.audio_output.is_some() {
-            info!("🔊 音频输出已准备，需要在主线程中定期调用 update_audio() 方法");
-            info!("🔊 音频帧队列已准备，解码线程将推送帧到队列");
+            }
         }
-        
-        // 在主线程的更新循环中处理音频帧
-        // 注意：这需要定期调用 update() 方法来从队列中取出音频帧并写入 AudioOutput
-
-        // 注意：视频渲染需要在主线程或有窗口上下文的线程中进行
-        // 这里我们只是解码,实际渲染需要在 Tauri 的窗口事件循环中处行
-        // 可以通过共享的 video_frame_queue 来获取解码后的帧
+        start.frames
     }
-    
-    /// 启动播放线程（使用 DemuxerThread - 网络流专用）
+
+    /// 启动播放线程
     /// 
-    /// 这个方法专门用于网络流，使用 DemuxerThread 在独立线程中运行 Demuxer
-    /// DemuxerThread 会持续读取 MediaPacket 并发送到 channel
-    fn start_playback_threads_with_demuxer_thread(
+    /// DemuxerThread 在独立线程中运行 Demuxer，持续读取数据包并按类型发送到视频、音频、字幕通道；
+    /// 这里为每个有解码器的流启动解码线程（没有解码器的流的接收端直接 drop）；
+    /// prefetched 为播放列表预读解码出的帧，解码线程先把它们当作解码出的帧处理
+    fn start_playback_threads(
         &mut self,
-        demuxer_thread: crate::player::DemuxerThread,
+        mut demuxer_thread: DemuxerThread,
         video_decoder: Option<VideoDecoder>,
         audio_decoder: Option<AudioDecoder>,
         subtitle_decoder: Option<SubtitleDecoder>,
+        prefetched: PrefetchedFrames,
     ) {
         self.running.store(true, Ordering::SeqCst);
+        self.audio_failure.reset();
+        self.reorder_corrections.store(0, Ordering::Relaxed);
+        self.video_counters.reset();
+        self.video_disabled.store(false, Ordering::SeqCst);
+        self.first_frame.lock().unwrap().restart(video_decoder.is_some());
     
-        info!("{} 🚀 启动播放线程（DemuxerThread 模式）", log_ctx());
+        info!("{} 🚀 启动播放线程", log_ctx());
     
-        // frame queues（保持你原来的 SegQueue）
         let video_frame_queue = self.video_frame_queue.clone();
         let audio_frame_queue = self.audio_frame_queue.clone();
+        let subtitle_frame_queue = self.subtitle_frame_queue.clone();
+        // 帧队列上限按源类型区分：本地文件读取稳定，少缓冲几帧节省内存；网络流多缓冲应对抖动
+        let is_network = self.is_network_source.load(Ordering::SeqCst);
     
         let running = self.running.clone();
         let clock = self.clock.clone();
-        let is_first_audio_frame = self.is_first_audio_frame.clone();
+        let awaiting_clock_frame = self.awaiting_clock_frame.clone();
+        // 没有音频输出（没有音轨或音频设备不可用）时由视频帧驱动时钟，音频解码线程丢弃数据包
+        let has_audio_output = self.audio_output.is_some();
     
         // 保存 demuxer_thread 到 manager，防止被 drop
+        self.demux_end_rx = demuxer_thread.take_end_receiver();
+        self.seek_status = demuxer_thread.seek_status().clone();
         self.demuxer_thread_handle = Some(demuxer_thread);
         
-        // 取出接收端（Receiver 不能 clone，需要移动）
-        let (video_packet_rx, audio_packet_rx) = self.demuxer_thread_handle.as_mut().unwrap().take_receivers();
+        // 取出接收端（移动到各解码线程）
+        let receivers = self.demuxer_thread_handle.as_mut().unwrap().take_receivers();
+        // 解码线程把播放位置写入预读状态，解封装线程据此限制预读
+        let read_ahead = self.demuxer_thread_handle.as_ref().unwrap().read_ahead().clone();
+        let PrefetchedFrames { video: prefetched_video, audio: prefetched_audio, video_counters: prefetched_counters } = prefetched;
     
         // 视频解码线程：使用 recv() 阻塞接收 packet
         if let Some(mut decoder) = video_decoder {
-            let video_rx = video_packet_rx;
+            let mut video_rx = receivers.video;
             let video_fq = video_frame_queue.clone();
             let decode_running = running.clone();
             let video_clock = clock.clone(); // 克隆 clock 供视频解码线程使用
+            let video_clock_frame = awaiting_clock_frame.clone();
+            let video_audio_failure = self.audio_failure.clone();
             let need_flush = self.need_flush_decoders.clone();
             let seek_pos = self.seek_position.clone();
+            let suspended = self.suspended.clone();
+            let pause_gate = self.pause_gate.clone();
+            let corrupt_notice = self.video_corrupt_notice.clone();
+            let hw_fallback = self.hw_fallback.clone();
+            let video_counters = self.video_counters.clone();
+            let video_disabled = self.video_disabled.clone();
+            let paused_seek = self.paused_seek.clone();
+            let mut reorder = FrameReorder::new(decoder.reorder_depth(), self.reorder_corrections.clone());
+            let video_read_ahead = read_ahead.clone();
+            let mut debug = self.debug_commands.port(DebugTarget::VideoDecoder);
+            
+            // 帧队列上限（本地文件基准 12/20 帧，网络流 36/48 帧，高帧率源按帧率放大）
+            let (fps, width, height) = self
+                .get_media_info()
+                .map(|info| (info.fps, info.width, info.height))
+                .unwrap_or((REFERENCE_FPS, 0, 0));
+            let (soft_limit, hard_limit) = if is_network { (36, 48) } else { (12, 20) };
+            let video_queue_soft_limit = scaled_video_queue_limit(soft_limit, fps, width, height);
+            let video_queue_hard_limit = scaled_video_queue_limit(hard_limit, fps, width, height);
+            if video_queue_hard_limit > hard_limit {
+                info!("🎞️  高帧率源 {:.0}fps，视频帧队列上限调整为 {}", fps, video_queue_hard_limit);
+            }
     
+            let crash_path = self.crash_scope_path();
             self.video_decode_thread = Some(thread::spawn(move || {
-                info!("{} 🎬 视频解码线程启动（DemuxerThread 模式）", log_ctx());
+                let _crash_scope = DecodeScope::enter(crash_path);
+                info!("{} 🎬 视频解码线程启动（帧重排序深度 {}）", log_ctx(), reorder.depth());
     
                 let mut video_packet_count: usize = 0;
+                let mut rejected_frames = 0u32;
+                let mut health = DecodeHealth::default();
+                let mut hw_watch = HwErrorWatch::default();
                 let mut decoded_frame_count: usize = 0;
+                let mut seek_filter = SeekFilter::default();  // seek 后筛选帧
                 let mut last_seek_time: Option<Instant> = None; // 记录最后一次 Seek 的时间
                 const SEEK_CLEANUP_DISABLE_DURATION: Duration = Duration::from_millis(500); // Seek 后500ms内禁用队列清理
-                const VIDEO_QUEUE_SOFT_LIMIT: usize = 36;
-                const VIDEO_QUEUE_HARD_LIMIT: usize = 48;
+                // 没有音频输出或音频解码器已失效：筛选后的第一帧视频初始化时钟（暂停状态下 seek 的目标帧也是这一帧）
+                let drive_clock = |pts: i64| {
+                    if !has_audio_output || video_audio_failure.has_failed() {
+                        start_clock_at_frame(&video_clock_frame, &video_clock, pts, "视频");
+                    }
+                };
+                // 按 PTS 顺序送出的帧：seek 后按模式丢弃目标（或关键帧）之前的帧，暂停状态下 seek 的目标帧交给界面，其余放入帧队列
+                let emit = |ready: Vec<VideoFrame>, seek_filter: &mut SeekFilter, decoded_frame_count: &mut usize| {
+                    let ready = ready
+                        .into_iter()
+                        .filter(|frame| seek_filter.accept_video(frame, Instant::now()))
+                        .inspect(|frame| drive_clock(frame.pts));
+                    for frame in ready.filter_map(|frame| paused_seek.offer(frame)) {
+                        *decoded_frame_count += 1;
+                        if *decoded_frame_count <= 5 || *decoded_frame_count % 100 == 0 {
+                            info!("{} 🎬 解码视频帧 #{}: PTS={}ms",log_ctx(), decoded_frame_count, frame.pts);
+                        }
+                        video_fq.push(frame);
+                    }
+                };
+
+                // 播放列表预读的开头：与之后解码出的帧一样经过重排序、seek 筛选和计数
+                if !prefetched_video.is_empty() {
+                    info!("{} ⏭️ 视频解码线程接管预读的 {} 帧", log_ctx(), prefetched_video.len());
+                }
+                video_counters.add(&prefetched_counters);
+                video_counters.record_decoder(decoder.stats());
+                seek_filter.sync(*seek_pos.lock().unwrap());
+                for frame in prefetched_video {
+                    emit(reorder.push(frame), &mut seek_filter, &mut decoded_frame_count);
+                }
     
                 while decode_running.load(Ordering::SeqCst) {
                     // ========== 检查是否需要 flush 解码器 ==========
@@ -1654,52 +2866,81 @@ impl PlaybackManager {
                                 error!("{} ❌ 视频解码器 flush 失败: {}", log_ctx(), e);
                             }
                         }
+                        reorder.clear();
                         // 记录 Seek 时间，用于暂时禁用队列清理
                         last_seek_time = Some(Instant::now());
                     }
+                    // 调试命令（调试模式关闭时只读取一个原子标志）
+                    while let Some(command) = debug.poll() {
+                        if command == DebugCommand::FlushDecoders {
+                            let before = video_fq.len();
+                            match decoder.flush() {
+                                Ok(flushed) => info!("{} 🧪 调试：视频解码器 flush，丢弃 {} 帧（帧队列 {} -> {}）", log_ctx(), flushed.len(), before, video_fq.len()),
+                                Err(e) => warn!("{} ⚠️ 调试：视频解码器 flush 失败: {}", log_ctx(), e),
+                            }
+                            reorder.clear();
+                        }
+                    }
                     
                     // 在取新包前，等待渲染线程消费，避免队列无限增长
-                    while decode_running.load(Ordering::SeqCst) && video_fq.len() >= VIDEO_QUEUE_HARD_LIMIT {
-                        thread::sleep(Duration::from_millis(5));
+                    video_read_ahead.set_position(video_clock.now());
+                    // 队列满时阻塞到渲染线程取帧（取帧、seek 清空队列时唤醒）；会话挂起时：暂停中休眠，播放中轮询
+                    while decode_running.load(Ordering::SeqCst)
+                        && (video_fq.len() >= video_queue_hard_limit || suspended.load(Ordering::SeqCst))
+                    {
+                        if suspended.load(Ordering::SeqCst) {
+                            let parked = pause_gate.park_while("视频解码线程", || {
+                                decode_running.load(Ordering::SeqCst) && suspended.load(Ordering::SeqCst)
+                            });
+                            if !parked {
+                                thread::sleep(Duration::from_millis(5));
+                            }
+                        } else {
+                            video_fq.wait_while(|len| len >= video_queue_hard_limit && decode_running.load(Ordering::SeqCst));
+                        }
+                        video_read_ahead.set_position(video_clock.now());
                     }
 
+                    // 输入中断（文件末尾或数据暂时不足）：先送出重排序缓冲区内的帧，再阻塞等待
+                    let received = match video_rx.try_recv() {
+                        Err(crossbeam_channel::TryRecvError::Empty) => {
+                            // 等待 flush 时缓冲区里的帧已经过时，由下一轮的 flush 清空
+                            if !need_flush.load(Ordering::SeqCst) {
+                                emit(reorder.drain(), &mut seek_filter, &mut decoded_frame_count);
+                            }
+                            video_rx.recv().map_err(|_| ())
+                        }
+                        result => result.map_err(|_| ()),
+                    };
+
                     // 阻塞等待一个包；当发送端被 drop 时 recv() 返回 Err，退出循环
-                    match video_rx.recv() {
+                    match received {
                         Ok(packet) => {
                             video_packet_count += 1;
                             if video_packet_count % 100 == 0 {
                                 debug!("{} 📦 已接收 {} 个视频包", log_ctx(), video_packet_count);
                             }
+
+                            // 视频轨道已损坏、解码器已失效或只播放音频：继续接收并丢弃数据包，避免阻塞解封装线程
+                            if rejected_frames >= CORRUPT_VIDEO_FRAME_LIMIT || health.has_failed() || video_disabled.load(Ordering::Relaxed) {
+                                continue;
+                            }
     
-                            match decoder.decode(&packet) {
+                            debug.delay_frame();
+                            let decoded = decoder.decode(&packet);
+                            video_counters.record(&decoded);
+                            video_counters.record_decoder(decoder.stats());
+                            match decoded {
                                 Ok(frames) => {
+                                    health.record_success();
+                                    hw_watch.record_success();
+                                    reorder.set_depth(decoder.reorder_depth());
+                                    if !frames.is_empty() {
+                                        rejected_frames = 0;
+                                    }
+                                    seek_filter.sync(*seek_pos.lock().unwrap());
                                     for frame in frames {
-                                        // Seek 后帧过滤：跳过太旧的帧
-                                        let should_skip = {
-                                            let seek_pos_guard = seek_pos.lock().unwrap();
-                                            if let Some((seek_target, seek_time)) = *seek_pos_guard {
-                                                // 超时检测
-                                                if seek_time.elapsed() > Duration::from_secs(2) {
-                                                    false // 超时，不再跳过
-                                                } else {
-                                                    // 跳过太旧的帧（PTS < 目标 - 1秒）
-                                                    frame.pts < seek_target - 1000
-                                                }
-                                            } else {
-                                                false
-                                            }
-                                        };
-                                        
-                                        if should_skip {
-                                            debug!("{} 🎬 Seek 后跳过旧视频帧: PTS={}ms", log_ctx(), frame.pts);
-                                            continue;
-                                        }
-                                        
-                                        decoded_frame_count += 1;
-                                        if decoded_frame_count <= 5 || decoded_frame_count % 100 == 0 {
-                                            info!("{} 🎬 解码视频帧 #{}: PTS={}ms",log_ctx(), decoded_frame_count, frame.pts);
-                                        }
-                                        video_fq.push(frame);
+                                        emit(reorder.push(frame), &mut seek_filter, &mut decoded_frame_count);
                                     }
     
                                     // 队列大小控制：通过等待方式做温和背压
@@ -1707,14 +2948,11 @@ impl PlaybackManager {
                                         // Seek 后保护期内不额外等待，尽快填充新帧
                                     } else {
                                         let queue_len = video_fq.len();
-                                        if queue_len >= VIDEO_QUEUE_HARD_LIMIT {
-                                            let mut backoff = 6u64;
-                                            while decode_running.load(Ordering::SeqCst) && video_fq.len() >= VIDEO_QUEUE_SOFT_LIMIT {
-                                                thread::sleep(Duration::from_millis(backoff));
-                                                backoff = (backoff + 2).min(20);
+                                        if queue_len >= video_queue_hard_limit {
+                                            // 阻塞到渲染线程消费回软上限以下
+                                            while decode_running.load(Ordering::SeqCst) && video_fq.len() >= video_queue_soft_limit {
+                                                video_fq.wait_while(|len| len >= video_queue_soft_limit && decode_running.load(Ordering::SeqCst));
                                             }
-                                        } else if queue_len >= VIDEO_QUEUE_SOFT_LIMIT {
-                                            thread::sleep(Duration::from_millis(4));
                                         }
                                     }
                                 }
@@ -1726,8 +2964,19 @@ impl PlaybackManager {
                                         crate::core::error::PlayerError::FFmpegError(ffmpeg::Error::Other { errno: 11 }) => {
                                             debug!("{} 🎬 解码器返回 EAGAIN（视频），忽略", log_ctx());
                                         }
+                                        PlayerError::InvalidFrameSize { width, height } => {
+                                            count_rejected_frame(&mut rejected_frames, &corrupt_notice, width, height);
+                                        }
                                         _ => {
                                             error!("{} ❌ 视频解码失败: {}", log_ctx(), e);
+                                            // 硬件解码连续出错：原地重建为软件解码，由管理器在当前位置重新 seek
+                                            if let Some(reason) = hw_watch.record_error(&mut decoder, &e) {
+                                                reorder.clear();
+                                                health = DecodeHealth::default();
+                                                hw_fallback.report(reason);
+                                            } else if health.record_error(&e) {
+                                                report_video_decoder_failure(&corrupt_notice);
+                                            }
                                         }
                                     }
                                 }
@@ -1747,22 +2996,63 @@ impl PlaybackManager {
     
         // 音频解码线程：audio 为主时钟
         if let Some(mut decoder) = audio_decoder {
-            let audio_rx = audio_packet_rx;
+            let mut audio_rx = receivers.audio;
             let audio_fq = audio_frame_queue.clone();
             let decode_running = running.clone();
             let audio_clock = clock.clone();
-            let first_audio_flag = is_first_audio_frame.clone();
+            let first_audio_flag = awaiting_clock_frame.clone();
             let need_flush = self.need_flush_decoders.clone();
             let seek_pos = self.seek_position.clone();
+            let suspended = self.suspended.clone();
+            let pause_gate = self.pause_gate.clone();
+            let audio_failure = self.audio_failure.clone();
+            let loops = self.loop_control.clone();
+            let audio_read_ahead = read_ahead.clone();
+            // 帧队列上限（本地文件 50/80 帧，网络流 80/120 帧）
+            let (audio_queue_soft_limit, audio_queue_hard_limit) = if is_network { (80, 120) } else { (50, 80) };
+            let mut debug = self.debug_commands.port(DebugTarget::AudioDecoder);
             let mut decoded_frame_count: usize = 0;
 
+            let crash_path = self.crash_scope_path();
             self.audio_decode_thread = Some(thread::spawn(move || {
-                info!("{} 🔊 音频解码线程启动（DemuxerThread 模式）", log_ctx());
+                let _crash_scope = DecodeScope::enter(crash_path);
+                info!("{} 🔊 音频解码线程启动", log_ctx());
+                let mut splicer = AudioSplicer::default();
+                let mut health = DecodeHealth::default();
+                let mut seek_filter = SeekFilter::default();  // seek 后筛选帧
     
                 let mut last_seek_time: Option<Instant> = None; // 记录最后一次 Seek 的时间
                 const SEEK_CLEANUP_DISABLE_DURATION: Duration = Duration::from_millis(500); // Seek 后500ms内禁用队列清理
-                const AUDIO_QUEUE_SOFT_LIMIT: usize = 80;
-                const AUDIO_QUEUE_HARD_LIMIT: usize = 120;
+                let emit = |frame: AudioFrame, seek_filter: &mut SeekFilter, splicer: &mut AudioSplicer, decoded_frame_count: &mut usize| {
+                    // Seek 后帧筛选：精确 seek 丢弃目标之前的帧并裁掉第一帧中目标之前的采样
+                    let pts = frame.pts;
+                    let Some(mut frame) = seek_filter.accept_audio(frame, Instant::now()) else {
+                        debug!("{} 🔊 Seek 后丢弃音频帧: PTS={}ms", log_ctx(), pts);
+                        return;
+                    };
+
+                    // 第一帧音频：初始化时钟（精确 seek 时正好从目标位置开始）
+                    start_clock_at_frame(&first_audio_flag, &audio_clock, frame.pts, "音频");
+                    // 单曲循环拼接处去爆音：按帧中点判断所在的遍数（帧 PTS 取整到毫秒，可能落在上一遍的末尾）
+                    let channels = frame.channels.max(1) as usize;
+                    let frame_ms = (frame.data.len() / channels) as i64 * 1000 / frame.sample_rate.max(1) as i64;
+                    let (pass, _) = loops.wrap(frame.pts + frame_ms / 2);
+                    splicer.process(pass, &mut frame.data, channels, frame.sample_rate);
+
+                    *decoded_frame_count += 1;
+                    if *decoded_frame_count <= 5 || *decoded_frame_count % 100 == 0 {
+                        info!("{} 🕐 解码音频帧 #{}: PTS={}ms",log_ctx(), decoded_frame_count, frame.pts);
+                    }
+                    audio_fq.push(frame);
+                };
+
+                // 播放列表预读的开头：与之后解码出的帧一样经过 seek 筛选（没有音频输出时与数据包一样丢弃）
+                if has_audio_output {
+                    seek_filter.sync(*seek_pos.lock().unwrap());
+                    for frame in prefetched_audio {
+                        emit(frame, &mut seek_filter, &mut splicer, &mut decoded_frame_count);
+                    }
+                }
     
                 while decode_running.load(Ordering::SeqCst) {
                     // ========== 检查是否需要 flush 解码器 ==========
@@ -1782,48 +3072,48 @@ impl PlaybackManager {
                         // 记录 Seek 时间，用于暂时禁用队列清理
                         last_seek_time = Some(Instant::now());
                     }
+                    // 调试命令（调试模式关闭时只读取一个原子标志）
+                    while let Some(command) = debug.poll() {
+                        if command == DebugCommand::FlushDecoders {
+                            let before = audio_fq.len();
+                            match decoder.flush() {
+                                Ok(flushed) => info!("{} 🧪 调试：音频解码器 flush，丢弃 {} 帧（帧队列 {} -> {}）", log_ctx(), flushed.len(), before, audio_fq.len()),
+                                Err(e) => warn!("{} ⚠️ 调试：音频解码器 flush 失败: {}", log_ctx(), e),
+                            }
+                        }
+                    }
                     
-                    while decode_running.load(Ordering::SeqCst) && audio_fq.len() >= AUDIO_QUEUE_HARD_LIMIT {
-                        thread::sleep(Duration::from_millis(5));
+                    audio_read_ahead.set_position(audio_clock.now());
+                    // 队列满时阻塞到音频输出取帧；会话挂起时：暂停中休眠，播放中轮询
+                    while decode_running.load(Ordering::SeqCst)
+                        && (audio_fq.len() >= audio_queue_hard_limit || suspended.load(Ordering::SeqCst))
+                    {
+                        if suspended.load(Ordering::SeqCst) {
+                            let parked = pause_gate.park_while("音频解码线程", || {
+                                decode_running.load(Ordering::SeqCst) && suspended.load(Ordering::SeqCst)
+                            });
+                            if !parked {
+                                thread::sleep(Duration::from_millis(5));
+                            }
+                        } else {
+                            audio_fq.wait_while(|len| len >= audio_queue_hard_limit && decode_running.load(Ordering::SeqCst));
+                        }
+                        audio_read_ahead.set_position(audio_clock.now());
                     }
 
                     match audio_rx.recv() {
                         Ok(packet) => {
+                            // 音频解码器已失效或没有音频输出：继续接收并丢弃数据包，避免阻塞解封装线程
+                            if health.has_failed() || !has_audio_output {
+                                continue;
+                            }
+                            debug.delay_frame();
                             match decoder.decode(&packet) {
                                 Ok(frames) => {
+                                    health.record_success();
+                                    seek_filter.sync(*seek_pos.lock().unwrap());
                                     for frame in frames {
-                                        // Seek 后帧过滤：跳过太旧的帧
-                                        let should_skip = {
-                                            let seek_pos_guard = seek_pos.lock().unwrap();
-                                            if let Some((seek_target, seek_time)) = *seek_pos_guard {
-                                                // 超时检测
-                                                if seek_time.elapsed() > Duration::from_secs(2) {
-                                                    false // 超时，不再跳过
-                                                } else {
-                                                    // 跳过太旧的帧（PTS < 目标 - 500ms）
-                                                    frame.pts < seek_target - 500
-                                                }
-                                            } else {
-                                                false
-                                            }
-                                        };
-                                        
-                                        if should_skip {
-                                            debug!("{} 🔊 Seek 后跳过旧音频帧: PTS={}ms", log_ctx(), frame.pts);  
-                                            continue;
-                                        }
-                                        
-                                        // 第一帧音频：初始化时钟
-                                        if first_audio_flag.compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
-                                            // 使用 frame.pts 初始化时钟（Seek 后时钟已经在 seek() 中设置）
-                                            info!("{} 🕐 音频时钟已初始化（首帧 PTS: {} ms）", log_ctx(), frame.pts);
-                                            audio_clock.set_time(frame.pts);
-                                        }
-                                        decoded_frame_count += 1;
-                                        if decoded_frame_count <= 5 || decoded_frame_count % 100 == 0 {
-                                            info!("{} 🕐 解码音频帧 #{}: PTS={}ms",log_ctx(), decoded_frame_count, frame.pts);
-                                        }
-                                        audio_fq.push(frame);
+                                        emit(frame, &mut seek_filter, &mut splicer, &mut decoded_frame_count);
                                     }
     
                                     // 音频队列大小控制：通过等待方式做温和背压
@@ -1831,14 +3121,11 @@ impl PlaybackManager {
                                         // Seek 后保护期内不额外等待，尽快填充新帧
                                     } else {
                                         let queue_len = audio_fq.len();
-                                        if queue_len >= AUDIO_QUEUE_HARD_LIMIT {
-                                            let mut backoff = 6u64;
-                                            while decode_running.load(Ordering::SeqCst) && audio_fq.len() >= AUDIO_QUEUE_SOFT_LIMIT {
-                                                thread::sleep(Duration::from_millis(backoff));
-                                                backoff = (backoff + 2).min(15);
+                                        if queue_len >= audio_queue_hard_limit {
+                                            // 阻塞到音频输出消费回软上限以下
+                                            while decode_running.load(Ordering::SeqCst) && audio_fq.len() >= audio_queue_soft_limit {
+                                                audio_fq.wait_while(|len| len >= audio_queue_soft_limit && decode_running.load(Ordering::SeqCst));
                                             }
-                                        } else if queue_len >= AUDIO_QUEUE_SOFT_LIMIT {
-                                            thread::sleep(Duration::from_millis(4));
                                         }
                                     }
                                 }
@@ -1852,6 +3139,9 @@ impl PlaybackManager {
                                         }
                                         _ => {
                                             error!("{} ❌ 音频解码失败: {}", log_ctx(), e);
+                                            if health.record_error(&e) {
+                                                report_audio_decoder_failure(&audio_failure);
+                                            }
                                         }
                                     }
                                 }
@@ -1868,17 +3158,45 @@ impl PlaybackManager {
             }));
         }
     
-        // 字幕：暂未改动（和你原来一致）
-        if let Some(_decoder) = subtitle_decoder {
-            warn!("{} ⚠️  DemuxerThread 模式暂不支持字幕解码", log_ctx());
+        // 字幕解码线程（字幕包稀疏，没有包时阻塞在 recv()）
+        if let Some(mut decoder) = subtitle_decoder {
+            let mut subtitle_rx = receivers.subtitle;
+            let subtitle_fq = subtitle_frame_queue.clone();
+            let decode_running = running.clone();
+
+            let crash_path = self.crash_scope_path();
+            self.subtitle_decode_thread = Some(thread::spawn(move || {
+                let _crash_scope = DecodeScope::enter(crash_path);
+                info!("{} 📝 字幕解码线程启动", log_ctx());
+                while decode_running.load(Ordering::SeqCst) {
+                    let Ok(packet) = subtitle_rx.recv() else {
+                        info!("{} 📝 字幕解码线程检测到发送端关闭，准备退出", log_ctx());
+                        break;
+                    };
+                    match decoder.decode(&packet) {
+                        Ok(frames) => {
+                            for frame in frames {
+                                debug!("📝 字幕帧加入待显示字幕: PTS={}ms, 文本=\"{}\"", frame.pts, frame.text);
+                                subtitle_fq.push(frame);
+                            }
+                        }
+                        Err(e) => {
+                            error!("{} ❌ 字幕解码失败: {}", log_ctx(), e);
+                        }
+                    }
+                }
+                info!("{} 📝 字幕解码线程结束", log_ctx());
+            }));
         }
     
-        // 音频输出在主线程中处理（保持原逻辑）
+        // 音频输出说明：
+        // AudioOutput 包含 cpal::Stream，不是 Send，无法跨线程传递
+        // 因此音频输出必须在主线程中处理，通过定期调用 update_audio() 方法从 audio_frame_queue 取帧写入
         if self.audio_output.is_some() {
-            info!("{} 🔊 音频输出已准备（DemuxerThread 模式）", log_ctx());
+            info!("{} 🔊 音频输出已准备，需要在主线程中定期调用 update_audio() 方法", log_ctx());
         }
     
-        info!("{} ✅ 所有播放线程已启动（DemuxerThread 模式）", log_ctx());
+        info!("{} ✅ 所有播放线程已启动", log_ctx());
     }    
     
     /// 打开网络流
@@ -1891,8 +3209,8 @@ impl PlaybackManager {
         // 标记为网络源
         self.is_network_source.store(true, Ordering::SeqCst);
         
-        // 重置首次音频帧标志
-        self.is_first_audio_frame.store(true, Ordering::SeqCst);
+        // 重置首帧时钟标志
+        self.awaiting_clock_frame.store(true, Ordering::SeqCst);
         
         // 重置 seek 位置
         {
@@ -1906,11 +3224,17 @@ impl PlaybackManager {
             state.state = PlaybackState::Opening;
         }
         
+        self.packet_inspector.clear();
+
         // 保存 URL（用于停止后重新播放）
         {
             let mut file_path = self.current_file_path.lock().unwrap();
+            if file_path.as_deref() != Some(url) {
+                self.ab_loop.clear();
+            }
             *file_path = Some(url.to_string());
         }
+        self.playlist.select(url);
         
         // 创建网络流管理器
         let mut stream_manager = NetworkStreamManager::new(url.to_string(), protocol);
@@ -1933,6 +3257,7 @@ impl PlaybackManager {
         // FFmpeg 会自动处理网络协议
         let demuxer = Demuxer::open(url)?;
         let media_info = demuxer.get_media_info()?;
+        self.update_track_lists(&demuxer, &[]);
         
         info!("网络流媒体信息: {:?}", media_info);
         
@@ -1944,102 +3269,129 @@ impl PlaybackManager {
             state.state = PlaybackState::Paused;
         }
         
-        // 创建视频解码器
-        let video_decoder = if let Some(stream) = demuxer.video_stream() {
-            match VideoDecoder::from_stream(stream) {
-                Ok(decoder) => {
-                    info!("视频解码器: {}", decoder.info());
-                    if decoder.is_hardware_accelerated() {
-                        info!("✓ 硬件加速已启用");
-                    }
-                    Some(decoder)
-                }
-                Err(e) => {
-                    info!("硬件解码不可用: {}, 回退到软件解码", e);
-                    let stream = demuxer.video_stream().unwrap();
-                    let decoder = VideoDecoder::from_stream_software(stream)?;
-                    info!("✓ 使用软件解码");
-                    Some(decoder)
-                }
-            }
-        } else {
-            None
-        };
-        
-        // 创建音频输出（先创建，获取实际配置）
-        self.audio_output = if media_info.audio_codec != "none" {
-            match AudioOutput::new(media_info.sample_rate, media_info.channels) {
-                Ok(mut output) => {
-                    output.start()?;
-                    Some(output)
-                }
-                Err(e) => {
-                    error!("{} 创建音频输出失败: {}", log_ctx(), e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
-        
-        // 获取音频输出的实际配置（用于解码器）
-        let (actual_sample_rate, actual_channels) = if let Some(ref output) = self.audio_output {
-            output.get_config()
-        } else {
-            (48000, 2) // 默认配置
-        };
-        
-        // 创建音频解码器（使用音频输出的实际配置）
-        let audio_decoder = if let Some(stream) = demuxer.audio_stream() {
-            Some(AudioDecoder::from_stream_with_config(
-                stream,
-                actual_sample_rate,
-                actual_channels,
-            )?)
-        } else {
-            None
-        };
-        
-        // 创建字幕解码器
-        let subtitle_decoder = if let Some(stream) = demuxer.subtitle_stream() {
-            match SubtitleDecoder::from_stream(stream) {
-                Ok(decoder) => {
-                    info!("字幕解码器创建成功");
-                    Some(decoder)
-                }
-                Err(e) => {
-                    warn!("{} 创建字幕解码器失败: {}，继续播放（无字幕）", log_ctx(), e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
-        
         // 网络流不支持外部字幕
         
+        // 创建解码器，启动 DemuxerThread 和解码线程
+        self.start_pipeline(demuxer, None)?;
+        
         // 保存网络流管理器
         self.network_stream = Some(stream_manager);
         
-        // 启动播放线程
-        self.start_playback_threads(
-            demuxer,
-            video_decoder,
-            audio_decoder,
-            subtitle_decoder,
-        );
-        
         Ok(media_info)
     }
     
-    /// 获取网络流状态（供 UI 使用）
-    pub fn get_stream_state(&self) -> Option<StreamState> {
-        self.stream_state.read().ok()?.clone()
+    /// 获取网络流状态（供 UI 使用）
+    pub fn get_stream_state(&self) -> Option<StreamState> {
+        self.stream_state.read().ok()?.clone()
+    }
+    
+    /// 检查是否正在播放网络流
+    pub fn is_network_stream(&self) -> bool {
+        self.network_stream.is_some()
+    }
+
+    /// 设置预览缓存（与胶片视图共享）
+    pub fn set_preview_cache(&mut self, cache: Arc<PreviewCache>) {
+        self.preview_cache = cache;
+    }
+
+    /// 预览缓存（新建标签页时共享）
+    pub fn preview_cache(&self) -> Arc<PreviewCache> {
+        self.preview_cache.clone()
+    }
+
+    /// 进度条悬停在 time_ms 处的预览（只支持本地视频文件，第一次悬停时启动预览线程）
+    pub fn hover_preview(&self, time_ms: i64) -> HoverPreviewState {
+        if self.is_network_source.load(Ordering::SeqCst) || self.still_image || self.image_sequence.is_some() {
+            return HoverPreviewState::Unavailable;
+        }
+        let Some(path) = self.current_file_path.lock().unwrap().clone().map(PathBuf::from) else {
+            return HoverPreviewState::Unavailable;
+        };
+        let mut hover_preview = self.hover_preview.lock().unwrap();
+        if hover_preview.as_ref().is_none_or(|preview| preview.source() != path) {
+            *hover_preview = None;
+            info!("{} 🖼️ 启动悬停预览: {}", log_ctx(), path.display());
+            *hover_preview = Some(HoverPreview::spawn(path, self.preview_cache.clone()));
+        }
+        match hover_preview.as_mut() {
+            Some(preview) => preview.hover(time_ms, Instant::now()),
+            None => HoverPreviewState::Unavailable,
+        }
+    }
+
+    /// 鼠标离开进度条
+    pub fn hover_preview_leave(&self) {
+        if let Some(preview) = self.hover_preview.lock().unwrap().as_mut() {
+            preview.leave();
+        }
+    }
+    
+    /// 最近一次 seek 的结果（进行中、成功、失败或不支持；尚未 seek 时为 None）
+    pub fn get_last_seek_result(&self) -> Option<SeekResult> {
+        self.seek_status.last()
+    }
+
+    /// 取出尚未提示过的 seek 失败（每次失败只返回一次）
+    pub fn poll_seek_failure(&self) -> Option<SeekResult> {
+        self.seek_status.take_failure()
+    }
+
+    /// 是否为直播流
+    pub fn is_live(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.media_info.as_ref().map(|info| info.is_live).unwrap_or(false)
     }
     
-    /// 检查是否正在播放网络流
-    pub fn is_network_stream(&self) -> bool {
-        self.network_stream.is_some()
+    /// 直播状态（直播边缘、可回看窗口；非直播源为 None）
+    pub fn live_status(&self) -> Option<LiveStatus> {
+        if !self.is_live() {
+            return None;
+        }
+        self.demuxer_thread_handle
+            .as_ref()
+            .and_then(|thread| thread.live_tracker())
+            .map(|tracker| tracker.status())
+    }
+    
+    /// 在可回看窗口内跳到直播边缘（没有窗口时返回 false，需要重新连接才能回到直播）
+    pub fn jump_to_live(&self) -> bool {
+        let Some(status) = self.live_status() else {
+            return false;
+        };
+        // 时移的录制就在本地：精确 seek 到录制末端附近，不停在更早的关键帧上
+        let target = if self.is_timeshifting() {
+            status.window.map(|(start, end)| ((end - TIMESHIFT_EDGE_MARGIN_MS).max(start), SeekMode::Accurate))
+        } else {
+            status.live_seek_target().map(|target_ms| (target_ms, SeekMode::Fast))
+        };
+        let Some((target_ms, mode)) = target else {
+            return false;
+        };
+        info!("{} 🔴 回到直播: {} ms", log_ctx(), target_ms);
+        self.seek(target_ms, mode);
+        true
+    }
+
+    /// 设置直播时移（None 关闭）；下次打开直播时生效
+    pub fn set_timeshift(&mut self, limits: Option<TimeshiftLimits>) {
+        if limits != self.timeshift {
+            match limits {
+                Some(limits) => info!("{} ⏺ 直播时移: 保留 {} 分钟 / {} MB", log_ctx(), limits.max_duration.as_secs() / 60, limits.max_bytes / 1024 / 1024),
+                None => info!("{} ⏺ 直播时移: 关闭", log_ctx()),
+            }
+            self.timeshift = limits;
+        }
+    }
+
+    /// 直播时移设置（None 未开启）
+    pub fn timeshift(&self) -> Option<TimeshiftLimits> {
+        self.timeshift
+    }
+
+    /// 当前直播是否在时移模式（录到临时文件，暂停和回看时从录制中播放）
+    pub fn is_timeshifting(&self) -> bool {
+        self.demuxer_thread_handle.as_ref().is_some_and(DemuxerThread::is_timeshifting)
     }
 }
 
@@ -2051,13 +3403,19 @@ impl Default for PlaybackManager {
 
 impl Drop for PlaybackManager {
     fn drop(&mut self) {
-        // 发送停止信号
+        // 发送停止信号，唤醒等待中的线程
         self.running.store(false, Ordering::SeqCst);
+        self.pause_gate.set_paused(false);
+        self.pause_gate.wake();
+        self.video_frame_queue.wake();
+        self.audio_frame_queue.wake();
         
-        // 等待线程结束
-        if let Some(thread) = self.demux_thread.take() {
-            let _ = thread.join();
+        // 停止解封装线程（关闭包通道后解码线程才能从 recv() 返回）
+        if let Some(mut demuxer_thread) = self.demuxer_thread_handle.take() {
+            demuxer_thread.stop();
         }
+        
+        // 等待线程结束
         if let Some(thread) = self.video_decode_thread.take() {
             let _ = thread.join();
         }
@@ -2070,3 +3428,1094 @@ impl Drop for PlaybackManager {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::audio_sync::RESYNC_THRESHOLD_MS;
+    use crate::player::playlist_prefetch::PREFETCH_VIDEO_MS;
+    use crate::player::debug_commands::DEMUX_STALL;
+    use crate::player::demuxer_source::{DemuxerSource, MediaPacket, PacketType};
+    use crate::player::seamless_loop::{LoopTimeline, SPLICE_THRESHOLD};
+    use crate::player::DemuxerThread;
+    use crate::test_support::{
+        assert_golden_frame, decode_streams, subtitle_asset, subtitle_cue, video_asset, FRAME_DURATION_MS,
+        SAMPLE_RATE, SUBTITLE_CUE_COUNT, TONE_AMPLITUDE,
+    };
+
+    #[test]
+    fn test_corrupt_video_notice_after_consecutive_rejections() {
+        let notice = AtomicBool::new(false);
+        let mut rejected_frames = 0;
+        for _ in 1..CORRUPT_VIDEO_FRAME_LIMIT {
+            count_rejected_frame(&mut rejected_frames, &notice, 0, 0);
+        }
+        assert!(!notice.load(Ordering::SeqCst));
+        count_rejected_frame(&mut rejected_frames, &notice, 65535, 65535);
+        assert!(notice.load(Ordering::SeqCst));
+
+        // 只通知一次
+        notice.store(false, Ordering::SeqCst);
+        count_rejected_frame(&mut rejected_frames, &notice, 0, 0);
+        assert!(!notice.load(Ordering::SeqCst));
+        assert!(rejected_frames > CORRUPT_VIDEO_FRAME_LIMIT);
+    }
+
+    /// 中途失效的音频解码器：每个数据包都返回错误
+    struct FailingAudioDecoder;
+
+    impl FailingAudioDecoder {
+        fn decode(&mut self, _packet: &[u8]) -> Result<Vec<AudioFrame>> {
+            Err(PlayerError::FFmpegError(ffmpeg::Error::InvalidData))
+        }
+    }
+
+    #[test]
+    fn test_audio_decoder_failure_keeps_clock_running() {
+        let mut manager = PlaybackManager::new();
+        manager.state.lock().unwrap().state = PlaybackState::Playing;
+        manager.clock.set_time(5_000);
+        manager.clock.play();
+        manager.audio_frame_queue.push(AudioFrame {
+            pts: 5_000,
+            sample_rate: 48_000,
+            channels: 2,
+            data: vec![0.0; 2_048],
+        });
+
+        // 与音频解码线程相同的失效判定
+        let failure = manager.audio_failure.clone();
+        let decode_thread = thread::spawn(move || {
+            let mut decoder = FailingAudioDecoder;
+            let mut health = DecodeHealth::default();
+            let mut packets = 0;
+            while !health.has_failed() && packets < FATAL_DECODE_ERROR_LIMIT * 2 {
+                packets += 1;
+                match decoder.decode(&[0u8; 16]) {
+                    Ok(_) => health.record_success(),
+                    Err(e) => {
+                        if health.record_error(&e) {
+                            report_audio_decoder_failure(&failure);
+                        }
+                    }
+                }
+            }
+            packets
+        });
+        assert_eq!(decode_thread.join().unwrap(), FATAL_DECODE_ERROR_LIMIT);
+
+        // 主线程：丢弃剩余的音频帧，不阻塞
+        manager.update_audio();
+        assert!(manager.audio_frame_queue.is_empty());
+        assert!(manager.take_audio_failure_notice());
+        assert!(!manager.take_audio_failure_notice());
+
+        // 时钟按墙上时间继续推进，状态仍可读取
+        let before = manager.get_clock_ms();
+        thread::sleep(Duration::from_millis(60));
+        manager.update_audio();
+        assert!(manager.get_clock_ms() >= before + 50, "时钟停止: {} -> {}", before, manager.get_clock_ms());
+        assert_eq!(manager.get_state().state, PlaybackState::Playing);
+
+        // 新管线启动时重置
+        manager.audio_failure.reset();
+        assert!(!manager.audio_failure.has_failed());
+    }
+
+    #[test]
+    fn test_next_audio_track_wraps() {
+        let mut manager = PlaybackManager::new();
+        assert_eq!(manager.next_audio_track(), None);
+
+        let track = |index| TrackInfo { source: TrackSource::Embedded(index), meta: StreamMeta::default() };
+        manager.audio_tracks = vec![track(1)];
+        manager.selected_audio_stream = Some(1);
+        assert_eq!(manager.next_audio_track(), None);
+
+        manager.audio_tracks = vec![track(1), track(3), track(4)];
+        assert_eq!(manager.next_audio_track(), Some(3));
+        manager.selected_audio_stream = Some(4);
+        assert_eq!(manager.next_audio_track(), Some(1));
+    }
+
+    #[test]
+    fn test_subtitles_off_discards_embedded_frames() {
+        let mut manager = PlaybackManager::new();
+        *manager.current_file_path.lock().unwrap() = Some("/videos/a.mkv".to_string());
+        let track = |index| TrackInfo { source: TrackSource::Embedded(index), meta: StreamMeta::default() };
+        manager.subtitle_tracks = vec![track(2), track(3)];
+        manager.selected_subtitle = Some(TrackSource::Embedded(2));
+        let subtitle = |pts| SubtitleFrame { pts, duration: 1_000, text: "字幕".to_string(), end_pts: pts + 1_000 };
+        manager.subtitle_frame_queue.push(subtitle(1_000));
+        assert_eq!(manager.get_current_subtitles(1_500).len(), 1);
+
+        manager.select_subtitle_track(None).unwrap();
+        assert_eq!(manager.current_subtitle_track(), None);
+        assert!(manager.subtitle_frame_queue.is_empty());
+        assert!(manager.select_subtitle_track(Some(TrackSource::Embedded(5))).is_err());
+
+        // 关闭期间解码线程送出的帧不显示、不堆积
+        manager.subtitle_frame_queue.push(subtitle(2_000));
+        assert!(manager.get_current_subtitles(2_500).is_empty());
+        assert!(manager.subtitle_frame_queue.is_empty());
+        assert_eq!(manager.file_memory["/videos/a.mkv"].subtitle, Some(None));
+    }
+
+    #[test]
+    fn test_add_external_subtitle_selects_it_once() {
+        let mut manager = PlaybackManager::new();
+        let file = PathBuf::from("/videos/a.zh.srt");
+        assert!(manager.add_external_subtitle(&file).is_err());
+
+        *manager.current_file_path.lock().unwrap() = Some("/videos/a.mkv".to_string());
+        manager.add_external_subtitle(&file).unwrap();
+        manager.add_external_subtitle(&file).unwrap();
+        assert_eq!(manager.get_subtitle_tracks().len(), 1);
+        assert_eq!(manager.get_subtitle_tracks()[0].meta.codec, "srt");
+        assert_eq!(manager.current_subtitle_track(), Some(&TrackSource::External(file)));
+    }
+
+    #[test]
+    fn test_subtitle_offset_shifts_display_range() {
+        let mut manager = PlaybackManager::new();
+        let subtitle = |pts| SubtitleFrame { pts, duration: 1_000, text: "字幕".to_string(), end_pts: pts + 1_000 };
+        manager.selected_subtitle = Some(TrackSource::External(PathBuf::from("/videos/a.srt")));
+        *manager.external_subtitle_frames.lock().unwrap() = vec![subtitle(10_000)];
+
+        // 推后 500ms：10.0s 的字幕在 10.5s - 11.5s 显示
+        manager.set_subtitle_offset_ms(500);
+        assert!(manager.get_current_subtitles(10_200).is_empty());
+        assert_eq!(manager.get_current_subtitles(11_200).len(), 1);
+
+        // 内嵌字幕同样生效；提前 1s：10.0s 的字幕在 9.0s - 10.0s 显示
+        manager.set_subtitle_offset_ms(-1_000);
+        manager.selected_subtitle = Some(TrackSource::Embedded(2));
+        manager.subtitle_frame_queue.push(subtitle(10_000));
+        assert_eq!(manager.get_current_subtitles(9_100).len(), 1);
+        assert!(manager.get_current_subtitles(10_100).is_empty());
+
+        // seek 后保持，超出范围时夹紧
+        manager.seek(3_000, SeekMode::Accurate);
+        assert_eq!(manager.subtitle_offset_ms(), -1_000);
+        manager.set_subtitle_offset_ms(-120_000);
+        assert_eq!(manager.subtitle_offset_ms(), -SUBTITLE_OFFSET_LIMIT_MS);
+        manager.set_subtitle_offset_ms(0);
+        assert_eq!(manager.subtitle_offset_ms(), 0);
+    }
+
+    /// 每次读包都返回同一结果的模拟解封装源
+    struct MockSource {
+        read: fn() -> Result<Option<MediaPacket>>,
+        media_info: MediaInfo,
+    }
+
+    impl DemuxerSource for MockSource {
+        fn read_packet(&mut self) -> Result<Option<MediaPacket>> {
+            (self.read)()
+        }
+        fn seek(&mut self, _timestamp_ms: i64) -> Result<()> {
+            Ok(())
+        }
+        fn get_media_info(&self) -> &MediaInfo {
+            &self.media_info
+        }
+        fn video_stream_index(&self) -> Option<usize> {
+            Some(0)
+        }
+        fn audio_stream_index(&self) -> Option<usize> {
+            None
+        }
+        fn description(&self) -> String {
+            "mock".to_string()
+        }
+    }
+
+    const MOCK_URL: &str = "http://example.com/live.ts";
+
+    /// 用模拟源启动播放（网络源，没有解码线程）
+    fn attach_mock_source(manager: &mut PlaybackManager, read: fn() -> Result<Option<MediaPacket>>) {
+        manager.is_network_source.store(true, Ordering::SeqCst);
+        *manager.current_file_path.lock().unwrap() = Some(MOCK_URL.to_string());
+        let source = MockSource { read, media_info: MediaInfo::default() };
+        let demuxer_thread = DemuxerThread::start(
+            Box::new(source),
+            manager.packet_inspector.clone(),
+            manager.stall_watchdog.clone(),
+            manager.debug_commands.port(DebugTarget::Demuxer),
+            manager.loop_control.clone(),
+            None,
+        );
+        manager.start_playback_threads(demuxer_thread, None, None, None, PrefetchedFrames::default());
+    }
+
+    /// 等待解封装线程报告结束原因，返回 (界面事件, 结束原因)
+    fn wait_demux_end(manager: &mut PlaybackManager) -> (Option<DemuxEvent>, Option<DemuxEnd>) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let event = manager.poll_demux_end();
+            let end = manager.demux_end.lock().unwrap().clone();
+            if event.is_some() || end.is_some() || Instant::now() > deadline {
+                return (event, end);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn network_timeout() -> Result<Option<MediaPacket>> {
+        Err(PlayerError::FFmpegError(ffmpeg::Error::Other { errno: ffmpeg::util::error::ETIMEDOUT }))
+    }
+
+    fn invalid_data() -> Result<Option<MediaPacket>> {
+        Err(PlayerError::FFmpegError(ffmpeg::Error::InvalidData))
+    }
+
+    fn video_packet() -> Result<Option<MediaPacket>> {
+        Ok(Some(MediaPacket { packet: ffmpeg::Packet::empty(), packet_type: PacketType::Video, stream_index: 0 }))
+    }
+
+    #[test]
+    fn test_demux_eof_finishes_playback() {
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, || Ok(None));
+
+        assert_eq!(wait_demux_end(&mut manager), (Some(DemuxEvent::Finished), Some(DemuxEnd::Eof)));
+        assert!(manager.is_source_exhausted());
+
+        // seek 后 DemuxerThread 继续读包，不再视为播放完毕
+        manager.seek(0, SeekMode::Accurate);
+        assert!(!manager.is_source_exhausted());
+        manager.stop();
+    }
+
+    #[test]
+    fn test_exhausted_source_enters_ended_state() {
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, || Ok(None));
+        manager.state.lock().unwrap().media_info = Some(MediaInfo { duration: 5_000, ..MediaInfo::default() });
+        assert_eq!(wait_demux_end(&mut manager).1, Some(DemuxEnd::Eof));
+
+        // 暂停中不进入 Ended
+        assert!(!manager.update_ended());
+        manager.play().unwrap();
+        assert!(manager.update_ended());
+        assert!(manager.is_ended());
+        assert!(!manager.is_playing());
+        assert!(!manager.update_ended());
+        // 时钟停在时长处
+        assert_eq!(manager.get_position_ms(), 5_000);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(manager.get_position_ms(), 5_000);
+        manager.pause();
+        assert!(manager.is_ended());
+
+        // DemuxerThread 仍在等待命令：seek 后回到暂停状态
+        manager.seek(0, SeekMode::Accurate);
+        assert_eq!(manager.get_state().state, PlaybackState::Paused);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_mute_keeps_volume_and_survives_stop() {
+        let mut manager = PlaybackManager::new();
+        manager.set_volume(0.7);
+        assert!(manager.toggle_mute());
+        assert!(manager.is_muted());
+        assert_eq!(manager.output_volume(), 0.0);
+        // 静音期间调整的音量在取消静音后生效
+        manager.set_volume(1.4);
+        manager.stop();
+        assert!(manager.is_muted());
+        assert!(!manager.toggle_mute());
+        assert_eq!(manager.output_volume(), 1.4);
+    }
+
+    #[test]
+    fn test_audio_device_choice_is_kept_without_output() {
+        let mut manager = PlaybackManager::new();
+        // 没有打开文件时只记录选择，下次创建音频输出时使用
+        manager.set_audio_device(Some("USB Headset".to_string())).unwrap();
+        assert_eq!(manager.audio_device.as_deref(), Some("USB Headset"));
+        assert!(manager.audio_output.is_none());
+        manager.set_audio_device(None).unwrap();
+        assert_eq!(manager.audio_device, None);
+        // 没有音频输出时不做检查
+        manager.audio_device_checked = Instant::now() - AUDIO_DEVICE_POLL;
+        manager.check_audio_device();
+        assert!(manager.audio_output.is_none());
+    }
+
+    #[test]
+    fn test_seek_chapter_uses_chapter_starts() {
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, || Ok(None));
+        assert_eq!(manager.seek_chapter(true), None);
+
+        let chapter = |start_ms, end_ms| Chapter { start_ms, end_ms, title: String::new() };
+        manager.chapters = vec![chapter(0, 60_000), chapter(60_000, 120_000)];
+        manager.clock.set_time(30_000);
+        assert_eq!(manager.seek_chapter(true), Some(1));
+        assert_eq!(manager.get_position_ms(), 60_000);
+        assert_eq!(manager.seek_chapter(true), None);
+        assert_eq!(manager.seek_chapter(false), Some(0));
+        assert_eq!(manager.get_position_ms(), 0);
+        assert_eq!(manager.seek_chapter(false), None);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_seek_keyframe_steps_through_index() {
+        use crate::player::keyframe_index::{Keyframe, KeyframeIndex};
+        use std::sync::OnceLock;
+
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, || Ok(None));
+        // 索引尚未完成：不 seek
+        let shared: SharedKeyframeIndex = Arc::new(OnceLock::new());
+        manager.keyframe_index = Some(shared.clone());
+        manager.clock.set_time(2_500);
+        assert_eq!(manager.seek_keyframe(true), None);
+        assert_eq!(manager.get_position_ms(), 2_500);
+
+        let mut index = KeyframeIndex::new();
+        for pts_ms in [0, 2_000, 4_000, 6_000] {
+            index.push(Keyframe { pts_ms, pos: pts_ms * 10 });
+        }
+        shared.set(index).unwrap();
+        assert_eq!(manager.seek_keyframe(true), Some(4_000));
+        assert_eq!(manager.get_position_ms(), 4_000);
+        assert_eq!(manager.seek_keyframe(false), Some(2_000));
+        // 播放中刚经过的关键帧不算上一个
+        manager.clock.set_time(2_200);
+        assert_eq!(manager.seek_keyframe(false), Some(0));
+        assert_eq!(manager.seek_keyframe(false), None);
+        manager.clock.set_time(6_000);
+        assert_eq!(manager.seek_keyframe(true), None);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_ab_loop_seeks_back_to_a_after_crossing_b() {
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, || Ok(None));
+        manager.clock.set_time(1_000);
+        manager.mark_ab_loop_a();
+        manager.clock.set_time(900);
+        assert!(manager.mark_ab_loop_b().is_err());
+        manager.clock.set_time(3_000);
+        assert_eq!(manager.mark_ab_loop_b().unwrap(), AbLoopMark::Range(1_000, 3_000));
+
+        manager.clock.set_time(2_900);
+        assert!(!manager.update_ab_loop());
+        manager.clock.set_time(3_050);
+        assert!(manager.update_ab_loop());
+        assert_eq!(manager.get_position_ms(), 1_000);
+
+        // 用户 seek 到 B 点之后不被拉回
+        manager.seek(5_000, SeekMode::Accurate);
+        assert!(!manager.update_ab_loop());
+        manager.clock.set_time(5_100);
+        assert!(!manager.update_ab_loop());
+
+        assert!(manager.set_loop_range(Some((4_000, 4_000))).is_err());
+        manager.set_loop_range(None).unwrap();
+        assert_eq!(manager.loop_range(), None);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_speed_is_clamped_and_drives_clock() {
+        let mut manager = PlaybackManager::new();
+        manager.set_speed(4.0);
+        assert_eq!(manager.speed(), 2.0);
+        manager.set_speed(0.1);
+        assert_eq!(manager.speed(), 0.5);
+
+        manager.set_speed(2.0);
+        manager.clock.set_time(1_000);
+        manager.clock.play();
+        thread::sleep(Duration::from_millis(100));
+        let advanced = manager.clock.now() - 1_000;
+        assert!((180..400).contains(&advanced), "{}", advanced);
+        manager.set_speed(1.0);
+        assert_eq!(manager.speed(), 1.0);
+    }
+
+    /// 测试用的 2x2 视频帧
+    fn tiny_frame(pts: i64) -> VideoFrame {
+        VideoFrame { pts, duration: 0, width: 2, height: 2, format: PixelFormat::RGBA, planes: None, data: vec![0; 16].into() }
+    }
+
+    #[test]
+    fn test_suspend_keeps_presented_frame_as_poster() {
+        let mut manager = PlaybackManager::new();
+        manager.running.store(true, Ordering::SeqCst);
+        manager.video_frame_queue.push(tiny_frame(0));
+        let (shown, _) = manager.take_newest_frame_until(0);
+        manager.notify_frame_presented(&shown.unwrap());
+        // 下一帧已经取出等待显示（预读位置），挂起时不应作为海报帧
+        manager.video_frame_queue.push(tiny_frame(40));
+        manager.take_newest_frame_until(20);
+        assert!(manager.video_lookahead.lock().unwrap().is_some());
+
+        manager.suspend();
+        assert_eq!(manager.poster_frame.as_ref().map(|frame| frame.pts), Some(0));
+        assert!(manager.video_lookahead.lock().unwrap().is_none());
+        manager.running.store(false, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_take_newest_frame_until_merges_due_frames() {
+        let manager = PlaybackManager::new();
+        for pts in [0, 40, 80, 120] {
+            manager.video_frame_queue.push(tiny_frame(pts));
+        }
+
+        // 时钟之前的帧合并为最新一帧，之后的帧留待下次（放在预读位置，不回到队列）
+        let take = |clock_ms| {
+            let (frame, merged) = manager.take_newest_frame_until(clock_ms);
+            (frame.map(|frame| frame.pts), merged)
+        };
+        assert_eq!(take(90), (Some(80), 2));
+        assert_eq!(take(100), (None, 0));
+        assert_eq!(take(120), (Some(120), 0));
+        assert_eq!(take(1_000), (None, 0));
+    }
+
+    #[test]
+    fn test_take_newest_frame_until_keeps_up_with_content_faster_than_display() {
+        // 120fps 内容在 60Hz 显示器上：每次刷新时钟推进约 16.7ms，取出最新一帧并合并中间的一帧
+        let manager = PlaybackManager::new();
+        let frame_ms = 1000.0 / 120.0;
+        for index in 0..120 {
+            manager.video_frame_queue.push(tiny_frame((index as f64 * frame_ms).round() as i64));
+        }
+
+        let refresh_ms = 1000.0 / 60.0;
+        let mut presented = Vec::new();
+        let mut merged_total = 0;
+        for refresh in 1..=30 {
+            let clock_ms = (refresh as f64 * refresh_ms).round() as i64;
+            let (frame, merged) = manager.take_newest_frame_until(clock_ms);
+            let frame = frame.expect("每次刷新都应有到期的帧");
+            assert!(clock_ms - frame.pts < frame_ms.ceil() as i64, "时钟 {}ms 显示的帧 PTS={}ms", clock_ms, frame.pts);
+            presented.push(frame.pts);
+            merged_total += merged;
+        }
+        // 画面始终追上时钟，30 次刷新消耗 60 帧（一半合并）
+        assert!(presented.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(presented.len() + merged_total, 61);
+        assert!((29..=31).contains(&merged_total), "合并 {} 帧", merged_total);
+    }
+
+    #[test]
+    fn test_scaled_video_queue_limit() {
+        // 参考帧率及以下不放大（帧率未知时同样按基准）
+        assert_eq!(scaled_video_queue_limit(12, 24.0, 1920, 1080), 12);
+        assert_eq!(scaled_video_queue_limit(12, REFERENCE_FPS, 1920, 1080), 12);
+        assert_eq!(scaled_video_queue_limit(12, 0.0, 1920, 1080), 12);
+        assert_eq!(scaled_video_queue_limit(12, f64::NAN, 1920, 1080), 12);
+        // 高帧率按帧率放大，覆盖相同的时长
+        assert_eq!(scaled_video_queue_limit(12, 60.0, 1920, 1080), 24);
+        assert_eq!(scaled_video_queue_limit(20, 120.0, 640, 360), 80);
+        // 超出内存预算时按预算截断，但不低于基准
+        assert_eq!(scaled_video_queue_limit(20, 120.0, 1920, 1080), VIDEO_QUEUE_BYTE_BUDGET / (1920 * 1080 * 4));
+        let uhd_budget = VIDEO_QUEUE_BYTE_BUDGET / (3840 * 2160 * 4);
+        assert_eq!(scaled_video_queue_limit(12, 120.0, 3840, 2160), uhd_budget.max(12));
+        assert_eq!(scaled_video_queue_limit(20, 240.0, 7680, 4320), 20);
+    }
+
+    #[test]
+    fn test_frame_step_forward_presents_next_queued_frame() {
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, || Ok(None));
+        manager.state.lock().unwrap().media_info = Some(MediaInfo { fps: 25.0, ..MediaInfo::default() });
+        manager.play().unwrap();
+        manager.notify_frame_presented(&tiny_frame(1_000));
+        for pts in [960, 1_040, 1_080] {
+            manager.video_frame_queue.push(tiny_frame(pts));
+        }
+
+        // 播放中按下：先暂停，跳过早于当前画面的帧
+        assert!(manager.step_frame_forward());
+        assert!(!manager.is_playing());
+        // 上一步显示前不再步进
+        assert!(!manager.step_frame_forward());
+        let frame = manager.take_paused_seek_frame().expect("没有步进的帧");
+        assert_eq!(frame.pts, 1_040);
+        assert_eq!(manager.get_position_ms(), 1_040);
+        manager.notify_frame_presented(&frame);
+
+        // 后退一帧：seek 到上一帧之前，等待解码线程送出上一帧
+        assert!(manager.step_frame_backward());
+        assert!(manager.is_paused_seek_pending());
+        assert_eq!(manager.get_position_ms(), 980);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_playlist_advances_after_eof_and_keeps_index_across_seek_and_stop() {
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, || Ok(None));
+        manager.set_playlist(vec![MOCK_URL.to_string(), "/videos/next.mkv".to_string()]);
+        assert_eq!(manager.playlist().current(), Some(0));
+        assert!(!manager.playlist_advance_due());
+
+        assert_eq!(wait_demux_end(&mut manager).1, Some(DemuxEnd::Eof));
+        assert!(manager.playlist_advance_due());
+        // 单曲循环时不前进
+        manager.set_repeat_one(true);
+        assert!(!manager.playlist_advance_due());
+        manager.set_repeat_one(false);
+
+        manager.seek(0, SeekMode::Accurate);
+        manager.stop();
+        assert_eq!(manager.playlist().current(), Some(0));
+        assert_eq!(manager.next_playlist_item().as_deref(), Some("/videos/next.mkv"));
+
+        // 最后一项：不循环时没有下一项并停止，循环时回到第一项
+        assert!(!manager.play_next().unwrap());
+        assert!(manager.is_idle());
+        assert_eq!(manager.playlist().current(), Some(1));
+        manager.set_playlist_repeat(RepeatMode::All);
+        assert_eq!(manager.next_playlist_item().as_deref(), Some(MOCK_URL));
+    }
+
+    #[test]
+    fn test_demux_network_timeout_requests_reconnect() {
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, network_timeout);
+
+        let reconnect = DemuxEvent::Reconnect { url: MOCK_URL.to_string(), position_ms: 0, attempt: 1 };
+        assert_eq!(wait_demux_end(&mut manager), (Some(reconnect), Some(DemuxEnd::NetworkTimeout)));
+        assert_eq!(manager.get_state().state, PlaybackState::Buffering);
+        assert_eq!(manager.get_stream_state(), Some(StreamState::Reconnecting { attempt: 1 }));
+        assert!(!manager.is_source_exhausted());
+
+        // 短时间内连续中断：超过重连次数后停止并提示
+        manager.network_interruptions = Some((Instant::now(), MAX_RECONNECT_ATTEMPTS));
+        manager.stop();
+        attach_mock_source(&mut manager, network_timeout);
+        let (event, _) = wait_demux_end(&mut manager);
+        assert_eq!(event, Some(DemuxEvent::Failed("网络连接中断".to_string())));
+        assert_eq!(manager.get_state().state, PlaybackState::Error);
+    }
+
+    #[test]
+    fn test_demux_io_error_stops_with_notice() {
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, invalid_data);
+        let message = PlayerError::FFmpegError(ffmpeg::Error::InvalidData).to_string();
+
+        let (event, _) = wait_demux_end(&mut manager);
+        assert_eq!(event, Some(DemuxEvent::Failed(message.clone())));
+        assert_eq!(manager.get_state().state, PlaybackState::Error);
+        assert_eq!(manager.get_stream_state(), Some(StreamState::Failed { reason: message }));
+        assert!(manager.demuxer_thread_handle.is_none());
+        assert!(!manager.is_source_exhausted());
+    }
+
+    #[test]
+    fn test_demux_cancelled_has_no_event() {
+        // 没有解码线程时 packet 接收端已关闭，发送失败按停止处理
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, video_packet);
+
+        assert_eq!(wait_demux_end(&mut manager), (None, Some(DemuxEnd::Cancelled)));
+        assert_eq!(manager.get_stream_state(), None);
+        assert!(!manager.is_source_exhausted());
+        manager.stop();
+    }
+
+    #[test]
+    fn test_debug_demux_stall_reports_stall_then_recovery() {
+        // 文件末尾：解封装线程每 100ms 读一次包，读包阶段执行调试命令
+        let mut manager = PlaybackManager::new();
+        attach_mock_source(&mut manager, || Ok(None));
+        manager.set_debug_commands_enabled(true);
+
+        let before = manager.get_clock_ms();
+        manager.debug_command(DebugCommand::ClockJump(5_000));
+        assert!(manager.get_clock_ms() >= before + 5_000);
+
+        manager.debug_command(DebugCommand::StallDemuxer(DEMUX_STALL));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut events = Vec::new();
+        while events.len() < 2 && Instant::now() < deadline {
+            events.extend(manager.poll_stall());
+            thread::sleep(Duration::from_millis(20));
+        }
+        // 停滞期间报告 Stalled，读包恢复后报告 Recovered
+        match events[..] {
+            [StallEvent::Stalled { waited }, StallEvent::Recovered { stalled_for }] => {
+                assert!(waited < DEMUX_STALL, "停滞结束后才报告: {:?}", waited);
+                assert!(stalled_for >= DEMUX_STALL, "停滞时间: {:?}", stalled_for);
+            }
+            _ => panic!("事件顺序错误: {:?}", events),
+        }
+        assert_eq!(manager.poll_stall(), None);
+
+        // 调试模式关闭后命令不再执行
+        manager.set_debug_commands_enabled(false);
+        manager.debug_command(DebugCommand::StallDemuxer(DEMUX_STALL));
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(manager.poll_stall(), None);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_external_subtitle_cue_active_at_midpoint() {
+        let mut manager = PlaybackManager::new();
+        let path = subtitle_asset().to_path_buf();
+        manager.load_external_subtitles(&path);
+        manager.selected_subtitle = Some(TrackSource::External(path));
+
+        for n in 0..SUBTITLE_CUE_COUNT {
+            let (start_ms, end_ms, text) = subtitle_cue(n);
+            let active = manager.get_current_subtitles((start_ms + end_ms) / 2);
+            assert_eq!(active.len(), 1, "第 {} 条字幕中点处应只有一条字幕", n);
+            assert_eq!(active[0].text, text);
+
+            // 字幕之间的空隙不显示
+            assert!(manager.get_current_subtitles(end_ms).is_empty());
+        }
+    }
+
+    /// 按界面的方式推进播放：更新音频输出，取出到期的视频帧并通知已呈现，返回期间呈现的帧
+    fn present_for(manager: &mut PlaybackManager, duration: Duration) -> Vec<VideoFrame> {
+        let deadline = Instant::now() + duration;
+        let mut presented = Vec::new();
+        while Instant::now() < deadline {
+            manager.update_audio();
+            if let (Some(frame), _) = manager.take_newest_frame_until(manager.get_clock_ms()) {
+                manager.notify_frame_presented(&frame);
+                presented.push(frame);
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        presented
+    }
+
+    /// 有音频输出时，正在播放的音频与时钟的偏差不超过重新对齐的阈值（加一帧的呈现间隔）
+    fn assert_audio_aligned(manager: &PlaybackManager) {
+        if manager.audio_output.is_some() {
+            let offset = manager.av_offset_ms().expect("播放中没有测量到正在播放的音频");
+            assert!(
+                offset.abs() <= RESYNC_THRESHOLD_MS + FRAME_DURATION_MS,
+                "音画偏差 {}ms",
+                offset
+            );
+        } else {
+            // 音频设备不可用：音频包被丢弃，时钟由视频帧驱动
+            assert!(manager.audio_frame_queue.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_pause_resume_keeps_av_alignment() {
+        let (reference_video, reference_audio) = decode_streams(video_asset());
+        let reference_pts: Vec<i64> = reference_video.iter().map(|frame| frame.pts).collect();
+        let mut manager = PlaybackManager::new();
+        manager.open_file(&video_asset().to_string_lossy()).expect("无法打开测试视频");
+        manager.play().unwrap();
+
+        let before = present_for(&mut manager, Duration::from_millis(1_500));
+        let last = before.last().expect("播放中没有呈现视频帧");
+        assert_audio_aligned(&manager);
+        manager.pause();
+        let paused_at = manager.get_clock_ms();
+        assert!(
+            (0..=2 * FRAME_DURATION_MS).contains(&(paused_at - last.pts)),
+            "暂停于 {}ms，最后呈现的帧 PTS={}ms",
+            paused_at,
+            last.pts
+        );
+        assert_golden_frame(last, (last.pts / FRAME_DURATION_MS) as u32, 1);
+
+        // 暂停期间时钟不前进，不再呈现暂停位置之后的帧，音频输出缓冲区为空
+        let during_pause = present_for(&mut manager, Duration::from_millis(300));
+        assert_eq!(manager.get_clock_ms(), paused_at);
+        assert!(during_pause.iter().all(|frame| frame.pts <= paused_at), "暂停期间呈现了后面的帧");
+        assert_eq!(manager.audio_output.as_ref().map_or(0, |output| output.buffer_size()), 0);
+        assert_eq!(manager.av_offset_ms(), None);
+
+        // 暂停位置有声音（测试正弦音，RMS = 幅度 / √2），恢复后应从这里继续
+        let audio = reference_audio
+            .iter()
+            .find(|frame| (frame.pts..frame.pts + frame.data.len() as i64 * 1000 / SAMPLE_RATE as i64).contains(&paused_at))
+            .expect("暂停位置没有音频帧");
+        let rms = (audio.data.iter().map(|s| s * s).sum::<f32>() / audio.data.len() as f32).sqrt();
+        assert!((rms - TONE_AMPLITUDE / 2f32.sqrt()).abs() < 0.05, "音频 RMS 异常: {}", rms);
+
+        // 恢复后从暂停位置继续：第一帧是暂停前最后一帧之后的一两帧，之后的帧按解码顺序连续且与时钟一致
+        manager.play().unwrap();
+        let after = present_for(&mut manager, Duration::from_millis(800));
+        let first = after.first().expect("恢复播放后没有呈现视频帧");
+        let index_of = |frame: &VideoFrame| {
+            reference_pts
+                .binary_search(&frame.pts)
+                .unwrap_or_else(|_| panic!("呈现的帧 PTS={}ms 不在解码结果中", frame.pts))
+        };
+        let skipped = index_of(first) as i64 - index_of(last) as i64;
+        assert!(
+            (1..=2).contains(&skipped),
+            "恢复后第一帧 PTS={}ms，暂停前最后一帧 PTS={}ms",
+            first.pts,
+            last.pts
+        );
+        assert!(after.windows(2).all(|pair| index_of(&pair[0]) < index_of(&pair[1])), "恢复后帧顺序错乱");
+        for frame in &after {
+            assert_golden_frame(frame, (frame.pts / FRAME_DURATION_MS) as u32, 1);
+        }
+        let resumed_for = manager.get_clock_ms() - paused_at;
+        assert!((500..=1_200).contains(&resumed_for), "恢复播放 800ms 后时钟前进了 {}ms", resumed_for);
+        assert!(manager.get_clock_ms() - after.last().unwrap().pts <= 2 * FRAME_DURATION_MS);
+        assert_audio_aligned(&manager);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_playlist_transition_splices_prefetched_start() {
+        let (reference_video, _) = decode_streams(video_asset());
+        let dir = std::env::temp_dir().join(format!("myy_prefetch_{}", process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let items: Vec<String> = ["first.mp4", "second.mp4"]
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                std::fs::copy(video_asset(), &path).unwrap();
+                path.to_string_lossy().into_owned()
+            })
+            .collect();
+
+        let mut manager = PlaybackManager::new();
+        manager.set_playlist(items.clone());
+        manager.open_file(&items[0]).expect("无法打开测试视频");
+        manager.play().unwrap();
+        // 测试视频只有 10 秒：打开后就在结尾 PREFETCH_LEAD_MS 以内，开始预读下一项
+        manager.update_playlist_prefetch();
+        assert_eq!(manager.prefetch.as_ref().map(PlaylistPrefetch::path), Some(items[1].as_str()));
+        manager.seek(9_000, SeekMode::Accurate);
+
+        // 按界面的方式播放到第一项结束
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !manager.playlist_advance_due() {
+            assert!(Instant::now() < deadline, "第一项没有播放完毕");
+            manager.poll_demux_end();
+            manager.update_playlist_prefetch();
+            manager.update_audio();
+            if let (Some(frame), _) = manager.take_newest_frame_until(manager.get_clock_ms()) {
+                manager.notify_frame_presented(&frame);
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        // 切换到下一项：预读已完成，解码线程接管预读的开头（不重新打开、不重新解码）
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !manager.prefetch.as_ref().unwrap().is_finished() {
+            assert!(Instant::now() < deadline, "下一项的预读没有完成");
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(manager.play_next().unwrap());
+        assert!(manager.prefetch.is_none());
+        let prefetched_frames = (PREFETCH_VIDEO_MS / FRAME_DURATION_MS) as usize;
+        // 预读的帧经过解码线程的计数，按 PTS 顺序、逐帧连续地进入帧队列，从下一项的第一帧开始
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while manager.video_frame_queue.len() < prefetched_frames {
+            assert!(Instant::now() < deadline, "帧队列中只有 {} 帧", manager.video_frame_queue.len());
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(manager.video_counters.snapshot().frames >= prefetched_frames as u64);
+        let queued: Vec<i64> = manager.video_frame_queue.with_items(|frames| frames.iter().map(|frame| frame.pts).collect());
+        assert_eq!(queued[0], reference_video[0].pts);
+        assert!(
+            queued.windows(2).all(|pair| pair[1] - pair[0] == FRAME_DURATION_MS),
+            "帧队列中的 PTS 不连续: {:?}",
+            queued
+        );
+        let first = loop {
+            manager.update_audio();
+            if let (Some(frame), _) = manager.take_newest_frame_until(manager.get_clock_ms()) {
+                manager.notify_frame_presented(&frame);
+                break frame;
+            }
+            assert!(Instant::now() < deadline, "下一项没有呈现视频帧");
+            thread::sleep(Duration::from_millis(1));
+        };
+        assert_golden_frame(&first, (first.pts / FRAME_DURATION_MS) as u32, 1);
+
+        // 接管后继续播放：帧连续、音画对齐
+        let after = present_for(&mut manager, Duration::from_millis(500));
+        assert!(!after.is_empty());
+        for frame in &after {
+            assert_golden_frame(frame, (frame.pts / FRAME_DURATION_MS) as u32, 1);
+        }
+        assert_audio_aligned(&manager);
+        manager.stop();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_safe_mode_skips_hw_decode_and_subtitle_autoload() {
+        // 视频旁边放一个同名外部字幕：正常模式下会自动选择
+        let dir = std::env::temp_dir().join(format!("myy_safe_mode_{}", process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let video = dir.join("clip.mp4");
+        std::fs::copy(video_asset(), &video).unwrap();
+        std::fs::copy(subtitle_asset(), dir.join("clip.srt")).unwrap();
+        let video = video.to_string_lossy().into_owned();
+
+        let mut manager = PlaybackManager::new();
+        manager.open_file(&video).expect("无法打开测试视频");
+        assert!(matches!(manager.current_subtitle_track(), Some(TrackSource::External(_))));
+        manager.stop();
+
+        manager.set_runtime_flags(RuntimeFlags::SAFE_MODE);
+        assert!(!manager.hw_decode_allowed());
+        manager.open_file(&video).expect("无法打开测试视频");
+        assert_eq!(manager.current_subtitle_track(), None);
+        assert!(manager.get_subtitle_tracks().iter().any(|track| matches!(track.source, TrackSource::External(_))));
+        assert!(manager.hw_fallback_reason().is_none());
+        manager.stop();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// 等待暂停状态下 seek 的目标帧（期间按界面的方式更新音频输出）
+    fn wait_paused_seek_frame(manager: &mut PlaybackManager) -> VideoFrame {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            manager.update_audio();
+            if let Some(frame) = manager.take_paused_seek_frame() {
+                return frame;
+            }
+            assert!(Instant::now() < deadline, "暂停状态下 seek 后没有送出目标帧");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_paused_seek_presents_target_frame() {
+        // 测试视频只有 10 秒：在 2 秒处暂停，再跳到 8 秒
+        let mut manager = PlaybackManager::new();
+        manager.open_file(&video_asset().to_string_lossy()).expect("无法打开测试视频");
+        manager.pause();
+        manager.seek(2_000, SeekMode::Accurate);
+        let paused_frame = wait_paused_seek_frame(&mut manager);
+        assert!((paused_frame.pts - 2_000).abs() <= FRAME_DURATION_MS);
+
+        let target_ms = 8_000;
+        manager.seek(target_ms, SeekMode::Accurate);
+        assert!(manager.is_paused_seek_pending());
+        let frame = wait_paused_seek_frame(&mut manager);
+        assert!((frame.pts - target_ms).abs() <= FRAME_DURATION_MS, "目标帧 PTS={}ms", frame.pts);
+        assert_golden_frame(&frame, (target_ms / FRAME_DURATION_MS) as u32, 1);
+
+        // 保持暂停，时钟对齐到显示的帧，音频始终没有输出
+        assert_eq!(manager.get_state().state, PlaybackState::Paused);
+        assert_eq!(manager.get_clock_ms(), frame.pts);
+        assert!(!manager.is_paused_seek_pending());
+        assert_eq!(manager.audio_output.as_ref().map_or(0, |output| output.buffer_size()), 0);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_seek_skips_packets_read_before_seek() {
+        // 先跳到 8 秒让包通道装满后面的数据，再立即跳回 1 秒：通道里的旧包不能解码出画面
+        let mut manager = PlaybackManager::new();
+        manager.open_file(&video_asset().to_string_lossy()).expect("无法打开测试视频");
+        manager.pause();
+        manager.seek(8_000, SeekMode::Accurate);
+        manager.seek(1_000, SeekMode::Accurate);
+        let frame = wait_paused_seek_frame(&mut manager);
+        assert!((frame.pts - 1_000).abs() <= FRAME_DURATION_MS, "目标帧 PTS={}ms", frame.pts);
+
+        // 之后解码出的帧从目标位置连续向后
+        thread::sleep(Duration::from_millis(200));
+        let queued: Vec<i64> = manager.video_frame_queue.with_items(|items| items.iter().map(|frame| frame.pts).collect());
+        assert!(!queued.is_empty());
+        assert!(queued.iter().all(|pts| (1_000..4_000).contains(pts)), "帧队列: {:?}", queued);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_local_file_eof_then_seek_returns_to_paused() {
+        let mut manager = PlaybackManager::new();
+        manager.open_file(&video_asset().to_string_lossy()).expect("无法打开测试视频");
+        manager.play().unwrap();
+        manager.seek(9_000, SeekMode::Fast);
+
+        // 读到末尾、帧队列播完后进入 Ended（测试中直接丢弃帧代替界面和音频输出消费）
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut finished = false;
+        while !manager.is_ended() {
+            assert!(Instant::now() < deadline, "没有进入播放完毕状态");
+            finished |= manager.poll_demux_end() == Some(DemuxEvent::Finished);
+            manager.video_frame_queue.clear();
+            manager.audio_frame_queue.clear();
+            manager.update_ended();
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(finished);
+        assert!(manager.demuxer_thread_handle.is_some());
+
+        // 解封装线程仍在等待命令：seek 后回到暂停状态并送出目标位置的画面
+        manager.seek(3_000, SeekMode::Accurate);
+        assert_eq!(manager.get_state().state, PlaybackState::Paused);
+        assert_eq!(*manager.demux_end.lock().unwrap(), None);
+        let frame = wait_paused_seek_frame(&mut manager);
+        assert!((frame.pts - 3_000).abs() <= FRAME_DURATION_MS, "目标帧 PTS={}ms", frame.pts);
+        manager.stop();
+    }
+
+    #[test]
+    fn test_stop_joins_pipeline_and_allows_reopen() {
+        let mut manager = PlaybackManager::new();
+        let path = video_asset().to_string_lossy().to_string();
+        manager.open_file(&path).expect("无法打开测试视频");
+        manager.play().unwrap();
+        thread::sleep(Duration::from_millis(200));
+        manager.stop();
+
+        assert!(manager.demuxer_thread_handle.is_none());
+        assert!(manager.video_decode_thread.is_none() && manager.audio_decode_thread.is_none());
+        assert!(manager.video_frame_queue.is_empty() && manager.audio_frame_queue.is_empty());
+        assert_eq!(manager.get_state().state, PlaybackState::Stopped);
+        assert_eq!(manager.get_clock_ms(), 0);
+
+        // 停止后重新打开：新的管线正常工作
+        manager.open_file(&path).expect("无法重新打开测试视频");
+        manager.pause();
+        manager.seek(5_000, SeekMode::Accurate);
+        let frame = wait_paused_seek_frame(&mut manager);
+        assert!((frame.pts - 5_000).abs() <= FRAME_DURATION_MS, "目标帧 PTS={}ms", frame.pts);
+        manager.stop();
+    }
+
+    /// 等待视频帧队列中出现帧，返回队首帧的 PTS
+    fn wait_first_queued_pts(manager: &PlaybackManager) -> i64 {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(pts) = manager.video_frame_queue.with_items(|frames| frames.front().map(|frame| frame.pts)) {
+                return pts;
+            }
+            assert!(Instant::now() < deadline, "没有解码出视频帧");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_video_drives_clock_without_audio_output() {
+        // 音频设备不可用：有音轨但没有音频输出
+        let mut manager = PlaybackManager::new();
+        let path = video_asset().to_string_lossy().to_string();
+        let demuxer = Demuxer::open(&path).expect("无法打开测试视频");
+        *manager.current_file_path.lock().unwrap() = Some(path);
+        let video_decoder = manager.create_video_decoder(&demuxer).unwrap();
+        let audio_decoder = AudioDecoder::from_stream_with_config(demuxer.audio_stream().unwrap(), SAMPLE_RATE as u32, 1)
+            .expect("无法创建音频解码器");
+        assert!(manager.audio_output.is_none());
+        let demuxer_thread = DemuxerThread::start(
+            Box::new(demuxer),
+            manager.packet_inspector.clone(),
+            manager.stall_watchdog.clone(),
+            manager.debug_commands.port(DebugTarget::Demuxer),
+            manager.loop_control.clone(),
+            None,
+        );
+        manager.start_playback_threads(demuxer_thread, video_decoder, Some(audio_decoder), None, PrefetchedFrames::default());
+
+        // 第一帧视频初始化时钟，音频帧不进入队列（否则队列满后会卡住解封装线程）
+        let first_pts = wait_first_queued_pts(&manager);
+        assert_eq!(manager.clock.now(), first_pts);
+        assert!(manager.audio_frame_queue.is_empty());
+
+        // 快速 seek 从关键帧开始：时钟对齐到 seek 后的第一帧，而不是停在预设的目标位置
+        manager.seek(4_500, SeekMode::Fast);
+        let keyframe_pts = wait_first_queued_pts(&manager);
+        assert!(keyframe_pts <= 4_500, "seek 后第一帧 PTS={}ms", keyframe_pts);
+        assert_eq!(manager.clock.now(), keyframe_pts);
+        assert!(manager.audio_frame_queue.is_empty());
+
+        manager.stop();
+    }
+
+    #[test]
+    fn test_sidecar_subtitle_loads_on_open_and_survives_seek() {
+        // 视频旁边的同名 .srt 打开时自动选中
+        let dir = std::env::temp_dir().join(format!("myy_sidecar_subtitle_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let video = dir.join("clip.mp4");
+        let subtitle = dir.join("clip.srt");
+        std::fs::copy(video_asset(), &video).unwrap();
+        std::fs::copy(subtitle_asset(), &subtitle).unwrap();
+
+        let mut manager = PlaybackManager::new();
+        manager.open_file(&video.to_string_lossy()).expect("无法打开测试视频");
+        assert_eq!(manager.current_subtitle_track(), Some(&TrackSource::External(subtitle.clone())));
+        let (start_ms, end_ms, text) = subtitle_cue(SUBTITLE_CUE_COUNT - 1);
+        manager.seek(start_ms, SeekMode::Accurate);
+        let active = manager.get_current_subtitles((start_ms + end_ms) / 2);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].text, text);
+
+        // 停止时清空外部字幕缓存
+        manager.stop();
+        assert!(manager.get_current_subtitles((start_ms + end_ms) / 2).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_seamless_loop_audio_is_continuous() {
+        let mut demuxer = Demuxer::open(&video_asset().to_string_lossy()).expect("无法打开测试视频");
+        let audio_index = demuxer.audio_stream_index().expect("测试视频缺少音频流");
+        let mut decoder = AudioDecoder::from_stream_with_config(demuxer.audio_stream().unwrap(), SAMPLE_RATE as u32, 1)
+            .expect("无法创建音频解码器");
+        let loops = LoopControl::new();
+        let mut timeline = LoopTimeline::new(demuxer.stream_start_us(audio_index));
+        let mut splicer = AudioSplicer::default();
+
+        // 与解封装线程相同：读到末尾时回到开头，之后的数据包加上一遍的时长（共两遍）
+        let mut frames = Vec::new();
+        let mut boundary = None;
+        let mut passes = 0;
+        while passes < 2 {
+            let Some((mut packet, _, _)) = demuxer.read_packet().expect("读取数据包失败") else {
+                let span_us = timeline.span_us().expect("没有读到音频包");
+                demuxer.seek(0).expect("回到开头失败");
+                timeline.wrap();
+                loops.set_timeline(timeline.start_us(), span_us);
+                boundary.get_or_insert(frames.len());
+                passes += 1;
+                continue;
+            };
+            if packet.stream() != audio_index {
+                continue;
+            }
+            if let Some(end_us) = demuxer.packet_end_us(&packet) {
+                timeline.observe(end_us);
+            }
+            demuxer.shift_packet(&mut packet, timeline.offset_us());
+            for mut frame in decoder.decode(&packet).expect("音频解码失败") {
+                let frame_ms = frame.data.len() as i64 * 1000 / SAMPLE_RATE as i64;
+                let (pass, _) = loops.wrap(frame.pts + frame_ms / 2);
+                splicer.process(pass, &mut frame.data, 1, SAMPLE_RATE as u32);
+                frames.push(frame);
+            }
+        }
+        let boundary = boundary.unwrap();
+        assert!(boundary > 0 && frames.len() > boundary);
+
+        // 时间轴单调递增，循环点没有空档
+        assert!(frames.windows(2).all(|pair| pair[1].pts >= pair[0].pts), "循环后时间戳回退");
+        let last = &frames[boundary - 1];
+        let last_end = last.pts + last.data.len() as i64 * 1000 / SAMPLE_RATE as i64;
+        assert!(frames[boundary].pts - last_end <= 2, "循环点存在空档: {}ms -> {}ms", last_end, frames[boundary].pts);
+        assert_eq!(loops.wrap(frames[boundary].pts).0, 1);
+
+        // 循环点附近的波形没有超过正弦音正常斜率的跳变
+        let natural_step = TONE_AMPLITUDE * 2.0 * std::f32::consts::PI * 1000.0 / SAMPLE_RATE as f32;
+        let mut joint: Vec<f32> = last.data.iter().rev().take(1).copied().collect();
+        joint.extend(frames[boundary].data.iter().take(SAMPLE_RATE as usize / 100));
+        let max_step = joint.windows(2).map(|pair| (pair[1] - pair[0]).abs()).fold(0.0, f32::max);
+        assert!(max_step <= natural_step + SPLICE_THRESHOLD, "循环点波形不连续: {} > {}", max_step, natural_step);
+    }
+}
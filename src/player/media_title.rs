@@ -0,0 +1,305 @@
+// 媒体显示标题（容器标题标签 / .nfo 侧车文件）
+//
+// 发布组命名的文件名（S01E03.1080p.WEB-DL.x265-GROUP.mkv）不适合直接显示。依次尝试容器的全局 title 标签、
+// 与媒体文件同目录的 Kodi 风格 .nfo 文件中的 <title>，都没有时由调用方退回文件名。
+// .nfo 只做宽松的标签查找（不引入 XML 解析库），超过 MAX_NFO_BYTES 或格式错误时忽略，不影响打开媒体。
+
+use log::{debug, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// .nfo 文件大小上限（超过时不读取）
+pub const MAX_NFO_BYTES: u64 = 1024 * 1024;
+
+/// 文件夹级 .nfo 文件名（电影文件夹中常见）
+const FOLDER_NFO_NAMES: [&str; 1] = ["movie.nfo"];
+
+/// 选择显示标题：容器标题优先，其次 .nfo（read_sidecar 关闭或不是本地文件时不读取）
+pub fn resolve_title(embedded: Option<&str>, media_path: &str, read_sidecar: bool) -> Option<String> {
+    if let Some(title) = embedded.map(normalize_whitespace).filter(|title| !title.is_empty()) {
+        return Some(title);
+    }
+    if !read_sidecar || media_path.contains("://") {
+        return None;
+    }
+    let nfo = find_nfo(Path::new(media_path))?;
+    read_nfo_title(&nfo)
+}
+
+/// 查找媒体文件对应的 .nfo（同名优先，其次文件夹级的 movie.nfo）
+pub fn find_nfo(media_path: &Path) -> Option<PathBuf> {
+    let same_name = media_path.with_extension("nfo");
+    let folder = media_path.parent()?;
+    std::iter::once(same_name)
+        .chain(FOLDER_NFO_NAMES.iter().map(|name| folder.join(name)))
+        .find(|path| path.is_file())
+}
+
+/// 读取 .nfo 中的标题（文件过大、无法读取或没有标题时为 None）
+pub fn read_nfo_title(path: &Path) -> Option<String> {
+    let size = fs::metadata(path).ok()?.len();
+    if size > MAX_NFO_BYTES {
+        warn!("⚠️  .nfo 文件过大（{} 字节），忽略: {}", size, path.display());
+        return None;
+    }
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("⚠️  读取 .nfo 失败: {} ({})", path.display(), e);
+            return None;
+        }
+    };
+    let title = parse_nfo_title(&decode_text(&bytes));
+    debug!("🏷️ .nfo 标题: {:?} ({})", title, path.display());
+    title
+}
+
+/// 按 BOM 识别 UTF-8 / UTF-16，没有 BOM 且不是合法 UTF-8 时按 Latin-1 解码
+pub fn decode_text(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8_lossy(rest).into_owned();
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return decode_utf16(rest, u16::from_le_bytes);
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return decode_utf16(rest, u16::from_be_bytes);
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| to_unit([pair[0], pair[1]]));
+    char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+}
+
+/// 从 Kodi 风格的 .nfo 中组合标题
+/// - 剧集（episodedetails）：「剧名 - S01E03 - 单集标题」，缺少的部分省略
+/// - 电影（movie）：「标题 (年份)」
+/// - 其他：<title> 原样
+pub fn parse_nfo_title(text: &str) -> Option<String> {
+    let lower = text.to_ascii_lowercase();
+    let root = ["episodedetails", "movie", "tvshow", "musicvideo"]
+        .into_iter()
+        .filter_map(|name| find_open_tag(&lower, name, 0).map(|(start, _)| (start, name)))
+        .min()
+        .map(|(_, name)| name);
+    let title = tag_text(text, &lower, "title");
+
+    match root {
+        Some("episodedetails") => {
+            let show = tag_text(text, &lower, "showtitle");
+            let season = tag_text(text, &lower, "season").and_then(|s| s.parse::<u32>().ok());
+            let episode = tag_text(text, &lower, "episode").and_then(|e| e.parse::<u32>().ok());
+            let number = season.zip(episode).map(|(season, episode)| format!("S{:02}E{:02}", season, episode));
+            let parts: Vec<String> = [show, number, title].into_iter().flatten().collect();
+            (!parts.is_empty()).then(|| parts.join(" - "))
+        }
+        Some("movie") => {
+            let year = tag_text(text, &lower, "year").filter(|year| year.len() == 4 && year.bytes().all(|b| b.is_ascii_digit()));
+            match (title, year) {
+                (Some(title), Some(year)) if !title.ends_with(&format!("({})", year)) => Some(format!("{} ({})", title, year)),
+                (title, _) => title,
+            }
+        }
+        _ => title,
+    }
+}
+
+/// 查找开始标签（`<name>` 或带属性的 `<name ...>`），返回 (标签起点, 内容起点)
+/// lower 为 ASCII 小写后的全文（字节位置与原文一致）
+fn find_open_tag(lower: &str, name: &str, from: usize) -> Option<(usize, usize)> {
+    let pattern = format!("<{}", name);
+    let mut search = from;
+    while let Some(offset) = lower[search..].find(&pattern) {
+        let start = search + offset;
+        let after = start + pattern.len();
+        match lower.as_bytes().get(after) {
+            Some(b'>') => return Some((start, after + 1)),
+            Some(b) if b.is_ascii_whitespace() => {
+                let end = lower[after..].find('>')? + after;
+                // 自闭合标签没有内容
+                if lower.as_bytes()[end - 1] != b'/' {
+                    return Some((start, end + 1));
+                }
+                search = end;
+            }
+            _ => search = after,
+        }
+    }
+    None
+}
+
+/// 第一个非空的标签内容（去除 CDATA、解码实体、合并空白）
+fn tag_text(text: &str, lower: &str, name: &str) -> Option<String> {
+    let close = format!("</{}>", name);
+    let mut search = 0;
+    while let Some((_, content_start)) = find_open_tag(lower, name, search) {
+        let content_end = lower[content_start..].find(&close)? + content_start;
+        let raw = text[content_start..content_end].trim();
+        let raw = raw
+            .strip_prefix("<![CDATA[")
+            .and_then(|inner| inner.strip_suffix("]]>"))
+            .map(str::to_string)
+            .unwrap_or_else(|| decode_entities(raw));
+        let value = normalize_whitespace(&raw);
+        if !value.is_empty() {
+            return Some(value);
+        }
+        search = content_end + close.len();
+    }
+    None
+}
+
+/// 解码 XML 实体（预定义实体和数字字符引用，无法识别的原样保留）
+fn decode_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPISODE_NFO: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes" ?>
+<episodedetails>
+    <title>The Rains of Castamere</title>
+    <showtitle>Game of Thrones</showtitle>
+    <season>3</season>
+    <episode>9</episode>
+    <uniqueid type="tvdb" default="true">4517466</uniqueid>
+    <actor>
+        <name>Peter Dinklage</name>
+        <role>Tyrion Lannister</role>
+    </actor>
+</episodedetails>
+"#;
+
+    const MOVIE_NFO: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<movie>
+    <title>Spirited Away</title>
+    <originaltitle>千と千尋の神隠し</originaltitle>
+    <set>
+        <name>Studio Ghibli</name>
+    </set>
+    <year>2001</year>
+    <plot><![CDATA[A girl wanders into a world of spirits.]]></plot>
+</movie>
+"#;
+
+    #[test]
+    fn test_episode_nfo() {
+        assert_eq!(
+            parse_nfo_title(EPISODE_NFO).as_deref(),
+            Some("Game of Thrones - S03E09 - The Rains of Castamere")
+        );
+        // 只有单集标题
+        let minimal = "<episodedetails><title>Pilot</title></episodedetails>";
+        assert_eq!(parse_nfo_title(minimal).as_deref(), Some("Pilot"));
+    }
+
+    #[test]
+    fn test_movie_nfo_with_entities_and_cdata() {
+        assert_eq!(parse_nfo_title(MOVIE_NFO).as_deref(), Some("Spirited Away (2001)"));
+
+        let nfo = "<movie>\n<title lang=\"en\">  Fast &amp; Furious\n 6 </title><year>2013</year></movie>";
+        assert_eq!(parse_nfo_title(nfo).as_deref(), Some("Fast & Furious 6 (2013)"));
+        let nfo = "<movie><title><![CDATA[Tom & Jerry]]></title></movie>";
+        assert_eq!(parse_nfo_title(nfo).as_deref(), Some("Tom & Jerry"));
+        // 标题中已经带年份时不重复
+        let nfo = "<MOVIE><TITLE>Heat (1995)</TITLE><YEAR>1995</YEAR></MOVIE>";
+        assert_eq!(parse_nfo_title(nfo).as_deref(), Some("Heat (1995)"));
+    }
+
+    #[test]
+    fn test_malformed_nfo_is_ignored() {
+        // 只有刮削链接的 .nfo、发布组的 ASCII 字符画、空标题、未闭合的标签
+        assert_eq!(parse_nfo_title("https://www.themoviedb.org/movie/129"), None);
+        assert_eq!(parse_nfo_title("  ▄▄▄ GROUP ▄▄▄\n Release: S01E03.1080p.WEB-DL"), None);
+        assert_eq!(parse_nfo_title("<movie><title>  </title><title/></movie>"), None);
+        assert_eq!(parse_nfo_title("<movie><title>Broken"), None);
+        assert_eq!(parse_nfo_title("<movie><titles>x</titles></movie>"), None);
+    }
+
+    #[test]
+    fn test_decode_text_encodings() {
+        assert_eq!(decode_text(b"\xEF\xBB\xBF<title>A</title>"), "<title>A</title>");
+        let utf16: Vec<u8> = [0xFF, 0xFE].into_iter().chain("<title>千</title>".encode_utf16().flat_map(u16::to_le_bytes)).collect();
+        assert_eq!(parse_nfo_title(&decode_text(&utf16)).as_deref(), Some("千"));
+        let utf16_be: Vec<u8> = [0xFE, 0xFF].into_iter().chain("<title>B</title>".encode_utf16().flat_map(u16::to_be_bytes)).collect();
+        assert_eq!(decode_text(&utf16_be), "<title>B</title>");
+        // Latin-1（ISO-8859-1）
+        assert_eq!(parse_nfo_title(&decode_text(b"<title>Am\xE9lie</title>")).as_deref(), Some("Amélie"));
+    }
+
+    #[test]
+    fn test_resolve_title_prefers_embedded() {
+        let dir = std::env::temp_dir().join(format!("myy_media_title_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let media = dir.join("S03E09.1080p.WEB-DL.x265-GROUP.mkv");
+        let media_path = media.to_string_lossy().to_string();
+
+        // 只有容器标题
+        assert_eq!(resolve_title(Some(" Embedded  Title "), &media_path, true).as_deref(), Some("Embedded Title"));
+        assert_eq!(resolve_title(None, &media_path, true), None);
+
+        fs::write(media.with_extension("nfo"), EPISODE_NFO).unwrap();
+        assert_eq!(resolve_title(Some("Embedded"), &media_path, true).as_deref(), Some("Embedded"));
+        assert_eq!(
+            resolve_title(Some(""), &media_path, true).as_deref(),
+            Some("Game of Thrones - S03E09 - The Rains of Castamere")
+        );
+        // 关闭侧车读取、网络地址
+        assert_eq!(resolve_title(None, &media_path, false), None);
+        assert_eq!(resolve_title(None, "http://example.com/S03E09.mkv", true), None);
+
+        // 同名 .nfo 不存在时使用文件夹级 movie.nfo；超过大小上限的忽略
+        fs::remove_file(media.with_extension("nfo")).unwrap();
+        fs::write(dir.join("movie.nfo"), MOVIE_NFO).unwrap();
+        assert_eq!(resolve_title(None, &media_path, true).as_deref(), Some("Spirited Away (2001)"));
+        fs::write(dir.join("movie.nfo"), " ".repeat(MAX_NFO_BYTES as usize) + MOVIE_NFO).unwrap();
+        assert_eq!(resolve_title(None, &media_path, true), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,25 +1,61 @@
 // 播放器核心模块
 
-pub mod demuxer;
-pub mod demuxer_source;  // 新增：Demuxer 抽象接口
-pub mod demuxer_thread;  // 新增：Demuxer 线程管理
-pub mod demuxer_factory; // 新增：Demuxer 工厂（异步创建）
+pub(crate) mod demuxer;
+pub(crate) mod demuxer_source;  // 新增：Demuxer 抽象接口
+pub(crate) mod demuxer_thread;  // 新增：Demuxer 线程管理
+pub(crate) mod demuxer_factory; // 新增：Demuxer 工厂（异步创建）
+pub mod demux_end;       // 解封装结束原因（文件末尾、网络中断、读取错误）
+pub(crate) mod read_ahead;      // 解封装预读限制（按媒体时间）
+pub mod stall_watchdog;  // 解封装停滞监视（读包长时间没有返回）
+pub(crate) mod decode_failure;  // 解码器中途失效时的降级播放（无声 / 仅音频）
 pub mod decoder;
+pub(crate) mod decoder_fallback;  // 硬件解码回退到软件解码（原因提示、按文件记录、播放中重建）
+pub mod first_frame;  // 首帧检测（视频流存在但始终解码不出画面）
+pub(crate) mod frame_reorder;  // 视频帧按显示顺序送出（B 帧重排序校正）
+pub(crate) mod paused_seek;  // 暂停状态下的 seek（跳转后立即显示目标位置的画面）
+pub(crate) mod pause_gate;  // 暂停时解封装/解码线程休眠（继续播放、seek 时唤醒）
+pub(crate) mod blocking_queue;  // 线程间交接数据包 / 帧的阻塞队列（替代 sleep 轮询）
+pub mod seek_filter;  // Seek 后的帧筛选（快速 / 精确 seek）
+pub mod seek_status;  // Seek 结果（解封装线程上报，界面读取）
 pub mod hw_decoder;
 // pub mod renderer;  // 暂时注释，后续版本实现
-pub mod audio_output;
+pub(crate) mod audio_output;
+pub mod audio_tempo;  // 变速不变调（WSOLA，0.5x - 2.0x）
+pub mod loudness;  // 响度均衡（按平均响度缓慢调整增益）
+pub mod clock_skew;  // 音频设备时钟偏差补偿（设备实际采样率与标称值不一致）
+pub mod audio_sync;  // 按实际播放的音频校正播放时钟（输出卡顿、欠载时画面跟随声音）
+pub mod subtitle_queue;  // 内嵌字幕按开始时间排序存放（按播放时间查询，插入时丢弃过期字幕）
 pub mod manager;
-pub mod external_subtitle;
-pub mod network_stream;
+pub mod crash_marker;  // 解码崩溃标记（下次打开该文件时提示以安全模式打开）
+pub(crate) mod external_subtitle;
+pub(crate) mod network_stream;
+pub mod packet_inspector;  // 数据包检查器（开发者面板）
+pub mod debug_commands;  // 调试命令（开发者面板中手动清空队列、模拟停滞等）
+pub mod position_history;  // 播放位置记录（断电安全）
+pub(crate) mod keyframe_index;  // 关键帧索引（后台扫描，加速 Seek）
+pub mod live;  // 直播流状态（直播延迟、可回看窗口）
+pub mod timeshift;  // 直播时移（录到临时文件，暂停和回看时从录制中播放）
+pub mod seamless_loop;  // 单曲循环（短文件无缝循环）
+pub mod ab_loop;  // A/B 循环（在两个标记点之间反复播放）
+pub mod playlist;  // 播放列表（上一个 / 下一个，播放完毕自动播放下一项）
+pub(crate) mod playlist_prefetch;  // 播放列表下一项预读（当前项接近结尾时解码下一项的开头，切换时直接接管）
+pub(crate) mod hdr;  // HDR 格式兼容性检测（杜比视界 / HDR10+）
+pub mod tone_map;  // HDR (PQ / HLG) 画面色调映射到 SDR
+pub(crate) mod media_title;  // 媒体显示标题（容器标题标签 / .nfo 侧车文件）
+pub mod thumbnailer;  // 缩略图生成（胶片视图）
+pub mod preview;  // 预览画面（按用途限制解码尺寸，胶片 / 悬停预览 / 最近文件缩略图共用）
+pub mod preview_cache;  // 预览缓存（内存 + 磁盘，存储压力下只用内存）
+pub mod hover_preview;  // 进度条悬停预览（后台线程按需生成，防抖 + LRU 缓存）
 
 pub use demuxer::Demuxer;
 // pub use demuxer_source::{DemuxerSource, MediaPacket, PacketType};  // 导出接口（暂时未使用，如需要可取消注释）
 pub use demuxer_thread::DemuxerThread;  // 导出线程管理
 pub use demuxer_factory::{DemuxerFactory, DemuxerCreationResult};  // 导出工厂
-pub use decoder::{VideoDecoder, AudioDecoder, SubtitleDecoder};
+pub use decoder::{decode_cover_art, VideoDecoder, AudioDecoder, SubtitleDecoder};
 // pub use renderer::Renderer;
 pub use audio_output::AudioOutput;
 // pub use manager::PlaybackManager;
 pub use external_subtitle::ExternalSubtitleParser;
 pub use network_stream::NetworkStreamManager;
+pub use packet_inspector::PacketInspector;
 
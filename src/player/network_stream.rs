@@ -1,57 +1,16 @@
 use crate::core::{Result, StreamProtocol, StreamState};
-use log::{debug, info, warn};
-use std::time::{Duration, Instant};
-
-/// 重连配置
-#[derive(Debug, Clone)]
-pub struct ReconnectConfig {
-    /// 是否启用自动重连
-    pub enabled: bool,
-    /// 最大重连次数
-    pub max_attempts: u32,
-    /// 当前重连次数
-    pub current_attempt: u32,
-    /// 重连间隔（秒）
-    pub retry_interval: u64,
-}
-
-impl Default for ReconnectConfig {
-    fn default() -> Self {
-        Self {
-            enabled: true,
-            max_attempts: 5,
-            current_attempt: 0,
-            retry_interval: 3,
-        }
-    }
-}
-
-/// 网络统计信息
-#[derive(Debug, Clone, Default)]
-pub struct NetworkStats {
-    /// 接收字节数
-    pub bytes_received: u64,
-    /// 当前带宽（字节/秒）
-    pub current_bandwidth: f64,
-    /// 丢包率（0.0-1.0）
-    pub packet_loss_rate: f64,
-    /// 平均延迟（毫秒）
-    pub average_latency: f64,
-    /// 连接持续时间
-    pub connection_duration: Duration,
-}
+use log::info;
+use std::time::Instant;
 
 /// 缓冲管理器
 /// 
-/// 负责管理网络流的缓冲策略，根据网络状况动态调整缓冲大小
+/// 负责记录网络流的缓冲进度
 #[derive(Debug)]
 pub struct BufferManager {
     /// 目标缓冲大小（秒）
     target_buffer_size: f64,
     /// 当前缓冲大小（秒）
     current_buffer_size: f64,
-    /// 最小缓冲阈值（秒）
-    min_buffer_threshold: f64,
     /// 是否正在缓冲
     is_buffering: bool,
 }
@@ -62,30 +21,10 @@ impl BufferManager {
         Self {
             target_buffer_size,
             current_buffer_size: 0.0,
-            min_buffer_threshold: target_buffer_size * 0.2, // 20% 阈值
             is_buffering: false,
         }
     }
     
-    /// 更新缓冲状态
-    pub fn update(&mut self, current_buffer: f64) {
-        self.current_buffer_size = current_buffer;
-        
-        // 判断是否需要缓冲
-        if self.current_buffer_size < self.min_buffer_threshold {
-            if !self.is_buffering {
-                info!("🔄 开始缓冲（当前: {:.2}s / 目标: {:.2}s）", 
-                      self.current_buffer_size, self.target_buffer_size);
-                self.is_buffering = true;
-            }
-        } else if self.current_buffer_size >= self.target_buffer_size {
-            if self.is_buffering {
-                info!("✅ 缓冲完成（当前: {:.2}s）", self.current_buffer_size);
-                self.is_buffering = false;
-            }
-        }
-    }
-    
     /// 是否应该缓冲
     pub fn should_buffer(&self) -> bool {
         self.is_buffering
@@ -95,27 +34,18 @@ impl BufferManager {
     pub fn buffer_progress(&self) -> f64 {
         (self.current_buffer_size / self.target_buffer_size).min(1.0)
     }
-    
-    /// 获取当前缓冲大小
-    pub fn current_buffer_size(&self) -> f64 {
-        self.current_buffer_size
-    }
 }
 
 /// 网络流管理器
 /// 
-/// 负责管理网络流的连接、重连、缓冲等功能
+/// 负责管理网络流的连接和缓冲状态
 pub struct NetworkStreamManager {
     /// URL
     url: String,
     /// 协议
     protocol: StreamProtocol,
-    /// 重连配置
-    reconnect_config: ReconnectConfig,
     /// 缓冲管理器
     buffer_manager: BufferManager,
-    /// 网络统计
-    network_stats: NetworkStats,
     /// 连接开始时间
     connection_start: Option<Instant>,
 }
@@ -126,9 +56,7 @@ impl NetworkStreamManager {
         Self {
             url,
             protocol,
-            reconnect_config: ReconnectConfig::default(),
             buffer_manager: BufferManager::new(3.0), // 默认 3 秒缓冲
-            network_stats: NetworkStats::default(),
             connection_start: None,
         }
     }
@@ -150,71 +78,6 @@ impl NetworkStreamManager {
         self.connection_start = None;
     }
     
-    /// 尝试重连
-    pub fn reconnect(&mut self) -> Result<()> {
-        if !self.reconnect_config.enabled {
-            return Err(crate::core::error::PlayerError::NetworkError(
-                "重连功能未启用".to_string()
-            ));
-        }
-        
-        if self.reconnect_config.current_attempt >= self.reconnect_config.max_attempts {
-            return Err(crate::core::error::PlayerError::NetworkError(format!(
-                "重连失败：已达到最大重连次数 ({})",
-                self.reconnect_config.max_attempts
-            )));
-        }
-        
-        self.reconnect_config.current_attempt += 1;
-        
-        warn!(
-            "🔄 尝试重连 ({}/{})",
-            self.reconnect_config.current_attempt,
-            self.reconnect_config.max_attempts
-        );
-        
-        // 等待重连间隔
-        std::thread::sleep(Duration::from_secs(self.reconnect_config.retry_interval));
-        
-        // 尝试连接
-        self.connect()
-    }
-    
-    /// 重置重连计数
-    pub fn reset_reconnect_count(&mut self) {
-        self.reconnect_config.current_attempt = 0;
-    }
-    
-    /// 更新网络统计
-    pub fn update_stats(&mut self, bytes_received: u64) {
-        self.network_stats.bytes_received += bytes_received;
-        
-        // 计算带宽
-        if let Some(start) = self.connection_start {
-            let duration = start.elapsed().as_secs_f64();
-            if duration > 0.0 {
-                self.network_stats.current_bandwidth = 
-                    self.network_stats.bytes_received as f64 / duration;
-            }
-        }
-        
-        debug!(
-            "📊 网络统计 - 接收: {} bytes, 带宽: {:.2} KB/s",
-            self.network_stats.bytes_received,
-            self.network_stats.current_bandwidth / 1024.0
-        );
-    }
-    
-    /// 获取网络统计
-    pub fn get_stats(&self) -> &NetworkStats {
-        &self.network_stats
-    }
-    
-    /// 获取缓冲管理器
-    pub fn buffer_manager(&mut self) -> &mut BufferManager {
-        &mut self.buffer_manager
-    }
-    
     /// 获取当前状态
     pub fn get_state(&self) -> StreamState {
         if self.connection_start.is_none() {
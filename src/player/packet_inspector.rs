@@ -0,0 +1,234 @@
+// 数据包检查器（开发者面板使用）
+//
+// 解封装线程在启用时把每个数据包的时间戳、大小、关键帧标志写入按流划分的环形缓冲区；
+// 未启用时 record() 只读取一个原子标志，不加锁、不分配内存。
+
+use ffmpeg_next as ffmpeg;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 每个流保留的数据包条数
+pub const PACKETS_PER_STREAM: usize = 200;
+
+/// 单个数据包的记录
+#[derive(Debug, Clone)]
+pub struct PacketRecord {
+    /// 显示时间戳（流时间基单位）
+    pub pts: Option<i64>,
+    /// 解码时间戳（流时间基单位）
+    pub dts: Option<i64>,
+    /// 字节数
+    pub size: usize,
+    /// 关键帧标志
+    pub is_key: bool,
+    /// 到达时间（相对启用检查器的时刻）
+    pub arrival: Duration,
+    /// DTS 比同一流的上一个包小（时间戳回跳）
+    pub dts_backwards: bool,
+}
+
+/// 单个流的统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamSummary {
+    /// 每秒到达的包数
+    pub packets_per_sec: f64,
+    /// 平均包大小（字节）
+    pub average_size: f64,
+    /// 相邻关键帧之间的平均包数（不足两个关键帧时为 None）
+    pub keyframe_interval: Option<f64>,
+}
+
+/// 单个流的最近数据包
+#[derive(Debug, Clone)]
+pub struct StreamPackets {
+    /// 流索引
+    pub stream_index: usize,
+    /// 按到达顺序排列的数据包记录
+    pub packets: Vec<PacketRecord>,
+}
+
+impl StreamPackets {
+    /// 这些数据包的统计
+    pub fn summary(&self) -> StreamSummary {
+        summarize(&self.packets)
+    }
+}
+
+#[derive(Debug)]
+struct InspectorState {
+    started_at: Instant,
+    streams: BTreeMap<usize, VecDeque<PacketRecord>>,
+}
+
+/// 数据包检查器（可克隆，克隆之间共享同一缓冲区）
+#[derive(Debug, Clone)]
+pub struct PacketInspector {
+    enabled: Arc<AtomicBool>,
+    state: Arc<Mutex<InspectorState>>,
+}
+
+impl Default for PacketInspector {
+    fn default() -> Self {
+        Self {
+            enabled: Arc::new(AtomicBool::new(false)),
+            state: Arc::new(Mutex::new(InspectorState {
+                started_at: Instant::now(),
+                streams: BTreeMap::new(),
+            })),
+        }
+    }
+}
+
+impl PacketInspector {
+    /// 创建检查器（默认不启用）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// 启用/停用（启用时清空旧记录并重新计时）
+    pub fn set_enabled(&self, enabled: bool) {
+        if enabled && !self.is_enabled() {
+            let mut state = self.state.lock().unwrap();
+            state.started_at = Instant::now();
+            state.streams.clear();
+        }
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// 清空记录（切换文件时调用）
+    pub fn clear(&self) {
+        self.state.lock().unwrap().streams.clear();
+    }
+
+    /// 记录一个数据包（解封装线程调用，未启用时直接返回）
+    #[inline]
+    pub fn record(&self, packet: &ffmpeg::Packet) {
+        if !self.is_enabled() {
+            return;
+        }
+        self.push(packet.stream(), packet.pts(), packet.dts(), packet.size(), packet.is_key());
+    }
+
+    fn push(&self, stream_index: usize, pts: Option<i64>, dts: Option<i64>, size: usize, is_key: bool) {
+        let mut state = self.state.lock().unwrap();
+        let arrival = state.started_at.elapsed();
+        let packets = state.streams.entry(stream_index).or_default();
+
+        let previous_dts = packets.back().and_then(|p| p.dts);
+        let dts_backwards = matches!((previous_dts, dts), (Some(prev), Some(cur)) if cur < prev);
+
+        if packets.len() >= PACKETS_PER_STREAM {
+            packets.pop_front();
+        }
+        packets.push_back(PacketRecord { pts, dts, size, is_key, arrival, dts_backwards });
+    }
+
+    /// 获取所有流最近的数据包（按流索引排序）
+    pub fn snapshot(&self) -> Vec<StreamPackets> {
+        let state = self.state.lock().unwrap();
+        state
+            .streams
+            .iter()
+            .map(|(stream_index, packets)| StreamPackets {
+                stream_index: *stream_index,
+                packets: packets.iter().cloned().collect(),
+            })
+            .collect()
+    }
+}
+
+/// 计算一组数据包（同一流，按到达顺序）的统计
+fn summarize(packets: &[PacketRecord]) -> StreamSummary {
+    if packets.is_empty() {
+        return StreamSummary { packets_per_sec: 0.0, average_size: 0.0, keyframe_interval: None };
+    }
+
+    let span = packets[packets.len() - 1].arrival.saturating_sub(packets[0].arrival).as_secs_f64();
+    let packets_per_sec = if span > 0.0 { (packets.len() - 1) as f64 / span } else { 0.0 };
+    let average_size = packets.iter().map(|p| p.size as f64).sum::<f64>() / packets.len() as f64;
+
+    let keyframes: Vec<usize> = packets.iter().enumerate().filter(|(_, p)| p.is_key).map(|(i, _)| i).collect();
+    let keyframe_interval = match keyframes.as_slice() {
+        [first, .., last] => Some((last - first) as f64 / (keyframes.len() - 1) as f64),
+        _ => None,
+    };
+
+    StreamSummary { packets_per_sec, average_size, keyframe_interval }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(arrival_ms: u64, size: usize, is_key: bool) -> PacketRecord {
+        PacketRecord {
+            pts: None,
+            dts: None,
+            size,
+            is_key,
+            arrival: Duration::from_millis(arrival_ms),
+            dts_backwards: false,
+        }
+    }
+
+    #[test]
+    fn test_disabled_inspector_records_nothing() {
+        let inspector = PacketInspector::new();
+        inspector.record(&ffmpeg::Packet::empty());
+        assert!(inspector.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_latest_packets_per_stream() {
+        let inspector = PacketInspector::new();
+        inspector.set_enabled(true);
+        for i in 0..(PACKETS_PER_STREAM as i64 + 50) {
+            inspector.push(0, Some(i), Some(i), 100, false);
+        }
+        inspector.push(1, Some(0), Some(0), 10, true);
+
+        let snapshot = inspector.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].packets.len(), PACKETS_PER_STREAM);
+        assert_eq!(snapshot[0].packets[0].pts, Some(50));
+        assert_eq!(snapshot[1].packets.len(), 1);
+    }
+
+    #[test]
+    fn test_dts_backwards_is_flagged() {
+        let inspector = PacketInspector::new();
+        inspector.set_enabled(true);
+        inspector.push(0, Some(10), Some(10), 1, true);
+        inspector.push(0, Some(30), Some(20), 1, false);
+        inspector.push(0, Some(20), Some(15), 1, false);
+
+        let flags: Vec<bool> = inspector.snapshot()[0].packets.iter().map(|p| p.dts_backwards).collect();
+        assert_eq!(flags, vec![false, false, true]);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let packets: Vec<PacketRecord> = (0..11)
+            .map(|i| record(i * 100, if i % 2 == 0 { 300 } else { 100 }, i % 5 == 0))
+            .collect();
+        let summary = summarize(&packets);
+        assert!((summary.packets_per_sec - 10.0).abs() < 1e-9);
+        assert!((summary.average_size - 2300.0 / 11.0).abs() < 1e-9);
+        assert_eq!(summary.keyframe_interval, Some(5.0));
+    }
+
+    #[test]
+    fn test_summarize_without_keyframe_pair() {
+        let summary = summarize(&[record(0, 10, true), record(0, 10, false)]);
+        assert_eq!(summary.packets_per_sec, 0.0);
+        assert_eq!(summary.keyframe_interval, None);
+        assert_eq!(summarize(&[]).keyframe_interval, None);
+    }
+}
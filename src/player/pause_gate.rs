@@ -0,0 +1,117 @@
+// 暂停时让解封装和解码线程休眠
+//
+// 队列满、队列空时的等待由 BlockingQueue 的条件变量处理；这里处理队列以外的等待条件：
+// 会话挂起（切换到其他标签页）且已暂停时，线程在条件变量上等待而不是每 10ms 轮询一次挂起标志。
+// play() 放行所有线程；seek() 唤醒等待的线程处理 seek 命令后重新检查；stop() 唤醒线程使其看到退出标志。
+// 等待带超时，作为漏掉唤醒时的兜底。
+
+use log::debug;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// 单次等待的上限（漏掉唤醒时最多延迟这么久重新检查）
+const PARK_TIMEOUT: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Default)]
+struct GateState {
+    paused: bool,
+    generation: u64,  // 每次唤醒递增，等待中的线程据此重新检查条件
+}
+
+/// 暂停闸门（播放管理器和各播放线程共享）
+#[derive(Debug, Clone, Default)]
+pub struct PauseGate {
+    inner: Arc<(Mutex<GateState>, Condvar)>,
+}
+
+impl PauseGate {
+    /// 暂停 / 继续（继续时立即唤醒所有等待的线程）
+    pub fn set_paused(&self, paused: bool) {
+        let (state, condvar) = &*self.inner;
+        let mut state = state.lock().unwrap();
+        if state.paused != paused {
+            state.paused = paused;
+            state.generation += 1;
+            condvar.notify_all();
+        }
+    }
+
+    /// 唤醒等待的线程重新检查条件（seek、取走帧、停止）
+    pub fn wake(&self) {
+        let (state, condvar) = &*self.inner;
+        state.lock().unwrap().generation += 1;
+        condvar.notify_all();
+    }
+
+    /// 暂停期间 blocked() 成立时等待（队列已满、会话挂起等），返回是否等待过。
+    /// 未暂停时立即返回 false，由调用方按原来的节奏轮询
+    pub fn park_while(&self, name: &str, blocked: impl Fn() -> bool) -> bool {
+        let (state, condvar) = &*self.inner;
+        let mut guard = state.lock().unwrap();
+        let mut parked = false;
+        while guard.paused && blocked() {
+            if !parked {
+                debug!("💤 {}：暂停中，等待继续播放或 seek", name);
+                parked = true;
+            }
+            let generation = guard.generation;
+            guard = condvar
+                .wait_timeout_while(guard, PARK_TIMEOUT, |state| state.paused && state.generation == generation)
+                .unwrap()
+                .0;
+        }
+        parked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::Instant;
+
+    #[test]
+    fn test_does_not_park_while_playing() {
+        let gate = PauseGate::default();
+        assert!(!gate.park_while("测试", || true));
+        gate.set_paused(true);
+        assert!(!gate.park_while("测试", || false));
+    }
+
+    #[test]
+    fn test_play_releases_parked_thread_immediately() {
+        let gate = PauseGate::default();
+        gate.set_paused(true);
+        let worker_gate = gate.clone();
+        let worker = thread::spawn(move || {
+            let started = Instant::now();
+            assert!(worker_gate.park_while("测试", || true));
+            started.elapsed()
+        });
+        thread::sleep(Duration::from_millis(50));
+        gate.set_paused(false);
+        // 继续播放时立即返回，不等到超时
+        assert!(worker.join().unwrap() < PARK_TIMEOUT * 2);
+    }
+
+    #[test]
+    fn test_wake_rechecks_condition_and_parks_again() {
+        let gate = PauseGate::default();
+        gate.set_paused(true);
+        let full = Arc::new(AtomicBool::new(true));
+        let (worker_gate, worker_full) = (gate.clone(), full.clone());
+        let worker = thread::spawn(move || worker_gate.park_while("测试", || worker_full.load(Ordering::SeqCst)));
+
+        // 唤醒但队列仍满：继续等待
+        thread::sleep(Duration::from_millis(30));
+        gate.wake();
+        thread::sleep(Duration::from_millis(30));
+        assert!(!worker.is_finished());
+
+        // seek 清空队列后唤醒：保持暂停也返回
+        full.store(false, Ordering::SeqCst);
+        gate.wake();
+        assert!(worker.join().unwrap());
+    }
+}
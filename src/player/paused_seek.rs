@@ -0,0 +1,162 @@
+// 暂停状态下的 seek（跳转后立即显示目标位置的画面）
+//
+// 暂停时时钟不前进，界面按时钟取帧的逻辑不会更新画面（向后跳转时新帧的 PTS 比当前帧小，
+// DemuxerThread 模式下 flush 后的缓冲也可能让旧画面一直停留）。暂停时 seek 登记目标位置，
+// 视频解码线程按 PTS 顺序丢弃目标之前的帧，把覆盖目标位置的那一帧交给界面（不进入帧队列），
+// 界面不比较时钟直接显示，播放管理器把时钟对齐到该帧 PTS，播放状态保持暂停。
+// 每次 seek 都会替换之前的请求（旧请求的帧不会被显示）；恢复播放时取消未完成的请求。
+//
+// 逐帧步进也通过这里把帧交给界面：前进一帧直接取帧队列中的下一帧，后退一帧按帧时长 seek 到上一帧。
+
+use crate::core::VideoFrame;
+use std::sync::{Arc, Mutex};
+
+/// 目标之后多远以内的帧视为目标帧（更远的帧多半是 seek 前残留在解码器中的旧帧）
+const TARGET_WINDOW_MS: i64 = 1000;
+
+/// 帧率未知时按 25fps 计算帧时长
+const FALLBACK_FRAME_DURATION_MS: i64 = 40;
+
+/// 帧时长（毫秒，帧率无效时按 25fps）
+pub fn frame_duration_ms(fps: f64) -> i64 {
+    if fps.is_finite() && fps > 0.0 {
+        ((1000.0 / fps).round() as i64).max(1)
+    } else {
+        FALLBACK_FRAME_DURATION_MS
+    }
+}
+
+/// 后退一帧的 seek 目标：上一帧和再上一帧之间（解码出的帧没有时长，目标之后的第一帧就是上一帧，
+/// 留出半帧余量容忍 PTS 取整误差）
+pub fn step_back_target(current_pts: i64, frame_duration_ms: i64) -> i64 {
+    (current_pts - frame_duration_ms * 3 / 2).max(0)
+}
+
+#[derive(Debug, Default)]
+enum Request {
+    #[default]
+    Idle,
+    /// 等待解码线程送出目标帧
+    Pending { target_ms: i64 },
+    /// 目标帧已解码，等待界面显示
+    Ready(VideoFrame),
+}
+
+/// 暂停状态下的 seek 请求（可克隆，播放管理器和视频解码线程共享）
+#[derive(Debug, Clone, Default)]
+pub struct PausedSeek {
+    request: Arc<Mutex<Request>>,
+}
+
+impl PausedSeek {
+    /// 登记目标位置（替换之前的请求）
+    pub fn request(&self, target_ms: i64) {
+        *self.request.lock().unwrap() = Request::Pending { target_ms };
+    }
+
+    /// 取消请求（恢复播放、停止或非暂停状态下的 seek）
+    pub fn cancel(&self) {
+        *self.request.lock().unwrap() = Request::Idle;
+    }
+
+    /// 直接把一帧交给界面（前进一帧，替换之前的请求）
+    pub fn present(&self, frame: VideoFrame) {
+        *self.request.lock().unwrap() = Request::Ready(frame);
+    }
+
+    /// 是否在等待目标帧（或目标帧尚未显示）
+    pub fn is_active(&self) -> bool {
+        !matches!(*self.request.lock().unwrap(), Request::Idle)
+    }
+
+    /// 解码线程按 PTS 顺序送入解码出的帧，返回需要推入帧队列的帧
+    /// - 等待目标帧时：目标之前的帧丢弃，覆盖目标位置的帧留给界面
+    /// - 没有请求或目标帧已解码：原样返回
+    pub fn offer(&self, frame: VideoFrame) -> Option<VideoFrame> {
+        let mut request = self.request.lock().unwrap();
+        let Request::Pending { target_ms } = *request else {
+            return Some(frame);
+        };
+        if frame.pts + frame.duration.max(1) <= target_ms {
+            return None;
+        }
+        if frame.pts > target_ms + TARGET_WINDOW_MS {
+            return Some(frame);
+        }
+        *request = Request::Ready(frame);
+        None
+    }
+
+    /// 取出已解码的目标帧（界面显示后请求结束）
+    pub fn take_frame(&self) -> Option<VideoFrame> {
+        let mut request = self.request.lock().unwrap();
+        match std::mem::take(&mut *request) {
+            Request::Ready(frame) => Some(frame),
+            other => {
+                *request = other;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::PixelFormat;
+
+    fn frame(pts: i64) -> VideoFrame {
+        VideoFrame { pts, duration: 40, width: 2, height: 2, format: PixelFormat::RGBA, planes: None, data: vec![0; 16].into() }
+    }
+
+    #[test]
+    fn test_only_the_target_frame_is_held_back() {
+        let seek = PausedSeek::default();
+        assert!(seek.offer(frame(0)).is_some());
+
+        // 从关键帧开始向前解码：目标之前的帧丢弃，覆盖目标的帧交给界面
+        seek.request(60_010);
+        for pts in (59_000..60_000).step_by(40) {
+            assert!(seek.offer(frame(pts)).is_none());
+        }
+        assert!(seek.offer(frame(60_000)).is_none());
+        assert!(seek.is_active());
+        assert_eq!(seek.offer(frame(60_040)).map(|f| f.pts), Some(60_040));
+        assert_eq!(seek.take_frame().map(|f| f.pts), Some(60_000));
+        assert!(!seek.is_active());
+        assert!(seek.take_frame().is_none());
+
+        // 向后跳转：seek 前残留的远处帧不当作目标帧；新的请求替换旧请求
+        seek.request(10_000);
+        assert_eq!(seek.offer(frame(60_080)).map(|f| f.pts), Some(60_080));
+        seek.request(20_000);
+        assert!(seek.offer(frame(10_000)).is_none());
+        assert!(seek.offer(frame(20_000)).is_none());
+        seek.cancel();
+        assert!(seek.take_frame().is_none());
+
+        // 前进一帧：直接交给界面
+        seek.present(frame(20_040));
+        assert!(seek.is_active());
+        assert_eq!(seek.take_frame().map(|f| f.pts), Some(20_040));
+    }
+
+    #[test]
+    fn test_step_back_target_lands_on_previous_frame() {
+        assert_eq!(frame_duration_ms(25.0), 40);
+        assert_eq!(frame_duration_ms(59.94), 17);
+        assert_eq!(frame_duration_ms(0.0), 40);
+        assert_eq!(frame_duration_ms(f64::NAN), 40);
+
+        // 目标之后的第一帧是上一帧（PTS 有 1ms 取整误差时也成立）
+        let target = step_back_target(1_000, 40);
+        assert!(target < 959 && target > 920, "{}", target);
+        let seek = PausedSeek::default();
+        seek.request(target);
+        for pts in [880, 920, 959, 1_000] {
+            let _ = seek.offer(VideoFrame { duration: 0, ..frame(pts) });
+        }
+        assert_eq!(seek.take_frame().map(|f| f.pts), Some(959));
+        assert_eq!(step_back_target(20, 40), 0);
+    }
+}
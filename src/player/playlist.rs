@@ -0,0 +1,185 @@
+// 播放列表（按顺序播放多个文件，上一个 / 下一个，播放完毕自动播放下一项）
+//
+// 播放列表只记录条目和当前项的位置，不负责打开文件：播放管理器打开任何源时按路径同步当前项，
+// 因此 seek、停止、重新播放都不会改变位置；打开不在列表中的文件时保留原来的位置，之后继续按列表播放。
+
+/// 播放到列表末尾后的行为
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepeatMode {
+    /// 停止
+    #[default]
+    Off,
+    /// 回到第一项
+    All,
+}
+
+/// 播放列表
+#[derive(Debug, Clone, Default)]
+pub struct Playlist {
+    items: Vec<String>,
+    current: Option<usize>,
+    repeat: RepeatMode,
+}
+
+impl Playlist {
+    /// 替换全部条目（当前项重置，等待打开时同步）
+    pub fn set(&mut self, items: Vec<String>) {
+        self.items = items;
+        self.current = None;
+    }
+
+    /// 追加到末尾
+    pub fn push(&mut self, item: String) {
+        self.items.push(item);
+    }
+
+    /// 清空列表
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.current = None;
+    }
+
+    /// 全部条目
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// 条目数
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// 列表为空
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 当前项的位置（尚未播放列表中的任何一项时为 None）
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// 循环方式
+    pub fn repeat(&self) -> RepeatMode {
+        self.repeat
+    }
+
+    /// 设置循环方式
+    pub fn set_repeat(&mut self, repeat: RepeatMode) {
+        self.repeat = repeat;
+    }
+
+    /// 打开 path 时同步当前项（优先当前项之后最近的同名条目；不在列表中时保持不变）
+    pub fn select(&mut self, path: &str) {
+        if self.current.is_some_and(|index| self.items[index] == path) {
+            return;
+        }
+        let start = self.current.map_or(0, |index| index + 1);
+        let found = self.items[start..]
+            .iter()
+            .position(|item| item == path)
+            .map(|offset| start + offset)
+            .or_else(|| self.items.iter().position(|item| item == path));
+        if found.is_some() {
+            self.current = found;
+        }
+    }
+
+    /// 下一项的位置（末尾且不循环时为 None）
+    pub fn next_index(&self) -> Option<usize> {
+        if self.items.is_empty() {
+            return None;
+        }
+        match self.current {
+            None => Some(0),
+            Some(index) if index + 1 < self.items.len() => Some(index + 1),
+            Some(_) => (self.repeat == RepeatMode::All).then_some(0),
+        }
+    }
+
+    /// 上一项的位置（第一项且不循环时为 None）
+    pub fn previous_index(&self) -> Option<usize> {
+        match self.current? {
+            0 => (self.repeat == RepeatMode::All).then(|| self.items.len() - 1),
+            index => Some(index - 1),
+        }
+    }
+
+    /// 移动到下一项并返回其路径
+    pub fn advance(&mut self) -> Option<String> {
+        let index = self.next_index()?;
+        self.current = Some(index);
+        Some(self.items[index].clone())
+    }
+
+    /// 移动到上一项并返回其路径
+    pub fn retreat(&mut self) -> Option<String> {
+        let index = self.previous_index()?;
+        self.current = Some(index);
+        Some(self.items[index].clone())
+    }
+
+    /// 当前项之后尚未播放的条目数
+    pub fn remaining(&self) -> usize {
+        self.current.map_or(self.items.len(), |index| self.items.len() - index - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playlist(items: &[&str]) -> Playlist {
+        let mut playlist = Playlist::default();
+        playlist.set(items.iter().map(|item| item.to_string()).collect());
+        playlist
+    }
+
+    #[test]
+    fn test_next_and_previous_stop_at_ends() {
+        let mut list = playlist(&["a.mkv", "b.mkv", "c.mkv"]);
+        assert_eq!(list.previous_index(), None);
+        assert_eq!(list.remaining(), 3);
+        assert_eq!(list.advance().as_deref(), Some("a.mkv"));
+        assert_eq!(list.retreat(), None);
+        assert_eq!(list.current(), Some(0));
+        assert_eq!(list.advance().as_deref(), Some("b.mkv"));
+        assert_eq!(list.advance().as_deref(), Some("c.mkv"));
+        assert_eq!(list.remaining(), 0);
+        assert_eq!(list.advance(), None);
+        // 到达末尾不改变当前项
+        assert_eq!(list.current(), Some(2));
+        assert_eq!(list.retreat().as_deref(), Some("b.mkv"));
+    }
+
+    #[test]
+    fn test_repeat_all_wraps() {
+        let mut list = playlist(&["a.mkv", "b.mkv"]);
+        list.set_repeat(RepeatMode::All);
+        list.select("b.mkv");
+        assert_eq!(list.advance().as_deref(), Some("a.mkv"));
+        assert_eq!(list.retreat().as_deref(), Some("b.mkv"));
+        assert_eq!(Playlist::default().advance(), None);
+    }
+
+    #[test]
+    fn test_select_syncs_current_and_ignores_unknown_paths() {
+        let mut list = playlist(&["a.mkv", "b.mkv", "a.mkv", "c.mkv"]);
+        list.select("b.mkv");
+        assert_eq!(list.current(), Some(1));
+        // 重新打开当前项（停止后重新播放）不改变位置
+        list.select("b.mkv");
+        assert_eq!(list.current(), Some(1));
+        // 重复的条目选择当前项之后最近的一个
+        list.select("a.mkv");
+        assert_eq!(list.current(), Some(2));
+        // 打开列表外的文件后仍从原位置继续
+        list.select("other.mkv");
+        assert_eq!(list.current(), Some(2));
+        assert_eq!(list.advance().as_deref(), Some("c.mkv"));
+        list.push("d.mkv".to_string());
+        assert_eq!(list.remaining(), 1);
+        list.clear();
+        assert_eq!(list.current(), None);
+    }
+}
@@ -0,0 +1,293 @@
+// 播放列表下一项预读（缩短切换间隔）
+//
+// 当前项播放到距结尾 PREFETCH_LEAD_MS 以内时，在后台线程打开播放列表的下一项并解码开头一段
+// （视频 PREFETCH_VIDEO_MS、音频 PREFETCH_AUDIO_MS，解码出的数据超过 PREFETCH_BYTE_BUDGET 时提前停止）。
+// 解封装器和解码器保持打开：切换到下一项时管理器直接接管，预读的帧交给解码线程（与之后解码的帧一样经过重排序、
+// seek 筛选和计数），解封装线程从预读停下的位置继续读包，不再重新打开文件、创建解码器和解码开头。
+// 切换时预读还没完成则放弃预读，按普通方式打开，不阻塞界面线程。
+// 预读时还不知道界面会选择哪条内嵌字幕，字幕包原样保留，接管后交给选中字幕流的解码器。
+
+use crate::core::{AudioFrame, PlayerError, Result, TrackSource, VideoFrame};
+use crate::player::decoder::{AudioDecoder, VideoDecoder};
+use crate::player::decoder_fallback::HwFallbackReason;
+use crate::player::first_frame::VideoDecodeCounters;
+use crate::player::keyframe_index;
+use crate::player::Demuxer;
+use ffmpeg_next as ffmpeg;
+use log::{debug, info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+/// 距当前项结尾这么久以内开始预读下一项（毫秒）
+pub const PREFETCH_LEAD_MS: i64 = 30_000;
+
+/// 预读的视频时长（毫秒）
+pub const PREFETCH_VIDEO_MS: i64 = 2_000;
+
+/// 预读的音频时长（毫秒）
+pub const PREFETCH_AUDIO_MS: i64 = 1_000;
+
+/// 预读的解码数据上限（像素 + 采样，字节）
+pub const PREFETCH_BYTE_BUDGET: usize = 128 * 1024 * 1024;
+
+/// 选定的视频解码器和硬解回退原因
+pub(crate) type SelectedVideoDecoder = (VideoDecoder, Option<HwFallbackReason>);
+
+/// 预读解码出的帧（接管后由解码线程按解码出的帧处理）
+#[derive(Default)]
+pub(crate) struct PrefetchedFrames {
+    pub video: Vec<VideoFrame>,                 // 解码器的输出顺序（未重排序）
+    pub audio: Vec<AudioFrame>,
+    pub video_counters: VideoDecodeCounters,    // 预读时的视频解码计数（包 / 帧 / 错误）
+}
+
+/// 预读的开头（管理器接管时帧交给解码线程，解码器交给解码线程继续使用）
+pub(crate) struct PrefetchedStart {
+    pub video_decoder: Option<SelectedVideoDecoder>,
+    pub audio_decoder: Option<AudioDecoder>,
+    pub audio_stream: Option<usize>,     // 音频解码器对应的流索引
+    pub audio_config: (u32, u16),        // 音频解码器的输出配置（采样率, 声道数）
+    pub frames: PrefetchedFrames,
+    pub subtitle_packets: Vec<ffmpeg::Packet>,  // 所有内嵌字幕流的包（未解码）
+}
+
+impl PrefetchedStart {
+    /// 音频流与实际打开的一致、音频输出的配置与预读时的解码配置一致（没有音频输出时不检查配置）
+    pub fn matches(&self, audio_stream: Option<usize>, output_config: Option<(u32, u16)>) -> bool {
+        self.audio_stream == audio_stream && output_config.is_none_or(|config| config == self.audio_config)
+    }
+}
+
+/// 预读完成的下一项
+pub(crate) struct PrefetchedItem {
+    pub demuxer: Demuxer,
+    pub start: PrefetchedStart,
+}
+
+/// 后台预读任务（drop 时取消并等待线程退出）
+pub(crate) struct PlaylistPrefetch {
+    path: String,
+    cancel: Arc<AtomicBool>,
+    thread_handle: Option<thread::JoinHandle<Result<PrefetchedItem>>>,
+}
+
+impl PlaylistPrefetch {
+    /// 在后台打开 path 并解码开头（audio_stream 为该文件记忆的音频流，open_video 选择视频解码器）
+    pub fn spawn<F>(path: String, audio_stream: Option<usize>, open_video: F) -> Self
+    where
+        F: FnOnce(&Demuxer) -> Result<Option<SelectedVideoDecoder>> + Send + 'static,
+    {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let thread_cancel = cancel.clone();
+        let thread_path = path.clone();
+        let thread_handle = thread::Builder::new()
+            .name("playlist-prefetch".to_string())
+            .spawn(move || {
+                let started = Instant::now();
+                let result = prefetch(&thread_path, audio_stream, open_video, &thread_cancel);
+                match &result {
+                    Ok(item) => info!(
+                        "⏭️ 下一项预读完成: 视频 {} 帧 / 音频 {} 帧 / 字幕包 {} 个，耗时 {}ms ({})",
+                        item.start.frames.video.len(),
+                        item.start.frames.audio.len(),
+                        item.start.subtitle_packets.len(),
+                        started.elapsed().as_millis(),
+                        thread_path
+                    ),
+                    Err(e) => debug!("⏭️ 下一项预读未完成: {} ({})", e, thread_path),
+                }
+                result
+            })
+            .ok();
+        if thread_handle.is_none() {
+            warn!("⚠️  无法启动预读线程");
+        }
+
+        Self { path, cancel, thread_handle }
+    }
+
+    /// 预读的文件
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// 预读线程是否已结束（完成或失败）
+    pub fn is_finished(&self) -> bool {
+        self.thread_handle.as_ref().is_none_or(thread::JoinHandle::is_finished)
+    }
+
+    /// 预读已完成时取出结果；还没完成时取消预读（不等待），与预读失败一样返回 None，由调用方按普通方式打开
+    pub fn try_finish(mut self) -> Option<PrefetchedItem> {
+        if !self.is_finished() {
+            info!("⏭️ 下一项预读尚未完成，取消预读并直接打开 ({})", self.path);
+            self.cancel.store(true, Ordering::SeqCst);
+            // 预读线程在读下一个包前检查取消标志，后台退出，不在这里等待
+            self.thread_handle = None;
+            return None;
+        }
+        let result = self.thread_handle.take()?.join().ok()?;
+        result.ok()
+    }
+}
+
+impl Drop for PlaylistPrefetch {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 已解码的视频覆盖的时长（毫秒）
+fn video_span_ms(frames: &[VideoFrame]) -> i64 {
+    match (frames.first(), frames.last()) {
+        (Some(first), Some(last)) => last.pts + last.duration - first.pts,
+        _ => 0,
+    }
+}
+
+/// 已解码的音频时长（毫秒）
+fn audio_span_ms(frames: &[AudioFrame]) -> i64 {
+    frames
+        .iter()
+        .map(|frame| (frame.data.len() / frame.channels.max(1) as usize) as i64 * 1000 / frame.sample_rate.max(1) as i64)
+        .sum()
+}
+
+/// 打开文件并解码开头，直到视频和音频都够长、超出数据上限或读到末尾
+fn prefetch<F>(path: &str, audio_stream: Option<usize>, open_video: F, cancel: &AtomicBool) -> Result<PrefetchedItem>
+where
+    F: FnOnce(&Demuxer) -> Result<Option<SelectedVideoDecoder>>,
+{
+    let mut demuxer = Demuxer::open(path)?;
+    if let Some(index) = audio_stream {
+        if let Err(e) = demuxer.select_audio_stream(index) {
+            warn!("⚠️  预读时恢复音频轨道失败: {}", e);
+        }
+    }
+    demuxer.start_keyframe_index(Some(keyframe_index::default_cache_dir()));
+
+    let mut video_decoder = open_video(&demuxer)?;
+    let media_info = demuxer.get_media_info()?;
+    let audio_config = (media_info.sample_rate, media_info.channels);
+    let mut audio_decoder = match demuxer.audio_stream() {
+        Some(stream) => Some(AudioDecoder::from_stream_with_config(stream, audio_config.0, audio_config.1)?),
+        None => None,
+    };
+    let video_stream = demuxer.video_stream_index().filter(|_| video_decoder.is_some());
+    let audio_stream = demuxer.audio_stream_index();
+    let subtitle_streams: Vec<usize> = demuxer
+        .subtitle_tracks()
+        .iter()
+        .filter_map(|track| match track.source {
+            TrackSource::Embedded(index) => Some(index),
+            _ => None,
+        })
+        .collect();
+
+    let mut frames = PrefetchedFrames::default();
+    let mut subtitle_packets = Vec::new();
+    let mut decoded_bytes = 0;
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            return Err(PlayerError::Other("预读已取消".to_string()));
+        }
+        let video_done = video_stream.is_none() || video_span_ms(&frames.video) >= PREFETCH_VIDEO_MS;
+        let audio_done = audio_decoder.is_none() || audio_span_ms(&frames.audio) >= PREFETCH_AUDIO_MS;
+        if (video_done && audio_done) || decoded_bytes >= PREFETCH_BYTE_BUDGET {
+            break;
+        }
+        let Some(packet) = demuxer.next_packet()? else {
+            break;
+        };
+
+        // 解码错误（开头的个别坏包、需要更多数据）不中断预读，接管后由解码线程按正常流程处理
+        let stream = packet.stream();
+        if Some(stream) == video_stream {
+            if let Some((decoder, _)) = &mut video_decoder {
+                let decoded = decoder.decode(&packet);
+                frames.video_counters.record(&decoded);
+                match decoded {
+                    Ok(decoded) => {
+                        decoded_bytes += decoded.iter().map(|frame| frame.data.len()).sum::<usize>();
+                        frames.video.extend(decoded);
+                    }
+                    Err(e) => debug!("预读视频包解码失败: {}", e),
+                }
+            }
+        } else if Some(stream) == audio_stream {
+            if let Some(decoder) = &mut audio_decoder {
+                match decoder.decode(&packet) {
+                    Ok(decoded) => {
+                        decoded_bytes += decoded.iter().map(|frame| frame.data.len() * size_of::<f32>()).sum::<usize>();
+                        frames.audio.extend(decoded);
+                    }
+                    Err(e) => debug!("预读音频包解码失败: {}", e),
+                }
+            }
+        } else if subtitle_streams.contains(&stream) {
+            subtitle_packets.push(packet);
+        }
+    }
+
+    Ok(PrefetchedItem {
+        demuxer,
+        start: PrefetchedStart {
+            video_decoder,
+            audio_decoder,
+            audio_stream,
+            audio_config,
+            frames,
+            subtitle_packets,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{FrameData, PixelFormat};
+    use crate::test_support::video_asset;
+    use std::sync::mpsc;
+
+    fn video_frame(pts: i64) -> VideoFrame {
+        VideoFrame { pts, duration: 40, width: 1, height: 1, format: PixelFormat::RGBA, planes: None, data: FrameData::from(vec![0; 4]) }
+    }
+
+    #[test]
+    fn test_spans() {
+        assert_eq!(video_span_ms(&[]), 0);
+        let frames: Vec<VideoFrame> = (0..50).map(|i| video_frame(1_000 + i * 40)).collect();
+        assert_eq!(video_span_ms(&frames), 2_000);
+
+        // 48kHz 立体声，每帧 1024 个采样帧（约 21ms）
+        let audio = AudioFrame { pts: 0, sample_rate: 48_000, channels: 2, data: vec![0.0; 2_048] };
+        assert_eq!(audio_span_ms(&vec![audio; 48]), 48 * 21);
+    }
+
+    #[test]
+    fn test_unfinished_prefetch_is_abandoned_without_waiting() {
+        // 选择视频解码器时卡住：预读没有完成，try_finish 取消预读并立即返回
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let path = video_asset().to_string_lossy().into_owned();
+        let prefetch = PlaylistPrefetch::spawn(path, None, move |_| {
+            let _ = release_rx.recv();
+            Ok(None)
+        });
+        assert!(!prefetch.is_finished());
+        assert!(prefetch.try_finish().is_none());
+        // 预读线程放行后看到取消标志，按取消结束
+        release_tx.send(()).unwrap();
+
+        let path = video_asset().to_string_lossy().into_owned();
+        let prefetch = PlaylistPrefetch::spawn(path, None, |_| Ok(None));
+        while !prefetch.is_finished() {
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let item = prefetch.try_finish().expect("预读完成后应取出结果");
+        assert!(item.start.frames.video.is_empty());
+    }
+}
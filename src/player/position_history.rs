@@ -0,0 +1,655 @@
+// 播放位置记录（断电安全）
+//
+// 播放中每 CHECKPOINT_INTERVAL 记录一次位置到内存，后台线程节流写盘（每 MIN_WRITE_INTERVAL 最多一次，
+// 停止/切换文件/退出时立即写入）。写盘使用"临时文件 + fsync + rename"，磁盘上的文件始终是完整的旧版本或新版本。
+//
+// 记录中同时保存文件时长和"已看完"标记：播放超过时长的 90% 或播放结束时标记，最近播放列表据此显示对勾或看到的百分比。
+// 记录数超过上限时淘汰最久未播放的位置，已看完的文件只清除位置、保留标记。
+// 媒体有友好标题（容器标题标签或 .nfo）时一并保存，最近播放列表显示标题而不是文件名。
+// 硬件解码失败并回退到软件解码时记录原因，再次打开同一文件时直接使用软件解码。
+
+use crate::core::{user_data_dir, PlayerError, Result};
+use crossbeam_channel::{unbounded, RecvTimeoutError, Sender};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 播放中记录位置的间隔
+pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 两次写盘的最小间隔（显式 flush 不受限制）
+pub const MIN_WRITE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 没有待写入数据时写盘线程的等待上限
+const IDLE_WAIT: Duration = Duration::from_secs(3600);
+
+/// 最多保留的播放位置数（超出时淘汰最久未播放的）
+pub const MAX_POSITION_RECORDS: usize = 500;
+
+/// 单个文件的播放位置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionRecord {
+    /// 播放位置（毫秒）
+    pub position_ms: i64,
+    /// 记录时刻（Unix 毫秒），合并并发更新时新者优先
+    pub updated_at_ms: u64,
+    /// 文件时长（用于显示看到的百分比）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+    /// 标记为已看完的时刻（Unix 毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watched_at_ms: Option<u64>,
+    /// 显示标题（没有时显示文件名）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// 硬件解码失败的原因（再次打开时跳过硬解）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hw_decode_failure: Option<String>,
+}
+
+/// 文件的观看状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WatchState {
+    /// 还没看过
+    Unwatched,
+    /// 看了一部分（0.0 ~ 1.0）
+    Partial(f32),
+    /// 已看完
+    Watched,
+}
+
+impl PositionRecord {
+    /// 观看状态（标记为已看完优先，否则按位置和时长计算）
+    pub fn watch_state(&self) -> WatchState {
+        if self.watched_at_ms.is_some() {
+            return WatchState::Watched;
+        }
+        match self.duration_ms {
+            Some(duration_ms) if duration_ms > 0 && self.position_ms > 0 => {
+                WatchState::Partial((self.position_ms as f64 / duration_ms as f64).clamp(0.0, 1.0) as f32)
+            }
+            _ => WatchState::Unwatched,
+        }
+    }
+}
+
+/// 是否算作已看完：播放结束时总是算（包括时长未知或不足 60 秒的短文件），否则播放位置达到时长的 90%
+pub fn is_watched(position_ms: i64, duration_ms: i64, finished: bool) -> bool {
+    finished || (duration_ms > 0 && position_ms * 10 >= duration_ms * 9)
+}
+
+/// 内存中的位置记录（UI 线程与检查点共享）
+#[derive(Debug, Clone, Default)]
+pub struct PositionStore {
+    records: Arc<Mutex<HashMap<String, PositionRecord>>>,
+}
+
+impl PositionStore {
+    pub fn new(records: HashMap<String, PositionRecord>) -> Self {
+        Self { records: Arc::new(Mutex::new(records)) }
+    }
+
+    /// 合并一条记录（比已有记录旧的更新被忽略），返回是否被采纳
+    pub fn merge(&self, path: &str, record: PositionRecord) -> bool {
+        let mut records = self.records.lock().unwrap();
+        match records.get(path) {
+            Some(existing) if existing.updated_at_ms > record.updated_at_ms => false,
+            _ => {
+                records.insert(path.to_string(), record);
+                evict(&mut records);
+                true
+            }
+        }
+    }
+
+    /// 设置/清除已看完标记（没有记录时新建），返回是否有变化
+    pub fn set_watched(&self, path: &str, watched_at_ms: Option<u64>) -> bool {
+        let mut records = self.records.lock().unwrap();
+        let record = records.entry(path.to_string()).or_insert(PositionRecord {
+            updated_at_ms: watched_at_ms.unwrap_or(0),
+            ..Default::default()
+        });
+        if record.watched_at_ms.is_some() == watched_at_ms.is_some() {
+            return false;
+        }
+        record.watched_at_ms = watched_at_ms;
+        // 淘汰后只剩标记的记录，清除标记后整条删除
+        if watched_at_ms.is_none() && record.position_ms == 0 && record.hw_decode_failure.is_none() {
+            records.remove(path);
+        }
+        evict(&mut records);
+        true
+    }
+
+    /// 设置已有记录的显示标题（没有记录时忽略），返回是否有变化
+    pub fn set_title(&self, path: &str, title: &str) -> bool {
+        let mut records = self.records.lock().unwrap();
+        match records.get_mut(path) {
+            Some(record) if record.title.as_deref() != Some(title) => {
+                record.title = Some(title.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 设置/清除硬件解码失败原因（没有记录时新建），返回是否有变化
+    pub fn set_hw_decode_failure(&self, path: &str, failure: Option<&str>, now_ms: u64) -> bool {
+        let mut records = self.records.lock().unwrap();
+        if records.get(path).map_or(failure.is_none(), |record| record.hw_decode_failure.as_deref() == failure) {
+            return false;
+        }
+        let record = records
+            .entry(path.to_string())
+            .or_insert(PositionRecord { updated_at_ms: now_ms, ..Default::default() });
+        record.hw_decode_failure = failure.map(str::to_string);
+        if failure.is_none() && record.position_ms == 0 && record.watched_at_ms.is_none() {
+            records.remove(path);
+        }
+        evict(&mut records);
+        true
+    }
+
+    /// 一次读取多个文件的观看状态（长列表渲染时只加锁一次）
+    pub fn watch_states<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> Vec<WatchState> {
+        let records = self.records.lock().unwrap();
+        paths
+            .into_iter()
+            .map(|path| records.get(path).map_or(WatchState::Unwatched, PositionRecord::watch_state))
+            .collect()
+    }
+
+    pub fn get(&self, path: &str) -> Option<PositionRecord> {
+        self.records.lock().unwrap().get(path).cloned()
+    }
+
+    /// 最近播放的文件（按记录时间从新到旧）
+    pub fn recent(&self, limit: usize) -> Vec<(String, PositionRecord)> {
+        let mut records: Vec<_> = self.records.lock().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        records.sort_by(|a, b| b.1.updated_at_ms.cmp(&a.1.updated_at_ms).then_with(|| a.0.cmp(&b.0)));
+        records.truncate(limit);
+        records
+    }
+
+    /// 按路径排序的快照（保证写出的文件内容稳定）
+    fn snapshot(&self) -> BTreeMap<String, PositionRecord> {
+        self.records.lock().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+/// 保存位置的记录超过上限时淘汰最久未播放的：已看完的只清除位置（保留标记），其余整条删除
+fn evict(records: &mut HashMap<String, PositionRecord>) {
+    if records.len() <= MAX_POSITION_RECORDS {
+        return;
+    }
+    let mut resumable: Vec<_> = records
+        .iter()
+        .filter(|(_, record)| record.position_ms > 0)
+        .map(|(path, record)| (record.updated_at_ms, path.clone()))
+        .collect();
+    if resumable.len() <= MAX_POSITION_RECORDS {
+        return;
+    }
+    resumable.sort();
+    let excess = resumable.len() - MAX_POSITION_RECORDS;
+    for (_, path) in resumable.into_iter().take(excess) {
+        let keep_flag = records
+            .get(&path)
+            .is_some_and(|record| record.watched_at_ms.is_some() || record.hw_decode_failure.is_some());
+        if keep_flag {
+            if let Some(record) = records.get_mut(&path) {
+                record.position_ms = 0;
+                record.duration_ms = None;
+            }
+        } else {
+            records.remove(&path);
+        }
+    }
+}
+
+/// 写盘线程命令
+enum WriterCommand {
+    Dirty,
+    Flush,
+    Shutdown,
+}
+
+/// 播放位置历史（内存记录 + 后台写盘线程）
+pub struct PositionHistory {
+    store: PositionStore,
+    file: PathBuf,
+    command_tx: Option<Sender<WriterCommand>>,
+    thread_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl PositionHistory {
+    /// 加载历史文件并启动写盘线程（文件损坏时从空记录开始，首次写入前保留原文件）
+    pub fn open(file: PathBuf) -> Self {
+        Self::with_write_interval(file, MIN_WRITE_INTERVAL)
+    }
+
+    /// 指定写盘间隔（测试使用较短间隔）
+    pub fn with_write_interval(file: PathBuf, min_write_interval: Duration) -> Self {
+        let records = match load(&file) {
+            Ok(records) => {
+                info!("📍 加载播放位置记录: {} 条 ({})", records.len(), file.display());
+                records
+            }
+            Err(e) => {
+                warn!("⚠️  播放位置记录无法读取，从空记录开始: {}", e);
+                HashMap::new()
+            }
+        };
+        let store = PositionStore::new(records);
+
+        let (command_tx, command_rx) = unbounded();
+        let thread_store = store.clone();
+        let thread_file = file.clone();
+        let thread_handle = thread::Builder::new()
+            .name("position-history".to_string())
+            .spawn(move || {
+                let mut dirty = false;
+                let mut last_write: Option<Instant> = None;
+                loop {
+                    let timeout = match (dirty, last_write) {
+                        (false, _) => IDLE_WAIT,
+                        (true, None) => Duration::ZERO,
+                        (true, Some(at)) => min_write_interval.saturating_sub(at.elapsed()),
+                    };
+                    let write_now = match command_rx.recv_timeout(timeout) {
+                        Ok(WriterCommand::Dirty) => {
+                            dirty = true;
+                            false
+                        }
+                        Ok(WriterCommand::Flush) => dirty,
+                        Ok(WriterCommand::Shutdown) => {
+                            if dirty {
+                                write_snapshot(&thread_file, &thread_store);
+                            }
+                            break;
+                        }
+                        Err(RecvTimeoutError::Timeout) => dirty,
+                        // 发送端意外消失（进程异常终止）：不再写盘
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    };
+                    if write_now {
+                        write_snapshot(&thread_file, &thread_store);
+                        dirty = false;
+                        last_write = Some(Instant::now());
+                    }
+                }
+            })
+            .ok();
+        if thread_handle.is_none() {
+            warn!("⚠️  无法启动播放位置写盘线程，位置记录将不会保存");
+        }
+
+        Self { store, file, command_tx: Some(command_tx), thread_handle }
+    }
+
+    /// 历史文件的默认位置（用户数据目录下）
+    pub fn default_file() -> PathBuf {
+        user_data_dir().join("positions.json")
+    }
+
+    /// 记录当前位置和时长（仅更新内存，写盘由后台线程节流；时长未知时传 0，保留已有的时长和已看完标记）
+    pub fn checkpoint(&self, path: &str, position_ms: i64, duration_ms: i64) {
+        let existing = self.store.get(path).unwrap_or_default();
+        let record = PositionRecord {
+            position_ms: position_ms.max(0),
+            updated_at_ms: now_ms(),
+            duration_ms: if duration_ms > 0 { Some(duration_ms) } else { existing.duration_ms },
+            watched_at_ms: existing.watched_at_ms,
+            title: existing.title,
+            hw_decode_failure: existing.hw_decode_failure,
+        };
+        let position_ms = record.position_ms;
+        if self.store.merge(path, record) {
+            debug!("📍 记录播放位置: {} @ {}ms", path, position_ms);
+            self.send(WriterCommand::Dirty);
+        }
+    }
+
+    /// 标记/取消已看完
+    pub fn set_watched(&self, path: &str, watched: bool) {
+        if self.store.set_watched(path, watched.then(now_ms)) {
+            info!("✅ {}: {}", if watched { "已看完" } else { "取消已看完" }, path);
+            self.send(WriterCommand::Dirty);
+        }
+    }
+
+    /// 保存显示标题（只更新已有的记录）
+    pub fn set_title(&self, path: &str, title: &str) {
+        if self.store.set_title(path, title) {
+            debug!("🏷️ 记录标题: {} -> {}", path, title);
+            self.send(WriterCommand::Dirty);
+        }
+    }
+
+    /// 记录/清除硬件解码失败原因
+    pub fn set_hw_decode_failure(&self, path: &str, failure: Option<&str>) {
+        if self.store.set_hw_decode_failure(path, failure, now_ms()) {
+            match failure {
+                Some(failure) => info!("🎞️ 记录硬解失败: {} ({})", path, failure),
+                None => info!("🎞️ 清除硬解失败记录: {}", path),
+            }
+            self.send(WriterCommand::Dirty);
+        }
+    }
+
+    /// 上次记录的硬件解码失败原因
+    pub fn hw_decode_failure(&self, path: &str) -> Option<String> {
+        self.store.get(path)?.hw_decode_failure
+    }
+
+    /// 一次读取多个文件的观看状态
+    pub fn watch_states<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> Vec<WatchState> {
+        self.store.watch_states(paths)
+    }
+
+    /// 立即写盘（不等待完成）
+    pub fn flush(&self) {
+        self.send(WriterCommand::Flush);
+    }
+
+    /// 上次记录的位置
+    pub fn position(&self, path: &str) -> Option<i64> {
+        self.store.get(path).map(|record| record.position_ms)
+    }
+
+    /// 最近播放的文件（按记录时间从新到旧）
+    pub fn recent(&self, limit: usize) -> Vec<(String, PositionRecord)> {
+        self.store.recent(limit)
+    }
+
+    fn send(&self, command: WriterCommand) {
+        if let Some(tx) = &self.command_tx {
+            let _ = tx.send(command);
+        }
+    }
+
+    /// 模拟进程崩溃：写盘线程直接退出，不写入待保存的数据
+    #[cfg(test)]
+    fn simulate_crash(mut self) {
+        self.command_tx = None;
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for PositionHistory {
+    fn drop(&mut self) {
+        // 正常退出：写入未保存的数据并等待写盘完成
+        if let Some(tx) = self.command_tx.take() {
+            let _ = tx.send(WriterCommand::Shutdown);
+        }
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+            debug!("📍 播放位置记录已保存: {}", self.file.display());
+        }
+    }
+}
+
+/// 读取历史文件（文件不存在时返回空记录）
+pub fn load(file: &Path) -> Result<HashMap<String, PositionRecord>> {
+    let json = match fs::read_to_string(file) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_str(&json)
+        .map_err(|e| PlayerError::ConfigError(format!("播放位置记录格式错误 ({}): {}", file.display(), e)))
+}
+
+fn write_snapshot(file: &Path, store: &PositionStore) {
+    let snapshot = store.snapshot();
+    let result = serde_json::to_vec_pretty(&snapshot)
+        .map_err(|e| PlayerError::ConfigError(format!("序列化失败: {}", e)))
+        .and_then(|bytes| write_atomic(file, &bytes));
+    match result {
+        Ok(()) => debug!("💾 写入播放位置记录: {} 条", snapshot.len()),
+        Err(e) => warn!("⚠️  写入播放位置记录失败: {}", e),
+    }
+}
+
+/// 原子写入：先写临时文件并 fsync，再 rename 覆盖目标文件
+pub fn write_atomic(file: &Path, bytes: &[u8]) -> Result<()> {
+    let dir = file.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let mut tmp_name = file.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp = PathBuf::from(tmp_name);
+    {
+        let mut out = File::create(&tmp)?;
+        out.write_all(bytes)?;
+        out.sync_all()?;
+    }
+    fs::rename(&tmp, file)?;
+
+    // 同步目录项，确保 rename 本身落盘（Windows 不支持打开目录，忽略）
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// 独立的临时历史文件
+    fn temp_file(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("myy_player_positions_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("positions.json")
+    }
+
+    #[test]
+    fn test_merge_keeps_newest() {
+        let store = PositionStore::default();
+        assert!(store.merge("a.mkv", PositionRecord { position_ms: 5_000, updated_at_ms: 200, ..Default::default() }));
+        assert!(!store.merge("a.mkv", PositionRecord { position_ms: 1_000, updated_at_ms: 100, ..Default::default() }));
+        assert_eq!(store.get("a.mkv").unwrap().position_ms, 5_000);
+        assert!(store.merge("a.mkv", PositionRecord { position_ms: 9_000, updated_at_ms: 300, ..Default::default() }));
+        assert_eq!(store.get("a.mkv").unwrap().position_ms, 9_000);
+    }
+
+    #[test]
+    fn test_recent_orders_by_update_time() {
+        let store = PositionStore::default();
+        store.merge("old.mkv", PositionRecord { position_ms: 0, updated_at_ms: 100, ..Default::default() });
+        store.merge("new.mkv", PositionRecord { position_ms: 0, updated_at_ms: 300, ..Default::default() });
+        store.merge("mid.mkv", PositionRecord { position_ms: 0, updated_at_ms: 200, ..Default::default() });
+        let names: Vec<_> = store.recent(2).into_iter().map(|(path, _)| path).collect();
+        assert_eq!(names, vec!["new.mkv", "mid.mkv"]);
+        assert_eq!(store.recent(10).len(), 3);
+    }
+
+    #[test]
+    fn test_writes_are_throttled_until_flush() {
+        let file = temp_file("throttle");
+        let history = PositionHistory::with_write_interval(file.clone(), Duration::from_secs(60));
+        history.checkpoint("movie.mkv", 30_000, 0);
+        thread::sleep(Duration::from_millis(200));
+        history.checkpoint("movie.mkv", 60_000, 0);
+        thread::sleep(Duration::from_millis(200));
+
+        // 首次写入立即发生，第二次被节流
+        assert_eq!(load(&file).unwrap()["movie.mkv"].position_ms, 30_000);
+
+        history.flush();
+        drop(history);
+        assert_eq!(load(&file).unwrap()["movie.mkv"].position_ms, 60_000);
+    }
+
+    #[test]
+    fn test_crash_loses_at_most_one_write_interval() {
+        // 时间按 1 秒 = 5ms 缩放：检查点间隔 30s -> 150ms，写盘间隔 60s -> 300ms
+        let file = temp_file("crash");
+        let history = PositionHistory::with_write_interval(file.clone(), Duration::from_millis(300));
+        let mut position_ms = 0;
+        for _ in 0..8 {
+            position_ms += CHECKPOINT_INTERVAL.as_millis() as i64;
+            history.checkpoint("movie.mkv", position_ms, 0);
+            thread::sleep(Duration::from_millis(150));
+        }
+        position_ms += CHECKPOINT_INTERVAL.as_millis() as i64;
+        history.checkpoint("movie.mkv", position_ms, 0);
+        history.simulate_crash();
+
+        let saved = load(&file).unwrap()["movie.mkv"].position_ms;
+        let lost = position_ms - saved;
+        assert!(lost <= MIN_WRITE_INTERVAL.as_millis() as i64, "丢失了 {}ms 的播放进度", lost);
+
+        // 重新打开后从磁盘上的位置继续
+        let reopened = PositionHistory::open(file);
+        assert_eq!(reopened.position("movie.mkv"), Some(saved));
+    }
+
+    #[test]
+    fn test_file_is_never_partially_written() {
+        let file = temp_file("atomic");
+        let history = PositionHistory::with_write_interval(file.clone(), Duration::ZERO);
+        let done = Arc::new(AtomicBool::new(false));
+
+        let reader_file = file.clone();
+        let reader_done = done.clone();
+        let reader = thread::spawn(move || {
+            let mut reads = 0;
+            loop {
+                let finished = reader_done.load(Ordering::SeqCst);
+                if reader_file.exists() {
+                    let records = load(&reader_file).expect("历史文件不完整");
+                    assert!(!records.is_empty());
+                    reads += 1;
+                }
+                if finished {
+                    return reads;
+                }
+            }
+        });
+
+        for i in 0..300 {
+            history.checkpoint(&format!("/media/episode{:03}.mkv", i % 40), i * 1_000, 0);
+        }
+        drop(history);
+        done.store(true, Ordering::SeqCst);
+        assert!(reader.join().unwrap() > 0);
+        assert_eq!(load(&file).unwrap().len(), 40);
+    }
+
+    #[test]
+    fn test_watched_threshold_is_ninety_percent() {
+        assert!(!is_watched(8_999, 10_000, false));
+        assert!(is_watched(9_000, 10_000, false));
+        assert!(is_watched(2 * 3_600_000 * 9 / 10, 2 * 3_600_000, false));
+        // 时长未知时只在播放结束时标记
+        assert!(!is_watched(50_000, 0, false));
+        assert!(is_watched(50_000, 0, true));
+    }
+
+    #[test]
+    fn test_short_files_count_as_watched_when_finished() {
+        // 不足 60 秒的文件：播放结束时总是算看完（即使最后的位置没有达到估算时长的 90%）
+        assert!(is_watched(20_000, 45_000, true));
+        assert!(!is_watched(20_000, 45_000, false));
+
+        let file = temp_file("watched");
+        let history = PositionHistory::with_write_interval(file.clone(), Duration::ZERO);
+        history.checkpoint("clip.mp4", 20_000, 45_000);
+        assert_eq!(history.watch_states(["clip.mp4"]), vec![WatchState::Partial(20_000.0 / 45_000.0)]);
+        history.set_watched("clip.mp4", true);
+        // 之后的检查点保留标记
+        history.checkpoint("clip.mp4", 0, 0);
+        assert_eq!(history.watch_states(["clip.mp4", "other.mp4"]), vec![WatchState::Watched, WatchState::Unwatched]);
+        drop(history);
+
+        let reopened = load(&file).unwrap();
+        assert!(reopened["clip.mp4"].watched_at_ms.is_some());
+        assert_eq!(reopened["clip.mp4"].duration_ms, Some(45_000));
+    }
+
+    #[test]
+    fn test_title_is_kept_across_checkpoints() {
+        let file = temp_file("title");
+        let history = PositionHistory::with_write_interval(file.clone(), Duration::ZERO);
+        // 没有记录时不保存标题
+        history.set_title("S01E03.1080p.WEB-DL.x265-GROUP.mkv", "Show - S01E03");
+        assert!(history.recent(10).is_empty());
+
+        history.checkpoint("S01E03.1080p.WEB-DL.x265-GROUP.mkv", 10_000, 0);
+        history.set_title("S01E03.1080p.WEB-DL.x265-GROUP.mkv", "Show - S01E03");
+        history.checkpoint("S01E03.1080p.WEB-DL.x265-GROUP.mkv", 20_000, 0);
+        drop(history);
+
+        let reopened = load(&file).unwrap();
+        let record = &reopened["S01E03.1080p.WEB-DL.x265-GROUP.mkv"];
+        assert_eq!((record.position_ms, record.title.as_deref()), (20_000, Some("Show - S01E03")));
+    }
+
+    #[test]
+    fn test_hw_decode_failure_is_remembered() {
+        let file = temp_file("hw_failure");
+        let history = PositionHistory::with_write_interval(file.clone(), Duration::ZERO);
+        history.set_hw_decode_failure("hevc10.mkv", Some("硬解初始化失败: device lost"));
+        history.checkpoint("hevc10.mkv", 30_000, 0);
+        drop(history);
+
+        let history = PositionHistory::with_write_interval(file.clone(), Duration::ZERO);
+        assert_eq!(history.hw_decode_failure("hevc10.mkv").as_deref(), Some("硬解初始化失败: device lost"));
+        assert_eq!(history.position("hevc10.mkv"), Some(30_000));
+        history.set_hw_decode_failure("hevc10.mkv", None);
+        assert_eq!(history.hw_decode_failure("hevc10.mkv"), None);
+        assert_eq!(history.position("hevc10.mkv"), Some(30_000));
+
+        // 只有失败记录的条目在清除后删除
+        history.set_hw_decode_failure("clip.mp4", Some("硬解播放中出错: x"));
+        history.set_hw_decode_failure("clip.mp4", None);
+        assert_eq!(history.recent(10).len(), 1);
+    }
+
+    #[test]
+    fn test_watched_flag_survives_eviction() {
+        let store = PositionStore::default();
+        let record = |position_ms, updated_at_ms| PositionRecord { position_ms, updated_at_ms, ..Default::default() };
+        store.merge("watched.mkv", record(50_000, 1));
+        store.set_watched("watched.mkv", Some(1));
+        store.merge("unwatched.mkv", record(50_000, 2));
+        for i in 0..MAX_POSITION_RECORDS {
+            store.merge(&format!("newer{}.mkv", i), record(1_000, 100 + i as u64));
+        }
+
+        // 最旧的两条位置被淘汰：已看完的只清除位置
+        assert_eq!(store.get("unwatched.mkv"), None);
+        let watched = store.get("watched.mkv").unwrap();
+        assert_eq!((watched.position_ms, watched.watch_state()), (0, WatchState::Watched));
+        assert_eq!(store.recent(usize::MAX).len(), MAX_POSITION_RECORDS + 1);
+
+        // 取消标记后整条删除
+        assert!(store.set_watched("watched.mkv", None));
+        assert_eq!(store.get("watched.mkv"), None);
+    }
+
+    #[test]
+    fn test_corrupt_file_is_reported() {
+        let file = temp_file("corrupt");
+        fs::write(&file, "{\"movie.mkv\": {\"position_ms\": 12").unwrap();
+        assert!(matches!(load(&file), Err(PlayerError::ConfigError(_))));
+        assert!(load(&file.with_file_name("missing.json")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+// 预览画面（胶片视图、进度条悬停预览、最近文件缩略图共用的解码和缓存）
+//
+// 预览只需要小图：解码器在 YUV -> RGBA 转换时直接缩小到用途对应的宽度（悬停预览 160px，
+// 胶片和最近文件缩略图 320px），不再先转换整幅 4K 画面再缩小。HDR (PQ) 画面不做完整的色调映射，
+// 只按亮度查表做近似压缩，避免预览发灰。生成的预览先查内存和磁盘缓存（见 preview_cache），
+// 同一个文件的连续请求复用已打开的解封装器和解码器。
+
+use crate::core::{PlayerError, Result, VideoFrame};
+use crate::player::preview_cache::PreviewCache;
+use crate::player::thumbnailer::downscale;
+use crate::player::tone_map::pq_to_nits;
+use crate::player::{Demuxer, VideoDecoder};
+use log::debug;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// 每个预览 Seek 后最多读取的视频包数（超过后放弃该时间点，避免超长 GOP 拖慢预览）
+const MAX_PACKETS_PER_PREVIEW: usize = 300;
+
+/// SDR 参考白（尼特，PQ 亮度按此归一化）
+const SDR_WHITE_NITS: f64 = 100.0;
+
+/// 近似色调映射中压缩到参考白的最高亮度（尼特）
+const PEAK_NITS: f64 = 1000.0;
+
+/// 预览用途（决定最大宽度）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PreviewPurpose {
+    /// 进度条悬停预览
+    Hover,
+    /// 胶片视图
+    Filmstrip,
+    /// 最近文件缩略图
+    RecentThumbnail,
+}
+
+impl PreviewPurpose {
+    /// 最大宽度（像素）
+    pub fn max_width(self) -> u32 {
+        match self {
+            PreviewPurpose::Hover => 160,
+            PreviewPurpose::Filmstrip | PreviewPurpose::RecentThumbnail => 320,
+        }
+    }
+}
+
+/// 预览请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewRequest {
+    /// 本地媒体文件
+    pub source: PathBuf,
+    /// 时间点（毫秒）
+    pub pts: i64,
+    /// 最大宽度（像素，高度按画面比例计算）
+    pub max_width: u32,
+    pub purpose: PreviewPurpose,
+}
+
+impl PreviewRequest {
+    /// 按用途的默认宽度创建请求
+    pub fn new(source: impl Into<PathBuf>, pts: i64, purpose: PreviewPurpose) -> Self {
+        Self { source: source.into(), pts, max_width: purpose.max_width(), purpose }
+    }
+}
+
+/// 缩小到 max_width 宽以内后的尺寸（不放大，保持画面比例）
+pub fn scaled_size(width: u32, height: u32, max_width: u32) -> (u32, u32) {
+    if width <= max_width || width == 0 {
+        return (width, height);
+    }
+    let max_width = max_width.max(1);
+    (max_width, ((height as u64 * max_width as u64 / width as u64) as u32).max(1))
+}
+
+/// 亮度映射表：PQ 编码的亮度值 -> SDR 亮度值（扩展 Reinhard 曲线，PEAK_NITS 映射到参考白）
+fn pq_luma_table() -> &'static [u8; 256] {
+    static TABLE: OnceLock<[u8; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let white = PEAK_NITS / SDR_WHITE_NITS;
+        let mut table = [0u8; 256];
+        for (code, value) in table.iter_mut().enumerate() {
+            let l = pq_to_nits(code as f64 / 255.0) / SDR_WHITE_NITS;
+            let mapped = (l * (1.0 + l / (white * white)) / (1.0 + l)).min(1.0);
+            *value = (mapped.powf(1.0 / 2.2) * 255.0).round() as u8;
+        }
+        table
+    })
+}
+
+/// HDR (PQ) 预览的亮度近似映射：按像素亮度查表得到目标亮度，RGB 等比缩放（不做色域转换）
+pub fn tone_map_pq_luma(frame: &mut VideoFrame) {
+    let table = pq_luma_table();
+    let data = frame.data.make_mut();
+    for pixel in data.chunks_exact_mut(4) {
+        // BT.2020 亮度系数（整数近似，和为 1024）
+        let luma = (269 * pixel[0] as u32 + 694 * pixel[1] as u32 + 61 * pixel[2] as u32) >> 10;
+        if luma == 0 {
+            continue;
+        }
+        let target = table[luma as usize] as u32;
+        for channel in &mut pixel[..3] {
+            *channel = (*channel as u32 * target / luma).min(255) as u8;
+        }
+    }
+}
+
+/// 打开的预览解码器（同一文件、同一宽度的请求复用）
+pub struct PreviewDecoder {
+    source: PathBuf,
+    max_width: u32,
+    demuxer: Demuxer,
+    decoder: VideoDecoder,
+}
+
+impl PreviewDecoder {
+    /// 打开文件并创建按 max_width 缩小输出的解码器
+    pub fn open(source: &Path, max_width: u32) -> Result<Self> {
+        let demuxer = Demuxer::open(&source.to_string_lossy())?;
+        let stream = demuxer.video_stream().ok_or(PlayerError::NoVideoStream)?;
+        let decoder = VideoDecoder::from_stream_preview(stream, max_width)?;
+        Ok(Self { source: source.to_path_buf(), max_width, demuxer, decoder })
+    }
+
+    fn matches(&self, request: &PreviewRequest) -> bool {
+        self.source == request.source && self.max_width == request.max_width
+    }
+
+    /// Seek 到 time_ms 后解码出的第一帧（cancel 被设置时提前返回）
+    pub fn decode_at(&mut self, time_ms: i64, cancel: &AtomicBool) -> Result<Option<VideoFrame>> {
+        decode_first_frame(&mut self.demuxer, &mut self.decoder, time_ms, cancel)
+    }
+}
+
+/// Seek 到 time_ms 后解码出的第一帧（解码器在返回前复位，供下一个时间点使用）
+pub fn decode_first_frame(
+    demuxer: &mut Demuxer,
+    decoder: &mut VideoDecoder,
+    time_ms: i64,
+    cancel: &AtomicBool,
+) -> Result<Option<VideoFrame>> {
+    demuxer.seek(time_ms)?;
+    let mut video_packets = 0;
+    let mut frame = None;
+    while let Some((packet, is_video, _)) = demuxer.read_packet()? {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        if !is_video {
+            continue;
+        }
+        if let Some(decoded) = decoder.decode(&packet)?.into_iter().next() {
+            frame = Some(decoded);
+            break;
+        }
+        video_packets += 1;
+        if video_packets >= MAX_PACKETS_PER_PREVIEW {
+            break;
+        }
+    }
+    let flushed = decoder.flush()?;
+    Ok(frame.or_else(|| flushed.into_iter().next()))
+}
+
+/// 预览管线（每个使用者一个，缓存在所有管线间共享）
+pub struct PreviewPipeline {
+    cache: Arc<PreviewCache>,
+    decoder: Option<PreviewDecoder>,
+}
+
+impl PreviewPipeline {
+    pub fn new(cache: Arc<PreviewCache>) -> Self {
+        Self { cache, decoder: None }
+    }
+
+    /// 生成预览（先查缓存；没有解码出画面时返回 None）
+    pub fn render(&mut self, request: &PreviewRequest, cancel: &AtomicBool) -> Result<Option<VideoFrame>> {
+        if let Some(frame) = self.cache.get(request) {
+            return Ok(Some(frame));
+        }
+        if !self.decoder.as_ref().is_some_and(|decoder| decoder.matches(request)) {
+            self.decoder = None;
+            self.decoder = Some(PreviewDecoder::open(&request.source, request.max_width)?);
+        }
+        let Some(decoder) = self.decoder.as_mut() else {
+            return Ok(None);
+        };
+        let Some(frame) = decoder.decode_at(request.pts, cancel)? else {
+            debug!("🖼️ {}ms 处没有解码出预览画面", request.pts);
+            return Ok(None);
+        };
+        // 解码时已缩小；这里只防止解码器中途改变尺寸后 scaler 仍按旧尺寸输出
+        let frame = downscale(&frame, request.max_width);
+        self.cache.insert(request, &frame);
+        Ok(Some(frame))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::PixelFormat;
+
+    #[test]
+    fn test_purpose_widths_and_scaled_size() {
+        let request = PreviewRequest::new("/videos/a.mkv", 1_000, PreviewPurpose::Hover);
+        assert_eq!(request.max_width, 160);
+        assert_eq!(PreviewPurpose::Filmstrip.max_width(), 320);
+        assert_eq!(PreviewPurpose::RecentThumbnail.max_width(), 320);
+
+        assert_eq!(scaled_size(3840, 2160, 480), (480, 270));
+        assert_eq!(scaled_size(3840, 1600, 320), (320, 133));
+        // 不放大
+        assert_eq!(scaled_size(160, 90, 320), (160, 90));
+        assert_eq!(scaled_size(4000, 1, 320), (320, 1));
+    }
+
+    #[test]
+    fn test_pq_luma_table_is_monotonic_and_bounded() {
+        let table = pq_luma_table();
+        assert_eq!(table[0], 0);
+        assert!(table.windows(2).all(|pair| pair[0] <= pair[1]));
+        // 1000 尼特（PQ 约 0.75）映射到参考白附近，100 尼特（PQ 约 0.51）明显低于参考白
+        assert!(table[192] >= 240, "{}", table[192]);
+        assert!((120..220).contains(&table[130]), "{}", table[130]);
+    }
+
+    #[test]
+    fn test_tone_map_keeps_hue_and_black() {
+        let pixels = vec![0, 0, 0, 255, 130, 130, 130, 255, 140, 100, 60, 255];
+        let mut frame = VideoFrame { pts: 0, duration: 0, width: 3, height: 1, format: PixelFormat::RGBA, planes: None, data: pixels.into() };
+        tone_map_pq_luma(&mut frame);
+        assert_eq!(frame.data[..4], [0, 0, 0, 255]);
+        // 灰色仍为灰色（PQ 中灰提亮），alpha 不变
+        let gray = &frame.data[4..8];
+        assert!(gray[0] == gray[1] && gray[1] == gray[2] && gray[0] > 130 && gray[3] == 255);
+        // 彩色像素各通道按同一比例缩放，保持顺序
+        let color = &frame.data[8..12];
+        assert!(color[0] > color[1] && color[1] > color[2]);
+    }
+}
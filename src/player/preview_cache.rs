@@ -0,0 +1,384 @@
+// 预览缓存（内存 + 磁盘）
+//
+// 内存中保留最近的若干张预览；磁盘缓存按 路径 + 文件大小 + 修改时间 + 时间点 + 宽度 的哈希命名，
+// 编码为质量 75 的 JPEG，单张超过 30 KB 时降低质量重试，仍然过大则不写入。
+// 存储压力：缓存目录超过预算或磁盘剩余空间不足 1 GB 时，按修改时间删除最旧的条目直到预算的四分之一，
+// 并在本次运行中停止写入磁盘（只用内存缓存，已有的磁盘条目仍可读取），只记录一次警告。
+
+use crate::core::{PixelFormat, PlayerError, Result, VideoFrame};
+use crate::player::position_history::write_atomic;
+use crate::player::preview::PreviewRequest;
+use log::{debug, info, warn};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 内存中保留的预览数
+const MEMORY_CAPACITY: usize = 48;
+
+/// 磁盘缓存条目的 JPEG 质量
+const JPEG_QUALITY: u8 = 75;
+
+/// 质量 75 仍然过大时依次尝试的质量
+const FALLBACK_QUALITIES: [u8; 2] = [55, 35];
+
+/// 单个磁盘缓存条目的大小上限
+pub const MAX_ENTRY_BYTES: usize = 30 * 1024;
+
+/// 磁盘缓存目录的默认预算
+pub const DEFAULT_BUDGET_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 磁盘剩余空间低于该值时停止写入
+pub const MIN_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// 每写入多少个条目重新检查一次存储压力
+const PRESSURE_CHECK_INTERVAL: u32 = 32;
+
+/// 缓存条目的扩展名
+const ENTRY_EXTENSION: &str = "jpg";
+
+/// 磁盘缓存状态
+#[derive(Debug, Default)]
+struct DiskState {
+    /// 距上次检查存储压力写入的条目数（None 表示尚未检查）
+    writes_since_check: Option<u32>,
+    /// 存储压力下已停止写入磁盘
+    writes_disabled: bool,
+}
+
+/// 预览缓存（可在多个预览管线间共享）
+pub struct PreviewCache {
+    dir: Option<PathBuf>,
+    budget_bytes: u64,
+    min_free_bytes: u64,
+    memory: Mutex<VecDeque<(String, VideoFrame)>>,
+    disk: Mutex<DiskState>,
+}
+
+impl PreviewCache {
+    /// 内存 + 磁盘缓存
+    pub fn open(dir: PathBuf) -> Self {
+        Self::with_limits(Some(dir), DEFAULT_BUDGET_BYTES, MIN_FREE_BYTES)
+    }
+
+    /// 只使用内存缓存
+    pub fn memory_only() -> Self {
+        Self::with_limits(None, 0, 0)
+    }
+
+    fn with_limits(dir: Option<PathBuf>, budget_bytes: u64, min_free_bytes: u64) -> Self {
+        Self { dir, budget_bytes, min_free_bytes, memory: Mutex::new(VecDeque::new()), disk: Mutex::new(DiskState::default()) }
+    }
+
+    /// 默认磁盘缓存目录
+    pub fn default_dir() -> PathBuf {
+        crate::core::user_data_dir().join("thumbnails")
+    }
+
+    /// 存储压力下是否已停止写入磁盘
+    pub fn disk_writes_disabled(&self) -> bool {
+        self.disk.lock().unwrap().writes_disabled
+    }
+
+    /// 查找缓存的预览（内存优先，其次磁盘）
+    pub fn get(&self, request: &PreviewRequest) -> Option<VideoFrame> {
+        let key = cache_key(request)?;
+        if let Some(frame) = self.memory.lock().unwrap().iter().find(|(k, _)| *k == key).map(|(_, frame)| frame.clone()) {
+            return Some(frame);
+        }
+        let frame = decode_entry(&fs::read(self.dir.as_ref()?.join(entry_name(&key))).ok()?, request.pts)?;
+        self.remember(key, &frame);
+        Some(frame)
+    }
+
+    /// 保存新生成的预览
+    pub fn insert(&self, request: &PreviewRequest, frame: &VideoFrame) {
+        let Some(key) = cache_key(request) else {
+            return;
+        };
+        self.remember(key.clone(), frame);
+        if let Some(dir) = &self.dir {
+            if self.disk_writable(dir) {
+                if let Err(e) = self.write_entry(dir, &key, frame) {
+                    debug!("🖼️ 预览缓存写入失败: {}", e);
+                }
+            }
+        }
+    }
+
+    fn remember(&self, key: String, frame: &VideoFrame) {
+        let mut memory = self.memory.lock().unwrap();
+        memory.retain(|(k, _)| *k != key);
+        memory.push_front((key, frame.clone()));
+        memory.truncate(MEMORY_CAPACITY);
+    }
+
+    /// 是否可以写入磁盘（定期检查存储压力，压力过大时清理并停止写入）
+    fn disk_writable(&self, dir: &Path) -> bool {
+        let mut state = self.disk.lock().unwrap();
+        if state.writes_disabled {
+            return false;
+        }
+        let due = state.writes_since_check.is_none_or(|writes| writes >= PRESSURE_CHECK_INTERVAL);
+        if !due {
+            state.writes_since_check = state.writes_since_check.map(|writes| writes + 1);
+            return true;
+        }
+        state.writes_since_check = Some(1);
+
+        let used = dir_entries(dir).iter().map(|entry| entry.1).sum::<u64>();
+        let free = available_space(dir);
+        let over_budget = used > self.budget_bytes;
+        let low_disk = free.is_some_and(|free| free < self.min_free_bytes);
+        if !over_budget && !low_disk {
+            return true;
+        }
+        let removed = evict_oldest(dir, self.budget_bytes / 4);
+        state.writes_disabled = true;
+        warn!(
+            "⚠️ 预览缓存存储压力（缓存 {} KB / 预算 {} KB，磁盘剩余 {}），已清理 {} 个条目，本次运行只使用内存缓存",
+            used / 1024,
+            self.budget_bytes / 1024,
+            free.map_or_else(|| "未知".to_string(), |free| format!("{} MB", free / (1024 * 1024))),
+            removed
+        );
+        false
+    }
+
+    fn write_entry(&self, dir: &Path, key: &str, frame: &VideoFrame) -> Result<()> {
+        let Some(bytes) = encode_entry(frame)? else {
+            debug!("🖼️ 预览 {}x{} 编码后超过 {} KB，不写入磁盘", frame.width, frame.height, MAX_ENTRY_BYTES / 1024);
+            return Ok(());
+        };
+        write_atomic(&dir.join(entry_name(key)), &bytes)
+    }
+}
+
+/// 缓存键（源文件不存在时为 None）：按 FNV-1a 哈希 路径 + 大小 + 修改时间 + 时间点 + 宽度
+fn cache_key(request: &PreviewRequest) -> Option<String> {
+    let metadata = fs::metadata(&request.source).ok()?;
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_millis() as u64);
+    let path = request.source.to_string_lossy();
+    let bytes = path
+        .bytes()
+        .chain(metadata.len().to_le_bytes())
+        .chain(modified_ms.to_le_bytes())
+        .chain(request.pts.to_le_bytes())
+        .chain(request.max_width.to_le_bytes());
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    Some(format!("{:016x}", hash))
+}
+
+fn entry_name(key: &str) -> String {
+    format!("{}.{}", key, ENTRY_EXTENSION)
+}
+
+/// 编码为 JPEG（超过单条上限时降低质量重试；仍然过大时返回 None）
+fn encode_entry(frame: &VideoFrame) -> Result<Option<Vec<u8>>> {
+    // JPEG 没有 alpha 通道
+    let rgb: Vec<u8> = frame.data.chunks_exact(4).flat_map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect();
+    for quality in std::iter::once(JPEG_QUALITY).chain(FALLBACK_QUALITIES) {
+        let mut bytes = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, quality)
+            .encode(&rgb, frame.width, frame.height, image::ColorType::Rgb8)
+            .map_err(|e| PlayerError::Other(format!("预览编码失败: {}", e)))?;
+        if bytes.len() <= MAX_ENTRY_BYTES {
+            return Ok(Some(bytes));
+        }
+    }
+    Ok(None)
+}
+
+/// 解码磁盘缓存条目（损坏时为 None）
+fn decode_entry(bytes: &[u8], pts: i64) -> Option<VideoFrame> {
+    let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Jpeg).ok()?.to_rgba8();
+    Some(VideoFrame {
+        pts,
+        duration: 0,
+        width: image.width(),
+        height: image.height(),
+        format: PixelFormat::RGBA,
+        planes: None,
+        data: image.into_raw().into(),
+    })
+}
+
+/// 缓存目录中的条目（路径, 大小, 修改时间）
+fn dir_entries(dir: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == ENTRY_EXTENSION))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((entry.path(), metadata.len(), metadata.modified().unwrap_or(UNIX_EPOCH)))
+        })
+        .collect()
+}
+
+/// 按修改时间从旧到新删除条目，直到总大小不超过 target_bytes，返回删除的条目数
+fn evict_oldest(dir: &Path, target_bytes: u64) -> usize {
+    let mut entries = dir_entries(dir);
+    entries.sort_by_key(|entry| entry.2);
+    let mut used = entries.iter().map(|entry| entry.1).sum::<u64>();
+    let mut removed = 0;
+    for (path, size, _) in entries {
+        if used <= target_bytes {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            used = used.saturating_sub(size);
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        info!("🧹 已清理 {} 个预览缓存条目", removed);
+    }
+    removed
+}
+
+/// 目录所在磁盘的剩余可用空间（无法获取时为 None）
+#[cfg(unix)]
+fn available_space(dir: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(dir.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs 是纯 C 结构体，全零是合法的初始值
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: path 是以 NUL 结尾的有效 C 字符串，stat 指向调用期间有效的可写结构体
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// 目录所在磁盘的剩余可用空间（无法获取时为 None）
+#[cfg(windows)]
+fn available_space(dir: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+    let wide: Vec<u16> = dir.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut free = 0u64;
+    // SAFETY: wide 是以 NUL 结尾的 UTF-16 路径，free 指向调用期间有效的 u64
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut free), None, None) }.ok()?;
+    Some(free)
+}
+
+/// 目录所在磁盘的剩余可用空间（无法获取时为 None）
+#[cfg(not(any(unix, windows)))]
+fn available_space(_dir: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::preview::PreviewPurpose;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("myy_preview_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 带渐变和噪点的画面（接近真实画面的压缩难度）
+    fn frame(width: u32, height: u32) -> VideoFrame {
+        let mut seed = 1u32;
+        let data = (0..width * height)
+            .flat_map(|i| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                let noise = (seed >> 24) as u8 / 8;
+                let (x, y) = (i % width, i / width);
+                [((x * 255 / width) as u8).saturating_add(noise / 2), (y * 255 / height) as u8, 128 + noise, 255]
+            })
+            .collect::<Vec<u8>>();
+        VideoFrame { pts: 0, duration: 0, width, height, format: PixelFormat::RGBA, planes: None, data: data.into() }
+    }
+
+    #[test]
+    fn test_disk_entries_are_capped_jpegs() {
+        let dir = temp_dir("entries");
+        let media = dir.join("media.mkv");
+        fs::write(&media, b"media").unwrap();
+        let cache_dir = dir.join("cache");
+        let request = PreviewRequest::new(&media, 5_000, PreviewPurpose::Filmstrip);
+
+        let cache = PreviewCache::open(cache_dir.clone());
+        assert!(cache.get(&request).is_none());
+        cache.insert(&request, &frame(320, 180));
+        let entries = dir_entries(&cache_dir);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].1 as usize <= MAX_ENTRY_BYTES, "{} 字节", entries[0].1);
+
+        // 新的缓存实例（没有内存条目）从磁盘读取
+        let reopened = PreviewCache::open(cache_dir.clone());
+        let cached = reopened.get(&request).expect("磁盘缓存未命中");
+        assert_eq!((cached.width, cached.height, cached.pts), (320, 180, 5_000));
+        // 不同的时间点或宽度是不同的条目
+        assert!(reopened.get(&PreviewRequest { pts: 6_000, ..request.clone() }).is_none());
+        assert!(reopened.get(&PreviewRequest::new(&media, 5_000, PreviewPurpose::Hover)).is_none());
+
+        // 源文件变化后缓存失效
+        fs::write(&media, b"changed media").unwrap();
+        assert!(reopened.get(&request).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_storage_pressure_evicts_and_switches_to_memory_only() {
+        let dir = temp_dir("pressure");
+        let media = dir.join("media.mkv");
+        fs::write(&media, b"media").unwrap();
+        let cache_dir = dir.join("cache");
+        fs::create_dir_all(&cache_dir).unwrap();
+        for i in 0..8 {
+            fs::write(cache_dir.join(format!("old{}.jpg", i)), vec![0u8; 10 * 1024]).unwrap();
+        }
+
+        // 预算 40 KB，已有 80 KB：清理到 10 KB 以内并停止写入磁盘
+        let cache = PreviewCache::with_limits(Some(cache_dir.clone()), 40 * 1024, 0);
+        let request = PreviewRequest::new(&media, 1_000, PreviewPurpose::Filmstrip);
+        cache.insert(&request, &frame(64, 36));
+        assert!(cache.disk_writes_disabled());
+        let used = dir_entries(&cache_dir).iter().map(|entry| entry.1).sum::<u64>();
+        assert!(used <= 10 * 1024, "清理后仍有 {} 字节", used);
+        // 内存缓存仍然可用，新的条目不再写入磁盘
+        assert!(cache.get(&request).is_some());
+        cache.insert(&PreviewRequest { pts: 2_000, ..request.clone() }, &frame(64, 36));
+        assert_eq!(dir_entries(&cache_dir).len() as u64 * 10 * 1024, used);
+
+        // 磁盘剩余空间不足时同样只使用内存缓存
+        let low_disk = PreviewCache::with_limits(Some(cache_dir.clone()), DEFAULT_BUDGET_BYTES, u64::MAX);
+        low_disk.insert(&request, &frame(64, 36));
+        assert!(low_disk.disk_writes_disabled());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_memory_cache_is_bounded() {
+        let dir = temp_dir("memory");
+        let media = dir.join("media.mkv");
+        fs::write(&media, b"media").unwrap();
+        let cache = PreviewCache::memory_only();
+        for pts in 0..(MEMORY_CAPACITY as i64 + 5) {
+            cache.insert(&PreviewRequest::new(&media, pts, PreviewPurpose::Hover), &frame(8, 4));
+        }
+        assert_eq!(cache.memory.lock().unwrap().len(), MEMORY_CAPACITY);
+        assert!(cache.get(&PreviewRequest::new(&media, 0, PreviewPurpose::Hover)).is_none());
+        assert!(cache.get(&PreviewRequest::new(&media, MEMORY_CAPACITY as i64, PreviewPurpose::Hover)).is_some());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,173 @@
+// 解封装预读限制（按媒体时间）
+//
+// DemuxerThread 原本只受 packet 通道容量限制：通道容量按包数计算，对低码率的网络点播来说
+// 能装下很长的内容，用户看一分钟就关掉时，后面的数据都白白下载了。这里按媒体时间限制预读：
+// - 解封装线程记录每个流已发出的最大时间戳，解码线程把播放时钟写入共享的原子变量
+// - 预读量取各个流领先播放位置的最小值（任何一个流的缓冲不足时都不等待）
+// - 预读量超过窗口时解封装线程在命令通道上等待（Seek / Stop 能立即唤醒），直到预读量回落
+// - Seek 时清空已发出的时间戳，从目标位置重新计算
+//
+// 默认窗口：网络点播 60 秒，本地文件 10 秒；直播由服务器控制发送速度，不限制。
+
+use crate::player::demuxer_source::PacketType;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 网络点播的默认预读窗口
+pub const NETWORK_READ_AHEAD: Duration = Duration::from_secs(60);
+
+/// 本地文件的默认预读窗口
+pub const LOCAL_READ_AHEAD: Duration = Duration::from_secs(10);
+
+/// 尚未发出数据包时的占位值
+const NONE: i64 = i64::MIN;
+
+/// 默认预读窗口（直播不限制）
+pub fn default_window(is_live: bool, is_network: bool) -> Option<Duration> {
+    match (is_live, is_network) {
+        (true, _) => None,
+        (false, true) => Some(NETWORK_READ_AHEAD),
+        (false, false) => Some(LOCAL_READ_AHEAD),
+    }
+}
+
+/// 预读状态（解封装线程写入已发出的时间戳，解码线程写入播放位置，统计面板读取预读量）
+#[derive(Debug, Clone)]
+pub struct ReadAhead {
+    inner: Arc<ReadAheadInner>,
+}
+
+#[derive(Debug)]
+struct ReadAheadInner {
+    window_ms: AtomicI64,  // 小于等于 0 表示不限制
+    position_ms: AtomicI64,
+    video_sent_ms: AtomicI64,
+    audio_sent_ms: AtomicI64,
+}
+
+impl ReadAhead {
+    pub fn new(window: Option<Duration>) -> Self {
+        let read_ahead = Self {
+            inner: Arc::new(ReadAheadInner {
+                window_ms: AtomicI64::new(0),
+                position_ms: AtomicI64::new(0),
+                video_sent_ms: AtomicI64::new(NONE),
+                audio_sent_ms: AtomicI64::new(NONE),
+            }),
+        };
+        read_ahead.set_window(window);
+        read_ahead
+    }
+
+    /// 修改预读窗口（None 表示不限制）
+    pub fn set_window(&self, window: Option<Duration>) {
+        let window_ms = window.map_or(0, |window| window.as_millis().min(i64::MAX as u128) as i64);
+        self.inner.window_ms.store(window_ms, Ordering::Relaxed);
+    }
+
+    pub fn window(&self) -> Option<Duration> {
+        let window_ms = self.inner.window_ms.load(Ordering::Relaxed);
+        (window_ms > 0).then(|| Duration::from_millis(window_ms as u64))
+    }
+
+    /// 更新播放位置（解码线程按播放时钟写入）
+    pub fn set_position(&self, position_ms: i64) {
+        self.inner.position_ms.store(position_ms, Ordering::Relaxed);
+    }
+
+    /// 记录已发往解码线程的数据包时间戳
+    pub fn record_sent(&self, packet_type: PacketType, pts_ms: i64) {
+        let sent = match packet_type {
+            PacketType::Video => &self.inner.video_sent_ms,
+            PacketType::Audio => &self.inner.audio_sent_ms,
+            PacketType::Subtitle => return,
+        };
+        sent.fetch_max(pts_ms, Ordering::Relaxed);
+    }
+
+    /// Seek 后从目标位置重新计算
+    pub fn reset(&self, position_ms: i64) {
+        self.inner.video_sent_ms.store(NONE, Ordering::Relaxed);
+        self.inner.audio_sent_ms.store(NONE, Ordering::Relaxed);
+        self.set_position(position_ms);
+    }
+
+    /// 当前预读量（毫秒；还没有发出带时间戳的数据包时为 None）
+    pub fn lead_ms(&self) -> Option<i64> {
+        let position_ms = self.inner.position_ms.load(Ordering::Relaxed);
+        [&self.inner.video_sent_ms, &self.inner.audio_sent_ms]
+            .into_iter()
+            .map(|sent| sent.load(Ordering::Relaxed))
+            .filter(|&sent| sent != NONE)
+            .map(|sent| (sent - position_ms).max(0))
+            .min()
+    }
+
+    /// 各个流都已发出的时间戳（进度条的缓冲位置；还没有发出带时间戳的数据包时为 None）
+    pub fn buffered_to_ms(&self) -> Option<i64> {
+        [&self.inner.video_sent_ms, &self.inner.audio_sent_ms]
+            .into_iter()
+            .map(|sent| sent.load(Ordering::Relaxed))
+            .filter(|&sent| sent != NONE)
+            .min()
+    }
+
+    /// 预读量是否已超过窗口（解封装线程应暂停读包）
+    pub fn is_ahead(&self) -> bool {
+        let window_ms = self.inner.window_ms.load(Ordering::Relaxed);
+        window_ms > 0 && self.lead_ms().is_some_and(|lead| lead > window_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_windows() {
+        assert_eq!(default_window(true, true), None);
+        assert_eq!(default_window(false, true), Some(NETWORK_READ_AHEAD));
+        assert_eq!(default_window(false, false), Some(LOCAL_READ_AHEAD));
+    }
+
+    #[test]
+    fn test_lead_is_the_smallest_stream_lead() {
+        let read_ahead = ReadAhead::new(Some(Duration::from_secs(10)));
+        assert_eq!(read_ahead.lead_ms(), None);
+        assert!(!read_ahead.is_ahead());
+
+        read_ahead.set_position(1_000);
+        read_ahead.record_sent(PacketType::Video, 15_000);
+        read_ahead.record_sent(PacketType::Video, 12_000);
+        read_ahead.record_sent(PacketType::Subtitle, 90_000);
+        assert_eq!(read_ahead.lead_ms(), Some(14_000));
+        assert!(read_ahead.is_ahead());
+
+        // 音频缓冲不足时继续读包
+        read_ahead.record_sent(PacketType::Audio, 5_000);
+        assert_eq!(read_ahead.lead_ms(), Some(4_000));
+        assert!(!read_ahead.is_ahead());
+        assert_eq!(read_ahead.buffered_to_ms(), Some(5_000));
+
+        // 播放位置超过已发出的数据时预读量为 0
+        read_ahead.set_position(20_000);
+        assert_eq!(read_ahead.lead_ms(), Some(0));
+    }
+
+    #[test]
+    fn test_reset_and_unlimited_window() {
+        let read_ahead = ReadAhead::new(Some(Duration::from_secs(10)));
+        read_ahead.record_sent(PacketType::Video, 60_000);
+        assert!(read_ahead.is_ahead());
+        read_ahead.reset(120_000);
+        assert_eq!(read_ahead.lead_ms(), None);
+        assert_eq!(read_ahead.buffered_to_ms(), None);
+        assert!(!read_ahead.is_ahead());
+
+        read_ahead.record_sent(PacketType::Video, 600_000);
+        read_ahead.set_window(None);
+        assert_eq!(read_ahead.window(), None);
+        assert!(!read_ahead.is_ahead());
+    }
+}
@@ -0,0 +1,275 @@
+// 单曲循环（短的本地文件无缝循环）
+//
+// 单曲循环时，解封装线程读到文件末尾后在后台 seek 回开头继续读包，并给之后的数据包加上一遍的时长作为时间偏移：
+// 解码器不清空、播放时钟不重置，内部时间轴单调递增（界面显示的位置按一遍的时长折回）。
+// 解码线程始终领先播放位置，到达循环点时开头的音视频帧已经解码在队列中，画面和声音都没有空档。
+// 音频在拼接处首尾采样不连续时，用几毫秒的过渡消除爆音。
+// 超过时长上限的文件使用普通的 seek 回开头（清空队列、重置时钟）。
+
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+
+/// 默认的无缝循环时长上限（更长的文件循环点的空档不明显，按普通 seek 处理）
+pub const DEFAULT_SEAMLESS_LIMIT_MS: i64 = 60_000;
+
+/// 拼接处的过渡时长
+const SPLICE_FADE_MS: u32 = 5;
+
+/// 拼接处首尾采样差超过该值才做过渡（连续的波形不做处理）
+pub const SPLICE_THRESHOLD: f32 = 0.05;
+
+/// 按一遍的时长折回内部时间轴，返回 (已完成的遍数, 文件内的位置)（单位相同即可）
+pub fn wrap_position(position: i64, start: i64, span: i64) -> (u64, i64) {
+    if span <= 0 || position < start + span {
+        return (0, position);
+    }
+    let elapsed = position - start;
+    ((elapsed / span) as u64, start + elapsed % span)
+}
+
+struct LoopShared {
+    repeat_one: AtomicBool,
+    seamless_limit_ms: AtomicI64,
+    start_us: AtomicI64,         // 文件起始时间戳（微秒）
+    span_us: AtomicI64,          // 一遍的时长（微秒，0 = 尚未发生无缝循环）
+    waiting_restart: AtomicBool, // 非无缝循环：已读到末尾，等待 seek 回开头
+}
+
+/// 循环设置与状态（UI 与解封装、音频解码线程共享）
+#[derive(Clone)]
+pub struct LoopControl {
+    shared: Arc<LoopShared>,
+}
+
+impl Default for LoopControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoopControl {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(LoopShared {
+                repeat_one: AtomicBool::new(false),
+                seamless_limit_ms: AtomicI64::new(DEFAULT_SEAMLESS_LIMIT_MS),
+                start_us: AtomicI64::new(0),
+                span_us: AtomicI64::new(0),
+                waiting_restart: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn repeat_one(&self) -> bool {
+        self.shared.repeat_one.load(Ordering::SeqCst)
+    }
+
+    pub fn set_repeat_one(&self, enabled: bool) {
+        self.shared.repeat_one.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn seamless_limit_ms(&self) -> i64 {
+        self.shared.seamless_limit_ms.load(Ordering::SeqCst)
+    }
+
+    pub fn set_seamless_limit_ms(&self, limit_ms: i64) {
+        self.shared.seamless_limit_ms.store(limit_ms.max(0), Ordering::SeqCst);
+    }
+
+    /// 时长为 duration_ms 的本地文件是否无缝循环
+    pub fn allows_seamless(&self, duration_ms: i64) -> bool {
+        duration_ms > 0 && duration_ms <= self.seamless_limit_ms()
+    }
+
+    /// 记录一遍的时间范围（微秒，第一次无缝循环时由解封装线程设置）
+    pub fn set_timeline(&self, start_us: i64, span_us: i64) {
+        self.shared.start_us.store(start_us, Ordering::SeqCst);
+        self.shared.span_us.store(span_us, Ordering::SeqCst);
+    }
+
+    /// 打开新文件时清除状态
+    pub fn reset(&self) {
+        self.set_timeline(0, 0);
+        self.shared.waiting_restart.store(false, Ordering::SeqCst);
+    }
+
+    /// 内部时间轴（毫秒）-> (已完成的遍数, 文件内的位置)
+    pub fn wrap(&self, position_ms: i64) -> (u64, i64) {
+        let start_us = self.shared.start_us.load(Ordering::SeqCst);
+        let span_us = self.shared.span_us.load(Ordering::SeqCst);
+        let (count, position_us) = wrap_position(position_ms * 1000, start_us, span_us);
+        (count, position_us / 1000)
+    }
+
+    pub fn is_waiting_restart(&self) -> bool {
+        self.shared.waiting_restart.load(Ordering::SeqCst)
+    }
+
+    pub fn set_waiting_restart(&self, waiting: bool) {
+        self.shared.waiting_restart.store(waiting, Ordering::SeqCst);
+    }
+}
+
+/// 解封装端的循环时间线（微秒）：记录主时钟流（有音频时为音频）在文件中的结束时间，回绕时累加偏移
+#[derive(Debug, Clone)]
+pub struct LoopTimeline {
+    start_us: i64,
+    end_us: Option<i64>,
+    offset_us: i64,
+}
+
+impl LoopTimeline {
+    pub fn new(start_us: i64) -> Self {
+        Self { start_us, end_us: None, offset_us: 0 }
+    }
+
+    /// 记录主时钟流数据包的结束时间（文件时间，尚未加偏移）
+    pub fn observe(&mut self, packet_end_us: i64) {
+        self.end_us = Some(self.end_us.map_or(packet_end_us, |end| end.max(packet_end_us)));
+    }
+
+    /// 一遍的时长（还没有读到数据时为 None）
+    pub fn span_us(&self) -> Option<i64> {
+        self.end_us.map(|end| end - self.start_us).filter(|span| *span > 0)
+    }
+
+    /// 回到开头：返回之后的数据包需要加上的偏移
+    pub fn wrap(&mut self) -> Option<i64> {
+        self.offset_us += self.span_us()?;
+        Some(self.offset_us)
+    }
+
+    /// 当前偏移
+    pub fn offset_us(&self) -> i64 {
+        self.offset_us
+    }
+
+    /// Seek 后数据包回到文件时间
+    pub fn reset_offset(&mut self) {
+        self.offset_us = 0;
+    }
+
+    pub fn start_us(&self) -> i64 {
+        self.start_us
+    }
+}
+
+/// 音频拼接：检测跨过循环点的第一帧，首尾采样不连续时做短过渡
+#[derive(Debug, Default)]
+pub struct AudioSplicer {
+    pass: u64,
+    tail: Vec<f32>,  // 上一帧最后一个采样帧（每声道一个值）
+}
+
+impl AudioSplicer {
+    /// 处理即将输出的音频帧（pass 为该帧所在的遍数）
+    pub fn process(&mut self, pass: u64, data: &mut [f32], channels: usize, sample_rate: u32) {
+        let channels = channels.max(1);
+        if pass == self.pass + 1 && self.tail.len() == channels {
+            let fade_frames = (sample_rate * SPLICE_FADE_MS / 1000) as usize;
+            splice_crossfade(&self.tail, data, channels, fade_frames);
+        }
+        // Seek 回到更早的遍数时只更新状态
+        self.pass = pass;
+        if data.len() >= channels {
+            self.tail = data[data.len() - channels..].to_vec();
+        }
+    }
+}
+
+/// 拼接处去爆音：新片段开头与上一段末尾的采样差超过阈值时，在 fade_frames 个采样帧内
+/// 从上一段末尾的值过渡到新信号
+pub fn splice_crossfade(tail: &[f32], next: &mut [f32], channels: usize, fade_frames: usize) {
+    if next.len() < channels || tail.len() != channels {
+        return;
+    }
+    let jump = (0..channels).map(|c| (next[c] - tail[c]).abs()).fold(0.0, f32::max);
+    if jump <= SPLICE_THRESHOLD {
+        return;
+    }
+    let frames = fade_frames.min(next.len() / channels);
+    for i in 0..frames {
+        let t = (i + 1) as f32 / (frames + 1) as f32;
+        for c in 0..channels {
+            let sample = &mut next[i * channels + c];
+            *sample = tail[c] * (1.0 - t) + *sample * t;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    /// 相邻采样的最大差值
+    fn max_step(samples: &[f32]) -> f32 {
+        samples.windows(2).map(|pair| (pair[1] - pair[0]).abs()).fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_wrap_position() {
+        assert_eq!(wrap_position(5_000, 0, 0), (0, 5_000));
+        assert_eq!(wrap_position(9_999, 0, 10_000), (0, 9_999));
+        assert_eq!(wrap_position(10_000, 0, 10_000), (1, 0));
+        assert_eq!(wrap_position(34_500, 0, 10_000), (3, 4_500));
+        // 文件起始时间不为 0
+        assert_eq!(wrap_position(21_000, 1_000, 10_000), (2, 1_000));
+    }
+
+    #[test]
+    fn test_timeline_offsets_accumulate() {
+        let mut timeline = LoopTimeline::new(0);
+        assert_eq!(timeline.wrap(), None);
+        for end in [21_333, 5_000_000, 9_978_666, 10_021_333] {
+            timeline.observe(end);
+        }
+        assert_eq!(timeline.span_us(), Some(10_021_333));
+        assert_eq!(timeline.wrap(), Some(10_021_333));
+        // 第二遍的数据包仍按文件时间记录，结束时间不变
+        timeline.observe(10_021_333);
+        assert_eq!(timeline.wrap(), Some(20_042_666));
+        timeline.reset_offset();
+        assert_eq!(timeline.offset_us(), 0);
+
+        // 界面按毫秒显示折回后的位置
+        let control = LoopControl::new();
+        control.set_timeline(0, 10_021_333);
+        assert_eq!(control.wrap(9_000), (0, 9_000));
+        assert_eq!(control.wrap(20_043), (2, 0));
+        assert_eq!(control.wrap(25_000), (2, 4_957));
+    }
+
+    #[test]
+    fn test_splice_removes_discontinuity() {
+        const RATE: u32 = 48_000;
+        // 437Hz 正弦音 0.1 秒（不是整数个周期，末尾与开头的采样不连续）
+        let clip: Vec<f32> = (0..RATE / 10).map(|n| 0.5 * (2.0 * PI * 437.0 * n as f32 / RATE as f32 + 0.3).sin()).collect();
+        let natural_step = max_step(&clip);
+
+        let mut splicer = AudioSplicer::default();
+        let mut output = Vec::new();
+        for pass in 0..3 {
+            let mut frame = clip.clone();
+            splicer.process(pass, &mut frame, 1, RATE);
+            output.extend(frame);
+        }
+        // 拼接处没有超过正常波形斜率的跳变
+        assert_eq!(output.len(), clip.len() * 3);
+        assert!(max_step(&output) <= natural_step + SPLICE_THRESHOLD, "{} > {}", max_step(&output), natural_step);
+
+        // 未处理时拼接处存在明显跳变
+        let raw: Vec<f32> = clip.iter().chain(clip.iter()).copied().collect();
+        assert!(max_step(&raw) > natural_step + SPLICE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_continuous_splice_is_untouched() {
+        let mut splicer = AudioSplicer::default();
+        let mut first = vec![0.0, 0.0, 0.1, -0.1];
+        splicer.process(0, &mut first, 2, 48_000);
+        let mut next = vec![0.11, -0.09, 0.2, -0.2];
+        splicer.process(1, &mut next, 2, 48_000);
+        assert_eq!(next, vec![0.11, -0.09, 0.2, -0.2]);
+    }
+}
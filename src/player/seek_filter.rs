@@ -0,0 +1,205 @@
+// Seek 后的帧筛选（快速 / 精确 seek）
+//
+// Demuxer 总是定位到目标之前的关键帧，之后由解码线程决定从哪一帧开始送出：
+// - 快速 seek（拖动进度条松开时）：从关键帧开始送出，音频时钟以第一个音频帧为准；
+//   长 GOP 的内容画面可能比目标早几秒，但不需要解码多余的帧
+// - 精确 seek（方向键、章节、A-B 循环等）：解码并丢弃目标之前的帧，视频从覆盖目标位置的帧开始，
+//   音频裁掉第一帧中目标之前的采样，声音正好从目标位置开始
+// 每个解码线程持有一个 SeekFilter，按共享的 SeekRequest 判断解码出的帧，新的 seek 替换旧请求后重新开始筛选。
+// 目标之后很远的帧是 seek 前已送入解码器的旧包解码出来的，直接丢弃；超过期限仍未到达目标时不再筛选（避免卡住）。
+
+use crate::core::{AudioFrame, VideoFrame};
+use std::time::{Duration, Instant};
+
+/// 筛选的期限（超过后送出所有帧）
+pub const SEEK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 比目标晚这么多以上的帧视为 seek 前残留的旧帧
+const STALE_AFTER_MS: i64 = 10_000;
+
+/// 快速 seek：关键帧最多比目标早这么多（更早的帧视为 seek 前残留的旧帧）
+const KEYFRAME_BEFORE_MS: i64 = 10_000;
+
+/// Seek 模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekMode {
+    /// 从目标之前的关键帧开始播放
+    Fast,
+    /// 丢弃目标之前的帧，从目标位置开始播放
+    Accurate,
+}
+
+/// 一次 seek 请求（播放管理器写入，解码线程读取）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeekRequest {
+    pub target_ms: i64,
+    pub mode: SeekMode,
+    pub issued: Instant,
+}
+
+impl SeekRequest {
+    pub fn new(target_ms: i64, mode: SeekMode) -> Self {
+        Self { target_ms, mode, issued: Instant::now() }
+    }
+}
+
+/// 解码线程的 seek 筛选状态（每个解码线程一个）
+#[derive(Debug, Default)]
+pub struct SeekFilter {
+    request: Option<SeekRequest>,
+    done: bool,  // 已送出目标位置的帧或已超时
+}
+
+impl SeekFilter {
+    /// 同步共享的 seek 请求，出现新请求时返回 true（重新开始筛选）
+    pub fn sync(&mut self, request: Option<SeekRequest>) -> bool {
+        if request == self.request {
+            return false;
+        }
+        self.request = request;
+        self.done = false;
+        request.is_some()
+    }
+
+    /// 正在筛选的请求（已到达目标或超时时为 None）
+    fn pending(&mut self, now: Instant) -> Option<SeekRequest> {
+        let request = self.request.filter(|_| !self.done)?;
+        if now.duration_since(request.issued) > SEEK_TIMEOUT {
+            self.done = true;
+            return None;
+        }
+        Some(request)
+    }
+
+    /// 视频帧（按 PTS 顺序）是否送出
+    pub fn accept_video(&mut self, frame: &VideoFrame, now: Instant) -> bool {
+        let Some(request) = self.pending(now) else {
+            return true;
+        };
+        let target = request.target_ms;
+        if frame.pts > target + STALE_AFTER_MS {
+            return false;
+        }
+        let accept = match request.mode {
+            SeekMode::Fast => frame.pts >= target - KEYFRAME_BEFORE_MS,
+            // 解码出的帧多数没有时长：时长未知时送出第一个不早于目标的帧
+            SeekMode::Accurate => frame.pts >= target || (frame.duration > 0 && frame.pts + frame.duration > target),
+        };
+        self.done = accept;
+        accept
+    }
+
+    /// 音频帧：丢弃时返回 None；精确 seek 时裁掉第一帧中目标之前的采样
+    pub fn accept_audio(&mut self, mut frame: AudioFrame, now: Instant) -> Option<AudioFrame> {
+        let Some(request) = self.pending(now) else {
+            return Some(frame);
+        };
+        let target = request.target_ms;
+        if frame.pts > target + STALE_AFTER_MS {
+            return None;
+        }
+        if request.mode == SeekMode::Fast {
+            self.done = frame.pts >= target - KEYFRAME_BEFORE_MS;
+            return self.done.then_some(frame);
+        }
+
+        let channels = frame.channels.max(1) as usize;
+        let sample_rate = frame.sample_rate.max(1) as i64;
+        let samples = (frame.data.len() / channels) as i64;
+        let skip = ((target - frame.pts) * sample_rate / 1000).clamp(0, samples);
+        if skip == samples {
+            return None;
+        }
+        self.done = true;
+        if skip > 0 {
+            frame.data.drain(..skip as usize * channels);
+            frame.pts += skip * 1000 / sample_rate;
+        }
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::PixelFormat;
+
+    fn video(pts: i64) -> VideoFrame {
+        VideoFrame { pts, duration: 0, width: 2, height: 2, format: PixelFormat::RGBA, planes: None, data: vec![0; 16].into() }
+    }
+
+    /// 20ms 的立体声 48kHz 音频帧，采样值为帧内序号
+    fn audio(pts: i64) -> AudioFrame {
+        AudioFrame {
+            pts,
+            sample_rate: 48_000,
+            channels: 2,
+            data: (0..960).flat_map(|i| [i as f32, i as f32]).collect(),
+        }
+    }
+
+    fn start(target_ms: i64, mode: SeekMode) -> (SeekFilter, Instant) {
+        let request = SeekRequest::new(target_ms, mode);
+        let mut filter = SeekFilter::default();
+        assert!(filter.sync(Some(request)));
+        assert!(!filter.sync(Some(request)));
+        (filter, request.issued)
+    }
+
+    #[test]
+    fn test_accurate_seek_discards_until_target() {
+        // 关键帧在 56s，目标 60.01s：之前的帧全部丢弃，从第一个不早于目标的帧开始
+        let (mut filter, now) = start(60_010, SeekMode::Accurate);
+        for pts in (56_000..60_010).step_by(40) {
+            assert!(!filter.accept_video(&video(pts), now), "{}", pts);
+        }
+        assert!(filter.accept_video(&video(60_040), now));
+        // 到达目标后不再筛选
+        assert!(filter.accept_video(&video(59_000), now));
+
+        // 帧时长已知时送出覆盖目标的帧
+        let (mut filter, now) = start(60_010, SeekMode::Accurate);
+        assert!(!filter.accept_video(&VideoFrame { duration: 40, ..video(59_960) }, now));
+        assert!(filter.accept_video(&VideoFrame { duration: 40, ..video(60_000) }, now));
+    }
+
+    #[test]
+    fn test_fast_seek_starts_at_keyframe() {
+        let (mut filter, now) = start(60_010, SeekMode::Fast);
+        // seek 前残留的旧帧
+        assert!(!filter.accept_video(&video(10_000), now));
+        assert!(!filter.accept_video(&video(90_000), now));
+        assert!(filter.accept_video(&video(56_000), now));
+        assert!(filter.accept_video(&video(56_040), now));
+
+        let (mut filter, now) = start(60_010, SeekMode::Fast);
+        assert_eq!(filter.accept_audio(audio(56_000), now).map(|frame| frame.data.len()), Some(1920));
+    }
+
+    #[test]
+    fn test_accurate_seek_trims_first_audio_frame() {
+        let (mut filter, now) = start(60_010, SeekMode::Accurate);
+        assert!(filter.accept_audio(audio(59_980), now).is_none());
+        // 60.0s 的帧覆盖目标：裁掉前 10ms（480 个采样）
+        let frame = filter.accept_audio(audio(60_000), now).unwrap();
+        assert_eq!(frame.pts, 60_010);
+        assert_eq!(frame.data.len(), 960);
+        assert_eq!(frame.data[..2], [480.0, 480.0]);
+        assert_eq!(filter.accept_audio(audio(60_020), now).unwrap().data.len(), 1920);
+    }
+
+    #[test]
+    fn test_new_request_restarts_and_timeout_gives_up() {
+        let (mut filter, now) = start(60_000, SeekMode::Accurate);
+        assert!(filter.accept_video(&video(60_000), now));
+        // 新的 seek：重新筛选
+        let request = SeekRequest { issued: now + Duration::from_millis(100), ..SeekRequest::new(10_000, SeekMode::Accurate) };
+        assert!(filter.sync(Some(request)));
+        assert!(!filter.accept_video(&video(60_040), request.issued));
+        assert!(!filter.accept_video(&video(9_000), request.issued));
+        // 超时后送出所有帧
+        assert!(filter.accept_video(&video(9_040), request.issued + SEEK_TIMEOUT + Duration::from_millis(1)));
+        assert!(!filter.sync(None));
+        assert!(filter.accept_video(&video(0), now));
+    }
+}
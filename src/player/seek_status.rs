@@ -0,0 +1,168 @@
+// Seek 结果（解封装线程执行后上报，界面读取）
+//
+// 网络流（HLS、RTSP）的 seek 经常失败或需要好几秒。播放管理器发出 seek 时记为进行中，解封装线程执行
+// Demuxer::seek 后上报成功、失败或不支持（没有可回看窗口的直播等不可 seek 的源）。界面据此在 seek
+// 完成前保持进度条在目标位置，失败时提示原因并让进度条回到实际位置。
+// 解封装线程按顺序处理 seek 命令：只有与最近一次请求的目标一致的结果才会记录，被新请求取代的结果直接忽略。
+
+use crate::core::{PlayerError, Result};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Seek 的执行结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeekOutcome {
+    /// 已发出，解封装线程尚未执行
+    Pending,
+    /// 已完成
+    Succeeded,
+    /// 失败（错误信息）
+    Failed(String),
+    /// 媒体源不支持 seek
+    Unsupported,
+}
+
+impl SeekOutcome {
+    /// Demuxer::seek 的返回值对应的结果
+    pub fn of(result: &Result<()>) -> Self {
+        match result {
+            Ok(()) => SeekOutcome::Succeeded,
+            Err(PlayerError::SeekUnsupported) => SeekOutcome::Unsupported,
+            Err(e) => SeekOutcome::Failed(e.to_string()),
+        }
+    }
+
+    /// 失败或不支持
+    pub fn is_failure(&self) -> bool {
+        matches!(self, SeekOutcome::Failed(_) | SeekOutcome::Unsupported)
+    }
+}
+
+/// 最近一次 seek 的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeekResult {
+    /// 请求序号（每次 seek 递增，界面据此区分不同的请求）
+    pub id: u64,
+    /// 目标位置（毫秒）
+    pub target_ms: i64,
+    /// 执行结果
+    pub outcome: SeekOutcome,
+    /// 从发出到完成（进行中时为已等待）的时间
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Default)]
+struct StatusState {
+    requests: u64,
+    current: Option<(i64, Instant, SeekOutcome, Option<Instant>)>,  // 目标、发出时间、结果、完成时间
+    failure_reported: bool,
+}
+
+/// 共享的 seek 状态（播放管理器和解封装线程各持有一份）
+#[derive(Debug, Clone, Default)]
+pub struct SeekStatus {
+    state: Arc<Mutex<StatusState>>,
+}
+
+impl SeekStatus {
+    /// 发出新的 seek（取代之前的请求）
+    pub fn begin(&self, target_ms: i64) {
+        let mut state = self.state.lock().unwrap();
+        state.requests += 1;
+        state.current = Some((target_ms, Instant::now(), SeekOutcome::Pending, None));
+        state.failure_reported = false;
+    }
+
+    /// 解封装线程执行完 seek（目标与进行中的请求不一致时忽略）
+    pub fn finish(&self, target_ms: i64, outcome: SeekOutcome) {
+        let mut state = self.state.lock().unwrap();
+        if let Some((target, _, current, finished_at)) = &mut state.current {
+            if *target == target_ms && *current == SeekOutcome::Pending {
+                *current = outcome;
+                *finished_at = Some(Instant::now());
+            }
+        }
+    }
+
+    /// 最近一次 seek 的结果
+    pub fn last(&self) -> Option<SeekResult> {
+        let state = self.state.lock().unwrap();
+        let (target_ms, issued, outcome, finished_at) = state.current.clone()?;
+        Some(SeekResult {
+            id: state.requests,
+            target_ms,
+            outcome,
+            elapsed: finished_at.unwrap_or_else(Instant::now).duration_since(issued),
+        })
+    }
+
+    /// 取出尚未提示过的失败结果（每次失败只返回一次）
+    pub fn take_failure(&self) -> Option<SeekResult> {
+        let result = self.last().filter(|result| result.outcome.is_failure())?;
+        let mut state = self.state.lock().unwrap();
+        if std::mem::replace(&mut state.failure_reported, true) {
+            return None;
+        }
+        Some(result)
+    }
+
+    /// 清除记录（停止播放或打开新文件）
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.current = None;
+        state.failure_reported = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_of_demuxer_result() {
+        assert_eq!(SeekOutcome::of(&Ok(())), SeekOutcome::Succeeded);
+        assert_eq!(SeekOutcome::of(&Err(PlayerError::SeekUnsupported)), SeekOutcome::Unsupported);
+        let failed = SeekOutcome::of(&Err(PlayerError::NetworkError("timeout".to_string())));
+        assert!(matches!(&failed, SeekOutcome::Failed(reason) if reason.contains("timeout")));
+        assert!(failed.is_failure() && SeekOutcome::Unsupported.is_failure());
+        assert!(!SeekOutcome::Pending.is_failure());
+    }
+
+    #[test]
+    fn test_result_follows_latest_request() {
+        let status = SeekStatus::default();
+        assert_eq!(status.last(), None);
+        status.begin(10_000);
+        let pending = status.last().unwrap();
+        assert_eq!((pending.id, pending.target_ms, pending.outcome), (1, 10_000, SeekOutcome::Pending));
+
+        // 被新请求取代的旧 seek 的结果不记录
+        status.begin(20_000);
+        let thread_status = status.clone();
+        thread_status.finish(10_000, SeekOutcome::Failed("旧请求".to_string()));
+        assert_eq!(status.last().unwrap().outcome, SeekOutcome::Pending);
+        thread_status.finish(20_000, SeekOutcome::Succeeded);
+        let done = status.last().unwrap();
+        assert_eq!((done.id, done.target_ms, done.outcome), (2, 20_000, SeekOutcome::Succeeded));
+        // 完成后不再覆盖
+        thread_status.finish(20_000, SeekOutcome::Unsupported);
+        assert_eq!(status.last().unwrap().outcome, SeekOutcome::Succeeded);
+
+        status.clear();
+        assert_eq!(status.last(), None);
+    }
+
+    #[test]
+    fn test_failure_is_reported_once() {
+        let status = SeekStatus::default();
+        status.begin(5_000);
+        assert_eq!(status.take_failure(), None);
+        status.finish(5_000, SeekOutcome::Unsupported);
+        assert_eq!(status.take_failure().map(|result| result.outcome), Some(SeekOutcome::Unsupported));
+        assert_eq!(status.take_failure(), None);
+        // 新的请求再次失败时重新提示
+        status.begin(6_000);
+        status.finish(6_000, SeekOutcome::Failed("超时".to_string()));
+        assert!(status.take_failure().is_some());
+    }
+}
@@ -0,0 +1,120 @@
+// 解封装停滞监视（读包长时间没有返回）
+//
+// 解封装线程在 read_packet() 前后调用 begin_read()/end_read()；通道背压、预读限制、
+// 文件末尾等待都不在读包阶段内，不算停滞。界面每帧调用 poll()：读包超过阈值时报告
+// Stalled，读包返回后报告 Recovered，两者总是成对并按顺序报告（停滞再短也不会漏报）。
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 单次读包超过多久视为停滞
+pub const STALL_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// 停滞事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallEvent {
+    /// 读包已持续 waited 没有返回
+    Stalled {
+        /// 已等待的时间
+        waited: Duration,
+    },
+    /// 停滞结束，共停滞 stalled_for
+    Recovered {
+        /// 停滞的总时长
+        stalled_for: Duration,
+    },
+}
+
+#[derive(Debug, Default)]
+struct WatchState {
+    read_started: Option<Instant>,
+    finished_stall: Option<Duration>,  // 已经结束、尚未报告恢复的停滞
+    reported: bool,                    // 已报告 Stalled，等待报告 Recovered
+}
+
+/// 停滞监视（可克隆，解封装线程和播放管理器共享）
+#[derive(Debug, Clone, Default)]
+pub struct StallWatchdog {
+    state: Arc<Mutex<WatchState>>,
+}
+
+impl StallWatchdog {
+    /// 开始读包
+    pub fn begin_read(&self) {
+        self.state.lock().unwrap().read_started = Some(Instant::now());
+    }
+
+    /// 读包返回（无论成功与否）
+    pub fn end_read(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(started) = state.read_started.take() {
+            let elapsed = started.elapsed();
+            if elapsed >= STALL_THRESHOLD {
+                state.finished_stall = Some(elapsed);
+            }
+        }
+    }
+
+    /// 检查停滞状态的变化
+    pub fn poll(&self, now: Instant) -> Option<StallEvent> {
+        let mut state = self.state.lock().unwrap();
+        let ongoing = state
+            .read_started
+            .map(|started| now.saturating_duration_since(started))
+            .filter(|&waited| waited >= STALL_THRESHOLD);
+        if !state.reported {
+            let waited = ongoing.or(state.finished_stall)?;
+            state.reported = true;
+            return Some(StallEvent::Stalled { waited });
+        }
+        if ongoing.is_some() {
+            return None;
+        }
+        let stalled_for = state.finished_stall.take()?;
+        state.reported = false;
+        Some(StallEvent::Recovered { stalled_for })
+    }
+
+    /// 新管线启动时重置
+    pub fn reset(&self) {
+        *self.state.lock().unwrap() = WatchState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_and_recovery_are_reported_in_order() {
+        let watchdog = StallWatchdog::default();
+        let start = Instant::now();
+        watchdog.state.lock().unwrap().read_started = Some(start);
+        assert_eq!(watchdog.poll(start), None);
+        assert_eq!(watchdog.poll(start + Duration::from_millis(500)), None);
+
+        let waited = Duration::from_millis(1500);
+        assert_eq!(watchdog.poll(start + waited), Some(StallEvent::Stalled { waited }));
+        assert_eq!(watchdog.poll(start + Duration::from_secs(2)), None);
+
+        // 读包返回后报告恢复（模拟读包耗时超过阈值）
+        watchdog.state.lock().unwrap().read_started = Some(Instant::now() - Duration::from_secs(2));
+        watchdog.end_read();
+        match watchdog.poll(Instant::now()) {
+            Some(StallEvent::Recovered { stalled_for }) => assert!(stalled_for >= Duration::from_secs(2)),
+            other => panic!("应报告恢复: {:?}", other),
+        }
+        assert_eq!(watchdog.poll(Instant::now()), None);
+
+        // 两次检查之间就已结束的停滞：先补报 Stalled 再报告 Recovered
+        watchdog.state.lock().unwrap().read_started = Some(Instant::now() - STALL_THRESHOLD);
+        watchdog.end_read();
+        assert!(matches!(watchdog.poll(Instant::now()), Some(StallEvent::Stalled { .. })));
+        assert!(matches!(watchdog.poll(Instant::now()), Some(StallEvent::Recovered { .. })));
+
+        // 正常读包不报告
+        watchdog.begin_read();
+        watchdog.end_read();
+        assert_eq!(watchdog.poll(Instant::now()), None);
+    }
+}
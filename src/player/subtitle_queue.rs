@@ -0,0 +1,178 @@
+// 内嵌字幕的待显示字幕（字幕解码线程插入，界面每帧按播放时间查询）
+//
+// 原先用 FIFO 队列：每次查询弹出最多 100 条检查后再放回，卡拉 OK / 特效字幕一个文件有上万条事件时，
+// 超出检查范围的字幕永远不会被检查也不会被丢弃，队列无限增长，每帧的扫描也越来越慢。
+// 这里按开始时间排序存放（BTreeMap，同一开始时间的字幕按插入顺序），查询时只看开始时间落在
+// [当前时间 - 最长字幕时长, 当前时间] 内的字幕，不随总数变慢；解码顺序乱序的字幕插入后同样有序。
+// 插入时丢弃结束时间比上次查询的时间早 PRUNE_MARGIN_MS 以上的字幕（留出余量，调整字幕延迟时刚过去的字幕还能显示）；
+// 字幕总数超过 MAX_CUES 时丢弃开始时间最早的字幕（解封装远远领先于播放时的兜底）。
+
+use crate::core::SubtitleFrame;
+use log::warn;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// 结束时间早于上次查询的时间这么久的字幕在插入时丢弃（毫秒）
+pub const PRUNE_MARGIN_MS: i64 = 5_000;
+
+/// 最多保留的字幕数
+pub const MAX_CUES: usize = 20_000;
+
+#[derive(Debug, Default)]
+struct Cues {
+    by_start: BTreeMap<i64, Vec<SubtitleFrame>>,  // 开始时间 -> 字幕
+    len: usize,
+    max_duration_ms: i64,  // 已插入字幕的最长时长（查询范围的下界据此确定，清空时复位）
+    last_query_ms: Option<i64>,  // 上次查询的时间（插入时据此丢弃过期字幕）
+    capped: bool,  // 已因超出 MAX_CUES 丢弃过字幕（只记录一次日志）
+}
+
+impl Cues {
+    /// 丢弃结束时间早于 cutoff_ms 的字幕
+    fn prune_before(&mut self, cutoff_ms: i64) {
+        // 开始时间不早于 cutoff_ms 的字幕都还没结束；更早开始的逐条检查结束时间
+        let kept = self.by_start.split_off(&cutoff_ms);
+        let older = std::mem::replace(&mut self.by_start, kept);
+        for (start, mut frames) in older {
+            let count = frames.len();
+            frames.retain(|frame| frame.end_pts >= cutoff_ms);
+            self.len -= count - frames.len();
+            if !frames.is_empty() {
+                self.by_start.insert(start, frames);
+            }
+        }
+    }
+}
+
+/// 按开始时间排序的字幕（播放管理器和字幕解码线程共享）
+#[derive(Debug, Default)]
+pub struct SubtitleQueue {
+    cues: Mutex<Cues>,
+}
+
+impl SubtitleQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 插入解码出的字幕（顺带丢弃已过期的字幕）
+    pub fn push(&self, frame: SubtitleFrame) {
+        let mut cues = self.cues.lock().unwrap();
+        if let Some(query_ms) = cues.last_query_ms {
+            if frame.end_pts < query_ms - PRUNE_MARGIN_MS {
+                return;
+            }
+            cues.prune_before(query_ms - PRUNE_MARGIN_MS);
+        }
+        cues.max_duration_ms = cues.max_duration_ms.max(frame.end_pts - frame.pts);
+        cues.by_start.entry(frame.pts).or_default().push(frame);
+        cues.len += 1;
+
+        if cues.len > MAX_CUES {
+            if !cues.capped {
+                warn!("⚠️ 待显示字幕超过 {} 条，丢弃开始时间最早的字幕", MAX_CUES);
+                cues.capped = true;
+            }
+            if let Some(mut earliest) = cues.by_start.first_entry() {
+                earliest.get_mut().remove(0);
+                if earliest.get().is_empty() {
+                    earliest.remove();
+                }
+                cues.len -= 1;
+            }
+        }
+    }
+
+    /// time_ms 时应显示的字幕（重叠的字幕同时显示，按开始时间排序，最晚开始的在最后）
+    pub fn active_at(&self, time_ms: i64) -> Vec<SubtitleFrame> {
+        let mut cues = self.cues.lock().unwrap();
+        cues.last_query_ms = Some(time_ms);
+        cues.by_start
+            .range(time_ms.saturating_sub(cues.max_duration_ms)..=time_ms)
+            .flat_map(|(_, frames)| frames)
+            .filter(|frame| time_ms < frame.end_pts)
+            .cloned()
+            .collect()
+    }
+
+    /// 清空（seek、切换字幕），返回丢弃的数量
+    pub fn clear(&self) -> usize {
+        std::mem::take(&mut *self.cues.lock().unwrap()).len
+    }
+
+    pub fn len(&self) -> usize {
+        self.cues.lock().unwrap().len
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(pts: i64, end_pts: i64, text: &str) -> SubtitleFrame {
+        SubtitleFrame { pts, duration: end_pts - pts, text: text.to_string(), end_pts }
+    }
+
+    fn texts(frames: &[SubtitleFrame]) -> Vec<&str> {
+        frames.iter().map(|frame| frame.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_overlapping_cues_sorted_latest_last() {
+        let queue = SubtitleQueue::new();
+        // 解码顺序乱序：后开始的字幕先插入
+        queue.push(cue(2_000, 3_000, "b"));
+        queue.push(cue(1_000, 10_000, "长"));
+        queue.push(cue(2_500, 2_800, "c"));
+        queue.push(cue(500, 1_500, "a"));
+        assert_eq!(texts(&queue.active_at(1_200)), ["a", "长"]);
+        assert_eq!(texts(&queue.active_at(2_600)), ["长", "b", "c"]);
+        // 结束时间不含在显示范围内
+        assert_eq!(texts(&queue.active_at(3_000)), ["长"]);
+        assert!(queue.active_at(400).is_empty());
+        assert_eq!(queue.len(), 4);
+    }
+
+    #[test]
+    fn test_expired_cues_pruned_on_insert() {
+        let queue = SubtitleQueue::new();
+        for i in 0..10 {
+            queue.push(cue(i * 1_000, i * 1_000 + 500, "卡拉 OK"));
+        }
+        queue.push(cue(0, 60_000, "标题"));
+        // 查询不丢弃字幕：稍微往回调整字幕延迟时刚过去的字幕还能显示
+        assert_eq!(texts(&queue.active_at(9_200)), ["标题", "卡拉 OK"]);
+        assert_eq!(queue.len(), 11);
+        assert_eq!(texts(&queue.active_at(4_200)), ["标题", "卡拉 OK"]);
+
+        // 插入时丢弃结束时间早于上次查询时间 PRUNE_MARGIN_MS 以上的字幕，仍在显示的长字幕保留
+        queue.active_at(9_200);
+        queue.push(cue(10_000, 10_500, "卡拉 OK"));
+        assert_eq!(queue.len(), 8);
+        assert_eq!(texts(&queue.active_at(4_200)), ["标题", "卡拉 OK"]);
+        // 早已过期的字幕不插入
+        queue.active_at(9_200);
+        queue.push(cue(100, 200, "过期"));
+        assert_eq!(queue.len(), 8);
+    }
+
+    #[test]
+    fn test_queue_is_capped() {
+        let queue = SubtitleQueue::new();
+        for i in 0..MAX_CUES as i64 + 10 {
+            queue.push(cue(i * 10, i * 10 + 5, "特效"));
+        }
+        // 丢弃开始时间最早的字幕
+        assert_eq!(queue.len(), MAX_CUES);
+        assert!(queue.active_at(0).is_empty());
+        assert_eq!(queue.active_at(100).len(), 1);
+
+        assert_eq!(queue.clear(), MAX_CUES);
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+// 缩略图生成（胶片视图）
+//
+// 在后台线程中独立打开本地文件（不影响播放中的解封装器），按均匀分布的时间点逐个通过预览管线
+// （见 preview，解码时直接缩小，结果进入共享的预览缓存）生成，通过通道交给界面，界面先显示占位框再逐个填充。
+// 时间点由调用方给出（胶片视图取时间轴上每一段的中点，避开片头黑场和片尾）。
+// 关键帧索引可用时（播放时已建立的缓存直接加载，否则在后台扫描）按索引把时间点对齐到不晚于它的关键帧：
+// 预览解码本来就从 seek 到的关键帧取画面，对齐后缓存按关键帧命中，相邻两段落在同一关键帧时直接复用上一张。
+// 每张开始前通过 progress 回调上报进度，回调返回 false（界面取消了后台任务）时直接放弃剩余的缩略图。
+
+use crate::core::{PixelFormat, PlayerError, Result, VideoFrame};
+use crate::player::keyframe_index::{self, KeyframeIndexer};
+use crate::player::preview::{PreviewPipeline, PreviewPurpose, PreviewRequest};
+use crate::player::preview_cache::PreviewCache;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::{debug, info, warn};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+/// 生成完成的缩略图
+#[derive(Debug, Clone)]
+pub struct Thumbnail {
+    /// 在胶片中的位置（对应时间点的下标）
+    pub index: usize,
+    /// 缩小后的 RGBA 画面
+    pub image: VideoFrame,
+}
+
+/// 把 RGBA 帧按区域平均缩小到 max_width 宽（不放大）
+pub fn downscale(frame: &VideoFrame, max_width: u32) -> VideoFrame {
+    if frame.width <= max_width || frame.width == 0 || frame.height == 0 {
+        return frame.clone();
+    }
+    let width = max_width.max(1);
+    let height = ((frame.height as u64 * width as u64 / frame.width as u64) as u32).max(1);
+    let (src_width, src_height) = (frame.width as usize, frame.height as usize);
+
+    let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height as usize {
+        let y0 = y * src_height / height as usize;
+        let y1 = ((y + 1) * src_height / height as usize).max(y0 + 1);
+        for x in 0..width as usize {
+            let x0 = x * src_width / width as usize;
+            let x1 = ((x + 1) * src_width / width as usize).max(x0 + 1);
+            let mut sum = [0u32; 4];
+            for row in y0..y1 {
+                let start = (row * src_width + x0) * 4;
+                let end = (row * src_width + x1) * 4;
+                let Some(pixels) = frame.data.get(start..end) else {
+                    continue;
+                };
+                for pixel in pixels.chunks_exact(4) {
+                    for (total, &value) in sum.iter_mut().zip(pixel) {
+                        *total += value as u32;
+                    }
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as u32;
+            data.extend(sum.iter().map(|total| (total / count) as u8));
+        }
+    }
+
+    VideoFrame { pts: frame.pts, duration: frame.duration, width, height, format: PixelFormat::RGBA, planes: None, data: data.into() }
+}
+
+/// 后台缩略图任务（drop 时取消并等待线程退出）
+pub struct Thumbnailer {
+    receiver: Receiver<Thumbnail>,
+    cancel: Arc<AtomicBool>,
+    thread_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Thumbnailer {
+    /// 按给定时间点（毫秒）依次生成缩略图
+    ///
+    /// 每张开始前以完成比例（0.0..1.0）调用 progress，返回 false 时放弃剩余的时间点；线程退出时释放 progress。
+    pub fn spawn(
+        path: PathBuf,
+        times: Vec<i64>,
+        cache: Arc<PreviewCache>,
+        mut progress: impl FnMut(f32) -> bool + Send + 'static,
+    ) -> Self {
+        let (sender, receiver) = unbounded();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let thread_cancel = cancel.clone();
+        let thread_handle = thread::Builder::new()
+            .name("thumbnailer".to_string())
+            .spawn(move || {
+                let started = Instant::now();
+                let keyframes = KeyframeIndexer::spawn(path.clone(), Some(keyframe_index::default_cache_dir()));
+                match generate(PreviewPipeline::new(cache), &path, &times, &keyframes, &sender, &thread_cancel, &mut progress) {
+                    Ok(count) => info!(
+                        "🎞️ 缩略图生成完成: {}/{} 张，耗时 {}ms ({})",
+                        count,
+                        times.len(),
+                        started.elapsed().as_millis(),
+                        path.display()
+                    ),
+                    Err(e) => debug!("🎞️ 缩略图生成未完成: {}", e),
+                }
+            })
+            .ok();
+        if thread_handle.is_none() {
+            warn!("⚠️  无法启动缩略图线程");
+        }
+
+        Self { receiver, cancel, thread_handle }
+    }
+
+    /// 取出已生成的缩略图（不阻塞）
+    pub fn poll(&self) -> Vec<Thumbnail> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// 线程是否已退出（全部生成完毕或出错）
+    pub fn is_finished(&self) -> bool {
+        self.thread_handle.as_ref().is_none_or(|handle| handle.is_finished())
+    }
+}
+
+impl Drop for Thumbnailer {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 逐个时间点生成预览，返回成功生成的数量（keyframes 完成后按关键帧对齐时间点）
+fn generate(
+    mut pipeline: PreviewPipeline,
+    path: &std::path::Path,
+    times: &[i64],
+    keyframes: &KeyframeIndexer,
+    sender: &Sender<Thumbnail>,
+    cancel: &AtomicBool,
+    progress: &mut dyn FnMut(f32) -> bool,
+) -> Result<usize> {
+    let mut generated = 0;
+    let mut previous: Option<(i64, VideoFrame)> = None;  // 上一张缩略图对齐到的关键帧和画面
+    for (index, &time_ms) in times.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) || !progress(index as f32 / times.len() as f32) {
+            return Err(PlayerError::Other("缩略图生成已取消".to_string()));
+        }
+        let keyframe_ms = keyframes.get().and_then(|keyframes| keyframes.nearest(time_ms)).map(|keyframe| keyframe.pts_ms);
+        if let Some((_, image)) = previous.as_ref().filter(|(pts, _)| Some(*pts) == keyframe_ms) {
+            if sender.send(Thumbnail { index, image: image.clone() }).is_err() {
+                break;
+            }
+            generated += 1;
+            continue;
+        }
+        let request = PreviewRequest::new(path, keyframe_ms.unwrap_or(time_ms), PreviewPurpose::Filmstrip);
+        let image = match pipeline.render(&request, cancel) {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                debug!("🎞️ {}ms 处没有解码出画面，跳过", time_ms);
+                continue;
+            }
+            Err(e @ (PlayerError::NoVideoStream | PlayerError::OpenError(_) | PlayerError::OpenFailed { .. })) => return Err(e),
+            Err(e) => {
+                debug!("🎞️ {}ms 处的缩略图生成失败: {}", time_ms, e);
+                continue;
+            }
+        };
+        previous = keyframe_ms.map(|pts| (pts, image.clone()));
+        if sender.send(Thumbnail { index, image }).is_err() {
+            // 界面已丢弃该任务
+            break;
+        }
+        generated += 1;
+    }
+    Ok(generated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::video_asset;
+
+    #[test]
+    fn test_cancelled_job_stops_generation() {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let times: Vec<i64> = (0..250).map(|i| i * 40 + 20).collect();
+        let (progress_cancelled, progress_reported) = (cancelled.clone(), reported.clone());
+        let thumbnailer = Thumbnailer::spawn(
+            video_asset().to_path_buf(),
+            times.clone(),
+            Arc::new(PreviewCache::memory_only()),
+            move |progress| {
+                progress_reported.lock().unwrap().push(progress);
+                !progress_cancelled.load(Ordering::Relaxed)
+            },
+        );
+
+        // 等第一张生成后取消：线程放弃剩余的时间点并释放回调
+        let deadline = Instant::now() + std::time::Duration::from_secs(10);
+        let mut received = Vec::new();
+        while received.is_empty() {
+            assert!(Instant::now() < deadline, "没有生成缩略图");
+            received.extend(thumbnailer.poll());
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert!(!reported.lock().unwrap().is_empty());
+        cancelled.store(true, Ordering::Relaxed);
+        while !thumbnailer.is_finished() {
+            assert!(Instant::now() < deadline, "取消后缩略图任务没有结束");
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(Arc::strong_count(&reported), 1);
+        received.extend(thumbnailer.poll());
+        assert!(received.len() < times.len(), "取消后仍生成了全部 {} 张", received.len());
+    }
+
+    #[test]
+    fn test_times_snap_to_indexed_keyframes() {
+        let mut keyframes = KeyframeIndexer::spawn(video_asset().to_path_buf(), None);
+        keyframes.wait();
+        let (sender, receiver) = unbounded();
+        let times: Vec<i64> = (0..40).map(|i| i * 250 + 125).collect();
+        let pipeline = PreviewPipeline::new(Arc::new(PreviewCache::memory_only()));
+        let generated = generate(pipeline, video_asset(), &times, &keyframes, &sender, &AtomicBool::new(false), &mut |_| true).unwrap();
+        assert_eq!(generated, times.len());
+
+        // 测试素材每秒一个关键帧：每张缩略图都是不晚于该段中点的关键帧，同一秒内的几段共用一张
+        let thumbnails: Vec<Thumbnail> = receiver.try_iter().collect();
+        assert_eq!(thumbnails.len(), times.len());
+        for (thumbnail, &time_ms) in thumbnails.iter().zip(&times) {
+            assert_eq!(thumbnail.image.pts, time_ms / 1_000 * 1_000, "{}ms 处的缩略图", time_ms);
+        }
+    }
+
+    #[test]
+    fn test_downscale_averages_pixels() {
+        // 4x2：左半白、右半黑 -> 2x1
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            data.extend([255u8; 8]);
+            data.extend([0, 0, 0, 255, 0, 0, 0, 255]);
+        }
+        let frame = VideoFrame { pts: 40, duration: 0, width: 4, height: 2, format: PixelFormat::RGBA, planes: None, data: data.into() };
+        let small = downscale(&frame, 2);
+        assert_eq!((small.width, small.height, small.pts), (2, 1, 40));
+        assert_eq!(*small.data, [255, 255, 255, 255, 0, 0, 0, 255]);
+
+        // 不放大
+        assert_eq!(downscale(&frame, 160).width, 4);
+    }
+}
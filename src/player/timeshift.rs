@@ -0,0 +1,466 @@
+// 直播时移（把直播流录到临时文件，暂停或回看时从录制中继续播放）
+//
+// 开启时移后，直播流的读包交给录制线程：录制线程不停地读包并追加到分段的临时文件，不受播放暂停的影响；
+// 解封装线程从读取位置取出录制的包送给解码线程。读取位置紧跟录制末端时就是直播，暂停或向回 seek 后
+// 读取位置落后于录制末端，差值就是时移量；回到直播时读取位置跳到录制末端附近的关键帧。
+// - 录制保留直播流的原始时间戳，录制的时间轴就是直播的时间轴：进度条的可回看窗口、seek 目标、
+//   回到直播的目标都直接使用直播时间，不需要换算
+// - 超出保留时长或大小上限时按 GOP 丢弃最旧的数据，整段删除不再引用的临时文件；
+//   读取位置的数据被丢弃时跳到最早保留的关键帧
+// - 临时目录在录制线程和解封装线程都退出后删除
+
+use crate::core::Result;
+use crate::player::demux_end::DemuxEnd;
+use crate::player::demuxer_source::{MediaPacket, PacketType};
+use ffmpeg_next as ffmpeg;
+use log::{debug, warn};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+/// 默认保留时长（分钟）
+pub const DEFAULT_TIMESHIFT_MINUTES: u32 = 30;
+
+/// 默认保留大小（MB）
+pub const DEFAULT_TIMESHIFT_MB: u32 = 2048;
+
+/// 回到直播时距录制末端的余量（录制就在本地，不需要等待下载）
+pub const TIMESHIFT_EDGE_MARGIN_MS: i64 = 500;
+
+/// 每个临时文件的大小（写满后换下一个文件，丢弃旧数据时整个文件删除）
+const SEGMENT_BYTES: u64 = 16 * 1024 * 1024;
+
+/// 临时目录编号（同一进程的多个会话各用一个目录）
+static NEXT_BUFFER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// 时移的保留上限（超出任一上限时丢弃最旧的数据）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeshiftLimits {
+    /// 最多保留的时长
+    pub max_duration: Duration,
+    /// 最多占用的磁盘空间（字节）
+    pub max_bytes: u64,
+}
+
+impl TimeshiftLimits {
+    /// 按分钟和 MB 设置上限（至少 1 分钟、1 MB）
+    pub fn new(minutes: u32, megabytes: u32) -> Self {
+        Self {
+            max_duration: Duration::from_secs(u64::from(minutes.max(1)) * 60),
+            max_bytes: u64::from(megabytes.max(1)) * 1024 * 1024,
+        }
+    }
+}
+
+impl Default for TimeshiftLimits {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIMESHIFT_MINUTES, DEFAULT_TIMESHIFT_MB)
+    }
+}
+
+/// 数据包的时间戳和标志（数据另存在临时文件中）
+#[derive(Debug, Clone, Copy)]
+struct PacketMeta {
+    packet_type: PacketType,
+    stream_index: usize,
+    pts: Option<i64>,
+    dts: Option<i64>,
+    duration: i64,
+    key: bool,
+    time_ms: Option<i64>,  // 直播时间（毫秒）
+}
+
+/// 录制的一个包
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    meta: PacketMeta,
+    segment: u64,
+    offset: u64,
+    len: usize,
+}
+
+impl Entry {
+    /// 按录制的数据重建数据包
+    fn to_packet(self, data: &[u8]) -> MediaPacket {
+        let meta = self.meta;
+        let mut packet = ffmpeg::Packet::copy(data);
+        packet.set_stream(meta.stream_index);
+        packet.set_pts(meta.pts);
+        packet.set_dts(meta.dts);
+        packet.set_duration(meta.duration);
+        if meta.key {
+            packet.set_flags(ffmpeg::packet::Flags::KEY);
+        }
+        MediaPacket { packet, packet_type: meta.packet_type, stream_index: meta.stream_index }
+    }
+}
+
+struct State {
+    entries: VecDeque<Entry>,
+    base: u64,                          // entries[0] 的序号（丢弃旧数据后其余包的序号不变）
+    sync_points: VecDeque<(u64, i64)>,  // 可以开始解码的位置（序号, 直播时间），按序号递增
+    latest_ms: Option<i64>,
+    bytes: u64,
+    writer: Option<(u64, File, u64)>,   // 正在写入的临时文件（编号, 文件, 已写入字节）
+    next_segment: u64,
+    oldest_segment: u64,                // 磁盘上最旧的临时文件编号
+    reader: Option<(u64, File)>,        // 最近读取的临时文件
+    ended: Option<DemuxEnd>,            // 录制结束原因（直播结束或读包失败）
+}
+
+/// 取下一个录制的包的结果
+enum Step {
+    Entry(Entry, Vec<u8>),
+    Waiting,
+    Ended(DemuxEnd),
+}
+
+/// 读取时移录制的结果
+pub enum TimeshiftRead {
+    /// 下一个包及其直播时间
+    Packet(MediaPacket, Option<i64>),
+    /// 已读到录制末端，等待录制线程写入
+    Waiting,
+    /// 录制已结束且全部读完
+    Ended(DemuxEnd),
+}
+
+/// 时移缓冲（录制线程写入，解封装线程读取）
+pub struct TimeshiftBuffer {
+    dir: PathBuf,
+    limits: TimeshiftLimits,
+    segment_bytes: u64,
+    sync_type: PacketType,  // 有视频时只有视频关键帧可以开始播放
+    state: Mutex<State>,
+    grown: Condvar,
+}
+
+impl TimeshiftBuffer {
+    /// 在系统临时目录下创建录制目录
+    pub fn create(limits: TimeshiftLimits, has_video: bool) -> Result<Self> {
+        Self::with_segment_bytes(limits, has_video, SEGMENT_BYTES)
+    }
+
+    fn with_segment_bytes(limits: TimeshiftLimits, has_video: bool, segment_bytes: u64) -> Result<Self> {
+        let id = NEXT_BUFFER_ID.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("myy_player_timeshift_{}_{}", std::process::id(), id));
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            limits,
+            segment_bytes,
+            sync_type: if has_video { PacketType::Video } else { PacketType::Audio },
+            state: Mutex::new(State {
+                entries: VecDeque::new(),
+                base: 0,
+                sync_points: VecDeque::new(),
+                latest_ms: None,
+                bytes: 0,
+                writer: None,
+                next_segment: 0,
+                oldest_segment: 0,
+                reader: None,
+                ended: None,
+            }),
+            grown: Condvar::new(),
+        })
+    }
+
+    fn segment_path(&self, segment: u64) -> PathBuf {
+        self.dir.join(format!("{:06}.bin", segment))
+    }
+
+    /// 录制一个从直播流读到的包（time_ms 为直播时间）
+    pub fn record(&self, media_packet: &MediaPacket, time_ms: Option<i64>) -> Result<()> {
+        let packet = &media_packet.packet;
+        let meta = PacketMeta {
+            packet_type: media_packet.packet_type,
+            stream_index: media_packet.stream_index,
+            pts: packet.pts(),
+            dts: packet.dts(),
+            duration: packet.duration(),
+            key: packet.is_key(),
+            time_ms,
+        };
+        self.append(meta, packet.data().unwrap_or(&[]))
+    }
+
+    fn append(&self, meta: PacketMeta, data: &[u8]) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let full = state.writer.as_ref().is_none_or(|(_, _, written)| *written > 0 && written + data.len() as u64 > self.segment_bytes);
+        if full {
+            let segment = state.next_segment;
+            let file = File::create(self.segment_path(segment))?;
+            state.next_segment += 1;
+            state.writer = Some((segment, file, 0));
+        }
+        let (segment, file, written) = state.writer.as_mut().expect("刚打开了临时文件");
+        file.write_all(data)?;
+        let entry = Entry { meta, segment: *segment, offset: *written, len: data.len() };
+        *written += data.len() as u64;
+
+        let index = state.base + state.entries.len() as u64;
+        // 有视频时从视频关键帧开始播放；纯音频时每个包都可以开始解码
+        let sync = meta.packet_type == self.sync_type && (meta.key || self.sync_type == PacketType::Audio);
+        if let (true, Some(time_ms)) = (sync, meta.time_ms) {
+            state.sync_points.push_back((index, time_ms));
+        }
+        if let Some(time_ms) = meta.time_ms {
+            state.latest_ms = Some(state.latest_ms.map_or(time_ms, |latest| latest.max(time_ms)));
+        }
+        state.entries.push_back(entry);
+        state.bytes += data.len() as u64;
+        self.truncate(&mut state);
+        drop(state);
+        self.grown.notify_all();
+        Ok(())
+    }
+
+    /// 超出保留上限时按 GOP 丢弃最旧的数据（保证录制总是从可以开始解码的位置开始），删除不再引用的临时文件
+    fn truncate(&self, state: &mut State) {
+        let max_ms = self.limits.max_duration.as_millis() as i64;
+        loop {
+            let over_time = matches!(
+                (state.sync_points.front(), state.latest_ms),
+                (Some(&(_, start_ms)), Some(latest_ms)) if latest_ms - start_ms > max_ms
+            );
+            let over_size = state.bytes > self.limits.max_bytes;
+            if !over_time && !over_size {
+                break;
+            }
+            // 丢到下一个关键帧为止；只剩一个 GOP 仍超出大小上限时逐个丢弃
+            let drop_until = match state.sync_points.get(1) {
+                Some(&(index, _)) => index,
+                None if over_size && state.entries.len() > 1 => state.base + 1,
+                None => break,
+            };
+            while state.base < drop_until {
+                let Some(entry) = state.entries.pop_front() else {
+                    break;
+                };
+                state.bytes -= entry.len as u64;
+                state.base += 1;
+            }
+            while state.sync_points.front().is_some_and(|&(index, _)| index < state.base) {
+                state.sync_points.pop_front();
+            }
+        }
+
+        let first_segment = match (state.entries.front(), &state.writer) {
+            (Some(entry), _) => entry.segment,
+            (None, Some((segment, _, _))) => *segment,
+            (None, None) => state.next_segment,
+        };
+        while state.oldest_segment < first_segment {
+            let segment = state.oldest_segment;
+            if state.reader.as_ref().is_some_and(|(reading, _)| *reading == segment) {
+                state.reader = None;
+            }
+            if let Err(e) = fs::remove_file(self.segment_path(segment)) {
+                warn!("⚠️ 删除时移临时文件失败: {}", e);
+            }
+            debug!("🗑️ 时移录制超出保留上限，删除临时文件 #{}", segment);
+            state.oldest_segment += 1;
+        }
+    }
+
+    /// 录制结束（直播结束或读包失败），读完已录制的数据后读取方收到结束原因
+    pub fn finish(&self, end: DemuxEnd) {
+        self.state.lock().unwrap().ended.get_or_insert(end);
+        self.grown.notify_all();
+    }
+
+    /// 可回看窗口（最早保留的关键帧, 最新的直播时间）
+    pub fn window(&self) -> Option<(i64, i64)> {
+        let state = self.state.lock().unwrap();
+        let &(_, start_ms) = state.sync_points.front()?;
+        state.latest_ms.map(|latest_ms| (start_ms, latest_ms.max(start_ms)))
+    }
+
+    /// 从 target_ms 开始播放时的读取位置（之前最近的关键帧；早于窗口时为窗口开始）
+    pub fn seek_index(&self, target_ms: i64) -> Option<u64> {
+        let state = self.state.lock().unwrap();
+        let before = state.sync_points.partition_point(|&(_, time_ms)| time_ms <= target_ms);
+        state.sync_points.get(before.saturating_sub(1)).map(|&(index, _)| index)
+    }
+
+    /// 读取 cursor 处的包并前移 cursor；已读到录制末端时最多等待 wait
+    pub fn read(&self, cursor: &mut u64, wait: Duration) -> Result<TimeshiftRead> {
+        Ok(match self.next(cursor, wait)? {
+            Step::Entry(entry, data) => TimeshiftRead::Packet(entry.to_packet(&data), entry.meta.time_ms),
+            Step::Waiting => TimeshiftRead::Waiting,
+            Step::Ended(end) => TimeshiftRead::Ended(end),
+        })
+    }
+
+    fn next(&self, cursor: &mut u64, wait: Duration) -> Result<Step> {
+        let mut state = self.state.lock().unwrap();
+        if *cursor < state.base {
+            // 暂停太久，读取位置的数据已被丢弃
+            let oldest = state.sync_points.front().map_or(state.base, |&(index, _)| index);
+            warn!("⚠️ 时移数据已超出保留上限被丢弃，从最早保留的关键帧继续（跳过 {} 个包）", oldest - *cursor);
+            *cursor = oldest;
+        }
+        let Some(&entry) = state.entries.get((*cursor - state.base) as usize) else {
+            if let Some(end) = &state.ended {
+                return Ok(Step::Ended(end.clone()));
+            }
+            drop(self.grown.wait_timeout(state, wait).unwrap());
+            return Ok(Step::Waiting);
+        };
+        let data = self.load(&mut state, &entry)?;
+        *cursor += 1;
+        Ok(Step::Entry(entry, data))
+    }
+
+    fn load(&self, state: &mut State, entry: &Entry) -> Result<Vec<u8>> {
+        if state.reader.as_ref().is_none_or(|(segment, _)| *segment != entry.segment) {
+            state.reader = Some((entry.segment, File::open(self.segment_path(entry.segment))?));
+        }
+        let (_, file) = state.reader.as_mut().expect("刚打开了临时文件");
+        file.seek(SeekFrom::Start(entry.offset))?;
+        let mut data = vec![0; entry.len];
+        file.read_exact(&mut data)?;
+        Ok(data)
+    }
+}
+
+impl Drop for TimeshiftBuffer {
+    fn drop(&mut self) {
+        // 先关闭文件（Windows 上无法删除打开的文件）
+        if let Ok(state) = self.state.get_mut() {
+            state.writer = None;
+            state.reader = None;
+        }
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            warn!("⚠️ 删除时移临时目录失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME_MS: i64 = 40;
+    const GOP: usize = 10;
+
+    /// 第 n 个视频包（每 GOP 个包一个关键帧），数据为 4 个字节的 n
+    fn append_video(buffer: &TimeshiftBuffer, n: usize) {
+        let meta = PacketMeta {
+            packet_type: PacketType::Video,
+            stream_index: 0,
+            pts: Some(n as i64),
+            dts: Some(n as i64),
+            duration: 1,
+            key: n.is_multiple_of(GOP),
+            time_ms: Some(n as i64 * FRAME_MS),
+        };
+        buffer.append(meta, &(n as u32).to_le_bytes()).unwrap();
+    }
+
+    fn next_data(buffer: &TimeshiftBuffer, cursor: &mut u64) -> Option<u32> {
+        match buffer.next(cursor, Duration::ZERO).unwrap() {
+            Step::Entry(_, data) => Some(u32::from_le_bytes(data.try_into().unwrap())),
+            _ => None,
+        }
+    }
+
+    fn segment_files(buffer: &TimeshiftBuffer) -> usize {
+        fs::read_dir(&buffer.dir).unwrap().count()
+    }
+
+    #[test]
+    fn test_reads_follow_the_recording() {
+        let buffer = TimeshiftBuffer::create(TimeshiftLimits::default(), true).unwrap();
+        let mut cursor = 0;
+        assert!(matches!(buffer.next(&mut cursor, Duration::from_millis(10)).unwrap(), Step::Waiting));
+
+        for n in 0..25 {
+            append_video(&buffer, n);
+        }
+        let read: Vec<_> = std::iter::from_fn(|| next_data(&buffer, &mut cursor)).collect();
+        assert_eq!(read, (0..25).collect::<Vec<u32>>());
+
+        // 录制结束：读完之后收到结束原因
+        assert!(matches!(buffer.next(&mut cursor, Duration::ZERO).unwrap(), Step::Waiting));
+        buffer.finish(DemuxEnd::Eof);
+        assert!(matches!(buffer.next(&mut cursor, Duration::ZERO).unwrap(), Step::Ended(DemuxEnd::Eof)));
+    }
+
+    #[test]
+    fn test_window_and_seek_use_keyframes() {
+        let buffer = TimeshiftBuffer::create(TimeshiftLimits::default(), true).unwrap();
+        assert_eq!(buffer.window(), None);
+        for n in 0..35 {
+            append_video(&buffer, n);
+        }
+        assert_eq!(buffer.window(), Some((0, 34 * FRAME_MS)));
+
+        // seek 目标之前最近的关键帧；早于窗口时从窗口开始
+        assert_eq!(buffer.seek_index(25 * FRAME_MS), Some(20));
+        assert_eq!(buffer.seek_index(20 * FRAME_MS), Some(20));
+        assert_eq!(buffer.seek_index(-1_000), Some(0));
+        assert_eq!(buffer.seek_index(i64::MAX), Some(30));
+
+        let mut cursor = buffer.seek_index(25 * FRAME_MS).unwrap();
+        assert_eq!(next_data(&buffer, &mut cursor), Some(20));
+    }
+
+    #[test]
+    fn test_retention_drops_oldest_gops_and_files() {
+        // 保留 1 分钟；每个临时文件 40 字节（10 个包）
+        let limits = TimeshiftLimits::new(1, 1024);
+        let buffer = TimeshiftBuffer::with_segment_bytes(limits, true, 40).unwrap();
+        let frames_per_minute = (60_000 / FRAME_MS) as usize;
+        for n in 0..frames_per_minute + 3 * GOP {
+            append_video(&buffer, n);
+        }
+
+        // 窗口不超过 1 分钟，从关键帧开始，之前的临时文件已删除
+        let (start_ms, end_ms) = buffer.window().unwrap();
+        assert!(end_ms - start_ms <= 60_000, "{}..{}", start_ms, end_ms);
+        assert_eq!(start_ms % (GOP as i64 * FRAME_MS), 0);
+        assert_eq!(start_ms, 3 * GOP as i64 * FRAME_MS);
+        assert_eq!(segment_files(&buffer), frames_per_minute / GOP);
+
+        // 停在被丢弃位置的读取跳到最早保留的关键帧
+        let mut cursor = 5;
+        assert_eq!(next_data(&buffer, &mut cursor), Some(3 * GOP as u32));
+    }
+
+    #[test]
+    fn test_size_cap_applies_without_keyframes() {
+        let limits = TimeshiftLimits { max_duration: Duration::from_secs(3_600), max_bytes: 40 };
+        let buffer = TimeshiftBuffer::create(limits, false).unwrap();
+        for n in 0..30u32 {
+            let meta = PacketMeta {
+                packet_type: PacketType::Audio,
+                stream_index: 1,
+                pts: Some(n.into()),
+                dts: Some(n.into()),
+                duration: 1,
+                key: false,
+                time_ms: Some(i64::from(n) * 20),
+            };
+            buffer.append(meta, &n.to_le_bytes()).unwrap();
+        }
+        // 纯音频：每个包都可以开始播放，只保留最新的 10 个包
+        assert_eq!(buffer.window(), Some((20 * 20, 29 * 20)));
+        assert_eq!(buffer.seek_index(25 * 20), Some(25));
+    }
+
+    #[test]
+    fn test_temp_dir_is_removed_on_drop() {
+        let buffer = TimeshiftBuffer::create(TimeshiftLimits::default(), true).unwrap();
+        append_video(&buffer, 0);
+        let dir = buffer.dir.clone();
+        assert!(dir.exists());
+        drop(buffer);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,246 @@
+// HDR (PQ / HLG) 画面的色调映射（HDR10、HLG 广播等 BT.2020 片源在 SDR 显示器上显示）
+//
+// swscale 按 BT.2020 矩阵（ffi_util::set_bt2020_colorspace）把 YUV 转换为 16-bit 的非线性 RGB（不先截断到 8-bit，避免 PQ 曲线上的色带），然后逐像素：
+// 按传输特性还原为线性光（SDR 参考白为 1.0）→ BT.2020 到 BT.709 色域转换 → 按亮度做扩展 Reinhard 压缩
+// （RGB 等比缩放，保持色相）→ 按 2.2 伽马编码为 8-bit。还原和编码都查表，画面按行分给多个线程处理。
+// SDR 片源不经过这里，输出与原来逐字节相同。
+
+use crate::core::FramePool;
+use ffmpeg_next::util;
+use std::sync::OnceLock;
+
+/// SDR 参考白（尼特，BT.2408 的 HDR 参考白）
+const REFERENCE_WHITE_NITS: f64 = 203.0;
+
+/// 压缩到满幅的最高亮度（尼特，常见 HDR10 母版亮度）
+const PEAK_NITS: f64 = 1000.0;
+
+/// HLG 按 1000 尼特显示器还原（系统伽马 1.2）
+const HLG_DISPLAY_NITS: f64 = 1000.0;
+const HLG_SYSTEM_GAMMA: f64 = 1.2;
+
+/// 查表精度（16-bit 编码值取高 12 位）
+const TABLE_BITS: u32 = 12;
+const TABLE_SIZE: usize = 1 << TABLE_BITS;
+
+/// BT.2020 -> BT.709 色域转换矩阵（线性光）
+const BT2020_TO_BT709: [[f32; 3]; 3] = [
+    [1.6605, -0.5876, -0.0728],
+    [-0.1246, 1.1329, -0.0083],
+    [-0.0182, -0.1006, 1.1187],
+];
+
+/// 需要色调映射的 HDR 传输特性
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HdrTransfer {
+    /// SMPTE ST 2084（HDR10 / HDR10+ / 杜比视界兼容层）
+    Pq,
+    /// ARIB STD-B67（HLG 广播）
+    Hlg,
+}
+
+impl HdrTransfer {
+    /// 按传输特性判断（SDR 返回 None）
+    pub fn detect(transfer: util::color::TransferCharacteristic) -> Option<Self> {
+        match transfer {
+            util::color::TransferCharacteristic::SMPTE2084 => Some(HdrTransfer::Pq),
+            util::color::TransferCharacteristic::ARIB_STD_B67 => Some(HdrTransfer::Hlg),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HdrTransfer::Pq => "PQ",
+            HdrTransfer::Hlg => "HLG",
+        }
+    }
+}
+
+/// 色彩空间说明（信息面板显示，如 "BT.2020 PQ (HDR, tone mapped)"）
+pub fn describe_color(primaries: util::color::Primaries, transfer: util::color::TransferCharacteristic) -> String {
+    use util::color::Primaries;
+    let primaries = match primaries {
+        Primaries::BT709 => "BT.709".to_string(),
+        Primaries::BT2020 => "BT.2020".to_string(),
+        Primaries::BT470BG | Primaries::SMPTE170M => "BT.601".to_string(),
+        Primaries::SMPTE432 => "Display P3".to_string(),
+        Primaries::Unspecified | Primaries::Reserved0 | Primaries::Reserved => "unspecified".to_string(),
+        other => format!("{:?}", other),
+    };
+    match HdrTransfer::detect(transfer) {
+        Some(hdr) => format!("{} {} (HDR, tone mapped)", primaries, hdr.label()),
+        None => primaries,
+    }
+}
+
+/// PQ (SMPTE ST 2084) 编码值 -> 亮度（尼特）
+pub(crate) fn pq_to_nits(code: f64) -> f64 {
+    const M1: f64 = 0.1593017578125;
+    const M2: f64 = 78.84375;
+    const C1: f64 = 0.8359375;
+    const C2: f64 = 18.8515625;
+    const C3: f64 = 18.6875;
+    let e = code.clamp(0.0, 1.0).powf(1.0 / M2);
+    let linear = ((e - C1).max(0.0) / (C2 - C3 * e)).powf(1.0 / M1);
+    linear * 10_000.0
+}
+
+/// HLG 编码值 -> 显示亮度（尼特；逐通道应用系统伽马的简化 OOTF）
+fn hlg_to_nits(code: f64) -> f64 {
+    const A: f64 = 0.17883277;
+    const B: f64 = 0.28466892;
+    const C: f64 = 0.55991073;
+    let code = code.clamp(0.0, 1.0);
+    let scene = if code <= 0.5 { code * code / 3.0 } else { (((code - C) / A).exp() + B) / 12.0 };
+    HLG_DISPLAY_NITS * scene.powf(HLG_SYSTEM_GAMMA)
+}
+
+/// 非线性编码值（高 12 位）-> 线性光（参考白为 1.0）
+fn linear_table(transfer: HdrTransfer) -> &'static [f32; TABLE_SIZE] {
+    static PQ: OnceLock<[f32; TABLE_SIZE]> = OnceLock::new();
+    static HLG: OnceLock<[f32; TABLE_SIZE]> = OnceLock::new();
+    let (table, to_nits): (_, fn(f64) -> f64) = match transfer {
+        HdrTransfer::Pq => (&PQ, pq_to_nits),
+        HdrTransfer::Hlg => (&HLG, hlg_to_nits),
+    };
+    table.get_or_init(|| {
+        let mut table = [0.0; TABLE_SIZE];
+        for (code, value) in table.iter_mut().enumerate() {
+            *value = (to_nits(code as f64 / (TABLE_SIZE - 1) as f64) / REFERENCE_WHITE_NITS) as f32;
+        }
+        table
+    })
+}
+
+/// 线性光（0.0 ~ 1.0，按 12 位量化）-> 8-bit（2.2 伽马）
+fn encode_table() -> &'static [u8; TABLE_SIZE] {
+    static TABLE: OnceLock<[u8; TABLE_SIZE]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0u8; TABLE_SIZE];
+        for (index, value) in table.iter_mut().enumerate() {
+            *value = ((index as f64 / (TABLE_SIZE - 1) as f64).powf(1.0 / 2.2) * 255.0).round() as u8;
+        }
+        table
+    })
+}
+
+/// 一个像素的色调映射（输入为 16-bit 非线性 BT.2020 RGB）
+fn map_pixel(linear: &[f32; TABLE_SIZE], encode: &[u8; TABLE_SIZE], rgb: [u16; 3]) -> [u8; 3] {
+    let source = rgb.map(|code| linear[(code >> (16 - TABLE_BITS)) as usize]);
+    let mut color = BT2020_TO_BT709.map(|row| (row[0] * source[0] + row[1] * source[1] + row[2] * source[2]).max(0.0));
+
+    let luma = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+    if luma > 0.0 {
+        let white = (PEAK_NITS / REFERENCE_WHITE_NITS) as f32;
+        let mapped = luma * (1.0 + luma / (white * white)) / (1.0 + luma);
+        let scale = mapped.min(1.0) / luma;
+        for channel in &mut color {
+            *channel *= scale;
+        }
+    }
+    color.map(|value| encode[(value.min(1.0) * (TABLE_SIZE - 1) as f32).round() as usize])
+}
+
+/// 把 swscale 输出的 RGBA64LE 平面色调映射为连续的 8-bit RGBA（alpha 为 255；缺失的行保持为 0；pool 不为 None 时从中取缓冲区）
+pub(crate) fn tone_map_rgba64(
+    transfer: HdrTransfer,
+    plane: &[u8],
+    stride: usize,
+    width: u32,
+    height: u32,
+    pool: Option<&FramePool>,
+) -> Vec<u8> {
+    let row_size = width as usize * 4;
+    if row_size == 0 || height == 0 {
+        return Vec::new();
+    }
+    let (linear, encode) = (linear_table(transfer), encode_table());
+    let len = row_size * height as usize;
+    let mut data = pool.map_or_else(|| vec![0u8; len], |pool| pool.acquire(len));
+
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get()).clamp(1, 8);
+    let rows_per_chunk = (height as usize).div_ceil(threads);
+    std::thread::scope(|scope| {
+        for (chunk_index, chunk) in data.chunks_mut(rows_per_chunk * row_size).enumerate() {
+            scope.spawn(move || {
+                for (row_index, row) in chunk.chunks_exact_mut(row_size).enumerate() {
+                    let y = chunk_index * rows_per_chunk + row_index;
+                    let src = y.checked_mul(stride).and_then(|offset| plane.get(offset..)).unwrap_or_default();
+                    let mapped = (src.len() / 8).min(width as usize);
+                    for (pixel, src) in row.chunks_exact_mut(4).zip(src.chunks_exact(8)) {
+                        let channel = |i: usize| u16::from_le_bytes([src[i * 2], src[i * 2 + 1]]);
+                        let [r, g, b] = map_pixel(linear, encode, [channel(0), channel(1), channel(2)]);
+                        pixel.copy_from_slice(&[r, g, b, 255]);
+                    }
+                    // 复用的缓冲区里是上一帧的内容
+                    row[mapped * 4..].fill(0);
+                }
+            });
+        }
+    });
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(normalized: f64) -> u16 {
+        (normalized * 65535.0).round() as u16
+    }
+
+    #[test]
+    fn test_reference_white_and_black() {
+        // PQ 约 0.58 为 203 尼特，HLG 75% 为参考白
+        let pq = linear_table(HdrTransfer::Pq);
+        assert_eq!(pq[0], 0.0);
+        assert!((pq[(0.5806 * 4095.0) as usize] - 1.0).abs() < 0.02, "{}", pq[(0.5806 * 4095.0) as usize]);
+        assert!(pq.windows(2).all(|pair| pair[0] <= pair[1]));
+        let hlg = linear_table(HdrTransfer::Hlg);
+        assert!((hlg[(0.75 * 4095.0) as usize] - 1.0).abs() < 0.02, "{}", hlg[(0.75 * 4095.0) as usize]);
+
+        let (linear, encode) = (linear_table(HdrTransfer::Pq), encode_table());
+        assert_eq!(map_pixel(linear, encode, [0, 0, 0]), [0, 0, 0]);
+        // 参考白仍为中性灰，且明显亮于中灰、低于满幅
+        let white = map_pixel(linear, encode, [code(0.5806); 3]);
+        assert!(white[0] == white[1] && white[1] == white[2] && (150..250).contains(&white[0]), "{:?}", white);
+    }
+
+    #[test]
+    fn test_highlights_are_compressed_and_hue_kept() {
+        let (linear, encode) = (linear_table(HdrTransfer::Pq), encode_table());
+        // 10000 尼特的白不会溢出，1000 尼特接近满幅
+        assert_eq!(map_pixel(linear, encode, [65535; 3]), [255; 3]);
+        let peak = map_pixel(linear, encode, [code(0.7518); 3]);
+        assert!(peak[0] >= 245, "{:?}", peak);
+        // BT.2020 的纯红映射为 BT.709 中以红为主的颜色
+        let red = map_pixel(linear, encode, [code(0.6), 0, 0]);
+        assert!(red[0] > 100 && red[1] == 0 && red[2] == 0, "{:?}", red);
+    }
+
+    #[test]
+    fn test_rgba64_plane_with_padding() {
+        // 2x2 画面，每行填充到 24 字节；第二行缺失
+        let mut plane = vec![0u8; 24];
+        for pixel in plane[..16].chunks_exact_mut(8) {
+            for channel in pixel.chunks_exact_mut(2) {
+                channel.copy_from_slice(&code(0.5806).to_le_bytes());
+            }
+        }
+        let data = tone_map_rgba64(HdrTransfer::Pq, &plane, 24, 2, 2, None);
+        assert_eq!(data.len(), 16);
+        assert_eq!(data[3], 255);
+        assert_eq!(data[0..4], data[4..8]);
+        assert_eq!(data[8..], [0; 8]);
+    }
+
+    #[test]
+    fn test_describe_color() {
+        use util::color::{Primaries, TransferCharacteristic};
+        assert_eq!(describe_color(Primaries::BT2020, TransferCharacteristic::SMPTE2084), "BT.2020 PQ (HDR, tone mapped)");
+        assert_eq!(describe_color(Primaries::BT2020, TransferCharacteristic::ARIB_STD_B67), "BT.2020 HLG (HDR, tone mapped)");
+        assert_eq!(describe_color(Primaries::BT709, TransferCharacteristic::BT709), "BT.709");
+        assert_eq!(describe_color(Primaries::Unspecified, TransferCharacteristic::Unspecified), "unspecified");
+    }
+}
@@ -1,11 +1,21 @@
-use anyhow::Result;
-use egui::{Ui, Rect, TextureHandle, ColorImage, TextureOptions};
-use log::{info, debug};
+use anyhow::{anyhow, Result};
+use egui::{Ui, Rect, TextureHandle, TextureId, ColorImage, TextureOptions};
+use egui::load::SizedTexture;
+use egui::mutex::RwLock;
+use log::{info, debug, warn};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use eframe::wgpu::{Device, Queue, Texture, TextureView, TextureDescriptor, TextureUsages, TextureDimension, TextureFormat, Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d};
+use eframe::egui_wgpu;
+use eframe::wgpu::{Device, ErrorFilter, FilterMode, Queue, Texture, TextureDescriptor, TextureUsages, TextureDimension, TextureFormat, Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d};
 
-use crate::core::VideoFrame;
+use super::video_view::display_size;
+use myy_player::VideoFrame;
+use super::gpu_recovery::{GpuRecovery, RenderMode};
+use super::luma_mip::LumaMip;
+use super::texture_cache::{TextureCache, TextureKey};
+use super::video_view::{FitMode, VideoView};
+use super::yuv_converter::{YuvConverter, YuvTarget};
 
 /// egui 视频渲染器 - 高性能零拷贝纹理更新
 pub struct EguiVideoRenderer {
@@ -13,30 +23,59 @@ pub struct EguiVideoRenderer {
     device: Arc<Device>,
     /// wgpu 队列 (Arc 包装)
     queue: Arc<Queue>,
-    /// 当前视频纹理
-    video_texture: Option<VideoTexture>,
+    /// egui 的 wgpu 渲染器（注册 YUV 转换输出的纹理）
+    egui_renderer: Arc<RwLock<egui_wgpu::Renderer>>,
+    /// YUV 转换管线（第一次收到 YUV 帧时创建）
+    yuv_converter: Option<YuvConverter>,
+    /// 当前视频纹理（按尺寸与格式复用）
+    video_texture: TextureCache<VideoTexture>,
     /// egui 纹理句柄缓存
     texture_cache: HashMap<String, TextureHandle>,
     /// 渲染统计
     stats: RenderStats,
+    /// 像素宽高比（SAR）
+    pixel_aspect: f64,
+    /// 显示时顺时针旋转角度（0/90/180/270）
+    rotation: u32,
+    /// 等待下一帧到达后生效的画面几何信息（切换文件时旧画面保持旧的宽高比与旋转）
+    pending_geometry: Option<(f64, u32)>,
+    /// 缩放模式、手动缩放与平移
+    view: VideoView,
+    /// 最近一次绘制时的 DPI 缩放（原始大小模式换算使用）
+    pixels_per_point: f32,
+    /// GPU 设备丢失标志（由 wgpu 设备丢失回调设置）
+    device_lost: Arc<AtomicBool>,
+    /// 设备丢失恢复状态
+    recovery: GpuRecovery,
+    /// 最近一帧的 CPU 数据（GPU 资源丢失后据此重建纹理）
+    last_frame: Option<VideoFrame>,
+    /// 待显示给用户的通知（如切换到兼容模式）
+    notification: Option<String>,
+    /// 当前帧的亮度缩略图（帧变化时更新）
+    luma_mip: Option<LumaMip>,
 }
 
 struct VideoTexture {
-    /// wgpu 纹理
-    wgpu_texture: Texture,
-    /// 纹理视图
-    texture_view: TextureView,
-    /// egui 纹理句柄
-    egui_handle: TextureHandle,
-    /// 纹理尺寸
-    width: u32,
-    height: u32,
+    /// 纹理内容
+    content: TextureContent,
+    /// 绘制使用的 egui 纹理
+    texture_id: TextureId,
     /// 最后更新时间戳
     last_pts: i64,
 }
 
+enum TextureContent {
+    /// RGBA 帧（GPU 模式）：wgpu 纹理，注册为 egui 原生纹理
+    Rgba(Texture),
+    /// YUV 帧（GPU 模式）：平面纹理与转换输出纹理，输出纹理注册为 egui 原生纹理
+    Yuv(YuvTarget),
+    /// 兼容模式：egui 管理的纹理
+    Egui(TextureHandle),
+}
+
+/// 渲染统计
 #[derive(Default)]
-struct RenderStats {
+pub struct RenderStats {
     frames_rendered: u64,
     texture_updates: u64,
     cache_hits: u64,
@@ -50,192 +89,342 @@ impl EguiVideoRenderer {
 
         let device = wgpu_render_state.device.clone();
         let queue = wgpu_render_state.queue.clone();
+        let egui_renderer = wgpu_render_state.renderer.clone();
+
+        // 监听设备丢失（驱动重置、切换显卡等），在下一帧尝试恢复
+        let device_lost = Arc::new(AtomicBool::new(false));
+        let lost_flag = device_lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            warn!("⚠️  GPU 设备丢失: {:?} {}", reason, message);
+            lost_flag.store(true, Ordering::SeqCst);
+        });
 
         Ok(Self {
             device,
             queue,
-            video_texture: None,
+            egui_renderer,
+            yuv_converter: None,
+            video_texture: TextureCache::default(),
             texture_cache: HashMap::new(),
             stats: RenderStats::default(),
+            pixel_aspect: 1.0,
+            rotation: 0,
+            pending_geometry: None,
+            view: VideoView::default(),
+            pixels_per_point: 1.0,
+            device_lost,
+            recovery: GpuRecovery::new(),
+            last_frame: None,
+            notification: None,
+            luma_mip: None,
         })
     }
 
-    /// 更新纹理并渲染视频帧
-    pub fn update_and_render(&mut self, ui: &mut Ui, frame: &VideoFrame, rect: Rect) -> Result<()> {
+    /// 更新纹理并渲染视频帧（保留帧的 CPU 数据，GPU 资源丢失后用于重建）
+    pub fn update_and_render(&mut self, ui: &mut Ui, frame: VideoFrame, rect: Rect) -> Result<()> {
+        self.check_device_lost();
+        self.pixels_per_point = ui.ctx().pixels_per_point();
+        if let Some((pixel_aspect, rotation)) = self.pending_geometry.take() {
+            self.pixel_aspect = pixel_aspect;
+            self.rotation = rotation;
+        }
+
         // 检查是否需要更新纹理（只在PTS变化时更新，避免重复更新同一帧）
-        let needs_update = self.video_texture.as_ref()
-            .map(|tex| {
+        let needs_update = self.recovery.needs_rebuild() || self.video_texture.current()
+            .map(|(key, tex)| {
                 // 只在以下情况更新：
                 // 1. PTS不同（新帧）
-                // 2. 尺寸变化
-                tex.last_pts != frame.pts || tex.width != frame.width || tex.height != frame.height
+                // 2. 尺寸或格式变化
+                tex.last_pts != frame.pts || key != TextureKey::of(&frame)
             })
             .unwrap_or(true);
 
-        if needs_update {
+        let result = if needs_update {
             debug!("📺 渲染视频帧: {}x{}, PTS: {}ms", frame.width, frame.height, frame.pts);
-            self.update_video_texture(ui.ctx(), frame)?;
             self.stats.texture_updates += 1;
+            self.luma_mip = match frame.planes {
+                Some(planes) => frame.data.get(planes.offsets[0]..).and_then(|plane| {
+                    LumaMip::from_luma_plane(frame.width, frame.height, plane, planes.strides[0], planes.full_range)
+                }),
+                None => LumaMip::from_rgba(frame.width, frame.height, &frame.data),
+            };
+            self.upload_frame(ui.ctx(), &frame)
         } else {
             self.stats.cache_hits += 1;
-        }
+            Ok(())
+        };
+        self.last_frame = Some(frame);
 
         // 渲染视频帧（即使没有更新纹理，也要渲染，因为egui可能重绘）
         self.render_video_frame(ui, rect)?;
         self.stats.frames_rendered += 1;
 
-        Ok(())
+        result
     }
 
-    /// 更新视频纹理
-    fn update_video_texture(&mut self, ctx: &egui::Context, frame: &VideoFrame) -> Result<()> {
-        debug!("🔄 更新视频纹理: {}x{}, PTS: {}ms", frame.width, frame.height, frame.pts);
+    /// 上传帧到纹理（GPU 模式下用错误作用域捕获 wgpu 错误），失败时释放资源等待重建
+    fn upload_frame(&mut self, ctx: &egui::Context, frame: &VideoFrame) -> Result<()> {
+        let result = match self.recovery.mode() {
+            RenderMode::Gpu => self.upload_with_error_scope(ctx, frame),
+            RenderMode::Fallback => self.update_video_texture(ctx, frame),
+        };
 
-        // 检查是否需要重新创建纹理
-        let needs_recreate = self.video_texture.as_ref()
-            .map(|tex| tex.width != frame.width || tex.height != frame.height)
-            .unwrap_or(true);
+        match result {
+            Ok(()) => {
+                if self.recovery.needs_rebuild() {
+                    info!("✅ 视频纹理已重建（{:?} 模式）", self.recovery.mode());
+                }
+                self.recovery.on_success();
+                Ok(())
+            }
+            Err(e) => {
+                self.handle_gpu_error(&e.to_string());
+                Err(e)
+            }
+        }
+    }
 
-        if needs_recreate {
-            info!("🆕 创建新视频纹理: {}x{}", frame.width, frame.height);
-            self.create_video_texture(ctx, frame)?;
-        } else {
-            // 只更新纹理数据
-            self.update_texture_data(ctx, frame)?;
+    /// 在 wgpu 错误作用域中上传帧
+    fn upload_with_error_scope(&mut self, ctx: &egui::Context, frame: &VideoFrame) -> Result<()> {
+        if self.device_lost.load(Ordering::SeqCst) {
+            return Err(anyhow!("GPU 设备已丢失"));
         }
 
-        Ok(())
+        self.device.push_error_scope(ErrorFilter::OutOfMemory);
+        self.device.push_error_scope(ErrorFilter::Validation);
+        let result = self.update_video_texture(ctx, frame);
+        let validation_error = pollster::block_on(self.device.pop_error_scope());
+        let memory_error = pollster::block_on(self.device.pop_error_scope());
+
+        result?;
+        match validation_error.or(memory_error) {
+            Some(error) => Err(anyhow!("GPU 错误: {}", error)),
+            None => Ok(()),
+        }
     }
 
-    /// 创建新的视频纹理
-    fn create_video_texture(&mut self, ctx: &egui::Context, frame: &VideoFrame) -> Result<()> {
-        // 创建 wgpu 纹理
-        let texture_desc = TextureDescriptor {
-            label: Some("Video Texture"),
-            size: Extent3d {
-                width: frame.width,
-                height: frame.height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: TextureDimension::D2,
-            format: TextureFormat::Rgba8UnormSrgb, // RGBA8 格式
-            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
-            view_formats: &[],
-        };
+    /// 检查设备丢失回调是否触发
+    fn check_device_lost(&mut self) {
+        if self.device_lost.load(Ordering::SeqCst) && self.recovery.mode() == RenderMode::Gpu && !self.recovery.needs_rebuild() {
+            self.handle_gpu_error("GPU 设备丢失");
+        }
+    }
 
-        let wgpu_texture = self.device.as_ref().create_texture(&texture_desc);
-        let texture_view = wgpu_texture.create_view(&Default::default());
-
-        // 上传初始纹理数据
-        self.queue.as_ref().write_texture(
-            ImageCopyTexture {
-                texture: &wgpu_texture,
-                mip_level: 0,
-                origin: Origin3d::ZERO,
-                aspect: eframe::wgpu::TextureAspect::All,
-            },
-            &frame.data,
-            ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * frame.width), // RGBA = 4 bytes per pixel
-                rows_per_image: Some(frame.height),
-            },
-            texture_desc.size,
-        );
-
-        // 创建 egui 纹理句柄
-        let egui_handle = self.create_egui_texture_handle(ctx, frame)?;
-
-        // 保存纹理信息
-        self.video_texture = Some(VideoTexture {
-            wgpu_texture,
-            texture_view,
-            egui_handle,
-            width: frame.width,
-            height: frame.height,
-            last_pts: frame.pts,
-        });
+    /// 处理 GPU 错误：释放缓存的 GPU 资源，下一帧重建；多次失败后切换到兼容模式
+    fn handle_gpu_error(&mut self, reason: &str) {
+        warn!("⚠️  视频渲染 GPU 错误: {}，释放纹理并在下一帧重建", reason);
+        self.release_video_texture();
+        self.yuv_converter = None;
+        self.texture_cache.clear();
 
-        Ok(())
+        if self.recovery.on_error() {
+            warn!("⚠️  GPU 资源多次重建失败，切换到兼容渲染模式");
+            self.notification = Some("GPU 渲染异常，已切换到兼容渲染模式".to_string());
+        }
+    }
+
+    /// 模拟设备丢失（调试命令：丢弃并重建缓存的纹理对象，用于验证恢复流程）
+    pub fn simulate_device_loss(&mut self) {
+        info!("🧪 模拟 GPU 设备丢失");
+        self.handle_gpu_error("模拟设备丢失");
+    }
+
+    /// 取出待显示的通知
+    pub fn take_notification(&mut self) -> Option<String> {
+        self.notification.take()
+    }
+
+    /// 是否直接接受 YUV 帧（GPU 模式下由着色器转换颜色；兼容模式下 YUV 帧要先在 CPU 上转换为 RGBA）
+    pub fn accepts_yuv(&self) -> bool {
+        self.recovery.mode() == RenderMode::Gpu
+    }
+
+    /// 释放当前纹理（下一帧重新创建）
+    fn release_video_texture(&mut self) {
+        if let Some(texture) = self.video_texture.take() {
+            self.release_texture(texture);
+        }
+    }
+
+    /// 释放纹理（注册给 egui 的原生纹理同时注销）
+    fn release_texture(&self, texture: VideoTexture) {
+        if !matches!(texture.content, TextureContent::Egui(_)) {
+            self.egui_renderer.write().free_texture(&texture.texture_id);
+        }
     }
 
-    /// 创建 egui 纹理句柄
-    fn create_egui_texture_handle(&self, ctx: &egui::Context, frame: &VideoFrame) -> Result<TextureHandle> {
-        // 将 RGBA 数据转换为 egui ColorImage
-        let color_image = ColorImage::from_rgba_unmultiplied(
-            [frame.width as usize, frame.height as usize],
-            &frame.data,
-        );
-
-        // 创建纹理句柄
-        let handle = ctx.load_texture(
-            "video_frame",
-            color_image,
-            TextureOptions::LINEAR, // 线性过滤获得更好的缩放质量
-        );
-
-        Ok(handle)
-    }
-
-    /// 更新现有纹理数据
-    fn update_texture_data(&mut self, _ctx: &egui::Context, frame: &VideoFrame) -> Result<()> {
-        if let Some(video_texture) = &mut self.video_texture {
-            // 只更新 egui 纹理句柄（不更新 wgpu 纹理，因为 egui 有自己的渲染管线）
-            // 将 RGBA 数据转换为 egui ColorImage 并更新纹理
-            let color_image = ColorImage::from_rgba_unmultiplied(
-                [frame.width as usize, frame.height as usize],
-                &frame.data,
-            );
-            
-            // 更新现有纹理（egui 会处理实际的 GPU 上传）
-            video_texture.egui_handle.set(color_image, TextureOptions::LINEAR);
-
-            video_texture.last_pts = frame.pts;
+    /// 更新视频纹理（尺寸和格式不变时原地写入，变化时替换为新纹理）
+    fn update_video_texture(&mut self, ctx: &egui::Context, frame: &VideoFrame) -> Result<()> {
+        debug!("🔄 更新视频纹理: {}x{}, PTS: {}ms", frame.width, frame.height, frame.pts);
+
+        // 兼容模式下 YUV 帧先在 CPU 上转换为 RGBA
+        if frame.planes.is_some() && self.recovery.mode() != RenderMode::Gpu {
+            return self.update_video_texture(ctx, &frame.to_rgba());
+        }
+
+        let key = TextureKey::of(frame);
+        if self.video_texture.get_mut(key).is_none() {
+            info!("🆕 创建新视频纹理: {}x{} {:?}", frame.width, frame.height, frame.format);
+            self.stats.cache_misses += 1;
+            let texture = self.create_video_texture(ctx, frame)?;
+            if let Some(old) = self.video_texture.insert(key, texture) {
+                self.release_texture(old);
+            }
         }
 
+        let Some(video_texture) = self.video_texture.get_mut(key) else {
+            return Ok(());
+        };
+        match &mut video_texture.content {
+            TextureContent::Rgba(texture) => write_rgba(&self.queue, texture, frame)?,
+            TextureContent::Yuv(target) => {
+                let converter = self.yuv_converter.as_ref().ok_or_else(|| anyhow!("YUV 转换管线未创建"))?;
+                target.convert(&self.device, &self.queue, converter, frame)?;
+            }
+            TextureContent::Egui(handle) => {
+                // 局部更新写入已有纹理（整体 set 会让 egui 重新分配纹理）
+                handle.set_partial([0, 0], color_image(frame), TextureOptions::LINEAR);
+            }
+        }
+        video_texture.last_pts = frame.pts;
         Ok(())
     }
 
+    /// 创建与帧尺寸、格式匹配的视频纹理（内容由 update_video_texture 写入）
+    fn create_video_texture(&mut self, ctx: &egui::Context, frame: &VideoFrame) -> Result<VideoTexture> {
+        // 兼容模式下不直接调用 wgpu
+        if self.recovery.mode() == RenderMode::Fallback {
+            let blank = ColorImage::new([frame.width as usize, frame.height as usize], egui::Color32::BLACK);
+            let handle = ctx.load_texture("video_frame", blank, TextureOptions::LINEAR);
+            return Ok(VideoTexture { texture_id: handle.id(), content: TextureContent::Egui(handle), last_pts: frame.pts });
+        }
+
+        // 线性过滤获得更好的缩放质量
+        let register = |view: &eframe::wgpu::TextureView| {
+            self.egui_renderer.write().register_native_texture(&self.device, view, FilterMode::Linear)
+        };
+        let (content, texture_id) = if frame.planes.is_some() {
+            let converter = self.yuv_converter.get_or_insert_with(|| {
+                info!("🎨 创建 GPU YUV 转换管线");
+                YuvConverter::new(&self.device)
+            });
+            let target = YuvTarget::new(&self.device, converter, frame)?;
+            let texture_id = register(target.output_view());
+            (TextureContent::Yuv(target), texture_id)
+        } else {
+            let texture = self.device.create_texture(&TextureDescriptor {
+                label: Some("Video Texture"),
+                size: Extent3d {
+                    width: frame.width,
+                    height: frame.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba8UnormSrgb, // RGBA8 格式
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            let texture_id = register(&texture.create_view(&Default::default()));
+            (TextureContent::Rgba(texture), texture_id)
+        };
+        Ok(VideoTexture { content, texture_id, last_pts: frame.pts })
+    }
+
     /// 渲染视频帧到 UI
     fn render_video_frame(&self, ui: &mut Ui, rect: Rect) -> Result<()> {
-        self.render_video_frame_only(ui, rect)
+        self.draw_texture(ui, rect)
     }
 
     /// 仅渲染视频帧（不更新纹理），用于避免重复更新导致的闪烁
-    pub fn render_video_frame_only(&self, ui: &mut Ui, rect: Rect) -> Result<()> {
-        if let Some(video_texture) = &self.video_texture {
-            // 计算视频的显示尺寸，保持宽高比
-            let video_aspect = video_texture.width as f32 / video_texture.height as f32;
-            let rect_aspect = rect.width() / rect.height();
-
-            let display_size = if video_aspect > rect_aspect {
-                // 视频更宽，以宽度为准
-                egui::Vec2::new(rect.width(), rect.width() / video_aspect)
-            } else {
-                // 视频更高，以高度为准
-                egui::Vec2::new(rect.height() * video_aspect, rect.height())
-            };
+    ///
+    /// GPU 资源丢失后在这里用保留的 CPU 帧重建纹理（暂停时也能恢复画面）
+    pub fn render_video_frame_only(&mut self, ui: &mut Ui, rect: Rect) -> Result<()> {
+        self.check_device_lost();
+        self.pixels_per_point = ui.ctx().pixels_per_point();
+        if self.recovery.needs_rebuild() {
+            if let Some(frame) = self.last_frame.take() {
+                let result = self.upload_frame(ui.ctx(), &frame);
+                self.last_frame = Some(frame);
+                if let Err(e) = result {
+                    debug!("重建视频纹理失败: {}", e);
+                    return Ok(());
+                }
+            }
+        }
+        self.draw_texture(ui, rect)
+    }
+
+    /// 视频在区域内的显示位置（按缩放模式、手动缩放与平移，考虑像素宽高比与旋转；可能超出区域）
+    fn fitted_rect(&self, width: u32, height: u32, rect: Rect) -> Rect {
+        let display_size = display_size(width, height, self.pixel_aspect, self.rotation);
+        self.view.layout(display_size, rect, self.pixels_per_point)
+    }
 
-            // 居中显示
-            let display_rect = Rect::from_center_size(rect.center(), display_size);
+    /// 画面中某个屏幕区域的平均亮度（0.0 - 1.0，基于亮度缩略图；没有画面或区域不在画面内时返回 None）
+    pub fn region_luma(&self, container: Rect, region: Rect) -> Option<f32> {
+        let mip = self.luma_mip.as_ref()?;
+        let frame = self.last_frame.as_ref()?;
+        let display_rect = self.fitted_rect(frame.width, frame.height, container);
+
+        // 屏幕坐标 -> 显示画面的归一化坐标 -> 帧坐标（撤销顺时针旋转）
+        let to_frame = |pos: egui::Pos2| {
+            let u = (pos.x - display_rect.left()) / display_rect.width();
+            let v = (pos.y - display_rect.top()) / display_rect.height();
+            match self.rotation {
+                90 => (v, 1.0 - u),
+                180 => (1.0 - u, 1.0 - v),
+                270 => (1.0 - v, u),
+                _ => (u, v),
+            }
+        };
+        let (x0, y0) = to_frame(region.left_top());
+        let (x1, y1) = to_frame(region.right_bottom());
+        mip.region_mean(x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1))
+    }
+
+    /// 绘制当前纹理（保持宽高比居中显示）
+    fn draw_texture(&self, ui: &mut Ui, rect: Rect) -> Result<()> {
+        if let Some((key, video_texture)) = self.video_texture.current() {
+            let display_rect = self.fitted_rect(key.width, key.height, rect);
+            let display_size = display_rect.size();
 
             // 渲染视频帧
-            ui.allocate_ui_at_rect(display_rect, |ui| {
-                ui.add(
-                    egui::Image::from_texture(&video_texture.egui_handle)
-                        .fit_to_exact_size(display_size)
-                        .rounding(egui::Rounding::same(4.0)) // 圆角
-                );
-            });
+            if self.rotation == 0 && rect.contains_rect(display_rect) {
+                ui.allocate_ui_at_rect(display_rect, |ui| {
+                    ui.add(
+                        egui::Image::from_texture(SizedTexture::new(video_texture.texture_id, display_size))
+                            .fit_to_exact_size(display_size)
+                            .rounding(egui::Rounding::same(4.0)) // 圆角
+                    );
+                });
+            } else {
+                // 放大或填满时裁掉区域外的部分；旋转时按未旋转的尺寸绘制，再绕中心旋转（egui 旋转时不支持圆角）
+                ui.scope(|ui| {
+                    ui.set_clip_rect(ui.clip_rect().intersect(rect));
+                    let image = egui::Image::from_texture(SizedTexture::new(video_texture.texture_id, display_size));
+                    if self.rotation == 0 {
+                        image.paint_at(ui, display_rect);
+                    } else {
+                        let image_size = if self.rotation % 180 == 90 {
+                            egui::Vec2::new(display_size.y, display_size.x)
+                        } else {
+                            display_size
+                        };
+                        image
+                            .rotate((self.rotation as f32).to_radians(), egui::Vec2::splat(0.5))
+                            .paint_at(ui, Rect::from_center_size(display_rect.center(), image_size));
+                    }
+                });
+            }
 
             // 调试信息 (可选)
             // if ui.ctx().debug_on_hover() {
             //     ui.allocate_ui_at_rect(
             //         Rect::from_min_size(rect.left_top() + egui::Vec2::new(10.0, 10.0), egui::Vec2::new(200.0, 60.0)),
             //         |ui| {
-            //             ui.label(format!("视频: {}x{}", video_texture.width, video_texture.height));
+            //             ui.label(format!("视频: {}x{}", key.width, key.height));
             //             ui.label(format!("PTS: {}ms", video_texture.last_pts));
             //             ui.label(format!("渲染: {} 帧", self.stats.frames_rendered));
             //         }
@@ -246,21 +435,89 @@ impl EguiVideoRenderer {
         Ok(())
     }
 
-    /// 获取渲染统计信息
+    /// 获取渲染统计信息（调试用）
+    #[allow(dead_code)]
     pub fn get_stats(&self) -> &RenderStats {
         &self.stats
     }
 
-    /// 检查是否有纹理（用于判断是否应该显示占位符）
+    /// 设置画面几何信息（像素宽高比与旋转角度）；正在显示其他文件的画面时，等下一帧到达后才生效
+    pub fn set_display_geometry(&mut self, pixel_aspect: f64, rotation: u32) {
+        let rotation = rotation % 360;
+        if self.last_frame.is_none() {
+            self.pixel_aspect = pixel_aspect;
+            self.rotation = rotation;
+            self.pending_geometry = None;
+        } else if (pixel_aspect, rotation) != (self.pixel_aspect, self.rotation) {
+            self.pending_geometry = Some((pixel_aspect, rotation));
+        } else {
+            self.pending_geometry = None;
+        }
+    }
+
+    /// 设置缩放模式（模式改变时清除手动缩放和平移）
+    pub fn set_fit_mode(&mut self, mode: FitMode) {
+        self.view.set_mode(mode);
+    }
+
+    /// 手动缩放倍数
+    pub fn zoom(&self) -> f32 {
+        self.view.zoom()
+    }
+
+    /// 是否有手动缩放或平移
+    pub fn is_zoomed(&self) -> bool {
+        self.view.is_adjusted()
+    }
+
+    /// 清除手动缩放和平移
+    pub fn reset_zoom(&mut self) {
+        self.view.reset_zoom();
+    }
+
+    /// 以 anchor 为中心缩放（container 为视频区域；没有画面时忽略）
+    pub fn zoom_at(&mut self, factor: f32, anchor: egui::Pos2, container: Rect) {
+        if let Some(size) = self.current_display_size() {
+            self.view.zoom_at(factor, anchor, size, container, self.pixels_per_point);
+        }
+    }
+
+    /// 拖动平移画面（container 为视频区域；没有画面时忽略）
+    pub fn pan_by(&mut self, delta: egui::Vec2, container: Rect) {
+        if let Some(size) = self.current_display_size() {
+            self.view.pan_by(delta, size, container, self.pixels_per_point);
+        }
+    }
+
+    fn current_display_size(&self) -> Option<(f64, f64)> {
+        let frame = self.last_frame.as_ref()?;
+        Some(display_size(frame.width, frame.height, self.pixel_aspect, self.rotation))
+    }
+
+    /// 当前显示的帧（RGBA，YUV 帧在 CPU 上转换）及显示时顺时针旋转的角度（截图使用）
+    pub fn displayed_frame(&self) -> Option<(VideoFrame, u32)> {
+        self.last_frame.as_ref().map(|frame| (frame.to_rgba(), self.rotation))
+    }
+
+    /// 当前帧在区域内的显示位置
+    pub fn displayed_rect(&self, container: Rect) -> Option<Rect> {
+        let frame = self.last_frame.as_ref()?;
+        Some(self.fitted_rect(frame.width, frame.height, container))
+    }
+
+    /// 检查是否有可显示的画面（用于判断是否应该显示占位符；纹理丢失但保留了帧数据时仍为 true）
     pub fn has_texture(&self) -> bool {
-        self.video_texture.is_some()
+        !self.video_texture.is_empty() || self.last_frame.is_some()
     }
 
     /// 清理资源
     pub fn cleanup(&mut self) {
         info!("🧹 清理 EguiVideoRenderer 资源");
-        self.video_texture = None;
+        self.release_video_texture();
         self.texture_cache.clear();
+        self.last_frame = None;
+        self.luma_mip = None;
+        self.pending_geometry = None;
     }
 }
 
@@ -286,19 +543,34 @@ impl EguiVideoRenderer {
         debug!("🚀 零拷贝纹理更新 (未实现)");
         
         // 当前回退到常规更新
-        self.update_texture_data(ctx, frame)
+        self.update_video_texture(ctx, frame)
     }
+}
 
-    /// 纹理池管理 (内存优化)
-    /// 
-    /// 重用纹理对象以减少分配开销
-    #[allow(dead_code)]
-    fn get_pooled_texture(&mut self, _width: u32, _height: u32) -> Result<&mut VideoTexture> {
-        // TODO: 实现纹理池
-        // 1. 维护不同尺寸的纹理池
-        // 2. 重用相同尺寸的纹理
-        // 3. 定期清理未使用的纹理
+/// 把 RGBA 帧写入已有纹理
+fn write_rgba(queue: &Queue, texture: &Texture, frame: &VideoFrame) -> Result<()> {
+    let row_size = 4 * frame.width; // RGBA = 4 bytes per pixel
+    let size = row_size as usize * frame.height as usize;
+    let data = frame.data.get(..size).ok_or_else(|| anyhow!("RGBA 帧数据不足: {} < {}", frame.data.len(), size))?;
+    queue.write_texture(
+        ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: eframe::wgpu::TextureAspect::All,
+        },
+        data,
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(row_size),
+            rows_per_image: Some(frame.height),
+        },
+        texture.size(),
+    );
+    Ok(())
+}
 
-        todo!("纹理池未实现")
-    }
+/// 把 RGBA 帧转换为 egui ColorImage（兼容模式）
+fn color_image(frame: &VideoFrame) -> ColorImage {
+    ColorImage::from_rgba_unmultiplied([frame.width as usize, frame.height as usize], &frame.data)
 }
@@ -0,0 +1,132 @@
+// GPU 设备丢失恢复策略
+//
+// 出现 GPU 错误（驱动重置、切换显卡等）后释放缓存的 GPU 资源，下一帧用保留的 CPU 帧重建；
+// 连续多次重建失败后切换到兼容模式（不再直接调用 wgpu，只通过 egui 纹理管理器上传画面）。
+
+/// 连续重建失败多少次后切换到兼容模式
+pub const MAX_REBUILD_ATTEMPTS: u32 = 3;
+
+/// 渲染模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderMode {
+    /// 直接使用 wgpu 创建和上传纹理
+    Gpu,
+    /// 兼容模式：只通过 egui 纹理管理器上传 CPU 帧
+    Fallback,
+}
+
+/// 设备丢失恢复状态
+#[derive(Debug)]
+pub struct GpuRecovery {
+    mode: RenderMode,
+    pending_rebuild: bool,  // GPU 资源已释放，等待重建
+    failed_attempts: u32,   // 连续重建失败次数
+}
+
+impl Default for GpuRecovery {
+    fn default() -> Self {
+        Self {
+            mode: RenderMode::Gpu,
+            pending_rebuild: false,
+            failed_attempts: 0,
+        }
+    }
+}
+
+impl GpuRecovery {
+    /// 创建恢复状态（初始为 GPU 模式）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 当前渲染模式
+    pub fn mode(&self) -> RenderMode {
+        self.mode
+    }
+
+    /// 是否需要重建 GPU 资源
+    pub fn needs_rebuild(&self) -> bool {
+        self.pending_rebuild
+    }
+
+    /// 记录一次 GPU 错误（返回 true 表示刚切换到兼容模式）
+    pub fn on_error(&mut self) -> bool {
+        if self.pending_rebuild {
+            // 重建本身失败
+            self.failed_attempts += 1;
+        }
+        self.pending_rebuild = true;
+
+        if self.mode == RenderMode::Gpu && self.failed_attempts >= MAX_REBUILD_ATTEMPTS {
+            self.mode = RenderMode::Fallback;
+            self.failed_attempts = 0;
+            return true;
+        }
+        false
+    }
+
+    /// 记录一次成功的上传（重建完成）
+    pub fn on_success(&mut self) {
+        self.pending_rebuild = false;
+        self.failed_attempts = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_successful_rebuild_stays_on_gpu() {
+        let mut recovery = GpuRecovery::new();
+        assert!(!recovery.on_error());
+        assert!(recovery.needs_rebuild());
+
+        recovery.on_success();
+        assert!(!recovery.needs_rebuild());
+        assert_eq!(recovery.mode(), RenderMode::Gpu);
+    }
+
+    #[test]
+    fn test_repeated_rebuild_failures_switch_to_fallback() {
+        let mut recovery = GpuRecovery::new();
+        assert!(!recovery.on_error());  // 设备丢失
+        for _ in 1..MAX_REBUILD_ATTEMPTS {
+            assert!(!recovery.on_error());  // 重建失败
+        }
+        assert!(recovery.on_error());
+        assert_eq!(recovery.mode(), RenderMode::Fallback);
+
+        // 兼容模式下仍然需要重建一次（通过 egui 纹理）
+        assert!(recovery.needs_rebuild());
+        recovery.on_success();
+        assert!(!recovery.needs_rebuild());
+        assert_eq!(recovery.mode(), RenderMode::Fallback);
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let mut recovery = GpuRecovery::new();
+        for _ in 0..MAX_REBUILD_ATTEMPTS {
+            recovery.on_error();
+        }
+        recovery.on_success();
+
+        // 之后的单次丢失不会立即切换
+        assert!(!recovery.on_error());
+        assert!(!recovery.on_error());
+        assert_eq!(recovery.mode(), RenderMode::Gpu);
+    }
+
+    #[test]
+    fn test_fallback_never_switches_again() {
+        let mut recovery = GpuRecovery::new();
+        for _ in 0..=MAX_REBUILD_ATTEMPTS {
+            recovery.on_error();
+        }
+        assert_eq!(recovery.mode(), RenderMode::Fallback);
+        for _ in 0..=MAX_REBUILD_ATTEMPTS * 2 {
+            assert!(!recovery.on_error());
+        }
+    }
+}
@@ -0,0 +1,135 @@
+// 亮度缩略图（32x18 的平均亮度网格，用于估计画面局部亮度，如字幕背景自适应）
+//
+// 每个格子只取固定数量的采样点，开销与画面分辨率无关，只在帧变化时更新。
+
+/// 网格尺寸
+pub const MIP_WIDTH: usize = 32;
+pub const MIP_HEIGHT: usize = 18;
+
+/// 每个格子每个方向的采样点数
+const SAMPLES_PER_CELL: usize = 2;
+
+/// 亮度网格（0.0 - 1.0，按帧的存储方向排列，未旋转）
+#[derive(Debug, Clone, PartialEq)]
+pub struct LumaMip {
+    cells: Vec<f32>,
+}
+
+impl LumaMip {
+    /// 从 RGBA 帧数据采样（数据不足时返回 None）
+    pub fn from_rgba(width: u32, height: u32, data: &[u8]) -> Option<Self> {
+        let (width, height) = (width as usize, height as usize);
+        if width == 0 || height == 0 || data.len() < width * height * 4 {
+            return None;
+        }
+        Some(Self::sample(width, height, |x, y| {
+            let i = (y * width + x) * 4;
+            luma(data[i], data[i + 1], data[i + 2])
+        }))
+    }
+
+    /// 从 YUV 帧的亮度平面采样（有限范围的亮度值先拉伸到 0-255；数据不足时返回 None）
+    pub fn from_luma_plane(width: u32, height: u32, plane: &[u8], stride: usize, full_range: bool) -> Option<Self> {
+        let (width, height) = (width as usize, height as usize);
+        if width == 0 || height == 0 || stride < width || plane.len() < stride * (height - 1) + width {
+            return None;
+        }
+        Some(Self::sample(width, height, |x, y| {
+            let value = plane[y * stride + x] as f32;
+            if full_range {
+                value / 255.0
+            } else {
+                ((value - 16.0) / 219.0).clamp(0.0, 1.0)
+            }
+        }))
+    }
+
+    /// 按网格均匀采样（sample 返回帧坐标处的亮度）
+    fn sample(width: usize, height: usize, sample: impl Fn(usize, usize) -> f32) -> Self {
+        let mut cells = Vec::with_capacity(MIP_WIDTH * MIP_HEIGHT);
+        for cy in 0..MIP_HEIGHT {
+            for cx in 0..MIP_WIDTH {
+                let mut sum = 0.0;
+                for sy in 0..SAMPLES_PER_CELL {
+                    for sx in 0..SAMPLES_PER_CELL {
+                        // 在格子内均匀分布的采样点
+                        let x = ((cx * SAMPLES_PER_CELL + sx) * 2 + 1) * width / (MIP_WIDTH * SAMPLES_PER_CELL * 2);
+                        let y = ((cy * SAMPLES_PER_CELL + sy) * 2 + 1) * height / (MIP_HEIGHT * SAMPLES_PER_CELL * 2);
+                        sum += sample(x, y);
+                    }
+                }
+                cells.push(sum / (SAMPLES_PER_CELL * SAMPLES_PER_CELL) as f32);
+            }
+        }
+        Self { cells }
+    }
+
+    /// 归一化区域（0.0 - 1.0，帧坐标）内的平均亮度；区域与画面不相交时返回 None
+    pub fn region_mean(&self, left: f32, top: f32, right: f32, bottom: f32) -> Option<f32> {
+        let to_cell = |v: f32, cells: usize| ((v.clamp(0.0, 1.0) * cells as f32) as usize).min(cells);
+        let (x0, x1) = (to_cell(left, MIP_WIDTH), to_cell(right, MIP_WIDTH));
+        let (y0, y1) = (to_cell(top, MIP_HEIGHT), to_cell(bottom, MIP_HEIGHT));
+        // 区域小于一个格子时取所在的格子
+        let x1 = if x1 == x0 && x0 < MIP_WIDTH { x0 + 1 } else { x1 };
+        let y1 = if y1 == y0 && y0 < MIP_HEIGHT { y0 + 1 } else { y1 };
+        if x1 <= x0 || y1 <= y0 {
+            return None;
+        }
+
+        let sum: f32 = (y0..y1)
+            .flat_map(|y| self.cells[y * MIP_WIDTH + x0..y * MIP_WIDTH + x1].iter())
+            .sum();
+        Some(sum / ((x1 - x0) * (y1 - y0)) as f32)
+    }
+}
+
+/// 感知亮度（BT.709 系数，sRGB 值直接加权）
+fn luma(r: u8, g: u8, b: u8) -> f32 {
+    (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 上半部分黑、下半部分白的画面
+    fn split_frame(width: u32, height: u32) -> Vec<u8> {
+        let mut data = vec![0u8; (width * height * 4) as usize];
+        for y in height / 2..height {
+            let row = (y * width * 4) as usize;
+            data[row..row + (width * 4) as usize].fill(255);
+        }
+        data
+    }
+
+    #[test]
+    fn test_region_mean_follows_frame_content() {
+        let mip = LumaMip::from_rgba(1920, 1080, &split_frame(1920, 1080)).unwrap();
+        assert!(mip.region_mean(0.0, 0.0, 1.0, 0.5).unwrap() < 0.01);
+        assert!(mip.region_mean(0.1, 0.8, 0.9, 0.95).unwrap() > 0.99);
+        let whole = mip.region_mean(0.0, 0.0, 1.0, 1.0).unwrap();
+        assert!((whole - 0.5).abs() < 0.01, "{}", whole);
+    }
+
+    #[test]
+    fn test_tiny_and_outside_regions() {
+        let mip = LumaMip::from_rgba(64, 36, &split_frame(64, 36)).unwrap();
+        assert!(mip.region_mean(0.5, 0.9, 0.5, 0.9).unwrap() > 0.99);
+        assert_eq!(mip.region_mean(1.2, 0.0, 1.5, 1.0), None);
+        assert!(LumaMip::from_rgba(64, 36, &[0; 16]).is_none());
+    }
+
+    #[test]
+    fn test_luma_plane_matches_rgba() {
+        // 有限范围的亮度平面（16 = 黑，235 = 白），行尾带填充
+        let (width, height, stride) = (64, 36, 80);
+        let mut plane = vec![16u8; stride * height];
+        for row in plane.chunks_exact_mut(stride).skip(height / 2) {
+            row.fill(235);
+        }
+        let from_plane = LumaMip::from_luma_plane(width as u32, height as u32, &plane, stride, false).unwrap();
+        let from_rgba = LumaMip::from_rgba(64, 36, &split_frame(64, 36)).unwrap();
+        assert!(from_plane.cells.iter().zip(&from_rgba.cells).all(|(a, b)| (a - b).abs() < 1e-4));
+        assert!(LumaMip::from_luma_plane(64, 36, &plane[..stride], stride, false).is_none());
+    }
+}
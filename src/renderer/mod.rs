@@ -1,5 +1,10 @@
 pub mod egui_video_renderer;
-pub mod shader;
+pub(crate) mod gpu_recovery;
+pub(crate) mod luma_mip;  // 亮度缩略图（字幕背景自适应）
+pub(crate) mod shader;
+pub(crate) mod texture_cache;  // 按尺寸与格式复用视频纹理
+pub mod video_view;  // 画面缩放模式、缩放与平移
+pub(crate) mod yuv_converter;  // GPU YUV -> RGB 转换
 
 // pub use egui_video_renderer::EguiVideoRenderer;
 
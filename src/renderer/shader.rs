@@ -1,42 +1,64 @@
-/// YUV 到 RGB 转换的 Shader
+/// YUV 到 RGB 转换的 Shader（YUV420P / NV12 平面纹理 -> sRGB 纹理，全屏三角形）
+///
+/// 系数与色度坐标变换由 core::YuvPlanes 计算：rgb = 系数矩阵 * (Y, U, V, 1)，
+/// 色度坐标 = 纹理坐标 * chroma.xy + chroma.zw。输出纹理为 sRGB 格式，写入前先转为线性值。
 pub const YUV_TO_RGB_SHADER: &str = r#"
-// Vertex shader
-struct VertexInput {
-    @location(0) position: vec2<f32>,
-    @location(1) tex_coords: vec2<f32>,
-}
-
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
 }
 
+// 覆盖整个输出纹理的三角形（不需要顶点缓冲）
 @vertex
-fn vs_main(in: VertexInput) -> VertexOutput {
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
     var out: VertexOutput;
-    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
-    out.tex_coords = in.tex_coords;
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.tex_coords = uv;
     return out;
 }
 
-// Fragment shader
+struct Params {
+    r: vec4<f32>,
+    g: vec4<f32>,
+    b: vec4<f32>,
+    chroma: vec4<f32>,
+}
+
 @group(0) @binding(0) var y_texture: texture_2d<f32>;
-@group(0) @binding(1) var u_texture: texture_2d<f32>;
+@group(0) @binding(1) var u_texture: texture_2d<f32>;  // NV12 时为交错的 UV 平面
 @group(0) @binding(2) var v_texture: texture_2d<f32>;
 @group(0) @binding(3) var texture_sampler: sampler;
+@group(0) @binding(4) var<uniform> params: Params;
+
+fn linear_from_srgb(value: vec3<f32>) -> vec3<f32> {
+    let cutoff = value < vec3<f32>(0.04045);
+    let lower = value / vec3<f32>(12.92);
+    let higher = pow((value + vec3<f32>(0.055)) / vec3<f32>(1.055), vec3<f32>(2.4));
+    return select(higher, lower, cutoff);
+}
+
+fn to_rgba(y: f32, u: f32, v: f32) -> vec4<f32> {
+    let yuv = vec4<f32>(y, u, v, 1.0);
+    let rgb = clamp(vec3<f32>(dot(params.r, yuv), dot(params.g, yuv), dot(params.b, yuv)), vec3<f32>(0.0), vec3<f32>(1.0));
+    return vec4<f32>(linear_from_srgb(rgb), 1.0);
+}
 
 @fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+fn fs_planar(in: VertexOutput) -> @location(0) vec4<f32> {
+    let chroma_coords = in.tex_coords * params.chroma.xy + params.chroma.zw;
+    let y = textureSample(y_texture, texture_sampler, in.tex_coords).r;
+    let u = textureSample(u_texture, texture_sampler, chroma_coords).r;
+    let v = textureSample(v_texture, texture_sampler, chroma_coords).r;
+    return to_rgba(y, u, v);
+}
+
+@fragment
+fn fs_nv12(in: VertexOutput) -> @location(0) vec4<f32> {
+    let chroma_coords = in.tex_coords * params.chroma.xy + params.chroma.zw;
     let y = textureSample(y_texture, texture_sampler, in.tex_coords).r;
-    let u = textureSample(u_texture, texture_sampler, in.tex_coords).r - 0.5;
-    let v = textureSample(v_texture, texture_sampler, in.tex_coords).r - 0.5;
-    
-    // BT.709 YUV to RGB conversion
-    let r = y + 1.5748 * v;
-    let g = y - 0.1873 * u - 0.4681 * v;
-    let b = y + 1.8556 * u;
-    
-    return vec4<f32>(r, g, b, 1.0);
+    let uv = textureSample(u_texture, texture_sampler, chroma_coords).rg;
+    return to_rgba(y, uv.x, uv.y);
 }
 "#;
 
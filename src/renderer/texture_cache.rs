@@ -0,0 +1,117 @@
+// 按 (宽, 高, 像素格式) 缓存的视频纹理
+//
+// 稳定播放时每帧只用 queue.write_texture 更新已有纹理；尺寸或格式变化（切换文件、自适应码率切换分辨率）时
+// 才替换为新纹理，旧纹理交还给调用方释放。allocations 记录创建次数，用于确认稳定播放时没有逐帧分配。
+
+use myy_player::{PixelFormat, VideoFrame};
+
+/// 纹理的缓存键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureKey {
+    pub width: u32,
+    pub height: u32,
+    pub format: PixelFormat,
+}
+
+impl TextureKey {
+    pub fn of(frame: &VideoFrame) -> Self {
+        Self { width: frame.width, height: frame.height, format: frame.format }
+    }
+}
+
+/// 单个视频纹理的缓存
+pub(crate) struct TextureCache<T> {
+    entry: Option<(TextureKey, T)>,
+    allocations: u64,
+}
+
+impl<T> Default for TextureCache<T> {
+    fn default() -> Self {
+        Self { entry: None, allocations: 0 }
+    }
+}
+
+impl<T> TextureCache<T> {
+    /// 与 key 匹配的纹理（尺寸或格式不同时为 None，需要重新创建）
+    pub fn get_mut(&mut self, key: TextureKey) -> Option<&mut T> {
+        self.entry.as_mut().filter(|(cached, _)| *cached == key).map(|(_, texture)| texture)
+    }
+
+    /// 当前的纹理（不论键）
+    pub fn current(&self) -> Option<(TextureKey, &T)> {
+        self.entry.as_ref().map(|(key, texture)| (*key, texture))
+    }
+
+    /// 放入新创建的纹理，返回被替换的旧纹理
+    pub fn insert(&mut self, key: TextureKey, texture: T) -> Option<T> {
+        self.allocations += 1;
+        self.entry.replace((key, texture)).map(|(_, old)| old)
+    }
+
+    /// 取出当前纹理（失效：下一帧重新创建）
+    pub fn take(&mut self) -> Option<T> {
+        self.entry.take().map(|(_, texture)| texture)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entry.is_none()
+    }
+
+    /// 累计创建的纹理数
+    #[cfg(test)]
+    pub fn allocations(&self) -> u64 {
+        self.allocations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(width: u32, height: u32) -> TextureKey {
+        TextureKey { width, height, format: PixelFormat::YUV420P }
+    }
+
+    /// 模拟逐帧上传：纹理不匹配时创建，返回本帧是否创建了纹理
+    fn present(cache: &mut TextureCache<u32>, key: TextureKey, frame: u32) -> bool {
+        if let Some(texture) = cache.get_mut(key) {
+            *texture = frame;
+            return false;
+        }
+        cache.insert(key, frame);
+        true
+    }
+
+    #[test]
+    fn test_steady_playback_allocates_once() {
+        let mut cache = TextureCache::default();
+        let allocated: Vec<bool> = (0..600).map(|frame| present(&mut cache, key(1920, 1080), frame)).collect();
+        assert!(allocated[0]);
+        assert!(allocated[1..].iter().all(|allocated| !allocated));
+        assert_eq!(cache.allocations(), 1);
+        assert_eq!(cache.current(), Some((key(1920, 1080), &599)));
+    }
+
+    #[test]
+    fn test_dimension_or_format_change_recreates() {
+        let mut cache = TextureCache::default();
+        for frame in 0..10 {
+            present(&mut cache, key(1920, 1080), frame);
+        }
+        // 自适应码率切换到 720p，旧纹理交还给调用方释放
+        assert!(cache.get_mut(key(1280, 720)).is_none());
+        assert_eq!(cache.insert(key(1280, 720), 10), Some(9));
+        for frame in 11..20 {
+            assert!(!present(&mut cache, key(1280, 720), frame));
+        }
+        let nv12 = TextureKey { format: PixelFormat::NV12, ..key(1280, 720) };
+        assert!(present(&mut cache, nv12, 20));
+        assert_eq!(cache.allocations(), 3);
+
+        // 失效后下一帧重新创建
+        assert_eq!(cache.take(), Some(20));
+        assert!(cache.is_empty());
+        assert!(present(&mut cache, nv12, 21));
+        assert_eq!(cache.allocations(), 4);
+    }
+}
@@ -0,0 +1,200 @@
+// 画面缩放模式（适应 / 填充 / 拉伸 / 原始大小）与手动缩放、平移
+//
+// 先按模式计算画面的基础尺寸（输入为已考虑像素宽高比与旋转的显示尺寸），再乘以缩放倍数，最后加上平移偏移。
+// 画面大于显示区域时，平移限制在画面边缘不进入区域内的范围（不会拖出空白或把画面拖出屏幕）；小于区域时总是居中。
+// 超出区域的部分由渲染器裁剪。
+
+use egui::{Pos2, Rect, Vec2};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// 手动缩放倍数的范围
+pub const ZOOM_RANGE: RangeInclusive<f32> = 0.25..=8.0;
+
+/// 画面缩放模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FitMode {
+    /// 完整显示，保持宽高比（留黑边）
+    #[default]
+    Fit,
+    /// 填满区域，保持宽高比（裁掉超出部分）
+    Fill,
+    /// 填满区域，忽略宽高比
+    Stretch,
+    /// 一个视频像素对应一个屏幕像素
+    Original,
+}
+
+impl FitMode {
+    pub const ALL: [FitMode; 4] = [FitMode::Fit, FitMode::Fill, FitMode::Stretch, FitMode::Original];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FitMode::Fit => "适应窗口",
+            FitMode::Fill => "填满窗口",
+            FitMode::Stretch => "拉伸",
+            FitMode::Original => "原始大小",
+        }
+    }
+
+    /// 循环切换的下一个模式
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|mode| *mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// 画面的缩放模式、缩放倍数与平移
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoView {
+    mode: FitMode,
+    zoom: f32,
+    pan: Vec2,  // 画面中心相对区域中心的偏移（逻辑像素）
+}
+
+impl Default for VideoView {
+    fn default() -> Self {
+        Self { mode: FitMode::Fit, zoom: 1.0, pan: Vec2::ZERO }
+    }
+}
+
+impl VideoView {
+    #[cfg(test)]
+    pub fn mode(&self) -> FitMode {
+        self.mode
+    }
+
+    /// 切换模式（清除手动缩放和平移）
+    pub fn set_mode(&mut self, mode: FitMode) {
+        if mode != self.mode {
+            self.mode = mode;
+            self.reset_zoom();
+        }
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// 是否有手动缩放或平移
+    pub fn is_adjusted(&self) -> bool {
+        self.zoom != 1.0 || self.pan != Vec2::ZERO
+    }
+
+    /// 清除手动缩放和平移
+    pub fn reset_zoom(&mut self) {
+        self.zoom = 1.0;
+        self.pan = Vec2::ZERO;
+    }
+
+    /// 画面在区域中的位置（可能超出区域）；display_size 为显示尺寸（视频像素），
+    /// pixels_per_point 用于原始大小模式换算为逻辑像素
+    pub fn layout(&self, display_size: (f64, f64), container: Rect, pixels_per_point: f32) -> Rect {
+        let size = self.base_size(display_size, container, pixels_per_point) * self.zoom;
+        Rect::from_center_size(container.center() + clamp_pan(self.pan, size, container.size()), size)
+    }
+
+    /// 以 anchor（屏幕坐标）为中心缩放 factor 倍，光标下的画面位置保持不变
+    pub fn zoom_at(&mut self, factor: f32, anchor: Pos2, display_size: (f64, f64), container: Rect, pixels_per_point: f32) {
+        let before = self.layout(display_size, container, pixels_per_point);
+        let zoom = (self.zoom * factor).clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end());
+        let scale = zoom / self.zoom;
+        self.zoom = zoom;
+        let center = anchor - (anchor - before.center()) * scale;
+        let size = before.size() * scale;
+        self.pan = clamp_pan(center - container.center(), size, container.size());
+    }
+
+    /// 平移画面（拖动）
+    pub fn pan_by(&mut self, delta: Vec2, display_size: (f64, f64), container: Rect, pixels_per_point: f32) {
+        let size = self.layout(display_size, container, pixels_per_point).size();
+        self.pan = clamp_pan(self.pan + delta, size, container.size());
+    }
+
+    /// 缩放模式决定的画面尺寸（未乘以手动缩放倍数）
+    fn base_size(&self, (width, height): (f64, f64), container: Rect, pixels_per_point: f32) -> Vec2 {
+        let (width, height) = (width as f32, height as f32);
+        if !(width > 0.0 && height > 0.0) {
+            return container.size();
+        }
+        match self.mode {
+            FitMode::Fit => Vec2::new(width, height) * (container.width() / width).min(container.height() / height),
+            FitMode::Fill => Vec2::new(width, height) * (container.width() / width).max(container.height() / height),
+            FitMode::Stretch => container.size(),
+            FitMode::Original => Vec2::new(width, height) / pixels_per_point.max(f32::EPSILON),
+        }
+    }
+}
+
+/// 计算画面显示尺寸：宽度按像素宽高比拉伸，旋转 90/270 度时交换宽高
+pub fn display_size(width: u32, height: u32, pixel_aspect: f64, rotation: u32) -> (f64, f64) {
+    let pixel_aspect = if pixel_aspect.is_finite() && pixel_aspect > 0.0 { pixel_aspect } else { 1.0 };
+    let (w, h) = (width as f64 * pixel_aspect, height as f64);
+    if rotation % 180 == 90 { (h, w) } else { (w, h) }
+}
+
+/// 限制平移：画面大于区域时边缘不进入区域内，小于区域时居中
+fn clamp_pan(pan: Vec2, size: Vec2, container: Vec2) -> Vec2 {
+    let limit = ((size - container) * 0.5).max(Vec2::ZERO);
+    pan.clamp(-limit, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTAINER: Rect = Rect { min: Pos2::new(0.0, 0.0), max: Pos2::new(1600.0, 900.0) };
+
+    #[test]
+    fn test_modes_size_the_picture() {
+        let mut view = VideoView::default();
+        // 4:3 画面放入 16:9 区域
+        let picture = (1440.0, 1080.0);
+        assert_eq!(view.layout(picture, CONTAINER, 1.0), Rect::from_center_size(CONTAINER.center(), Vec2::new(1200.0, 900.0)));
+        view.set_mode(FitMode::Fill);
+        assert_eq!(view.layout(picture, CONTAINER, 1.0).size(), Vec2::new(1600.0, 1200.0));
+        view.set_mode(FitMode::Stretch);
+        assert_eq!(view.layout(picture, CONTAINER, 1.0), CONTAINER);
+        view.set_mode(FitMode::Original);
+        assert_eq!(view.layout(picture, CONTAINER, 2.0).size(), Vec2::new(720.0, 540.0));
+        assert_eq!(view.mode().next(), FitMode::Fit);
+    }
+
+    #[test]
+    fn test_non_square_pixels_widen_the_picture() {
+        // PAL 4:3 采集（720x576，SAR 16:11）按 20:11 显示，而不是存储的 5:4
+        let picture = display_size(720, 576, 16.0 / 11.0, 0);
+        let rect = VideoView::default().layout(picture, CONTAINER, 1.0);
+        assert!((rect.width() - 1600.0).abs() < 0.01 && (rect.height() - 880.0).abs() < 0.01, "{:?}", rect);
+        assert!((rect.center() - CONTAINER.center()).length() < 0.01);
+
+        let mut view = VideoView::default();
+        view.set_mode(FitMode::Original);
+        let size = view.layout(picture, CONTAINER, 1.0).size();
+        assert!((size.x - 1047.27).abs() < 0.01 && size.y == 576.0, "{:?}", size);
+    }
+
+    #[test]
+    fn test_zoom_keeps_anchor_and_pan_is_clamped() {
+        let mut view = VideoView::default();
+        let picture = (1600.0, 900.0);
+        let anchor = Pos2::new(400.0, 225.0);
+        view.zoom_at(2.0, anchor, picture, CONTAINER, 1.0);
+        let rect = view.layout(picture, CONTAINER, 1.0);
+        assert_eq!(rect.size(), Vec2::new(3200.0, 1800.0));
+        // 光标下仍是画面左上四分之一处
+        assert_eq!(rect.min + (rect.size() * 0.25), anchor);
+
+        // 拖到尽头：画面边缘停在区域边缘
+        view.pan_by(Vec2::new(10_000.0, -10_000.0), picture, CONTAINER, 1.0);
+        let rect = view.layout(picture, CONTAINER, 1.0);
+        assert_eq!((rect.left(), rect.bottom()), (0.0, 900.0));
+
+        // 缩小到比区域小时居中
+        view.zoom_at(0.25, anchor, picture, CONTAINER, 1.0);
+        assert_eq!(view.layout(picture, CONTAINER, 1.0).center(), CONTAINER.center());
+        assert_eq!(view.zoom(), 0.5);
+        view.set_mode(FitMode::Fill);
+        assert!(!view.is_adjusted());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::core::{Result, VideoFrame};
+use myy_player::{Result, VideoFrame};
 use log::info;
 use std::sync::Arc;
 use wgpu::util::DeviceExt;
@@ -0,0 +1,384 @@
+// 单实例（双击文件时把文件转发给已运行的播放器，而不是再启动一个进程和一套 FFmpeg 管线）
+//
+// 第一个实例监听本机回环地址的随机端口，以独占创建（create_new）的方式写入用户数据目录的 instance.lock。
+// 之后启动的实例读取端口并连接，发送握手行和位置参数（每行一个，本地路径已转为绝对路径），收到确认后退出。
+// 多个实例同时启动时只有一个能创建锁文件，其余的等它写入端口后转发给它。
+// 锁文件里的端口连接不上（进程崩溃留下的）或握手不匹配（端口被其他程序占用）时，删除锁文件后重新抢占。
+// 主实例对每个连接限制总时长和消息大小，异常的连接不会长期占住监听线程。
+
+use crossbeam_channel::Receiver;
+use log::{info, warn};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::app::{user_data_dir, write_atomic};
+
+/// 握手行（区分占用同一端口的其他程序）
+const HANDSHAKE: &str = "MYY_PLAYER_OPEN 1";
+
+/// 主实例的确认回复
+const ACK: &str = "OK";
+
+/// 连接和读写超时（主实例卡住时不让新启动的进程一直等待）
+const IO_TIMEOUT: Duration = Duration::from_millis(1_000);
+
+/// 主实例处理一个连接的总时限（逐字节慢速发送也不能超过）
+const CONNECTION_DEADLINE: Duration = Duration::from_millis(2_000);
+
+/// 一次转发的最大字节数和最多参数行数（超出时拒绝，不回复确认）
+const MAX_MESSAGE_BYTES: u64 = 64 * 1024;
+const MAX_ARGS: usize = 16;
+
+/// 抢占锁文件的重试间隔和次数（等待抢到锁文件的实例写入端口）
+const CLAIM_RETRY_INTERVAL: Duration = Duration::from_millis(25);
+const CLAIM_ATTEMPTS: usize = 80;
+
+/// 锁文件内容一直无法解析多久后当作崩溃留下的（创建后、写入端口前崩溃）
+const UNREADABLE_GRACE: Duration = Duration::from_millis(500);
+
+/// 默认锁文件位置（用户数据目录）
+pub fn default_lock_file() -> PathBuf {
+    user_data_dir().join("instance.lock")
+}
+
+/// 启动时抢占单实例的结果
+pub enum Claim {
+    /// 已转发给运行中的实例（当前进程应退出）
+    Forwarded,
+    /// 当前进程成为主实例
+    Primary(InstanceServer),
+}
+
+/// 成为主实例，或把位置参数转发给已运行的实例
+///
+/// args 为 None（路径无法转发）时不转发，直接覆盖锁文件成为主实例。
+pub fn claim(lock_file: PathBuf, args: Option<&[String]>) -> std::io::Result<Claim> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    let Some(args) = args else {
+        write_atomic(&lock_file, port.to_string().as_bytes())?;
+        return InstanceServer::start(listener, lock_file, port).map(Claim::Primary);
+    };
+    if let Some(dir) = lock_file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut unreadable_since = None;
+    for _ in 0..CLAIM_ATTEMPTS {
+        match OpenOptions::new().write(true).create_new(true).open(&lock_file) {
+            Ok(mut file) => {
+                file.write_all(port.to_string().as_bytes())?;
+                file.sync_all()?;
+                return InstanceServer::start(listener, lock_file, port).map(Claim::Primary);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+
+        // 锁文件已存在：抢到锁文件的实例可能还没写入端口
+        let text = fs::read_to_string(&lock_file).unwrap_or_default();
+        let Ok(running_port) = text.trim().parse::<u16>() else {
+            let since = *unreadable_since.get_or_insert_with(Instant::now);
+            if since.elapsed() >= UNREADABLE_GRACE {
+                info!("锁文件内容无效，重新抢占");
+                remove_if_unchanged(&lock_file, &text);
+                unreadable_since = None;
+            }
+            std::thread::sleep(CLAIM_RETRY_INTERVAL);
+            continue;
+        };
+        unreadable_since = None;
+        match send(running_port, args) {
+            Ok(()) => {
+                info!("📨 已转发给运行中的播放器 (端口 {})", running_port);
+                return Ok(Claim::Forwarded);
+            }
+            Err(e) => {
+                // 锁文件是崩溃留下的，或端口已被其他程序使用
+                info!("锁文件中的实例无响应 (端口 {}): {}，重新抢占", running_port, e);
+                remove_if_unchanged(&lock_file, &text);
+            }
+        }
+    }
+    Err(std::io::Error::new(ErrorKind::TimedOut, "抢占锁文件超时"))
+}
+
+/// 删除锁文件（内容已被其他实例改写时保留：对方已抢占成功）
+fn remove_if_unchanged(lock_file: &Path, expected: &str) {
+    if fs::read_to_string(lock_file).is_ok_and(|text| text == expected) {
+        let _ = fs::remove_file(lock_file);
+    }
+}
+
+fn send(port: u16, args: &[String]) -> std::io::Result<()> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&address, IO_TIMEOUT)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut message = format!("{}\n", HANDSHAKE);
+    for arg in args {
+        message.push_str(arg);
+        message.push('\n');
+    }
+    stream.write_all(message.as_bytes())?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut reply = String::new();
+    stream.take(ACK.len() as u64 + 2).read_to_string(&mut reply)?;
+    if reply.trim_end() == ACK {
+        Ok(())
+    } else {
+        Err(std::io::Error::new(ErrorKind::InvalidData, "握手不匹配"))
+    }
+}
+
+/// 主实例的监听端（抢占成功时即开始接受连接，转发先排队，spawn 后交给回调；退出时删除锁文件）
+pub struct InstanceServer {
+    forwarded: Receiver<Vec<String>>,
+    lock_file: PathBuf,
+    port: u16,
+}
+
+impl InstanceServer {
+    /// 启动接受连接的线程（界面创建前到达的转发也会确认，避免对方误判为无响应）
+    fn start(listener: TcpListener, lock_file: PathBuf, port: u16) -> std::io::Result<Self> {
+        let (tx, forwarded) = crossbeam_channel::unbounded();
+        std::thread::Builder::new().name("single-instance".to_string()).spawn(move || {
+            for stream in listener.incoming() {
+                match stream.and_then(receive) {
+                    Ok(Some(args)) => {
+                        if tx.send(args).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("⚠️ 接收转发失败: {}", e),
+                }
+            }
+        })?;
+        info!("🔒 单实例监听端口 {}", port);
+        Ok(Self { forwarded, lock_file, port })
+    }
+
+    /// 启动回调线程，每收到一次转发调用 on_open（参数为转发的位置参数）
+    pub fn spawn(&self, on_open: impl Fn(Vec<String>) + Send + 'static) -> std::io::Result<()> {
+        let forwarded = self.forwarded.clone();
+        std::thread::Builder::new().name("single-instance-open".to_string()).spawn(move || {
+            for args in forwarded {
+                on_open(args);
+            }
+        })?;
+        Ok(())
+    }
+}
+
+impl Drop for InstanceServer {
+    fn drop(&mut self) {
+        // 只删除自己写入的锁文件（其他实例可能已在本实例卡住期间覆盖）
+        if fs::read_to_string(&self.lock_file).is_ok_and(|text| text.trim() == self.port.to_string()) {
+            let _ = fs::remove_file(&self.lock_file);
+        }
+    }
+}
+
+/// 按连接的总时限读取（每次读取前把读超时设为剩余时间）
+struct DeadlineReader {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(std::io::Error::new(ErrorKind::TimedOut, "连接超过总时限"));
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+/// 读取一次转发（握手不匹配时返回 None，不回复确认；超过时限或大小限制时返回错误）
+fn receive(stream: TcpStream) -> std::io::Result<Option<Vec<String>>> {
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let reader = DeadlineReader { stream: stream.try_clone()?, deadline: Instant::now() + CONNECTION_DEADLINE };
+    let mut message = String::new();
+    reader.take(MAX_MESSAGE_BYTES + 1).read_to_string(&mut message)?;
+    if message.len() as u64 > MAX_MESSAGE_BYTES {
+        return Err(std::io::Error::new(ErrorKind::InvalidData, "转发内容过长"));
+    }
+    let mut lines = message.lines();
+    if lines.next() != Some(HANDSHAKE) {
+        return Ok(None);
+    }
+    let args: Vec<String> = lines.map(str::to_string).collect();
+    if args.len() > MAX_ARGS {
+        return Err(std::io::Error::new(ErrorKind::InvalidData, "转发的参数过多"));
+    }
+    (&stream).write_all(format!("{}\n", ACK).as_bytes())?;
+    Ok(Some(args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Barrier};
+
+    fn temp_lock_file(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("myy_instance_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("instance.lock")
+    }
+
+    fn primary(claim: Claim) -> InstanceServer {
+        match claim {
+            Claim::Primary(server) => server,
+            Claim::Forwarded => panic!("应成为主实例"),
+        }
+    }
+
+    #[test]
+    fn test_forwarded_args_reach_running_instance() {
+        let lock_file = temp_lock_file("forward");
+        let server = primary(claim(lock_file.clone(), Some(&[])).unwrap());
+        let (tx, rx) = crossbeam_channel::unbounded();
+        server.spawn(move |args| tx.send(args).unwrap()).unwrap();
+
+        let args = vec!["/videos/a b.mkv".to_string(), "/videos/a b.srt".to_string()];
+        assert!(matches!(claim(lock_file.clone(), Some(&args)).unwrap(), Claim::Forwarded));
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), args);
+        // 不带参数：只唤起已运行的窗口
+        assert!(matches!(claim(lock_file.clone(), Some(&[])).unwrap(), Claim::Forwarded));
+        assert!(rx.recv_timeout(Duration::from_secs(5)).unwrap().is_empty());
+
+        drop(server);
+        assert!(!lock_file.exists());
+        fs::remove_dir_all(lock_file.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_stale_lock_file_is_reclaimed() {
+        let lock_file = temp_lock_file("stale");
+
+        // 崩溃留下的锁文件：端口已无人监听
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap().local_addr().unwrap().port();
+        fs::write(&lock_file, port.to_string()).unwrap();
+        let server = primary(claim(lock_file.clone(), Some(&[])).unwrap());
+        assert_eq!(fs::read_to_string(&lock_file).unwrap(), server.port.to_string());
+        drop(server);
+
+        // 端口被其他程序占用：握手不匹配
+        let other = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        fs::write(&lock_file, other.local_addr().unwrap().port().to_string()).unwrap();
+        std::thread::spawn(move || {
+            let (stream, _) = other.accept().unwrap();
+            (&stream).write_all(b"HTTP/1.1 400 Bad Request\n").unwrap();
+        });
+        let server = primary(claim(lock_file.clone(), Some(&[])).unwrap());
+        assert_eq!(fs::read_to_string(&lock_file).unwrap(), server.port.to_string());
+        drop(server);
+
+        // 创建后、写入端口前崩溃留下的空锁文件
+        fs::write(&lock_file, "").unwrap();
+        let server = primary(claim(lock_file.clone(), Some(&[])).unwrap());
+        assert_eq!(fs::read_to_string(&lock_file).unwrap(), server.port.to_string());
+        drop(server);
+        fs::remove_dir_all(lock_file.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_simultaneous_launches_elect_one_primary() {
+        const LAUNCHES: usize = 6;
+        let lock_file = temp_lock_file("race");
+        let barrier = Arc::new(Barrier::new(LAUNCHES));
+        let launches: Vec<_> = (0..LAUNCHES)
+            .map(|i| {
+                let lock_file = lock_file.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    claim(lock_file, Some(&[format!("/videos/{}.mkv", i)])).unwrap()
+                })
+            })
+            .collect();
+        let mut servers: Vec<InstanceServer> = launches
+            .into_iter()
+            .filter_map(|launch| match launch.join().unwrap() {
+                Claim::Primary(server) => Some(server),
+                Claim::Forwarded => None,
+            })
+            .collect();
+        assert_eq!(servers.len(), 1, "同时启动时只能有一个主实例");
+
+        // 其余实例的参数都转发给了主实例（主实例自己的参数由它自己打开）
+        let server = servers.pop().unwrap();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        server.spawn(move |args| tx.send(args).unwrap()).unwrap();
+        for _ in 0..LAUNCHES - 1 {
+            assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap().len(), 1);
+        }
+        drop(server);
+        fs::remove_dir_all(lock_file.parent().unwrap()).unwrap();
+    }
+
+    /// 连接到主实例，发送原始内容后返回收到的回复
+    fn send_raw(port: u16, message: &[u8]) -> String {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        // 主实例提前拒绝时写入可能失败，只关心回复
+        let _ = stream.write_all(message);
+        let _ = stream.shutdown(std::net::Shutdown::Write);
+        let mut reply = String::new();
+        let _ = stream.read_to_string(&mut reply);
+        reply
+    }
+
+    #[test]
+    fn test_oversized_messages_are_rejected() {
+        let lock_file = temp_lock_file("limits");
+        let server = primary(claim(lock_file.clone(), Some(&[])).unwrap());
+        let (tx, rx) = crossbeam_channel::unbounded();
+        server.spawn(move |args| tx.send(args).unwrap()).unwrap();
+
+        // 参数行数超出限制
+        let too_many = format!("{}\n{}", HANDSHAKE, "a\n".repeat(MAX_ARGS + 1));
+        assert_eq!(send_raw(server.port, too_many.as_bytes()), "");
+        // 单行过长
+        let too_long = format!("{}\n{}\n", HANDSHAKE, "a".repeat(MAX_MESSAGE_BYTES as usize));
+        assert_eq!(send_raw(server.port, too_long.as_bytes()), "");
+        assert!(rx.try_recv().is_err());
+
+        // 限制之内的转发不受影响
+        let ok = format!("{}\n{}", HANDSHAKE, "a\n".repeat(MAX_ARGS));
+        assert_eq!(send_raw(server.port, ok.as_bytes()).trim_end(), ACK);
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap().len(), MAX_ARGS);
+        drop(server);
+        fs::remove_dir_all(lock_file.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_slow_connection_hits_overall_deadline() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // 每次只发一个字节、间隔小于单次读超时，但总时长超过连接时限
+        let sender = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+            for byte in HANDSHAKE.bytes().cycle().take(15) {
+                if stream.write_all(&[byte]).is_err() {
+                    break;
+                }
+                std::thread::sleep(CONNECTION_DEADLINE / 10);
+            }
+        });
+        let (stream, _) = listener.accept().unwrap();
+        let error = receive(stream).unwrap_err();
+        assert!(
+            matches!(error.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock),
+            "应在总时限到达时放弃连接: {:?}",
+            error
+        );
+        sender.join().unwrap();
+    }
+}
@@ -0,0 +1,370 @@
+// 测试素材生成器
+//
+// 视频：每帧为单一颜色（由帧序号决定），底部烧录二进制帧序号条码
+// 音频：1kHz 正弦音，相位 = 2π·1000·t（与时间轴连续对应）
+// 字幕：固定间隔的 SRT 字幕
+
+use crate::core::{PlayerError, Result};
+use ffmpeg_next as ffmpeg;
+use ffmpeg_next::{codec, encoder, format, frame, ChannelLayout, Dictionary, Packet, Rational};
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+/// 视频尺寸
+pub const WIDTH: u32 = 320;
+pub const HEIGHT: u32 = 180;
+
+/// 帧率
+pub const FPS: i32 = 25;
+
+/// 总帧数（10 秒）
+pub const FRAME_COUNT: u32 = 250;
+
+/// 每帧时长（毫秒）
+pub const FRAME_DURATION_MS: i64 = 1000 / FPS as i64;
+
+/// 音频采样率
+pub const SAMPLE_RATE: i32 = 48_000;
+
+/// 正弦音频率与幅度
+pub const TONE_HZ: f64 = 1000.0;
+pub const TONE_AMPLITUDE: f32 = 0.5;
+
+/// 封面尺寸与颜色（纯色 JPEG）
+pub const COVER_SIZE: u32 = 64;
+pub const COVER_COLOR: [u8; 3] = [212, 42, 85];
+
+/// 封面测试音频时长（秒）
+const COVER_AUDIO_SECONDS: i64 = 2;
+
+/// 字幕条数
+pub const SUBTITLE_CUE_COUNT: usize = 5;
+
+/// 每个颜色通道的取值（7 级，最多区分 343 帧）
+pub const COLOR_LEVELS: [u8; 7] = [0, 42, 85, 127, 170, 212, 255];
+
+/// 底部帧序号条码的高度（像素）与位数
+pub const TIMECODE_HEIGHT: u32 = 20;
+pub const TIMECODE_BITS: u32 = 10;
+
+/// 第 index 帧的颜色（RGB）
+pub fn frame_color(index: u32) -> [u8; 3] {
+    let levels = COLOR_LEVELS.len() as u32;
+    [
+        COLOR_LEVELS[(index / (levels * levels) % levels) as usize],
+        COLOR_LEVELS[(index / levels % levels) as usize],
+        COLOR_LEVELS[(index % levels) as usize],
+    ]
+}
+
+/// 第 n 条字幕（开始毫秒, 结束毫秒, 文本）
+pub fn subtitle_cue(n: usize) -> (i64, i64, String) {
+    let start_ms = 500 + n as i64 * 2000;
+    (start_ms, start_ms + 1500, format!("Cue {}", n))
+}
+
+/// 第 n 个音频采样的值
+pub fn tone_sample(n: i64) -> f32 {
+    let t = n as f64 / SAMPLE_RATE as f64;
+    TONE_AMPLITUDE * (2.0 * PI * TONE_HZ * t).sin() as f32
+}
+
+/// RGB 转 YUV（BT.601 有限范围，与解码端默认转换一致）
+fn rgb_to_yuv([r, g, b]: [u8; 3]) -> [u8; 3] {
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+    let y = 16.0 + (65.481 * r + 128.553 * g + 24.966 * b) / 255.0;
+    let u = 128.0 + (-37.797 * r - 74.203 * g + 112.0 * b) / 255.0;
+    let v = 128.0 + (112.0 * r - 93.786 * g - 18.214 * b) / 255.0;
+    [y.round() as u8, u.round() as u8, v.round() as u8]
+}
+
+/// 生成测试字幕
+pub fn write_subtitles(path: &Path) -> Result<()> {
+    let srt_time = |ms: i64| {
+        format!("{:02}:{:02}:{:02},{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000)
+    };
+
+    let mut content = String::new();
+    for n in 0..SUBTITLE_CUE_COUNT {
+        let (start_ms, end_ms, text) = subtitle_cue(n);
+        content.push_str(&format!("{}\n{} --> {}\n{}\n\n", n + 1, srt_time(start_ms), srt_time(end_ms), text));
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// 生成测试视频（H.264 + AAC，MP4 封装）
+pub fn write_video(path: &Path) -> Result<()> {
+    encode_video(path, false)
+}
+
+/// 生成 open GOP 测试视频（每个 GOP 开头有参考上一个 GOP 的前导 B 帧）
+pub fn write_open_gop_video(path: &Path) -> Result<()> {
+    encode_video(path, true)
+}
+
+fn encode_video(path: &Path, open_gop: bool) -> Result<()> {
+    ffmpeg::init()?;
+
+    let mut octx = format::output_as(path, "mp4")?;
+    let global_header = octx.format().flags().contains(format::Flags::GLOBAL_HEADER);
+
+    // ========== 视频编码器 ==========
+    let video_codec = encoder::find_by_name("libx264")
+        .or_else(|| encoder::find(codec::Id::H264))
+        .ok_or_else(|| PlayerError::Other("没有可用的 H.264 编码器".to_string()))?;
+    let mut video_stream = octx.add_stream(video_codec)?;
+    let video_index = video_stream.index();
+
+    let mut video_encoder = codec::context::Context::from_parameters(video_stream.parameters())?
+        .encoder()
+        .video()?;
+    video_encoder.set_width(WIDTH);
+    video_encoder.set_height(HEIGHT);
+    video_encoder.set_format(format::Pixel::YUV420P);
+    video_encoder.set_time_base(Rational(1, FPS));
+    video_encoder.set_frame_rate(Some(Rational(FPS, 1)));
+    video_encoder.set_gop(FPS as u32);  // 每秒一个关键帧
+    video_encoder.set_max_b_frames(if open_gop { 3 } else { 0 });
+    if global_header {
+        video_encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+    }
+
+    let mut options = Dictionary::new();
+    options.set("preset", "ultrafast");
+    options.set("crf", "10");
+    if open_gop {
+        options.set("x264-params", "open-gop=1:bframes=3:b-pyramid=normal");
+    }
+    let mut video_encoder = video_encoder.open_as_with(video_codec, options)?;
+    video_stream.set_parameters(&video_encoder);
+    video_stream.set_time_base(Rational(1, FPS));
+
+    // ========== 音频编码器 ==========
+    let audio_codec = encoder::find(codec::Id::AAC)
+        .ok_or_else(|| PlayerError::Other("没有可用的 AAC 编码器".to_string()))?;
+    let mut audio_stream = octx.add_stream(audio_codec)?;
+    let audio_index = audio_stream.index();
+
+    let mut audio_encoder = codec::context::Context::from_parameters(audio_stream.parameters())?
+        .encoder()
+        .audio()?;
+    audio_encoder.set_rate(SAMPLE_RATE);
+    audio_encoder.set_channel_layout(ChannelLayout::MONO);
+    audio_encoder.set_channels(1);
+    audio_encoder.set_format(format::Sample::F32(format::sample::Type::Planar));
+    audio_encoder.set_bit_rate(128_000);
+    audio_encoder.set_time_base(Rational(1, SAMPLE_RATE));
+    if global_header {
+        audio_encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+    }
+
+    let mut audio_encoder = audio_encoder.open_as(audio_codec)?;
+    audio_stream.set_parameters(&audio_encoder);
+    audio_stream.set_time_base(Rational(1, SAMPLE_RATE));
+
+    octx.write_header()?;
+    let video_time_base = octx.stream(video_index).map(|s| s.time_base()).unwrap_or(Rational(1, FPS));
+    let audio_time_base = octx.stream(audio_index).map(|s| s.time_base()).unwrap_or(Rational(1, SAMPLE_RATE));
+
+    // ========== 按时间交错编码音视频 ==========
+    let frame_size = match audio_encoder.frame_size() {
+        0 => 1024,
+        size => size as usize,
+    };
+    let total_samples = SAMPLE_RATE as i64 * FRAME_COUNT as i64 / FPS as i64;
+    let mut next_sample = 0i64;
+
+    for index in 0..FRAME_COUNT {
+        let mut video_frame = frame::Video::new(format::Pixel::YUV420P, WIDTH, HEIGHT);
+        fill_video_frame(&mut video_frame, index);
+        video_frame.set_pts(Some(index as i64));
+        video_encoder.send_frame(&video_frame)?;
+        write_packets(&mut video_encoder, &mut octx, video_index, Rational(1, FPS), video_time_base)?;
+
+        // 音频编码到当前视频帧结束的时刻
+        let frame_end_sample = (SAMPLE_RATE as i64 * (index as i64 + 1) / FPS as i64).min(total_samples);
+        while next_sample < frame_end_sample {
+            let samples = frame_size.min((total_samples - next_sample) as usize);
+            let mut audio_frame = frame::Audio::new(
+                format::Sample::F32(format::sample::Type::Planar),
+                samples,
+                ChannelLayout::MONO,
+            );
+            audio_frame.set_rate(SAMPLE_RATE as u32);
+            for (i, sample) in audio_frame.plane_mut::<f32>(0).iter_mut().enumerate() {
+                *sample = tone_sample(next_sample + i as i64);
+            }
+            audio_frame.set_pts(Some(next_sample));
+            audio_encoder.send_frame(&audio_frame)?;
+            write_packets(&mut audio_encoder, &mut octx, audio_index, Rational(1, SAMPLE_RATE), audio_time_base)?;
+            next_sample += samples as i64;
+        }
+    }
+
+    video_encoder.send_eof()?;
+    write_packets(&mut video_encoder, &mut octx, video_index, Rational(1, FPS), video_time_base)?;
+    audio_encoder.send_eof()?;
+    write_packets(&mut audio_encoder, &mut octx, audio_index, Rational(1, SAMPLE_RATE), audio_time_base)?;
+
+    octx.write_trailer()?;
+    Ok(())
+}
+
+/// 生成带内嵌封面的测试音频（MP3 + JPEG 封面，ID3v2 APIC）
+pub fn write_cover_art_audio(path: &Path) -> Result<()> {
+    ffmpeg::init()?;
+
+    let mut octx = format::output_as(path, "mp3")?;
+
+    // ========== 音频编码器 ==========
+    let audio_codec = encoder::find_by_name("libmp3lame")
+        .or_else(|| encoder::find(codec::Id::MP3))
+        .ok_or_else(|| PlayerError::Other("没有可用的 MP3 编码器".to_string()))?;
+    let mut audio_stream = octx.add_stream(audio_codec)?;
+    let audio_index = audio_stream.index();
+
+    let mut audio_encoder = codec::context::Context::from_parameters(audio_stream.parameters())?
+        .encoder()
+        .audio()?;
+    audio_encoder.set_rate(SAMPLE_RATE);
+    audio_encoder.set_channel_layout(ChannelLayout::MONO);
+    audio_encoder.set_channels(1);
+    audio_encoder.set_format(format::Sample::F32(format::sample::Type::Planar));
+    audio_encoder.set_bit_rate(128_000);
+    audio_encoder.set_time_base(Rational(1, SAMPLE_RATE));
+
+    let mut audio_encoder = audio_encoder.open_as(audio_codec)?;
+    audio_stream.set_parameters(&audio_encoder);
+    audio_stream.set_time_base(Rational(1, SAMPLE_RATE));
+
+    // ========== 封面（单帧 JPEG，ATTACHED_PIC） ==========
+    let cover_codec = encoder::find(codec::Id::MJPEG)
+        .ok_or_else(|| PlayerError::Other("没有可用的 JPEG 编码器".to_string()))?;
+    let mut cover_stream = octx.add_stream(cover_codec)?;
+    let cover_index = cover_stream.index();
+
+    let mut cover_encoder = codec::context::Context::from_parameters(cover_stream.parameters())?
+        .encoder()
+        .video()?;
+    cover_encoder.set_width(COVER_SIZE);
+    cover_encoder.set_height(COVER_SIZE);
+    cover_encoder.set_format(format::Pixel::YUVJ420P);
+    cover_encoder.set_time_base(Rational(1, 1));
+
+    let mut cover_encoder = cover_encoder.open_as(cover_codec)?;
+    cover_stream.set_parameters(&cover_encoder);
+    // SAFETY: cover_stream 是刚添加到 octx 的有效流，写文件头前设置 disposition
+    unsafe {
+        (*cover_stream.as_mut_ptr()).disposition = ffmpeg::ffi::AV_DISPOSITION_ATTACHED_PIC;
+    }
+
+    octx.write_header()?;
+    let audio_time_base = octx.stream(audio_index).map(|s| s.time_base()).unwrap_or(Rational(1, SAMPLE_RATE));
+    let cover_time_base = octx.stream(cover_index).map(|s| s.time_base()).unwrap_or(Rational(1, 1));
+
+    // 封面必须在音频之前写入（MP3 封装会缓存音频直到收到封面）
+    let mut cover_frame = frame::Video::new(format::Pixel::YUVJ420P, COVER_SIZE, COVER_SIZE);
+    let yuv = rgb_to_yuv_full_range(COVER_COLOR);
+    for (plane, scale) in [(0usize, 1u32), (1, 2), (2, 2)] {
+        let stride = cover_frame.stride(plane);
+        let size = (COVER_SIZE / scale) as usize;
+        let data = cover_frame.data_mut(plane);
+        for row in 0..size {
+            data[row * stride..row * stride + size].fill(yuv[plane]);
+        }
+    }
+    cover_frame.set_pts(Some(0));
+    cover_encoder.send_frame(&cover_frame)?;
+    cover_encoder.send_eof()?;
+    write_packets(&mut cover_encoder, &mut octx, cover_index, Rational(1, 1), cover_time_base)?;
+
+    // ========== 正弦音 ==========
+    let frame_size = match audio_encoder.frame_size() {
+        0 => 1152,
+        size => size as usize,
+    };
+    let total_samples = SAMPLE_RATE as i64 * COVER_AUDIO_SECONDS;
+    let mut next_sample = 0i64;
+    while next_sample < total_samples {
+        let samples = frame_size.min((total_samples - next_sample) as usize);
+        let mut audio_frame = frame::Audio::new(
+            format::Sample::F32(format::sample::Type::Planar),
+            samples,
+            ChannelLayout::MONO,
+        );
+        audio_frame.set_rate(SAMPLE_RATE as u32);
+        for (i, sample) in audio_frame.plane_mut::<f32>(0).iter_mut().enumerate() {
+            *sample = tone_sample(next_sample + i as i64);
+        }
+        audio_frame.set_pts(Some(next_sample));
+        audio_encoder.send_frame(&audio_frame)?;
+        write_packets(&mut audio_encoder, &mut octx, audio_index, Rational(1, SAMPLE_RATE), audio_time_base)?;
+        next_sample += samples as i64;
+    }
+    audio_encoder.send_eof()?;
+    write_packets(&mut audio_encoder, &mut octx, audio_index, Rational(1, SAMPLE_RATE), audio_time_base)?;
+
+    octx.write_trailer()?;
+    Ok(())
+}
+
+/// RGB 转 YUV（BT.601 全范围，JPEG 使用）
+fn rgb_to_yuv_full_range([r, g, b]: [u8; 3]) -> [u8; 3] {
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+    let y = 0.299 * r + 0.587 * g + 0.114 * b;
+    let u = 128.0 - 0.168736 * r - 0.331264 * g + 0.5 * b;
+    let v = 128.0 + 0.5 * r - 0.418688 * g - 0.081312 * b;
+    [y.round().clamp(0.0, 255.0) as u8, u.round().clamp(0.0, 255.0) as u8, v.round().clamp(0.0, 255.0) as u8]
+}
+
+/// 取出编码器输出的所有数据包并写入封装
+fn write_packets(
+    encoder: &mut encoder::Encoder,
+    octx: &mut format::context::Output,
+    stream_index: usize,
+    encoder_time_base: Rational,
+    stream_time_base: Rational,
+) -> Result<()> {
+    let mut packet = Packet::empty();
+    while encoder.receive_packet(&mut packet).is_ok() {
+        packet.set_stream(stream_index);
+        packet.rescale_ts(encoder_time_base, stream_time_base);
+        packet.write_interleaved(octx)?;
+    }
+    Ok(())
+}
+
+/// 填充视频帧：整帧为帧序号对应的颜色，底部为帧序号条码（白=1，黑=0，高位在左）
+fn fill_video_frame(video_frame: &mut frame::Video, index: u32) {
+    let [y, u, v] = rgb_to_yuv(frame_color(index));
+    let [white_y, white_u, white_v] = rgb_to_yuv([255, 255, 255]);
+    let [black_y, black_u, black_v] = rgb_to_yuv([0, 0, 0]);
+    let bit_width = WIDTH / TIMECODE_BITS;
+
+    for (plane, scale) in [(0usize, 1u32), (1, 2), (2, 2)] {
+        let stride = video_frame.stride(plane);
+        let width = WIDTH / scale;
+        let height = HEIGHT / scale;
+        let timecode_top = (HEIGHT - TIMECODE_HEIGHT) / scale;
+        let (fill, one, zero) = match plane {
+            0 => (y, white_y, black_y),
+            1 => (u, white_u, black_u),
+            _ => (v, white_v, black_v),
+        };
+
+        let data = video_frame.data_mut(plane);
+        for row in 0..height {
+            for col in 0..width {
+                let value = if row < timecode_top {
+                    fill
+                } else {
+                    let bit = TIMECODE_BITS - 1 - (col * scale / bit_width).min(TIMECODE_BITS - 1);
+                    if index >> bit & 1 == 1 { one } else { zero }
+                };
+                data[row as usize * stride + col as usize] = value;
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// 黄金帧比对：通过播放器自身的解封装/解码流程取帧，由画面颜色还原帧序号
+
+use super::generator::{frame_color, COLOR_LEVELS, FRAME_DURATION_MS, HEIGHT, SAMPLE_RATE, TIMECODE_BITS, TIMECODE_HEIGHT};
+use crate::core::{AudioFrame, VideoFrame};
+use crate::player::decoder::{AudioDecoder, VideoDecoder};
+use crate::player::demuxer::Demuxer;
+use std::path::Path;
+
+/// 单个颜色通道允许的最大偏差（编码损失 + YUV/RGB 转换误差）
+const COLOR_TOLERANCE: i32 = 16;
+
+/// 画面主体区域的平均颜色（排除底部条码区域）
+fn average_color(frame: &VideoFrame) -> [i32; 3] {
+    let width = frame.width as usize;
+    // 条码上方留出几行余量，避免色度下采样在边界处混色
+    let rows = frame.height as usize * (HEIGHT - TIMECODE_HEIGHT - 4) as usize / HEIGHT as usize;
+
+    let mut sum = [0u64; 3];
+    for row in 0..rows {
+        for col in 0..width {
+            let offset = (row * width + col) * 4;
+            for (channel, total) in sum.iter_mut().enumerate() {
+                *total += frame.data[offset + channel] as u64;
+            }
+        }
+    }
+
+    let count = (rows * width).max(1) as u64;
+    sum.map(|total| (total / count) as i32)
+}
+
+/// 由画面主体颜色还原帧序号（颜色偏差超出容差时返回 None）
+pub fn frame_index_of(frame: &VideoFrame) -> Option<u32> {
+    let levels = COLOR_LEVELS.len() as u32;
+    let mut index = 0;
+    for value in average_color(frame) {
+        let (level, nearest) = COLOR_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (**level as i32 - value).abs())?;
+        if (*nearest as i32 - value).abs() > COLOR_TOLERANCE {
+            return None;
+        }
+        index = index * levels + level as u32;
+    }
+    Some(index)
+}
+
+/// 读取底部烧录的帧序号条码
+fn burned_index_of(frame: &VideoFrame) -> u32 {
+    let width = frame.width as usize;
+    let row = frame.height as usize - (TIMECODE_HEIGHT as usize / 2) * frame.height as usize / HEIGHT as usize;
+    let bit_width = width / TIMECODE_BITS as usize;
+
+    (0..TIMECODE_BITS as usize).fold(0, |index, bit| {
+        let col = bit * bit_width + bit_width / 2;
+        let offset = (row * width + col) * 4;
+        let luma = (frame.data[offset] as u32 + frame.data[offset + 1] as u32 + frame.data[offset + 2] as u32) / 3;
+        index << 1 | (luma > 128) as u32
+    })
+}
+
+/// 断言帧内容对应期望的帧序号（允许 tolerance 帧的偏差）
+pub fn assert_golden_frame(frame: &VideoFrame, expected_index: u32, tolerance: u32) {
+    let index = frame_index_of(frame).unwrap_or_else(|| {
+        panic!(
+            "PTS={}ms 的画面颜色 {:?} 无法匹配任何帧（期望帧 {} 的颜色 {:?}）",
+            frame.pts,
+            average_color(frame),
+            expected_index,
+            frame_color(expected_index)
+        )
+    });
+    assert_eq!(index, burned_index_of(frame), "PTS={}ms 的画面颜色与条码不一致", frame.pts);
+    assert!(
+        index.abs_diff(expected_index) <= tolerance,
+        "PTS={}ms 的画面为第 {} 帧，期望第 {} 帧（允许偏差 {} 帧）",
+        frame.pts,
+        index,
+        expected_index,
+        tolerance
+    );
+}
+
+/// Seek 到指定位置后解码，返回第一个 PTS 不早于目标的视频帧
+pub fn decode_frame_at(path: &Path, target_ms: i64) -> VideoFrame {
+    let mut demuxer = Demuxer::open(&path.to_string_lossy()).expect("无法打开测试视频");
+    let mut decoder = VideoDecoder::from_stream_software(demuxer.video_stream().expect("测试视频缺少视频流"))
+        .expect("无法创建视频解码器");
+    demuxer.seek(target_ms).expect("Seek 失败");
+
+    // 与播放器一致：丢弃 Seek 目标之前的帧（容忍半帧的时间戳取整误差）
+    let is_target = |frame: &VideoFrame| frame.pts >= target_ms - FRAME_DURATION_MS / 2;
+    while let Some((packet, is_video, _)) = demuxer.read_packet().expect("读取数据包失败") {
+        if !is_video {
+            continue;
+        }
+        let frames = decoder.decode(&packet).expect("视频解码失败");
+        if let Some(frame) = frames.into_iter().find(is_target) {
+            return frame;
+        }
+    }
+
+    decoder
+        .flush()
+        .expect("视频解码失败")
+        .into_iter()
+        .find(is_target)
+        .unwrap_or_else(|| panic!("Seek 到 {}ms 后没有解码出视频帧", target_ms))
+}
+
+/// 从头解码全部音视频帧（音频输出为单声道 f32）
+pub fn decode_streams(path: &Path) -> (Vec<VideoFrame>, Vec<AudioFrame>) {
+    let mut demuxer = Demuxer::open(&path.to_string_lossy()).expect("无法打开测试视频");
+    let mut video_decoder = VideoDecoder::from_stream_software(demuxer.video_stream().expect("测试视频缺少视频流"))
+        .expect("无法创建视频解码器");
+    let audio_stream = demuxer.audio_stream().expect("测试视频缺少音频流");
+    let audio_index = audio_stream.index();
+    let mut audio_decoder = AudioDecoder::from_stream_with_config(audio_stream, SAMPLE_RATE as u32, 1)
+        .expect("无法创建音频解码器");
+
+    let mut video_frames = Vec::new();
+    let mut audio_frames = Vec::new();
+    while let Some((packet, is_video, _)) = demuxer.read_packet().expect("读取数据包失败") {
+        // 只把音频流的包交给音频解码器，字幕等其他流跳过
+        if is_video {
+            video_frames.extend(video_decoder.decode(&packet).expect("视频解码失败"));
+        } else if packet.stream() == audio_index {
+            audio_frames.extend(audio_decoder.decode(&packet).expect("音频解码失败"));
+        }
+    }
+    video_frames.extend(video_decoder.flush().expect("视频解码失败"));
+    audio_frames.extend(audio_decoder.flush().expect("音频解码失败"));
+
+    video_frames.sort_by_key(|frame| frame.pts);
+    audio_frames.sort_by_key(|frame| frame.pts);
+    (video_frames, audio_frames)
+}
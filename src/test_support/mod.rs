@@ -0,0 +1,71 @@
+// 测试辅助模块（仅测试构建）
+//
+// 在测试时用 FFmpeg 编码 API 生成确定性的小型测试素材，并提供基于画面颜色的帧校验。
+// 素材缓存在 target/test-assets/v{GENERATOR_VERSION}/ 下，修改生成逻辑时需递增版本号。
+
+mod generator;
+mod golden;
+
+pub use generator::{subtitle_cue, COVER_COLOR, COVER_SIZE, FRAME_DURATION_MS, SAMPLE_RATE, SUBTITLE_CUE_COUNT, TONE_AMPLITUDE};
+pub use golden::{assert_golden_frame, decode_frame_at, decode_streams};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::core::Result;
+
+/// 生成器版本（生成逻辑变化时递增，使旧缓存失效）
+const GENERATOR_VERSION: u32 = 1;
+
+/// 测试视频（10 秒 320x180 H.264 + AAC 1kHz 正弦音）
+pub fn video_asset() -> &'static Path {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| cached_asset("av_sync.mp4", generator::write_video))
+}
+
+/// open GOP 测试视频（内容与 video_asset 相同，H.264 带 3 个 B 帧）
+pub fn open_gop_asset() -> &'static Path {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| cached_asset("open_gop.mp4", generator::write_open_gop_video))
+}
+
+/// 测试字幕（SRT，字幕内容见 `subtitle_cue`）
+pub fn subtitle_asset() -> &'static Path {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| cached_asset("cues.srt", generator::write_subtitles))
+}
+
+/// 带内嵌封面的测试音频（2 秒 MP3 正弦音 + 64x64 纯色 JPEG 封面）
+pub fn cover_art_asset() -> &'static Path {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| cached_asset("cover_art.mp3", generator::write_cover_art_audio))
+}
+
+/// 素材缓存目录
+fn asset_dir() -> PathBuf {
+    let target_dir = option_env!("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("target"));
+    target_dir
+        .join("test-assets")
+        .join(format!("v{}", GENERATOR_VERSION))
+}
+
+/// 获取缓存的素材，不存在时生成（先写临时文件再重命名，避免留下不完整的文件）
+fn cached_asset(name: &str, generate: fn(&Path) -> Result<()>) -> PathBuf {
+    let dir = asset_dir();
+    let path = dir.join(name);
+    if path.is_file() {
+        return path;
+    }
+
+    fs::create_dir_all(&dir).expect("无法创建测试素材目录");
+    let temp_path = dir.join(format!("{}.{}.tmp", name, std::process::id()));
+    if let Err(e) = generate(&temp_path) {
+        let _ = fs::remove_file(&temp_path);
+        panic!("生成测试素材 {} 失败: {}", name, e);
+    }
+    fs::rename(&temp_path, &path).expect("无法保存测试素材");
+    path
+}